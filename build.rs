@@ -1,6 +1,6 @@
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 fn main() {
@@ -16,7 +16,7 @@ fn main() {
     }
 }
 
-fn framework_supports_target_arch(framework: &PathBuf, target_arch: &str) -> bool {
+fn framework_supports_target_arch(framework: &Path, target_arch: &str) -> bool {
     let bin = framework.join("Syphon");
     if !bin.exists() {
         return false;
@@ -39,8 +39,27 @@ fn build_syphon() {
         .expect("Syphon.framework has parent");
     let sdk_path = sdk_path();
 
+    // Optional subsystems: the glue only compiles (and the binary only links the frameworks for) the enabled ones
+    let feature = |name: &str| env::var(format!("CARGO_FEATURE_{}", name)).is_ok();
+    let glue_defines: Vec<&str> = [
+        ("CORE_IMAGE", "SYPHON_GLUE_CORE_IMAGE"),
+        ("SCREEN_CAPTURE", "SYPHON_GLUE_SCREEN_CAPTURE"),
+        ("CAMERA", "SYPHON_GLUE_CAMERA"),
+        ("RECORDER", "SYPHON_GLUE_RECORDER"),
+        ("ENCODER", "SYPHON_GLUE_ENCODER"),
+        ("PASTEBOARD", "SYPHON_GLUE_PASTEBOARD"),
+        ("HOTKEY", "SYPHON_GLUE_HOTKEY"),
+    ]
+    .into_iter()
+    .filter(|(name, _)| feature(name))
+    .map(|(_, define)| define)
+    .collect();
+
     // Compile the C/ObjC glue with ARC so __bridge_retained/__bridge_transfer work (no warnings)
     let mut cc = cc::Build::new();
+    for define in &glue_defines {
+        cc.define(define, "1");
+    }
     cc.file("syphon_glue/syphon_glue.m")
         .include("syphon_glue")
         .flag("-fobjc-arc")
@@ -53,6 +72,7 @@ fn build_syphon() {
     // Run bindgen on the glue header
     let bindings = bindgen::Builder::default()
         .header("syphon_glue/syphon_glue.h")
+        .clang_args(glue_defines.iter().map(|define| format!("-D{}=1", define)))
         .clang_arg("-F")
         .clang_arg(framework_parent.to_str().unwrap())
         .clang_arg("-isysroot")
@@ -77,10 +97,31 @@ fn build_syphon() {
     println!("cargo:rustc-link-lib=framework=Foundation");
     println!("cargo:rustc-link-lib=framework=OpenGL");
     println!("cargo:rustc-link-lib=framework=IOSurface");
+    println!("cargo:rustc-link-lib=framework=CoreVideo");
     println!("cargo:rustc-link-lib=framework=Metal");
     println!("cargo:rustc-link-lib=framework=CoreFoundation");
     println!("cargo:rustc-link-lib=framework=QuartzCore");
     println!("cargo:rustc-link-lib=framework=AppKit");
+    // Recorders and encoders render frames through Core Image, like the `core-image` interop.
+    if feature("CORE_IMAGE") || feature("RECORDER") || feature("ENCODER") {
+        println!("cargo:rustc-link-lib=framework=CoreImage");
+    }
+    if feature("SCREEN_CAPTURE") || feature("CAMERA") || feature("RECORDER") || feature("ENCODER") {
+        println!("cargo:rustc-link-lib=framework=CoreMedia");
+    }
+    if feature("CAMERA") || feature("RECORDER") {
+        println!("cargo:rustc-link-lib=framework=AVFoundation");
+    }
+    if feature("ENCODER") {
+        println!("cargo:rustc-link-lib=framework=VideoToolbox");
+    }
+    if feature("SCREEN_CAPTURE") {
+        // Weak: ScreenCaptureKit only exists on macOS 12.3+, and the glue checks availability at runtime.
+        println!("cargo:rustc-link-arg=-Wl,-weak_framework,ScreenCaptureKit");
+    }
+    if feature("HOTKEY") {
+        println!("cargo:rustc-link-lib=framework=Carbon");
+    }
     stage_syphon_framework_for_runtime(&syphon_framework_dir);
 
     // Re-run if these change
@@ -145,18 +186,18 @@ fn copy_dir_recursive(src: &PathBuf, dst: &PathBuf) -> std::io::Result<()> {
 }
 
 /// Resolve lib/bin dirs from a candidate root: accept root, root/lib, root/bin (grabs what it needs).
-fn resolve_spout_prebuilt(root: &PathBuf) -> Option<(PathBuf, PathBuf)> {
+fn resolve_spout_prebuilt(root: &Path) -> Option<(PathBuf, PathBuf)> {
     let lib_dir = if root.join("lib").join("SpoutLibrary.lib").exists() {
         root.join("lib")
     } else if root.join("SpoutLibrary.lib").exists() {
-        root.clone()
+        root.to_path_buf()
     } else {
         return None;
     };
     let bin_dir = if root.join("bin").join("SpoutLibrary.dll").exists() {
         root.join("bin")
     } else if root.join("SpoutLibrary.dll").exists() {
-        root.clone()
+        root.to_path_buf()
     } else {
         root.join("bin") // copy_dll will no-op if dll not present
     };
@@ -179,7 +220,7 @@ fn build_spout() {
         (lib_dir, bin_dir)
     } else {
         let dst = cmake::Config::new("Spout2")
-            .define("SPOUT_BUILD_SPOUTDX", "OFF")
+            .define("SPOUT_BUILD_SPOUTDX", "ON")
             .define("SPOUT_BUILD_LIBRARY", "ON")
             .build();
         let spout_lib = dst.join("lib");
@@ -204,14 +245,28 @@ fn build_spout() {
     println!("cargo:rustc-link-search=native={}", spout_lib.display());
     copy_dll(&spout_bin, "SpoutLibrary.dll");
     println!("cargo:rustc-link-lib=SpoutLibrary");
-
-    // Compile spout_glue.cpp (includes SpoutLibrary.h, links to SpoutLibrary)
-    let spout_include = manifest_dir.join("Spout2").join("SPOUTSDK").join("SpoutLibrary");
+    // SpoutDX (DirectX 11 senders and receivers) for `SpoutDxServer` / `SpoutDxClient`.
+    copy_dll(&spout_bin, "SpoutDX.dll");
+    println!("cargo:rustc-link-lib=SpoutDX");
+    println!("cargo:rustc-link-lib=d3d11");
+    // SpoutDX12 (D3D11On12 on top of SpoutDX) for `SpoutDx12Server` / `SpoutDx12Client`; it ships as
+    // source only, so it is compiled into the glue.
+    println!("cargo:rustc-link-lib=d3d12");
+    // WGL_NV_DX_interop entry points (wglGetProcAddress) for `SpoutGlDxServer` / `SpoutGlDxClient`.
+    println!("cargo:rustc-link-lib=opengl32");
+
+    // Compile spout_glue.cpp (includes SpoutLibrary.h, SpoutDX.h and SpoutDX12.h, links to all three)
+    let spout_sdk = manifest_dir.join("Spout2").join("SPOUTSDK");
+    let spout_dx12 = spout_sdk.join("SpoutDirectX").join("SpoutDX12");
     cc::Build::new()
         .cpp(true)
         .file("spout_glue/spout_glue.cpp")
-        .include(&spout_include)
+        .file(spout_dx12.join("SpoutDX12.cpp"))
+        .include(spout_sdk.join("SpoutLibrary"))
+        .include(spout_sdk.join("SpoutDirectX").join("SpoutDX"))
+        .include(&spout_dx12)
         .include("spout_glue")
+        .define("SPOUT_IMPORT_DLL", None)
         .compile("spout_glue");
 
     // Generate Rust bindings for spout_glue.h
@@ -256,7 +311,7 @@ fn build_spout_stub_macos() {
     println!("cargo:rerun-if-changed=spout_glue/spout_glue_stub.c");
 }
 
-fn copy_dll(bin_dir: &PathBuf, dll_name: &str) {
+fn copy_dll(bin_dir: &Path, dll_name: &str) {
     let src = bin_dir.join(dll_name);
     if !src.exists() {
         return;
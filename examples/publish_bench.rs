@@ -0,0 +1,98 @@
+//! Publish benchmark: publishes a 4K OpenGL texture repeatedly and reports the time spent in
+//! `publish_whole_texture` (mean, p50, p99, max), then the publish-to-callback latency of a client with
+//! `CallbackDispatch::Serialized` vs `CallbackDispatch::Direct`.
+//!
+//! Run on macOS: cargo run --release --example publish_bench [frames]
+
+#[cfg(target_os = "macos")]
+mod macos_bench {
+    use rusty_syphon_spout::{
+        cgl_create_headless_context, cgl_destroy_context, cgl_make_current, gl_create_texture_rectangle_rgba8,
+        gl_delete_texture, CallbackDispatch, OpenGLClient, OpenGLServer, GL_TEXTURE_RECTANGLE,
+    };
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    const W: usize = 3840;
+    const H: usize = 2160;
+
+    pub fn report(label: &str, samples: &mut [Duration]) {
+        if samples.is_empty() {
+            println!("{label}: no samples");
+            return;
+        }
+        samples.sort();
+        let mean = samples.iter().sum::<Duration>() / samples.len() as u32;
+        let pct = |p: usize| samples[(samples.len() - 1) * p / 100];
+        println!(
+            "{label}: n={} mean={:?} p50={:?} p99={:?} max={:?}",
+            samples.len(),
+            mean,
+            pct(50),
+            pct(99),
+            samples[samples.len() - 1]
+        );
+    }
+
+    pub fn run(frames: usize) {
+        let ctx = cgl_create_headless_context().expect("create headless CGL context");
+        cgl_make_current(ctx);
+        let tex = gl_create_texture_rectangle_rgba8(W, H, &vec![128u8; W * H * 4]);
+        assert!(tex != 0, "failed to create {W}x{H} texture");
+        let server = OpenGLServer::new(Some("rusty-syphon-publish-bench"), ctx, None).expect("create OpenGL server");
+
+        let mut samples = Vec::with_capacity(frames);
+        for _ in 0..frames {
+            let start = Instant::now();
+            server.publish_whole_texture(tex, GL_TEXTURE_RECTANGLE, W as f64, H as f64, false);
+            samples.push(start.elapsed());
+        }
+        report(&format!("publish_frame {W}x{H}"), &mut samples);
+
+        let desc = server.server_description().expect("missing server description");
+        for dispatch in [CallbackDispatch::Serialized, CallbackDispatch::Direct] {
+            let published = Arc::new(Mutex::new(Instant::now()));
+            let latencies = Arc::new(Mutex::new(Vec::with_capacity(frames)));
+            let client = {
+                let (published, latencies) = (published.clone(), latencies.clone());
+                OpenGLClient::new_with_dispatch(
+                    &desc,
+                    ctx,
+                    None,
+                    Some(Box::new(move |_| {
+                        let elapsed = published.lock().unwrap().elapsed();
+                        latencies.lock().unwrap().push(elapsed);
+                    })),
+                    dispatch,
+                )
+                .expect("create OpenGL client")
+            };
+            for _ in 0..frames {
+                *published.lock().unwrap() = Instant::now();
+                server.publish_whole_texture(tex, GL_TEXTURE_RECTANGLE, W as f64, H as f64, false);
+                // Space frames out so each callback measures its own publish.
+                std::thread::sleep(Duration::from_millis(4));
+            }
+            drop(client);
+            let mut samples = std::mem::take(&mut *latencies.lock().unwrap());
+            report(&format!("callback latency ({dispatch:?})"), &mut samples);
+        }
+
+        drop(server);
+        gl_delete_texture(tex);
+        cgl_destroy_context(ctx);
+    }
+}
+
+fn main() {
+    let frames = std::env::args().nth(1).and_then(|s| s.parse().ok()).unwrap_or(600);
+
+    #[cfg(target_os = "macos")]
+    macos_bench::run(frames);
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = frames;
+        println!("Syphon is macOS-only. This example does nothing on other platforms.");
+    }
+}
@@ -10,7 +10,7 @@ use metal::{Device, MTLPixelFormat, MTLRegion, MTLTextureType, Texture, TextureD
 use rusty_syphon_spout::{
     cgl_create_headless_context, cgl_destroy_context, cgl_make_current,
     gl_create_texture_rectangle_rgba8, gl_delete_texture, gl_read_texture_rectangle_rgba8,
-    OpenGLClient, OpenGLImage, OpenGLServer, MetalClient, MetalServer, MetalTexture,
+    OpenGLClient, OpenGLImage, OpenGLServer, MetalClient, MetalServer, MetalTexture, Rect,
     GL_TEXTURE_RECTANGLE,
 };
 use std::time::Duration;
@@ -69,13 +69,10 @@ fn test_metal_roundtrip() {
     .expect("create Metal server");
 
     let cmd_buf = queue.new_command_buffer();
-    server.publish_frame(
+    server.publish(
         texture.as_ptr() as *mut _,
         cmd_buf.as_ptr() as *mut _,
-        0.0,
-        0.0,
-        W as f64,
-        H as f64,
+        Rect::new(0.0, 0.0, W as f64, H as f64),
         false,
     );
     cmd_buf.commit();
@@ -136,17 +133,7 @@ fn test_opengl_roundtrip() {
 
     let server = OpenGLServer::new(Some("rusty-syphon-roundtrip-opengl"), ctx, None)
         .expect("create OpenGL server");
-    server.publish_frame(
-        tex_id,
-        GL_TEXTURE_RECTANGLE,
-        0.0,
-        0.0,
-        W as f64,
-        H as f64,
-        W as f64,
-        H as f64,
-        false,
-    );
+    server.publish_whole_texture(tex_id, GL_TEXTURE_RECTANGLE, W as f64, H as f64, false);
 
     let server_desc = server.server_description().expect("server description");
     std::thread::sleep(Duration::from_millis(50));
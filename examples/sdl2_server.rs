@@ -0,0 +1,59 @@
+//! SDL2 example: opens a GL window, draws an animated clear color, and publishes every frame as the
+//! Syphon server "SDL2 example" (watch it in Syphon Recorder, Resolume, or `syphon-monitor`).
+//!
+//! Run on macOS: cargo run --example sdl2_server --features sdl2,glow
+
+#[cfg(target_os = "macos")]
+fn main() -> Result<(), String> {
+    use glow::HasContext;
+    use rusty_syphon_spout::Sdl2WindowPublisher;
+
+    let sdl = sdl2::init()?;
+    let video = sdl.video()?;
+    let gl_attr = video.gl_attr();
+    gl_attr.set_context_profile(sdl2::video::GLProfile::Core);
+    gl_attr.set_context_version(3, 2);
+
+    let window = video
+        .window("Syphon SDL2 example", 640, 360)
+        .opengl()
+        .allow_highdpi()
+        .resizable()
+        .build()
+        .map_err(|e| e.to_string())?;
+    let context = window.gl_create_context()?;
+    let gl = unsafe { glow::Context::from_loader_function(|name| video.gl_get_proc_address(name) as *const _) };
+    let mut publisher =
+        Sdl2WindowPublisher::new(Some("SDL2 example"), &window, &context, None).map_err(|e| e.to_string())?;
+
+    let mut events = sdl.event_pump()?;
+    let start = std::time::Instant::now();
+    'running: loop {
+        for event in events.poll_iter() {
+            if let sdl2::event::Event::Quit { .. } = event {
+                break 'running;
+            }
+        }
+
+        let t = start.elapsed().as_secs_f32();
+        let (w, h) = window.drawable_size();
+        unsafe {
+            gl.viewport(0, 0, w as i32, h as i32);
+            gl.clear_color(0.5 + 0.5 * t.sin(), 0.5 + 0.5 * (t * 0.7).cos(), 0.6, 1.0);
+            gl.clear(glow::COLOR_BUFFER_BIT);
+        }
+
+        publisher.publish(&window);
+        window.gl_swap_window();
+    }
+
+    // The publisher's GL objects are released with the window's context current.
+    drop(publisher);
+    drop(context);
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn main() {
+    println!("Syphon is macOS-only. This example does nothing on other platforms.");
+}
@@ -0,0 +1,35 @@
+//! Opens a debug window on a Syphon server: the first one whose name or app contains the first argument,
+//! or the first server found.
+//!
+//! Run on macOS: cargo run --example viewer --features viewer -- [name]
+
+#[cfg(target_os = "macos")]
+fn main() {
+    use rusty_syphon_spout::{ServerDirectory, Viewer};
+
+    let filter = std::env::args().nth(1);
+    let Some(directory) = ServerDirectory::shared() else {
+        eprintln!("Failed to get Syphon server directory");
+        return;
+    };
+    let matches = |text: Option<String>| match &filter {
+        Some(filter) => text.is_some_and(|t| t.contains(filter.as_str())),
+        None => true,
+    };
+    let server = directory
+        .servers()
+        .into_iter()
+        .find(|desc| matches(desc.name()) || matches(desc.app_name()));
+    let Some(server) = server else {
+        eprintln!("No matching Syphon server");
+        return;
+    };
+    if let Err(e) = Viewer::open(&server) {
+        eprintln!("Viewer failed: {e}");
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn main() {
+    println!("Syphon is macOS-only. This example does nothing on other platforms.");
+}
@@ -0,0 +1,25 @@
+//! GStreamer plugin exposing Syphon (macOS) as GStreamer elements:
+//!
+//! - `syphonclientsrc`: receives frames from a Syphon server as `video/x-raw,format=RGBA`.
+//! - `syphonserversink`: publishes a `video/x-raw,format=RGBA` stream as a Syphon server.
+//!
+//! The elements live in `rusty-syphon-spout` behind its `gstreamer` feature; this crate only packages
+//! them as a loadable plugin.
+
+use gst::glib;
+
+fn plugin_init(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
+    rusty_syphon_spout::register_gstreamer_elements(Some(plugin))
+}
+
+gst::plugin_define!(
+    syphon,
+    env!("CARGO_PKG_DESCRIPTION"),
+    plugin_init,
+    concat!(env!("CARGO_PKG_VERSION"), "-", env!("COMMIT_ID")),
+    "MIT/X11",
+    env!("CARGO_PKG_NAME"),
+    env!("CARGO_PKG_NAME"),
+    env!("CARGO_PKG_REPOSITORY"),
+    env!("BUILD_REL_DATE")
+);
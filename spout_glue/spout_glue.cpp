@@ -4,9 +4,13 @@
 #ifdef _WIN32
 
 #include "SpoutLibrary.h"
+#include "SpoutDX.h"
+#include "SpoutDX12.h"
 #include "spout_glue.h"
+#include <GL/gl.h>
 #include <cstdlib>
 #include <cstring>
+#include <new>
 
 static SPOUTLIBRARY* ptr(spout_handle h) {
     return reinterpret_cast<SPOUTLIBRARY*>(h);
@@ -248,4 +252,485 @@ bool spout_set_cpu_mode(spout_handle h, bool cpu_mode) {
     return h && ptr(h)->SetCPUmode(cpu_mode);
 }
 
+/* ---- DirectX 11 (SpoutDX) ---- */
+
+static spoutDX* dx(void* h) {
+    return reinterpret_cast<spoutDX*>(h);
+}
+
+void* spout_dx_create(void* device) {
+    spoutDX* s = new (std::nothrow) spoutDX();
+    if (!s) return nullptr;
+    if (!s->OpenDirectX11(static_cast<ID3D11Device*>(device))) {
+        delete s;
+        return nullptr;
+    }
+    return s;
+}
+
+void spout_dx_destroy(void* h) {
+    if (!h) return;
+    dx(h)->ReleaseSender();
+    dx(h)->ReleaseReceiver();
+    dx(h)->CloseDirectX11();
+    delete dx(h);
+}
+
+void* spout_dx_get_device(void* h) {
+    return h ? dx(h)->GetDX11Device() : nullptr;
+}
+
+bool spout_dx_sender_set_name(void* h, const char* name) {
+    return h && dx(h)->SetSenderName(name);
+}
+
+bool spout_dx_sender_send_texture(void* h, void* texture) {
+    return h && texture && dx(h)->SendTexture(static_cast<ID3D11Texture2D*>(texture));
+}
+
+void spout_dx_sender_release(void* h) {
+    if (h) dx(h)->ReleaseSender();
+}
+
+char* spout_dx_sender_get_name(void* h) {
+    if (!h) return nullptr;
+    const char* s = dx(h)->GetName();
+    return s && *s ? _strdup(s) : nullptr;
+}
+
+void spout_dx_receiver_set_name(void* h, const char* sender_name) {
+    if (h) dx(h)->SetReceiverName(sender_name);
+}
+
+bool spout_dx_receiver_receive_texture(void* h) {
+    return h && dx(h)->ReceiveTexture();
+}
+
+void* spout_dx_receiver_get_sender_texture(void* h) {
+    return h ? dx(h)->GetSenderTexture() : nullptr;
+}
+
+void spout_dx_receiver_release(void* h) {
+    if (h) dx(h)->ReleaseReceiver();
+}
+
+bool spout_dx_receiver_get_sender_name(void* h, char* buf, int max_chars) {
+    if (!h || !buf || max_chars <= 0) return false;
+    const char* s = dx(h)->GetSenderName();
+    if (!s || !*s) return false;
+    strncpy_s(buf, (size_t)max_chars, s, _TRUNCATE);
+    return true;
+}
+
+bool spout_dx_receiver_is_frame_new(void* h) {
+    return h && dx(h)->IsFrameNew();
+}
+
+bool spout_dx_receiver_is_updated(void* h) {
+    return h && dx(h)->IsUpdated();
+}
+
+bool spout_dx_receiver_is_connected(void* h) {
+    return h && dx(h)->IsConnected();
+}
+
+unsigned int spout_dx_receiver_get_sender_width(void* h) {
+    return h ? dx(h)->GetSenderWidth() : 0;
+}
+
+unsigned int spout_dx_receiver_get_sender_height(void* h) {
+    return h ? dx(h)->GetSenderHeight() : 0;
+}
+
+unsigned int spout_dx_receiver_get_sender_format(void* h) {
+    return h ? (unsigned int)dx(h)->GetSenderFormat() : 0;
+}
+
+bool spout_dx_receiver_copy_to_texture(void* h, void* texture) {
+    if (!h || !texture || !dx(h)->ReceiveTexture()) return false;
+    ID3D11Texture2D* source = dx(h)->GetSenderTexture();
+    if (!source) return false;
+    dx(h)->GetDX11Context()->CopyResource(static_cast<ID3D11Texture2D*>(texture), source);
+    return true;
+}
+
+/* ---- DirectX 12 (SpoutDX12) ---- */
+
+/* SpoutDX12 plus the D3D11 wrapper of the last sent resource and the received resource. */
+struct Dx12Glue {
+    spoutDX12 spout;
+    ID3D12Resource* wrapped_source = nullptr;
+    ID3D11Resource* wrapped = nullptr;
+    ID3D12Resource* received = nullptr;
+};
+
+static Dx12Glue* dx12(void* h) {
+    return reinterpret_cast<Dx12Glue*>(h);
+}
+
+static void dx12_release_wrapped(Dx12Glue* g) {
+    if (g->wrapped) g->wrapped->Release();
+    g->wrapped = nullptr;
+    g->wrapped_source = nullptr;
+}
+
+void* spout_dx12_create(void* device, void* command_queue) {
+    Dx12Glue* g = new (std::nothrow) Dx12Glue();
+    if (!g) return nullptr;
+    IUnknown* queue = static_cast<IUnknown*>(command_queue);
+    if (!g->spout.OpenDirectX12(static_cast<ID3D12Device*>(device), queue ? &queue : nullptr)) {
+        delete g;
+        return nullptr;
+    }
+    return g;
+}
+
+void spout_dx12_destroy(void* h) {
+    if (!h) return;
+    Dx12Glue* g = dx12(h);
+    g->spout.ReleaseSender();
+    g->spout.ReleaseReceiver();
+    dx12_release_wrapped(g);
+    if (g->received) g->received->Release();
+    g->spout.CloseDirectX12();
+    delete g;
+}
+
+void* spout_dx12_get_device(void* h) {
+    return h ? dx12(h)->spout.GetDX12device() : nullptr;
+}
+
+bool spout_dx12_sender_set_name(void* h, const char* name) {
+    return h && dx12(h)->spout.SetSenderName(name);
+}
+
+bool spout_dx12_sender_send_resource(void* h, void* resource, unsigned int state) {
+    if (!h || !resource) return false;
+    Dx12Glue* g = dx12(h);
+    ID3D12Resource* source = static_cast<ID3D12Resource*>(resource);
+    if (source != g->wrapped_source) {
+        dx12_release_wrapped(g);
+        if (!g->spout.WrapDX12Resource(source, &g->wrapped, (D3D12_RESOURCE_STATES)state)) return false;
+        g->wrapped_source = source;
+    }
+    return g->spout.SendDX11Resource(g->wrapped);
+}
+
+void spout_dx12_sender_release(void* h) {
+    if (!h) return;
+    dx12(h)->spout.ReleaseSender();
+    dx12_release_wrapped(dx12(h));
+}
+
+char* spout_dx12_sender_get_name(void* h) {
+    if (!h) return nullptr;
+    const char* s = dx12(h)->spout.GetName();
+    return s && *s ? _strdup(s) : nullptr;
+}
+
+void spout_dx12_receiver_set_name(void* h, const char* sender_name) {
+    if (h) dx12(h)->spout.SetReceiverName(sender_name);
+}
+
+bool spout_dx12_receiver_receive_resource(void* h) {
+    return h && dx12(h)->spout.ReceiveDX12Resource(&dx12(h)->received) && dx12(h)->received;
+}
+
+void* spout_dx12_receiver_get_resource(void* h) {
+    return h ? dx12(h)->received : nullptr;
+}
+
+void spout_dx12_receiver_release(void* h) {
+    if (h) dx12(h)->spout.ReleaseReceiver();
+}
+
+bool spout_dx12_receiver_get_sender_name(void* h, char* buf, int max_chars) {
+    if (!h || !buf || max_chars <= 0) return false;
+    const char* s = dx12(h)->spout.GetSenderName();
+    if (!s || !*s) return false;
+    strncpy_s(buf, (size_t)max_chars, s, _TRUNCATE);
+    return true;
+}
+
+bool spout_dx12_receiver_is_frame_new(void* h) {
+    return h && dx12(h)->spout.IsFrameNew();
+}
+
+bool spout_dx12_receiver_is_updated(void* h) {
+    return h && dx12(h)->spout.IsUpdated();
+}
+
+bool spout_dx12_receiver_is_connected(void* h) {
+    return h && dx12(h)->spout.IsConnected();
+}
+
+unsigned int spout_dx12_receiver_get_sender_width(void* h) {
+    return h ? dx12(h)->spout.GetSenderWidth() : 0;
+}
+
+unsigned int spout_dx12_receiver_get_sender_height(void* h) {
+    return h ? dx12(h)->spout.GetSenderHeight() : 0;
+}
+
+unsigned int spout_dx12_receiver_get_sender_format(void* h) {
+    return h ? (unsigned int)dx12(h)->spout.GetSenderFormat() : 0;
+}
+
+/* ---- OpenGL through WGL_NV_DX_interop ---- */
+
+#define GLDX_ACCESS_READ_WRITE 0x0001 /* WGL_ACCESS_READ_WRITE_NV */
+#define GLDX_READ_FRAMEBUFFER 0x8CA8
+#define GLDX_DRAW_FRAMEBUFFER 0x8CA9
+#define GLDX_READ_FRAMEBUFFER_BINDING 0x8CAA
+#define GLDX_DRAW_FRAMEBUFFER_BINDING 0x8CA6
+#define GLDX_COLOR_ATTACHMENT0 0x8CE0
+
+typedef HANDLE(WINAPI* GldxOpenDevice)(void* dx_device);
+typedef BOOL(WINAPI* GldxCloseDevice)(HANDLE device);
+typedef HANDLE(WINAPI* GldxRegisterObject)(HANDLE device, void* dx_object, GLuint name, GLenum type, GLenum access);
+typedef BOOL(WINAPI* GldxUnregisterObject)(HANDLE device, HANDLE object);
+typedef BOOL(WINAPI* GldxLockObjects)(HANDLE device, GLint count, HANDLE* objects);
+typedef void(APIENTRY* GldxGenFramebuffers)(GLsizei n, GLuint* framebuffers);
+typedef void(APIENTRY* GldxDeleteFramebuffers)(GLsizei n, const GLuint* framebuffers);
+typedef void(APIENTRY* GldxBindFramebuffer)(GLenum target, GLuint framebuffer);
+typedef void(APIENTRY* GldxFramebufferTexture2D)(GLenum target, GLenum attachment, GLenum textarget, GLuint texture, GLint level);
+typedef void(APIENTRY* GldxBlitFramebuffer)(GLint sx0, GLint sy0, GLint sx1, GLint sy1, GLint dx0, GLint dy0, GLint dx1, GLint dy1, GLbitfield mask, GLenum filter);
+
+/* WGL_NV_DX_interop and framebuffer entry points of the current context. */
+struct GldxProcs {
+    GldxOpenDevice open_device = nullptr;
+    GldxCloseDevice close_device = nullptr;
+    GldxRegisterObject register_object = nullptr;
+    GldxUnregisterObject unregister_object = nullptr;
+    GldxLockObjects lock_objects = nullptr;
+    GldxLockObjects unlock_objects = nullptr;
+    GldxGenFramebuffers gen_framebuffers = nullptr;
+    GldxDeleteFramebuffers delete_framebuffers = nullptr;
+    GldxBindFramebuffer bind_framebuffer = nullptr;
+    GldxFramebufferTexture2D framebuffer_texture_2d = nullptr;
+    GldxBlitFramebuffer blit_framebuffer = nullptr;
+
+    bool load() {
+        if (!wglGetCurrentContext()) return false;
+        open_device = (GldxOpenDevice)wglGetProcAddress("wglDXOpenDeviceNV");
+        close_device = (GldxCloseDevice)wglGetProcAddress("wglDXCloseDeviceNV");
+        register_object = (GldxRegisterObject)wglGetProcAddress("wglDXRegisterObjectNV");
+        unregister_object = (GldxUnregisterObject)wglGetProcAddress("wglDXUnregisterObjectNV");
+        lock_objects = (GldxLockObjects)wglGetProcAddress("wglDXLockObjectsNV");
+        unlock_objects = (GldxLockObjects)wglGetProcAddress("wglDXUnlockObjectsNV");
+        gen_framebuffers = (GldxGenFramebuffers)wglGetProcAddress("glGenFramebuffers");
+        delete_framebuffers = (GldxDeleteFramebuffers)wglGetProcAddress("glDeleteFramebuffers");
+        bind_framebuffer = (GldxBindFramebuffer)wglGetProcAddress("glBindFramebuffer");
+        framebuffer_texture_2d = (GldxFramebufferTexture2D)wglGetProcAddress("glFramebufferTexture2D");
+        blit_framebuffer = (GldxBlitFramebuffer)wglGetProcAddress("glBlitFramebuffer");
+        return open_device && close_device && register_object && unregister_object && lock_objects &&
+               unlock_objects && gen_framebuffers && delete_framebuffers && bind_framebuffer &&
+               framebuffer_texture_2d && blit_framebuffer;
+    }
+};
+
+/* SpoutDX on its own device, plus the GL texture linked to a D3D11 texture of that device. */
+struct GldxGlue {
+    spoutDX spout;
+    GldxProcs gl;
+    HANDLE interop_device = nullptr;
+    ID3D11Texture2D* texture = nullptr;
+    HANDLE interop_object = nullptr;
+    GLuint gl_texture = 0;
+    GLuint framebuffers[2] = {0, 0};
+    unsigned int width = 0;
+    unsigned int height = 0;
+    DXGI_FORMAT format = DXGI_FORMAT_UNKNOWN;
+    bool locked = false;
+};
+
+static GldxGlue* gldx(void* h) {
+    return reinterpret_cast<GldxGlue*>(h);
+}
+
+bool spout_gldx_available(void) {
+    return wglGetCurrentContext() && wglGetProcAddress("wglDXOpenDeviceNV");
+}
+
+/* Open the interop device on first use, when a GL context is current. */
+static bool gldx_open(GldxGlue* g) {
+    if (g->interop_device) return true;
+    if (!g->gl.load()) return false;
+    g->interop_device = g->gl.open_device(g->spout.GetDX11Device());
+    if (!g->interop_device) return false;
+    g->gl.gen_framebuffers(2, g->framebuffers);
+    return true;
+}
+
+static void gldx_unlock(GldxGlue* g) {
+    if (g->locked) g->gl.unlock_objects(g->interop_device, 1, &g->interop_object);
+    g->locked = false;
+}
+
+static bool gldx_lock(GldxGlue* g) {
+    if (!g->locked) g->locked = g->gl.lock_objects(g->interop_device, 1, &g->interop_object) != FALSE;
+    return g->locked;
+}
+
+static void gldx_unlink(GldxGlue* g) {
+    gldx_unlock(g);
+    if (g->interop_object) g->gl.unregister_object(g->interop_device, g->interop_object);
+    g->interop_object = nullptr;
+    if (g->texture) g->texture->Release();
+    g->texture = nullptr;
+    g->width = g->height = 0;
+}
+
+/* (Re)create the linked texture at `width` x `height` in `format`. */
+static bool gldx_link(GldxGlue* g, unsigned int width, unsigned int height, DXGI_FORMAT format) {
+    if (g->texture && g->width == width && g->height == height && g->format == format) return true;
+    gldx_unlink(g);
+    D3D11_TEXTURE2D_DESC desc = {};
+    desc.Width = width;
+    desc.Height = height;
+    desc.MipLevels = 1;
+    desc.ArraySize = 1;
+    desc.Format = format;
+    desc.SampleDesc.Count = 1;
+    desc.Usage = D3D11_USAGE_DEFAULT;
+    desc.BindFlags = D3D11_BIND_RENDER_TARGET | D3D11_BIND_SHADER_RESOURCE;
+    if (FAILED(g->spout.GetDX11Device()->CreateTexture2D(&desc, nullptr, &g->texture))) {
+        g->texture = nullptr;
+        return false;
+    }
+    if (!g->gl_texture) glGenTextures(1, &g->gl_texture);
+    g->interop_object = g->gl.register_object(g->interop_device, g->texture, g->gl_texture, GL_TEXTURE_2D,
+                                              GLDX_ACCESS_READ_WRITE);
+    if (!g->interop_object) {
+        gldx_unlink(g);
+        return false;
+    }
+    g->width = width;
+    g->height = height;
+    g->format = format;
+    return true;
+}
+
+/* Blit between two textures through the glue's framebuffers, restoring the app's bindings. */
+static void gldx_blit(GldxGlue* g, GLuint src, GLenum src_target, GLuint dst, GLenum dst_target,
+                      unsigned int width, unsigned int height, bool invert) {
+    GLint read_fbo = 0, draw_fbo = 0;
+    glGetIntegerv(GLDX_READ_FRAMEBUFFER_BINDING, &read_fbo);
+    glGetIntegerv(GLDX_DRAW_FRAMEBUFFER_BINDING, &draw_fbo);
+    g->gl.bind_framebuffer(GLDX_READ_FRAMEBUFFER, g->framebuffers[0]);
+    g->gl.framebuffer_texture_2d(GLDX_READ_FRAMEBUFFER, GLDX_COLOR_ATTACHMENT0, src_target, src, 0);
+    g->gl.bind_framebuffer(GLDX_DRAW_FRAMEBUFFER, g->framebuffers[1]);
+    g->gl.framebuffer_texture_2d(GLDX_DRAW_FRAMEBUFFER, GLDX_COLOR_ATTACHMENT0, dst_target, dst, 0);
+    GLint w = (GLint)width, h = (GLint)height;
+    g->gl.blit_framebuffer(0, 0, w, h, 0, invert ? h : 0, w, invert ? 0 : h, GL_COLOR_BUFFER_BIT, GL_NEAREST);
+    g->gl.bind_framebuffer(GLDX_READ_FRAMEBUFFER, (GLuint)read_fbo);
+    g->gl.bind_framebuffer(GLDX_DRAW_FRAMEBUFFER, (GLuint)draw_fbo);
+}
+
+void* spout_gldx_create(void) {
+    GldxGlue* g = new (std::nothrow) GldxGlue();
+    if (!g) return nullptr;
+    if (!g->spout.OpenDirectX11(nullptr)) {
+        delete g;
+        return nullptr;
+    }
+    return g;
+}
+
+void spout_gldx_destroy(void* h) {
+    if (!h) return;
+    GldxGlue* g = gldx(h);
+    g->spout.ReleaseSender();
+    g->spout.ReleaseReceiver();
+    if (g->interop_device) {
+        gldx_unlink(g);
+        g->gl.delete_framebuffers(2, g->framebuffers);
+        g->gl.close_device(g->interop_device);
+    }
+    if (g->gl_texture) glDeleteTextures(1, &g->gl_texture);
+    g->spout.CloseDirectX11();
+    delete g;
+}
+
+bool spout_gldx_sender_set_name(void* h, const char* name) {
+    return h && gldx(h)->spout.SetSenderName(name);
+}
+
+char* spout_gldx_sender_get_name(void* h) {
+    if (!h) return nullptr;
+    const char* s = gldx(h)->spout.GetName();
+    return s && *s ? _strdup(s) : nullptr;
+}
+
+bool spout_gldx_sender_send_texture(void* h, unsigned int tex_id, unsigned int target, unsigned int width,
+                                    unsigned int height, bool invert) {
+    if (!h || !tex_id || !width || !height) return false;
+    GldxGlue* g = gldx(h);
+    if (!gldx_open(g) || !gldx_link(g, width, height, DXGI_FORMAT_B8G8R8A8_UNORM) || !gldx_lock(g)) return false;
+    gldx_blit(g, tex_id, target, g->gl_texture, GL_TEXTURE_2D, width, height, invert);
+    gldx_unlock(g);
+    return g->spout.SendTexture(g->texture);
+}
+
+void spout_gldx_sender_release(void* h) {
+    if (h) gldx(h)->spout.ReleaseSender();
+}
+
+void spout_gldx_receiver_set_name(void* h, const char* sender_name) {
+    if (h) gldx(h)->spout.SetReceiverName(sender_name);
+}
+
+bool spout_gldx_receiver_receive_texture(void* h, unsigned int tex_id, unsigned int target, bool invert) {
+    if (!h) return false;
+    GldxGlue* g = gldx(h);
+    if (!gldx_open(g)) return false;
+    gldx_unlock(g);
+    if (!g->spout.ReceiveTexture()) return false;
+    ID3D11Texture2D* source = g->spout.GetSenderTexture();
+    if (!source) return false;
+    D3D11_TEXTURE2D_DESC desc = {};
+    source->GetDesc(&desc);
+    if (!gldx_link(g, desc.Width, desc.Height, desc.Format)) return false;
+    g->spout.GetDX11Context()->CopyResource(g->texture, source);
+    if (!gldx_lock(g)) return false;
+    if (tex_id) gldx_blit(g, g->gl_texture, GL_TEXTURE_2D, tex_id, target, g->width, g->height, invert);
+    return true;
+}
+
+unsigned int spout_gldx_receiver_get_texture(void* h) {
+    return h && gldx(h)->locked ? gldx(h)->gl_texture : 0;
+}
+
+void spout_gldx_receiver_release(void* h) {
+    if (h) gldx(h)->spout.ReleaseReceiver();
+}
+
+bool spout_gldx_receiver_get_sender_name(void* h, char* buf, int max_chars) {
+    if (!h || !buf || max_chars <= 0) return false;
+    const char* s = gldx(h)->spout.GetSenderName();
+    if (!s || !*s) return false;
+    strncpy_s(buf, (size_t)max_chars, s, _TRUNCATE);
+    return true;
+}
+
+bool spout_gldx_receiver_is_frame_new(void* h) {
+    return h && gldx(h)->spout.IsFrameNew();
+}
+
+bool spout_gldx_receiver_is_updated(void* h) {
+    return h && gldx(h)->spout.IsUpdated();
+}
+
+bool spout_gldx_receiver_is_connected(void* h) {
+    return h && gldx(h)->spout.IsConnected();
+}
+
+unsigned int spout_gldx_receiver_get_sender_width(void* h) {
+    return h ? gldx(h)->spout.GetSenderWidth() : 0;
+}
+
+unsigned int spout_gldx_receiver_get_sender_height(void* h) {
+    return h ? gldx(h)->spout.GetSenderHeight() : 0;
+}
+
 #endif /* _WIN32 */
@@ -86,6 +86,83 @@ void spout_set_buffers(spout_handle h, int buffers);
 bool spout_get_cpu_mode(spout_handle h);
 bool spout_set_cpu_mode(spout_handle h, bool cpu_mode);
 
+/* ---- DirectX 11 (SpoutDX) ----
+ * Handles are SpoutDX instances. Devices and textures are ID3D11Device* / ID3D11Texture2D*. */
+/* Open SpoutDX on `device` (NULL: SpoutDX creates its own device). NULL on failure. */
+void* spout_dx_create(void* device);
+void spout_dx_destroy(void* h);
+void* spout_dx_get_device(void* h);
+bool spout_dx_sender_set_name(void* h, const char* name);
+bool spout_dx_sender_send_texture(void* h, void* texture);
+void spout_dx_sender_release(void* h);
+char* spout_dx_sender_get_name(void* h);
+void spout_dx_receiver_set_name(void* h, const char* sender_name);
+/* Receive into SpoutDX's texture (spout_dx_receiver_get_sender_texture); false if no sender. */
+bool spout_dx_receiver_receive_texture(void* h);
+void* spout_dx_receiver_get_sender_texture(void* h);
+void spout_dx_receiver_release(void* h);
+bool spout_dx_receiver_get_sender_name(void* h, char* buf, int max_chars);
+bool spout_dx_receiver_is_frame_new(void* h);
+bool spout_dx_receiver_is_updated(void* h);
+bool spout_dx_receiver_is_connected(void* h);
+unsigned int spout_dx_receiver_get_sender_width(void* h);
+unsigned int spout_dx_receiver_get_sender_height(void* h);
+unsigned int spout_dx_receiver_get_sender_format(void* h);
+/* Receive and copy the frame into the caller's ID3D11Texture2D (same size and format as the sender). */
+bool spout_dx_receiver_copy_to_texture(void* h, void* texture);
+
+/* ---- DirectX 12 (SpoutDX12, through D3D11On12) ----
+ * Handles are SpoutDX12 instances. Devices, queues and resources are ID3D12Device* /
+ * ID3D12CommandQueue* / ID3D12Resource*; states are D3D12_RESOURCE_STATES values. */
+/* Open SpoutDX12 on `device` and `command_queue` (both NULL: SpoutDX12 creates its own). NULL on failure. */
+void* spout_dx12_create(void* device, void* command_queue);
+void spout_dx12_destroy(void* h);
+void* spout_dx12_get_device(void* h);
+bool spout_dx12_sender_set_name(void* h, const char* name);
+/* Share `resource`, which is in `state` before and after the call. The D3D11 wrapper is kept until a
+ * different resource is sent. */
+bool spout_dx12_sender_send_resource(void* h, void* resource, unsigned int state);
+void spout_dx12_sender_release(void* h);
+char* spout_dx12_sender_get_name(void* h);
+void spout_dx12_receiver_set_name(void* h, const char* sender_name);
+/* Receive into the glue's ID3D12Resource (spout_dx12_receiver_get_resource); false if no sender. */
+bool spout_dx12_receiver_receive_resource(void* h);
+void* spout_dx12_receiver_get_resource(void* h);
+void spout_dx12_receiver_release(void* h);
+bool spout_dx12_receiver_get_sender_name(void* h, char* buf, int max_chars);
+bool spout_dx12_receiver_is_frame_new(void* h);
+bool spout_dx12_receiver_is_updated(void* h);
+bool spout_dx12_receiver_is_connected(void* h);
+unsigned int spout_dx12_receiver_get_sender_width(void* h);
+unsigned int spout_dx12_receiver_get_sender_height(void* h);
+unsigned int spout_dx12_receiver_get_sender_format(void* h);
+
+/* ---- OpenGL through WGL_NV_DX_interop ----
+ * Handles own a SpoutDX instance on its own D3D11 device and a GL texture linked to a D3D11 texture of
+ * it, so GL frames reach Spout's shared textures without CPU copies. Call with a GL context current. */
+/* Whether the current GL context exposes WGL_NV_DX_interop. */
+bool spout_gldx_available(void);
+void* spout_gldx_create(void);
+void spout_gldx_destroy(void* h);
+bool spout_gldx_sender_set_name(void* h, const char* name);
+char* spout_gldx_sender_get_name(void* h);
+/* Blit `tex_id` (bound to `target`) into the linked texture (flipping rows if `invert`) and share it. */
+bool spout_gldx_sender_send_texture(void* h, unsigned int tex_id, unsigned int target, unsigned int width, unsigned int height, bool invert);
+void spout_gldx_sender_release(void* h);
+void spout_gldx_receiver_set_name(void* h, const char* sender_name);
+/* Receive into the linked texture and, unless `tex_id` is 0, blit it into `tex_id` (flipping rows if
+ * `invert`). The linked texture stays usable from GL until the next receive. */
+bool spout_gldx_receiver_receive_texture(void* h, unsigned int tex_id, unsigned int target, bool invert);
+/* GL name of the linked GL_TEXTURE_2D (top-down rows); 0 before the first frame. */
+unsigned int spout_gldx_receiver_get_texture(void* h);
+void spout_gldx_receiver_release(void* h);
+bool spout_gldx_receiver_get_sender_name(void* h, char* buf, int max_chars);
+bool spout_gldx_receiver_is_frame_new(void* h);
+bool spout_gldx_receiver_is_updated(void* h);
+bool spout_gldx_receiver_is_connected(void* h);
+unsigned int spout_gldx_receiver_get_sender_width(void* h);
+unsigned int spout_gldx_receiver_get_sender_height(void* h);
+
 #ifdef __cplusplus
 }
 #endif
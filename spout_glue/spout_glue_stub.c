@@ -295,3 +295,284 @@ bool spout_set_cpu_mode(spout_handle h, bool cpu_mode) {
     (void)cpu_mode;
     return false;
 }
+
+void* spout_dx_create(void* device) {
+    (void)device;
+    return NULL;
+}
+
+void spout_dx_destroy(void* h) {
+    (void)h;
+}
+
+void* spout_dx_get_device(void* h) {
+    (void)h;
+    return NULL;
+}
+
+bool spout_dx_sender_set_name(void* h, const char* name) {
+    (void)h;
+    (void)name;
+    return false;
+}
+
+bool spout_dx_sender_send_texture(void* h, void* texture) {
+    (void)h;
+    (void)texture;
+    return false;
+}
+
+void spout_dx_sender_release(void* h) {
+    (void)h;
+}
+
+char* spout_dx_sender_get_name(void* h) {
+    (void)h;
+    return NULL;
+}
+
+void spout_dx_receiver_set_name(void* h, const char* sender_name) {
+    (void)h;
+    (void)sender_name;
+}
+
+bool spout_dx_receiver_receive_texture(void* h) {
+    (void)h;
+    return false;
+}
+
+void* spout_dx_receiver_get_sender_texture(void* h) {
+    (void)h;
+    return NULL;
+}
+
+void spout_dx_receiver_release(void* h) {
+    (void)h;
+}
+
+bool spout_dx_receiver_get_sender_name(void* h, char* buf, int max_chars) {
+    (void)h;
+    if (buf && max_chars > 0)
+        buf[0] = '\0';
+    return false;
+}
+
+bool spout_dx_receiver_is_frame_new(void* h) {
+    (void)h;
+    return false;
+}
+
+bool spout_dx_receiver_is_updated(void* h) {
+    (void)h;
+    return false;
+}
+
+bool spout_dx_receiver_is_connected(void* h) {
+    (void)h;
+    return false;
+}
+
+unsigned int spout_dx_receiver_get_sender_width(void* h) {
+    (void)h;
+    return 0;
+}
+
+unsigned int spout_dx_receiver_get_sender_height(void* h) {
+    (void)h;
+    return 0;
+}
+
+unsigned int spout_dx_receiver_get_sender_format(void* h) {
+    (void)h;
+    return 0;
+}
+
+bool spout_dx_receiver_copy_to_texture(void* h, void* texture) {
+    (void)h;
+    (void)texture;
+    return false;
+}
+
+void* spout_dx12_create(void* device, void* command_queue) {
+    (void)device;
+    (void)command_queue;
+    return NULL;
+}
+
+void spout_dx12_destroy(void* h) {
+    (void)h;
+}
+
+void* spout_dx12_get_device(void* h) {
+    (void)h;
+    return NULL;
+}
+
+bool spout_dx12_sender_set_name(void* h, const char* name) {
+    (void)h;
+    (void)name;
+    return false;
+}
+
+bool spout_dx12_sender_send_resource(void* h, void* resource, unsigned int state) {
+    (void)h;
+    (void)resource;
+    (void)state;
+    return false;
+}
+
+void spout_dx12_sender_release(void* h) {
+    (void)h;
+}
+
+char* spout_dx12_sender_get_name(void* h) {
+    (void)h;
+    return NULL;
+}
+
+void spout_dx12_receiver_set_name(void* h, const char* sender_name) {
+    (void)h;
+    (void)sender_name;
+}
+
+bool spout_dx12_receiver_receive_resource(void* h) {
+    (void)h;
+    return false;
+}
+
+void* spout_dx12_receiver_get_resource(void* h) {
+    (void)h;
+    return NULL;
+}
+
+void spout_dx12_receiver_release(void* h) {
+    (void)h;
+}
+
+bool spout_dx12_receiver_get_sender_name(void* h, char* buf, int max_chars) {
+    (void)h;
+    if (buf && max_chars > 0)
+        buf[0] = '\0';
+    return false;
+}
+
+bool spout_dx12_receiver_is_frame_new(void* h) {
+    (void)h;
+    return false;
+}
+
+bool spout_dx12_receiver_is_updated(void* h) {
+    (void)h;
+    return false;
+}
+
+bool spout_dx12_receiver_is_connected(void* h) {
+    (void)h;
+    return false;
+}
+
+unsigned int spout_dx12_receiver_get_sender_width(void* h) {
+    (void)h;
+    return 0;
+}
+
+unsigned int spout_dx12_receiver_get_sender_height(void* h) {
+    (void)h;
+    return 0;
+}
+
+unsigned int spout_dx12_receiver_get_sender_format(void* h) {
+    (void)h;
+    return 0;
+}
+
+bool spout_gldx_available(void) {
+    return false;
+}
+
+void* spout_gldx_create(void) {
+    return NULL;
+}
+
+void spout_gldx_destroy(void* h) {
+    (void)h;
+}
+
+bool spout_gldx_sender_set_name(void* h, const char* name) {
+    (void)h;
+    (void)name;
+    return false;
+}
+
+char* spout_gldx_sender_get_name(void* h) {
+    (void)h;
+    return NULL;
+}
+
+bool spout_gldx_sender_send_texture(void* h, unsigned int tex_id, unsigned int target, unsigned int width, unsigned int height, bool invert) {
+    (void)h;
+    (void)tex_id;
+    (void)target;
+    (void)width;
+    (void)height;
+    (void)invert;
+    return false;
+}
+
+void spout_gldx_sender_release(void* h) {
+    (void)h;
+}
+
+void spout_gldx_receiver_set_name(void* h, const char* sender_name) {
+    (void)h;
+    (void)sender_name;
+}
+
+bool spout_gldx_receiver_receive_texture(void* h, unsigned int tex_id, unsigned int target, bool invert) {
+    (void)h;
+    (void)tex_id;
+    (void)target;
+    (void)invert;
+    return false;
+}
+
+unsigned int spout_gldx_receiver_get_texture(void* h) {
+    (void)h;
+    return 0;
+}
+
+void spout_gldx_receiver_release(void* h) {
+    (void)h;
+}
+
+bool spout_gldx_receiver_get_sender_name(void* h, char* buf, int max_chars) {
+    (void)h;
+    if (buf && max_chars > 0)
+        buf[0] = '\0';
+    return false;
+}
+
+bool spout_gldx_receiver_is_frame_new(void* h) {
+    (void)h;
+    return false;
+}
+
+bool spout_gldx_receiver_is_updated(void* h) {
+    (void)h;
+    return false;
+}
+
+bool spout_gldx_receiver_is_connected(void* h) {
+    (void)h;
+    return false;
+}
+
+unsigned int spout_gldx_receiver_get_sender_width(void* h) {
+    (void)h;
+    return 0;
+}
+
+unsigned int spout_gldx_receiver_get_sender_height(void* h) {
+    (void)h;
+    return 0;
+}
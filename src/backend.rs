@@ -0,0 +1,168 @@
+//! Backend-generic servers and clients: `FrameServer` / `FrameClient` are implemented by the OpenGL and
+//! Metal types, so apps with both render paths can share the code around them (naming, discovery,
+//! polling, shutdown). Publishing stays backend-specific, since it takes GL texture names or Metal objects.
+
+use crate::{
+    Frame, MetalClient, MetalServer, MetalTexture, NewFrameCallback, OpenGLClient, OpenGLImage, OpenGLServer,
+    ServerDescription, SyphonError, TimedFrame,
+};
+
+/// Graphics API a server or client shares frames through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backend {
+    OpenGL,
+    Metal,
+}
+
+/// A Syphon server of either backend.
+pub trait FrameServer {
+    /// The backend's frame handle (`OpenGLImage` or `MetalTexture`).
+    type Frame;
+
+    /// Which backend this server publishes through.
+    fn backend(&self) -> Backend;
+    /// Human-readable server name, if set.
+    fn name(&self) -> Option<String>;
+    /// Change the name; `InvalidArgument` if it contains a NUL byte.
+    fn try_set_name(&self, name: Option<&str>) -> Result<(), SyphonError>;
+    /// Whether any clients are attached.
+    fn has_clients(&self) -> bool;
+    /// The description clients connect with.
+    fn server_description(&self) -> Option<ServerDescription>;
+    /// The frame most recently published.
+    fn new_frame_image(&self) -> Option<Self::Frame>;
+    /// Stop the server (idempotent).
+    fn stop(&self);
+}
+
+/// A Syphon client of either backend.
+pub trait FrameClient {
+    /// The backend's frame handle (`OpenGLImage` or `MetalTexture`).
+    type Frame;
+
+    /// Which backend this client receives through.
+    fn backend(&self) -> Backend;
+    /// Description of the server this client is attached to.
+    fn server_description(&self) -> Option<ServerDescription>;
+    /// False once the server has gone away.
+    fn is_valid(&self) -> bool;
+    /// Whether a frame arrived since the last `new_frame_image`.
+    fn has_new_frame(&self) -> bool;
+    /// The latest frame.
+    fn new_frame_image(&self) -> Option<Self::Frame>;
+    /// The latest frame as a backend-agnostic `Frame`, stamped with the time it was taken.
+    fn new_frame(&self) -> Option<TimedFrame>
+    where
+        Self::Frame: Into<Frame>,
+    {
+        self.new_frame_image().map(TimedFrame::now)
+    }
+    /// Replace (or with None, remove) the new-frame callback.
+    fn set_new_frame_handler(&self, callback: Option<NewFrameCallback>);
+    /// Stop receiving (idempotent).
+    fn stop(&self);
+}
+
+impl FrameServer for OpenGLServer {
+    type Frame = OpenGLImage;
+
+    fn backend(&self) -> Backend {
+        Backend::OpenGL
+    }
+    fn name(&self) -> Option<String> {
+        OpenGLServer::name(self)
+    }
+    fn try_set_name(&self, name: Option<&str>) -> Result<(), SyphonError> {
+        OpenGLServer::try_set_name(self, name)
+    }
+    fn has_clients(&self) -> bool {
+        OpenGLServer::has_clients(self)
+    }
+    fn server_description(&self) -> Option<ServerDescription> {
+        OpenGLServer::server_description(self)
+    }
+    fn new_frame_image(&self) -> Option<OpenGLImage> {
+        OpenGLServer::new_frame_image(self)
+    }
+    fn stop(&self) {
+        OpenGLServer::stop(self)
+    }
+}
+
+impl FrameServer for MetalServer {
+    type Frame = MetalTexture;
+
+    fn backend(&self) -> Backend {
+        Backend::Metal
+    }
+    fn name(&self) -> Option<String> {
+        MetalServer::name(self)
+    }
+    fn try_set_name(&self, name: Option<&str>) -> Result<(), SyphonError> {
+        MetalServer::try_set_name(self, name)
+    }
+    fn has_clients(&self) -> bool {
+        MetalServer::has_clients(self)
+    }
+    fn server_description(&self) -> Option<ServerDescription> {
+        MetalServer::server_description(self)
+    }
+    fn new_frame_image(&self) -> Option<MetalTexture> {
+        MetalServer::new_frame_image(self)
+    }
+    fn stop(&self) {
+        MetalServer::stop(self)
+    }
+}
+
+impl FrameClient for OpenGLClient {
+    type Frame = OpenGLImage;
+
+    fn backend(&self) -> Backend {
+        Backend::OpenGL
+    }
+    fn server_description(&self) -> Option<ServerDescription> {
+        OpenGLClient::server_description(self)
+    }
+    fn is_valid(&self) -> bool {
+        OpenGLClient::is_valid(self)
+    }
+    fn has_new_frame(&self) -> bool {
+        OpenGLClient::has_new_frame(self)
+    }
+    fn new_frame_image(&self) -> Option<OpenGLImage> {
+        OpenGLClient::new_frame_image(self)
+    }
+    fn set_new_frame_handler(&self, callback: Option<NewFrameCallback>) {
+        OpenGLClient::set_new_frame_handler(self, callback)
+    }
+    fn stop(&self) {
+        OpenGLClient::stop(self)
+    }
+}
+
+impl FrameClient for MetalClient {
+    type Frame = MetalTexture;
+
+    fn backend(&self) -> Backend {
+        Backend::Metal
+    }
+    fn server_description(&self) -> Option<ServerDescription> {
+        MetalClient::server_description(self)
+    }
+    fn is_valid(&self) -> bool {
+        MetalClient::is_valid(self)
+    }
+    fn has_new_frame(&self) -> bool {
+        MetalClient::has_new_frame(self)
+    }
+    fn new_frame_image(&self) -> Option<MetalTexture> {
+        MetalClient::new_frame_image(self)
+    }
+    fn set_new_frame_handler(&self, callback: Option<NewFrameCallback>) {
+        MetalClient::set_new_frame_handler(self, callback)
+    }
+    fn stop(&self) {
+        MetalClient::stop(self)
+    }
+}
@@ -0,0 +1,263 @@
+//! `syphon-monitor`: a top-like terminal view of all Syphon servers.
+//!
+//! Shows app, frame size, measured FPS, last-frame age and connection state for every server,
+//! using a lightweight OpenGL probe client per server on a headless CGL context. Probes are added and
+//! removed as directory notifications arrive.
+//!
+//! Run on macOS: cargo run --features monitor --bin syphon-monitor [-- --interval <ms>]
+//! Keys: `q` / `Esc` quit.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use rusty_syphon_spout::{
+    cgl_create_headless_context, cgl_destroy_context, cgl_make_current, pump_run_loop, CGLContextObj,
+    CallbackDispatch, DirectoryEvent, DirectorySubscription, OpenGLClient, ServerDescription, ServerDirectory,
+};
+
+/// Longest wait for a key press before the run loop is pumped again.
+const RUN_LOOP_SLICE: Duration = Duration::from_millis(50);
+
+/// What a probe's new-frame callback last saw.
+#[derive(Default)]
+struct LastFrame {
+    at: Option<Instant>,
+    size: Option<(usize, usize)>,
+}
+
+/// Per-server probe: a client whose new-frame callback counts frames and notes their size.
+struct Probe {
+    name: String,
+    app: String,
+    client: Option<OpenGLClient>,
+    frames: Arc<AtomicU64>,
+    last_frame: Arc<Mutex<LastFrame>>,
+    last_count: u64,
+    fps: f64,
+}
+
+impl Probe {
+    fn state(&self) -> (&'static str, Color) {
+        match &self.client {
+            None => ("no client", Color::Red),
+            Some(c) if !c.is_valid() => ("lost", Color::Red),
+            Some(_) if self.frames.load(Ordering::Relaxed) == 0 => ("waiting", Color::Yellow),
+            Some(_) => ("connected", Color::Green),
+        }
+    }
+
+    fn last_frame(&self) -> std::sync::MutexGuard<'_, LastFrame> {
+        self.last_frame.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+struct Monitor {
+    context: Option<CGLContextObj>,
+    probes: HashMap<String, Probe>,
+    /// Directory events, forwarded from the subscription's callback.
+    events: Receiver<DirectoryEvent>,
+    _subscription: Option<DirectorySubscription>,
+    last_refresh: Instant,
+}
+
+impl Monitor {
+    fn new() -> Self {
+        let context = cgl_create_headless_context();
+        if let Some(ctx) = context {
+            cgl_make_current(ctx);
+        }
+        let (tx, events) = mpsc::channel();
+        let directory = ServerDirectory::shared();
+        // Subscribe before listing, so a server announced in between isn't missed (a duplicate is ignored).
+        let subscription = directory.as_ref().and_then(|dir| {
+            dir.subscribe(Box::new(move |event| {
+                let _ = tx.send(event);
+            }))
+        });
+        let mut monitor = Self {
+            context,
+            probes: HashMap::new(),
+            events,
+            _subscription: subscription,
+            last_refresh: Instant::now(),
+        };
+        for desc in directory.map(|dir| dir.servers()).unwrap_or_default() {
+            monitor.add_probe(&desc);
+        }
+        monitor
+    }
+
+    fn add_probe(&mut self, desc: &ServerDescription) {
+        let Some(uuid) = desc.uuid() else { return };
+        let context = self.context;
+        self.probes.entry(uuid).or_insert_with(|| {
+            let frames = Arc::new(AtomicU64::new(0));
+            let last_frame = Arc::new(Mutex::new(LastFrame::default()));
+            let client = context.and_then(|ctx| {
+                let frames = Arc::clone(&frames);
+                let last_frame = Arc::clone(&last_frame);
+                OpenGLClient::new_with_dispatch(
+                    desc,
+                    ctx,
+                    None,
+                    Some(Box::new(move |info| {
+                        frames.fetch_add(1, Ordering::Relaxed);
+                        let mut last = last_frame.lock().unwrap_or_else(|e| e.into_inner());
+                        last.at = Some(info.timestamp);
+                        last.size = info.size.or(last.size);
+                    })),
+                    CallbackDispatch::SerializedWithSize,
+                )
+            });
+            Probe {
+                name: desc.name().unwrap_or_default(),
+                app: desc.app_name().unwrap_or_default(),
+                client,
+                frames,
+                last_frame,
+                last_count: 0,
+                fps: 0.0,
+            }
+        });
+    }
+
+    /// Apply pending directory events and update measured stats.
+    fn refresh(&mut self) {
+        let dt = self.last_refresh.elapsed().as_secs_f64();
+        self.last_refresh = Instant::now();
+
+        while let Ok(event) = self.events.try_recv() {
+            match &event {
+                DirectoryEvent::Added(desc) => self.add_probe(desc),
+                DirectoryEvent::Removed(desc) => {
+                    if let Some(uuid) = desc.uuid() {
+                        self.probes.remove(&uuid);
+                    }
+                }
+                DirectoryEvent::Updated(desc) => {
+                    if let Some(probe) = desc.uuid().and_then(|uuid| self.probes.get_mut(&uuid)) {
+                        probe.name = desc.name().unwrap_or_default();
+                        probe.app = desc.app_name().unwrap_or_default();
+                    }
+                }
+            }
+        }
+
+        for probe in self.probes.values_mut() {
+            let count = probe.frames.load(Ordering::Relaxed);
+            if dt > 0.0 {
+                probe.fps = (count - probe.last_count) as f64 / dt;
+            }
+            probe.last_count = count;
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [table_area, help_area] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+
+        let mut probes: Vec<&Probe> = self.probes.values().collect();
+        probes.sort_by(|a, b| (&a.app, &a.name).cmp(&(&b.app, &b.name)));
+        let rows = probes.iter().map(|p| {
+            let (state, color) = p.state();
+            let last = p.last_frame();
+            let size = last
+                .size
+                .map(|(w, h)| format!("{w}x{h}"))
+                .unwrap_or_else(|| "-".into());
+            let age = last
+                .at
+                .map(|t| format!("{} ms", t.elapsed().as_millis()))
+                .unwrap_or_else(|| "-".into());
+            drop(last);
+            Row::new(vec![
+                p.name.clone(),
+                p.app.clone(),
+                size,
+                format!("{:.1}", p.fps),
+                age,
+                state.to_string(),
+            ])
+            .style(Style::default().fg(color))
+        });
+        let widths = [
+            Constraint::Percentage(25),
+            Constraint::Percentage(25),
+            Constraint::Length(11),
+            Constraint::Length(7),
+            Constraint::Length(11),
+            Constraint::Length(10),
+        ];
+        let header = Row::new(["Server", "App", "Size", "FPS", "Last frame", "State"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        let title = if cfg!(target_os = "macos") {
+            format!(" Syphon servers ({}) ", probes.len())
+        } else {
+            " Syphon servers (macOS only) ".to_string()
+        };
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::bordered().title(title));
+        frame.render_widget(table, table_area);
+        frame.render_widget(Line::from(" q / Esc: quit"), help_area);
+    }
+}
+
+impl Drop for Monitor {
+    fn drop(&mut self) {
+        // Clients must go before the context they were created with.
+        self.probes.clear();
+        if let Some(ctx) = self.context.take() {
+            cgl_destroy_context(ctx);
+        }
+    }
+}
+
+fn parse_interval() -> Duration {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|a| a == "--interval")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(Duration::from_millis(500))
+}
+
+fn run(terminal: &mut DefaultTerminal, interval: Duration) -> std::io::Result<()> {
+    let mut monitor = Monitor::new();
+    monitor.refresh();
+    loop {
+        terminal.draw(|frame| monitor.draw(frame))?;
+        let deadline = Instant::now() + interval;
+        while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+            // Deliver directory notifications between key polls.
+            pump_run_loop(Duration::ZERO);
+            if !event::poll(timeout.min(RUN_LOOP_SLICE))? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    return Ok(());
+                }
+            }
+        }
+        monitor.refresh();
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let interval = parse_interval();
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, interval);
+    ratatui::restore();
+    result
+}
@@ -0,0 +1,383 @@
+//! `syphon`: command-line tools for checking a Syphon installation without writing Rust.
+//!
+//! ```text
+//! syphon list [--json]                         servers: name, app, size, UUID
+//! syphon watch                                 print servers appearing, changing and going away
+//! syphon snapshot <server> <out.png>           save a server's current frame
+//! syphon send <image | dir | video> [--name N]  publish a still, an image sequence, or a video (ffmpeg)
+//! syphon monitor [--interval <ms>]             FPS and frame size per server
+//! ```
+//!
+//! `<server>` matches a UUID, then a server name, then an app name. Frames move through `CpuServer` /
+//! `CpuClient`, so no GL context is needed.
+//!
+//! Run on macOS: cargo run --features cli --bin syphon -- list
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::{IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rusty_syphon_spout::{
+    image_sequence, supported_image_extensions, CallbackDispatch, CpuClient, CpuServer, DirectoryEvent, FrameInfo,
+    ImagePublisher, ImagePublisherOptions, ServerDescription, ServerDirectory,
+};
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+const USAGE: &str = "usage:
+  syphon list [--json]
+  syphon watch
+  syphon snapshot <server> <out.png> [--timeout <ms>]
+  syphon send <image | directory | video> [--sequence] [--name <name>] [--fps <fps>]
+  syphon monitor [--interval <ms>]";
+
+/// How long servers get to announce themselves before the directory is read.
+const DISCOVERY_WAIT: Duration = Duration::from_millis(500);
+
+/// Options that take no value.
+const SWITCHES: &[&str] = &["json", "sequence"];
+
+/// Positional arguments, `--flag value` options and `--switch`es after the subcommand.
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Self {
+            positional: Vec::new(),
+            options: HashMap::new(),
+        };
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(flag) if SWITCHES.contains(&flag) => {
+                    parsed.options.insert(flag.to_owned(), String::new());
+                }
+                Some(flag) => {
+                    let value = args.next().ok_or_else(|| format!("--{flag} needs a value"))?;
+                    parsed.options.insert(flag.to_owned(), value);
+                }
+                None => parsed.positional.push(arg),
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn positional(&self, index: usize, what: &str) -> Result<&str> {
+        self.positional
+            .get(index)
+            .map(String::as_str)
+            .ok_or_else(|| format!("missing {what}\n{USAGE}").into())
+    }
+
+    fn number(&self, flag: &str, default: f64) -> Result<f64> {
+        match self.options.get(flag) {
+            Some(v) => v
+                .parse::<f64>()
+                .ok()
+                .filter(|n| *n > 0.0)
+                .ok_or_else(|| format!("--{flag} must be a positive number").into()),
+            None => Ok(default),
+        }
+    }
+
+    /// A duration given in milliseconds; fractions are truncated, and values under 1 ms are rejected.
+    fn millis(&self, flag: &str, default: f64) -> Result<Duration> {
+        let ms = self.number(flag, default)? as u64;
+        if ms == 0 {
+            return Err(format!("--{flag} must be at least 1 ms").into());
+        }
+        Ok(Duration::from_millis(ms))
+    }
+
+    /// The time between frames for a rate given in frames per second.
+    fn frame_interval(&self, flag: &str, default_fps: f64) -> Result<Duration> {
+        let fps = self.number(flag, default_fps)?;
+        Duration::try_from_secs_f64(1.0 / fps)
+            .ok()
+            .filter(|interval| !interval.is_zero())
+            .ok_or_else(|| format!("--{flag} {fps} is out of range").into())
+    }
+}
+
+/// The shared directory, kept current by a background thread and given time to fill.
+fn directory() -> Result<ServerDirectory> {
+    ServerDirectory::start_background_updates();
+    let directory = ServerDirectory::try_shared()?;
+    std::thread::sleep(DISCOVERY_WAIT);
+    Ok(directory)
+}
+
+fn find_server(directory: &ServerDirectory, query: &str) -> Result<ServerDescription> {
+    let servers = directory.servers();
+    let by = |field: fn(&ServerDescription) -> Option<String>| {
+        servers.iter().find(|d| field(d).as_deref() == Some(query)).cloned()
+    };
+    by(ServerDescription::uuid)
+        .or_else(|| by(ServerDescription::name))
+        .or_else(|| by(ServerDescription::app_name))
+        .ok_or_else(|| format!("no server matching {query:?} (see `syphon list`)").into())
+}
+
+fn describe(d: &ServerDescription) -> String {
+    let size = d.size().map(|(w, h)| format!("{w}x{h}")).unwrap_or_else(|| "-".into());
+    format!(
+        "{}\t{}\t{}\t{}",
+        d.name().unwrap_or_default(),
+        d.app_name().unwrap_or_default(),
+        size,
+        d.uuid().unwrap_or_default()
+    )
+}
+
+fn list(args: &Args) -> Result<()> {
+    let directory = directory()?;
+    if args.options.contains_key("json") {
+        println!("{}", directory.snapshot_json());
+        return Ok(());
+    }
+    let mut servers = directory.servers();
+    servers.sort_by_key(|d| (d.app_name(), d.name()));
+    println!("NAME\tAPP\tSIZE\tUUID");
+    for d in &servers {
+        println!("{}", describe(d));
+    }
+    Ok(())
+}
+
+fn watch() -> Result<()> {
+    let directory = directory()?;
+    for d in directory.servers() {
+        println!("present\t{}", describe(&d));
+    }
+    let _subscription = directory
+        .subscribe(Box::new(|event| {
+            let kind = match &event {
+                DirectoryEvent::Added(_) => "added",
+                DirectoryEvent::Removed(_) => "removed",
+                DirectoryEvent::Updated(_) => "updated",
+            };
+            println!("{kind}\t{}", describe(event.description()));
+        }))
+        .ok_or("directory notifications are unavailable")?;
+    loop {
+        std::thread::park();
+    }
+}
+
+fn snapshot(args: &Args) -> Result<()> {
+    let query = args.positional(0, "server")?;
+    let out = PathBuf::from(args.positional(1, "output path")?);
+    let timeout = args.millis("timeout", 5000.0)?;
+    let description = find_server(&directory()?, query)?;
+    let client = CpuClient::try_new(&description, None)?;
+    let deadline = Instant::now().checked_add(timeout).ok_or("--timeout is out of range")?;
+    let frame = loop {
+        if let Some(frame) = client.latest_frame() {
+            break frame;
+        }
+        if Instant::now() >= deadline || !client.is_valid() {
+            return Err(format!("no frame from {query:?} within {} ms", timeout.as_millis()).into());
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+    write_png(&out, frame.width, frame.height, &frame.rgba)?;
+    println!("{}: {}x{}", out.display(), frame.width, frame.height);
+    Ok(())
+}
+
+fn write_png(path: &Path, width: usize, height: usize, rgba: &[u8]) -> Result<()> {
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut encoder = png::Encoder::new(file, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(rgba)?;
+    Ok(())
+}
+
+/// Width, height and frame rate of a video's first stream, from `ffprobe`.
+fn probe_video(path: &Path) -> Result<(usize, usize, f64)> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "v:0", "-show_entries", "stream=width,height,r_frame_rate"])
+        .args(["-of", "csv=p=0"])
+        .arg(path)
+        .output()
+        .map_err(|e| format!("ffprobe (needed to send videos): {e}"))?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let fields: Vec<&str> = text.trim().split(',').collect();
+    let parse = |s: Option<&&str>| s.and_then(|s| s.parse::<usize>().ok()).filter(|n| *n > 0);
+    let (Some(width), Some(height)) = (parse(fields.first()), parse(fields.get(1))) else {
+        return Err(format!("{}: no video stream", path.display()).into());
+    };
+    let fps = fields
+        .get(2)
+        .and_then(|rate| {
+            let (num, den) = rate.split_once('/').unwrap_or((rate, "1"));
+            Some(num.parse::<f64>().ok()? / den.parse::<f64>().ok()?)
+        })
+        .filter(|fps| fps.is_finite() && *fps > 0.0)
+        .unwrap_or(30.0);
+    Ok((width, height, fps))
+}
+
+fn send(args: &Args) -> Result<()> {
+    let path = PathBuf::from(args.positional(0, "image or video")?);
+    let default_name = path.file_stem().map(|s| s.to_string_lossy().into_owned());
+    let name = args.options.get("name").cloned().or(default_name);
+    let is_image = path.is_dir()
+        || path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| supported_image_extensions().contains(&e.to_ascii_lowercase().as_str()));
+    if is_image {
+        let paths = if path.is_dir() || args.options.contains_key("sequence") {
+            image_sequence(&path)
+        } else {
+            vec![path.clone()]
+        };
+        let options = ImagePublisherOptions {
+            fps: args.number("fps", if paths.len() > 1 { 30.0 } else { 1.0 })?,
+            looping: true,
+        };
+        let publisher = ImagePublisher::try_new(name.as_deref(), paths, &options)?;
+        println!(
+            "sending {} ({} image(s) at {:.2} fps) as {:?}",
+            path.display(),
+            publisher.frame_count(),
+            options.fps,
+            name.unwrap_or_default()
+        );
+        let mut reported = None;
+        while publisher.is_running() {
+            if let Some(error) = publisher.last_error().filter(|e| reported.as_ref() != Some(e)) {
+                eprintln!("syphon: skipped a frame: {error}");
+                reported = Some(error);
+            }
+            std::thread::sleep(Duration::from_millis(250));
+        }
+        return Err("image publisher stopped".into());
+    }
+    let (width, height, video_fps) = probe_video(&path)?;
+    let interval = args.frame_interval("fps", video_fps)?;
+    let server = CpuServer::try_new(name.as_deref(), None)?;
+    let mut ffmpeg = Command::new("ffmpeg")
+        .args(["-v", "error", "-stream_loop", "-1", "-i"])
+        .arg(&path)
+        .args(["-f", "rawvideo", "-pix_fmt", "rgba", "-"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("ffmpeg (needed to send videos): {e}"))?;
+    let mut stdout = ffmpeg.stdout.take().ok_or("ffmpeg has no output")?;
+    let fps = 1.0 / interval.as_secs_f64();
+    println!("sending {} ({width}x{height} at {fps:.2} fps) as {:?}", path.display(), name.unwrap_or_default());
+    let mut frame = vec![0; width * height * 4];
+    let mut next = Instant::now();
+    while stdout.read_exact(&mut frame).is_ok() {
+        server.publish(&frame, width, height);
+        next += interval;
+        match next.checked_duration_since(Instant::now()) {
+            Some(wait) => std::thread::sleep(wait),
+            None => next = Instant::now(),
+        }
+    }
+    let status = ffmpeg.wait()?;
+    Err(format!("ffmpeg stopped ({status})").into())
+}
+
+/// Frames counted by a monitor client's callback.
+#[derive(Default)]
+struct Counter {
+    frames: u64,
+    size: Option<(usize, usize)>,
+    last_frame: Option<Instant>,
+}
+
+struct Probe {
+    description: ServerDescription,
+    counter: Arc<Mutex<Counter>>,
+    last_count: u64,
+    _client: Option<CpuClient>,
+}
+
+fn monitor(args: &Args) -> Result<()> {
+    let interval = args.millis("interval", 1000.0)?;
+    let directory = directory()?;
+    let clear = std::io::stdout().is_terminal();
+    let mut probes: HashMap<String, Probe> = HashMap::new();
+    loop {
+        let servers = directory.servers();
+        probes.retain(|uuid, _| servers.iter().any(|d| d.uuid().as_ref() == Some(uuid)));
+        for d in servers {
+            let Some(uuid) = d.uuid() else { continue };
+            probes.entry(uuid).or_insert_with(|| {
+                let counter = Arc::new(Mutex::new(Counter::default()));
+                let callback = {
+                    let counter = Arc::clone(&counter);
+                    Box::new(move |info: FrameInfo| {
+                        let mut counter = counter.lock().unwrap_or_else(|e| e.into_inner());
+                        counter.frames += 1;
+                        counter.size = info.size;
+                        counter.last_frame = Some(info.timestamp);
+                    })
+                };
+                Probe {
+                    // Frames are only counted, never read, so consuming each one for its size is harmless.
+                    _client: CpuClient::try_new_with_dispatch(&d, Some(callback), CallbackDispatch::SerializedWithSize).ok(),
+                    description: d,
+                    counter,
+                    last_count: 0,
+                }
+            });
+        }
+        std::thread::sleep(interval);
+
+        let mut rows: Vec<_> = probes
+            .values_mut()
+            .map(|probe| {
+                let counter = probe.counter.lock().unwrap_or_else(|e| e.into_inner());
+                let fps = (counter.frames - probe.last_count) as f64 / interval.as_secs_f64();
+                probe.last_count = counter.frames;
+                let size = counter.size.map(|(w, h)| format!("{w}x{h}")).unwrap_or_else(|| "-".into());
+                let age = counter
+                    .last_frame
+                    .map(|t| format!("{} ms", t.elapsed().as_millis()))
+                    .unwrap_or_else(|| "-".into());
+                let d = &probe.description;
+                (d.app_name().unwrap_or_default(), d.name().unwrap_or_default(), size, fps, age)
+            })
+            .collect();
+        rows.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        if clear {
+            print!("\x1b[2J\x1b[H");
+        }
+        println!("{:<24} {:<24} {:>11} {:>7} {:>11}", "APP", "SERVER", "SIZE", "FPS", "LAST FRAME");
+        for (app, name, size, fps, age) in rows {
+            println!("{app:<24} {name:<24} {size:>11} {fps:>7.1} {age:>11}");
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let command = args.next();
+    let result = Args::parse(args).and_then(|args| match command.as_deref() {
+        Some("list") => list(&args),
+        Some("watch") => watch(),
+        Some("snapshot") => snapshot(&args),
+        Some("send") => send(&args),
+        Some("monitor") => monitor(&args),
+        _ => Err(USAGE.into()),
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("syphon: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,304 @@
+//! Frame blending for low-FPS sources: crossfades between the last two received frames when the
+//! consumer renders faster than the source publishes (e.g. a 24/30 fps feed on a 60 Hz output).
+//!
+//! `BlendClock` turns frame arrival times into a blend factor; `GlFrameBlender` and
+//! `MetalFrameBlender` keep owned copies of the last two frames and mix them on the GPU. Blending
+//! ramps from the previous frame to the newest over one source interval, so it adds up to one source
+//! frame of latency in exchange for smooth motion.
+
+#[cfg(target_os = "macos")]
+use std::ptr::NonNull;
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "macos")]
+use crate::ffi;
+use crate::{
+    gl_delete_texture, MTLCommandBufferPtr, MTLCommandQueuePtr, MTLDevicePtr, MTLTexturePtr, MetalTexture,
+    MetalTextureOptions, MetalTexturePool, OpenGLImage,
+};
+
+/// How frames are combined between arrivals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    /// Always show the newest frame.
+    Off,
+    /// Crossfade linearly from the previous frame to the newest over one source interval.
+    #[default]
+    Linear,
+}
+
+/// Tracks frame arrivals and computes the blend factor for a render time.
+#[derive(Debug, Clone, Default)]
+pub struct BlendClock {
+    mode: BlendMode,
+    previous: Option<Instant>,
+    latest: Option<Instant>,
+}
+
+impl BlendClock {
+    pub fn new(mode: BlendMode) -> Self {
+        Self {
+            mode,
+            previous: None,
+            latest: None,
+        }
+    }
+
+    pub fn mode(&self) -> BlendMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: BlendMode) {
+        self.mode = mode;
+    }
+
+    /// Record that a new frame arrived at `at`.
+    pub fn push(&mut self, at: Instant) {
+        self.previous = self.latest;
+        self.latest = Some(at);
+    }
+
+    /// Interval between the last two frames, if two have arrived.
+    pub fn source_interval(&self) -> Option<Duration> {
+        Some(self.latest?.saturating_duration_since(self.previous?))
+    }
+
+    /// Blend factor at render time `now`: 0.0 shows the previous frame, 1.0 the newest.
+    pub fn factor(&self, now: Instant) -> f32 {
+        if self.mode == BlendMode::Off {
+            return 1.0;
+        }
+        let (Some(latest), Some(interval)) = (self.latest, self.source_interval()) else {
+            return 1.0;
+        };
+        if interval.is_zero() {
+            return 1.0;
+        }
+        let elapsed = now.saturating_duration_since(latest);
+        (elapsed.as_secs_f32() / interval.as_secs_f32()).clamp(0.0, 1.0)
+    }
+}
+
+/// Blends the last two frames of an OpenGL client. Create and use with the client's context current.
+pub struct GlFrameBlender {
+    #[cfg(target_os = "macos")]
+    ptr: NonNull<std::ffi::c_void>,
+    clock: BlendClock,
+    /// Owned GL_TEXTURE_2D copies; `slots[newest]` is the latest frame.
+    slots: [u32; 2],
+    newest: usize,
+    size: (usize, usize),
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl GlFrameBlender {
+    /// Compile the blend program on the current context. Returns None if it fails.
+    pub fn new(mode: BlendMode) -> Option<Self> {
+        #[cfg(target_os = "macos")]
+        {
+            let ptr = unsafe { ffi::syphon_gl_blender_create() };
+            NonNull::new(ptr).map(|ptr| Self {
+                ptr,
+                clock: BlendClock::new(mode),
+                slots: [0; 2],
+                newest: 0,
+                size: (0, 0),
+            })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    pub fn clock(&self) -> &BlendClock {
+        &self.clock
+    }
+
+    pub fn set_mode(&mut self, mode: BlendMode) {
+        self.clock.set_mode(mode);
+    }
+
+    /// Copy a newly received frame in. Call once per new frame (e.g. when `has_new_frame` was true).
+    pub fn push_image(&mut self, image: &OpenGLImage) -> bool {
+        let (w, h) = image.texture_size();
+        let size = (w as usize, h as usize);
+        if size != self.size {
+            self.delete_slots();
+            self.size = size;
+        }
+        let slot = 1 - self.newest;
+        let dst = (self.slots[slot] != 0).then_some(self.slots[slot]);
+        match image.copy_to_owned_texture(dst) {
+            Some(tex) => {
+                self.slots[slot] = tex;
+                self.newest = slot;
+                self.clock.push(Instant::now());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The `GL_TEXTURE_2D` to draw at `now`: a blend of the last two frames (owned by the blender,
+    /// valid until the next call), or the only frame so far. None before the first frame.
+    pub fn render(&mut self, now: Instant) -> Option<u32> {
+        let latest = self.slots[self.newest];
+        let previous = self.slots[1 - self.newest];
+        if latest == 0 {
+            return None;
+        }
+        let t = self.clock.factor(now);
+        if previous == 0 || t >= 1.0 {
+            return Some(latest);
+        }
+        #[cfg(target_os = "macos")]
+        {
+            let (w, h) = self.size;
+            let out = unsafe { ffi::syphon_gl_blender_blend(self.ptr.as_ptr(), previous, latest, w, h, t) };
+            Some(if out != 0 { out } else { latest })
+        }
+        #[cfg(not(target_os = "macos"))]
+        Some(latest)
+    }
+
+    fn delete_slots(&mut self) {
+        for tex in &mut self.slots {
+            if *tex != 0 {
+                gl_delete_texture(*tex);
+                *tex = 0;
+            }
+        }
+    }
+}
+
+impl Drop for GlFrameBlender {
+    fn drop(&mut self) {
+        self.delete_slots();
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_gl_blender_release(self.ptr.as_ptr());
+        }
+    }
+}
+
+/// Blends the last two frames of a Metal client on the GPU.
+pub struct MetalFrameBlender {
+    #[cfg(target_os = "macos")]
+    ptr: NonNull<std::ffi::c_void>,
+    clock: BlendClock,
+    command_queue: Option<MTLCommandQueuePtr>,
+    texture_options: MetalTextureOptions,
+    pool: Option<MetalTexturePool>,
+    previous: Option<MetalTexture>,
+    latest: Option<MetalTexture>,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl MetalFrameBlender {
+    /// Build the blend pipeline on `device`. `command_queue` is used for frame copies (None creates one per copy).
+    pub fn new(device: MTLDevicePtr, command_queue: Option<MTLCommandQueuePtr>, mode: BlendMode) -> Option<Self> {
+        #[cfg(target_os = "macos")]
+        {
+            if device.is_null() {
+                return None;
+            }
+            let ptr = unsafe { ffi::syphon_metal_blender_create(device as *mut _) };
+            NonNull::new(ptr).map(|ptr| Self {
+                ptr,
+                clock: BlendClock::new(mode),
+                command_queue,
+                texture_options: MetalTextureOptions::default(),
+                pool: None,
+                previous: None,
+                latest: None,
+            })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    pub fn clock(&self) -> &BlendClock {
+        &self.clock
+    }
+
+    pub fn set_mode(&mut self, mode: BlendMode) {
+        self.clock.set_mode(mode);
+    }
+
+    /// Storage mode and usage of the frame copies and the blended output (shader-write is always
+    /// added to the output). Applies to frames pushed and blends rendered after the call.
+    pub fn set_texture_options(&mut self, options: MetalTextureOptions) {
+        self.texture_options = options;
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_metal_blender_set_output_options(
+                self.ptr.as_ptr(),
+                options.storage_mode as _,
+                options.usage as _,
+            );
+        }
+    }
+
+    pub fn texture_options(&self) -> MetalTextureOptions {
+        self.texture_options
+    }
+
+    /// Allocate frame copies from `pool` (its storage mode replaces the options' one) instead of the
+    /// device. None goes back to device allocations.
+    pub fn set_texture_pool(&mut self, pool: Option<MetalTexturePool>) {
+        self.pool = pool;
+    }
+
+    /// Copy a newly received frame in (the Syphon texture can be dropped right after).
+    pub fn push_texture(&mut self, texture: &MetalTexture) -> bool {
+        let copy = match &self.pool {
+            Some(pool) => pool.copy(texture, self.command_queue, self.texture_options.usage),
+            None => texture.copy_to_owned_with(self.command_queue, self.texture_options),
+        };
+        match copy {
+            Some(copy) => {
+                self.previous = self.latest.replace(copy);
+                self.clock.push(Instant::now());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The texture to draw at `now`. Blending is encoded on `command_buffer` and the result (owned by
+    /// the blender, valid until the next call) is returned; otherwise the newest frame. Null before the first frame.
+    pub fn render(&mut self, command_buffer: MTLCommandBufferPtr, now: Instant) -> MTLTexturePtr {
+        let Some(latest) = &self.latest else {
+            return std::ptr::null_mut();
+        };
+        let t = self.clock.factor(now);
+        match &self.previous {
+            #[cfg(target_os = "macos")]
+            Some(previous) if t < 1.0 && !command_buffer.is_null() => {
+                let out = unsafe {
+                    ffi::syphon_metal_blender_blend(
+                        self.ptr.as_ptr(),
+                        previous.as_ptr() as *mut _,
+                        latest.as_ptr() as *mut _,
+                        command_buffer as *mut _,
+                        t,
+                    )
+                };
+                if out.is_null() {
+                    latest.as_ptr()
+                } else {
+                    out as MTLTexturePtr
+                }
+            }
+            _ => latest.as_ptr(),
+        }
+    }
+}
+
+impl Drop for MetalFrameBlender {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_metal_blender_release(self.ptr.as_ptr());
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Cameras to Syphon (`camera` feature): `CameraCapture` runs an AVFoundation capture session on a camera or
+//! capture card and publishes every frame through its own `MetalServer`, zero-copy from the capture buffers'
+//! IOSurfaces. The process needs camera permission (asked for on first use; a bundled app needs
+//! `NSCameraUsageDescription`).
+
+#[cfg(target_os = "macos")]
+use std::ptr::NonNull;
+
+#[cfg(target_os = "macos")]
+use crate::ffi;
+#[cfg(target_os = "macos")]
+use crate::safe::{creation_failed, optional_cstring, opt_cstr_to_string};
+use crate::{MetalServer, SyphonError};
+
+/// A video capture device (built-in, external or Continuity camera, capture card).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CameraDevice {
+    /// Stable identifier, for `CameraCapture::new`.
+    pub unique_id: String,
+    /// Display name (e.g. "FaceTime HD Camera").
+    pub name: String,
+    pub model_id: Option<String>,
+}
+
+/// Camera settings; the default keeps the device's active format and rate.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CameraCaptureOptions {
+    /// Capture size in pixels; the device must offer a format of exactly this size (it is ignored otherwise).
+    pub size: Option<(usize, usize)>,
+    /// Frames per second; applied only if the format supports it.
+    pub frame_rate: Option<f64>,
+}
+
+/// The video capture devices currently connected (empty off macOS).
+pub fn camera_devices() -> Vec<CameraDevice> {
+    #[cfg(target_os = "macos")]
+    {
+        let devices = unsafe { ffi::syphon_av_video_devices_copy() };
+        if devices.is_null() {
+            return Vec::new();
+        }
+        let count = unsafe { ffi::syphon_av_video_devices_count(devices) };
+        let out = (0..count)
+            .filter_map(|i| {
+                Some(CameraDevice {
+                    unique_id: opt_cstr_to_string(unsafe { ffi::syphon_av_video_devices_copy_unique_id(devices, i) })?,
+                    name: opt_cstr_to_string(unsafe { ffi::syphon_av_video_devices_copy_name(devices, i) })
+                        .unwrap_or_default(),
+                    model_id: opt_cstr_to_string(unsafe { ffi::syphon_av_video_devices_copy_model_id(devices, i) }),
+                })
+            })
+            .collect();
+        unsafe { ffi::syphon_metal_object_release(devices) };
+        out
+    }
+    #[cfg(not(target_os = "macos"))]
+    Vec::new()
+}
+
+/// A running camera session publishing through its server; dropping it stops the session, then the server.
+pub struct CameraCapture {
+    #[cfg(target_os = "macos")]
+    ptr: NonNull<std::ffi::c_void>,
+    server: MetalServer,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl CameraCapture {
+    /// Start capturing from the device with `unique_id` (None for the default camera) into a new server named
+    /// `name` on the system default Metal device. Blocks while the user answers the camera permission prompt.
+    pub fn new(name: Option<&str>, unique_id: Option<&str>, options: &CameraCaptureOptions) -> Option<Self> {
+        Self::try_new(name, unique_id, options).ok()
+    }
+
+    /// Like `new`, reporting why capture could not start (no such device, access denied, ...).
+    pub fn try_new(
+        name: Option<&str>,
+        unique_id: Option<&str>,
+        options: &CameraCaptureOptions,
+    ) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            let unique_id = optional_cstring(unique_id)?;
+            let server = MetalServer::try_new_with_default_device(name, None)?;
+            let (width, height) = options.size.unwrap_or((0, 0));
+            let ptr = unsafe {
+                ffi::syphon_camera_capture_create(
+                    server.as_ptr(),
+                    unique_id.as_ref().map_or(std::ptr::null(), |c| c.as_ptr()),
+                    width,
+                    height,
+                    options.frame_rate.unwrap_or(0.0),
+                )
+            };
+            NonNull::new(ptr)
+                .map(|ptr| Self { ptr, server })
+                .ok_or_else(|| creation_failed("camera capture"))
+        }
+        #[cfg(not(target_os = "macos"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// The server the camera publishes through.
+    pub fn server(&self) -> &MetalServer {
+        &self.server
+    }
+}
+
+impl Drop for CameraCapture {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_camera_capture_release(self.ptr.as_ptr());
+        }
+    }
+}
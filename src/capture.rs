@@ -0,0 +1,190 @@
+//! Burst capture: write the next N distinct frames of a client to numbered image files.
+//!
+//! Captures run on the calling thread with the client's context current. Each new-frame notification
+//! triggers a readback of the server's latest frame, unless that frame was already read; encoding and
+//! disk writes happen on a writer thread behind a short queue, so at most a few frames are held in
+//! memory. Frames published while a readback is waiting for the queue are skipped, not read twice.
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{OpenGLClient, OpenGLImage};
+
+/// File format for captured frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureFormat {
+    /// Binary PPM (`P6`, RGB; alpha dropped). Readable by most image tools, no dependencies.
+    Ppm,
+    /// Headerless tightly packed RGBA8, top row first; size is in the file name.
+    Rgba,
+    /// PNG (RGBA8). Writing it requires the `png` feature; without it writes fail with
+    /// `io::ErrorKind::Unsupported`.
+    Png,
+}
+
+impl CaptureFormat {
+    /// Err(`Unsupported`) for formats this build can't write (PNG without the `png` feature).
+    pub(crate) fn check_supported(self) -> io::Result<()> {
+        #[cfg(not(feature = "png"))]
+        if self == CaptureFormat::Png {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "PNG output needs the `png` feature"));
+        }
+        Ok(())
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            CaptureFormat::Ppm => "ppm",
+            CaptureFormat::Rgba => "rgba",
+            CaptureFormat::Png => "png",
+        }
+    }
+}
+
+/// How long to wait for a frame before checking that the server is still there.
+const CAPTURE_POLL: Duration = Duration::from_millis(250);
+
+/// Frames read back but not yet written; bounds a burst's memory to a few frames.
+const WRITE_QUEUE: usize = 2;
+
+struct CapturedFrame {
+    /// When the frame was read back.
+    timestamp: SystemTime,
+    width: usize,
+    height: usize,
+    /// RGBA8, top row first.
+    rgba: Vec<u8>,
+}
+
+impl OpenGLClient {
+    /// Capture the next `n` distinct frames from this client's server into `dir` (created if needed)
+    /// as `frame-0001-<unix ms>.<ext>`, ..., the time being when the frame was read back. The client's
+    /// context must be current on this thread.
+    ///
+    /// A temporary second client is notified of each published frame; pending notifications are
+    /// drained before each readback and a frame already read is never written again, so frames published
+    /// faster than they can be read and written are skipped. Returns the written paths in capture
+    /// order; fewer than `n` if the server went away.
+    pub fn capture_sequence(&self, n: usize, dir: &Path, format: CaptureFormat) -> io::Result<Vec<PathBuf>> {
+        format.check_supported()?;
+        std::fs::create_dir_all(dir)?;
+        let description = self
+            .server_description()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotConnected, "client has no server"))?;
+
+        let (tx, rx) = mpsc::channel::<()>();
+        let capture_client = OpenGLClient::new(
+            &description,
+            self.context(),
+            None,
+            Some(Box::new(move |_| {
+                let _ = tx.send(());
+            })),
+        )
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotConnected, "failed to attach capture client"))?;
+
+        let (frame_tx, frame_rx) = mpsc::sync_channel::<CapturedFrame>(WRITE_QUEUE);
+        let writer_dir = dir.to_owned();
+        let writer = std::thread::Builder::new()
+            .name("rusty-syphon capture writer".into())
+            .spawn(move || -> io::Result<Vec<PathBuf>> {
+                frame_rx
+                    .iter()
+                    .enumerate()
+                    .map(|(i, frame)| write_frame(&writer_dir, i + 1, &frame, format))
+                    .collect()
+            })?;
+
+        let mut captured = 0;
+        while captured < n {
+            match rx.recv_timeout(CAPTURE_POLL) {
+                Ok(()) => {}
+                Err(mpsc::RecvTimeoutError::Timeout) if capture_client.is_valid() => continue,
+                Err(_) => break,
+            }
+            // Later notifications name the same latest frame: one readback covers them all.
+            while rx.try_recv().is_ok() {}
+            if !capture_client.has_new_frame() {
+                continue;
+            }
+            let Some(frame) = read_frame(&capture_client) else { continue };
+            // Fails only once the writer stopped on an error, which joining reports.
+            if frame_tx.send(frame).is_err() {
+                break;
+            }
+            captured += 1;
+        }
+        drop(capture_client);
+        drop(frame_tx);
+        writer
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("capture writer panicked")))
+    }
+}
+
+fn read_frame(client: &OpenGLClient) -> Option<CapturedFrame> {
+    let image = client.new_frame_image()?;
+    let (width, height, rgba) = read_gl_image_top_down(&image)?;
+    Some(CapturedFrame {
+        timestamp: SystemTime::now(),
+        width,
+        height,
+        rgba,
+    })
+}
+
+/// Read back an OpenGL frame as tightly packed RGBA8, top row first. The image's context must be current.
+pub(crate) fn read_gl_image_top_down(image: &OpenGLImage) -> Option<(usize, usize, Vec<u8>)> {
+    let (w, h) = image.texture_size();
+    let (width, height) = (w as usize, h as usize);
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let rgba = image.try_read_pixels().ok()?;
+    Some((width, height, rgba))
+}
+
+fn write_frame(dir: &Path, index: usize, frame: &CapturedFrame, format: CaptureFormat) -> io::Result<PathBuf> {
+    let millis = frame
+        .timestamp
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let name = match format {
+        CaptureFormat::Rgba => format!("frame-{index:04}-{millis}-{}x{}.rgba", frame.width, frame.height),
+        _ => format!("frame-{index:04}-{millis}.{}", format.extension()),
+    };
+    let path = dir.join(name);
+    write_image(&path, frame.width, frame.height, &frame.rgba, format)?;
+    Ok(path)
+}
+
+/// Write a top-down RGBA8 image to `path` in `format`.
+pub(crate) fn write_image(path: &Path, width: usize, height: usize, rgba: &[u8], format: CaptureFormat) -> io::Result<()> {
+    format.check_supported()?;
+    let mut file = io::BufWriter::new(std::fs::File::create(path)?);
+    match format {
+        CaptureFormat::Ppm => {
+            write!(file, "P6\n{} {}\n255\n", width, height)?;
+            for px in rgba.chunks_exact(4) {
+                file.write_all(&px[..3])?;
+            }
+        }
+        CaptureFormat::Rgba => file.write_all(rgba)?,
+        #[cfg(feature = "png")]
+        CaptureFormat::Png => {
+            let mut encoder = png::Encoder::new(&mut file, width as u32, height as u32);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder
+                .write_header()
+                .and_then(|mut writer| writer.write_image_data(rgba))
+                .map_err(io::Error::other)?;
+        }
+        #[cfg(not(feature = "png"))]
+        CaptureFormat::Png => return format.check_supported(),
+    }
+    file.flush()
+}
@@ -0,0 +1,221 @@
+//! sRGB transfer-function conversion for what is shared: encode linear-light frames as sRGB before
+//! publishing (so downstream apps, which assume sRGB, don't show them washed out), or decode received
+//! sRGB frames to linear.
+//!
+//! `GlSrgbConverter` (fragment shader) and `MetalSrgbConverter` (compute kernel) convert on the GPU on
+//! the publish path; `SrgbConversion::apply_rgba8` and `OpenGLImage::read_pixels_converted` convert
+//! RGBA8 readbacks on the CPU. Alpha is never touched.
+
+#[cfg(target_os = "macos")]
+use std::ptr::NonNull;
+use std::sync::OnceLock;
+
+#[cfg(target_os = "macos")]
+use crate::ffi;
+use crate::{MTLCommandBufferPtr, MTLDevicePtr, MTLTexturePtr, MetalServer, OpenGLImage, OpenGLServer, Rect, GL_TEXTURE_2D};
+
+/// Transfer-function conversion applied to color channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SrgbConversion {
+    /// Share pixels as they are.
+    #[default]
+    None,
+    /// Linear light to sRGB (the piecewise sRGB curve, roughly gamma 1/2.2).
+    Encode,
+    /// sRGB to linear light.
+    Decode,
+}
+
+impl SrgbConversion {
+    /// Glue mode: 0 copy, 1 encode, 2 decode.
+    #[cfg(target_os = "macos")]
+    fn mode(self) -> i32 {
+        match self {
+            SrgbConversion::None => 0,
+            SrgbConversion::Encode => 1,
+            SrgbConversion::Decode => 2,
+        }
+    }
+
+    /// Convert the color channels of RGBA8 (or BGRA8) pixels in place through a lookup table.
+    pub fn apply_rgba8(self, pixels: &mut [u8]) {
+        let table = match self {
+            SrgbConversion::None => return,
+            SrgbConversion::Encode => encode_table(),
+            SrgbConversion::Decode => decode_table(),
+        };
+        for pixel in pixels.chunks_exact_mut(4) {
+            for c in &mut pixel[..3] {
+                *c = table[*c as usize];
+            }
+        }
+    }
+}
+
+fn build_table(f: fn(f32) -> f32) -> [u8; 256] {
+    let mut table = [0u8; 256];
+    for (i, v) in table.iter_mut().enumerate() {
+        *v = (f(i as f32 / 255.0) * 255.0).round().clamp(0.0, 255.0) as u8;
+    }
+    table
+}
+
+fn encode_table() -> &'static [u8; 256] {
+    static TABLE: OnceLock<[u8; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        build_table(|c| if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 })
+    })
+}
+
+fn decode_table() -> &'static [u8; 256] {
+    static TABLE: OnceLock<[u8; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| build_table(|c| if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }))
+}
+
+impl OpenGLImage {
+    /// `read_pixels` with `conversion` applied to the color channels.
+    pub fn read_pixels_converted(&self, conversion: SrgbConversion) -> Vec<u8> {
+        let mut pixels = self.read_pixels();
+        conversion.apply_rgba8(&mut pixels);
+        pixels
+    }
+}
+
+/// Converts GL textures into an owned RGBA8 `GL_TEXTURE_2D` with a fragment shader. Create, use and drop
+/// with the same CGL context current.
+pub struct GlSrgbConverter {
+    #[cfg(target_os = "macos")]
+    ptr: NonNull<std::ffi::c_void>,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl GlSrgbConverter {
+    /// Set up on the current context (shaders are compiled on first use). None without a current context.
+    pub fn new() -> Option<Self> {
+        #[cfg(target_os = "macos")]
+        {
+            let ptr = unsafe { ffi::syphon_gl_srgb_converter_create() };
+            NonNull::new(ptr).map(|ptr| Self { ptr })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Render `tex_id` (`GL_TEXTURE_RECTANGLE` or `GL_TEXTURE_2D`, `width` x `height`) through
+    /// `conversion` into the converter's texture and return it (valid until the next call), or None.
+    pub fn convert(&mut self, tex_id: u32, target: u32, width: usize, height: usize, conversion: SrgbConversion) -> Option<u32> {
+        #[cfg(target_os = "macos")]
+        {
+            let tex = unsafe {
+                ffi::syphon_gl_srgb_converter_convert(self.ptr.as_ptr(), tex_id, target, width, height, conversion.mode())
+            };
+            (tex != 0).then_some(tex)
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Publish `tex_id` as the whole `width` x `height` frame with `conversion` applied
+    /// (`SrgbConversion::None` publishes it directly). False if the conversion failed.
+    pub fn publish(
+        &mut self,
+        server: &OpenGLServer,
+        tex_id: u32,
+        target: u32,
+        width: usize,
+        height: usize,
+        conversion: SrgbConversion,
+    ) -> bool {
+        let (tex_id, target) = match conversion {
+            SrgbConversion::None => (tex_id, target),
+            _ => match self.convert(tex_id, target, width, height, conversion) {
+                Some(converted) => (converted, GL_TEXTURE_2D),
+                None => return false,
+            },
+        };
+        server.publish_whole_texture(tex_id, target, width as f64, height as f64, false);
+        true
+    }
+}
+
+impl Drop for GlSrgbConverter {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_gl_srgb_converter_release(self.ptr.as_ptr());
+        }
+    }
+}
+
+/// Converts Metal textures with a compute kernel into an owned private texture of the same size and
+/// pixel format. For `*_sRGB` pixel formats Metal already converts on access; use `SrgbConversion::None`.
+pub struct MetalSrgbConverter {
+    #[cfg(target_os = "macos")]
+    ptr: NonNull<std::ffi::c_void>,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl MetalSrgbConverter {
+    /// Build the conversion pipeline on `device`. None if it fails.
+    pub fn new(device: MTLDevicePtr) -> Option<Self> {
+        #[cfg(target_os = "macos")]
+        {
+            let ptr = unsafe { ffi::syphon_metal_srgb_converter_create(device as *mut _) };
+            NonNull::new(ptr).map(|ptr| Self { ptr })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Encode the conversion of `texture` on `command_buffer` and return the output (owned by the
+    /// converter, valid until the next call), or null on failure.
+    pub fn convert(
+        &mut self,
+        texture: MTLTexturePtr,
+        command_buffer: MTLCommandBufferPtr,
+        conversion: SrgbConversion,
+    ) -> MTLTexturePtr {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_metal_srgb_converter_convert(
+                self.ptr.as_ptr(),
+                texture as *mut _,
+                command_buffer as *mut _,
+                conversion.mode(),
+            ) as MTLTexturePtr
+        }
+        #[cfg(not(target_os = "macos"))]
+        std::ptr::null_mut()
+    }
+
+    /// Publish `region` of `texture` with `conversion` applied, encoding both on `command_buffer`
+    /// (`SrgbConversion::None` publishes it directly). False if the conversion failed.
+    pub fn publish(
+        &mut self,
+        server: &MetalServer,
+        texture: MTLTexturePtr,
+        command_buffer: MTLCommandBufferPtr,
+        region: Rect,
+        flipped: bool,
+        conversion: SrgbConversion,
+    ) -> bool {
+        let texture = match conversion {
+            SrgbConversion::None => texture,
+            _ => self.convert(texture, command_buffer, conversion),
+        };
+        if texture.is_null() {
+            return false;
+        }
+        server.publish(texture, command_buffer, region, flipped);
+        true
+    }
+}
+
+impl Drop for MetalSrgbConverter {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_metal_srgb_converter_release(self.ptr.as_ptr());
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! Core Image integration (`core-image` feature): frames as `objc2-core-image` `CIImage`s, and CIImages
+//! published through a Metal server.
+
+use objc2::rc::Retained;
+use objc2_core_image::CIImage;
+
+use crate::{Frame, MetalServer};
+
+impl Frame {
+    /// `ci_image_ptr` as a retained `CIImage` (no copy of the pixels).
+    pub fn as_ci_image(&self) -> Option<Retained<CIImage>> {
+        unsafe { Retained::from_raw(self.ci_image_ptr()? as *mut CIImage) }
+    }
+}
+
+impl MetalServer {
+    /// `publish_ci_image_ptr` with an `objc2-core-image` image.
+    pub fn publish_ci_image(&self, image: &CIImage) -> bool {
+        self.publish_ci_image_ptr(image as *const CIImage as *mut std::ffi::c_void)
+    }
+}
@@ -0,0 +1,183 @@
+//! Bytes in, bytes out: `CpuServer` and `CpuClient` share RGBA8 frames from CPU memory without the
+//! caller touching OpenGL or Metal. Both run on the system default Metal device; the server uploads
+//! through a staging texture (`MetalServer::publish_pixels`) and the client reads frames back through a
+//! shared buffer (`MetalTexture::read_to_vec`).
+
+#[cfg(target_os = "macos")]
+use std::ptr::NonNull;
+use std::time::Instant;
+
+#[cfg(target_os = "macos")]
+use crate::ffi;
+use crate::{
+    CallbackDispatch, MetalClient, MetalServer, NewFrameCallback, ServerDescription, SyphonError, SyphonOptions,
+};
+#[cfg(target_os = "macos")]
+use crate::{
+    MetalTexture, MTLCommandQueuePtr, MTL_PIXEL_FORMAT_BGRA8_UNORM, MTL_PIXEL_FORMAT_BGRA8_UNORM_SRGB,
+    MTL_PIXEL_FORMAT_RGBA8_UNORM, MTL_PIXEL_FORMAT_RGBA8_UNORM_SRGB,
+};
+
+/// A frame read back to CPU memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpuFrame {
+    pub width: usize,
+    pub height: usize,
+    /// Tightly packed RGBA8 pixels, top row first.
+    pub rgba: Vec<u8>,
+    /// When the frame was read back.
+    pub timestamp: Instant,
+}
+
+#[cfg(target_os = "macos")]
+impl CpuFrame {
+    /// Read `texture` back as RGBA8 on `queue`; None for pixel formats other than 8-bit RGBA / BGRA.
+    fn read(texture: &MetalTexture, queue: MTLCommandQueuePtr) -> Option<Self> {
+        let bgra = match texture.pixel_format() {
+            MTL_PIXEL_FORMAT_BGRA8_UNORM | MTL_PIXEL_FORMAT_BGRA8_UNORM_SRGB => true,
+            MTL_PIXEL_FORMAT_RGBA8_UNORM | MTL_PIXEL_FORMAT_RGBA8_UNORM_SRGB => false,
+            _ => return None,
+        };
+        let (width, height) = texture.size();
+        let mut rgba = texture.read_to_vec(Some(queue));
+        if rgba.is_empty() {
+            return None;
+        }
+        if bgra {
+            for pixel in rgba.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+        Some(Self {
+            width,
+            height,
+            rgba,
+            timestamp: Instant::now(),
+        })
+    }
+}
+
+/// Publishes RGBA8 frames from CPU memory.
+pub struct CpuServer {
+    server: MetalServer,
+}
+
+impl CpuServer {
+    /// Create a server on the system default Metal device. `name` and `options` as for `MetalServer::new`.
+    pub fn new(name: Option<&str>, options: Option<&SyphonOptions>) -> Option<Self> {
+        Self::try_new(name, options).ok()
+    }
+
+    /// Like `new`, reporting why creation failed.
+    pub fn try_new(name: Option<&str>, options: Option<&SyphonOptions>) -> Result<Self, SyphonError> {
+        MetalServer::try_new_with_default_device(name, options).map(|server| Self { server })
+    }
+
+    /// Publish a `width` x `height` frame of RGBA8 pixels, top row first. False if `rgba` is too short
+    /// or the upload failed.
+    pub fn publish(&self, rgba: &[u8], width: usize, height: usize) -> bool {
+        self.server.publish_pixels(rgba, width, height, false)
+    }
+
+    /// Whether any clients are attached.
+    pub fn has_clients(&self) -> bool {
+        self.server.has_clients()
+    }
+
+    /// The description clients connect with.
+    pub fn server_description(&self) -> Option<ServerDescription> {
+        self.server.server_description()
+    }
+
+    /// The underlying Metal server (naming, stopping, ...).
+    pub fn server(&self) -> &MetalServer {
+        &self.server
+    }
+}
+
+/// Receives frames as RGBA8 bytes.
+pub struct CpuClient {
+    client: MetalClient,
+    #[cfg(target_os = "macos")]
+    queue: NonNull<std::ffi::c_void>,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl CpuClient {
+    /// Attach to the server in `description` on the system default Metal device. `callback` as for
+    /// `MetalClient::new`; call `latest_frame` from it or from your own loop.
+    pub fn new(description: &ServerDescription, callback: Option<NewFrameCallback>) -> Option<Self> {
+        Self::try_new(description, callback).ok()
+    }
+
+    /// Like `new`, reporting why creation failed.
+    pub fn try_new(description: &ServerDescription, callback: Option<NewFrameCallback>) -> Result<Self, SyphonError> {
+        Self::try_new_with_dispatch(description, callback, CallbackDispatch::Serialized)
+    }
+
+    /// Like `try_new`, choosing how `callback` is dispatched (as for `MetalClient::new_with_dispatch`).
+    pub fn try_new_with_dispatch(
+        description: &ServerDescription,
+        callback: Option<NewFrameCallback>,
+        dispatch: CallbackDispatch,
+    ) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            let device = unsafe { ffi::syphon_metal_device_create_default() };
+            if device.is_null() {
+                return Err(SyphonError::CreationFailed {
+                    object: "Metal device",
+                    reason: "no system default MTLDevice".into(),
+                });
+            }
+            // The client and the queue keep the device alive.
+            let client = MetalClient::try_new_with_dispatch(description, device as _, None, callback, dispatch);
+            let queue = unsafe { ffi::syphon_metal_device_new_command_queue(device) };
+            unsafe { ffi::syphon_metal_object_release(device) };
+            let client = client?;
+            let queue = NonNull::new(queue).ok_or(SyphonError::CreationFailed {
+                object: "Metal command queue",
+                reason: "newCommandQueue returned nil".into(),
+            })?;
+            Ok(Self { client, queue })
+        }
+        #[cfg(not(target_os = "macos"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Whether a frame arrived since the last `latest_frame`.
+    pub fn has_new_frame(&self) -> bool {
+        self.client.has_new_frame()
+    }
+
+    /// The server's current frame as RGBA8 (blocks for the GPU readback). None if there is no frame
+    /// yet, it is not 8-bit RGBA / BGRA, or the readback failed.
+    pub fn latest_frame(&self) -> Option<CpuFrame> {
+        #[cfg(target_os = "macos")]
+        {
+            let texture = self.client.new_frame_image()?;
+            CpuFrame::read(&texture, self.queue.as_ptr() as _)
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// False once the server has gone away.
+    pub fn is_valid(&self) -> bool {
+        self.client.is_valid()
+    }
+
+    /// The underlying Metal client.
+    pub fn client(&self) -> &MetalClient {
+        &self.client
+    }
+}
+
+impl Drop for CpuClient {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_metal_object_release(self.queue.as_ptr());
+        }
+    }
+}
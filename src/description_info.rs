@@ -0,0 +1,49 @@
+//! Plain-data snapshots of server descriptions, for persisting a chosen source (e.g. the last-used server
+//! in an app's config) and finding it again later. With the `serde` feature, `ServerDescriptionInfo`
+//! implements `Serialize` / `Deserialize`.
+
+use crate::{ServerDescription, ServerDirectory};
+
+/// The identifying fields of a `ServerDescription`, detached from Syphon.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ServerDescriptionInfo {
+    pub uuid: Option<String>,
+    pub name: Option<String>,
+    pub app_name: Option<String>,
+    pub bundle_identifier: Option<String>,
+}
+
+impl ServerDescription {
+    /// Snapshot of this description's identifying fields.
+    pub fn info(&self) -> ServerDescriptionInfo {
+        ServerDescriptionInfo {
+            uuid: self.uuid(),
+            name: self.name(),
+            app_name: self.app_name(),
+            bundle_identifier: self.bundle_identifier(),
+        }
+    }
+}
+
+impl ServerDescriptionInfo {
+    /// The live description in `directory` for this snapshot: the server with the same UUID if it is
+    /// still running, else (e.g. after the app restarted and its server got a new UUID) the first one
+    /// with the same name and app name. None if neither is listed.
+    pub fn resolve(&self, directory: &ServerDirectory) -> Option<ServerDescription> {
+        let servers = directory.servers();
+        if let Some(uuid) = &self.uuid {
+            if let Some(found) = servers.iter().find(|d| d.uuid().as_ref() == Some(uuid)) {
+                return Some(found.clone());
+            }
+        }
+        if self.name.is_none() && self.app_name.is_none() {
+            return None;
+        }
+        directory
+            .servers_matching(self.name.as_deref(), self.app_name.as_deref())
+            .into_iter()
+            .next()
+    }
+}
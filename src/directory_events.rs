@@ -0,0 +1,230 @@
+//! Server directory notifications: servers appearing, disappearing and changing (name, surfaces), and
+//! clients' `on_invalidated` callbacks built on them.
+//!
+//! Syphon posts these on the main thread, so the process's main run loop must be running (an
+//! `NSApplication`, `CFRunLoopRun`, or `pump_run_loop` once per frame on the main thread) for callbacks
+//! to arrive, unless `ServerDirectory::start_background_updates` moved directory updates to a
+//! crate-owned thread. With
+//! the `futures` feature, `ServerDirectory::events` offers the same events as an async `Stream`.
+
+#[cfg(target_os = "macos")]
+use std::ptr::NonNull;
+
+#[cfg(target_os = "macos")]
+use crate::ffi;
+use crate::{MetalClient, OpenGLClient, ServerDescription, ServerDirectory};
+
+/// A change in the set of available servers.
+#[derive(Clone)]
+pub enum DirectoryEvent {
+    /// A server was announced.
+    Added(ServerDescription),
+    /// A server went away.
+    Removed(ServerDescription),
+    /// A server's description changed (e.g. it was renamed).
+    Updated(ServerDescription),
+}
+
+impl DirectoryEvent {
+    /// The description carried by the event.
+    pub fn description(&self) -> &ServerDescription {
+        match self {
+            DirectoryEvent::Added(d) | DirectoryEvent::Removed(d) | DirectoryEvent::Updated(d) => d,
+        }
+    }
+}
+
+impl std::fmt::Debug for DirectoryEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            DirectoryEvent::Added(_) => "Added",
+            DirectoryEvent::Removed(_) => "Removed",
+            DirectoryEvent::Updated(_) => "Updated",
+        };
+        let d = self.description();
+        f.debug_tuple(kind)
+            .field(&d.name())
+            .field(&d.app_name())
+            .field(&d.uuid())
+            .finish()
+    }
+}
+
+/// Run the calling thread's run loop for up to `timeout` (zero: handle what is pending and return), for
+/// apps that own their main thread (winit, SDL, game loops): call it once per frame on the main thread
+/// so directory updates and Syphon notifications are delivered without a background thread. Returns
+/// early if the run loop has nothing to wait on.
+pub fn pump_run_loop(timeout: std::time::Duration) {
+    #[cfg(target_os = "macos")]
+    unsafe {
+        ffi::syphon_run_loop_pump(timeout.as_secs_f64());
+    }
+    #[cfg(not(target_os = "macos"))]
+    let _ = timeout;
+}
+
+/// Callback for directory events (invoked on the thread delivering directory updates).
+pub type DirectoryCallback = Box<dyn Fn(DirectoryEvent) + Send>;
+
+/// An active `ServerDirectory::subscribe` registration. Dropping it unsubscribes: once the drop
+/// returns the callback is not running and will not be invoked again.
+pub struct DirectorySubscription {
+    #[cfg(target_os = "macos")]
+    ptr: NonNull<std::ffi::c_void>,
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn directory_callback(userdata: *mut std::ffi::c_void, event: i32, description: *mut std::ffi::c_void) {
+    let (Some(callback), Some(description)) = ((userdata as *const DirectoryCallback).as_ref(), NonNull::new(description))
+    else {
+        return;
+    };
+    let description = ServerDescription::retained_from_borrowed(description);
+    let event = match event {
+        0 => DirectoryEvent::Added(description),
+        1 => DirectoryEvent::Removed(description),
+        _ => DirectoryEvent::Updated(description),
+    };
+    callback(event);
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn directory_release(userdata: *mut std::ffi::c_void) {
+    if !userdata.is_null() {
+        drop(Box::from_raw(userdata as *mut DirectoryCallback));
+    }
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl ServerDirectory {
+    /// Keep the directory current without a main run loop (CLI tools, services): starts a background
+    /// thread, once per process, that creates the shared directory and runs a run loop for it, so the
+    /// server list updates and `subscribe` callbacks are delivered on that thread. Call it before the
+    /// first `ServerDirectory::shared`. Returns true once the thread is running.
+    pub fn start_background_updates() -> bool {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_background_updates_start()
+        }
+        #[cfg(not(target_os = "macos"))]
+        false
+    }
+
+    /// Call `callback` for every server announcement, retirement and update until the returned
+    /// subscription is dropped. Events are delivered on the main thread (or the background updates
+    /// thread). None if subscribing failed.
+    pub fn subscribe(&self, callback: DirectoryCallback) -> Option<DirectorySubscription> {
+        #[cfg(target_os = "macos")]
+        {
+            let userdata = Box::into_raw(Box::new(callback)) as *mut std::ffi::c_void;
+            let ptr = unsafe {
+                ffi::syphon_directory_subscribe(Some(directory_callback), Some(directory_release), userdata)
+            };
+            NonNull::new(ptr).map(|ptr| DirectorySubscription { ptr })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+}
+
+/// Callback for a client losing its server (invoked at most once, on the thread delivering directory updates).
+pub type InvalidationCallback = Box<dyn Fn() + Send>;
+
+/// Subscribe to the retirement of the server with `uuid`, calling `callback` once. If `valid` is false
+/// the callback runs immediately on this thread instead.
+fn watch_retirement(uuid: Option<String>, valid: bool, callback: InvalidationCallback) -> Option<DirectorySubscription> {
+    let uuid = match uuid {
+        Some(uuid) if valid => uuid,
+        _ => {
+            callback();
+            return None;
+        }
+    };
+    let fired = std::sync::atomic::AtomicBool::new(false);
+    ServerDirectory::shared()?.subscribe(Box::new(move |event| {
+        if let DirectoryEvent::Removed(description) = &event {
+            if description.uuid().as_deref() == Some(uuid.as_str())
+                && !fired.swap(true, std::sync::atomic::Ordering::SeqCst)
+            {
+                callback();
+            }
+        }
+    }))
+}
+
+impl OpenGLClient {
+    /// Call `callback` as soon as this client's server retires (its app quit or stopped the server),
+    /// instead of polling `is_valid`, until the returned subscription is dropped. Delivered like
+    /// directory events (see `ServerDirectory::subscribe`). If the client is already invalid the
+    /// callback runs immediately and None is returned.
+    pub fn on_invalidated(&self, callback: InvalidationCallback) -> Option<DirectorySubscription> {
+        let uuid = self.server_description().and_then(|d| d.uuid());
+        watch_retirement(uuid, self.is_valid(), callback)
+    }
+}
+
+impl MetalClient {
+    /// Call `callback` as soon as this client's server retires; see `OpenGLClient::on_invalidated`.
+    pub fn on_invalidated(&self, callback: InvalidationCallback) -> Option<DirectorySubscription> {
+        let uuid = self.server_description().and_then(|d| d.uuid());
+        watch_retirement(uuid, self.is_valid(), callback)
+    }
+}
+
+// Unsubscribing is thread-safe in the glue (observer removal plus the subscription's lock).
+#[cfg(target_os = "macos")]
+unsafe impl Send for DirectorySubscription {}
+#[cfg(target_os = "macos")]
+unsafe impl Sync for DirectorySubscription {}
+
+impl Drop for DirectorySubscription {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_directory_unsubscribe(self.ptr.as_ptr());
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+mod stream {
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use futures_core::Stream;
+
+    use super::{DirectoryEvent, DirectorySubscription};
+    use crate::stream::{event_queue, EventStream};
+    use crate::ServerDirectory;
+
+    /// Async stream of directory events from `ServerDirectory::events`. Never ends; drop it to unsubscribe.
+    /// Events queue up (unbounded) while the stream isn't polled.
+    pub struct DirectoryEvents {
+        events: EventStream<DirectoryEvent>,
+        _subscription: DirectorySubscription,
+    }
+
+    impl ServerDirectory {
+        /// Directory events as a `Stream` (e.g. for `tokio::select!`). Requires the `futures` feature;
+        /// events still originate on the main thread, whose run loop must be running.
+        pub fn events(&self) -> Option<DirectoryEvents> {
+            let (sink, events) = event_queue();
+            let subscription = self.subscribe(Box::new(move |event| sink.push(event)))?;
+            Some(DirectoryEvents {
+                events,
+                _subscription: subscription,
+            })
+        }
+    }
+
+    impl Stream for DirectoryEvents {
+        type Item = DirectoryEvent;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<DirectoryEvent>> {
+            Pin::new(&mut self.events).poll_next(cx)
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+pub use stream::DirectoryEvents;
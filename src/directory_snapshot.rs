@@ -0,0 +1,176 @@
+//! Machine-readable server lists for dashboards and orchestration scripts: `ServerDirectory::snapshot`
+//! captures every listed server as plain data (`Serialize` / `Deserialize` with the `serde` feature), and
+//! `ServerDirectory::snapshot_json` renders it as JSON without any extra dependency.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{ServerDescription, ServerDirectory};
+
+/// Every server listed by a `ServerDirectory` at one point in time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct DirectorySnapshot {
+    /// When the snapshot was taken, in milliseconds since the Unix epoch.
+    pub taken_at_ms: u64,
+    pub servers: Vec<ServerSnapshot>,
+}
+
+/// One server in a `DirectorySnapshot`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ServerSnapshot {
+    pub uuid: Option<String>,
+    pub name: Option<String>,
+    pub app_name: Option<String>,
+    pub bundle_identifier: Option<String>,
+    /// `"syphon"` or `"spout"`.
+    pub backend: String,
+    /// How frames are shared: Syphon's surface types (e.g. `SyphonSurfaceTypeIOSurface`); empty for Spout
+    /// senders, which always share a DirectX texture.
+    pub surface_types: Vec<String>,
+    /// Frame size, for Spout senders (Syphon descriptions carry none).
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Spout sender texture format (`DXGI_FORMAT`).
+    pub format: Option<u32>,
+    /// When this process first listed the server, in milliseconds since the Unix epoch: the first
+    /// snapshot that included it, or this one.
+    pub first_seen_ms: u64,
+}
+
+/// UUID -> first snapshot time of every server listed by the last snapshot.
+fn first_seen() -> &'static Mutex<HashMap<String, u64>> {
+    static FIRST_SEEN: OnceLock<Mutex<HashMap<String, u64>>> = OnceLock::new();
+    FIRST_SEEN.get_or_init(Default::default)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+impl ServerSnapshot {
+    fn new(description: &ServerDescription, first_seen_ms: u64) -> Self {
+        let size = description.size();
+        Self {
+            uuid: description.uuid(),
+            name: description.name(),
+            app_name: description.app_name(),
+            bundle_identifier: description.bundle_identifier(),
+            backend: if cfg!(target_os = "windows") { "spout" } else { "syphon" }.to_owned(),
+            surface_types: description.surface_types(),
+            width: size.map(|(w, _)| w),
+            height: size.map(|(_, h)| h),
+            format: description.sender_format(),
+            first_seen_ms,
+        }
+    }
+}
+
+impl ServerDirectory {
+    /// The servers listed now, as plain data. `first_seen_ms` is tracked across snapshots, so take them
+    /// periodically (rather than once) for it to tell when each server appeared.
+    pub fn snapshot(&self) -> DirectorySnapshot {
+        let taken_at_ms = now_ms();
+        let mut first_seen = first_seen().lock().unwrap_or_else(|e| e.into_inner());
+        let mut seen = HashMap::new();
+        let servers = self
+            .servers()
+            .iter()
+            .map(|description| {
+                let uuid = description.uuid().unwrap_or_default();
+                let first = *first_seen.get(&uuid).unwrap_or(&taken_at_ms);
+                seen.insert(uuid, first);
+                ServerSnapshot::new(description, first)
+            })
+            .collect();
+        // Forget servers that went away, so a restarted one counts as new.
+        *first_seen = seen;
+        DirectorySnapshot { taken_at_ms, servers }
+    }
+
+    /// `snapshot` as a JSON object (see `DirectorySnapshot::to_json`).
+    pub fn snapshot_json(&self) -> String {
+        self.snapshot().to_json()
+    }
+}
+
+impl DirectorySnapshot {
+    /// The snapshot as compact JSON, with the field names of `DirectorySnapshot` and `ServerSnapshot`
+    /// (and `null` for absent values), matching what `serde_json` produces with the `serde` feature.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        let _ = write!(out, "{{\"taken_at_ms\":{},\"servers\":[", self.taken_at_ms);
+        for (i, server) in self.servers.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            server.write_json(&mut out);
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
+impl ServerSnapshot {
+    fn write_json(&self, out: &mut String) {
+        out.push('{');
+        let strings = [
+            ("uuid", &self.uuid),
+            ("name", &self.name),
+            ("app_name", &self.app_name),
+            ("bundle_identifier", &self.bundle_identifier),
+        ];
+        for (key, value) in strings {
+            let _ = write!(out, "\"{key}\":");
+            match value {
+                Some(s) => json_string(out, s),
+                None => out.push_str("null"),
+            }
+            out.push(',');
+        }
+        out.push_str("\"backend\":");
+        json_string(out, &self.backend);
+        out.push_str(",\"surface_types\":[");
+        for (i, surface_type) in self.surface_types.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            json_string(out, surface_type);
+        }
+        out.push(']');
+        for (key, value) in [("width", self.width), ("height", self.height), ("format", self.format)] {
+            let _ = match value {
+                Some(n) => write!(out, ",\"{key}\":{n}"),
+                None => write!(out, ",\"{key}\":null"),
+            };
+        }
+        let _ = write!(out, ",\"first_seen_ms\":{}}}", self.first_seen_ms);
+    }
+}
+
+/// Append `s` as a JSON string literal.
+fn json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,71 @@
+//! egui preview widget (`egui` feature): `SyphonView` receives a server's frames through a `CpuClient`,
+//! keeps the latest one as an egui texture, and asks egui to repaint when a new frame arrives. It works
+//! with any egui backend, at the cost of one readback and upload per displayed frame.
+
+use crate::{CpuClient, ServerDescription, SyphonError};
+
+/// A live preview of one server. Add it with `ui.add(&mut view)`; it shows the latest frame scaled to fit
+/// the available space (keeping its aspect ratio), or a placeholder until the first frame.
+pub struct SyphonView {
+    client: CpuClient,
+    texture: Option<egui::TextureHandle>,
+    options: egui::TextureOptions,
+}
+
+impl SyphonView {
+    /// Preview the server in `description`; new frames request a repaint of `ctx`.
+    pub fn new(ctx: &egui::Context, description: &ServerDescription) -> Option<Self> {
+        Self::try_new(ctx, description).ok()
+    }
+
+    /// Like `new`, reporting why the client could not be created.
+    pub fn try_new(ctx: &egui::Context, description: &ServerDescription) -> Result<Self, SyphonError> {
+        let ctx = ctx.clone();
+        let client = CpuClient::try_new(description, Some(Box::new(move |_| ctx.request_repaint())))?;
+        Ok(Self {
+            client,
+            texture: None,
+            options: egui::TextureOptions::LINEAR,
+        })
+    }
+
+    /// Sampling options for the preview texture (linear by default; `NEAREST` for pixel-exact inspection).
+    pub fn with_texture_options(mut self, options: egui::TextureOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Upload the newest frame if one arrived, and return the texture holding the latest frame (None
+    /// before the first). The widget calls this itself; use it to draw the frame your own way.
+    pub fn texture(&mut self, ctx: &egui::Context) -> Option<&egui::TextureHandle> {
+        if self.client.has_new_frame() {
+            if let Some(frame) = self.client.latest_frame() {
+                let image = egui::ColorImage::from_rgba_unmultiplied([frame.width, frame.height], &frame.rgba);
+                match &mut self.texture {
+                    Some(texture) => texture.set(image, self.options),
+                    None => self.texture = Some(ctx.load_texture("syphon-view", image, self.options)),
+                }
+            }
+        }
+        self.texture.as_ref()
+    }
+
+    /// False once the server has gone away (the last frame stays on screen).
+    pub fn is_valid(&self) -> bool {
+        self.client.is_valid()
+    }
+
+    /// The client receiving the frames.
+    pub fn client(&self) -> &CpuClient {
+        &self.client
+    }
+}
+
+impl egui::Widget for &mut SyphonView {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        match self.texture(ui.ctx()) {
+            Some(texture) => ui.add(egui::Image::new(texture).maintain_aspect_ratio(true).shrink_to_fit()),
+            None => ui.label("Waiting for frames…"),
+        }
+    }
+}
@@ -0,0 +1,303 @@
+//! Hardware encoding for streaming (`encoder` feature): `Encoder` compresses frames from any client to H.264
+//! or HEVC with VideoToolbox and hands back Annex-B or AVCC packets through a callback or channel, ready for
+//! the RTMP, SRT or WebRTC stack an app already has. Frames are never reordered, so decode order matches
+//! presentation order.
+
+#[cfg(target_os = "macos")]
+use std::ptr::NonNull;
+#[cfg(target_os = "macos")]
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+#[cfg(target_os = "macos")]
+use std::time::Instant;
+
+#[cfg(target_os = "macos")]
+use crate::ffi;
+#[cfg(target_os = "macos")]
+use crate::safe::creation_failed;
+use crate::{Frame, FrameClient, SyphonError, TimedFrame};
+
+/// Compression format of an `Encoder`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum VideoCodec {
+    /// H.264 High profile.
+    #[default]
+    H264,
+    /// HEVC (H.265) Main profile.
+    Hevc,
+}
+
+/// How NAL units are framed in `EncodedPacket::data`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PacketFormat {
+    /// Start codes (`00 00 00 01`), with the parameter sets repeated before every keyframe: what MPEG-TS,
+    /// SRT and most network stacks expect.
+    #[default]
+    AnnexB,
+    /// Four-byte big-endian lengths, as in MP4 / FLV (RTMP); the parameter sets only come in
+    /// `EncodedPacket::parameter_sets`.
+    Avcc,
+}
+
+/// Encoder settings; the default is Annex-B H.264 at the first frame's size, with VideoToolbox's rate control.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EncoderOptions {
+    pub codec: VideoCodec,
+    pub format: PacketFormat,
+    /// Output size in pixels (frames are scaled to fit and letterboxed); None for the first frame's size.
+    pub size: Option<(usize, usize)>,
+    /// Average bit rate in bits per second.
+    pub bit_rate: Option<f64>,
+    /// Expected frames per second, a rate-control hint.
+    pub frame_rate: Option<f64>,
+    /// Maximum frames between keyframes.
+    pub keyframe_interval: Option<u32>,
+}
+
+/// One compressed frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedPacket {
+    /// The frame's NAL units, framed as `EncoderOptions::format` says.
+    pub data: Vec<u8>,
+    /// SPS and PPS (and VPS for HEVC) NAL units without framing, on keyframes; empty otherwise.
+    pub parameter_sets: Vec<Vec<u8>>,
+    /// Presentation time, relative to the first encoded frame.
+    pub pts: Duration,
+    /// Decode time (equal to `pts`, since frames are not reordered).
+    pub dts: Duration,
+    pub keyframe: bool,
+}
+
+/// Called with each packet, on VideoToolbox's thread.
+pub type PacketCallback = Box<dyn Fn(EncodedPacket) + Send>;
+
+/// Shared with the session so a failed open can be retried with the same callback.
+#[cfg(target_os = "macos")]
+type SharedPacketCallback = Arc<Mutex<PacketCallback>>;
+
+/// Split four-byte length-prefixed NAL units.
+#[cfg(target_os = "macos")]
+fn split_length_prefixed(mut data: &[u8]) -> Vec<Vec<u8>> {
+    let mut units = Vec::new();
+    while data.len() >= 4 {
+        let len = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+        let Some(unit) = data.get(4..4 + len) else {
+            break;
+        };
+        units.push(unit.to_vec());
+        data = &data[4 + len..];
+    }
+    units
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn packet_callback(
+    userdata: *mut std::ffi::c_void,
+    data: *const u8,
+    len: usize,
+    parameter_sets: *const u8,
+    parameter_sets_len: usize,
+    pts: f64,
+    dts: f64,
+    keyframe: bool,
+) {
+    let Some(Ok(callback)) = (userdata as *const SharedPacketCallback).as_ref().map(|c| c.lock()) else {
+        return;
+    };
+    let bytes = |ptr: *const u8, len: usize| match ptr.is_null() {
+        true => &[][..],
+        false => std::slice::from_raw_parts(ptr, len),
+    };
+    callback(EncodedPacket {
+        data: bytes(data, len).to_vec(),
+        parameter_sets: split_length_prefixed(bytes(parameter_sets, parameter_sets_len)),
+        pts: Duration::from_secs_f64(pts.max(0.0)),
+        dts: Duration::from_secs_f64(dts.max(0.0)),
+        keyframe,
+    });
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn packet_release(userdata: *mut std::ffi::c_void) {
+    if !userdata.is_null() {
+        drop(Box::from_raw(userdata as *mut SharedPacketCallback));
+    }
+}
+
+/// A VideoToolbox compression session. It opens on the first frame; packets arrive asynchronously through
+/// the callback, timed from `TimedFrame::timestamp` relative to the first frame. Dropping the encoder
+/// delivers the remaining packets, then stops it.
+pub struct Encoder {
+    #[cfg(target_os = "macos")]
+    options: EncoderOptions,
+    #[cfg(target_os = "macos")]
+    callback: SharedPacketCallback,
+    #[cfg(target_os = "macos")]
+    session: Option<NonNull<std::ffi::c_void>>,
+    #[cfg(target_os = "macos")]
+    start: Option<Instant>,
+    force_keyframe: bool,
+    frames_encoded: u64,
+    frames_dropped: u64,
+}
+
+// The session is only used through `&mut self`; VideoToolbox sessions may be driven from any thread.
+unsafe impl Send for Encoder {}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl Encoder {
+    /// An encoder delivering packets to `callback`. None off macOS.
+    pub fn new(options: &EncoderOptions, callback: PacketCallback) -> Option<Self> {
+        Self::try_new(options, callback).ok()
+    }
+
+    /// Like `new`, reporting why the encoder can't be used.
+    pub fn try_new(options: &EncoderOptions, callback: PacketCallback) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            if options.size.is_some_and(|(w, h)| w == 0 || h == 0) {
+                return Err(SyphonError::InvalidArgument("encoder size must not be zero"));
+            }
+            Ok(Self {
+                options: *options,
+                callback: Arc::new(Mutex::new(callback)),
+                session: None,
+                start: None,
+                force_keyframe: false,
+                frames_encoded: 0,
+                frames_dropped: 0,
+            })
+        }
+        #[cfg(not(target_os = "macos"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// An encoder queueing its packets on a channel.
+    pub fn new_with_channel(options: &EncoderOptions) -> Option<(Self, Receiver<EncodedPacket>)> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let encoder = Self::new(
+            options,
+            Box::new(move |packet| {
+                let _ = tx.send(packet);
+            }),
+        )?;
+        Some((encoder, rx))
+    }
+
+    /// Submit `frame`. Ok(false) if it was dropped because its timestamp isn't after the previous frame's
+    /// or the encoder skipped it; `CreationFailed` if the session can't be opened or encoding fails.
+    pub fn encode(&mut self, frame: &TimedFrame) -> Result<bool, SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            let session = self.session(&frame.frame)?;
+            let Some(buffer) = frame.frame.to_pixel_buffer() else {
+                self.frames_dropped += 1;
+                return Ok(false);
+            };
+            let start = *self.start.get_or_insert(frame.timestamp);
+            let seconds = frame.timestamp.saturating_duration_since(start).as_secs_f64();
+            let flip = !frame.frame.is_flipped();
+            match unsafe { ffi::syphon_encoder_encode(session, buffer.as_ptr(), seconds, flip, self.force_keyframe) } {
+                1 => {
+                    self.force_keyframe = false;
+                    self.frames_encoded += 1;
+                    Ok(true)
+                }
+                0 => {
+                    self.frames_dropped += 1;
+                    Ok(false)
+                }
+                _ => Err(creation_failed("encoded frame")),
+            }
+        }
+        #[cfg(not(target_os = "macos"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Encode `client`'s latest frame if one arrived since the last call, stamped now; Ok(false) if there
+    /// was none.
+    pub fn encode_from<C>(&mut self, client: &C) -> Result<bool, SyphonError>
+    where
+        C: FrameClient,
+        C::Frame: Into<Frame>,
+    {
+        if !client.has_new_frame() {
+            return Ok(false);
+        }
+        match client.new_frame() {
+            Some(frame) => self.encode(&frame),
+            None => Ok(false),
+        }
+    }
+
+    /// Make the next encoded frame a keyframe (e.g. when a viewer joins the stream).
+    pub fn request_keyframe(&mut self) {
+        self.force_keyframe = true;
+    }
+
+    /// Block until the packets of every submitted frame have been delivered.
+    pub fn flush(&mut self) -> Result<(), SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            match self.session {
+                Some(session) if !unsafe { ffi::syphon_encoder_flush(session.as_ptr()) } => {
+                    Err(creation_failed("encoder flush"))
+                }
+                _ => Ok(()),
+            }
+        }
+        #[cfg(not(target_os = "macos"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Frames submitted to the encoder so far.
+    pub fn frames_encoded(&self) -> u64 {
+        self.frames_encoded
+    }
+
+    /// Frames skipped (out-of-order timestamps, no pixels to read, or dropped by VideoToolbox).
+    pub fn frames_dropped(&self) -> u64 {
+        self.frames_dropped
+    }
+
+    /// The session, opened at the configured size (or `frame`'s) on first use.
+    #[cfg(target_os = "macos")]
+    fn session(&mut self, frame: &Frame) -> Result<*mut std::ffi::c_void, SyphonError> {
+        if let Some(session) = self.session {
+            return Ok(session.as_ptr());
+        }
+        let (width, height) = self.options.size.unwrap_or_else(|| frame.size());
+        let codec = match self.options.codec {
+            VideoCodec::H264 => 0,
+            VideoCodec::Hevc => 1,
+        };
+        let userdata = Box::into_raw(Box::new(self.callback.clone())) as *mut std::ffi::c_void;
+        let ptr = unsafe {
+            ffi::syphon_encoder_create(
+                codec,
+                width,
+                height,
+                self.options.bit_rate.unwrap_or(0.0),
+                self.options.frame_rate.unwrap_or(0.0),
+                self.options.keyframe_interval.unwrap_or(0),
+                self.options.format == PacketFormat::AnnexB,
+                Some(packet_callback),
+                Some(packet_release),
+                userdata,
+            )
+        };
+        let session = NonNull::new(ptr).ok_or_else(|| creation_failed("encoder"))?;
+        self.session = Some(session);
+        Ok(session.as_ptr())
+    }
+}
+
+impl Drop for Encoder {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        if let Some(session) = self.session.take() {
+            unsafe { ffi::syphon_encoder_release(session.as_ptr()) };
+        }
+    }
+}
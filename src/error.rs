@@ -0,0 +1,67 @@
+//! Error type for the fallible (`try_*`) APIs.
+//!
+//! Every constructor returning `Option` has a `try_*` counterpart returning `Result<_, SyphonError>`
+//! (`OpenGLServer::try_new`, `MetalClient::try_new`, `try_cgl_create_headless_context`, ...).
+
+use std::fmt;
+use std::thread::ThreadId;
+
+/// Why a Syphon/GL operation was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SyphonError {
+    /// Not available on this platform: Syphon types need macOS, Spout types Windows.
+    Unsupported,
+    /// An argument was rejected before reaching Syphon (null device or context, NUL byte in a name, ...).
+    InvalidArgument(&'static str),
+    /// Syphon or the system failed to create `object`; `reason` is the cause reported by the glue
+    /// (an Objective-C exception, a nil initializer, a CGL error).
+    CreationFailed {
+        object: &'static str,
+        reason: String,
+    },
+    /// The calling thread has no current CGL context, or a different one than required.
+    /// Contexts are reported by address; `expected` is None when any context would do.
+    NoCurrentContext {
+        expected: Option<usize>,
+        actual: Option<usize>,
+    },
+    /// The required context was last made current on another thread.
+    WrongThread {
+        context: usize,
+        expected: ThreadId,
+        actual: ThreadId,
+    },
+}
+
+impl fmt::Display for SyphonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyphonError::Unsupported => write!(f, "Syphon is only available on macOS and Spout on Windows"),
+            SyphonError::InvalidArgument(what) => write!(f, "invalid argument: {what}"),
+            SyphonError::CreationFailed { object, reason } => write!(f, "failed to create {object}: {reason}"),
+            SyphonError::NoCurrentContext { expected, actual } => {
+                match expected {
+                    Some(ctx) => write!(f, "CGL context {ctx:#x} is not current on this thread")?,
+                    None => write!(f, "no CGL context is current on this thread")?,
+                }
+                match actual {
+                    Some(ctx) => write!(f, " (current: {ctx:#x})")?,
+                    None => write!(f, " (current: none)")?,
+                }
+                write!(f, "; call cgl_make_current first")
+            }
+            SyphonError::WrongThread {
+                context,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "CGL context {context:#x} is current on thread {expected:?} but was used from {actual:?}; \
+                 use it on that thread or make it current here"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SyphonError {}
@@ -0,0 +1,121 @@
+//! `Frame`: a received frame of either backend, for helpers and app code that don't care which
+//! render path produced it.
+
+use std::time::Instant;
+
+#[cfg(all(target_os = "macos", feature = "core-image"))]
+use crate::ffi;
+#[cfg(feature = "core-image")]
+use crate::CIImagePtr;
+use crate::{Backend, MetalTexture, OpenGLImage};
+
+/// A frame from an OpenGL or Metal server or client.
+pub enum Frame {
+    OpenGL(OpenGLImage),
+    Metal(MetalTexture),
+}
+
+impl Frame {
+    /// Which backend the frame came through.
+    pub fn backend(&self) -> Backend {
+        match self {
+            Frame::OpenGL(_) => Backend::OpenGL,
+            Frame::Metal(_) => Backend::Metal,
+        }
+    }
+
+    /// Width and height in pixels.
+    pub fn size(&self) -> (usize, usize) {
+        match self {
+            Frame::OpenGL(image) => {
+                let (w, h) = image.texture_size();
+                (w as usize, h as usize)
+            }
+            Frame::Metal(texture) => texture.size(),
+        }
+    }
+
+    /// Width in pixels.
+    pub fn width(&self) -> usize {
+        self.size().0
+    }
+
+    /// Height in pixels.
+    pub fn height(&self) -> usize {
+        self.size().1
+    }
+
+    /// Whether the texture's rows are stored top-down: false for OpenGL frames (Syphon draws published
+    /// frames, including ones published with `flipped`, bottom-up), true for Metal textures.
+    pub fn is_flipped(&self) -> bool {
+        match self {
+            Frame::OpenGL(_) => false,
+            Frame::Metal(_) => true,
+        }
+    }
+
+    /// The OpenGL image, if this is an OpenGL frame.
+    pub fn as_opengl(&self) -> Option<&OpenGLImage> {
+        match self {
+            Frame::OpenGL(image) => Some(image),
+            Frame::Metal(_) => None,
+        }
+    }
+
+    /// The Metal texture, if this is a Metal frame.
+    pub fn as_metal(&self) -> Option<&MetalTexture> {
+        match self {
+            Frame::Metal(texture) => Some(texture),
+            Frame::OpenGL(_) => None,
+        }
+    }
+
+    /// A new `CIImage` over the frame's IOSurface (no copy), oriented upright. Returned +1: release it with
+    /// `CFRelease` (`as_ci_image` manages this for you).
+    #[cfg(feature = "core-image")]
+    pub fn ci_image_ptr(&self) -> Option<CIImagePtr> {
+        let surface = match self {
+            Frame::OpenGL(image) => image.io_surface_ptr(),
+            Frame::Metal(texture) => texture.io_surface_ptr(),
+        }?;
+        #[cfg(target_os = "macos")]
+        {
+            let image = unsafe { ffi::syphon_ci_image_create_with_io_surface(surface) };
+            (!image.is_null()).then_some(image)
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            let _ = surface;
+            None
+        }
+    }
+}
+
+impl From<OpenGLImage> for Frame {
+    fn from(image: OpenGLImage) -> Self {
+        Frame::OpenGL(image)
+    }
+}
+
+impl From<MetalTexture> for Frame {
+    fn from(texture: MetalTexture) -> Self {
+        Frame::Metal(texture)
+    }
+}
+
+/// A `Frame` with the time it was taken from its client (Syphon frames carry no capture time of their own).
+pub struct TimedFrame {
+    pub frame: Frame,
+    pub timestamp: Instant,
+}
+
+impl TimedFrame {
+    /// Stamp `frame` with the current time.
+    pub fn now(frame: impl Into<Frame>) -> Self {
+        Self {
+            frame: frame.into(),
+            timestamp: Instant::now(),
+        }
+    }
+}
+
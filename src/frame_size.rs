@@ -0,0 +1,82 @@
+//! Frame size-change notifications: resources sized to the incoming frames (swapchains, render
+//! targets, encoders) must be reallocated when the server changes resolution. The tracking wraps a
+//! new-frame callback and compares each frame's size with the previous one.
+
+use std::sync::Mutex;
+
+use crate::{
+    CGLContextObj, CallbackDispatch, MTLDevicePtr, MetalClient, NewFrameCallback, OpenGLClient, ServerDescription,
+    SyphonOptions,
+};
+
+/// A change in the size of a client's frames, reported before the first frame of the new size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeChange {
+    /// Previous frame size in pixels; 0 x 0 for the client's first frame.
+    pub old_width: usize,
+    pub old_height: usize,
+    /// Size of the frame that triggered the change.
+    pub width: usize,
+    pub height: usize,
+    /// `FrameInfo::frame_index` of that frame.
+    pub frame_index: u64,
+}
+
+/// Callback for frame size changes (invoked on the thread delivering new-frame notifications).
+pub type SizeChangeCallback = Box<dyn Fn(SizeChange) + Send>;
+
+/// Wrap `callback` so `on_size_change` runs first whenever a frame's size differs from the previous
+/// frame's (including the first frame). Only callbacks dispatched with `CallbackDispatch::SerializedWithSize`
+/// see sizes (frames without one are ignored): pass it to `set_new_frame_handler_with_dispatch`, or use
+/// the constructors below.
+pub fn track_size_changes(callback: Option<NewFrameCallback>, on_size_change: SizeChangeCallback) -> NewFrameCallback {
+    let last = Mutex::new((0usize, 0usize));
+    Box::new(move |info| {
+        if let Some((width, height)) = info.size {
+            let mut last = last.lock().unwrap_or_else(|e| e.into_inner());
+            if *last != (width, height) {
+                let (old_width, old_height) = std::mem::replace(&mut *last, (width, height));
+                on_size_change(SizeChange {
+                    old_width,
+                    old_height,
+                    width,
+                    height,
+                    frame_index: info.frame_index,
+                });
+            }
+        }
+        if let Some(callback) = &callback {
+            callback(info);
+        }
+    })
+}
+
+impl OpenGLClient {
+    /// Like `new`, also calling `on_size_change` (before `callback`) when the frame size changes. Uses
+    /// `CallbackDispatch::SerializedWithSize` (see there for its cost).
+    pub fn new_with_size_changes(
+        description: &ServerDescription,
+        context: CGLContextObj,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+        on_size_change: SizeChangeCallback,
+    ) -> Option<Self> {
+        let callback = Some(track_size_changes(callback, on_size_change));
+        Self::new_with_dispatch(description, context, options, callback, CallbackDispatch::SerializedWithSize)
+    }
+}
+
+impl MetalClient {
+    /// Like `new`, also calling `on_size_change` (before `callback`) when the frame size changes. Uses
+    /// `CallbackDispatch::SerializedWithSize` (see there for its cost).
+    pub fn new_with_size_changes(
+        description: &ServerDescription,
+        device: MTLDevicePtr,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+        on_size_change: SizeChangeCallback,
+    ) -> Option<Self> {
+        let callback = Some(track_size_changes(callback, on_size_change));
+        Self::new_with_dispatch(description, device, options, callback, CallbackDispatch::SerializedWithSize)
+    }
+}
@@ -0,0 +1,62 @@
+//! `glow` integration (`glow` feature): received images as `glow::NativeTexture`s with their target and size,
+//! and publishing from glow texture handles, so glow renderers need none of the raw GL helpers. As with the
+//! raw API, the client's (or server's) CGL context must be current.
+
+use std::marker::PhantomData;
+use std::num::NonZeroU32;
+
+use glow::NativeTexture;
+
+use crate::{OpenGLImage, OpenGLServer, Rect, Size, SyphonError, GL_TEXTURE_RECTANGLE};
+
+/// A received image's texture as glow sees it. Borrowed from the `OpenGLImage`: the texture is only valid
+/// while the image is alive.
+#[derive(Debug, Clone, Copy)]
+pub struct GlowTexture<'a> {
+    pub texture: NativeTexture,
+    /// Always `GL_TEXTURE_RECTANGLE` (sample with unnormalized coordinates, `sampler2DRect` in GLSL).
+    pub target: u32,
+    pub width: u32,
+    pub height: u32,
+    _image: PhantomData<&'a OpenGLImage>,
+}
+
+impl OpenGLImage {
+    /// The image's texture as a glow handle; None if the image has no texture.
+    pub fn glow_texture(&self) -> Option<GlowTexture<'_>> {
+        let (width, height) = self.texture_size();
+        Some(GlowTexture {
+            texture: NativeTexture(NonZeroU32::new(self.texture_name())?),
+            target: GL_TEXTURE_RECTANGLE,
+            width: width as u32,
+            height: height as u32,
+            _image: PhantomData,
+        })
+    }
+}
+
+impl OpenGLServer {
+    /// `publish` with a glow texture handle.
+    pub fn publish_glow_texture(
+        &self,
+        texture: NativeTexture,
+        target: u32,
+        region: Rect,
+        texture_size: Size,
+        flipped: bool,
+    ) {
+        self.publish(texture.0.get(), target, region, texture_size, flipped);
+    }
+
+    /// `try_publish` with a glow texture handle.
+    pub fn try_publish_glow_texture(
+        &self,
+        texture: NativeTexture,
+        target: u32,
+        region: Rect,
+        texture_size: Size,
+        flipped: bool,
+    ) -> Result<(), SyphonError> {
+        self.try_publish(texture.0.get(), target, region, texture_size, flipped)
+    }
+}
@@ -0,0 +1,60 @@
+//! glutin integration (`glutin` feature): servers and clients created straight from a glutin context, so
+//! windowed GL apps share their window's context with Syphon in one call.
+
+use glutin::context::{AsRawContext, RawContext};
+
+use crate::{
+    cgl_context_from_nsopengl_context, CGLContextObj, NewFrameCallback, OpenGLClient, OpenGLServer,
+    ServerDescription, SyphonError, SyphonOptions,
+};
+
+/// The CGL context behind a glutin context (e.g. a `PossiblyCurrentContext`), unretained: valid while
+/// `context` lives.
+pub fn cgl_context_from_glutin(context: &impl AsRawContext) -> Option<CGLContextObj> {
+    let RawContext::Cgl(ns_context) = context.raw_context();
+    cgl_context_from_nsopengl_context(ns_context as *mut _)
+}
+
+impl OpenGLServer {
+    /// `new` on a glutin context's CGL context.
+    pub fn with_glutin_context(
+        name: Option<&str>,
+        context: &impl AsRawContext,
+        options: Option<&SyphonOptions>,
+    ) -> Option<Self> {
+        Self::try_with_glutin_context(name, context, options).ok()
+    }
+
+    /// Like `with_glutin_context`, reporting why creation failed.
+    pub fn try_with_glutin_context(
+        name: Option<&str>,
+        context: &impl AsRawContext,
+        options: Option<&SyphonOptions>,
+    ) -> Result<Self, SyphonError> {
+        let context = cgl_context_from_glutin(context).ok_or(SyphonError::InvalidArgument("null CGL context"))?;
+        Self::try_new(name, context, options)
+    }
+}
+
+impl OpenGLClient {
+    /// `new` on a glutin context's CGL context.
+    pub fn with_glutin_context(
+        description: &ServerDescription,
+        context: &impl AsRawContext,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+    ) -> Option<Self> {
+        Self::try_with_glutin_context(description, context, options, callback).ok()
+    }
+
+    /// Like `with_glutin_context`, reporting why creation failed.
+    pub fn try_with_glutin_context(
+        description: &ServerDescription,
+        context: &impl AsRawContext,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+    ) -> Result<Self, SyphonError> {
+        let context = cgl_context_from_glutin(context).ok_or(SyphonError::InvalidArgument("null CGL context"))?;
+        Self::try_new(description, context, options, callback)
+    }
+}
@@ -0,0 +1,24 @@
+//! GStreamer elements for Syphon (macOS):
+//!
+//! - `syphonclientsrc`: receives frames from a Syphon server as `video/x-raw,format=RGBA`.
+//! - `syphonserversink`: publishes a `video/x-raw,format=RGBA` stream as a Syphon server.
+//!
+//! Both elements use a private headless CGL context and the OpenGL Syphon types. Register them in an
+//! application with `register_gstreamer_elements(None)` after `gst::init`, or load the
+//! `gst-plugin-syphon` plugin, which registers them from its `plugin_init`.
+
+use gst::glib;
+
+mod syphonclientsrc;
+mod syphonserversink;
+
+pub use syphonclientsrc::SyphonClientSrc;
+pub use syphonserversink::SyphonServerSink;
+
+/// Register `syphonclientsrc` and `syphonserversink` with `plugin`, or statically for this process
+/// with None (GStreamer must be initialized first).
+pub fn register_gstreamer_elements(plugin: Option<&gst::Plugin>) -> Result<(), glib::BoolError> {
+    syphonclientsrc::register(plugin)?;
+    syphonserversink::register(plugin)?;
+    Ok(())
+}
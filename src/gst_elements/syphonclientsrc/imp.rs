@@ -0,0 +1,295 @@
+//! `syphonclientsrc`: live source that reads frames from a Syphon server into RGBA system-memory buffers.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+use gst_base::prelude::*;
+use gst_base::subclass::base_src::CreateSuccess;
+use gst_base::subclass::prelude::*;
+
+use crate::{
+    cgl_create_headless_context, cgl_destroy_context, cgl_make_current, gl_read_texture_rectangle_rgba8,
+    CGLContextObj, OpenGLClient, ServerDescription, ServerDirectory,
+};
+
+static CAT: LazyLock<gst::DebugCategory> = LazyLock::new(|| {
+    gst::DebugCategory::new(
+        "syphonclientsrc",
+        gst::DebugColorFlags::empty(),
+        Some("Syphon client source"),
+    )
+});
+
+/// How long `create` sleeps between new-frame polls.
+const POLL_INTERVAL: Duration = Duration::from_millis(2);
+
+#[derive(Debug, Clone, Default)]
+struct Settings {
+    server_name: Option<String>,
+    app_name: Option<String>,
+    server_uuid: Option<String>,
+}
+
+struct State {
+    context: CGLContextObj,
+    client: Option<OpenGLClient>,
+    info: Option<gst_video::VideoInfo>,
+}
+
+// The CGL context and client are only touched from the streaming thread (behind `state`'s mutex),
+// and the context is made current before every GL call.
+unsafe impl Send for State {}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        cgl_make_current(self.context);
+        // The client must be released before the context it was created with.
+        if let Some(client) = self.client.take() {
+            client.stop();
+        }
+        cgl_destroy_context(self.context);
+    }
+}
+
+#[derive(Default)]
+pub struct SyphonClientSrc {
+    settings: Mutex<Settings>,
+    state: Mutex<Option<State>>,
+    flushing: AtomicBool,
+}
+
+impl SyphonClientSrc {
+    fn find_server(settings: &Settings) -> Option<ServerDescription> {
+        let dir = ServerDirectory::shared()?;
+        dir.servers().into_iter().find(|desc| {
+            let uuid_ok = settings
+                .server_uuid
+                .as_ref()
+                .is_none_or(|u| desc.uuid().as_ref() == Some(u));
+            let name_ok = settings
+                .server_name
+                .as_ref()
+                .is_none_or(|n| desc.name().as_ref() == Some(n));
+            let app_ok = settings
+                .app_name
+                .as_ref()
+                .is_none_or(|a| desc.app_name().as_ref() == Some(a));
+            uuid_ok && name_ok && app_ok
+        })
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for SyphonClientSrc {
+    const NAME: &'static str = "GstSyphonClientSrc";
+    type Type = super::SyphonClientSrc;
+    type ParentType = gst_base::PushSrc;
+}
+
+impl ObjectImpl for SyphonClientSrc {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: LazyLock<Vec<glib::ParamSpec>> = LazyLock::new(|| {
+            vec![
+                glib::ParamSpecString::builder("server-name")
+                    .nick("Server name")
+                    .blurb("Name of the Syphon server to receive from (any if unset)")
+                    .mutable_ready()
+                    .build(),
+                glib::ParamSpecString::builder("app-name")
+                    .nick("App name")
+                    .blurb("Application hosting the Syphon server (any if unset)")
+                    .mutable_ready()
+                    .build(),
+                glib::ParamSpecString::builder("server-uuid")
+                    .nick("Server UUID")
+                    .blurb("Exact Syphon server UUID (takes precedence over names)")
+                    .mutable_ready()
+                    .build(),
+            ]
+        });
+        PROPERTIES.as_ref()
+    }
+
+    fn set_property(&self, _id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+        let mut settings = self.settings.lock().unwrap();
+        let v: Option<String> = value.get().expect("type checked upstream");
+        match pspec.name() {
+            "server-name" => settings.server_name = v,
+            "app-name" => settings.app_name = v,
+            "server-uuid" => settings.server_uuid = v,
+            _ => unreachable!(),
+        }
+    }
+
+    fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        let settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "server-name" => settings.server_name.to_value(),
+            "app-name" => settings.app_name.to_value(),
+            "server-uuid" => settings.server_uuid.to_value(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn constructed(&self) {
+        self.parent_constructed();
+        let obj = self.obj();
+        obj.set_live(true);
+        obj.set_format(gst::Format::Time);
+        obj.set_do_timestamp(true);
+    }
+}
+
+impl GstObjectImpl for SyphonClientSrc {}
+
+impl ElementImpl for SyphonClientSrc {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: LazyLock<gst::subclass::ElementMetadata> = LazyLock::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Syphon Client Source",
+                "Source/Video",
+                "Receives frames from a Syphon server",
+                "rusty-syphon-spout contributors",
+            )
+        });
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: LazyLock<Vec<gst::PadTemplate>> = LazyLock::new(|| {
+            let caps = gst_video::VideoCapsBuilder::new()
+                .format(gst_video::VideoFormat::Rgba)
+                .build();
+            vec![gst::PadTemplate::new(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap()]
+        });
+        PAD_TEMPLATES.as_ref()
+    }
+}
+
+impl BaseSrcImpl for SyphonClientSrc {
+    fn start(&self) -> Result<(), gst::ErrorMessage> {
+        let settings = self.settings.lock().unwrap().clone();
+        let desc = Self::find_server(&settings).ok_or_else(|| {
+            gst::error_msg!(
+                gst::ResourceError::NotFound,
+                ["No Syphon server matching {:?}", settings]
+            )
+        })?;
+        let context = cgl_create_headless_context().ok_or_else(|| {
+            gst::error_msg!(gst::ResourceError::Failed, ["Failed to create CGL context"])
+        })?;
+        cgl_make_current(context);
+        let Some(client) = OpenGLClient::new(&desc, context, None, None) else {
+            cgl_destroy_context(context);
+            return Err(gst::error_msg!(
+                gst::ResourceError::OpenRead,
+                ["Failed to create Syphon client for {:?}", desc.name()]
+            ));
+        };
+        gst::info!(CAT, imp = self, "Connected to Syphon server {:?}", desc.name());
+        *self.state.lock().unwrap() = Some(State {
+            context,
+            client: Some(client),
+            info: None,
+        });
+        Ok(())
+    }
+
+    fn stop(&self) -> Result<(), gst::ErrorMessage> {
+        self.state.lock().unwrap().take();
+        Ok(())
+    }
+
+    fn is_seekable(&self) -> bool {
+        false
+    }
+
+    fn unlock(&self) -> Result<(), gst::ErrorMessage> {
+        self.flushing.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn unlock_stop(&self) -> Result<(), gst::ErrorMessage> {
+        self.flushing.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+impl PushSrcImpl for SyphonClientSrc {
+    fn create(&self, _buffer: Option<&mut gst::BufferRef>) -> Result<CreateSuccess, gst::FlowError> {
+        // Set after renegotiating, so the frame that changed the size is read again without waiting for a new one.
+        let mut refetch = false;
+        loop {
+            if self.flushing.load(Ordering::SeqCst) {
+                return Err(gst::FlowError::Flushing);
+            }
+            let guard = self.state.lock().unwrap();
+            let state = guard.as_ref().ok_or(gst::FlowError::Flushing)?;
+            let client = state.client.as_ref().ok_or(gst::FlowError::Flushing)?;
+            if !client.is_valid() {
+                gst::element_imp_error!(self, gst::ResourceError::Read, ["Syphon server went away"]);
+                return Err(gst::FlowError::Error);
+            }
+            if !refetch && !client.has_new_frame() {
+                drop(guard);
+                std::thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+            refetch = false;
+
+            cgl_make_current(state.context);
+            let image = client.new_frame_image().ok_or(gst::FlowError::Error)?;
+            let (w, h) = image.texture_size();
+            let (w, h) = (w as u32, h as u32);
+            if state.info.as_ref().is_none_or(|i| i.width() != w || i.height() != h) {
+                let info = gst_video::VideoInfo::builder(gst_video::VideoFormat::Rgba, w, h)
+                    .fps(gst::Fraction::new(0, 1))
+                    .build()
+                    .map_err(|_| gst::FlowError::NotNegotiated)?;
+                let caps = info.to_caps().map_err(|_| gst::FlowError::NotNegotiated)?;
+                // The lock is released while caps are set, and `stop` may then destroy the context, so the
+                // image goes first and the frame is read again under the lock.
+                drop(image);
+                drop(guard);
+                self.obj()
+                    .set_caps(&caps)
+                    .map_err(|_| gst::FlowError::NotNegotiated)?;
+                gst::debug!(CAT, imp = self, "Negotiated {}x{}", w, h);
+                let mut guard = self.state.lock().unwrap();
+                let state = guard.as_mut().ok_or(gst::FlowError::Flushing)?;
+                state.info = Some(info);
+                refetch = true;
+                continue;
+            }
+
+            let size = w as usize * h as usize * 4;
+            let mut buffer = gst::Buffer::with_size(size).map_err(|_| gst::FlowError::Error)?;
+            {
+                let buffer = buffer.get_mut().unwrap();
+                let mut map = buffer.map_writable().map_err(|_| gst::FlowError::Error)?;
+                let pixels = map.as_mut_slice();
+                gl_read_texture_rectangle_rgba8(image.texture_name(), w as usize, h as usize, pixels);
+                // Syphon frames have a bottom-left origin; GStreamer rows are top-down.
+                let row = w as usize * 4;
+                for y in 0..h as usize / 2 {
+                    let (top, bottom) = pixels.split_at_mut((h as usize - 1 - y) * row);
+                    top[y * row..(y + 1) * row].swap_with_slice(&mut bottom[..row]);
+                }
+            }
+            // Released while the lock is still held, so `stop` can't destroy the context under it.
+            drop(image);
+            drop(guard);
+            return Ok(CreateSuccess::NewBuffer(buffer));
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! `syphonserversink`: publishes RGBA system-memory video frames as a Syphon OpenGL server.
+
+use std::sync::{LazyLock, Mutex};
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+use gst_base::subclass::prelude::*;
+use gst_video::subclass::prelude::*;
+
+use crate::{
+    cgl_create_headless_context, cgl_destroy_context, cgl_make_current, gl_create_texture_rectangle_rgba8,
+    gl_delete_texture, gl_write_texture_region_rgba8, CGLContextObj, OpenGLServer, GL_TEXTURE_RECTANGLE,
+};
+
+static CAT: LazyLock<gst::DebugCategory> = LazyLock::new(|| {
+    gst::DebugCategory::new(
+        "syphonserversink",
+        gst::DebugColorFlags::empty(),
+        Some("Syphon server sink"),
+    )
+});
+
+const DEFAULT_SERVER_NAME: &str = "GStreamer";
+
+#[derive(Debug, Clone)]
+struct Settings {
+    server_name: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            server_name: DEFAULT_SERVER_NAME.to_string(),
+        }
+    }
+}
+
+struct State {
+    context: CGLContextObj,
+    server: Option<OpenGLServer>,
+    texture: u32,
+    info: Option<gst_video::VideoInfo>,
+    /// Tightly packed copy of the frame, used when the buffer stride has row padding.
+    scratch: Vec<u8>,
+}
+
+// The CGL context, server and texture are only touched from the streaming thread (behind `state`'s
+// mutex), and the context is made current before every GL call.
+unsafe impl Send for State {}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        cgl_make_current(self.context);
+        if let Some(server) = self.server.take() {
+            server.stop();
+        }
+        if self.texture != 0 {
+            gl_delete_texture(self.texture);
+        }
+        cgl_destroy_context(self.context);
+    }
+}
+
+#[derive(Default)]
+pub struct SyphonServerSink {
+    settings: Mutex<Settings>,
+    state: Mutex<Option<State>>,
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for SyphonServerSink {
+    const NAME: &'static str = "GstSyphonServerSink";
+    type Type = super::SyphonServerSink;
+    type ParentType = gst_video::VideoSink;
+}
+
+impl ObjectImpl for SyphonServerSink {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: LazyLock<Vec<glib::ParamSpec>> = LazyLock::new(|| {
+            vec![glib::ParamSpecString::builder("server-name")
+                .nick("Server name")
+                .blurb("Name the Syphon server is announced under")
+                .default_value(Some(DEFAULT_SERVER_NAME))
+                .mutable_ready()
+                .build()]
+        });
+        PROPERTIES.as_ref()
+    }
+
+    fn set_property(&self, _id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+        match pspec.name() {
+            "server-name" => {
+                let name: Option<String> = value.get().expect("type checked upstream");
+                self.settings.lock().unwrap().server_name =
+                    name.unwrap_or_else(|| DEFAULT_SERVER_NAME.to_string());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        match pspec.name() {
+            "server-name" => self.settings.lock().unwrap().server_name.to_value(),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl GstObjectImpl for SyphonServerSink {}
+
+impl ElementImpl for SyphonServerSink {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: LazyLock<gst::subclass::ElementMetadata> = LazyLock::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Syphon Server Sink",
+                "Sink/Video",
+                "Publishes video frames as a Syphon server",
+                "rusty-syphon-spout contributors",
+            )
+        });
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: LazyLock<Vec<gst::PadTemplate>> = LazyLock::new(|| {
+            let caps = gst_video::VideoCapsBuilder::new()
+                .format(gst_video::VideoFormat::Rgba)
+                .build();
+            vec![gst::PadTemplate::new(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap()]
+        });
+        PAD_TEMPLATES.as_ref()
+    }
+}
+
+impl BaseSinkImpl for SyphonServerSink {
+    fn start(&self) -> Result<(), gst::ErrorMessage> {
+        let settings = self.settings.lock().unwrap().clone();
+        let context = cgl_create_headless_context().ok_or_else(|| {
+            gst::error_msg!(gst::ResourceError::Failed, ["Failed to create CGL context"])
+        })?;
+        cgl_make_current(context);
+        let Some(server) = OpenGLServer::new(Some(&settings.server_name), context, None) else {
+            cgl_destroy_context(context);
+            return Err(gst::error_msg!(
+                gst::ResourceError::OpenWrite,
+                ["Failed to create Syphon server {:?}", settings.server_name]
+            ));
+        };
+        gst::info!(CAT, imp = self, "Publishing as Syphon server {:?}", settings.server_name);
+        *self.state.lock().unwrap() = Some(State {
+            context,
+            server: Some(server),
+            texture: 0,
+            info: None,
+            scratch: Vec::new(),
+        });
+        Ok(())
+    }
+
+    fn stop(&self) -> Result<(), gst::ErrorMessage> {
+        self.state.lock().unwrap().take();
+        Ok(())
+    }
+
+    fn set_caps(&self, caps: &gst::Caps) -> Result<(), gst::LoggableError> {
+        let info = gst_video::VideoInfo::from_caps(caps)
+            .map_err(|_| gst::loggable_error!(CAT, "Failed to parse caps {}", caps))?;
+        let mut guard = self.state.lock().unwrap();
+        let state = guard
+            .as_mut()
+            .ok_or_else(|| gst::loggable_error!(CAT, "Caps set before start"))?;
+
+        let (w, h) = (info.width() as usize, info.height() as usize);
+        cgl_make_current(state.context);
+        if state.texture != 0 {
+            gl_delete_texture(state.texture);
+        }
+        state.texture = gl_create_texture_rectangle_rgba8(w, h, &vec![0u8; w * h * 4]);
+        if state.texture == 0 {
+            return Err(gst::loggable_error!(CAT, "Failed to create {}x{} texture", w, h));
+        }
+        gst::debug!(CAT, imp = self, "Configured for {}x{}", w, h);
+        state.info = Some(info);
+        Ok(())
+    }
+}
+
+impl VideoSinkImpl for SyphonServerSink {
+    fn show_frame(&self, buffer: &gst::Buffer) -> Result<gst::FlowSuccess, gst::FlowError> {
+        let mut guard = self.state.lock().unwrap();
+        let state = guard.as_mut().ok_or(gst::FlowError::Flushing)?;
+        let info = state.info.as_ref().ok_or(gst::FlowError::NotNegotiated)?;
+        let frame = gst_video::VideoFrameRef::from_buffer_ref_readable(buffer.as_ref(), info)
+            .map_err(|_| gst::FlowError::Error)?;
+
+        let (w, h) = (frame.width() as usize, frame.height() as usize);
+        let stride = frame.plane_stride()[0] as usize;
+        let data = frame.plane_data(0).map_err(|_| gst::FlowError::Error)?;
+        let row = w * 4;
+        let pixels = if stride == row {
+            &data[..row * h]
+        } else {
+            state.scratch.resize(row * h, 0);
+            for (dst, src) in state.scratch.chunks_exact_mut(row).zip(data.chunks(stride)) {
+                dst.copy_from_slice(&src[..row]);
+            }
+            &state.scratch[..]
+        };
+
+        cgl_make_current(state.context);
+        if !gl_write_texture_region_rgba8(state.texture, GL_TEXTURE_RECTANGLE, 0, 0, w, h, pixels) {
+            gst::element_imp_error!(self, gst::ResourceError::Write, ["Texture upload failed"]);
+            return Err(gst::FlowError::Error);
+        }
+        if let Some(server) = &state.server {
+            // GStreamer rows are top-down; Syphon's GL origin is bottom-left.
+            server.publish_whole_texture(state.texture, GL_TEXTURE_RECTANGLE, w as f64, h as f64, true);
+        }
+        Ok(gst::FlowSuccess::Ok)
+    }
+}
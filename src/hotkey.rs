@@ -0,0 +1,408 @@
+//! System-wide hotkeys and a snapshot service that saves a still of a chosen server on each press (`hotkey`
+//! feature).
+//!
+//! Hotkeys are Carbon `RegisterEventHotKey` registrations, delivered on the main thread: the process's
+//! main run loop must be running (an `NSApplication`, or `CFRunLoopRun` on the main thread).
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(target_os = "macos")]
+use std::ptr::NonNull;
+
+use crate::capture::{read_gl_image_top_down, write_image};
+#[cfg(target_os = "macos")]
+use crate::ffi;
+use crate::{cgl_destroy_context, try_cgl_create_headless_context, cgl_make_current, CaptureFormat, OpenGLClient, ServerDirectory};
+
+/// Carbon modifier flags for `HotKey::modifiers`.
+pub const HOTKEY_CMD: u32 = 0x0100;
+pub const HOTKEY_SHIFT: u32 = 0x0200;
+pub const HOTKEY_OPTION: u32 = 0x0800;
+pub const HOTKEY_CONTROL: u32 = 0x1000;
+
+/// A key combination: virtual key code (`kVK_*`) plus `HOTKEY_*` modifier flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HotKey {
+    pub key_code: u32,
+    pub modifiers: u32,
+}
+
+/// ANSI-layout virtual key codes for letters, digits, F-keys and a few named keys.
+const KEY_CODES: &[(&str, u32)] = &[
+    ("a", 0x00), ("s", 0x01), ("d", 0x02), ("f", 0x03), ("h", 0x04), ("g", 0x05), ("z", 0x06),
+    ("x", 0x07), ("c", 0x08), ("v", 0x09), ("b", 0x0B), ("q", 0x0C), ("w", 0x0D), ("e", 0x0E),
+    ("r", 0x0F), ("y", 0x10), ("t", 0x11), ("1", 0x12), ("2", 0x13), ("3", 0x14), ("4", 0x15),
+    ("6", 0x16), ("5", 0x17), ("9", 0x19), ("7", 0x1A), ("8", 0x1C), ("0", 0x1D), ("o", 0x1F),
+    ("u", 0x20), ("i", 0x22), ("p", 0x23), ("l", 0x25), ("j", 0x26), ("k", 0x28), ("n", 0x2D),
+    ("m", 0x2E), ("return", 0x24), ("tab", 0x30), ("space", 0x31), ("escape", 0x35),
+    ("f1", 0x7A), ("f2", 0x78), ("f3", 0x63), ("f4", 0x76), ("f5", 0x60), ("f6", 0x61),
+    ("f7", 0x62), ("f8", 0x64), ("f9", 0x65), ("f10", 0x6D), ("f11", 0x67), ("f12", 0x6F),
+];
+
+impl HotKey {
+    pub fn new(key_code: u32, modifiers: u32) -> Self {
+        Self { key_code, modifiers }
+    }
+
+    /// Parse a combination such as `"cmd+shift+s"` or `"ctrl+option+f5"` (case-insensitive; modifiers
+    /// `cmd`/`command`, `shift`, `option`/`alt`, `ctrl`/`control`; one key from the ANSI letters,
+    /// digits, `f1`..`f12`, `space`, `return`, `tab`, `escape`).
+    pub fn parse(combo: &str) -> Option<Self> {
+        let mut modifiers = 0;
+        let mut key = None;
+        for part in combo.split('+').map(|p| p.trim().to_ascii_lowercase()) {
+            match part.as_str() {
+                "cmd" | "command" => modifiers |= HOTKEY_CMD,
+                "shift" => modifiers |= HOTKEY_SHIFT,
+                "option" | "alt" => modifiers |= HOTKEY_OPTION,
+                "ctrl" | "control" => modifiers |= HOTKEY_CONTROL,
+                name => {
+                    let code = KEY_CODES.iter().find(|(n, _)| *n == name)?.1;
+                    if key.replace(code).is_some() {
+                        return None;
+                    }
+                }
+            }
+        }
+        key.map(|key_code| Self { key_code, modifiers })
+    }
+}
+
+/// A registered system-wide hotkey; unregistered when dropped.
+pub struct GlobalHotKey {
+    #[cfg(target_os = "macos")]
+    ptr: NonNull<std::ffi::c_void>,
+    hotkey: HotKey,
+}
+
+#[cfg(target_os = "macos")]
+type HotKeyCallback = Box<dyn Fn() + Send>;
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn hotkey_callback(userdata: *mut std::ffi::c_void) {
+    if let Some(callback) = (userdata as *const HotKeyCallback).as_ref() {
+        callback();
+    }
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn hotkey_release(userdata: *mut std::ffi::c_void) {
+    if !userdata.is_null() {
+        drop(Box::from_raw(userdata as *mut HotKeyCallback));
+    }
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl GlobalHotKey {
+    /// Register `hotkey`; `callback` runs on the main thread on each press. None if the combination is
+    /// taken or can't be registered.
+    pub fn register(hotkey: HotKey, callback: Box<dyn Fn() + Send>) -> Option<Self> {
+        #[cfg(target_os = "macos")]
+        {
+            let userdata = Box::into_raw(Box::new(callback)) as *mut std::ffi::c_void;
+            let ptr = unsafe {
+                ffi::syphon_hotkey_register(
+                    hotkey.key_code,
+                    hotkey.modifiers,
+                    Some(hotkey_callback),
+                    Some(hotkey_release),
+                    userdata,
+                )
+            };
+            NonNull::new(ptr).map(|ptr| Self { ptr, hotkey })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    pub fn hotkey(&self) -> HotKey {
+        self.hotkey
+    }
+}
+
+impl Drop for GlobalHotKey {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_hotkey_unregister(self.ptr.as_ptr());
+        }
+    }
+}
+
+/// How snapshot files are named (both sort chronologically).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SnapshotNaming {
+    /// `<prefix>-<unix ms>.<ext>`
+    #[default]
+    Timestamp,
+    /// `<prefix>-00001.<ext>`, continuing after the highest number already in the directory.
+    Sequence,
+}
+
+/// What `SnapshotService` grabs and where it saves it.
+#[derive(Debug, Clone)]
+pub struct SnapshotConfig {
+    pub hotkey: HotKey,
+    /// Server to snapshot, matched like `ServerDirectory::servers_matching` (None matches any).
+    pub server_name: Option<String>,
+    pub app_name: Option<String>,
+    pub dir: PathBuf,
+    pub prefix: String,
+    pub format: CaptureFormat,
+    pub naming: SnapshotNaming,
+    /// Keep at most this many snapshots with `prefix` in `dir`, deleting the oldest (lowest number).
+    /// None keeps all; must not be `Some(0)`.
+    pub keep: Option<usize>,
+    /// How long to wait for the server's frame after a press.
+    pub frame_timeout: Duration,
+}
+
+impl SnapshotConfig {
+    /// Snapshot the first matching server into `dir` as PPM files named `snapshot-<unix ms>.ppm`.
+    pub fn new(hotkey: HotKey, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            hotkey,
+            server_name: None,
+            app_name: None,
+            dir: dir.into(),
+            prefix: "snapshot".to_string(),
+            format: CaptureFormat::Ppm,
+            naming: SnapshotNaming::Timestamp,
+            keep: None,
+            frame_timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Saves a snapshot of the configured server whenever the hotkey is pressed (or `trigger` is called).
+/// Frames are read on a worker thread with its own headless CGL context.
+pub struct SnapshotService {
+    hotkey: Option<GlobalHotKey>,
+    trigger: Option<Sender<()>>,
+    worker: Option<JoinHandle<()>>,
+    results: Receiver<io::Result<PathBuf>>,
+}
+
+impl SnapshotService {
+    /// Register the hotkey and start the worker. None if `keep` is `Some(0)`, the hotkey can't be
+    /// registered or `dir` can't be created.
+    pub fn start(config: SnapshotConfig) -> Option<Self> {
+        if config.keep == Some(0) {
+            return None;
+        }
+        std::fs::create_dir_all(&config.dir).ok()?;
+        let (trigger_tx, trigger_rx) = mpsc::channel::<()>();
+        let (result_tx, results) = mpsc::channel();
+        let hotkey = {
+            let tx = trigger_tx.clone();
+            GlobalHotKey::register(
+                config.hotkey,
+                Box::new(move || {
+                    let _ = tx.send(());
+                }),
+            )?
+        };
+        let worker = std::thread::Builder::new()
+            .name("syphon-snapshot".into())
+            .spawn(move || {
+                while trigger_rx.recv().is_ok() {
+                    if result_tx.send(take_snapshot(&config)).is_err() {
+                        break;
+                    }
+                }
+            })
+            .ok()?;
+        Some(Self {
+            hotkey: Some(hotkey),
+            trigger: Some(trigger_tx),
+            worker: Some(worker),
+            results,
+        })
+    }
+
+    /// Take a snapshot now, as if the hotkey was pressed.
+    pub fn trigger(&self) {
+        if let Some(tx) = &self.trigger {
+            let _ = tx.send(());
+        }
+    }
+
+    /// Outcome of the next finished snapshot (saved path or error), if any.
+    pub fn try_recv(&self) -> Option<io::Result<PathBuf>> {
+        self.results.try_recv().ok()
+    }
+}
+
+impl Drop for SnapshotService {
+    fn drop(&mut self) {
+        // Unregister first so no press can queue after the worker is told to stop.
+        self.hotkey.take();
+        self.trigger.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn take_snapshot(config: &SnapshotConfig) -> io::Result<PathBuf> {
+    let description = ServerDirectory::shared()
+        .and_then(|dir| {
+            dir.servers_matching(config.server_name.as_deref(), config.app_name.as_deref())
+                .into_iter()
+                .next()
+        })
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no matching Syphon server"))?;
+    let context = try_cgl_create_headless_context().map_err(io::Error::other)?;
+    cgl_make_current(context);
+    let frame = OpenGLClient::new(&description, context, None, None).and_then(|client| {
+        let deadline = Instant::now() + config.frame_timeout;
+        loop {
+            if let Some(frame) = client.new_frame_image().and_then(|image| read_gl_image_top_down(&image)) {
+                return Some(frame);
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    });
+    cgl_destroy_context(context);
+    let (width, height, rgba) =
+        frame.ok_or_else(|| io::Error::new(io::ErrorKind::TimedOut, "no frame from server"))?;
+
+    let path = config.dir.join(snapshot_file_name(config)?);
+    write_image(&path, width, height, &rgba, config.format)?;
+    if let Some(keep) = config.keep {
+        rotate_snapshots(&config.dir, &config.prefix, config.format.extension(), keep)?;
+    }
+    Ok(path)
+}
+
+fn snapshot_file_name(config: &SnapshotConfig) -> io::Result<String> {
+    let ext = config.format.extension();
+    let stem = match config.naming {
+        SnapshotNaming::Timestamp => {
+            let millis = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0);
+            format!("{millis}")
+        }
+        SnapshotNaming::Sequence => {
+            let last = snapshot_files(&config.dir, &config.prefix, ext)?.last().map_or(0, |(n, _)| *n);
+            format!("{:05}", last + 1)
+        }
+    };
+    Ok(format!("{}-{stem}.{ext}", config.prefix))
+}
+
+/// Snapshot files for `prefix`/`ext` in `dir` with their number (timestamp or sequence), oldest first.
+/// Sorted by the number rather than the name, so `snapshot-100000` comes after `snapshot-99999`.
+fn snapshot_files(dir: &Path, prefix: &str, ext: &str) -> io::Result<Vec<(u64, PathBuf)>> {
+    let head = format!("{prefix}-");
+    let tail = format!(".{ext}");
+    let mut files: Vec<(u64, PathBuf)> = std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter_map(|p| {
+            let name = p.file_name()?.to_str()?;
+            let number = name.strip_prefix(&head)?.strip_suffix(&tail)?.parse().ok()?;
+            Some((number, p))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Delete the oldest snapshots beyond `keep` (at least 1, so the one just written stays).
+fn rotate_snapshots(dir: &Path, prefix: &str, ext: &str, keep: usize) -> io::Result<()> {
+    let files = snapshot_files(dir, prefix, ext)?;
+    let excess = files.len().saturating_sub(keep.max(1));
+    for (_, old) in &files[..excess] {
+        std::fs::remove_file(old)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory under the system temp dir, removed on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("rss-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn touch(&self, names: &[&str]) {
+            for name in names {
+                std::fs::write(self.0.join(name), b"").unwrap();
+            }
+        }
+
+        fn names(&self) -> Vec<String> {
+            let mut names: Vec<String> = std::fs::read_dir(&self.0)
+                .unwrap()
+                .map(|e| e.unwrap().file_name().into_string().unwrap())
+                .collect();
+            names.sort();
+            names
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn snapshot_files_sort_by_number() {
+        let dir = TempDir::new("snapshot-order");
+        dir.touch(&["snap-100000.ppm", "snap-99999.ppm", "snap-00002.ppm", "snap-x.ppm", "snap-5.png", "other-1.ppm"]);
+        let numbers: Vec<u64> = snapshot_files(&dir.0, "snap", "ppm").unwrap().into_iter().map(|(n, _)| n).collect();
+        assert_eq!(numbers, [2, 99999, 100000]);
+    }
+
+    #[test]
+    fn rotation_deletes_the_lowest_numbers() {
+        let dir = TempDir::new("snapshot-rotate");
+        dir.touch(&["snap-99998.ppm", "snap-99999.ppm", "snap-100000.ppm", "snap-100001.ppm", "keep-1.ppm"]);
+        rotate_snapshots(&dir.0, "snap", "ppm", 2).unwrap();
+        assert_eq!(dir.names(), ["keep-1.ppm", "snap-100000.ppm", "snap-100001.ppm"]);
+    }
+
+    #[test]
+    fn rotation_keeps_the_newest_snapshot() {
+        let dir = TempDir::new("snapshot-keep-zero");
+        dir.touch(&["snap-1.ppm", "snap-2.ppm"]);
+        rotate_snapshots(&dir.0, "snap", "ppm", 0).unwrap();
+        assert_eq!(dir.names(), ["snap-2.ppm"]);
+    }
+
+    #[test]
+    fn sequence_naming_continues_after_the_highest_number() {
+        let dir = TempDir::new("snapshot-sequence");
+        let mut config = SnapshotConfig::new(HotKey::new(0, 0), &dir.0);
+        config.naming = SnapshotNaming::Sequence;
+        assert_eq!(snapshot_file_name(&config).unwrap(), "snapshot-00001.ppm");
+        dir.touch(&["snapshot-99999.ppm", "snapshot-100000.ppm", "snapshot-00007.ppm"]);
+        assert_eq!(snapshot_file_name(&config).unwrap(), "snapshot-100001.ppm");
+    }
+
+    #[test]
+    fn start_rejects_keeping_no_snapshots() {
+        let dir = TempDir::new("snapshot-start");
+        let mut config = SnapshotConfig::new(HotKey::new(0, 0), &dir.0);
+        config.keep = Some(0);
+        assert!(SnapshotService::start(config).is_none());
+    }
+}
@@ -0,0 +1,360 @@
+//! Still images and image sequences as servers: `load_image` decodes PNG (`png` feature), JPEG (`jpeg`)
+//! and OpenEXR (`exr`) files to RGBA8, and `ImagePublisher` publishes one file, or a numbered sequence
+//! found by `image_sequence`, at a chosen frame rate through a `CpuServer` on its own thread.
+//!
+//! Every format ends up as top-down, straight-alpha RGBA8: grayscale and RGB gain opaque alpha, 16-bit
+//! samples keep their high byte, CMYK JPEGs are converted to RGB, and EXR's linear half/float samples
+//! are clamped to 0..1 and encoded with the sRGB curve.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::worker::{frame_interval, Worker, POLL_INTERVAL};
+use crate::{CpuServer, ServerDescription, SyphonError};
+
+/// A decoded image: `width` x `height` RGBA8 pixels, top row first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StillImage {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+}
+
+/// Extensions `load_image` can decode in this build.
+pub fn supported_image_extensions() -> &'static [&'static str] {
+    &[
+        #[cfg(feature = "png")]
+        "png",
+        #[cfg(feature = "jpeg")]
+        "jpg",
+        #[cfg(feature = "jpeg")]
+        "jpeg",
+        #[cfg(feature = "exr")]
+        "exr",
+    ]
+}
+
+fn extension(path: &Path) -> Option<String> {
+    Some(path.extension()?.to_str()?.to_ascii_lowercase())
+}
+
+fn load_failed(path: &Path, reason: impl std::fmt::Display) -> SyphonError {
+    SyphonError::CreationFailed {
+        object: "image",
+        reason: format!("{}: {reason}", path.display()),
+    }
+}
+
+/// Decode the image at `path`, chosen by its extension. `InvalidArgument` for extensions this build
+/// can't decode (see `supported_image_extensions`), `CreationFailed` if reading or decoding failed.
+pub fn load_image(path: &Path) -> Result<StillImage, SyphonError> {
+    let ext = extension(path).unwrap_or_default();
+    if !supported_image_extensions().contains(&ext.as_str()) {
+        return Err(SyphonError::InvalidArgument(
+            "unsupported image format (PNG needs the `png` feature, JPEG `jpeg`, OpenEXR `exr`)",
+        ));
+    }
+    let image = match ext.as_str() {
+        #[cfg(feature = "png")]
+        "png" => load_png(path),
+        #[cfg(feature = "jpeg")]
+        "jpg" | "jpeg" => load_jpeg(path),
+        #[cfg(feature = "exr")]
+        "exr" => load_exr(path),
+        _ => Err("unsupported image format".to_owned()),
+    };
+    image.map_err(|reason| load_failed(path, reason))
+}
+
+#[cfg(feature = "png")]
+fn load_png(path: &Path) -> Result<StillImage, String> {
+    let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut decoder = png::Decoder::new(std::io::BufReader::new(file));
+    // Palette and low bit depths expand to 8 bits per sample, 16-bit samples drop their low byte.
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).map_err(|e| e.to_string())?;
+    let pixels = &buf[..info.buffer_size()];
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => pixels.to_vec(),
+        png::ColorType::Rgb => pixels.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => pixels.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        png::ColorType::Grayscale => pixels.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+        png::ColorType::Indexed => return Err("palette was not expanded".into()),
+    };
+    Ok(StillImage {
+        width: info.width as usize,
+        height: info.height as usize,
+        rgba,
+    })
+}
+
+#[cfg(feature = "jpeg")]
+fn load_jpeg(path: &Path) -> Result<StillImage, String> {
+    let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut decoder = jpeg_decoder::Decoder::new(std::io::BufReader::new(file));
+    let pixels = decoder.decode().map_err(|e| e.to_string())?;
+    let info = decoder.info().ok_or("no frame")?;
+    let rgba = match info.pixel_format {
+        jpeg_decoder::PixelFormat::RGB24 => pixels.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        jpeg_decoder::PixelFormat::L8 => pixels.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+        jpeg_decoder::PixelFormat::L16 => pixels
+            .chunks_exact(2)
+            .flat_map(|p| {
+                let l = (u16::from_ne_bytes([p[0], p[1]]) >> 8) as u8;
+                [l, l, l, 255]
+            })
+            .collect(),
+        jpeg_decoder::PixelFormat::CMYK32 => pixels
+            .chunks_exact(4)
+            .flat_map(|p| {
+                let k = 255 - p[3] as u32;
+                let channel = |c: u8| ((255 - c as u32) * k / 255) as u8;
+                [channel(p[0]), channel(p[1]), channel(p[2]), 255]
+            })
+            .collect(),
+    };
+    Ok(StillImage {
+        width: info.width as usize,
+        height: info.height as usize,
+        rgba,
+    })
+}
+
+#[cfg(feature = "exr")]
+fn load_exr(path: &Path) -> Result<StillImage, String> {
+    fn srgb8(linear: f32) -> u8 {
+        let c = if linear.is_nan() { 0.0 } else { linear.clamp(0.0, 1.0) };
+        let encoded = if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+        (encoded * 255.0).round() as u8
+    }
+    let image = exr::prelude::read_first_rgba_layer_from_file(
+        path,
+        |resolution, _| StillImage {
+            width: resolution.width(),
+            height: resolution.height(),
+            rgba: vec![0; resolution.width() * resolution.height() * 4],
+        },
+        |image: &mut StillImage, position, (r, g, b, a): (f32, f32, f32, f32)| {
+            let offset = (position.y() * image.width + position.x()) * 4;
+            let alpha = (a.clamp(0.0, 1.0) * 255.0).round() as u8;
+            image.rgba[offset..offset + 4].copy_from_slice(&[srgb8(r), srgb8(g), srgb8(b), alpha]);
+        },
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(image.layer_data.channel_data.pixels)
+}
+
+/// The numbered sequence `path` belongs to: every file in its directory with the same name apart from
+/// the last run of digits (e.g. `shot_0001.exr`, `shot_0002.exr`, ...), in numeric order. If `path` is a
+/// directory, all files in it that `load_image` can decode, by name. Just `path` if its name has no
+/// digits; empty if its directory can't be read.
+pub fn image_sequence(path: &Path) -> Vec<PathBuf> {
+    if path.is_dir() {
+        let mut files: Vec<PathBuf> = read_dir_files(path)
+            .filter(|p| extension(p).is_some_and(|e| supported_image_extensions().contains(&e.as_str())))
+            .collect();
+        files.sort();
+        return files;
+    }
+    let Some((prefix, _, suffix)) = path.file_name().and_then(|n| n.to_str()).and_then(split_frame_number) else {
+        return vec![path.to_owned()];
+    };
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut frames: Vec<(u64, PathBuf)> = read_dir_files(dir)
+        .filter_map(|p| {
+            let (p_prefix, number, p_suffix) = split_frame_number(p.file_name()?.to_str()?)?;
+            if (p_prefix, p_suffix) != (prefix, suffix) {
+                return None;
+            }
+            let number: u64 = number.parse().ok()?;
+            Some((number, p))
+        })
+        .collect();
+    frames.sort();
+    frames.into_iter().map(|(_, p)| p).collect()
+}
+
+fn read_dir_files(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|p| p.is_file())
+}
+
+/// Split a file name around its last run of ASCII digits: `("shot_", "0042", ".exr")`.
+fn split_frame_number(name: &str) -> Option<(&str, &str, &str)> {
+    let end = name.rfind(|c: char| c.is_ascii_digit())? + 1;
+    let start = name[..end].rfind(|c: char| !c.is_ascii_digit()).map_or(0, |i| i + 1);
+    Some((&name[..start], &name[start..end], &name[end..]))
+}
+
+/// Rate and end-of-sequence behavior of an `ImagePublisher`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImagePublisherOptions {
+    /// Frames published per second (positive and finite). A single still is republished at this rate so
+    /// clients that attach later get it straight away.
+    pub fps: f64,
+    /// Start over after the last frame; otherwise keep publishing the last frame.
+    pub looping: bool,
+}
+
+impl Default for ImagePublisherOptions {
+    fn default() -> Self {
+        Self {
+            fps: 30.0,
+            looping: true,
+        }
+    }
+}
+
+/// Publishes image files as a Syphon server until dropped, decoding each frame when it is due.
+pub struct ImagePublisher {
+    worker: Worker,
+    description: Option<ServerDescription>,
+    frame_count: usize,
+    position: Arc<AtomicUsize>,
+    frames: Arc<AtomicU64>,
+    last_error: Arc<Mutex<Option<SyphonError>>>,
+}
+
+impl ImagePublisher {
+    /// Publish `paths` in order (one path for a still) as the server `name`.
+    pub fn new(name: Option<&str>, paths: Vec<PathBuf>, options: &ImagePublisherOptions) -> Option<Self> {
+        Self::try_new(name, paths, options).ok()
+    }
+
+    /// Like `new`, reporting no paths or a non-positive rate as `InvalidArgument`, and a first frame that
+    /// can't be decoded or a server that can't be created as their errors.
+    pub fn try_new(name: Option<&str>, paths: Vec<PathBuf>, options: &ImagePublisherOptions) -> Result<Self, SyphonError> {
+        if paths.is_empty() {
+            return Err(SyphonError::InvalidArgument("no images to publish"));
+        }
+        let Some(interval) = frame_interval(options.fps) else {
+            return Err(SyphonError::InvalidArgument("image publishing rate must be positive and finite"));
+        };
+        let first = load_image(&paths[0])?;
+        let options = *options;
+        let name = name.map(str::to_owned);
+        let frame_count = paths.len();
+        let position = Arc::new(AtomicUsize::new(0));
+        let frames = Arc::new(AtomicU64::new(0));
+        let last_error = Arc::new(Mutex::new(None));
+        let state = (position.clone(), frames.clone(), last_error.clone());
+        let (worker, description) = Worker::spawn("syphon-image-publisher", move |ready, stop| {
+            let server = match CpuServer::try_new(name.as_deref(), None) {
+                Ok(server) => server,
+                Err(e) => {
+                    let _ = ready.send(Err(e));
+                    return;
+                }
+            };
+            let _ = ready.send(Ok(server.server_description()));
+            let (position, frames, last_error) = state;
+            let sequence = Sequence {
+                paths,
+                options,
+                interval,
+                position,
+                frames,
+                last_error,
+            };
+            sequence.run(&server, first, stop);
+        })?;
+        Ok(Self {
+            worker,
+            description,
+            frame_count,
+            position,
+            frames,
+            last_error,
+        })
+    }
+
+    /// Number of images being published.
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /// Index of the image published last.
+    pub fn position(&self) -> usize {
+        self.position.load(Ordering::Relaxed)
+    }
+
+    /// Frames published so far.
+    pub fn frames_published(&self) -> u64 {
+        self.frames.load(Ordering::Relaxed)
+    }
+
+    /// Why the most recent image that failed to decode was skipped (the previous frame is published in
+    /// its place), if any did.
+    pub fn last_error(&self) -> Option<SyphonError> {
+        self.last_error.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// The description Syphon clients connect with.
+    pub fn server_description(&self) -> Option<ServerDescription> {
+        self.description.clone()
+    }
+
+    /// False if the publishing thread has stopped.
+    pub fn is_running(&self) -> bool {
+        self.worker.is_running()
+    }
+}
+
+/// The worker's side of an `ImagePublisher`.
+struct Sequence {
+    paths: Vec<PathBuf>,
+    options: ImagePublisherOptions,
+    /// Time between frames, from `options.fps`.
+    interval: Duration,
+    position: Arc<AtomicUsize>,
+    frames: Arc<AtomicU64>,
+    last_error: Arc<Mutex<Option<SyphonError>>>,
+}
+
+impl Sequence {
+    /// Publish on a fixed schedule until `stop` is set, starting with the already decoded `image` of
+    /// the first path. After a stall the schedule restarts from now rather than skipping frames.
+    fn run(&self, server: &CpuServer, mut image: StillImage, stop: &AtomicBool) {
+        let interval = self.interval;
+        let mut next = Instant::now();
+        let mut index = 0;
+        let mut loaded = 0;
+        while !stop.load(Ordering::Relaxed) {
+            let now = Instant::now();
+            if now < next {
+                std::thread::sleep((next - now).min(POLL_INTERVAL));
+                continue;
+            }
+            if index != loaded {
+                match load_image(&self.paths[index]) {
+                    Ok(decoded) => image = decoded,
+                    Err(e) => *self.last_error.lock().unwrap_or_else(|e| e.into_inner()) = Some(e),
+                }
+                loaded = index;
+            }
+            if server.publish(&image.rgba, image.width, image.height) {
+                self.frames.fetch_add(1, Ordering::Relaxed);
+            }
+            self.position.store(index, Ordering::Relaxed);
+            index = match index + 1 {
+                next if next < self.paths.len() => next,
+                _ if self.options.looping => 0,
+                _ => index,
+            };
+            next += interval;
+            if next < Instant::now() {
+                next = Instant::now();
+            }
+        }
+    }
+}
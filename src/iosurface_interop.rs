@@ -0,0 +1,21 @@
+//! Typed IOSurface access (`io-surface` feature): Syphon frames live in IOSurfaces, so a received frame's
+//! surface can be handed to CoreVideo, VideoToolbox or another GPU API without copying the pixels.
+
+use objc2_io_surface::IOSurfaceRef;
+
+use crate::{MetalTexture, OpenGLImage};
+
+impl OpenGLImage {
+    /// `io_surface_ptr` as an `objc2-io-surface` reference, borrowed for as long as the image is alive
+    /// (retain it to keep it longer).
+    pub fn io_surface(&self) -> Option<&IOSurfaceRef> {
+        self.io_surface_ptr().map(|surface| unsafe { &*(surface as *const IOSurfaceRef) })
+    }
+}
+
+impl MetalTexture {
+    /// `io_surface_ptr` as an `objc2-io-surface` reference, borrowed for as long as the texture is alive.
+    pub fn io_surface(&self) -> Option<&IOSurfaceRef> {
+        self.io_surface_ptr().map(|surface| unsafe { &*(surface as *const IOSurfaceRef) })
+    }
+}
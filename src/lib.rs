@@ -3,9 +3,142 @@
 //! Share video frames between applications: **Syphon on macOS**, **Spout on Windows**.
 //!
 //! - **macOS**: Server directory, `SyphonOptions`, OpenGL and Metal servers/clients, CGL/GL helpers.
-//! - **Windows**: `Spout` type for sender and receiver (OpenGL textures), sender list discovery.
+//! - **Windows**: `Spout` type for sender and receiver (OpenGL textures), sender list discovery, and
+//!   `Spout*Server` / `Spout*Client` types mirroring the Syphon ones (OpenGL, DirectX 11 and 12).
+//!
+//! The helpers built on top (CPU frames, directory events, recording, relays, test patterns, ...) are
+//! described in their module docs, the README and BINDINGS.md; several are behind Cargo features.
 
+mod backend;
+mod blend;
+#[cfg(feature = "camera")]
+mod camera_capture;
+mod capture;
+mod color;
+#[cfg(all(target_os = "macos", feature = "core-image"))]
+mod coreimage_interop;
+mod cpu;
+mod description_info;
+mod directory_events;
+mod directory_snapshot;
+#[cfg(feature = "egui")]
+mod egui_view;
+#[cfg(feature = "encoder")]
+mod encoder;
+mod error;
 mod ffi;
+mod frame;
+mod frame_size;
+#[cfg(all(target_os = "macos", feature = "glow"))]
+mod glow_interop;
+#[cfg(feature = "hotkey")]
+mod hotkey;
+mod image_publisher;
+#[cfg(all(target_os = "macos", feature = "glutin"))]
+mod glutin_interop;
+#[cfg(all(target_os = "macos", feature = "gstreamer"))]
+mod gst_elements;
+#[cfg(all(target_os = "macos", feature = "io-surface"))]
+mod iosurface_interop;
+#[cfg(all(target_os = "linux", feature = "linux-shm"))]
+mod linux_share;
+#[cfg(all(target_os = "macos", feature = "metal"))]
+mod metal_interop;
+#[cfg(all(target_os = "macos", feature = "ndi"))]
+mod ndi;
+#[cfg(all(target_os = "macos", feature = "objc2-metal"))]
+mod objc2_interop;
+mod overlay;
+#[cfg(feature = "pasteboard")]
+mod pasteboard;
+mod pixel_buffer;
+mod readback;
+mod receiver_set;
+mod reconnect;
+#[cfg(feature = "recorder")]
+mod recorder;
+#[cfg(feature = "relay")]
+mod relay;
+mod resolve;
 mod safe;
+#[cfg(feature = "screen-capture")]
+mod screen_capture;
+#[cfg(all(target_os = "macos", feature = "sdl2"))]
+mod sdl2_interop;
+mod shm_export;
+mod spout;
+#[cfg(feature = "futures")]
+mod stream;
+mod texture_cache;
+mod texture_pool;
+mod test_pattern;
+mod texture_share;
+#[cfg(all(target_os = "macos", feature = "viewer"))]
+mod viewer;
+#[cfg(all(target_os = "macos", feature = "vulkan"))]
+mod vulkan_interop;
+#[cfg(all(target_os = "macos", feature = "wgpu"))]
+mod wgpu_interop;
+#[cfg(all(target_os = "windows", feature = "windows"))]
+mod windows_interop;
+mod worker;
 
+pub use backend::*;
+pub use blend::*;
+#[cfg(feature = "camera")]
+pub use camera_capture::*;
+pub use capture::*;
+pub use color::*;
+pub use cpu::*;
+pub use description_info::*;
+pub use directory_events::*;
+pub use directory_snapshot::*;
+#[cfg(feature = "egui")]
+pub use egui_view::SyphonView;
+#[cfg(feature = "encoder")]
+pub use encoder::*;
+pub use error::*;
+pub use frame::*;
+pub use frame_size::*;
+#[cfg(all(target_os = "macos", feature = "glow"))]
+pub use glow_interop::GlowTexture;
+#[cfg(all(target_os = "macos", feature = "glutin"))]
+pub use glutin_interop::cgl_context_from_glutin;
+#[cfg(all(target_os = "macos", feature = "gstreamer"))]
+pub use gst_elements::{register_gstreamer_elements, SyphonClientSrc, SyphonServerSink};
+#[cfg(feature = "hotkey")]
+pub use hotkey::*;
+pub use image_publisher::*;
+#[cfg(all(target_os = "macos", feature = "ndi"))]
+pub use ndi::{ndi_sources, NdiSource, NdiToSyphon, SyphonToNdi};
+pub use overlay::*;
+#[cfg(feature = "pasteboard")]
+pub use pasteboard::*;
+pub use pixel_buffer::*;
+pub use readback::*;
+pub use receiver_set::*;
+pub use reconnect::*;
+#[cfg(feature = "recorder")]
+pub use recorder::*;
+#[cfg(feature = "relay")]
+pub use relay::{RelayCompression, RelayReceiver, RelayReceiverOptions, RelaySender, RelayTransport};
+pub use resolve::*;
 pub use safe::*;
+#[cfg(all(target_os = "macos", feature = "sdl2"))]
+pub use sdl2_interop::{cgl_context_from_sdl2, Sdl2WindowPublisher};
+#[cfg(feature = "screen-capture")]
+pub use screen_capture::*;
+pub use shm_export::*;
+pub use spout::*;
+#[cfg(feature = "futures")]
+pub use stream::FrameStream;
+pub use texture_cache::*;
+pub use texture_pool::*;
+pub use test_pattern::*;
+pub use texture_share::*;
+#[cfg(all(target_os = "macos", feature = "viewer"))]
+pub use viewer::Viewer;
+#[cfg(all(target_os = "macos", feature = "vulkan"))]
+pub use vulkan_interop::{VulkanImage, VulkanInterop};
+#[cfg(all(target_os = "macos", feature = "wgpu"))]
+pub use wgpu_interop::wgpu_texture_from_io_surface;
@@ -0,0 +1,373 @@
+//! Linux backend of the texture-share facade (`linux-shm` feature): a server is a `SharedFrameExporter`
+//! region named `/texture-share.<name>`, so clients in any process find it by listing `/dev/shm`.
+//! Textures go through the CPU: the server reads each frame back with `glGetTexImage` and clients upload
+//! it with `glTexSubImage2D`, using the system libGL loaded at run time (GLX or EGL contexts alike).
+
+use std::cell::{Cell, RefCell};
+use std::ffi::c_void;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::{CpuFrame, ServerDescriptionInfo, SharedFrameExporter, SharedFrameReader, SyphonError, SHM_FLAG_BOTTOM_UP};
+
+/// Prefix of the region names servers publish under (after the leading `/`).
+const REGION_PREFIX: &str = "texture-share.";
+/// How long a client's region may go without a new frame before it checks whether the server replaced
+/// it (`shm_open` + `fstat`); while frames keep coming it doesn't check at all.
+const LIVENESS_INTERVAL: Duration = Duration::from_millis(250);
+
+const GL_VERSION: u32 = 0x1F02;
+const GL_RGBA: u32 = 0x1908;
+const GL_UNSIGNED_BYTE: u32 = 0x1401;
+const GL_TEXTURE_WIDTH: u32 = 0x1000;
+const GL_TEXTURE_HEIGHT: u32 = 0x1001;
+const GL_TEXTURE_BINDING_2D: u32 = 0x8069;
+const GL_TEXTURE_RECTANGLE: u32 = 0x84F5;
+const GL_TEXTURE_BINDING_RECTANGLE: u32 = 0x84F6;
+const GL_PACK_ALIGNMENT: u32 = 0x0D05;
+const GL_UNPACK_ALIGNMENT: u32 = 0x0CF5;
+
+/// The GL entry points the backend uses, resolved from the system libGL.
+struct Gl {
+    _lib: libloading::Library,
+    get_string: unsafe extern "C" fn(u32) -> *const u8,
+    get_integerv: unsafe extern "C" fn(u32, *mut i32),
+    pixel_storei: unsafe extern "C" fn(u32, i32),
+    bind_texture: unsafe extern "C" fn(u32, u32),
+    get_tex_level_parameteriv: unsafe extern "C" fn(u32, i32, u32, *mut i32),
+    get_tex_image: unsafe extern "C" fn(u32, i32, u32, u32, *mut c_void),
+    tex_sub_image_2d: unsafe extern "C" fn(u32, i32, i32, i32, i32, i32, u32, u32, *const c_void),
+}
+
+impl Gl {
+    /// The loaded library, checked to have a context current on this thread.
+    fn current() -> Result<&'static Self, SyphonError> {
+        static GL: OnceLock<Result<Gl, String>> = OnceLock::new();
+        let gl = GL.get_or_init(Self::load).as_ref().map_err(|reason| SyphonError::CreationFailed {
+            object: "OpenGL",
+            reason: reason.clone(),
+        })?;
+        // Without a current context (GLX or EGL), GL calls are no-ops and glGetString returns NULL.
+        if unsafe { (gl.get_string)(GL_VERSION) }.is_null() {
+            return Err(SyphonError::NoCurrentContext {
+                expected: None,
+                actual: None,
+            });
+        }
+        Ok(gl)
+    }
+
+    fn load() -> Result<Self, String> {
+        let mut error = "libGL not found".to_string();
+        for name in ["libGL.so.1", "libOpenGL.so.0"] {
+            match unsafe { libloading::Library::new(name) } {
+                Ok(lib) => return Self::bind(lib),
+                Err(e) => error = format!("{name}: {e}"),
+            }
+        }
+        Err(error)
+    }
+
+    fn bind(lib: libloading::Library) -> Result<Self, String> {
+        unsafe fn symbol<T: Copy>(lib: &libloading::Library, name: &[u8]) -> Result<T, String> {
+            lib.get::<T>(name).map(|s| *s).map_err(|e| e.to_string())
+        }
+        unsafe {
+            Ok(Self {
+                get_string: symbol(&lib, b"glGetString\0")?,
+                get_integerv: symbol(&lib, b"glGetIntegerv\0")?,
+                pixel_storei: symbol(&lib, b"glPixelStorei\0")?,
+                bind_texture: symbol(&lib, b"glBindTexture\0")?,
+                get_tex_level_parameteriv: symbol(&lib, b"glGetTexLevelParameteriv\0")?,
+                get_tex_image: symbol(&lib, b"glGetTexImage\0")?,
+                tex_sub_image_2d: symbol(&lib, b"glTexSubImage2D\0")?,
+                _lib: lib,
+            })
+        }
+    }
+
+    /// Run `f` with `tex_id` bound to `target`, restoring the previous binding.
+    fn with_texture<T>(&self, tex_id: u32, target: u32, f: impl FnOnce() -> T) -> T {
+        let binding = match target {
+            GL_TEXTURE_RECTANGLE => GL_TEXTURE_BINDING_RECTANGLE,
+            _ => GL_TEXTURE_BINDING_2D,
+        };
+        let mut previous = 0;
+        unsafe {
+            (self.get_integerv)(binding, &mut previous);
+            (self.bind_texture)(target, tex_id);
+        }
+        let result = f();
+        unsafe { (self.bind_texture)(target, previous as u32) };
+        result
+    }
+
+    /// Size of level 0 of the texture bound to `target`.
+    fn bound_size(&self, target: u32) -> (usize, usize) {
+        let (mut width, mut height) = (0, 0);
+        unsafe {
+            (self.get_tex_level_parameteriv)(target, 0, GL_TEXTURE_WIDTH, &mut width);
+            (self.get_tex_level_parameteriv)(target, 0, GL_TEXTURE_HEIGHT, &mut height);
+        }
+        (width.max(0) as usize, height.max(0) as usize)
+    }
+}
+
+/// Region name for a server called `name`, or `InvalidArgument` if it can't name a region.
+fn region_for(name: &str) -> Result<String, SyphonError> {
+    if name.is_empty() || name.contains(['/', '\0']) {
+        return Err(SyphonError::InvalidArgument("texture share names must be non-empty, without '/' or NUL"));
+    }
+    Ok(format!("/{REGION_PREFIX}{name}"))
+}
+
+/// Name of a server created without one: the executable's name, as Syphon and Spout default to.
+fn default_name() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.file_stem()?.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "texture-share".into())
+}
+
+/// Servers currently publishing, found by listing `/dev/shm`.
+pub(crate) fn sources() -> Vec<ServerDescriptionInfo> {
+    let Ok(entries) = std::fs::read_dir("/dev/shm") else {
+        return Vec::new();
+    };
+    let mut sources: Vec<_> = entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter_map(|file| {
+            let name = file.strip_prefix(REGION_PREFIX)?.to_owned();
+            SharedFrameReader::open(&file)?;
+            Some(ServerDescriptionInfo {
+                uuid: Some(format!("/{file}")),
+                name: Some(name),
+                ..Default::default()
+            })
+        })
+        .collect();
+    sources.sort_by(|a, b| a.name.cmp(&b.name));
+    sources
+}
+
+/// A texture-share server backed by a shared-memory region, grown as frames get larger.
+pub(crate) struct ShmServer {
+    name: RefCell<String>,
+    exporter: RefCell<Option<SharedFrameExporter>>,
+}
+
+impl ShmServer {
+    pub(crate) fn try_new(name: Option<&str>) -> Result<Self, SyphonError> {
+        let name = name.map_or_else(default_name, str::to_owned);
+        let exporter = Self::export(&name, 0)?;
+        Ok(Self {
+            name: RefCell::new(name),
+            exporter: RefCell::new(Some(exporter)),
+        })
+    }
+
+    /// A region for `name` holding up to `capacity` bytes of pixels.
+    fn export(name: &str, capacity: usize) -> Result<SharedFrameExporter, SyphonError> {
+        let region = region_for(name)?;
+        SharedFrameExporter::new(&region, capacity / 4, 1).ok_or_else(|| SyphonError::CreationFailed {
+            object: "texture share server",
+            reason: format!("could not create shared memory region {region}"),
+        })
+    }
+
+    pub(crate) fn name(&self) -> Option<String> {
+        Some(self.name.borrow().clone())
+    }
+
+    pub(crate) fn set_name(&self, name: Option<&str>) {
+        let _ = self.try_set_name(name);
+    }
+
+    pub(crate) fn try_set_name(&self, name: Option<&str>) -> Result<(), SyphonError> {
+        let name = name.map_or_else(default_name, str::to_owned);
+        let capacity = self.exporter.borrow().as_ref().map_or(0, |e| e.capacity());
+        // Drop the old region first: it unlinks its name, which may be the one being re-created.
+        self.exporter.borrow_mut().take();
+        *self.exporter.borrow_mut() = Some(Self::export(&name, capacity)?);
+        *self.name.borrow_mut() = name;
+        Ok(())
+    }
+
+    pub(crate) fn publish_whole_texture(&self, tex_id: u32, target: u32, width: f64, height: f64, flipped: bool) {
+        let _ = self.try_publish_whole_texture(tex_id, target, width, height, flipped);
+    }
+
+    pub(crate) fn try_publish_whole_texture(
+        &self,
+        tex_id: u32,
+        target: u32,
+        width: f64,
+        height: f64,
+        flipped: bool,
+    ) -> Result<(), SyphonError> {
+        if width < 1.0 || height < 1.0 {
+            return Err(SyphonError::InvalidArgument("texture size must not be zero"));
+        }
+        let gl = Gl::current()?;
+        let (width, height) = (width as usize, height as usize);
+        let bytes = width
+            .checked_mul(height)
+            .and_then(|n| n.checked_mul(4))
+            .ok_or(SyphonError::InvalidArgument("texture size is too large"))?;
+        let mut exporter = self.exporter.borrow_mut();
+        if exporter.as_ref().is_none_or(|e| e.capacity() < bytes) {
+            // Readers see the old region go away and reopen the name.
+            exporter.take();
+            *exporter = Some(Self::export(&self.name.borrow(), bytes)?);
+        }
+        let exporter = exporter.as_mut().ok_or(SyphonError::Unsupported)?;
+        let flags = if flipped { 0 } else { SHM_FLAG_BOTTOM_UP };
+        gl.with_texture(tex_id, target, || {
+            let (tex_width, tex_height) = gl.bound_size(target);
+            if tex_width < width || tex_height < height {
+                return Err(SyphonError::InvalidArgument("texture is smaller than the published size"));
+            }
+            let mut whole = Vec::new();
+            let written = exporter.write_frame(width, height, flags, |pixels| unsafe {
+                (gl.pixel_storei)(GL_PACK_ALIGNMENT, 4);
+                if (tex_width, tex_height) == (width, height) {
+                    (gl.get_tex_image)(target, 0, GL_RGBA, GL_UNSIGNED_BYTE, pixels.as_mut_ptr().cast());
+                } else {
+                    // glGetTexImage reads the whole level: read it, then keep the lower-left corner.
+                    whole.resize(tex_width * tex_height * 4, 0);
+                    (gl.get_tex_image)(target, 0, GL_RGBA, GL_UNSIGNED_BYTE, whole.as_mut_ptr().cast());
+                    for (row, out) in pixels.chunks_exact_mut(width * 4).enumerate() {
+                        let start = row * tex_width * 4;
+                        out.copy_from_slice(&whole[start..start + width * 4]);
+                    }
+                }
+            });
+            if !written {
+                return Err(SyphonError::InvalidArgument("texture size does not fit the shared-memory header"));
+            }
+            Ok(())
+        })
+    }
+
+    pub(crate) fn source(&self) -> Option<ServerDescriptionInfo> {
+        let exporter = self.exporter.borrow();
+        Some(ServerDescriptionInfo {
+            uuid: Some(exporter.as_ref()?.name().to_owned()),
+            name: Some(self.name.borrow().clone()),
+            ..Default::default()
+        })
+    }
+
+    pub(crate) fn stop(&self) {
+        self.exporter.borrow_mut().take();
+    }
+}
+
+/// A texture-share client reading a server's region, reopened when the server replaces it.
+pub(crate) struct ShmClient {
+    region: String,
+    reader: RefCell<Option<SharedFrameReader>>,
+    /// Frames written to the region when last seen to change, and when that was.
+    progress: Cell<(u64, Instant)>,
+}
+
+impl ShmClient {
+    /// Follow the region named by `source.uuid`, else the one for `source.name`.
+    pub(crate) fn try_new(source: &ServerDescriptionInfo) -> Result<Self, SyphonError> {
+        let region = match (&source.uuid, &source.name) {
+            (Some(uuid), _) if uuid.starts_with(&format!("/{REGION_PREFIX}")) => uuid.clone(),
+            (_, Some(name)) => region_for(name)?,
+            _ => return Err(SyphonError::InvalidArgument("source has no texture share name")),
+        };
+        let reader = SharedFrameReader::open(&region).ok_or_else(|| SyphonError::CreationFailed {
+            object: "texture share client",
+            reason: "the source is not running".into(),
+        })?;
+        let progress = Cell::new((reader.frames_written(), Instant::now()));
+        Ok(Self {
+            region,
+            reader: RefCell::new(Some(reader)),
+            progress,
+        })
+    }
+
+    /// The current reader, reopened if the server replaced or re-created its region.
+    fn with_reader<T>(&self, f: impl FnOnce(&SharedFrameReader) -> Option<T>) -> Option<T> {
+        let mut reader = self.reader.borrow_mut();
+        if reader.as_ref().is_none_or(|r| self.stalled(r) && !r.is_live()) {
+            *reader = SharedFrameReader::open(&self.region);
+            if let Some(r) = reader.as_ref() {
+                self.progress.set((r.frames_written(), Instant::now()));
+            }
+        }
+        f(reader.as_ref()?)
+    }
+
+    /// True if `reader` has had no new frame for `LIVENESS_INTERVAL` since the last check.
+    fn stalled(&self, reader: &SharedFrameReader) -> bool {
+        let frames = reader.frames_written();
+        let (last, since) = self.progress.get();
+        if frames == last && since.elapsed() < LIVENESS_INTERVAL {
+            return false;
+        }
+        self.progress.set((frames, Instant::now()));
+        frames == last
+    }
+
+    pub(crate) fn server_name(&self) -> Option<String> {
+        self.region.strip_prefix(&format!("/{REGION_PREFIX}")).map(str::to_owned)
+    }
+
+    /// Unlike the frame accessors, always checks that the region is still the server's.
+    pub(crate) fn is_valid(&self) -> bool {
+        let mut reader = self.reader.borrow_mut();
+        if reader.as_ref().is_some_and(|r| !r.is_live()) {
+            *reader = None;
+        }
+        drop(reader);
+        self.with_reader(|_| Some(())).is_some()
+    }
+
+    pub(crate) fn frame_size(&self) -> Option<(usize, usize)> {
+        self.with_reader(SharedFrameReader::frame_size)
+    }
+
+    pub(crate) fn copy_frame_to(&self, tex_id: u32, target: u32) -> bool {
+        let Ok(gl) = Gl::current() else {
+            return false;
+        };
+        // The header comes from another process; `read_rows` rejects empty or oversized frames.
+        let Some((width, height, rgba)) = self.with_reader(|r| r.read_rows(true)) else {
+            return false;
+        };
+        gl.with_texture(tex_id, target, || {
+            let (tex_width, tex_height) = gl.bound_size(target);
+            if tex_width < width || tex_height < height {
+                return false;
+            }
+            unsafe {
+                (gl.pixel_storei)(GL_UNPACK_ALIGNMENT, 4);
+                (gl.tex_sub_image_2d)(
+                    target,
+                    0,
+                    0,
+                    0,
+                    width as i32,
+                    height as i32,
+                    GL_RGBA,
+                    GL_UNSIGNED_BYTE,
+                    rgba.as_ptr().cast(),
+                );
+            }
+            true
+        })
+    }
+
+    pub(crate) fn latest_frame(&self) -> Option<CpuFrame> {
+        self.with_reader(SharedFrameReader::read_frame)
+    }
+
+    pub(crate) fn stop(&self) {
+        self.reader.borrow_mut().take();
+    }
+}
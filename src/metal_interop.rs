@@ -0,0 +1,105 @@
+//! Typed `metal` crate integration (`metal` feature): `MetalServer` / `MetalClient` take
+//! `&metal::DeviceRef`, `&metal::TextureRef` and `&metal::CommandBufferRef`, and frames come back as
+//! owned `metal::Texture`s, so no raw pointers or `ForeignType::from_ptr` are needed.
+
+use metal::foreign_types::{ForeignType, ForeignTypeRef};
+use metal::{CommandBufferRef, CommandQueueRef, DeviceRef, MTLTexture, TextureRef};
+
+use crate::{
+    MetalClient, MetalServer, MetalTexture, NewFrameCallback, Rect, ServerDescription, SyphonError, SyphonOptions,
+};
+
+impl MetalServer {
+    /// `new` with a `metal` device.
+    pub fn with_device(name: Option<&str>, device: &DeviceRef, options: Option<&SyphonOptions>) -> Option<Self> {
+        Self::new(name, device.as_ptr() as *mut _, options)
+    }
+
+    /// Like `with_device`, reporting why creation failed.
+    pub fn try_with_device(
+        name: Option<&str>,
+        device: &DeviceRef,
+        options: Option<&SyphonOptions>,
+    ) -> Result<Self, SyphonError> {
+        Self::try_new(name, device.as_ptr() as *mut _, options)
+    }
+
+    /// The server's device as a `metal::Device`.
+    pub fn metal_device(&self) -> Option<metal::Device> {
+        let ptr = self.device();
+        (!ptr.is_null()).then(|| unsafe { DeviceRef::from_ptr(ptr as *mut _) }.to_owned())
+    }
+
+    /// `publish` with `metal` types. You must commit `command_buffer`.
+    pub fn publish_texture(&self, texture: &TextureRef, command_buffer: &CommandBufferRef, region: Rect, flipped: bool) {
+        self.publish(texture.as_ptr() as *mut _, command_buffer.as_ptr() as *mut _, region, flipped);
+    }
+
+    /// `publish_frame_simple` with a `metal` texture.
+    pub fn publish_texture_simple(&self, texture: &TextureRef, region: Rect, flipped: bool) -> bool {
+        self.publish_frame_simple(texture.as_ptr() as *mut _, region, flipped)
+    }
+
+    /// `new_with_command_queue` with `metal` types.
+    pub fn with_command_queue(
+        name: Option<&str>,
+        queue: &CommandQueueRef,
+        options: Option<&SyphonOptions>,
+    ) -> Option<Self> {
+        let device = queue.device();
+        Self::new_with_command_queue(name, device.as_ptr() as *mut _, options, Some(queue.as_ptr() as *mut _))
+    }
+
+    /// `new_frame_image` as an owned `metal::Texture`.
+    pub fn new_frame_texture(&self) -> Option<metal::Texture> {
+        self.new_frame_image().map(metal::Texture::from)
+    }
+}
+
+impl MetalClient {
+    /// `new` with a `metal` device.
+    pub fn with_device(
+        description: &ServerDescription,
+        device: &DeviceRef,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+    ) -> Option<Self> {
+        Self::new(description, device.as_ptr() as *mut _, options, callback)
+    }
+
+    /// Like `with_device`, reporting why creation failed.
+    pub fn try_with_device(
+        description: &ServerDescription,
+        device: &DeviceRef,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+    ) -> Result<Self, SyphonError> {
+        Self::try_new(description, device.as_ptr() as *mut _, options, callback)
+    }
+
+    /// `copy_frame_to` with `metal` types. You must commit `command_buffer`.
+    pub fn copy_frame_to_texture(&self, texture: &TextureRef, command_buffer: &CommandBufferRef) -> bool {
+        self.copy_frame_to(texture.as_ptr() as *mut _, command_buffer.as_ptr() as *mut _)
+    }
+
+    /// `new_frame_image` as an owned `metal::Texture`.
+    pub fn new_frame_texture(&self) -> Option<metal::Texture> {
+        self.new_frame_image().map(metal::Texture::from)
+    }
+}
+
+impl MetalTexture {
+    /// Borrow as a `metal::TextureRef`.
+    pub fn as_texture_ref(&self) -> &TextureRef {
+        unsafe { TextureRef::from_ptr(self.as_ptr() as *mut MTLTexture) }
+    }
+}
+
+impl From<MetalTexture> for metal::Texture {
+    /// Hands the texture's reference over to `metal::Texture`, which releases it on drop.
+    fn from(texture: MetalTexture) -> Self {
+        let ptr = texture.as_ptr() as *mut MTLTexture;
+        std::mem::forget(texture);
+        unsafe { metal::Texture::from_ptr(ptr) }
+    }
+}
@@ -0,0 +1,416 @@
+//! NDI bridges (`ndi` feature): `SyphonToNdi` sends a Syphon server's frames out as an NDI source and
+//! `NdiToSyphon` publishes an NDI source as a Syphon server, each pumping frames on its own thread and
+//! converting pixel formats on the way (RGBA8 on the Syphon side). The NDI runtime (`libndi.dylib`, from
+//! the NDI SDK or NDI Tools) is loaded on first use, from `NDI_RUNTIME_DIR_V6` / `NDI_RUNTIME_DIR_V5` or
+//! the standard install locations, so apps build and run without it until a bridge is created.
+
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::worker::{Worker, POLL_INTERVAL};
+use crate::{CpuClient, CpuServer, NewFrameCallback, ServerDescription, SyphonError};
+
+const fn fourcc(code: &[u8; 4]) -> u32 {
+    u32::from_le_bytes(*code)
+}
+
+const FOURCC_RGBA: u32 = fourcc(b"RGBA");
+const FOURCC_RGBX: u32 = fourcc(b"RGBX");
+const FOURCC_BGRA: u32 = fourcc(b"BGRA");
+const FOURCC_BGRX: u32 = fourcc(b"BGRX");
+const FRAME_FORMAT_PROGRESSIVE: i32 = 1;
+const FRAME_TYPE_VIDEO: i32 = 1;
+const RECV_COLOR_FORMAT_RGBX_RGBA: i32 = 2;
+const RECV_BANDWIDTH_HIGHEST: i32 = 100;
+const TIMECODE_SYNTHESIZE: i64 = i64::MAX;
+
+/// `NDIlib_video_frame_v2_t`.
+#[repr(C)]
+struct VideoFrame {
+    xres: i32,
+    yres: i32,
+    fourcc: u32,
+    frame_rate_n: i32,
+    frame_rate_d: i32,
+    picture_aspect_ratio: f32,
+    frame_format_type: i32,
+    timecode: i64,
+    data: *mut u8,
+    line_stride_in_bytes: i32,
+    metadata: *const c_char,
+    timestamp: i64,
+}
+
+impl VideoFrame {
+    fn empty() -> Self {
+        Self {
+            xres: 0,
+            yres: 0,
+            fourcc: 0,
+            frame_rate_n: 0,
+            frame_rate_d: 0,
+            picture_aspect_ratio: 0.0,
+            frame_format_type: FRAME_FORMAT_PROGRESSIVE,
+            timecode: TIMECODE_SYNTHESIZE,
+            data: std::ptr::null_mut(),
+            line_stride_in_bytes: 0,
+            metadata: std::ptr::null(),
+            timestamp: 0,
+        }
+    }
+}
+
+/// `NDIlib_send_create_t`.
+#[repr(C)]
+struct SendCreate {
+    name: *const c_char,
+    groups: *const c_char,
+    clock_video: bool,
+    clock_audio: bool,
+}
+
+/// `NDIlib_source_t`.
+#[repr(C)]
+struct Source {
+    name: *const c_char,
+    url_address: *const c_char,
+}
+
+/// `NDIlib_find_create_t`.
+#[repr(C)]
+struct FindCreate {
+    show_local_sources: bool,
+    groups: *const c_char,
+    extra_ips: *const c_char,
+}
+
+/// `NDIlib_recv_create_v3_t`.
+#[repr(C)]
+struct RecvCreate {
+    source: Source,
+    color_format: i32,
+    bandwidth: i32,
+    allow_video_fields: bool,
+    name: *const c_char,
+}
+
+/// The NDI entry points the bridges use, resolved from the runtime library.
+struct NdiLib {
+    _lib: libloading::Library,
+    send_create: unsafe extern "C" fn(*const SendCreate) -> *mut c_void,
+    send_destroy: unsafe extern "C" fn(*mut c_void),
+    send_video: unsafe extern "C" fn(*mut c_void, *const VideoFrame),
+    find_create: unsafe extern "C" fn(*const FindCreate) -> *mut c_void,
+    find_wait_for_sources: unsafe extern "C" fn(*mut c_void, u32) -> bool,
+    find_get_current_sources: unsafe extern "C" fn(*mut c_void, *mut u32) -> *const Source,
+    find_destroy: unsafe extern "C" fn(*mut c_void),
+    recv_create: unsafe extern "C" fn(*const RecvCreate) -> *mut c_void,
+    recv_capture: unsafe extern "C" fn(*mut c_void, *mut VideoFrame, *mut c_void, *mut c_void, u32) -> i32,
+    recv_free_video: unsafe extern "C" fn(*mut c_void, *const VideoFrame),
+    recv_destroy: unsafe extern "C" fn(*mut c_void),
+}
+
+impl NdiLib {
+    /// The loaded and initialized runtime; `CreationFailed` if it can't be found or initialized.
+    fn get() -> Result<&'static Self, SyphonError> {
+        static LIB: OnceLock<Result<NdiLib, String>> = OnceLock::new();
+        LIB.get_or_init(Self::load).as_ref().map_err(|reason| SyphonError::CreationFailed {
+            object: "NDI runtime",
+            reason: reason.clone(),
+        })
+    }
+
+    fn load() -> Result<Self, String> {
+        let candidates = ["NDI_RUNTIME_DIR_V6", "NDI_RUNTIME_DIR_V5"]
+            .into_iter()
+            .filter_map(std::env::var_os)
+            .map(|dir| PathBuf::from(dir).join("libndi.dylib"))
+            .chain(
+                ["/usr/local/lib/libndi.dylib", "/Library/NDI SDK for Apple/lib/macOS/libndi.dylib"].map(PathBuf::from),
+            );
+        let mut error = "libndi.dylib not found".to_string();
+        for path in candidates {
+            match unsafe { libloading::Library::new(&path) } {
+                Ok(lib) => return Self::bind(lib),
+                Err(e) => error = format!("{}: {e}", path.display()),
+            }
+        }
+        Err(error)
+    }
+
+    fn bind(lib: libloading::Library) -> Result<Self, String> {
+        unsafe fn symbol<T: Copy>(lib: &libloading::Library, name: &[u8]) -> Result<T, String> {
+            lib.get::<T>(name).map(|s| *s).map_err(|e| e.to_string())
+        }
+        unsafe {
+            let initialize: unsafe extern "C" fn() -> bool = symbol(&lib, b"NDIlib_initialize\0")?;
+            if !initialize() {
+                return Err("NDIlib_initialize failed (unsupported CPU)".into());
+            }
+            Ok(Self {
+                send_create: symbol(&lib, b"NDIlib_send_create\0")?,
+                send_destroy: symbol(&lib, b"NDIlib_send_destroy\0")?,
+                send_video: symbol(&lib, b"NDIlib_send_send_video_v2\0")?,
+                find_create: symbol(&lib, b"NDIlib_find_create_v2\0")?,
+                find_wait_for_sources: symbol(&lib, b"NDIlib_find_wait_for_sources\0")?,
+                find_get_current_sources: symbol(&lib, b"NDIlib_find_get_current_sources\0")?,
+                find_destroy: symbol(&lib, b"NDIlib_find_destroy\0")?,
+                recv_create: symbol(&lib, b"NDIlib_recv_create_v3\0")?,
+                recv_capture: symbol(&lib, b"NDIlib_recv_capture_v2\0")?,
+                recv_free_video: symbol(&lib, b"NDIlib_recv_free_video_v2\0")?,
+                recv_destroy: symbol(&lib, b"NDIlib_recv_destroy\0")?,
+                _lib: lib,
+            })
+        }
+    }
+}
+
+fn failed(object: &'static str, reason: impl Into<String>) -> SyphonError {
+    SyphonError::CreationFailed {
+        object,
+        reason: reason.into(),
+    }
+}
+
+fn nul_free(text: &str, what: &'static str) -> Result<CString, SyphonError> {
+    CString::new(text).map_err(|_| SyphonError::InvalidArgument(what))
+}
+
+fn opt_cstr(ptr: *const c_char) -> Option<String> {
+    (!ptr.is_null()).then(|| unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned())
+}
+
+/// An NDI source seen on the network.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NdiSource {
+    /// Full source name, `"MACHINE (Source)"`.
+    pub name: String,
+    /// Address the source was found at, if known (connecting by name works without it).
+    pub url_address: Option<String>,
+}
+
+/// The NDI sources found on the network (local ones included) within `timeout`.
+pub fn ndi_sources(timeout: Duration) -> Result<Vec<NdiSource>, SyphonError> {
+    let lib = NdiLib::get()?;
+    let create = FindCreate {
+        show_local_sources: true,
+        groups: std::ptr::null(),
+        extra_ips: std::ptr::null(),
+    };
+    let finder = unsafe { (lib.find_create)(&create) };
+    if finder.is_null() {
+        return Err(failed("NDI finder", "NDIlib_find_create_v2 failed"));
+    }
+    unsafe { (lib.find_wait_for_sources)(finder, timeout.as_millis().min(u32::MAX as u128) as u32) };
+    let mut count = 0;
+    let sources = unsafe { (lib.find_get_current_sources)(finder, &mut count) };
+    let out = match sources.is_null() {
+        true => Vec::new(),
+        false => unsafe { std::slice::from_raw_parts(sources, count as usize) }
+            .iter()
+            .filter_map(|source| {
+                Some(NdiSource {
+                    name: opt_cstr(source.name)?,
+                    url_address: opt_cstr(source.url_address),
+                })
+            })
+            .collect(),
+    };
+    unsafe { (lib.find_destroy)(finder) };
+    Ok(out)
+}
+
+/// Sends a Syphon server's frames as an NDI source. Frames are read back as RGBA8 and sent as they arrive
+/// (unclocked, tagged 60 fps); the bridge stops when the Syphon server goes away or the bridge is dropped.
+pub struct SyphonToNdi {
+    worker: Worker,
+}
+
+impl SyphonToNdi {
+    /// Start sending the server in `description` as the NDI source `ndi_name` (shown as
+    /// `"MACHINE (ndi_name)"`).
+    pub fn new(description: &ServerDescription, ndi_name: &str) -> Option<Self> {
+        Self::try_new(description, ndi_name).ok()
+    }
+
+    /// Like `new`, reporting why the bridge couldn't start (no NDI runtime, Syphon client or NDI sender).
+    pub fn try_new(description: &ServerDescription, ndi_name: &str) -> Result<Self, SyphonError> {
+        let lib = NdiLib::get()?;
+        let name = nul_free(ndi_name, "NDI name contains a NUL byte")?;
+        let description = description.clone();
+        let (worker, ()) = Worker::spawn("syphon-to-ndi", move |ready, stop| {
+            let (frame_tx, frame_rx) = mpsc::channel();
+            let callback: NewFrameCallback = Box::new(move |_| {
+                let _ = frame_tx.send(());
+            });
+            let client = match CpuClient::try_new(&description, Some(callback)) {
+                Ok(client) => client,
+                Err(e) => {
+                    let _ = ready.send(Err(e));
+                    return;
+                }
+            };
+            let create = SendCreate {
+                name: name.as_ptr(),
+                groups: std::ptr::null(),
+                clock_video: false,
+                clock_audio: false,
+            };
+            let sender = unsafe { (lib.send_create)(&create) };
+            if sender.is_null() {
+                let _ = ready.send(Err(failed("NDI sender", "NDIlib_send_create failed")));
+                return;
+            }
+            let _ = ready.send(Ok(()));
+            while !stop.load(Ordering::Relaxed) {
+                match frame_rx.recv_timeout(POLL_INTERVAL) {
+                    Ok(()) => {}
+                    Err(RecvTimeoutError::Timeout) if client.is_valid() => continue,
+                    Err(_) => break,
+                }
+                // Send only the newest frame if several arrived during the last send.
+                while frame_rx.try_recv().is_ok() {}
+                let Some(mut frame) = client.latest_frame() else {
+                    continue;
+                };
+                let video = VideoFrame {
+                    xres: frame.width as i32,
+                    yres: frame.height as i32,
+                    fourcc: FOURCC_RGBA,
+                    frame_rate_n: 60,
+                    frame_rate_d: 1,
+                    picture_aspect_ratio: frame.width as f32 / frame.height.max(1) as f32,
+                    data: frame.rgba.as_mut_ptr(),
+                    line_stride_in_bytes: frame.width as i32 * 4,
+                    ..VideoFrame::empty()
+                };
+                // Synchronous: the SDK is done with the pixels when this returns.
+                unsafe { (lib.send_video)(sender, &video) };
+            }
+            unsafe { (lib.send_destroy)(sender) };
+        })?;
+        Ok(Self { worker })
+    }
+
+    /// False once the bridge has stopped because the Syphon server went away.
+    pub fn is_running(&self) -> bool {
+        self.worker.is_running()
+    }
+}
+
+/// Publishes an NDI source as a Syphon server. Frames are received as RGBA / RGBX (alpha made opaque) at
+/// the source's full bandwidth and published through a `CpuServer` as they arrive.
+pub struct NdiToSyphon {
+    worker: Worker,
+    description: Option<ServerDescription>,
+}
+
+impl NdiToSyphon {
+    /// Receive `source` and publish it as the Syphon server `name`.
+    pub fn new(source: &NdiSource, name: Option<&str>) -> Option<Self> {
+        Self::try_new(source, name).ok()
+    }
+
+    /// Like `new`, reporting why the bridge couldn't start (no NDI runtime, receiver or Syphon server).
+    pub fn try_new(source: &NdiSource, name: Option<&str>) -> Result<Self, SyphonError> {
+        let lib = NdiLib::get()?;
+        let source_name = nul_free(&source.name, "NDI source name contains a NUL byte")?;
+        let url_address = match &source.url_address {
+            Some(url) => Some(nul_free(url, "NDI source address contains a NUL byte")?),
+            None => None,
+        };
+        let name = name.map(str::to_owned);
+        let (worker, description) = Worker::spawn("ndi-to-syphon", move |ready, stop| {
+            let server = match CpuServer::try_new(name.as_deref(), None) {
+                Ok(server) => server,
+                Err(e) => {
+                    let _ = ready.send(Err(e));
+                    return;
+                }
+            };
+            let create = RecvCreate {
+                source: Source {
+                    name: source_name.as_ptr(),
+                    url_address: url_address.as_ref().map_or(std::ptr::null(), |u| u.as_ptr()),
+                },
+                color_format: RECV_COLOR_FORMAT_RGBX_RGBA,
+                bandwidth: RECV_BANDWIDTH_HIGHEST,
+                allow_video_fields: false,
+                name: std::ptr::null(),
+            };
+            let receiver = unsafe { (lib.recv_create)(&create) };
+            if receiver.is_null() {
+                let _ = ready.send(Err(failed("NDI receiver", "NDIlib_recv_create_v3 failed")));
+                return;
+            }
+            let _ = ready.send(Ok(server.server_description()));
+            let mut rgba = Vec::new();
+            let timeout = POLL_INTERVAL.as_millis() as u32;
+            while !stop.load(Ordering::Relaxed) {
+                let mut video = VideoFrame::empty();
+                let kind = unsafe {
+                    (lib.recv_capture)(receiver, &mut video, std::ptr::null_mut(), std::ptr::null_mut(), timeout)
+                };
+                if kind != FRAME_TYPE_VIDEO {
+                    continue;
+                }
+                let converted = unsafe { frame_to_rgba(&video, &mut rgba) };
+                unsafe { (lib.recv_free_video)(receiver, &video) };
+                if converted {
+                    server.publish(&rgba, video.xres as usize, video.yres as usize);
+                }
+            }
+            unsafe { (lib.recv_destroy)(receiver) };
+        })?;
+        Ok(Self { worker, description })
+    }
+
+    /// The description Syphon clients connect with.
+    pub fn server_description(&self) -> Option<ServerDescription> {
+        self.description.clone()
+    }
+
+    /// False if the bridge's thread has stopped.
+    pub fn is_running(&self) -> bool {
+        self.worker.is_running()
+    }
+}
+
+/// Copy a received 8-bit RGB(A/X) or BGR(A/X) frame into `out` as tightly packed RGBA8 with real alpha
+/// (X channels become opaque). False for other formats.
+///
+/// Safety: `video` must be a frame from `NDIlib_recv_capture_v2` that has not been freed.
+unsafe fn frame_to_rgba(video: &VideoFrame, out: &mut Vec<u8>) -> bool {
+    let (width, height, stride) = (video.xres as usize, video.yres as usize, video.line_stride_in_bytes as usize);
+    if video.data.is_null() || width == 0 || height == 0 || stride < width * 4 {
+        return false;
+    }
+    let (bgr, opaque) = match video.fourcc {
+        FOURCC_RGBA => (false, false),
+        FOURCC_RGBX => (false, true),
+        FOURCC_BGRA => (true, false),
+        FOURCC_BGRX => (true, true),
+        _ => return false,
+    };
+    let src = std::slice::from_raw_parts(video.data, stride * height);
+    out.clear();
+    out.reserve(width * height * 4);
+    for row in src.chunks_exact(stride) {
+        out.extend_from_slice(&row[..width * 4]);
+    }
+    if bgr || opaque {
+        for pixel in out.chunks_exact_mut(4) {
+            if bgr {
+                pixel.swap(0, 2);
+            }
+            if opaque {
+                pixel[3] = 255;
+            }
+        }
+    }
+    true
+}
@@ -0,0 +1,121 @@
+//! Typed `objc2-metal` integration (`objc2-metal` feature): `MetalServer` / `MetalClient` take
+//! `&ProtocolObject<dyn MTLDevice>` (and texture / command-buffer counterparts), and frames come back as
+//! `Retained<ProtocolObject<dyn MTLTexture>>`, so ownership is tracked by `objc2` instead of raw pointers.
+
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2_metal::{MTLCommandBuffer, MTLCommandQueue, MTLDevice, MTLTexture};
+
+use crate::{
+    MetalClient, MetalServer, MetalTexture, NewFrameCallback, Rect, ServerDescription, SyphonError, SyphonOptions,
+};
+
+fn raw<T: ?Sized>(object: &ProtocolObject<T>) -> *mut std::ffi::c_void {
+    object as *const ProtocolObject<T> as *mut std::ffi::c_void
+}
+
+impl MetalServer {
+    /// `new` with an `objc2-metal` device.
+    pub fn with_objc2_device(
+        name: Option<&str>,
+        device: &ProtocolObject<dyn MTLDevice>,
+        options: Option<&SyphonOptions>,
+    ) -> Option<Self> {
+        Self::new(name, raw(device), options)
+    }
+
+    /// Like `with_objc2_device`, reporting why creation failed.
+    pub fn try_with_objc2_device(
+        name: Option<&str>,
+        device: &ProtocolObject<dyn MTLDevice>,
+        options: Option<&SyphonOptions>,
+    ) -> Result<Self, SyphonError> {
+        Self::try_new(name, raw(device), options)
+    }
+
+    /// `new_with_command_queue` with an `objc2-metal` queue (the server uses the queue's device).
+    pub fn with_objc2_command_queue(
+        name: Option<&str>,
+        queue: &ProtocolObject<dyn MTLCommandQueue>,
+        options: Option<&SyphonOptions>,
+    ) -> Option<Self> {
+        let device = queue.device();
+        Self::new_with_command_queue(name, raw(&device), options, Some(raw(queue)))
+    }
+
+    /// The server's device, retained.
+    pub fn objc2_device(&self) -> Option<Retained<ProtocolObject<dyn MTLDevice>>> {
+        unsafe { Retained::retain(self.device() as *mut ProtocolObject<dyn MTLDevice>) }
+    }
+
+    /// `publish` with `objc2-metal` objects. You must commit `command_buffer`.
+    pub fn publish_objc2(
+        &self,
+        texture: &ProtocolObject<dyn MTLTexture>,
+        command_buffer: &ProtocolObject<dyn MTLCommandBuffer>,
+        region: Rect,
+        flipped: bool,
+    ) {
+        self.publish(raw(texture), raw(command_buffer), region, flipped);
+    }
+
+    /// `publish_frame_simple` with an `objc2-metal` texture.
+    pub fn publish_objc2_simple(&self, texture: &ProtocolObject<dyn MTLTexture>, region: Rect, flipped: bool) -> bool {
+        self.publish_frame_simple(raw(texture), region, flipped)
+    }
+
+    /// `new_frame_image` as a retained `objc2-metal` texture.
+    pub fn new_frame_objc2(&self) -> Option<Retained<ProtocolObject<dyn MTLTexture>>> {
+        self.new_frame_image().map(MetalTexture::into_retained)
+    }
+}
+
+impl MetalClient {
+    /// `new` with an `objc2-metal` device.
+    pub fn with_objc2_device(
+        description: &ServerDescription,
+        device: &ProtocolObject<dyn MTLDevice>,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+    ) -> Option<Self> {
+        Self::new(description, raw(device), options, callback)
+    }
+
+    /// Like `with_objc2_device`, reporting why creation failed.
+    pub fn try_with_objc2_device(
+        description: &ServerDescription,
+        device: &ProtocolObject<dyn MTLDevice>,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+    ) -> Result<Self, SyphonError> {
+        Self::try_new(description, raw(device), options, callback)
+    }
+
+    /// `copy_frame_to` with `objc2-metal` objects. You must commit `command_buffer`.
+    pub fn copy_frame_to_objc2(
+        &self,
+        texture: &ProtocolObject<dyn MTLTexture>,
+        command_buffer: &ProtocolObject<dyn MTLCommandBuffer>,
+    ) -> bool {
+        self.copy_frame_to(raw(texture), raw(command_buffer))
+    }
+
+    /// `new_frame_image` as a retained `objc2-metal` texture.
+    pub fn new_frame_objc2(&self) -> Option<Retained<ProtocolObject<dyn MTLTexture>>> {
+        self.new_frame_image().map(MetalTexture::into_retained)
+    }
+}
+
+impl MetalTexture {
+    /// Borrow as an `objc2-metal` texture.
+    pub fn as_protocol_object(&self) -> &ProtocolObject<dyn MTLTexture> {
+        unsafe { &*(self.as_ptr() as *const ProtocolObject<dyn MTLTexture>) }
+    }
+
+    /// Hand the texture's reference over to `objc2`.
+    pub fn into_retained(self) -> Retained<ProtocolObject<dyn MTLTexture>> {
+        let ptr = self.as_ptr() as *mut ProtocolObject<dyn MTLTexture>;
+        std::mem::forget(self);
+        unsafe { Retained::from_raw(ptr) }.expect("MetalTexture holds a non-null texture")
+    }
+}
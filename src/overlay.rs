@@ -0,0 +1,360 @@
+//! Debug overlay: burns FPS, frame sequence number, timestamp and server name into a corner of a frame.
+//!
+//! Call `stamp_gl` / `stamp_metal` once per frame right before publishing (or on your own copy of a
+//! received frame). Stats keep running while the overlay is disabled, so toggling it on shows accurate FPS.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(target_os = "macos")]
+use crate::ffi;
+use crate::{gl_write_texture_region_rgba8, MTLCommandBufferPtr, MTLTexturePtr};
+
+/// Corner of the frame the overlay is drawn into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverlayCorner {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// An RGBA8 bitmap (top row first) rendered by the overlay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlayBitmap {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+}
+
+/// Burn-in overlay for debugging sync and drop issues across applications. Thread-safe; toggle from any thread.
+pub struct DebugOverlay {
+    enabled: AtomicBool,
+    state: Mutex<OverlayState>,
+}
+
+struct OverlayState {
+    label: Option<String>,
+    corner: OverlayCorner,
+    scale: usize,
+    sequence: u64,
+    last_tick: Option<Instant>,
+    fps: f64,
+}
+
+/// Pixels between the overlay and the frame edge (before scaling).
+const MARGIN: usize = 4;
+/// Padding around the text inside the overlay box (before scaling).
+const PADDING: usize = 2;
+const GLYPH_W: usize = 5;
+const GLYPH_H: usize = 7;
+
+impl DebugOverlay {
+    /// Create an enabled overlay. `label` is typically the server name.
+    pub fn new(label: Option<&str>) -> Self {
+        Self {
+            enabled: AtomicBool::new(true),
+            state: Mutex::new(OverlayState {
+                label: label.map(str::to_owned),
+                corner: OverlayCorner::TopLeft,
+                scale: 2,
+                sequence: 0,
+                last_tick: None,
+                fps: 0.0,
+            }),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Flip the enabled state; returns the new state.
+    pub fn toggle(&self) -> bool {
+        !self.enabled.fetch_xor(true, Ordering::Relaxed)
+    }
+
+    /// Set the label line (e.g. the server name). None hides the line.
+    pub fn set_label(&self, label: Option<&str>) {
+        self.lock().label = label.map(str::to_owned);
+    }
+
+    pub fn set_corner(&self, corner: OverlayCorner) {
+        self.lock().corner = corner;
+    }
+
+    /// Integer pixel scale of the built-in 5x7 font (clamped to at least 1).
+    pub fn set_scale(&self, scale: usize) {
+        self.lock().scale = scale.max(1);
+    }
+
+    /// Number of frames stamped so far (counted even while disabled).
+    pub fn sequence(&self) -> u64 {
+        self.lock().sequence
+    }
+
+    /// Smoothed frames per second measured from stamp calls.
+    pub fn fps(&self) -> f64 {
+        self.lock().fps
+    }
+
+    /// Render the overlay for the current stats without advancing them.
+    pub fn render(&self) -> OverlayBitmap {
+        let state = self.lock();
+        render_state(&state)
+    }
+
+    /// Advance stats for one frame and, if enabled, stamp the overlay into an OpenGL texture.
+    /// `flipped` must match what you pass to `OpenGLServer::publish`. CGL context must be current.
+    /// Returns true if the overlay was drawn.
+    pub fn stamp_gl(&self, tex_id: u32, target: u32, width: usize, height: usize, flipped: bool) -> bool {
+        let Some((bitmap, x, y)) = self.tick_and_layout(width, height, !flipped) else {
+            return false;
+        };
+        gl_write_texture_region_rgba8(tex_id, target, x, y, bitmap.width, bitmap.height, &bitmap.rgba)
+    }
+
+    /// Advance stats for one frame and, if enabled, encode a blit of the overlay into `texture` on
+    /// `command_buffer` (BGRA8/RGBA8 textures). Call before `MetalServer::publish` on the same buffer.
+    /// Returns true if the overlay was encoded.
+    pub fn stamp_metal(
+        &self,
+        texture: MTLTexturePtr,
+        command_buffer: MTLCommandBufferPtr,
+        width: usize,
+        height: usize,
+        flipped: bool,
+    ) -> bool {
+        let Some((bitmap, x, y)) = self.tick_and_layout(width, height, flipped) else {
+            return false;
+        };
+        metal_write_texture_region_rgba8(texture, command_buffer, x, y, &bitmap)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, OverlayState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Count a frame; when enabled, return the bitmap (rows in texture order) and its texture origin.
+    fn tick_and_layout(
+        &self,
+        width: usize,
+        height: usize,
+        bottom_up: bool,
+    ) -> Option<(OverlayBitmap, usize, usize)> {
+        let mut state = self.lock();
+        let now = Instant::now();
+        if let Some(last) = state.last_tick {
+            let dt = now.duration_since(last).as_secs_f64();
+            if dt > 0.0 {
+                let instant = 1.0 / dt;
+                state.fps = if state.fps == 0.0 { instant } else { state.fps * 0.9 + instant * 0.1 };
+            }
+        }
+        state.last_tick = Some(now);
+        state.sequence += 1;
+        if !self.is_enabled() {
+            return None;
+        }
+        let mut bitmap = render_state(&state);
+        let (x, top) = overlay_origin(state.corner, state.scale, &bitmap, width, height)?;
+        let y = if bottom_up {
+            flip_rows(&mut bitmap);
+            height - top - bitmap.height
+        } else {
+            top
+        };
+        Some((bitmap, x, y))
+    }
+}
+
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+fn render_state(state: &OverlayState) -> OverlayBitmap {
+    let stats = format!("FPS {:.1} #{}", state.fps, state.sequence);
+    let time = utc_time_of_day(SystemTime::now());
+    let mut lines: Vec<&str> = Vec::with_capacity(3);
+    if let Some(label) = state.label.as_deref() {
+        lines.push(label);
+    }
+    lines.push(&stats);
+    lines.push(&time);
+    render_overlay_text(&lines, state.scale)
+}
+
+/// Top-left origin of the overlay inside a `width`x`height` frame, or None if it does not fit.
+fn overlay_origin(
+    corner: OverlayCorner,
+    scale: usize,
+    bitmap: &OverlayBitmap,
+    width: usize,
+    height: usize,
+) -> Option<(usize, usize)> {
+    let margin = MARGIN * scale;
+    if bitmap.width + 2 * margin > width || bitmap.height + 2 * margin > height {
+        return None;
+    }
+    let right = width - margin - bitmap.width;
+    let bottom = height - margin - bitmap.height;
+    Some(match corner {
+        OverlayCorner::TopLeft => (margin, margin),
+        OverlayCorner::TopRight => (right, margin),
+        OverlayCorner::BottomLeft => (margin, bottom),
+        OverlayCorner::BottomRight => (right, bottom),
+    })
+}
+
+fn flip_rows(bitmap: &mut OverlayBitmap) {
+    let row = bitmap.width * 4;
+    for y in 0..bitmap.height / 2 {
+        let (top, bottom) = bitmap.rgba.split_at_mut((bitmap.height - 1 - y) * row);
+        top[y * row..(y + 1) * row].swap_with_slice(&mut bottom[..row]);
+    }
+}
+
+/// Format a wall-clock time as `HH:MM:SS.mmm` (UTC).
+fn utc_time_of_day(t: SystemTime) -> String {
+    let d = t.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = d.as_secs() % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        secs / 3600,
+        (secs / 60) % 60,
+        secs % 60,
+        d.subsec_millis()
+    )
+}
+
+/// Render lines of text with the built-in 5x7 font: white on opaque black, top row first.
+/// Lowercase is drawn as uppercase; unsupported characters render as `?`.
+pub fn render_overlay_text(lines: &[&str], scale: usize) -> OverlayBitmap {
+    let scale = scale.max(1);
+    let cols = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let cell_w = GLYPH_W + 1;
+    let cell_h = GLYPH_H + 1;
+    let width = (2 * PADDING + cols * cell_w).saturating_sub(usize::from(cols > 0)) * scale;
+    let height = (2 * PADDING + lines.len() * cell_h).saturating_sub(usize::from(!lines.is_empty())) * scale;
+    let mut rgba = vec![0u8; width * height * 4];
+    for px in rgba.chunks_exact_mut(4) {
+        px[3] = 255;
+    }
+    for (row, line) in lines.iter().enumerate() {
+        for (col, ch) in line.chars().enumerate() {
+            let glyph = glyph(ch);
+            let gx = (PADDING + col * cell_w) * scale;
+            let gy = (PADDING + row * cell_h) * scale;
+            for (dy, bits) in glyph.iter().enumerate() {
+                for dx in 0..GLYPH_W {
+                    if bits & (0x10 >> dx) == 0 {
+                        continue;
+                    }
+                    for sy in 0..scale {
+                        let y = gy + dy * scale + sy;
+                        let start = (y * width + gx + dx * scale) * 4;
+                        for px in rgba[start..start + scale * 4].chunks_exact_mut(4) {
+                            px.copy_from_slice(&[255, 255, 255, 255]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    OverlayBitmap { width, height, rgba }
+}
+
+/// 5x7 glyph rows, most significant of the low 5 bits is the leftmost pixel.
+fn glyph(ch: char) -> [u8; GLYPH_H] {
+    match ch.to_ascii_uppercase() {
+        ' ' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn metal_write_texture_region_rgba8(
+    texture: MTLTexturePtr,
+    command_buffer: MTLCommandBufferPtr,
+    x: usize,
+    y: usize,
+    bitmap: &OverlayBitmap,
+) -> bool {
+    if texture.is_null() || command_buffer.is_null() {
+        return false;
+    }
+    unsafe {
+        ffi::syphon_metal_texture_write_region_rgba8(
+            texture,
+            command_buffer,
+            x,
+            y,
+            bitmap.width,
+            bitmap.height,
+            bitmap.rgba.as_ptr(),
+        )
+    }
+}
+#[cfg(not(target_os = "macos"))]
+fn metal_write_texture_region_rgba8(
+    _texture: MTLTexturePtr,
+    _command_buffer: MTLCommandBufferPtr,
+    _x: usize,
+    _y: usize,
+    _bitmap: &OverlayBitmap,
+) -> bool {
+    false
+}
@@ -0,0 +1,100 @@
+//! Clipboard and drag-source helpers (`pasteboard` feature): put a frame on the general `NSPasteboard` as
+//! PNG/TIFF, or wrap it in an `NSDraggingItem` so preview tools can copy-paste or drag frames into other apps.
+
+#[cfg(target_os = "macos")]
+use std::ptr::NonNull;
+
+#[cfg(target_os = "macos")]
+use crate::ffi;
+use crate::OpenGLImage;
+
+/// Image representations offered on the pasteboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasteboardFormats {
+    pub png: bool,
+    pub tiff: bool,
+}
+
+impl Default for PasteboardFormats {
+    /// PNG and TIFF: most design apps take one or the other.
+    fn default() -> Self {
+        Self { png: true, tiff: true }
+    }
+}
+
+/// Replace the general pasteboard's contents with an RGBA8 image (top row first, `width*height*4` bytes).
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+pub fn copy_rgba_to_pasteboard(width: usize, height: usize, rgba: &[u8], formats: PasteboardFormats) -> bool {
+    if width == 0 || height == 0 || rgba.len() < width * height * 4 {
+        return false;
+    }
+    #[cfg(target_os = "macos")]
+    unsafe {
+        ffi::syphon_pasteboard_write_rgba8(rgba.as_ptr(), width, height, formats.png, formats.tiff)
+    }
+    #[cfg(not(target_os = "macos"))]
+    false
+}
+
+/// Copy a received OpenGL frame to the general pasteboard. The image's context must be current.
+pub fn copy_gl_image_to_pasteboard(image: &OpenGLImage, formats: PasteboardFormats) -> bool {
+    crate::capture::read_gl_image_top_down(image)
+        .is_some_and(|(w, h, rgba)| copy_rgba_to_pasteboard(w, h, &rgba, formats))
+}
+
+/// An `NSDraggingItem` carrying a frame, for `-[NSView beginDraggingSessionWithItems:event:source:]`.
+/// AppKit retains the item when the session starts, so it can be dropped afterwards.
+pub struct FrameDragItem {
+    #[cfg(target_os = "macos")]
+    ptr: NonNull<std::ffi::c_void>,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl FrameDragItem {
+    /// Build a dragging item from an RGBA8 image (top row first). `preview_size` sets the drag image's
+    /// frame in the source view's coordinates (None uses the image size); move it with `setDraggingFrame:`.
+    pub fn from_rgba(
+        width: usize,
+        height: usize,
+        rgba: &[u8],
+        formats: PasteboardFormats,
+        preview_size: Option<(f64, f64)>,
+    ) -> Option<Self> {
+        if width == 0 || height == 0 || rgba.len() < width * height * 4 {
+            return None;
+        }
+        #[cfg(target_os = "macos")]
+        {
+            let (pw, ph) = preview_size.unwrap_or((0.0, 0.0));
+            let ptr = unsafe {
+                ffi::syphon_dragging_item_create_rgba8(rgba.as_ptr(), width, height, formats.png, formats.tiff, pw, ph)
+            };
+            NonNull::new(ptr).map(|ptr| Self { ptr })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Build a dragging item from a received OpenGL frame. The image's context must be current.
+    pub fn from_gl_image(image: &OpenGLImage, formats: PasteboardFormats, preview_size: Option<(f64, f64)>) -> Option<Self> {
+        let (w, h, rgba) = crate::capture::read_gl_image_top_down(image)?;
+        Self::from_rgba(w, h, &rgba, formats, preview_size)
+    }
+
+    /// The `NSDraggingItem *` (not retained).
+    pub fn as_ptr(&self) -> *mut std::ffi::c_void {
+        #[cfg(target_os = "macos")]
+        return self.ptr.as_ptr();
+        #[cfg(not(target_os = "macos"))]
+        std::ptr::null_mut()
+    }
+}
+
+impl Drop for FrameDragItem {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_dragging_item_release(self.ptr.as_ptr());
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! CoreVideo interop: `PixelBuffer` wraps a received frame's IOSurface in a `CVPixelBuffer` without
+//! copying, ready for AVFoundation writers, VideoToolbox sessions or CoreML / Vision requests.
+
+#[cfg(target_os = "macos")]
+use std::ptr::NonNull;
+
+#[cfg(target_os = "macos")]
+use crate::ffi;
+use crate::{Frame, IOSurfacePtr, MetalTexture, OpenGLImage};
+
+/// Opaque pointer to a CVPixelBufferRef.
+pub type CVPixelBufferPtr = *mut std::ffi::c_void;
+
+/// A retained `CVPixelBuffer` sharing a frame's IOSurface. The pixels are the server's live surface: it
+/// keeps the surface alive, but the server may draw the next frame into it, so consume the buffer promptly
+/// (or copy it) if frames must not change underneath you.
+pub struct PixelBuffer {
+    #[cfg(target_os = "macos")]
+    ptr: NonNull<std::ffi::c_void>,
+}
+
+// CVPixelBuffers are CF objects and may be retained and released on any thread.
+unsafe impl Send for PixelBuffer {}
+unsafe impl Sync for PixelBuffer {}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl PixelBuffer {
+    /// Wrap `surface` (retained by the buffer). None if `surface` is null or CoreVideo rejects it.
+    pub fn from_io_surface(surface: IOSurfacePtr) -> Option<Self> {
+        #[cfg(target_os = "macos")]
+        {
+            let ptr = unsafe { ffi::syphon_cv_pixel_buffer_create_with_io_surface(surface) };
+            NonNull::new(ptr).map(|ptr| Self { ptr })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// The CVPixelBufferRef, valid while `self` is alive (`CVPixelBufferRetain` it to keep it longer).
+    pub fn as_ptr(&self) -> CVPixelBufferPtr {
+        #[cfg(target_os = "macos")]
+        {
+            self.ptr.as_ptr()
+        }
+        #[cfg(not(target_os = "macos"))]
+        std::ptr::null_mut()
+    }
+
+    /// Hand the buffer's reference to the caller, who must `CVPixelBufferRelease` it.
+    pub fn into_raw(self) -> CVPixelBufferPtr {
+        let ptr = self.as_ptr();
+        std::mem::forget(self);
+        ptr
+    }
+
+    /// Width and height in pixels.
+    pub fn size(&self) -> (usize, usize) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            (
+                ffi::syphon_cv_pixel_buffer_width(self.ptr.as_ptr()),
+                ffi::syphon_cv_pixel_buffer_height(self.ptr.as_ptr()),
+            )
+        }
+        #[cfg(not(target_os = "macos"))]
+        (0, 0)
+    }
+
+    /// CoreVideo pixel format FourCC (e.g. `u32::from_be_bytes(*b"BGRA")` for Syphon's usual frames).
+    pub fn pixel_format(&self) -> u32 {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_cv_pixel_buffer_pixel_format(self.ptr.as_ptr())
+        }
+        #[cfg(not(target_os = "macos"))]
+        0
+    }
+}
+
+impl Drop for PixelBuffer {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_cv_pixel_buffer_release(self.ptr.as_ptr());
+        }
+    }
+}
+
+impl OpenGLImage {
+    /// The image's IOSurface as a `CVPixelBuffer` (no copy). CoreVideo rows run top-down in surface
+    /// memory order, which OpenGL samples bottom-up (see `Frame::is_flipped`).
+    pub fn to_pixel_buffer(&self) -> Option<PixelBuffer> {
+        PixelBuffer::from_io_surface(self.io_surface_ptr()?)
+    }
+}
+
+impl MetalTexture {
+    /// The texture's IOSurface as a `CVPixelBuffer` (no copy); None if it isn't IOSurface-backed.
+    pub fn to_pixel_buffer(&self) -> Option<PixelBuffer> {
+        PixelBuffer::from_io_surface(self.io_surface_ptr()?)
+    }
+}
+
+impl Frame {
+    /// The frame's IOSurface as a `CVPixelBuffer` (no copy).
+    pub fn to_pixel_buffer(&self) -> Option<PixelBuffer> {
+        match self {
+            Frame::OpenGL(image) => image.to_pixel_buffer(),
+            Frame::Metal(texture) => texture.to_pixel_buffer(),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Asynchronous GL readback through pixel-pack buffers (PBOs).
+//!
+//! `glReadPixels` into client memory stalls until the GPU has rendered the frame. `AsyncReadback::begin`
+//! instead queues the copy into a PBO and returns at once; the `ReadbackFuture` is collected a frame
+//! later, when the copy has usually landed. Buffers are recycled by the `AsyncReadback`, so the usual
+//! pipeline (begin frame N, then `wait` on frame N-1) double-buffers with two PBOs.
+//!
+//! Everything except `AsyncReadback::new` must run with the same CGL context current, including drops.
+
+#[cfg(target_os = "macos")]
+use std::ptr::NonNull;
+
+#[cfg(target_os = "macos")]
+use crate::ffi;
+use crate::{check_current_context, OpenGLImage, SyphonError, GL_TEXTURE_RECTANGLE};
+
+/// A recycling pool of pixel-pack buffers for asynchronous RGBA8 readback.
+pub struct AsyncReadback {
+    #[cfg(target_os = "macos")]
+    ptr: NonNull<std::ffi::c_void>,
+}
+
+/// A readback queued by `AsyncReadback::begin`. Dropping it abandons the copy and returns its buffer.
+pub struct ReadbackFuture<'a> {
+    #[cfg(target_os = "macos")]
+    ptr: NonNull<std::ffi::c_void>,
+    width: usize,
+    height: usize,
+    _pool: std::marker::PhantomData<&'a AsyncReadback>,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl AsyncReadback {
+    /// An empty pool (buffers are created by `begin` as needed). None off macOS.
+    pub fn new() -> Option<Self> {
+        #[cfg(target_os = "macos")]
+        {
+            let ptr = unsafe { ffi::syphon_gl_readback_pool_create() };
+            NonNull::new(ptr).map(|ptr| Self { ptr })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Queue a copy of texture `tex_id` (`target`: `GL_TEXTURE_RECTANGLE` or `GL_TEXTURE_2D`, RGBA8
+    /// readable, at least `width` x `height`) and return without waiting. CGL context must be current.
+    pub fn begin(&self, tex_id: u32, target: u32, width: usize, height: usize) -> Option<ReadbackFuture<'_>> {
+        self.try_begin(tex_id, target, width, height).ok().flatten()
+    }
+
+    /// Like `begin`, reporting a missing current context as an error. Ok(None) means GL refused the copy.
+    pub fn try_begin(
+        &self,
+        tex_id: u32,
+        target: u32,
+        width: usize,
+        height: usize,
+    ) -> Result<Option<ReadbackFuture<'_>>, SyphonError> {
+        check_current_context(None)?;
+        #[cfg(target_os = "macos")]
+        {
+            let ptr = unsafe { ffi::syphon_gl_readback_begin(self.ptr.as_ptr(), tex_id, target, width, height) };
+            Ok(NonNull::new(ptr).map(|ptr| ReadbackFuture {
+                ptr,
+                width,
+                height,
+                _pool: std::marker::PhantomData,
+            }))
+        }
+        #[cfg(not(target_os = "macos"))]
+        Ok(None)
+    }
+
+    /// `begin` on a received frame, so the image can be dropped right away.
+    pub fn begin_image(&self, image: &OpenGLImage) -> Option<ReadbackFuture<'_>> {
+        let (w, h) = image.texture_size();
+        self.begin(image.texture_name(), GL_TEXTURE_RECTANGLE, w as usize, h as usize)
+    }
+}
+
+impl ReadbackFuture<'_> {
+    /// Width of the queued copy in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height of the queued copy in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// True once the GPU has finished the copy, so `wait` returns without blocking.
+    pub fn is_ready(&self) -> bool {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_gl_readback_is_ready(self.ptr.as_ptr())
+        }
+        #[cfg(not(target_os = "macos"))]
+        true
+    }
+
+    /// Block until the copy has finished and return RGBA8 pixels, row y=0 first (for Syphon frames, the
+    /// top row). Empty if mapping the buffer failed.
+    pub fn wait(self) -> Vec<u8> {
+        #[cfg(target_os = "macos")]
+        {
+            let mut pixels = vec![0u8; self.width * self.height * 4];
+            let ok = unsafe { ffi::syphon_gl_readback_finish(self.ptr.as_ptr(), pixels.as_mut_ptr(), pixels.len()) };
+            if ok {
+                return pixels;
+            }
+        }
+        Vec::new()
+    }
+
+    /// The pixels if the copy has finished, else the future back to retry later.
+    pub fn try_take(self) -> Result<Vec<u8>, Self> {
+        if self.is_ready() {
+            Ok(self.wait())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Drop for ReadbackFuture<'_> {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_gl_readback_release(self.ptr.as_ptr());
+        }
+    }
+}
+
+impl Drop for AsyncReadback {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_gl_readback_pool_release(self.ptr.as_ptr());
+        }
+    }
+}
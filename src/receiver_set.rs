@@ -0,0 +1,229 @@
+//! `ReceiverSet`: a dynamic set of OpenGL clients keyed by server UUID, with one tagged event stream.
+//!
+//! Sources are added explicitly or automatically from the server directory, and dropped or
+//! reconnected as servers come and go. Call `sync` regularly (e.g. once per render frame) to track
+//! the directory, then drain `try_recv` / `recv_timeout` for frame and lifecycle events.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+use crate::{CGLContextObj, OpenGLClient, OpenGLImage, ServerDescription, ServerDirectory};
+
+/// What happened to a source in a `ReceiverSet`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReceiverEventKind {
+    /// The source was added to the set.
+    Added,
+    /// The source published a new frame.
+    Frame,
+    /// The source's server disappeared or its client became invalid; it is kept and reconnected if it returns.
+    Lost,
+    /// A lost source's server is available again and a new client was attached.
+    Reconnected,
+    /// The source was removed from the set.
+    Removed,
+}
+
+/// An event from a `ReceiverSet`, tagged with the source's server UUID.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReceiverEvent {
+    pub source: String,
+    pub kind: ReceiverEventKind,
+}
+
+/// Decides which directory servers a `ReceiverSet` adds automatically.
+pub type ReceiverFilter = Box<dyn Fn(&ServerDescription) -> bool>;
+
+struct Source {
+    name: Option<String>,
+    app_name: Option<String>,
+    client: Option<OpenGLClient>,
+}
+
+/// Owns many clients on one CGL context and multiplexes their events onto one channel.
+pub struct ReceiverSet {
+    context: CGLContextObj,
+    sources: HashMap<String, Source>,
+    auto_add: Option<ReceiverFilter>,
+    tx: Sender<ReceiverEvent>,
+    rx: Receiver<ReceiverEvent>,
+}
+
+impl ReceiverSet {
+    /// Create an empty set whose clients use `context` (which must outlive the set).
+    pub fn new(context: CGLContextObj) -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            context,
+            sources: HashMap::new(),
+            auto_add: None,
+            tx,
+            rx,
+        }
+    }
+
+    /// Automatically add directory servers accepted by `filter` on each `sync`. None disables auto-add.
+    pub fn set_auto_add(&mut self, filter: Option<ReceiverFilter>) {
+        self.auto_add = filter;
+    }
+
+    /// Add a source for `description`. Returns false if it has no UUID or is already in the set.
+    pub fn add(&mut self, description: &ServerDescription) -> bool {
+        let Some(uuid) = description.uuid() else {
+            return false;
+        };
+        if self.sources.contains_key(&uuid) {
+            return false;
+        }
+        let client = self.connect(&uuid, description);
+        let connected = client.is_some();
+        self.sources.insert(
+            uuid.clone(),
+            Source {
+                name: description.name(),
+                app_name: description.app_name(),
+                client,
+            },
+        );
+        self.emit(&uuid, ReceiverEventKind::Added);
+        if !connected {
+            self.emit(&uuid, ReceiverEventKind::Lost);
+        }
+        true
+    }
+
+    /// Remove a source. Its client is stopped before this returns, so no further `Frame` events for it are queued.
+    pub fn remove(&mut self, uuid: &str) -> bool {
+        if self.sources.remove(uuid).is_none() {
+            return false;
+        }
+        self.emit(uuid, ReceiverEventKind::Removed);
+        true
+    }
+
+    /// Server UUIDs of all sources in the set.
+    pub fn ids(&self) -> Vec<String> {
+        self.sources.keys().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Whether the source currently has a valid client.
+    pub fn is_connected(&self, uuid: &str) -> bool {
+        self.sources
+            .get(uuid)
+            .and_then(|s| s.client.as_ref())
+            .is_some_and(OpenGLClient::is_valid)
+    }
+
+    /// Server and app name recorded when the source was added.
+    pub fn source_names(&self, uuid: &str) -> Option<(Option<String>, Option<String>)> {
+        self.sources.get(uuid).map(|s| (s.name.clone(), s.app_name.clone()))
+    }
+
+    /// The source's client, if connected.
+    pub fn client(&self, uuid: &str) -> Option<&OpenGLClient> {
+        self.sources.get(uuid).and_then(|s| s.client.as_ref())
+    }
+
+    /// The source's current frame. The set's context must be current.
+    pub fn frame_image(&self, uuid: &str) -> Option<OpenGLImage> {
+        self.client(uuid).and_then(OpenGLClient::new_frame_image)
+    }
+
+    /// Track the directory: auto-add matching servers, mark vanished or invalid sources `Lost`, and
+    /// reconnect lost sources whose server is listed again.
+    pub fn sync(&mut self) {
+        let servers = ServerDirectory::shared()
+            .map(|dir| dir.servers())
+            .unwrap_or_default();
+        let listed: HashMap<String, &ServerDescription> = servers
+            .iter()
+            .filter_map(|d| d.uuid().map(|u| (u, d)))
+            .collect();
+
+        let mut lost = Vec::new();
+        let mut reconnect = Vec::new();
+        for (uuid, source) in &self.sources {
+            let valid = source.client.as_ref().is_some_and(OpenGLClient::is_valid);
+            match (valid, listed.contains_key(uuid)) {
+                (true, true) => {}
+                (true, false) => lost.push(uuid.clone()),
+                (false, true) => reconnect.push(uuid.clone()),
+                (false, false) if source.client.is_some() => lost.push(uuid.clone()),
+                (false, false) => {}
+            }
+        }
+        for uuid in lost {
+            if let Some(source) = self.sources.get_mut(&uuid) {
+                source.client = None;
+            }
+            self.emit(&uuid, ReceiverEventKind::Lost);
+        }
+        for uuid in reconnect {
+            let client = self.connect(&uuid, listed[&uuid]);
+            let connected = client.is_some();
+            if let Some(source) = self.sources.get_mut(&uuid) {
+                source.client = client;
+            }
+            if connected {
+                self.emit(&uuid, ReceiverEventKind::Reconnected);
+            }
+        }
+
+        if let Some(filter) = self.auto_add.take() {
+            for (uuid, desc) in &listed {
+                if !self.sources.contains_key(uuid) && filter(desc) {
+                    self.add(desc);
+                }
+            }
+            self.auto_add = Some(filter);
+        }
+    }
+
+    /// Next pending event, if any.
+    pub fn try_recv(&self) -> Option<ReceiverEvent> {
+        self.rx.try_recv().ok()
+    }
+
+    /// Wait up to `timeout` for the next event. Frame events arrive from Syphon's threads; lifecycle
+    /// events are only produced by `add`, `remove` and `sync` on this thread.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<ReceiverEvent> {
+        self.rx.recv_timeout(timeout).ok()
+    }
+
+    /// Drain all pending events.
+    pub fn drain(&self) -> Vec<ReceiverEvent> {
+        self.rx.try_iter().collect()
+    }
+
+    fn connect(&self, uuid: &str, description: &ServerDescription) -> Option<OpenGLClient> {
+        let tx = self.tx.clone();
+        let source = uuid.to_owned();
+        OpenGLClient::new(
+            description,
+            self.context,
+            None,
+            Some(Box::new(move |_| {
+                let _ = tx.send(ReceiverEvent {
+                    source: source.clone(),
+                    kind: ReceiverEventKind::Frame,
+                });
+            })),
+        )
+    }
+
+    fn emit(&self, uuid: &str, kind: ReceiverEventKind) {
+        let _ = self.tx.send(ReceiverEvent {
+            source: uuid.to_owned(),
+            kind,
+        });
+    }
+}
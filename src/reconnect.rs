@@ -0,0 +1,153 @@
+//! Clients that survive server restarts.
+//!
+//! A restarted source app publishes a server with a new UUID, which leaves an existing client invalid
+//! for good. `ReconnectingClient` (OpenGL) and `ReconnectingMetalClient` remember the server's name and
+//! app name and, whenever `sync` finds their client invalid, attach a new one to the matching server
+//! (the same UUID if it is still listed, else the first server with that name and app name).
+
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    CGLContextObj, MTLDevicePtr, MetalClient, MetalTexture, NewFrameCallback, OpenGLClient, OpenGLImage,
+    ServerDescription, ServerDescriptionInfo, ServerDirectory,
+};
+
+/// The server to follow and the new-frame callback shared by every client attached to it.
+struct Target {
+    info: ServerDescriptionInfo,
+    callback: Option<Arc<Mutex<NewFrameCallback>>>,
+}
+
+impl Target {
+    fn new(description: &ServerDescription, callback: Option<NewFrameCallback>) -> Self {
+        Self {
+            info: description.info(),
+            callback: callback.map(|cb| Arc::new(Mutex::new(cb))),
+        }
+    }
+
+    fn frame_callback(&self) -> Option<NewFrameCallback> {
+        let callback = self.callback.clone()?;
+        Some(Box::new(move |info| {
+            let callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+            callback(info);
+        }))
+    }
+
+    /// Connect with `connect` to the live server for this target, recording its new UUID.
+    fn reconnect<C>(&mut self, connect: impl FnOnce(&ServerDescription, Option<NewFrameCallback>) -> Option<C>) -> Option<C> {
+        let directory = ServerDirectory::shared()?;
+        let description = self.info.resolve(&directory)?;
+        let client = connect(&description, self.frame_callback())?;
+        self.info = description.info();
+        Some(client)
+    }
+}
+
+/// An `OpenGLClient` that reattaches to its server (matched by name and app name) after a restart.
+pub struct ReconnectingClient {
+    target: Target,
+    context: CGLContextObj,
+    client: Option<OpenGLClient>,
+}
+
+impl ReconnectingClient {
+    /// Follow the server in `description`, attaching clients on `context` (which must outlive this).
+    /// `callback` is kept across reconnections. Connects immediately if the server is available.
+    pub fn new(description: &ServerDescription, context: CGLContextObj, callback: Option<NewFrameCallback>) -> Self {
+        let target = Target::new(description, callback);
+        let client = OpenGLClient::new(description, context, None, target.frame_callback());
+        Self {
+            target,
+            context,
+            client,
+        }
+    }
+
+    /// Reconnect if the client is missing or invalid and a matching server is listed. Returns true
+    /// if a new client was attached. Call regularly (e.g. once per render frame).
+    pub fn sync(&mut self) -> bool {
+        if self.is_connected() {
+            return false;
+        }
+        self.client = None;
+        let context = self.context;
+        self.client = self
+            .target
+            .reconnect(|description, callback| OpenGLClient::new(description, context, None, callback));
+        self.client.is_some()
+    }
+
+    /// Whether the current client is valid.
+    pub fn is_connected(&self) -> bool {
+        self.client.as_ref().is_some_and(OpenGLClient::is_valid)
+    }
+
+    /// The current client, if any (may have gone invalid since the last `sync`).
+    pub fn client(&self) -> Option<&OpenGLClient> {
+        self.client.as_ref()
+    }
+
+    /// The server being followed; its UUID is updated on every reconnection.
+    pub fn target(&self) -> &ServerDescriptionInfo {
+        &self.target.info
+    }
+
+    /// `sync`, then the current frame. The context must be current.
+    pub fn new_frame_image(&mut self) -> Option<OpenGLImage> {
+        self.sync();
+        self.client.as_ref().and_then(OpenGLClient::new_frame_image)
+    }
+}
+
+/// A `MetalClient` that reattaches to its server (matched by name and app name) after a restart.
+pub struct ReconnectingMetalClient {
+    target: Target,
+    device: MTLDevicePtr,
+    client: Option<MetalClient>,
+}
+
+impl ReconnectingMetalClient {
+    /// Follow the server in `description`, attaching clients on `device`. `callback` is kept across
+    /// reconnections. Connects immediately if the server is available.
+    pub fn new(description: &ServerDescription, device: MTLDevicePtr, callback: Option<NewFrameCallback>) -> Self {
+        let target = Target::new(description, callback);
+        let client = MetalClient::new(description, device, None, target.frame_callback());
+        Self { target, device, client }
+    }
+
+    /// Reconnect if the client is missing or invalid and a matching server is listed. Returns true
+    /// if a new client was attached.
+    pub fn sync(&mut self) -> bool {
+        if self.is_connected() {
+            return false;
+        }
+        self.client = None;
+        let device = self.device;
+        self.client = self
+            .target
+            .reconnect(|description, callback| MetalClient::new(description, device, None, callback));
+        self.client.is_some()
+    }
+
+    /// Whether the current client is valid.
+    pub fn is_connected(&self) -> bool {
+        self.client.as_ref().is_some_and(MetalClient::is_valid)
+    }
+
+    /// The current client, if any (may have gone invalid since the last `sync`).
+    pub fn client(&self) -> Option<&MetalClient> {
+        self.client.as_ref()
+    }
+
+    /// The server being followed; its UUID is updated on every reconnection.
+    pub fn target(&self) -> &ServerDescriptionInfo {
+        &self.target.info
+    }
+
+    /// `sync`, then the current frame.
+    pub fn new_frame_image(&mut self) -> Option<MetalTexture> {
+        self.sync();
+        self.client.as_ref().and_then(MetalClient::new_frame_image)
+    }
+}
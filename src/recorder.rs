@@ -0,0 +1,215 @@
+//! Recording to movie files (`recorder` feature): `Recorder` writes frames from any client to a QuickTime
+//! movie through AVAssetWriter, in H.264, ProRes 422 or ProRes 4444, timed by when each frame was taken. It
+//! replaces Syphon Recorder for apps that want to record in-process.
+
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "macos")]
+use std::ptr::NonNull;
+#[cfg(target_os = "macos")]
+use std::time::Instant;
+
+#[cfg(target_os = "macos")]
+use crate::ffi;
+#[cfg(target_os = "macos")]
+use crate::safe::creation_failed;
+use crate::{Frame, FrameClient, SyphonError, TimedFrame};
+
+/// Video codec of a recording.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RecorderCodec {
+    /// H.264, hardware-encoded where available; small files for review and sharing.
+    #[default]
+    H264,
+    /// Apple ProRes 422, for editing.
+    ProRes422,
+    /// Apple ProRes 4444, which keeps the frames' alpha channel.
+    ProRes4444,
+}
+
+impl RecorderCodec {
+    #[cfg(target_os = "macos")]
+    fn raw(self) -> u32 {
+        match self {
+            RecorderCodec::H264 => 0,
+            RecorderCodec::ProRes422 => 1,
+            RecorderCodec::ProRes4444 => 2,
+        }
+    }
+}
+
+/// Recording settings; the default records H.264 at the first frame's size.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RecorderOptions {
+    pub codec: RecorderCodec,
+    /// Movie size in pixels (frames are scaled to fit and letterboxed); None for the first frame's size.
+    pub size: Option<(usize, usize)>,
+    /// Average bit rate in bits per second for H.264; None lets AVFoundation choose. ProRes ignores it.
+    pub bit_rate: Option<f64>,
+}
+
+/// Writes frames to a `.mov` file. The writer opens on the first frame (replacing any file at the path);
+/// timestamps come from `TimedFrame::timestamp`, relative to the first frame. Call `finish` to complete
+/// the file and see whether it was written; dropping an unfinished recorder finishes it, ignoring errors.
+pub struct Recorder {
+    path: PathBuf,
+    #[cfg(target_os = "macos")]
+    options: RecorderOptions,
+    #[cfg(target_os = "macos")]
+    writer: Option<NonNull<std::ffi::c_void>>,
+    #[cfg(target_os = "macos")]
+    start: Option<Instant>,
+    frames_written: u64,
+    frames_dropped: u64,
+}
+
+// The writer is only used through `&mut self`, and AVAssetWriter may be driven from any one thread at a time.
+unsafe impl Send for Recorder {}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl Recorder {
+    /// A recorder writing to `path`. None off macOS or if the path isn't valid UTF-8 or contains a NUL byte.
+    pub fn new(path: impl AsRef<Path>, options: &RecorderOptions) -> Option<Self> {
+        Self::try_new(path, options).ok()
+    }
+
+    /// Like `new`, reporting why the recorder can't be used.
+    pub fn try_new(path: impl AsRef<Path>, options: &RecorderOptions) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            let path = path.as_ref();
+            if path.to_str().is_none_or(|p| p.contains('\0')) {
+                return Err(SyphonError::InvalidArgument("recording path must be UTF-8 without NUL bytes"));
+            }
+            if options.size.is_some_and(|(w, h)| w == 0 || h == 0) {
+                return Err(SyphonError::InvalidArgument("recording size must not be zero"));
+            }
+            Ok(Self {
+                path: path.to_path_buf(),
+                options: *options,
+                writer: None,
+                start: None,
+                frames_written: 0,
+                frames_dropped: 0,
+            })
+        }
+        #[cfg(not(target_os = "macos"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// The movie file being written.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `frame`. Ok(false) if it was dropped because the encoder is still busy with earlier frames or
+    /// its timestamp isn't after the previous frame's; `CreationFailed` if the file can't be opened or
+    /// written.
+    pub fn record(&mut self, frame: &TimedFrame) -> Result<bool, SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            let writer = self.writer(&frame.frame)?;
+            let Some(buffer) = frame.frame.to_pixel_buffer() else {
+                self.frames_dropped += 1;
+                return Ok(false);
+            };
+            let start = *self.start.get_or_insert(frame.timestamp);
+            let seconds = frame.timestamp.saturating_duration_since(start).as_secs_f64();
+            let flip = !frame.frame.is_flipped();
+            match unsafe { ffi::syphon_recorder_append(writer, buffer.as_ptr(), seconds, flip) } {
+                1 => {
+                    self.frames_written += 1;
+                    Ok(true)
+                }
+                0 => {
+                    self.frames_dropped += 1;
+                    Ok(false)
+                }
+                _ => Err(creation_failed("recording")),
+            }
+        }
+        #[cfg(not(target_os = "macos"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Record `client`'s latest frame if one arrived since the last call, stamped now; Ok(false) if there
+    /// was none. Call it from the client's new-frame handler or a polling loop.
+    pub fn record_from<C>(&mut self, client: &C) -> Result<bool, SyphonError>
+    where
+        C: FrameClient,
+        C::Frame: Into<Frame>,
+    {
+        if !client.has_new_frame() {
+            return Ok(false);
+        }
+        match client.new_frame() {
+            Some(frame) => self.record(&frame),
+            None => Ok(false),
+        }
+    }
+
+    /// Frames appended to the file so far.
+    pub fn frames_written(&self) -> u64 {
+        self.frames_written
+    }
+
+    /// Frames skipped because the encoder fell behind or had no pixels to read.
+    pub fn frames_dropped(&self) -> u64 {
+        self.frames_dropped
+    }
+
+    /// Complete the movie, blocking until it is written. `CreationFailed` if no frame was recorded (no file
+    /// is left behind) or the file couldn't be completed.
+    pub fn finish(mut self) -> Result<(), SyphonError> {
+        self.finish_writer()
+    }
+
+    /// The writer, opened at the configured size (or `frame`'s) on first use.
+    #[cfg(target_os = "macos")]
+    fn writer(&mut self, frame: &Frame) -> Result<*mut std::ffi::c_void, SyphonError> {
+        if let Some(writer) = self.writer {
+            return Ok(writer.as_ptr());
+        }
+        let (width, height) = self.options.size.unwrap_or_else(|| frame.size());
+        let path = std::ffi::CString::new(self.path.to_str().unwrap_or_default())
+            .map_err(|_| SyphonError::InvalidArgument("recording path contains a NUL byte"))?;
+        let ptr = unsafe {
+            ffi::syphon_recorder_create(
+                path.as_ptr(),
+                self.options.codec.raw(),
+                width,
+                height,
+                self.options.bit_rate.unwrap_or(0.0),
+            )
+        };
+        let writer = NonNull::new(ptr).ok_or_else(|| creation_failed("recorder"))?;
+        self.writer = Some(writer);
+        Ok(writer.as_ptr())
+    }
+
+    fn finish_writer(&mut self) -> Result<(), SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            let Some(writer) = self.writer.take() else {
+                return Err(SyphonError::CreationFailed {
+                    object: "recording",
+                    reason: "no frames were recorded".into(),
+                });
+            };
+            let finished = unsafe { ffi::syphon_recorder_finish(writer.as_ptr()) };
+            let result = if finished { Ok(()) } else { Err(creation_failed("recording")) };
+            unsafe { ffi::syphon_recorder_release(writer.as_ptr()) };
+            result
+        }
+        #[cfg(not(target_os = "macos"))]
+        Err(SyphonError::Unsupported)
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        if self.writer.is_some() {
+            let _ = self.finish_writer();
+        }
+    }
+}
@@ -0,0 +1,1118 @@
+//! Network relay (`relay` feature): Syphon is machine-local, so `RelaySender` streams a server's frames over
+//! TCP or UDP and `RelayReceiver` republishes them as a Syphon server on another Mac. When a receiver
+//! connects it asks for a maximum size, a frame-rate cap and a compression (none, LZ4 with `relay-lz4`,
+//! JPEG with `relay-jpeg`); the sender scales, paces and compresses that receiver's stream to match.
+//! Receivers reconnect on their own when the sender restarts or the network drops.
+//!
+//! The wire format is this crate's own, so both ends must use this module. All integers are little-endian:
+//! - hello (receiver → sender, 20 bytes): `SYRL`, version, compression, JPEG quality, 0, max width u32,
+//!   max height u32, max fps f32 (0 = no limit);
+//! - accept (sender → receiver, 8 bytes): `SYRL`, version, compression granted, quality, 0;
+//! - frame: index u32, width u32, height u32, compression u8, payload length u32, payload (RGBA8, LZ4 with
+//!   prepended size, or JPEG);
+//! - over UDP each frame is split into datagrams of `SYRC`, frame index u32, chunk u16, chunk count u16,
+//!   then up to 1388 bytes of the frame.
+
+use std::borrow::Cow;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::worker::{frame_interval, Worker, POLL_INTERVAL};
+use crate::{CpuClient, CpuFrame, CpuServer, NewFrameCallback, ServerDescription, SyphonError};
+
+const MAGIC: [u8; 4] = *b"SYRL";
+const CHUNK_MAGIC: [u8; 4] = *b"SYRC";
+const VERSION: u8 = 1;
+const HELLO_LEN: usize = 20;
+const ACCEPT_LEN: usize = 8;
+const FRAME_HEADER_LEN: usize = 17;
+const CHUNK_HEADER_LEN: usize = 12;
+/// Datagram size that fits common MTUs without IP fragmentation.
+const DATAGRAM_LEN: usize = 1400;
+/// Frames larger than this are treated as a corrupt stream.
+const MAX_PAYLOAD: usize = 256 << 20;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+/// A TCP receiver that takes longer than this to accept one frame is dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+/// UDP receivers repeat their hello this often; senders forget receivers silent for `PEER_TIMEOUT`.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(1);
+const PEER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How frames travel from sender to receiver.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RelayTransport {
+    /// Reliable and ordered; a receiver on a slow link skips frames (without holding up the others)
+    /// rather than getting damaged ones.
+    #[default]
+    Tcp,
+    /// Lower latency; a frame missing a datagram is skipped. Best with JPEG, since an uncompressed
+    /// 1080p frame takes thousands of datagrams.
+    Udp,
+}
+
+/// How frames are compressed on the wire.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RelayCompression {
+    /// Raw RGBA8.
+    #[default]
+    None,
+    /// Lossless LZ4 (`relay-lz4` feature).
+    Lz4,
+    /// Lossy JPEG at `quality` 1-100 (`relay-jpeg` feature); alpha becomes opaque.
+    Jpeg { quality: u8 },
+}
+
+impl RelayCompression {
+    /// Whether this build can compress and decompress it.
+    pub fn is_available(self) -> bool {
+        match self {
+            RelayCompression::None => true,
+            RelayCompression::Lz4 => cfg!(feature = "relay-lz4"),
+            RelayCompression::Jpeg { .. } => cfg!(feature = "relay-jpeg"),
+        }
+    }
+
+    fn code(self) -> (u8, u8) {
+        match self {
+            RelayCompression::None => (0, 0),
+            RelayCompression::Lz4 => (1, 0),
+            RelayCompression::Jpeg { quality } => (2, quality),
+        }
+    }
+
+    fn from_code(code: u8, quality: u8) -> Option<Self> {
+        match code {
+            0 => Some(RelayCompression::None),
+            1 => Some(RelayCompression::Lz4),
+            2 => Some(RelayCompression::Jpeg { quality }),
+            _ => None,
+        }
+    }
+
+    #[cfg_attr(not(feature = "relay-jpeg"), allow(unused_variables))]
+    fn compress(self, rgba: &[u8], width: usize, height: usize) -> Option<Vec<u8>> {
+        match self {
+            RelayCompression::None => Some(rgba.to_vec()),
+            #[cfg(feature = "relay-lz4")]
+            RelayCompression::Lz4 => Some(lz4_flex::compress_prepend_size(rgba)),
+            #[cfg(feature = "relay-jpeg")]
+            RelayCompression::Jpeg { quality } => {
+                let mut out = Vec::new();
+                let encoder = jpeg_encoder::Encoder::new(&mut out, quality.clamp(1, 100));
+                let (width, height) = (u16::try_from(width).ok()?, u16::try_from(height).ok()?);
+                encoder.encode(rgba, width, height, jpeg_encoder::ColorType::Rgba).ok()?;
+                Some(out)
+            }
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// Decode `payload` to the RGBA8 frame `header` describes; None if it decodes to anything else.
+    fn decompress(self, payload: &[u8], header: &FrameHeader) -> Option<Vec<u8>> {
+        let frame_len = header.frame_len;
+        let rgba = match self {
+            RelayCompression::None => payload.to_vec(),
+            #[cfg(feature = "relay-lz4")]
+            RelayCompression::Lz4 => {
+                // The size prefix comes from the peer: check it before LZ4 allocates that much.
+                if u32_at(payload.get(..4)?, 0) as usize != frame_len {
+                    return None;
+                }
+                lz4_flex::decompress_size_prepended(payload).ok()?
+            }
+            #[cfg(feature = "relay-jpeg")]
+            RelayCompression::Jpeg { .. } => {
+                let mut decoder = jpeg_decoder::Decoder::new(payload);
+                // Check the JPEG's own size before decoding allocates for it.
+                decoder.read_info().ok()?;
+                let info = decoder.info()?;
+                if (info.width as usize, info.height as usize) != (header.width, header.height) {
+                    return None;
+                }
+                let pixels = decoder.decode().ok()?;
+                match decoder.info()?.pixel_format {
+                    jpeg_decoder::PixelFormat::RGB24 => {
+                        pixels.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect()
+                    }
+                    jpeg_decoder::PixelFormat::L8 => pixels.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+                    _ => return None,
+                }
+            }
+            #[allow(unreachable_patterns)]
+            _ => return None,
+        };
+        (rgba.len() == frame_len).then_some(rgba)
+    }
+}
+
+/// What a receiver asks the sender for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RelayReceiverOptions {
+    pub transport: RelayTransport,
+    /// Requested compression; the sender falls back to `None` if its build lacks it.
+    pub compression: RelayCompression,
+    /// Largest frame wanted; the sender scales bigger frames down to fit. None for full size.
+    pub max_size: Option<(usize, usize)>,
+    /// Frame-rate cap; None for every frame the source publishes. Must be positive and finite, and not
+    /// so small that the time between frames overflows a `Duration`.
+    pub max_fps: Option<f64>,
+    /// Wait between reconnection attempts.
+    pub reconnect_delay: Duration,
+}
+
+impl Default for RelayReceiverOptions {
+    fn default() -> Self {
+        Self {
+            transport: RelayTransport::Tcp,
+            compression: RelayCompression::None,
+            max_size: None,
+            max_fps: None,
+            reconnect_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+fn socket_error(object: &'static str, error: io::Error) -> SyphonError {
+    SyphonError::CreationFailed {
+        object,
+        reason: error.to_string(),
+    }
+}
+
+fn invalid_data(what: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, what.to_string())
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+/// A receiver's request, sent when it connects (and repeated as a keepalive over UDP).
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Hello {
+    compression: RelayCompression,
+    /// 0 for no limit.
+    max_width: u32,
+    max_height: u32,
+    max_fps: f32,
+}
+
+impl Hello {
+    fn to_bytes(self) -> [u8; HELLO_LEN] {
+        let (code, quality) = self.compression.code();
+        let mut out = [0; HELLO_LEN];
+        out[..4].copy_from_slice(&MAGIC);
+        out[4..8].copy_from_slice(&[VERSION, code, quality, 0]);
+        out[8..12].copy_from_slice(&self.max_width.to_le_bytes());
+        out[12..16].copy_from_slice(&self.max_height.to_le_bytes());
+        out[16..20].copy_from_slice(&self.max_fps.to_le_bytes());
+        out
+    }
+
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != HELLO_LEN || bytes[..4] != MAGIC || bytes[4] != VERSION {
+            return None;
+        }
+        Some(Self {
+            compression: RelayCompression::from_code(bytes[5], bytes[6])?,
+            max_width: u32_at(bytes, 8),
+            max_height: u32_at(bytes, 12),
+            max_fps: f32::from_le_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]),
+        })
+    }
+}
+
+/// The sender's answer: the compression it will actually use.
+fn accept_bytes(compression: RelayCompression) -> [u8; ACCEPT_LEN] {
+    let (code, quality) = compression.code();
+    [MAGIC[0], MAGIC[1], MAGIC[2], MAGIC[3], VERSION, code, quality, 0]
+}
+
+fn parse_accept(bytes: &[u8]) -> Option<RelayCompression> {
+    if bytes.len() != ACCEPT_LEN || bytes[..4] != MAGIC || bytes[4] != VERSION {
+        return None;
+    }
+    RelayCompression::from_code(bytes[5], bytes[6])
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FrameHeader {
+    width: usize,
+    height: usize,
+    compression: u8,
+    payload_len: usize,
+    /// Size of the decoded RGBA8 frame.
+    frame_len: usize,
+}
+
+impl FrameHeader {
+    /// None if the payload or the decoded frame would exceed `MAX_PAYLOAD`.
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        let width = u32_at(bytes, 4) as usize;
+        let height = u32_at(bytes, 8) as usize;
+        let frame_len = width.checked_mul(height)?.checked_mul(4)?;
+        let header = Self {
+            width,
+            height,
+            compression: bytes[12],
+            payload_len: u32_at(bytes, 13) as usize,
+            frame_len,
+        };
+        (header.payload_len <= MAX_PAYLOAD && frame_len <= MAX_PAYLOAD).then_some(header)
+    }
+
+    /// Decode `payload` to RGBA8.
+    fn decode(&self, payload: &[u8]) -> Option<Vec<u8>> {
+        RelayCompression::from_code(self.compression, 0)?.decompress(payload, self)
+    }
+}
+
+/// A frame on the wire: header (index, size, compression, payload length) then the payload.
+fn encode_frame(index: u32, rgba: &[u8], width: usize, height: usize, compression: RelayCompression) -> Option<Vec<u8>> {
+    let payload = compression.compress(rgba, width, height)?;
+    let mut out = Vec::with_capacity(FRAME_HEADER_LEN + payload.len());
+    out.extend_from_slice(&index.to_le_bytes());
+    out.extend_from_slice(&(width as u32).to_le_bytes());
+    out.extend_from_slice(&(height as u32).to_le_bytes());
+    out.push(compression.code().0);
+    out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    out.extend_from_slice(&payload);
+    Some(out)
+}
+
+/// `width` x `height` scaled down to fit `max` (keeping the aspect ratio); unchanged if it already fits.
+fn fit_size(width: usize, height: usize, max: Option<(usize, usize)>) -> (usize, usize) {
+    match max {
+        Some((max_w, max_h)) if width > max_w || height > max_h => {
+            let scale = (max_w as f64 / width as f64).min(max_h as f64 / height as f64);
+            (((width as f64 * scale) as usize).max(1), ((height as f64 * scale) as usize).max(1))
+        }
+        _ => (width, height),
+    }
+}
+
+/// Nearest-neighbour resample of `frame` to `width` x `height`.
+fn scale_frame(frame: &CpuFrame, width: usize, height: usize) -> Cow<'_, [u8]> {
+    if (width, height) == (frame.width, frame.height) {
+        return Cow::Borrowed(&frame.rgba);
+    }
+    let mut out = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        let row = y * frame.height / height * frame.width;
+        for x in 0..width {
+            let i = (row + x * frame.width / width) * 4;
+            out.extend_from_slice(&frame.rgba[i..i + 4]);
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Output size and compression: receivers sharing both share one encoded frame.
+type EncodingKey = ((usize, usize), RelayCompression);
+
+enum PeerLink {
+    Tcp(TcpWriter),
+    Udp(SocketAddr),
+}
+
+/// What the sender hands a `TcpWriter`'s thread.
+#[derive(Default)]
+struct PendingFrame {
+    frame: Option<Arc<[u8]>>,
+    closed: bool,
+}
+
+/// A TCP receiver's writer thread. It holds at most one frame waiting, replaced by each newer one, so a
+/// receiver that falls behind skips frames instead of delaying the other receivers.
+struct TcpWriter {
+    stream: TcpStream,
+    pending: Arc<(Mutex<PendingFrame>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl TcpWriter {
+    fn spawn(stream: TcpStream) -> io::Result<Self> {
+        let pending = Arc::new((Mutex::new(PendingFrame::default()), Condvar::new()));
+        let (mut thread_stream, thread_pending) = (stream.try_clone()?, pending.clone());
+        let thread = std::thread::Builder::new()
+            .name("syphon-relay-writer".into())
+            .spawn(move || {
+                let (lock, wake) = &*thread_pending;
+                loop {
+                    let mut pending = lock.lock().unwrap_or_else(|e| e.into_inner());
+                    let frame = loop {
+                        if pending.closed {
+                            return;
+                        }
+                        if let Some(frame) = pending.frame.take() {
+                            break frame;
+                        }
+                        pending = wake.wait(pending).unwrap_or_else(|e| e.into_inner());
+                    };
+                    drop(pending);
+                    // A failed or timed-out write ends the thread, and `is_open` then reports the receiver gone.
+                    if thread_stream.write_all(&frame).is_err() {
+                        return;
+                    }
+                }
+            })?;
+        Ok(Self {
+            stream,
+            pending,
+            thread: Some(thread),
+        })
+    }
+
+    /// False once a write has failed.
+    fn is_open(&self) -> bool {
+        self.thread.as_ref().is_some_and(|t| !t.is_finished())
+    }
+
+    /// Queue `frame`, replacing any frame the thread hasn't started writing.
+    fn send(&self, frame: &Arc<[u8]>) -> io::Result<()> {
+        if !self.is_open() {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+        let (lock, wake) = &*self.pending;
+        lock.lock().unwrap_or_else(|e| e.into_inner()).frame = Some(frame.clone());
+        wake.notify_one();
+        Ok(())
+    }
+}
+
+impl Drop for TcpWriter {
+    fn drop(&mut self) {
+        let (lock, wake) = &*self.pending;
+        lock.lock().unwrap_or_else(|e| e.into_inner()).closed = true;
+        wake.notify_one();
+        // Ends a write blocked on a stalled receiver, so the join doesn't wait for `WRITE_TIMEOUT`.
+        let _ = self.stream.shutdown(Shutdown::Both);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// A connected receiver and what it asked for.
+struct Peer {
+    link: PeerLink,
+    compression: RelayCompression,
+    max_size: Option<(usize, usize)>,
+    min_interval: Option<Duration>,
+    last_sent: Option<Instant>,
+    last_seen: Instant,
+}
+
+impl Peer {
+    fn new(link: PeerLink, hello: &Hello) -> Self {
+        let limit = |v: u32| (v > 0).then_some(v as usize);
+        Self {
+            link,
+            compression: Self::granted(hello),
+            max_size: limit(hello.max_width).zip(limit(hello.max_height)),
+            // A rate the sender can't turn into an interval (0, NaN, absurdly small) means no cap.
+            min_interval: frame_interval(hello.max_fps as f64),
+            last_sent: None,
+            last_seen: Instant::now(),
+        }
+    }
+
+    /// The compression the receiver gets: what it asked for, or `None` if this build lacks it.
+    fn granted(hello: &Hello) -> RelayCompression {
+        if hello.compression.is_available() {
+            hello.compression
+        } else {
+            RelayCompression::None
+        }
+    }
+
+    /// Whether the frame-rate cap allows a frame now. A quarter interval of slack keeps a 60 fps source
+    /// capped at 30 from dropping to 20 through timing jitter.
+    fn is_due(&self, now: Instant) -> bool {
+        match (self.min_interval, self.last_sent) {
+            (Some(interval), Some(last)) => now.duration_since(last) + interval / 4 >= interval,
+            _ => true,
+        }
+    }
+}
+
+/// A TCP receiver that connected but hasn't sent its whole hello yet.
+struct Handshake {
+    stream: TcpStream,
+    buffer: StreamBuffer,
+    deadline: Instant,
+}
+
+/// The sender's socket: a TCP listener or a UDP socket, both non-blocking.
+enum Endpoint {
+    Tcp(TcpListener),
+    Udp(UdpSocket),
+}
+
+impl Endpoint {
+    fn bind(addr: impl ToSocketAddrs, transport: RelayTransport) -> io::Result<Self> {
+        let endpoint = match transport {
+            RelayTransport::Tcp => Endpoint::Tcp(TcpListener::bind(addr)?),
+            RelayTransport::Udp => Endpoint::Udp(UdpSocket::bind(addr)?),
+        };
+        match &endpoint {
+            Endpoint::Tcp(listener) => listener.set_nonblocking(true)?,
+            Endpoint::Udp(socket) => socket.set_nonblocking(true)?,
+        }
+        Ok(endpoint)
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        match self {
+            Endpoint::Tcp(listener) => listener.local_addr(),
+            Endpoint::Udp(socket) => socket.local_addr(),
+        }
+    }
+
+    /// Take in new receivers (and UDP keepalives), and forget TCP receivers whose writes failed and UDP
+    /// receivers that went quiet. TCP receivers wait in `handshakes` until their hello has arrived, or are
+    /// dropped after `CONNECT_TIMEOUT`.
+    fn poll(&self, peers: &mut Vec<Peer>, handshakes: &mut Vec<Handshake>) {
+        match self {
+            Endpoint::Tcp(listener) => {
+                while let Ok((stream, _)) = listener.accept() {
+                    // Accepted sockets don't inherit non-blocking mode everywhere.
+                    if stream.set_nonblocking(true).is_ok() {
+                        handshakes.push(Handshake {
+                            stream,
+                            buffer: StreamBuffer::default(),
+                            deadline: Instant::now() + CONNECT_TIMEOUT,
+                        });
+                    }
+                }
+                handshakes.retain_mut(|handshake| match handshake.buffer.take(&mut handshake.stream, HELLO_LEN) {
+                    Ok(Some(hello)) => {
+                        if let Ok(peer) = Self::accept(&handshake.stream, &hello) {
+                            peers.push(peer);
+                        }
+                        false
+                    }
+                    Ok(None) => Instant::now() < handshake.deadline,
+                    Err(_) => false,
+                });
+                peers.retain(|p| !matches!(&p.link, PeerLink::Tcp(writer) if !writer.is_open()));
+            }
+            Endpoint::Udp(socket) => {
+                let mut buf = [0; HELLO_LEN + 1];
+                while let Ok((len, from)) = socket.recv_from(&mut buf) {
+                    let Some(hello) = Hello::parse(&buf[..len]) else {
+                        continue;
+                    };
+                    // Every hello gets an accept, so a receiver whose first accept was lost still connects.
+                    match peers.iter_mut().find(|p| matches!(p.link, PeerLink::Udp(addr) if addr == from)) {
+                        Some(peer) => {
+                            peer.last_seen = Instant::now();
+                            let _ = socket.send_to(&accept_bytes(peer.compression), from);
+                        }
+                        None => {
+                            let peer = Peer::new(PeerLink::Udp(from), &hello);
+                            let _ = socket.send_to(&accept_bytes(peer.compression), from);
+                            peers.push(peer);
+                        }
+                    }
+                }
+                peers.retain(|p| p.last_seen.elapsed() < PEER_TIMEOUT);
+            }
+        }
+    }
+
+    /// Answer a TCP receiver's `hello`; frames are then sent blocking, with `WRITE_TIMEOUT`, on the
+    /// receiver's own `TcpWriter` thread.
+    fn accept(stream: &TcpStream, hello: &[u8]) -> io::Result<Peer> {
+        let hello = Hello::parse(hello).ok_or_else(|| invalid_data("bad relay hello"))?;
+        let mut stream = stream.try_clone()?;
+        stream.set_nonblocking(false)?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        stream.set_nodelay(true)?;
+        stream.write_all(&accept_bytes(Peer::granted(&hello)))?;
+        Ok(Peer::new(PeerLink::Tcp(TcpWriter::spawn(stream)?), &hello))
+    }
+
+    /// Send one encoded frame (TCP receivers get it queued on their writer thread); an error means the
+    /// receiver is gone.
+    fn send(&self, link: &PeerLink, frame: &Arc<[u8]>, index: u32) -> io::Result<()> {
+        match (self, link) {
+            (_, PeerLink::Tcp(writer)) => writer.send(frame),
+            (Endpoint::Udp(socket), PeerLink::Udp(addr)) => {
+                let chunks = frame.chunks(DATAGRAM_LEN - CHUNK_HEADER_LEN);
+                let count = u16::try_from(chunks.len()).map_err(|_| invalid_data("frame too large for UDP"))?;
+                let mut datagram = Vec::with_capacity(DATAGRAM_LEN);
+                for (i, chunk) in chunks.enumerate() {
+                    datagram.clear();
+                    datagram.extend_from_slice(&CHUNK_MAGIC);
+                    datagram.extend_from_slice(&index.to_le_bytes());
+                    datagram.extend_from_slice(&(i as u16).to_le_bytes());
+                    datagram.extend_from_slice(&count.to_le_bytes());
+                    datagram.extend_from_slice(chunk);
+                    // Lost or refused datagrams only cost this frame; receivers time out on their own.
+                    let _ = socket.send_to(&datagram, *addr);
+                }
+                Ok(())
+            }
+            (Endpoint::Tcp(_), PeerLink::Udp(_)) => Err(invalid_data("UDP receiver on a TCP sender")),
+        }
+    }
+}
+
+/// Streams a Syphon server's frames to `RelayReceiver`s. Frames are read back as RGBA8 on a worker thread
+/// and sent to each connected receiver as it asked (scaled, paced, compressed); the sender stops when the
+/// Syphon server goes away or it is dropped.
+pub struct RelaySender {
+    worker: Worker,
+    local_addr: SocketAddr,
+    receivers: Arc<AtomicUsize>,
+}
+
+impl RelaySender {
+    /// Relay the server in `description`, listening on `bind` (e.g. `"0.0.0.0:9870"`; port 0 picks one,
+    /// see `local_addr`).
+    pub fn new(description: &ServerDescription, bind: impl ToSocketAddrs, transport: RelayTransport) -> Option<Self> {
+        Self::try_new(description, bind, transport).ok()
+    }
+
+    /// Like `new`, reporting why the sender couldn't start (socket in use, no Syphon client, ...).
+    pub fn try_new(
+        description: &ServerDescription,
+        bind: impl ToSocketAddrs,
+        transport: RelayTransport,
+    ) -> Result<Self, SyphonError> {
+        let endpoint = Endpoint::bind(bind, transport).map_err(|e| socket_error("relay socket", e))?;
+        let local_addr = endpoint.local_addr().map_err(|e| socket_error("relay socket", e))?;
+        let description = description.clone();
+        let receivers = Arc::new(AtomicUsize::new(0));
+        let receiver_count = receivers.clone();
+        let (worker, ()) = Worker::spawn("syphon-relay-sender", move |ready, stop| {
+            let (frame_tx, frame_rx) = mpsc::channel();
+            let callback: NewFrameCallback = Box::new(move |_| {
+                let _ = frame_tx.send(());
+            });
+            let client = match CpuClient::try_new(&description, Some(callback)) {
+                Ok(client) => client,
+                Err(e) => {
+                    let _ = ready.send(Err(e));
+                    return;
+                }
+            };
+            let _ = ready.send(Ok(()));
+            let mut peers = Vec::new();
+            let mut handshakes = Vec::new();
+            let mut index = 0u32;
+            while !stop.load(Ordering::Relaxed) {
+                endpoint.poll(&mut peers, &mut handshakes);
+                receiver_count.store(peers.len(), Ordering::Relaxed);
+                match frame_rx.recv_timeout(POLL_INTERVAL) {
+                    Ok(()) => {}
+                    Err(RecvTimeoutError::Timeout) if client.is_valid() => continue,
+                    Err(_) => break,
+                }
+                // Send only the newest frame if several arrived during the last send.
+                while frame_rx.try_recv().is_ok() {}
+                if peers.is_empty() {
+                    continue;
+                }
+                let Some(frame) = client.latest_frame() else {
+                    continue;
+                };
+                index = index.wrapping_add(1);
+                let now = Instant::now();
+                // Receivers asking for the same size and compression share one encoded frame.
+                let mut encoded: Vec<(EncodingKey, Option<Arc<[u8]>>)> = Vec::new();
+                peers.retain_mut(|peer| {
+                    if !peer.is_due(now) {
+                        return true;
+                    }
+                    let (width, height) = fit_size(frame.width, frame.height, peer.max_size);
+                    let key = ((width, height), peer.compression);
+                    let slot = match encoded.iter().position(|(k, _)| *k == key) {
+                        Some(slot) => slot,
+                        None => {
+                            let rgba = scale_frame(&frame, width, height);
+                            let bytes = encode_frame(index, &rgba, width, height, key.1).map(Arc::from);
+                            encoded.push((key, bytes));
+                            encoded.len() - 1
+                        }
+                    };
+                    let Some(bytes) = &encoded[slot].1 else {
+                        return true;
+                    };
+                    peer.last_sent = Some(now);
+                    endpoint.send(&peer.link, bytes, index).is_ok()
+                });
+            }
+        })?;
+        Ok(Self {
+            worker,
+            local_addr,
+            receivers,
+        })
+    }
+
+    /// The address receivers connect to.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Receivers currently connected.
+    pub fn receiver_count(&self) -> usize {
+        self.receivers.load(Ordering::Relaxed)
+    }
+
+    /// False once the sender has stopped because the Syphon server went away.
+    pub fn is_running(&self) -> bool {
+        self.worker.is_running()
+    }
+}
+
+/// Buffers partial reads from a stream with a read timeout (or a non-blocking one), so a timeout
+/// mid-frame loses nothing.
+#[derive(Default)]
+struct StreamBuffer {
+    buf: Vec<u8>,
+}
+
+impl StreamBuffer {
+    /// Read until `len` bytes are buffered and return them; Ok(None) if the read timed out (or would
+    /// block) first.
+    fn take(&mut self, stream: &mut impl Read, len: usize) -> io::Result<Option<Vec<u8>>> {
+        let mut chunk = [0; 64 * 1024];
+        while self.buf.len() < len {
+            let want = (len - self.buf.len()).min(chunk.len());
+            match stream.read(&mut chunk[..want]) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => self.buf.extend_from_slice(&chunk[..n]),
+                Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => return Ok(None),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(Some(std::mem::take(&mut self.buf)))
+    }
+}
+
+/// Collects the datagrams of the newest UDP frame.
+#[derive(Default)]
+struct FrameAssembly {
+    index: Option<u32>,
+    chunks: Vec<Option<Vec<u8>>>,
+    received: usize,
+}
+
+impl FrameAssembly {
+    /// Add one chunk datagram; the whole frame once its last chunk is in. Chunks of a frame older than
+    /// the one being collected are ignored, and a newer frame abandons an incomplete one.
+    fn add(&mut self, datagram: &[u8]) -> Option<Vec<u8>> {
+        if datagram.len() < CHUNK_HEADER_LEN || datagram[..4] != CHUNK_MAGIC {
+            return None;
+        }
+        let index = u32_at(datagram, 4);
+        let chunk = u16::from_le_bytes([datagram[8], datagram[9]]) as usize;
+        let count = u16::from_le_bytes([datagram[10], datagram[11]]) as usize;
+        match self.index {
+            Some(current) if current == index => {}
+            Some(current) if index.wrapping_sub(current) > u32::MAX / 2 => return None,
+            _ => {
+                self.index = Some(index);
+                self.chunks = vec![None; count];
+                self.received = 0;
+            }
+        }
+        let slot = self.chunks.get_mut(chunk)?;
+        if slot.is_none() {
+            *slot = Some(datagram[CHUNK_HEADER_LEN..].to_vec());
+            self.received += 1;
+        }
+        if self.received < self.chunks.len() {
+            return None;
+        }
+        self.received = 0;
+        Some(self.chunks.drain(..).flatten().flatten().collect())
+    }
+}
+
+/// Publish one received frame (header + payload); malformed frames are skipped.
+fn publish_frame(server: &CpuServer, header: &FrameHeader, payload: &[u8]) {
+    if let Some(rgba) = header.decode(payload) {
+        server.publish(&rgba, header.width, header.height);
+    }
+}
+
+/// One TCP session: connect, ask, then publish frames until the connection drops or `stop` is set.
+fn receive_tcp(
+    addrs: &[SocketAddr],
+    hello: Hello,
+    server: &CpuServer,
+    stop: &AtomicBool,
+    connected: &AtomicBool,
+) -> io::Result<()> {
+    let mut stream = addrs
+        .iter()
+        .find_map(|addr| TcpStream::connect_timeout(addr, CONNECT_TIMEOUT).ok())
+        .ok_or_else(|| io::Error::from(io::ErrorKind::ConnectionRefused))?;
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+    stream.write_all(&hello.to_bytes())?;
+    let mut buffer = StreamBuffer::default();
+    let mut header = None;
+    while !stop.load(Ordering::Relaxed) {
+        if !connected.load(Ordering::Relaxed) {
+            if let Some(accept) = buffer.take(&mut stream, ACCEPT_LEN)? {
+                parse_accept(&accept).ok_or_else(|| invalid_data("bad relay accept"))?;
+                connected.store(true, Ordering::Relaxed);
+            }
+            continue;
+        }
+        let current = match header {
+            Some(current) => current,
+            None => match buffer.take(&mut stream, FRAME_HEADER_LEN)? {
+                Some(bytes) => FrameHeader::parse(&bytes).ok_or_else(|| invalid_data("bad relay frame"))?,
+                None => continue,
+            },
+        };
+        header = Some(current);
+        if let Some(payload) = buffer.take(&mut stream, current.payload_len)? {
+            header = None;
+            publish_frame(server, &current, &payload);
+        }
+    }
+    Ok(())
+}
+
+/// One UDP session: send hellos as keepalives and publish complete frames, until the sender is silent
+/// for `PEER_TIMEOUT` or `stop` is set.
+fn receive_udp(
+    addrs: &[SocketAddr],
+    hello: Hello,
+    server: &CpuServer,
+    stop: &AtomicBool,
+    connected: &AtomicBool,
+) -> io::Result<()> {
+    let local: SocketAddr = match addrs.first() {
+        Some(SocketAddr::V6(_)) => "[::]:0".parse().expect("valid address"),
+        _ => "0.0.0.0:0".parse().expect("valid address"),
+    };
+    let socket = UdpSocket::bind(local)?;
+    socket.connect(addrs)?;
+    socket.set_read_timeout(Some(POLL_INTERVAL))?;
+    let mut assembly = FrameAssembly::default();
+    let mut datagram = vec![0; 64 * 1024];
+    let mut last_hello: Option<Instant> = None;
+    let mut last_heard = Instant::now();
+    while !stop.load(Ordering::Relaxed) {
+        if last_hello.is_none_or(|t| t.elapsed() >= KEEPALIVE_INTERVAL) {
+            socket.send(&hello.to_bytes())?;
+            last_hello = Some(Instant::now());
+        }
+        let len = match socket.recv(&mut datagram) {
+            Ok(len) => len,
+            Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+                if last_heard.elapsed() >= PEER_TIMEOUT {
+                    return Err(io::ErrorKind::TimedOut.into());
+                }
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        last_heard = Instant::now();
+        let datagram = &datagram[..len];
+        if parse_accept(datagram).is_some() {
+            connected.store(true, Ordering::Relaxed);
+        } else if let Some(frame) = assembly.add(datagram) {
+            let header = frame.get(..FRAME_HEADER_LEN).and_then(FrameHeader::parse);
+            if let Some(header) = header.filter(|h| frame.len() == FRAME_HEADER_LEN + h.payload_len) {
+                // Frames only come after an accept, even if that datagram was lost.
+                connected.store(true, Ordering::Relaxed);
+                publish_frame(server, &header, &frame[FRAME_HEADER_LEN..]);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Receives a `RelaySender`'s stream and publishes it as a Syphon server, reconnecting whenever the
+/// connection drops. The server exists (and keeps its last frame) while the sender is unreachable.
+pub struct RelayReceiver {
+    worker: Worker,
+    description: Option<ServerDescription>,
+    connected: Arc<AtomicBool>,
+}
+
+impl RelayReceiver {
+    /// Receive from the sender at `sender` and publish as the Syphon server `name`.
+    pub fn new(sender: impl ToSocketAddrs, name: Option<&str>, options: &RelayReceiverOptions) -> Option<Self> {
+        Self::try_new(sender, name, options).ok()
+    }
+
+    /// Like `new`, reporting why the receiver couldn't start: `InvalidArgument` for a compression this
+    /// build lacks or a zero size or rate, `CreationFailed` for an unresolvable address or no Syphon server.
+    pub fn try_new(
+        sender: impl ToSocketAddrs,
+        name: Option<&str>,
+        options: &RelayReceiverOptions,
+    ) -> Result<Self, SyphonError> {
+        if !options.compression.is_available() {
+            return Err(SyphonError::InvalidArgument(
+                "relay compression needs the relay-lz4 / relay-jpeg feature",
+            ));
+        }
+        if matches!(options.compression, RelayCompression::Jpeg { quality } if !(1..=100).contains(&quality)) {
+            return Err(SyphonError::InvalidArgument("JPEG quality must be 1-100"));
+        }
+        // The rate travels as an f32, so check what the sender will see.
+        let bad_fps = |f: f64| frame_interval(f as f32 as f64).is_none();
+        if options.max_size.is_some_and(|(w, h)| w == 0 || h == 0) || options.max_fps.is_some_and(bad_fps) {
+            return Err(SyphonError::InvalidArgument("relay size and frame rate limits must be positive"));
+        }
+        let addrs: Vec<SocketAddr> = sender
+            .to_socket_addrs()
+            .map_err(|e| socket_error("relay address", e))?
+            .collect();
+        if addrs.is_empty() {
+            return Err(socket_error("relay address", io::ErrorKind::NotFound.into()));
+        }
+        let clamp = |v: usize| u32::try_from(v).unwrap_or(u32::MAX);
+        let hello = Hello {
+            compression: options.compression,
+            max_width: options.max_size.map_or(0, |(w, _)| clamp(w)),
+            max_height: options.max_size.map_or(0, |(_, h)| clamp(h)),
+            max_fps: options.max_fps.unwrap_or(0.0) as f32,
+        };
+        let (transport, reconnect_delay) = (options.transport, options.reconnect_delay);
+        let name = name.map(str::to_owned);
+        let connected = Arc::new(AtomicBool::new(false));
+        let session_connected = connected.clone();
+        let (worker, description) = Worker::spawn("syphon-relay-receiver", move |ready, stop| {
+            let server = match CpuServer::try_new(name.as_deref(), None) {
+                Ok(server) => server,
+                Err(e) => {
+                    let _ = ready.send(Err(e));
+                    return;
+                }
+            };
+            let _ = ready.send(Ok(server.server_description()));
+            while !stop.load(Ordering::Relaxed) {
+                let _ = match transport {
+                    RelayTransport::Tcp => receive_tcp(&addrs, hello, &server, stop, &session_connected),
+                    RelayTransport::Udp => receive_udp(&addrs, hello, &server, stop, &session_connected),
+                };
+                session_connected.store(false, Ordering::Relaxed);
+                let retry = Instant::now() + reconnect_delay;
+                while Instant::now() < retry && !stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(POLL_INTERVAL.min(reconnect_delay));
+                }
+            }
+        })?;
+        Ok(Self {
+            worker,
+            description,
+            connected,
+        })
+    }
+
+    /// The description Syphon clients connect with.
+    pub fn server_description(&self) -> Option<ServerDescription> {
+        self.description.clone()
+    }
+
+    /// Whether the sender has accepted this receiver and the connection is up.
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// False if the receiver's thread has stopped.
+    pub fn is_running(&self) -> bool {
+        self.worker.is_running()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datagram(index: u32, chunk: u16, count: u16, data: &[u8]) -> Vec<u8> {
+        let mut out = CHUNK_MAGIC.to_vec();
+        out.extend_from_slice(&index.to_le_bytes());
+        out.extend_from_slice(&chunk.to_le_bytes());
+        out.extend_from_slice(&count.to_le_bytes());
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn header_bytes(width: u32, height: u32, compression: u8, payload_len: u32) -> Vec<u8> {
+        let mut out = 7u32.to_le_bytes().to_vec();
+        out.extend_from_slice(&width.to_le_bytes());
+        out.extend_from_slice(&height.to_le_bytes());
+        out.push(compression);
+        out.extend_from_slice(&payload_len.to_le_bytes());
+        out
+    }
+
+    #[test]
+    fn hello_round_trips() {
+        let hello = Hello {
+            compression: RelayCompression::Jpeg { quality: 80 },
+            max_width: 1920,
+            max_height: 1080,
+            max_fps: 29.97,
+        };
+        let bytes = hello.to_bytes();
+        assert_eq!(Hello::parse(&bytes), Some(hello));
+        assert_eq!(Hello::parse(&bytes[..HELLO_LEN - 1]), None);
+        let mut bad = bytes;
+        bad[4] = VERSION + 1;
+        assert_eq!(Hello::parse(&bad), None);
+        let mut bad = bytes;
+        bad[5] = 9;
+        assert_eq!(Hello::parse(&bad), None);
+    }
+
+    #[test]
+    fn accept_round_trips() {
+        for compression in [RelayCompression::None, RelayCompression::Lz4, RelayCompression::Jpeg { quality: 55 }] {
+            assert_eq!(parse_accept(&accept_bytes(compression)), Some(compression));
+        }
+        let mut bad = accept_bytes(RelayCompression::None);
+        bad[0] = b'X';
+        assert_eq!(parse_accept(&bad), None);
+        let hello = Hello {
+            compression: RelayCompression::None,
+            max_width: 0,
+            max_height: 0,
+            max_fps: 0.0,
+        };
+        assert_eq!(parse_accept(&hello.to_bytes()), None);
+    }
+
+    #[test]
+    fn frame_header_round_trips() {
+        let rgba = vec![9; 3 * 2 * 4];
+        let frame = encode_frame(7, &rgba, 3, 2, RelayCompression::None).unwrap();
+        let header = FrameHeader::parse(&frame[..FRAME_HEADER_LEN]).unwrap();
+        assert_eq!((header.width, header.height, header.payload_len, header.frame_len), (3, 2, 24, 24));
+        assert_eq!(header.decode(&frame[FRAME_HEADER_LEN..]), Some(rgba));
+    }
+
+    #[test]
+    fn frame_header_rejects_oversized_frames() {
+        let max = MAX_PAYLOAD as u32;
+        assert!(FrameHeader::parse(&header_bytes(16, 16, 0, max)).is_some());
+        assert!(FrameHeader::parse(&header_bytes(16, 16, 0, max + 1)).is_none());
+        // 16384 x 16384 RGBA8 is 1 GiB.
+        assert!(FrameHeader::parse(&header_bytes(16384, 16384, 0, 1024)).is_none());
+        // width x height x 4 overflows.
+        assert!(FrameHeader::parse(&header_bytes(u32::MAX, u32::MAX, 0, 1024)).is_none());
+    }
+
+    #[test]
+    fn decode_rejects_a_payload_of_the_wrong_size() {
+        let header = FrameHeader::parse(&header_bytes(2, 2, 0, 15)).unwrap();
+        assert_eq!(header.decode(&[0; 15]), None);
+        assert_eq!(header.decode(&[0; 16]).map(|rgba| rgba.len()), Some(16));
+    }
+
+    #[test]
+    fn assembly_puts_out_of_order_chunks_back_in_order() {
+        let mut assembly = FrameAssembly::default();
+        assert_eq!(assembly.add(&datagram(1, 2, 3, b"ef")), None);
+        assert_eq!(assembly.add(&datagram(1, 0, 3, b"ab")), None);
+        assert_eq!(assembly.add(&datagram(1, 1, 3, b"cd")), Some(b"abcdef".to_vec()));
+    }
+
+    #[test]
+    fn assembly_ignores_duplicate_chunks() {
+        let mut assembly = FrameAssembly::default();
+        assert_eq!(assembly.add(&datagram(1, 0, 2, b"ab")), None);
+        assert_eq!(assembly.add(&datagram(1, 0, 2, b"ab")), None);
+        assert_eq!(assembly.add(&datagram(1, 1, 2, b"cd")), Some(b"abcd".to_vec()));
+        // A late duplicate of a finished frame doesn't produce it again.
+        assert_eq!(assembly.add(&datagram(1, 1, 2, b"cd")), None);
+    }
+
+    #[test]
+    fn assembly_ignores_chunks_of_older_frames() {
+        let mut assembly = FrameAssembly::default();
+        assert_eq!(assembly.add(&datagram(5, 0, 2, b"ab")), None);
+        assert_eq!(assembly.add(&datagram(4, 1, 2, b"xx")), None);
+        assert_eq!(assembly.add(&datagram(5, 1, 2, b"cd")), Some(b"abcd".to_vec()));
+        // Older across the u32 wrap too.
+        let mut assembly = FrameAssembly::default();
+        assert_eq!(assembly.add(&datagram(1, 0, 2, b"ab")), None);
+        assert_eq!(assembly.add(&datagram(u32::MAX, 0, 1, b"xx")), None);
+        assert_eq!(assembly.add(&datagram(1, 1, 2, b"cd")), Some(b"abcd".to_vec()));
+    }
+
+    #[test]
+    fn assembly_abandons_an_incomplete_frame_for_a_newer_one() {
+        let mut assembly = FrameAssembly::default();
+        assert_eq!(assembly.add(&datagram(5, 0, 2, b"ab")), None);
+        assert_eq!(assembly.add(&datagram(6, 1, 2, b"gh")), None);
+        assert_eq!(assembly.add(&datagram(6, 0, 2, b"ef")), Some(b"efgh".to_vec()));
+        assert_eq!(assembly.add(&datagram(5, 1, 2, b"cd")), None);
+    }
+
+    #[test]
+    fn assembly_rejects_malformed_datagrams() {
+        let mut assembly = FrameAssembly::default();
+        assert_eq!(assembly.add(&datagram(1, 0, 1, b"ab")[..CHUNK_HEADER_LEN - 1]), None);
+        let mut bad = datagram(1, 0, 1, b"ab");
+        bad[0] = b'X';
+        assert_eq!(assembly.add(&bad), None);
+        assert_eq!(assembly.add(&datagram(2, 3, 2, b"ab")), None);
+    }
+
+    #[test]
+    fn tcp_writer_doesnt_wait_for_a_stalled_receiver() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        // Accepted but never read from.
+        let (_stalled, _) = listener.accept().unwrap();
+        let writer = TcpWriter::spawn(stream).unwrap();
+        let frame: Arc<[u8]> = vec![0; 64 << 20].into();
+        let start = Instant::now();
+        for _ in 0..4 {
+            writer.send(&frame).unwrap();
+        }
+        drop(writer);
+        assert!(start.elapsed() < WRITE_TIMEOUT);
+    }
+
+    #[cfg(feature = "relay-lz4")]
+    #[test]
+    fn lz4_rejects_a_size_prefix_that_doesnt_match_the_header() {
+        let rgba = vec![3; 4 * 4 * 4];
+        let payload = RelayCompression::Lz4.compress(&rgba, 4, 4).unwrap();
+        let code = RelayCompression::Lz4.code().0;
+        let header = FrameHeader::parse(&header_bytes(4, 4, code, payload.len() as u32)).unwrap();
+        assert_eq!(header.decode(&payload), Some(rgba));
+        let smaller = FrameHeader::parse(&header_bytes(2, 4, code, payload.len() as u32)).unwrap();
+        assert_eq!(smaller.decode(&payload), None);
+        // A prefix claiming far more than the header allows is rejected before decompressing.
+        let mut inflated = payload.clone();
+        inflated[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(header.decode(&inflated), None);
+        assert_eq!(header.decode(&payload[..3]), None);
+    }
+
+    #[cfg(feature = "relay-jpeg")]
+    #[test]
+    fn jpeg_rejects_a_size_that_doesnt_match_the_header() {
+        let rgba = vec![128; 8 * 8 * 4];
+        let jpeg = RelayCompression::Jpeg { quality: 90 };
+        let payload = jpeg.compress(&rgba, 8, 8).unwrap();
+        let code = jpeg.code().0;
+        let header = FrameHeader::parse(&header_bytes(8, 8, code, payload.len() as u32)).unwrap();
+        assert_eq!(header.decode(&payload).map(|rgba| rgba.len()), Some(rgba.len()));
+        let wider = FrameHeader::parse(&header_bytes(16, 8, code, payload.len() as u32)).unwrap();
+        assert_eq!(wider.decode(&payload), None);
+    }
+}
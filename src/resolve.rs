@@ -0,0 +1,70 @@
+//! Publishing from multisampled framebuffers: Syphon publishes single-sample textures, so an MSAA
+//! render target has to be resolved first. `MsaaResolver` keeps the resolve target between frames.
+
+#[cfg(target_os = "macos")]
+use std::ptr::NonNull;
+
+#[cfg(target_os = "macos")]
+use crate::ffi;
+use crate::{OpenGLServer, GL_TEXTURE_2D};
+
+/// Resolves a multisampled FBO into an owned single-sample RGBA8 `GL_TEXTURE_2D` (recreated when the
+/// frame size changes) and publishes it. Use and drop with the server's context current.
+pub struct MsaaResolver {
+    #[cfg(target_os = "macos")]
+    ptr: NonNull<std::ffi::c_void>,
+    texture: u32,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl MsaaResolver {
+    /// An empty resolver; GL objects are created by the first resolve. None off macOS.
+    pub fn new() -> Option<Self> {
+        #[cfg(target_os = "macos")]
+        {
+            let ptr = unsafe { ffi::syphon_gl_resolver_create() };
+            NonNull::new(ptr).map(|ptr| Self { ptr, texture: 0 })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Resolve `(0, 0, width, height)` of `msaa_fbo`'s color buffer (0 for the default framebuffer) and
+    /// return the resolved texture (owned by the resolver; valid until the next resolve), or None.
+    pub fn resolve(&mut self, msaa_fbo: u32, width: usize, height: usize) -> Option<u32> {
+        #[cfg(target_os = "macos")]
+        {
+            let texture = unsafe { ffi::syphon_gl_resolver_resolve(self.ptr.as_ptr(), msaa_fbo, width, height) };
+            self.texture = texture;
+            (texture != 0).then_some(texture)
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Resolve `msaa_fbo` and publish the result as the whole `width` x `height` frame. Returns false
+    /// if the resolve failed (incomplete framebuffer, GL error); nothing is published then.
+    pub fn resolve_and_publish(&mut self, server: &OpenGLServer, msaa_fbo: u32, width: usize, height: usize) -> bool {
+        match self.resolve(msaa_fbo, width, height) {
+            Some(texture) => {
+                server.publish_whole_texture(texture, GL_TEXTURE_2D, width as f64, height as f64, false);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The texture from the last resolve (0 if there was none or it failed).
+    pub fn texture(&self) -> u32 {
+        self.texture
+    }
+}
+
+impl Drop for MsaaResolver {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_gl_resolver_release(self.ptr.as_ptr());
+        }
+    }
+}
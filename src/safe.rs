@@ -3,6 +3,7 @@
 //! OpenGL: CGL context and GL usage must follow Syphon's and macOS's rules.
 //! Metal: pass `MTLDevice`/`MTLTexture`/`MTLCommandBuffer` pointers (e.g. from the `metal` crate).
 
+#[cfg(any(target_os = "macos", target_os = "windows"))]
 use std::ptr::NonNull;
 #[cfg(target_os = "macos")]
 use std::ffi::CStr;
@@ -13,6 +14,7 @@ use std::os::raw::c_char;
 use crate::ffi;
 #[cfg(target_os = "windows")]
 use crate::ffi as spout_ffi;
+use crate::SyphonError;
 
 /// CGL context (from OpenGL/OpenGL.h). On macOS this is the real type from the FFI; elsewhere a placeholder.
 #[cfg(target_os = "macos")]
@@ -22,94 +24,541 @@ pub type CGLContextObj = *mut std::ffi::c_void;
 
 /// OpenGL texture target for rectangle textures (Syphon uses this).
 pub const GL_TEXTURE_RECTANGLE: u32 = 0x84F5;
+/// OpenGL 2D texture target (normalized coordinates; also accepted by Syphon's publish).
+pub const GL_TEXTURE_2D: u32 = 0x0DE1;
 
 /// Create a headless CGL context for offscreen OpenGL (e.g. tests). Caller must destroy with `cgl_destroy_context`.
-#[cfg(target_os = "macos")]
 pub fn cgl_create_headless_context() -> Option<CGLContextObj> {
-    let ctx = unsafe { ffi::syphon_cgl_create_headless_context() };
-    if ctx.is_null() {
-        None
-    } else {
-        Some(ctx)
+    try_cgl_create_headless_context().ok()
+}
+
+/// Like `cgl_create_headless_context`, reporting the CGL error on failure.
+pub fn try_cgl_create_headless_context() -> Result<CGLContextObj, SyphonError> {
+    #[cfg(target_os = "macos")]
+    {
+        let ctx = unsafe { ffi::syphon_cgl_create_headless_context() };
+        if ctx.is_null() {
+            Err(creation_failed("CGL context"))
+        } else {
+            Ok(ctx)
+        }
     }
+    #[cfg(not(target_os = "macos"))]
+    Err(SyphonError::Unsupported)
 }
-#[cfg(not(target_os = "macos"))]
-pub fn cgl_create_headless_context() -> Option<CGLContextObj> {
-    None
+
+/// Pixel format choices for a headless CGL context; see `HeadlessContext::builder`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeadlessContextBuilder {
+    core_profile: bool,
+    color_float: bool,
+    samples: u32,
+}
+
+impl Default for HeadlessContextBuilder {
+    fn default() -> Self {
+        Self {
+            core_profile: true,
+            color_float: false,
+            samples: 0,
+        }
+    }
+}
+
+impl HeadlessContextBuilder {
+    /// 3.2 core profile (default) or a legacy (2.1) context.
+    pub fn core_profile(mut self, core_profile: bool) -> Self {
+        self.core_profile = core_profile;
+        self
+    }
+
+    /// 16-bit float color (64-bit pixels) instead of RGBA8, for HDR rendering.
+    pub fn color_float(mut self, color_float: bool) -> Self {
+        self.color_float = color_float;
+        self
+    }
+
+    /// Multisample count for the default framebuffer (0, the default, disables multisampling).
+    pub fn samples(mut self, samples: u32) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    /// Create the context. Returns None if no pixel format matches or creation failed.
+    pub fn build(self) -> Option<HeadlessContext> {
+        self.try_build().ok()
+    }
+
+    /// Like `build`, reporting the CGL error on failure.
+    pub fn try_build(self) -> Result<HeadlessContext, SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            let ctx =
+                unsafe { ffi::syphon_cgl_create_headless_context_with(self.core_profile, self.color_float, self.samples) };
+            if ctx.is_null() {
+                Err(creation_failed("CGL context"))
+            } else {
+                Ok(HeadlessContext { ctx })
+            }
+        }
+        #[cfg(not(target_os = "macos"))]
+        Err(SyphonError::Unsupported)
+    }
+}
+
+/// An owned headless CGL context, destroyed on drop. Use `context()` wherever a `CGLContextObj` is taken.
+pub struct HeadlessContext {
+    ctx: CGLContextObj,
+}
+
+impl HeadlessContext {
+    /// Choose the profile, color depth and multisampling before creating the context.
+    pub fn builder() -> HeadlessContextBuilder {
+        HeadlessContextBuilder::default()
+    }
+
+    /// The CGL context.
+    pub fn context(&self) -> CGLContextObj {
+        self.ctx
+    }
+
+    /// Make the context current on this thread (`cgl_make_current`).
+    pub fn make_current(&self) {
+        cgl_make_current(self.ctx);
+    }
+
+    /// Give up ownership; the caller must destroy the context with `cgl_destroy_context`.
+    pub fn into_raw(self) -> CGLContextObj {
+        let ctx = self.ctx;
+        std::mem::forget(self);
+        ctx
+    }
+}
+
+impl Drop for HeadlessContext {
+    fn drop(&mut self) {
+        cgl_destroy_context(self.ctx);
+    }
 }
 
 /// Destroy a CGL context created with `cgl_create_headless_context`.
 #[cfg(target_os = "macos")]
 pub fn cgl_destroy_context(ctx: CGLContextObj) {
     if !ctx.is_null() {
+        context_threads().remove(&(ctx as usize));
         unsafe { ffi::syphon_cgl_destroy_context(ctx) };
     }
 }
 #[cfg(not(target_os = "macos"))]
 pub fn cgl_destroy_context(_ctx: CGLContextObj) {}
 
+/// Thread each context was last made current on through `cgl_make_current`, for `WrongThread` reports.
+#[cfg(target_os = "macos")]
+fn context_threads() -> std::sync::MutexGuard<'static, std::collections::HashMap<usize, std::thread::ThreadId>> {
+    static THREADS: std::sync::OnceLock<std::sync::Mutex<std::collections::HashMap<usize, std::thread::ThreadId>>> =
+        std::sync::OnceLock::new();
+    THREADS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
 /// Make the given CGL context current on this thread.
 #[cfg(target_os = "macos")]
 pub fn cgl_make_current(ctx: CGLContextObj) {
     unsafe { ffi::syphon_cgl_make_current(ctx) };
+    let this = std::thread::current().id();
+    let mut threads = context_threads();
+    threads.retain(|_, t| *t != this);
+    if !ctx.is_null() {
+        threads.insert(ctx as usize, this);
+    }
 }
 #[cfg(not(target_os = "macos"))]
 pub fn cgl_make_current(_ctx: CGLContextObj) {}
 
-/// Create a GL_TEXTURE_RECTANGLE RGBA8 texture and upload `rgba` (width*height*4 bytes). CGL context must be current. Returns 0 on failure.
-#[cfg(target_os = "macos")]
-pub fn gl_create_texture_rectangle_rgba8(width: usize, height: usize, rgba: &[u8]) -> u32 {
-    let expected = width * height * 4;
-    if rgba.len() < expected {
-        return 0;
+/// The CGL context current on this thread (null if none).
+pub fn cgl_current_context() -> CGLContextObj {
+    #[cfg(target_os = "macos")]
+    unsafe { ffi::syphon_cgl_get_current_context() }
+    #[cfg(not(target_os = "macos"))]
+    std::ptr::null_mut()
+}
+
+/// The CGL context behind an `NSOpenGLContext*` (e.g. a windowed app's view context), for creating
+/// servers and clients on it. None if `context` is null.
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+pub fn cgl_context_from_nsopengl_context(context: *mut std::ffi::c_void) -> Option<CGLContextObj> {
+    #[cfg(target_os = "macos")]
+    {
+        let ctx = unsafe { ffi::syphon_cgl_context_from_nsopengl_context(context) };
+        (!ctx.is_null()).then_some(ctx)
     }
+    #[cfg(not(target_os = "macos"))]
+    None
+}
+
+/// True if `ctx` uses a 3.2+ core profile (false for legacy contexts and null). Syphon 5+ servers and
+/// clients draw with either profile; on older frameworks, which draw fixed-function, creating one with
+/// a core-profile context fails.
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+pub fn cgl_context_is_core_profile(ctx: CGLContextObj) -> bool {
+    #[cfg(target_os = "macos")]
     unsafe {
-        ffi::syphon_gl_create_texture_rectangle_rgba8(width, height, rgba.as_ptr())
+        ffi::syphon_cgl_context_is_core_profile(ctx)
     }
+    #[cfg(not(target_os = "macos"))]
+    false
 }
-#[cfg(not(target_os = "macos"))]
-pub fn gl_create_texture_rectangle_rgba8(_width: usize, _height: usize, _rgba: &[u8]) -> u32 {
-    0
+
+/// Check that `expected` is current on this thread (or, if None, that any context is).
+/// Reports `WrongThread` when `expected` was made current on another thread via `cgl_make_current`.
+pub fn check_current_context(expected: Option<CGLContextObj>) -> Result<(), SyphonError> {
+    let current = cgl_current_context();
+    let actual = (!current.is_null()).then_some(current as usize);
+    match expected {
+        None if actual.is_some() => return Ok(()),
+        Some(ctx) if ctx == current && !ctx.is_null() => return Ok(()),
+        _ => {}
+    }
+    #[cfg(target_os = "macos")]
+    if let Some(ctx) = expected {
+        let this = std::thread::current().id();
+        if let Some(&owner) = context_threads().get(&(ctx as usize)) {
+            if owner != this {
+                return Err(SyphonError::WrongThread {
+                    context: ctx as usize,
+                    expected: owner,
+                    actual: this,
+                });
+            }
+        }
+    }
+    Err(SyphonError::NoCurrentContext {
+        expected: expected.map(|ctx| ctx as usize),
+        actual,
+    })
+}
+
+/// Create a GL_TEXTURE_RECTANGLE RGBA8 texture and upload `rgba` (width*height*4 bytes). CGL context must be current. Returns 0 on failure.
+pub fn gl_create_texture_rectangle_rgba8(width: usize, height: usize, rgba: &[u8]) -> u32 {
+    try_gl_create_texture_rectangle_rgba8(width, height, rgba).unwrap_or(0)
+}
+
+/// Like `gl_create_texture_rectangle_rgba8`, reporting a missing current context as an error.
+/// Ok(0) means GL failed or `rgba` is too short.
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+pub fn try_gl_create_texture_rectangle_rgba8(width: usize, height: usize, rgba: &[u8]) -> Result<u32, SyphonError> {
+    check_current_context(None)?;
+    #[cfg(target_os = "macos")]
+    {
+        if rgba.len() < width * height * 4 {
+            return Ok(0);
+        }
+        Ok(unsafe { ffi::syphon_gl_create_texture_rectangle_rgba8(width, height, rgba.as_ptr()) })
+    }
+    #[cfg(not(target_os = "macos"))]
+    Ok(0)
 }
 
 /// Read back a GL_TEXTURE_RECTANGLE texture into `out_rgba` (width*height*4 bytes). CGL context must be current.
-#[cfg(target_os = "macos")]
 pub fn gl_read_texture_rectangle_rgba8(tex_id: u32, width: usize, height: usize, out_rgba: &mut [u8]) {
-    let expected = width * height * 4;
-    if out_rgba.len() < expected {
-        return;
+    let _ = try_gl_read_texture_rectangle_rgba8(tex_id, width, height, out_rgba);
+}
+
+/// Like `gl_read_texture_rectangle_rgba8`, reporting a missing current context as an error.
+/// Ok(false) means `out_rgba` is too short.
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+pub fn try_gl_read_texture_rectangle_rgba8(
+    tex_id: u32,
+    width: usize,
+    height: usize,
+    out_rgba: &mut [u8],
+) -> Result<bool, SyphonError> {
+    check_current_context(None)?;
+    #[cfg(target_os = "macos")]
+    {
+        if out_rgba.len() < width * height * 4 {
+            return Ok(false);
+        }
+        unsafe {
+            ffi::syphon_gl_read_texture_rectangle_rgba8(tex_id, width, height, out_rgba.as_mut_ptr());
+        }
+        Ok(true)
     }
-    unsafe {
-        ffi::syphon_gl_read_texture_rectangle_rgba8(tex_id, width, height, out_rgba.as_mut_ptr());
+    #[cfg(not(target_os = "macos"))]
+    Ok(false)
+}
+
+/// Create a GL_TEXTURE_2D RGBA8 texture of any size (NPOT included: linear filtering, no mipmaps,
+/// clamped to edge) and upload `rgba` (width*height*4 bytes). CGL context must be current. Returns 0 on failure.
+pub fn gl_create_texture_2d_rgba8(width: usize, height: usize, rgba: &[u8]) -> u32 {
+    try_gl_create_texture_2d_rgba8(width, height, rgba).unwrap_or(0)
+}
+
+/// Like `gl_create_texture_2d_rgba8`, reporting a missing current context as an error.
+/// Ok(0) means GL failed, or `rgba` is too short (or the size overflows).
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+pub fn try_gl_create_texture_2d_rgba8(width: usize, height: usize, rgba: &[u8]) -> Result<u32, SyphonError> {
+    check_current_context(None)?;
+    #[cfg(target_os = "macos")]
+    {
+        if width.checked_mul(height).and_then(|n| n.checked_mul(4)).is_none_or(|n| rgba.len() < n) {
+            return Ok(0);
+        }
+        Ok(unsafe { ffi::syphon_gl_create_texture_2d_rgba8(width, height, rgba.as_ptr()) })
     }
+    #[cfg(not(target_os = "macos"))]
+    Ok(0)
 }
-#[cfg(not(target_os = "macos"))]
-pub fn gl_read_texture_rectangle_rgba8(_tex_id: u32, _width: usize, _height: usize, _out_rgba: &mut [u8]) {
+
+/// Read back a GL_TEXTURE_2D texture into `out_rgba` (width*height*4 bytes, row y=0 first). CGL context must be current.
+pub fn gl_read_texture_2d_rgba8(tex_id: u32, width: usize, height: usize, out_rgba: &mut [u8]) {
+    let _ = try_gl_read_texture_2d_rgba8(tex_id, width, height, out_rgba);
+}
+
+/// Like `gl_read_texture_2d_rgba8`, reporting a missing current context as an error.
+/// Ok(false) means `out_rgba` is too short (or the size overflows).
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+pub fn try_gl_read_texture_2d_rgba8(
+    tex_id: u32,
+    width: usize,
+    height: usize,
+    out_rgba: &mut [u8],
+) -> Result<bool, SyphonError> {
+    check_current_context(None)?;
+    #[cfg(target_os = "macos")]
+    {
+        if width.checked_mul(height).and_then(|n| n.checked_mul(4)).is_none_or(|n| out_rgba.len() < n) {
+            return Ok(false);
+        }
+        unsafe {
+            ffi::syphon_gl_read_texture_2d_rgba8(tex_id, width, height, out_rgba.as_mut_ptr());
+        }
+        Ok(true)
+    }
+    #[cfg(not(target_os = "macos"))]
+    Ok(false)
+}
+
+/// Glue format codes for `syphon_gl_create_texture` / `syphon_gl_read_texture`.
+const GL_PIXELS_BGRA8: u32 = 1;
+const GL_PIXELS_RGBA16F: u32 = 2;
+
+/// Create a texture from 4-component pixels (`width*height*4` elements of `T`). Ok(0) if GL failed or
+/// `pixels` is too short (or the size overflows).
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+fn try_gl_create_texture<T>(target: u32, format: u32, width: usize, height: usize, pixels: &[T]) -> Result<u32, SyphonError> {
+    check_current_context(None)?;
+    #[cfg(target_os = "macos")]
+    {
+        if width.checked_mul(height).and_then(|n| n.checked_mul(4)).is_none_or(|n| pixels.len() < n) {
+            return Ok(0);
+        }
+        Ok(unsafe { ffi::syphon_gl_create_texture(target, format, width, height, pixels.as_ptr().cast()) })
+    }
+    #[cfg(not(target_os = "macos"))]
+    Ok(0)
+}
+
+/// Read a whole texture as 4-component pixels into `out` (`width*height*4` elements). Ok(false) if `out`
+/// is too short (or the size overflows).
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+fn try_gl_read_texture<T>(
+    target: u32,
+    format: u32,
+    tex_id: u32,
+    width: usize,
+    height: usize,
+    out: &mut [T],
+) -> Result<bool, SyphonError> {
+    check_current_context(None)?;
+    #[cfg(target_os = "macos")]
+    {
+        if width.checked_mul(height).and_then(|n| n.checked_mul(4)).is_none_or(|n| out.len() < n) {
+            return Ok(false);
+        }
+        Ok(unsafe { ffi::syphon_gl_read_texture(target, tex_id, format, width, height, out.as_mut_ptr().cast()) })
+    }
+    #[cfg(not(target_os = "macos"))]
+    Ok(false)
+}
+
+/// Create a GL_TEXTURE_RECTANGLE BGRA8 texture and upload `pixels` (width*height*4 bytes, B G R A order). CGL context must be current.
+/// Returns 0 on failure.
+pub fn gl_create_texture_rectangle_bgra8(width: usize, height: usize, pixels: &[u8]) -> u32 {
+    try_gl_create_texture_rectangle_bgra8(width, height, pixels).unwrap_or(0)
+}
+
+/// Like `gl_create_texture_rectangle_bgra8`, reporting a missing current context as an error.
+pub fn try_gl_create_texture_rectangle_bgra8(width: usize, height: usize, pixels: &[u8]) -> Result<u32, SyphonError> {
+    try_gl_create_texture(GL_TEXTURE_RECTANGLE, GL_PIXELS_BGRA8, width, height, pixels)
+}
+
+/// Read back a GL_TEXTURE_RECTANGLE texture as BGRA8 into `out` (width*height*4 bytes, B G R A order, row y=0 first). CGL context must be current.
+pub fn gl_read_texture_rectangle_bgra8(tex_id: u32, width: usize, height: usize, out: &mut [u8]) {
+    let _ = try_gl_read_texture_rectangle_bgra8(tex_id, width, height, out);
+}
+
+/// Like `gl_read_texture_rectangle_bgra8`, reporting a missing current context as an error. Ok(false) means
+/// `out` is too short.
+pub fn try_gl_read_texture_rectangle_bgra8(tex_id: u32, width: usize, height: usize, out: &mut [u8]) -> Result<bool, SyphonError> {
+    try_gl_read_texture(GL_TEXTURE_RECTANGLE, GL_PIXELS_BGRA8, tex_id, width, height, out)
+}
+
+
+/// Create a GL_TEXTURE_2D BGRA8 texture and upload `pixels` (width*height*4 bytes, B G R A order). CGL context must be current.
+/// Returns 0 on failure.
+pub fn gl_create_texture_2d_bgra8(width: usize, height: usize, pixels: &[u8]) -> u32 {
+    try_gl_create_texture_2d_bgra8(width, height, pixels).unwrap_or(0)
+}
+
+/// Like `gl_create_texture_2d_bgra8`, reporting a missing current context as an error.
+pub fn try_gl_create_texture_2d_bgra8(width: usize, height: usize, pixels: &[u8]) -> Result<u32, SyphonError> {
+    try_gl_create_texture(GL_TEXTURE_2D, GL_PIXELS_BGRA8, width, height, pixels)
+}
+
+/// Read back a GL_TEXTURE_2D texture as BGRA8 into `out` (width*height*4 bytes, B G R A order, row y=0 first). CGL context must be current.
+pub fn gl_read_texture_2d_bgra8(tex_id: u32, width: usize, height: usize, out: &mut [u8]) {
+    let _ = try_gl_read_texture_2d_bgra8(tex_id, width, height, out);
+}
+
+/// Like `gl_read_texture_2d_bgra8`, reporting a missing current context as an error. Ok(false) means
+/// `out` is too short.
+pub fn try_gl_read_texture_2d_bgra8(tex_id: u32, width: usize, height: usize, out: &mut [u8]) -> Result<bool, SyphonError> {
+    try_gl_read_texture(GL_TEXTURE_2D, GL_PIXELS_BGRA8, tex_id, width, height, out)
+}
+
+
+/// Create a GL_TEXTURE_RECTANGLE RGBA16F texture and upload `pixels` (width*height*4 half floats as raw bits). CGL context must be current.
+/// Returns 0 on failure.
+pub fn gl_create_texture_rectangle_rgba16f(width: usize, height: usize, pixels: &[u16]) -> u32 {
+    try_gl_create_texture_rectangle_rgba16f(width, height, pixels).unwrap_or(0)
+}
+
+/// Like `gl_create_texture_rectangle_rgba16f`, reporting a missing current context as an error.
+pub fn try_gl_create_texture_rectangle_rgba16f(width: usize, height: usize, pixels: &[u16]) -> Result<u32, SyphonError> {
+    try_gl_create_texture(GL_TEXTURE_RECTANGLE, GL_PIXELS_RGBA16F, width, height, pixels)
+}
+
+/// Read back a GL_TEXTURE_RECTANGLE texture as RGBA16F into `out` (width*height*4 half floats as raw bits, row y=0 first). CGL context must be current.
+pub fn gl_read_texture_rectangle_rgba16f(tex_id: u32, width: usize, height: usize, out: &mut [u16]) {
+    let _ = try_gl_read_texture_rectangle_rgba16f(tex_id, width, height, out);
+}
+
+/// Like `gl_read_texture_rectangle_rgba16f`, reporting a missing current context as an error. Ok(false) means
+/// `out` is too short.
+pub fn try_gl_read_texture_rectangle_rgba16f(tex_id: u32, width: usize, height: usize, out: &mut [u16]) -> Result<bool, SyphonError> {
+    try_gl_read_texture(GL_TEXTURE_RECTANGLE, GL_PIXELS_RGBA16F, tex_id, width, height, out)
+}
+
+
+/// Create a GL_TEXTURE_2D RGBA16F texture and upload `pixels` (width*height*4 half floats as raw bits). CGL context must be current.
+/// Returns 0 on failure.
+pub fn gl_create_texture_2d_rgba16f(width: usize, height: usize, pixels: &[u16]) -> u32 {
+    try_gl_create_texture_2d_rgba16f(width, height, pixels).unwrap_or(0)
+}
+
+/// Like `gl_create_texture_2d_rgba16f`, reporting a missing current context as an error.
+pub fn try_gl_create_texture_2d_rgba16f(width: usize, height: usize, pixels: &[u16]) -> Result<u32, SyphonError> {
+    try_gl_create_texture(GL_TEXTURE_2D, GL_PIXELS_RGBA16F, width, height, pixels)
+}
+
+/// Read back a GL_TEXTURE_2D texture as RGBA16F into `out` (width*height*4 half floats as raw bits, row y=0 first). CGL context must be current.
+pub fn gl_read_texture_2d_rgba16f(tex_id: u32, width: usize, height: usize, out: &mut [u16]) {
+    let _ = try_gl_read_texture_2d_rgba16f(tex_id, width, height, out);
+}
+
+/// Like `gl_read_texture_2d_rgba16f`, reporting a missing current context as an error. Ok(false) means
+/// `out` is too short.
+pub fn try_gl_read_texture_2d_rgba16f(tex_id: u32, width: usize, height: usize, out: &mut [u16]) -> Result<bool, SyphonError> {
+    try_gl_read_texture(GL_TEXTURE_2D, GL_PIXELS_RGBA16F, tex_id, width, height, out)
+}
+
+/// Upload RGBA8 pixels into a region of an existing GL texture (rectangle or 2D). CGL context must be current.
+pub fn gl_write_texture_region_rgba8(
+    tex_id: u32,
+    target: u32,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    rgba: &[u8],
+) -> bool {
+    try_gl_write_texture_region_rgba8(tex_id, target, x, y, width, height, rgba).unwrap_or(false)
+}
+
+/// Like `gl_write_texture_region_rgba8`, reporting a missing current context as an error.
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+pub fn try_gl_write_texture_region_rgba8(
+    tex_id: u32,
+    target: u32,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    rgba: &[u8],
+) -> Result<bool, SyphonError> {
+    check_current_context(None)?;
+    #[cfg(target_os = "macos")]
+    {
+        if tex_id == 0 || rgba.len() < width * height * 4 {
+            return Ok(false);
+        }
+        Ok(unsafe { ffi::syphon_gl_write_texture_region_rgba8(tex_id, target, x, y, width, height, rgba.as_ptr()) })
+    }
+    #[cfg(not(target_os = "macos"))]
+    Ok(false)
+}
+
+/// Copy a received frame into your own texture `dest_tex` (`dest_target`: `GL_TEXTURE_RECTANGLE` or
+/// `GL_TEXTURE_2D`, framebuffer-attachable), filling `(0, 0, width, height)` and scaling with linear
+/// filtering if that differs from the frame size. The frame then belongs to you and `image` can be
+/// dropped at once, as Syphon recommends. The image's context must be current. False if GL refused the blit.
+pub fn gl_copy_image_to_texture(image: &OpenGLImage, dest_tex: u32, dest_target: u32, width: usize, height: usize) -> bool {
+    try_gl_copy_image_to_texture(image, dest_tex, dest_target, width, height).unwrap_or(false)
+}
+
+/// Like `gl_copy_image_to_texture`, reporting a missing current context as an error.
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+pub fn try_gl_copy_image_to_texture(
+    image: &OpenGLImage,
+    dest_tex: u32,
+    dest_target: u32,
+    width: usize,
+    height: usize,
+) -> Result<bool, SyphonError> {
+    check_current_context(None)?;
+    #[cfg(target_os = "macos")]
+    {
+        Ok(unsafe { ffi::syphon_opengl_image_blit_to_texture(image.ptr.as_ptr(), dest_tex, dest_target, width, height) })
+    }
+    #[cfg(not(target_os = "macos"))]
+    Ok(false)
 }
 
 /// Delete a GL texture created with `gl_create_texture_rectangle_rgba8` or returned by Syphon.
+/// Does nothing if no context is current.
 #[cfg(target_os = "macos")]
 pub fn gl_delete_texture(tex_id: u32) {
-    if tex_id != 0 {
+    if tex_id != 0 && check_current_context(None).is_ok() {
         unsafe { ffi::syphon_gl_delete_texture(tex_id) };
     }
 }
 #[cfg(not(target_os = "macos"))]
 pub fn gl_delete_texture(_tex_id: u32) {}
 
-/// Server directory: shared singleton listing available Syphon servers.
+/// Server directory: shared singleton listing available Syphon servers. On Windows it lists Spout
+/// senders instead, so the same discovery code runs on both platforms.
 pub struct ServerDirectory {
     #[cfg(target_os = "macos")]
     ptr: NonNull<std::ffi::c_void>,
+    #[cfg(target_os = "windows")]
+    spout: Spout,
 }
 
-/// Result of a server directory query by name/app name. Release when done (implements Drop).
-pub struct ServerDirectoryMatch {
-    #[cfg(target_os = "macos")]
-    ptr: NonNull<std::ffi::c_void>,
-}
-
-/// Builder for server creation options (private, antialias, depth/stencil). Pass to `OpenGLServer::new` or `MetalServer::new`.
+/// Builder for Syphon options (an `NSDictionary` in the glue): server options (private, antialias,
+/// depth/stencil) for `OpenGLServer::new` / `MetalServer::new`, and any keys for the client constructors.
 pub struct SyphonOptions {
     #[cfg(target_os = "macos")]
     ptr: NonNull<std::ffi::c_void>,
@@ -148,14 +597,127 @@ pub fn notification_name_server_retire() -> Option<String> {
     None
 }
 
+/// A region in pixels, for the publish APIs (bottom-left origin for OpenGL, as Syphon expects).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub w: f64,
+    pub h: f64,
+}
+
+impl Rect {
+    pub fn new(x: f64, y: f64, w: f64, h: f64) -> Self {
+        Self { x, y, w, h }
+    }
+
+    /// The whole of a texture of `size`, at the origin.
+    pub fn from_size(size: Size) -> Self {
+        Self::new(0.0, 0.0, size.w, size.h)
+    }
+}
+
+/// A width and height in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Size {
+    pub w: f64,
+    pub h: f64,
+}
+
+impl Size {
+    pub fn new(w: f64, h: f64) -> Self {
+        Self { w, h }
+    }
+}
+
+/// `values` as pixel counts; None unless each is a whole, non-negative number.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn whole_pixels<const N: usize>(values: [f64; N]) -> Option<[usize; N]> {
+    let mut out = [0; N];
+    for (out, value) in out.iter_mut().zip(values) {
+        if !(value >= 0.0 && value.fract() == 0.0 && value <= usize::MAX as f64) {
+            return None;
+        }
+        *out = value as usize;
+    }
+    Some(out)
+}
+
+/// Frame dimensions and pixel format reported by `probe_server_size`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerFrameSize {
+    pub width: usize,
+    pub height: usize,
+    /// Raw `MTLPixelFormat` value of the server's surface (e.g. 80 = `BGRA8Unorm`).
+    pub pixel_format: u64,
+}
+
+/// Briefly attach a throwaway client to `description` and report the current frame size and pixel
+/// format, waiting up to `timeout` for a frame. For source pickers that show resolutions without
+/// managing a client. Returns None if no frame arrived in time.
+pub fn probe_server_size(description: &ServerDescription, timeout: std::time::Duration) -> Option<ServerFrameSize> {
+    #[cfg(target_os = "macos")]
+    {
+        let (mut width, mut height, mut pixel_format) = (0usize, 0usize, 0u64);
+        let ok = unsafe {
+            ffi::syphon_probe_server_frame(
+                description.ptr.as_ptr(),
+                timeout.as_secs_f64(),
+                &mut width,
+                &mut height,
+                &mut pixel_format,
+            )
+        };
+        ok.then_some(ServerFrameSize {
+            width,
+            height,
+            pixel_format,
+        })
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = (description, timeout);
+        None
+    }
+}
+
 /// A description of a Syphon server (from the directory or from a server's `server_description`).
-/// If you retain it for longer than a directory snapshot, use `retain`/`release` or clone the strings.
+/// Each instance holds its own retain, so it stays valid after the directory updates; clone to share.
+/// On Windows it is a snapshot of a Spout sender (name, size, format, share handle) taken when the
+/// directory was read.
 pub struct ServerDescription {
     #[cfg(target_os = "macos")]
     ptr: NonNull<std::ffi::c_void>,
-    /// If true, we own a retain and must release on drop.
-    #[cfg(target_os = "macos")]
-    owned: bool,
+    #[cfg(target_os = "windows")]
+    sender: SpoutSenderDescription,
+}
+
+// Descriptions are immutable NSDictionaries, and retain/release are thread-safe.
+#[cfg(target_os = "macos")]
+unsafe impl Send for ServerDescription {}
+#[cfg(target_os = "macos")]
+unsafe impl Sync for ServerDescription {}
+
+/// A Spout sender as listed when the directory was read.
+#[cfg(target_os = "windows")]
+#[derive(Clone)]
+struct SpoutSenderDescription {
+    name: String,
+    info: SpoutSenderInfo,
+}
+
+// The share handle is a system-wide DXGI handle value, never dereferenced here.
+#[cfg(target_os = "windows")]
+unsafe impl Send for ServerDescription {}
+#[cfg(target_os = "windows")]
+unsafe impl Sync for ServerDescription {}
+
+/// Application icon bitmap from `ServerDescription::app_icon`: premultiplied RGBA8, top row first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppIcon {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
 }
 
 /// OpenGL Syphon server: publishes frames to clients.
@@ -168,9 +730,6 @@ pub struct OpenGLServer {
 pub struct OpenGLClient {
     #[cfg(target_os = "macos")]
     ptr: NonNull<std::ffi::c_void>,
-    /// Keeps the callback alive and gives a stable pointer to the C side.
-    #[cfg(target_os = "macos")]
-    _callback_storage: Option<Box<CallbackHolder>>,
 }
 
 /// A single frame image from a client. Release promptly after drawing.
@@ -188,6 +747,21 @@ pub type MTLTexturePtr = *mut std::ffi::c_void;
 /// Opaque pointer to MTLCommandBuffer. Use when publishing a frame on the Metal server.
 pub type MTLCommandBufferPtr = *mut std::ffi::c_void;
 
+/// Opaque pointer to MTLCommandQueue.
+pub type MTLCommandQueuePtr = *mut std::ffi::c_void;
+
+/// Opaque pointer to a CAMetalDrawable (e.g. from `CAMetalLayer::nextDrawable`).
+pub type CAMetalDrawablePtr = *mut std::ffi::c_void;
+
+/// Opaque pointer to an MTLSharedEvent (e.g. from `metal::Device::new_shared_event`).
+pub type MTLSharedEventPtr = *mut std::ffi::c_void;
+
+/// Opaque pointer to an IOSurfaceRef (the shared surface behind a Syphon frame).
+pub type IOSurfacePtr = *mut std::ffi::c_void;
+
+/// Opaque pointer to a Core Image `CIImage`.
+pub type CIImagePtr = *mut std::ffi::c_void;
+
 /// Metal Syphon server: publishes frames from Metal textures.
 pub struct MetalServer {
     #[cfg(target_os = "macos")]
@@ -198,18 +772,71 @@ pub struct MetalServer {
 pub struct MetalClient {
     #[cfg(target_os = "macos")]
     ptr: NonNull<std::ffi::c_void>,
-    #[cfg(target_os = "macos")]
-    _callback_storage: Option<Box<CallbackHolder>>,
 }
 
-/// A Metal texture from Syphon (server or client). Release when done drawing.
+/// `MTLStorageMode` values for `MetalTextureOptions::storage_mode`.
+pub const MTL_STORAGE_MODE_SHARED: u64 = 0;
+pub const MTL_STORAGE_MODE_MANAGED: u64 = 1;
+pub const MTL_STORAGE_MODE_PRIVATE: u64 = 2;
+
+/// Raw `MTLPixelFormat` values (`SyphonOptions::set_pixel_format`, `MetalServer::pixel_format`,
+/// `MetalTexture::pixel_format`, `ServerFrameSize::pixel_format`).
+pub const MTL_PIXEL_FORMAT_RGBA8_UNORM: u64 = 70;
+pub const MTL_PIXEL_FORMAT_RGBA8_UNORM_SRGB: u64 = 71;
+pub const MTL_PIXEL_FORMAT_BGRA8_UNORM: u64 = 80;
+pub const MTL_PIXEL_FORMAT_BGRA8_UNORM_SRGB: u64 = 81;
+pub const MTL_PIXEL_FORMAT_RGBA16_FLOAT: u64 = 115;
+
+/// `MTLTextureUsage` flags for `MetalTextureOptions::usage`.
+pub const MTL_TEXTURE_USAGE_SHADER_READ: u64 = 0x01;
+pub const MTL_TEXTURE_USAGE_SHADER_WRITE: u64 = 0x02;
+pub const MTL_TEXTURE_USAGE_RENDER_TARGET: u64 = 0x04;
+pub const MTL_TEXTURE_USAGE_PIXEL_FORMAT_VIEW: u64 = 0x10;
+
+/// Storage mode and usage for textures the crate creates from received frames (owned copies, blender
+/// output). The default is private storage, shader-read only. Shared storage requires Apple silicon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetalTextureOptions {
+    /// Raw `MTLStorageMode` (`MTL_STORAGE_MODE_*`).
+    pub storage_mode: u64,
+    /// Raw `MTLTextureUsage` (`MTL_TEXTURE_USAGE_*` flags).
+    pub usage: u64,
+}
+
+impl Default for MetalTextureOptions {
+    fn default() -> Self {
+        Self {
+            storage_mode: MTL_STORAGE_MODE_PRIVATE,
+            usage: MTL_TEXTURE_USAGE_SHADER_READ,
+        }
+    }
+}
+
+/// A Metal texture from Syphon (server or client). Release when done drawing. `size`, `pixel_format`,
+/// `usage` and `storage_mode` describe it for allocating matching resources.
 pub struct MetalTexture {
     #[cfg(target_os = "macos")]
     ptr: NonNull<std::ffi::c_void>,
 }
 
+/// `SyphonError::CreationFailed` for `object` with the glue's reason for the last failed create on this thread.
+#[cfg(target_os = "macos")]
+pub(crate) fn creation_failed(object: &'static str) -> SyphonError {
+    let reason = opt_cstr_to_string(unsafe { ffi::syphon_copy_last_error() })
+        .unwrap_or_else(|| "unknown error".to_string());
+    SyphonError::CreationFailed { object, reason }
+}
+
+/// Optional name as a C string; `InvalidArgument` if it contains a NUL byte.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+pub(crate) fn optional_cstring(name: Option<&str>) -> Result<Option<std::ffi::CString>, SyphonError> {
+    name.map(std::ffi::CString::new)
+        .transpose()
+        .map_err(|_| SyphonError::InvalidArgument("name contains a NUL byte"))
+}
+
 #[cfg(target_os = "macos")]
-fn opt_cstr_to_string(s: *mut c_char) -> Option<String> {
+pub(crate) fn opt_cstr_to_string(s: *mut c_char) -> Option<String> {
     if s.is_null() {
         return None;
     }
@@ -223,13 +850,25 @@ fn opt_cstr_to_string(s: *mut c_char) -> Option<String> {
 impl ServerDirectory {
     /// Returns the shared server directory, or `None` on failure.
     pub fn shared() -> Option<Self> {
+        Self::try_shared().ok()
+    }
+
+    /// Like `shared`, reporting why the directory is unavailable.
+    pub fn try_shared() -> Result<Self, SyphonError> {
         #[cfg(target_os = "macos")]
         {
             let ptr = unsafe { ffi::syphon_server_directory_shared() };
-            NonNull::new(ptr).map(|ptr| Self { ptr })
+            NonNull::new(ptr).map(|ptr| Self { ptr }).ok_or_else(|| creation_failed("server directory"))
         }
-        #[cfg(not(target_os = "macos"))]
-        None
+        #[cfg(target_os = "windows")]
+        {
+            Spout::new().map(|spout| Self { spout }).ok_or_else(|| SyphonError::CreationFailed {
+                object: "server directory",
+                reason: "Spout is unavailable".into(),
+            })
+        }
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        Err(SyphonError::Unsupported)
     }
 
     /// Number of servers currently in the directory.
@@ -238,22 +877,35 @@ impl ServerDirectory {
         {
             unsafe { ffi::syphon_server_directory_servers_count(self.ptr.as_ptr()) }
         }
-        #[cfg(not(target_os = "macos"))]
+        #[cfg(target_os = "windows")]
+        {
+            usize::try_from(self.spout.sender_count()).unwrap_or(0)
+        }
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
         0
     }
 
-    /// Server description at index (not retained; valid only while directory is not refreshed).
+    /// Server description at index (retained). On Windows, None if the sender closed since
+    /// `servers_count` was read.
     pub fn server_at_index(&self, index: usize) -> Option<ServerDescription> {
         #[cfg(target_os = "macos")]
         {
             let ptr = unsafe { ffi::syphon_server_directory_server_at_index(self.ptr.as_ptr(), index) };
-            NonNull::new(ptr).map(|ptr| ServerDescription { ptr, owned: false })
+            NonNull::new(ptr).map(|ptr| ServerDescription { ptr })
         }
-        #[cfg(not(target_os = "macos"))]
+        #[cfg(target_os = "windows")]
+        {
+            let name = self.spout.sender_name_at(i32::try_from(index).ok()?)?;
+            let info = self.spout.sender_info(&name)?;
+            Some(ServerDescription {
+                sender: SpoutSenderDescription { name, info },
+            })
+        }
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
         None
     }
 
-    /// All current server descriptions. Descriptions are not retained; valid only until the next directory update.
+    /// All current server descriptions (each retained).
     pub fn servers(&self) -> Vec<ServerDescription> {
         let n = self.servers_count();
         (0..n)
@@ -261,73 +913,91 @@ impl ServerDirectory {
             .collect()
     }
 
-    /// Query servers by optional name and/or app name. Returns a match result you must iterate and drop.
-    pub fn servers_matching(
+    /// Servers whose name and/or app name equal `name` / `app_name` (None matches any), using Syphon's
+    /// own `serversMatchingName:appName:` so filtering agrees with other Syphon apps. Each is retained.
+    /// Spout senders have no app name, so on Windows any `app_name` matches nothing. A name containing a
+    /// NUL byte matches nothing (see `try_servers_matching`).
+    pub fn servers_matching(&self, name: Option<&str>, app_name: Option<&str>) -> Vec<ServerDescription> {
+        self.try_servers_matching(name, app_name).unwrap_or_default()
+    }
+
+    /// Like `servers_matching`, reporting a name containing a NUL byte as `InvalidArgument`.
+    #[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+    pub fn try_servers_matching(
         &self,
         name: Option<&str>,
         app_name: Option<&str>,
-    ) -> Option<ServerDirectoryMatch> {
-        #[cfg(target_os = "macos")]
-        {
-            let name_ptr = name
-                .and_then(|s| std::ffi::CString::new(s).ok())
-                .as_ref()
-                .map(|c| c.as_ptr())
-                .unwrap_or(std::ptr::null());
-            let app_ptr = app_name
-                .and_then(|s| std::ffi::CString::new(s).ok())
-                .as_ref()
-                .map(|c| c.as_ptr())
-                .unwrap_or(std::ptr::null());
-            let ptr = unsafe {
+    ) -> Result<Vec<ServerDescription>, SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            // Keep the C strings alive across the call.
+            let name = optional_cstring(name)?;
+            let app_name = optional_cstring(app_name)?;
+            let name_ptr = name.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
+            let app_ptr = app_name.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
+            let matches = unsafe {
                 ffi::syphon_server_directory_servers_matching(self.ptr.as_ptr(), name_ptr, app_ptr)
             };
-            NonNull::new(ptr).map(|ptr| ServerDirectoryMatch { ptr })
+            if matches.is_null() {
+                return Ok(Vec::new());
+            }
+            let count = unsafe { ffi::syphon_server_directory_match_count(matches) };
+            let servers = (0..count)
+                .filter_map(|i| NonNull::new(unsafe { ffi::syphon_server_directory_match_at_index(matches, i) }))
+                .map(|ptr| ServerDescription { ptr })
+                .collect();
+            unsafe { ffi::syphon_server_directory_match_release(matches) };
+            Ok(servers)
         }
-        #[cfg(not(target_os = "macos"))]
-        None
-    }
-}
-
-#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
-impl ServerDirectoryMatch {
-    /// Number of server descriptions in this match.
-    pub fn count(&self) -> usize {
-        #[cfg(target_os = "macos")]
-        unsafe { ffi::syphon_server_directory_match_count(self.ptr.as_ptr()) }
-        #[cfg(not(target_os = "macos"))]
-        0
-    }
-
-    /// Server description at index (retained; caller owns).
-    pub fn at(&self, index: usize) -> Option<ServerDescription> {
-        #[cfg(target_os = "macos")]
+        #[cfg(target_os = "windows")]
         {
-            let ptr = unsafe { ffi::syphon_server_directory_match_at_index(self.ptr.as_ptr(), index) };
-            NonNull::new(ptr).map(|ptr| ServerDescription { ptr, owned: true })
+            optional_cstring(name)?;
+            optional_cstring(app_name)?;
+            if app_name.is_some() {
+                return Ok(Vec::new());
+            }
+            Ok(self
+                .servers()
+                .into_iter()
+                .filter(|d| name.is_none_or(|name| d.sender.name == name))
+                .collect())
         }
-        #[cfg(not(target_os = "macos"))]
-        None
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        Ok(Vec::new())
     }
 
-    /// Iterate over matched server descriptions (each retained).
-    pub fn iter(&self) -> impl Iterator<Item = ServerDescription> + '_ {
-        (0..self.count()).filter_map(|i| self.at(i))
-    }
-}
-
-impl Drop for ServerDirectoryMatch {
-    fn drop(&mut self) {
-        #[cfg(target_os = "macos")]
-        unsafe {
-            ffi::syphon_server_directory_match_release(self.ptr.as_ptr());
+    /// Block until a server matching `name` / `app_name` (None matches any) is listed, or `timeout`
+    /// elapses. On the main thread the run loop is run while waiting so the directory can update. A name
+    /// containing a NUL byte matches nothing, so this returns None at once.
+    pub fn wait_for_server(
+        &self,
+        name: Option<&str>,
+        app_name: Option<&str>,
+        timeout: std::time::Duration,
+    ) -> Option<ServerDescription> {
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            if let Some(found) = self.try_servers_matching(name, app_name).ok()?.into_iter().next() {
+                return Some(found);
+            }
+            let left = deadline.saturating_duration_since(std::time::Instant::now());
+            if left.is_zero() {
+                return None;
+            }
+            let slice = left.min(std::time::Duration::from_millis(50));
+            #[cfg(target_os = "macos")]
+            unsafe {
+                ffi::syphon_run_loop_wait(slice.as_secs_f64());
+            }
+            #[cfg(not(target_os = "macos"))]
+            std::thread::sleep(slice);
         }
     }
 }
 
 #[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
 impl SyphonOptions {
-    /// Create an empty options builder. Use `set_is_private`, `set_antialias_sample_count`, etc., then pass to server create.
+    /// Create an empty options builder. Use `set_is_private`, `set_antialias_sample_count`, etc., then pass to server or client create.
     pub fn new() -> Option<Self> {
         #[cfg(target_os = "macos")]
         {
@@ -392,6 +1062,46 @@ impl SyphonOptions {
         }
     }
 
+    /// Request the raw `MTLPixelFormat` of a Metal server's shared surface (e.g.
+    /// `MTL_PIXEL_FORMAT_RGBA16_FLOAT` for HDR). Released Syphon frameworks ignore it and share BGRA8,
+    /// converting float frames on publish; check `MetalServer::pixel_format` for what was negotiated.
+    pub fn set_pixel_format(&self, pixel_format: u64) {
+        #[cfg(target_os = "macos")]
+        {
+            let k = unsafe { ffi::syphon_server_option_key_pixel_format() };
+            if !k.is_null() {
+                unsafe {
+                    ffi::syphon_options_set_unsigned_long(self.ptr.as_ptr(), k, pixel_format);
+                }
+                unsafe { libc::free(k as *mut _) };
+            }
+        }
+    }
+
+    /// Set an arbitrary boolean option, for keys without a typed setter.
+    pub fn set_bool(&self, key: &str, value: bool) {
+        #[cfg(target_os = "macos")]
+        if let Ok(k) = std::ffi::CString::new(key) {
+            unsafe { ffi::syphon_options_set_bool(self.ptr.as_ptr(), k.as_ptr(), value) };
+        }
+    }
+
+    /// Set an arbitrary unsigned integer option.
+    pub fn set_unsigned(&self, key: &str, value: u64) {
+        #[cfg(target_os = "macos")]
+        if let Ok(k) = std::ffi::CString::new(key) {
+            unsafe { ffi::syphon_options_set_unsigned_long(self.ptr.as_ptr(), k.as_ptr(), value as _) };
+        }
+    }
+
+    /// Set an arbitrary string option.
+    pub fn set_string(&self, key: &str, value: &str) {
+        #[cfg(target_os = "macos")]
+        if let (Ok(k), Ok(v)) = (std::ffi::CString::new(key), std::ffi::CString::new(value)) {
+            unsafe { ffi::syphon_options_set_string(self.ptr.as_ptr(), k.as_ptr(), v.as_ptr()) };
+        }
+    }
+
     #[cfg(target_os = "macos")]
     pub(crate) fn as_ptr(&self) -> *mut std::ffi::c_void {
         self.ptr.as_ptr()
@@ -407,15 +1117,28 @@ impl Drop for SyphonOptions {
     }
 }
 
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
 impl ServerDescription {
-    /// Copy the server UUID (unique id), if present.
+    /// Retain a description the glue lends for the duration of a call, taking ownership of that retain.
+    #[cfg(target_os = "macos")]
+    pub(crate) unsafe fn retained_from_borrowed(ptr: NonNull<std::ffi::c_void>) -> Self {
+        ffi::syphon_server_description_retain(ptr.as_ptr());
+        ServerDescription { ptr }
+    }
+
+    /// Copy the server UUID (unique id), if present. Spout sender names are unique system-wide, so on
+    /// Windows this is the sender name.
     pub fn uuid(&self) -> Option<String> {
         #[cfg(target_os = "macos")]
         {
             let s = unsafe { ffi::syphon_server_description_copy_uuid(self.ptr.as_ptr()) };
             opt_cstr_to_string(s)
         }
-        #[cfg(not(target_os = "macos"))]
+        #[cfg(target_os = "windows")]
+        {
+            Some(self.sender.name.clone())
+        }
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
         None
     }
 
@@ -426,7 +1149,42 @@ impl ServerDescription {
             let s = unsafe { ffi::syphon_server_description_copy_name(self.ptr.as_ptr()) };
             opt_cstr_to_string(s)
         }
-        #[cfg(not(target_os = "macos"))]
+        #[cfg(target_os = "windows")]
+        {
+            Some(self.sender.name.clone())
+        }
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        None
+    }
+
+    /// Frame size in pixels a Spout sender was sharing when the directory was read. Syphon
+    /// descriptions carry no size (see `probe_server_size`), so None on macOS.
+    pub fn size(&self) -> Option<(u32, u32)> {
+        #[cfg(target_os = "windows")]
+        {
+            Some((self.sender.info.width, self.sender.info.height))
+        }
+        #[cfg(not(target_os = "windows"))]
+        None
+    }
+
+    /// The Spout sender's DXGI share handle, for opening its texture with DirectX. None on macOS.
+    pub fn sender_handle(&self) -> Option<*mut std::ffi::c_void> {
+        #[cfg(target_os = "windows")]
+        {
+            Some(self.sender.info.share_handle)
+        }
+        #[cfg(not(target_os = "windows"))]
+        None
+    }
+
+    /// The Spout sender's texture format (a `DXGI_FORMAT`, e.g. 87 for BGRA8). None on macOS.
+    pub fn sender_format(&self) -> Option<u32> {
+        #[cfg(target_os = "windows")]
+        {
+            Some(self.sender.info.format)
+        }
+        #[cfg(not(target_os = "windows"))]
         None
     }
 
@@ -441,31 +1199,144 @@ impl ServerDescription {
         None
     }
 
-    /// Retain the description so it remains valid after the directory updates. Call `release` or drop a retained clone when done.
-    pub fn retain(&self) {
+    /// Bundle identifier of the hosting application (e.g. `com.example.VJApp`), resolved from the
+    /// running applications by `app_name`. None if the app isn't found or several running apps
+    /// share that name with different bundle identifiers.
+    pub fn bundle_identifier(&self) -> Option<String> {
         #[cfg(target_os = "macos")]
-        unsafe {
-            ffi::syphon_server_description_retain(self.ptr.as_ptr());
+        {
+            let s = unsafe { ffi::syphon_server_description_copy_bundle_identifier(self.ptr.as_ptr()) };
+            opt_cstr_to_string(s)
         }
+        #[cfg(not(target_os = "macos"))]
+        None
     }
 
-    /// Release a description that was retained.
-    pub fn release(&self) {
+    /// The hosting application's icon rendered at `size` x `size` pixels, for picker UIs. Uses an
+    /// icon published with the server if present, else the running application's icon.
+    pub fn app_icon(&self, size: usize) -> Option<AppIcon> {
         #[cfg(target_os = "macos")]
-        unsafe {
-            ffi::syphon_server_description_release(self.ptr.as_ptr());
+        {
+            let ptr = unsafe { ffi::syphon_server_description_copy_app_icon_rgba8(self.ptr.as_ptr(), size) };
+            if ptr.is_null() {
+                return None;
+            }
+            let rgba = unsafe { std::slice::from_raw_parts(ptr, size * size * 4) }.to_vec();
+            unsafe { libc::free(ptr as *mut _) };
+            Some(AppIcon {
+                width: size,
+                height: size,
+                rgba,
+            })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Every entry of the underlying description dictionary, keyed by Syphon's key names (UUID, name,
+    /// app name, dictionary version, surfaces, icon, ...). Values are stringified: numbers in decimal,
+    /// data as base64, arrays and dictionaries as JSON. On Windows: the sender's `name`, `width`,
+    /// `height`, `format` and `handle` (hex).
+    pub fn dictionary(&self) -> std::collections::HashMap<String, String> {
+        #[cfg(target_os = "macos")]
+        {
+            let mut len = 0usize;
+            let ptr = unsafe { ffi::syphon_server_description_copy_entries(self.ptr.as_ptr(), &mut len) };
+            if ptr.is_null() {
+                return std::collections::HashMap::new();
+            }
+            let bytes = unsafe { std::slice::from_raw_parts(ptr as *const u8, len) };
+            let mut parts = bytes
+                .split(|&b| b == 0)
+                .map(|part| String::from_utf8_lossy(part).into_owned());
+            let mut entries = std::collections::HashMap::new();
+            while let (Some(key), Some(value)) = (parts.next(), parts.next()) {
+                entries.insert(key, value);
+            }
+            unsafe { libc::free(ptr as *mut _) };
+            entries
+        }
+        #[cfg(target_os = "windows")]
+        {
+            let info = &self.sender.info;
+            std::collections::HashMap::from([
+                ("name".to_owned(), self.sender.name.clone()),
+                ("width".to_owned(), info.width.to_string()),
+                ("height".to_owned(), info.height.to_string()),
+                ("format".to_owned(), info.format.to_string()),
+                ("handle".to_owned(), format!("{:#x}", info.share_handle as usize)),
+            ])
+        }
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        std::collections::HashMap::new()
+    }
+
+    /// Version of the description dictionary format the server published, if present.
+    pub fn dictionary_version(&self) -> Option<u32> {
+        #[cfg(target_os = "macos")]
+        {
+            let version = unsafe { ffi::syphon_server_description_dictionary_version(self.ptr.as_ptr()) };
+            u32::try_from(version).ok()
         }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Surface types the server offers (e.g. `SyphonSurfaceTypeIOSurface`); empty if not listed.
+    pub fn surface_types(&self) -> Vec<String> {
+        #[cfg(target_os = "macos")]
+        {
+            let s = unsafe { ffi::syphon_server_description_copy_surface_types(self.ptr.as_ptr()) };
+            opt_cstr_to_string(s)
+                .map(|types| types.lines().filter(|t| !t.is_empty()).map(str::to_owned).collect())
+                .unwrap_or_default()
+        }
+        #[cfg(not(target_os = "macos"))]
+        Vec::new()
+    }
+
+    /// Serialize the description (binary property list) so another process can connect to this server
+    /// with `from_bytes`, e.g. to reach a private server that is not listed in the directory.
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        #[cfg(target_os = "macos")]
+        {
+            let mut len = 0usize;
+            let ptr = unsafe { ffi::syphon_server_description_copy_plist(self.ptr.as_ptr(), &mut len) };
+            if ptr.is_null() {
+                return None;
+            }
+            let bytes = unsafe { std::slice::from_raw_parts(ptr, len) }.to_vec();
+            unsafe { libc::free(ptr as *mut _) };
+            Some(bytes)
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Rebuild a description produced by `to_bytes` (owned). Pass it to a client constructor to
+    /// connect; None if `bytes` is not a server description.
+    pub fn from_bytes(bytes: &[u8]) -> Option<ServerDescription> {
+        #[cfg(target_os = "macos")]
+        {
+            let ptr = unsafe { ffi::syphon_server_description_create_from_plist(bytes.as_ptr(), bytes.len()) };
+            NonNull::new(ptr).map(|ptr| ServerDescription { ptr })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
     }
 }
 
 impl Clone for ServerDescription {
     fn clone(&self) -> Self {
-        self.retain();
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_server_description_retain(self.ptr.as_ptr());
+        }
         Self {
             #[cfg(target_os = "macos")]
             ptr: self.ptr,
-            #[cfg(target_os = "macos")]
-            owned: true,
+            #[cfg(target_os = "windows")]
+            sender: self.sender.clone(),
         }
     }
 }
@@ -473,35 +1344,116 @@ impl Clone for ServerDescription {
 impl Drop for ServerDescription {
     fn drop(&mut self) {
         #[cfg(target_os = "macos")]
-        if self.owned {
-            unsafe { ffi::syphon_server_description_release(self.ptr.as_ptr()) };
+        unsafe {
+            ffi::syphon_server_description_release(self.ptr.as_ptr());
+        }
+    }
+}
+
+/// Descriptions are equal when they name the same server UUID, so a server compares equal across
+/// directory refreshes and to a client's `server_description`. Descriptions without a UUID are only
+/// equal to the same description object.
+impl PartialEq for ServerDescription {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.uuid(), other.uuid()) {
+            (Some(a), Some(b)) => a == b,
+            #[cfg(target_os = "macos")]
+            (None, None) => self.ptr == other.ptr,
+            #[cfg(not(target_os = "macos"))]
+            (None, None) => true,
+            _ => false,
         }
     }
 }
 
+impl Eq for ServerDescription {}
+
+impl std::hash::Hash for ServerDescription {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.uuid().hash(state);
+    }
+}
+
 #[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
 impl OpenGLServer {
     /// Create a new OpenGL server. `name` can be None (empty). `options` can be None or a `SyphonOptions` (e.g. private server, antialias, depth/stencil).
     /// Returns None if creation failed.
     pub fn new(
         name: Option<&str>,
-        context: CGLContextObj,
+        context: CGLContextObj,
+        options: Option<&SyphonOptions>,
+    ) -> Option<Self> {
+        Self::try_new(name, context, options).ok()
+    }
+
+    /// Like `new`, reporting why creation failed (null context, NUL in `name`, Syphon's own error).
+    pub fn try_new(
+        name: Option<&str>,
+        context: CGLContextObj,
+        options: Option<&SyphonOptions>,
+    ) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            if context.is_null() {
+                return Err(SyphonError::InvalidArgument("null CGL context"));
+            }
+            let name = optional_cstring(name)?;
+            let name_ptr = name.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
+            let opts_ptr = options.map(|o| o.as_ptr()).unwrap_or(std::ptr::null_mut());
+            let ptr = unsafe { ffi::syphon_opengl_server_create(name_ptr, context, opts_ptr) };
+            NonNull::new(ptr).map(|ptr| Self { ptr }).ok_or_else(|| creation_failed("OpenGL server"))
+        }
+        #[cfg(not(target_os = "macos"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Create a server on the CGL context current on this thread (e.g. one made current by
+    /// NSOpenGLContext, SDL or glutin). Returns None if no context is current or creation failed.
+    pub fn new_with_current_context(name: Option<&str>, options: Option<&SyphonOptions>) -> Option<Self> {
+        Self::try_new_with_current_context(name, options).ok()
+    }
+
+    /// Like `new_with_current_context`, reporting `NoCurrentContext` when no context is current.
+    pub fn try_new_with_current_context(
+        name: Option<&str>,
         options: Option<&SyphonOptions>,
-    ) -> Option<Self> {
+    ) -> Result<Self, SyphonError> {
         #[cfg(target_os = "macos")]
         {
-            let name_ptr = name
-                .map(|s| std::ffi::CString::new(s).ok())
-                .flatten()
-                .as_ref()
-                .map(|c| c.as_ptr())
-                .unwrap_or(std::ptr::null());
-            let opts_ptr = options.map(|o| o.as_ptr()).unwrap_or(std::ptr::null_mut());
-            let ptr = unsafe { ffi::syphon_opengl_server_create(name_ptr, context, opts_ptr) };
-            NonNull::new(ptr).map(|ptr| Self { ptr })
+            check_current_context(None)?;
+            Self::try_new(name, cgl_current_context(), options)
         }
         #[cfg(not(target_os = "macos"))]
-        None
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Create a server on the CGL context of an `NSOpenGLContext*` (e.g. an `NSOpenGLView`'s
+    /// `openGLContext`). Returns None if `ns_context` is null or creation failed.
+    pub fn with_nsopengl_context(
+        name: Option<&str>,
+        ns_context: *mut std::ffi::c_void,
+        options: Option<&SyphonOptions>,
+    ) -> Option<Self> {
+        Self::try_with_nsopengl_context(name, ns_context, options).ok()
+    }
+
+    /// Like `with_nsopengl_context`, reporting why creation failed.
+    pub fn try_with_nsopengl_context(
+        name: Option<&str>,
+        ns_context: *mut std::ffi::c_void,
+        options: Option<&SyphonOptions>,
+    ) -> Result<Self, SyphonError> {
+        let context = cgl_context_from_nsopengl_context(ns_context).unwrap_or(std::ptr::null_mut());
+        Self::try_new(name, context, options)
+    }
+
+    /// Create a private server: it is not announced, so it never appears in the directory (or in other
+    /// apps' pickers). Hand `server_description().to_bytes()` to the receiving process, which connects
+    /// with `ServerDescription::from_bytes`.
+    pub fn new_private(name: Option<&str>, context: CGLContextObj) -> Option<Self> {
+        let options = SyphonOptions::new()?;
+        options.set_is_private(true);
+        Self::new(name, context, Some(&options))
     }
 
     /// The CGL context the server uses for drawing.
@@ -512,7 +1464,7 @@ impl OpenGLServer {
         std::ptr::null_mut()
     }
 
-    /// Human-readable server name, if set.
+    /// Human-readable server name, if set (reflects `set_name`).
     pub fn name(&self) -> Option<String> {
         #[cfg(target_os = "macos")]
         {
@@ -523,18 +1475,24 @@ impl OpenGLServer {
         None
     }
 
-    /// Set the server's human-readable name.
+    /// Rename the server while it runs (None clears the name). Clients and pickers see the change
+    /// through the directory's update notification (`DirectoryEvent::Updated`). Names containing a NUL
+    /// byte are ignored; see `try_set_name`.
     pub fn set_name(&self, name: Option<&str>) {
+        let _ = self.try_set_name(name);
+    }
+
+    /// Like `set_name`, reporting a NUL byte in `name` as `InvalidArgument`.
+    pub fn try_set_name(&self, name: Option<&str>) -> Result<(), SyphonError> {
         #[cfg(target_os = "macos")]
         {
-            let name_ptr = name
-                .map(|s| std::ffi::CString::new(s).ok())
-                .flatten()
-                .as_ref()
-                .map(|c| c.as_ptr())
-                .unwrap_or(std::ptr::null());
+            let name = optional_cstring(name)?;
+            let name_ptr = name.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
             unsafe { ffi::syphon_opengl_server_set_name(self.ptr.as_ptr(), name_ptr) };
+            Ok(())
         }
+        #[cfg(not(target_os = "macos"))]
+        Err(SyphonError::Unsupported)
     }
 
     /// True if any clients are attached.
@@ -550,13 +1508,52 @@ impl OpenGLServer {
         #[cfg(target_os = "macos")]
         {
             let ptr = unsafe { ffi::syphon_opengl_server_server_description(self.ptr.as_ptr()) };
-            NonNull::new(ptr).map(|ptr| ServerDescription { ptr, owned: true })
+            NonNull::new(ptr).map(|ptr| ServerDescription { ptr })
         }
         #[cfg(not(target_os = "macos"))]
         None
     }
 
+    /// Publish `region` of a texture of `texture_size` (bound to `target`, e.g. `GL_TEXTURE_RECTANGLE`);
+    /// `flipped` if its rows are top-down. With the `validate` feature, the frame is dropped if the
+    /// server's context is not current here.
+    pub fn publish(&self, tex_id: u32, target: u32, region: Rect, texture_size: Size, flipped: bool) {
+        let _ = self.try_publish(tex_id, target, region, texture_size, flipped);
+    }
+
+    /// Like `publish`; with the `validate` feature, returns `NoCurrentContext` / `WrongThread`
+    /// instead of publishing a black frame when the server's context is not current on this thread.
+    pub fn try_publish(
+        &self,
+        tex_id: u32,
+        target: u32,
+        region: Rect,
+        texture_size: Size,
+        flipped: bool,
+    ) -> Result<(), SyphonError> {
+        #[cfg(feature = "validate")]
+        check_current_context(Some(self.context()))?;
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_opengl_server_publish_frame(
+                self.ptr.as_ptr(),
+                tex_id,
+                target,
+                region.x,
+                region.y,
+                region.w,
+                region.h,
+                texture_size.w,
+                texture_size.h,
+                flipped,
+            );
+        }
+        Ok(())
+    }
+
     /// Publish a frame from a texture. Region (x,y,w,h) and texture size (tex_w, tex_h), flipped.
+    #[deprecated(note = "use `publish` with a `Rect` and `Size`")]
+    #[allow(clippy::too_many_arguments)]
     pub fn publish_frame(
         &self,
         tex_id: u32,
@@ -569,29 +1566,88 @@ impl OpenGLServer {
         tex_h: f64,
         flipped: bool,
     ) {
+        self.publish(tex_id, target, Rect::new(x, y, w, h), Size::new(tex_w, tex_h), flipped);
+    }
+
+    /// Like `publish_frame`, reporting context errors as `try_publish` does.
+    #[deprecated(note = "use `try_publish` with a `Rect` and `Size`")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_publish_frame(
+        &self,
+        tex_id: u32,
+        target: u32,
+        x: f64,
+        y: f64,
+        w: f64,
+        h: f64,
+        tex_w: f64,
+        tex_h: f64,
+        flipped: bool,
+    ) -> Result<(), SyphonError> {
+        self.try_publish(tex_id, target, Rect::new(x, y, w, h), Size::new(tex_w, tex_h), flipped)
+    }
+
+    /// Publish all of a `width` x `height` texture: `publish` with the region set to the whole texture.
+    pub fn publish_whole_texture(&self, tex_id: u32, target: u32, width: f64, height: f64, flipped: bool) {
+        let size = Size::new(width, height);
+        self.publish(tex_id, target, Rect::from_size(size), size, flipped);
+    }
+
+    /// Like `publish_whole_texture`, reporting context errors as `try_publish` does.
+    pub fn try_publish_whole_texture(
+        &self,
+        tex_id: u32,
+        target: u32,
+        width: f64,
+        height: f64,
+        flipped: bool,
+    ) -> Result<(), SyphonError> {
+        let size = Size::new(width, height);
+        self.try_publish(tex_id, target, Rect::from_size(size), size, flipped)
+    }
+
+    /// Publish a texture of `size` of which only `region` (texture coordinates, bottom-left origin)
+    /// changed since the previous `publish_dirty_region`. If that frame had the same size, only the region
+    /// is drawn into the server's surface; otherwise (first frame, resize, or a `publish` in between) the
+    /// whole texture is published. Needs the server's context current.
+    pub fn publish_dirty_region(&self, tex_id: u32, target: u32, region: Rect, size: Size, flipped: bool) -> bool {
+        #[cfg(feature = "validate")]
+        if check_current_context(Some(self.context())).is_err() {
+            return false;
+        }
         #[cfg(target_os = "macos")]
         unsafe {
-            ffi::syphon_opengl_server_publish_frame(
+            ffi::syphon_opengl_server_publish_dirty_region(
                 self.ptr.as_ptr(),
                 tex_id,
                 target,
-                x,
-                y,
-                w,
-                h,
-                tex_w,
-                tex_h,
+                size.w,
+                size.h,
+                region.x,
+                region.y,
+                region.w,
+                region.h,
                 flipped,
-            );
+            )
         }
+        #[cfg(not(target_os = "macos"))]
+        false
     }
 
-    /// Bind the server's FBO to draw a frame of the given size. Pair with `unbind_and_publish`.
+    /// Bind the server's FBO to draw a frame of the given size. Pair with `unbind_and_publish`, or use
+    /// `draw_frame` to have the pairing done for you.
     pub fn bind_to_draw_frame(&self, w: f64, h: f64) -> bool {
+        self.try_bind_to_draw_frame(w, h).unwrap_or(false)
+    }
+
+    /// Like `bind_to_draw_frame`, with the same `validate`-feature context check as `try_publish`.
+    pub fn try_bind_to_draw_frame(&self, w: f64, h: f64) -> Result<bool, SyphonError> {
+        #[cfg(feature = "validate")]
+        check_current_context(Some(self.context()))?;
         #[cfg(target_os = "macos")]
-        unsafe { ffi::syphon_opengl_server_bind_to_draw_frame(self.ptr.as_ptr(), w, h) }
+        return Ok(unsafe { ffi::syphon_opengl_server_bind_to_draw_frame(self.ptr.as_ptr(), w, h) });
         #[cfg(not(target_os = "macos"))]
-        false
+        Ok(false)
     }
 
     /// Unbind and publish the just-drawn frame.
@@ -602,6 +1658,12 @@ impl OpenGLServer {
         }
     }
 
+    /// Bind the server's FBO to draw a `w` x `h` frame; the frame is published when the returned guard
+    /// is dropped (also on early return or unwinding). None if binding failed.
+    pub fn draw_frame(&self, w: f64, h: f64) -> Option<DrawFrameGuard<'_>> {
+        self.bind_to_draw_frame(w, h).then_some(DrawFrameGuard { server: self })
+    }
+
     /// Returns the current output frame as an OpenGL image (e.g. for loopback). Caller must release the returned image.
     pub fn new_frame_image(&self) -> Option<OpenGLImage> {
         #[cfg(target_os = "macos")]
@@ -613,7 +1675,7 @@ impl OpenGLServer {
         None
     }
 
-    /// Stop the server.
+    /// Stop the server. Idempotent: only the first call (explicit or from `Drop`) stops it.
     pub fn stop(&self) {
         #[cfg(target_os = "macos")]
         unsafe {
@@ -622,6 +1684,25 @@ impl OpenGLServer {
     }
 }
 
+/// A frame being drawn into an `OpenGLServer`'s FBO (from `OpenGLServer::draw_frame`). Dropping it
+/// unbinds the FBO and publishes the frame.
+pub struct DrawFrameGuard<'a> {
+    server: &'a OpenGLServer,
+}
+
+impl DrawFrameGuard<'_> {
+    /// The server being drawn into.
+    pub fn server(&self) -> &OpenGLServer {
+        self.server
+    }
+}
+
+impl Drop for DrawFrameGuard<'_> {
+    fn drop(&mut self) {
+        self.server.unbind_and_publish();
+    }
+}
+
 impl Drop for OpenGLServer {
     fn drop(&mut self) {
         #[cfg(target_os = "macos")]
@@ -634,56 +1715,231 @@ impl Drop for OpenGLServer {
     }
 }
 
+/// What a new-frame callback is told about the frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// Per-callback delivery counter: 1 for the first notification this callback receives, restarting
+    /// at 1 when the handler is replaced. It numbers notifications rather than frames, so it can't tell
+    /// a stale frame from a current one.
+    pub frame_index: u64,
+    /// When the notification reached the crate.
+    pub timestamp: std::time::Instant,
+    /// Frame size in pixels (width, height); None unless the callback uses
+    /// `CallbackDispatch::SerializedWithSize`, or if the glue couldn't fetch the frame.
+    pub size: Option<(usize, usize)>,
+}
+
 /// Callback for new frames: invoked when a new frame is available (may be on another thread).
-pub type NewFrameCallback = Box<dyn Fn() + Send>;
+pub type NewFrameCallback = Box<dyn Fn(FrameInfo) + Send>;
+
+/// How a client's new-frame callback is invoked on Syphon's delivery thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CallbackDispatch {
+    /// Guarded invocation: the callback may stop or drop its own client, and objects it autoreleases
+    /// are drained after each call. `FrameInfo::size` is None.
+    #[default]
+    Serialized,
+    /// `Serialized`, also filling in `FrameInfo`'s size. Syphon doesn't announce frame sizes, so the
+    /// glue fetches the new frame on the delivery thread before each call. For OpenGL clients that
+    /// uses the client's CGL context from that thread, alongside any thread rendering with it; for
+    /// both it consumes the new frame, so `has_new_frame` reports false until the next one.
+    SerializedWithSize,
+    /// Lowest-latency invocation: no autorelease pool, and the frame isn't fetched (so
+    /// `FrameInfo::size` is None). The glue calls the closure holding an `os_unfair_lock`; stopping or dropping
+    /// the client from inside the callback is detected and skips that lock, and the closure is then
+    /// released after it returns. Return quickly and signal another thread for any real work.
+    Direct,
+}
 
-/// Holds the closure so we can pass a single pointer to C and invoke it from the callback.
+/// Holds the closure behind an `Arc` whose raw pointer is the C userdata. The glue owns that
+/// reference and hands it back through `raw_release` exactly once, after the last invocation.
 #[cfg(target_os = "macos")]
-struct CallbackHolder(Box<dyn Fn() + Send>);
+struct CallbackHolder(NewFrameCallback);
+
+// All dispatch modes serialize invocations behind a glue lock (recursive for `Serialized`, an
+// `os_unfair_lock` for `Direct`), so the closure is never called concurrently.
+#[cfg(target_os = "macos")]
+unsafe impl Sync for CallbackHolder {}
+
+#[cfg(target_os = "macos")]
+type RawNewFrameCallback = unsafe extern "C" fn(*mut std::ffi::c_void, u64, usize, usize);
+#[cfg(target_os = "macos")]
+type RawReleaseCallback = unsafe extern "C" fn(*mut std::ffi::c_void);
+
+#[cfg(target_os = "macos")]
+fn frame_info(frame_index: u64, size: Option<(usize, usize)>) -> FrameInfo {
+    FrameInfo {
+        frame_index,
+        timestamp: std::time::Instant::now(),
+        size,
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl CallbackHolder {
+    unsafe fn invoke_serialized(userdata: *mut std::ffi::c_void, info: FrameInfo) {
+        if userdata.is_null() {
+            return;
+        }
+        // Hold our own reference for the call: the closure may drop its client (re-entrantly
+        // releasing the glue's reference) while it runs.
+        let ptr = userdata as *const CallbackHolder;
+        std::sync::Arc::increment_strong_count(ptr);
+        let holder = std::sync::Arc::from_raw(ptr);
+        (holder.0)(info);
+    }
+
+    unsafe extern "C" fn raw_callback(userdata: *mut std::ffi::c_void, frame_index: u64, _width: usize, _height: usize) {
+        Self::invoke_serialized(userdata, frame_info(frame_index, None));
+    }
+
+    /// `SerializedWithSize` variant: the glue reports 0 x 0 when it couldn't fetch the frame.
+    unsafe extern "C" fn raw_callback_with_size(userdata: *mut std::ffi::c_void, frame_index: u64, width: usize, height: usize) {
+        let size = (width > 0 && height > 0).then_some((width, height));
+        Self::invoke_serialized(userdata, frame_info(frame_index, size));
+    }
+
+    /// Direct-dispatch variant: the glue holds its reference for the whole call (a re-entrant
+    /// release is deferred until the call returns), so no per-call reference is taken.
+    unsafe extern "C" fn raw_callback_direct(userdata: *mut std::ffi::c_void, frame_index: u64, _width: usize, _height: usize) {
+        if let Some(holder) = (userdata as *const CallbackHolder).as_ref() {
+            (holder.0)(frame_info(frame_index, None));
+        }
+    }
+
+    unsafe extern "C" fn raw_release(userdata: *mut std::ffi::c_void) {
+        if !userdata.is_null() {
+            drop(std::sync::Arc::from_raw(userdata as *const CallbackHolder));
+        }
+    }
+
+    /// Callback, release function and userdata to pass to a client create call.
+    fn into_raw(
+        callback: Option<NewFrameCallback>,
+        dispatch: CallbackDispatch,
+    ) -> (Option<RawNewFrameCallback>, Option<RawReleaseCallback>, *mut std::ffi::c_void) {
+        let invoke = match dispatch {
+            CallbackDispatch::Serialized => Self::raw_callback as RawNewFrameCallback,
+            CallbackDispatch::SerializedWithSize => Self::raw_callback_with_size as RawNewFrameCallback,
+            CallbackDispatch::Direct => Self::raw_callback_direct as RawNewFrameCallback,
+        };
+        match callback {
+            Some(c) => (
+                Some(invoke),
+                Some(Self::raw_release as RawReleaseCallback),
+                std::sync::Arc::into_raw(std::sync::Arc::new(CallbackHolder(c))) as *mut std::ffi::c_void,
+            ),
+            None => (None, None, std::ptr::null_mut()),
+        }
+    }
+}
 
 #[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
 impl OpenGLClient {
-    /// Create a client for the given server description and context. `callback` can be None (no handler).
-    /// The callback may be invoked on a different thread. When provided, it is kept for the client's lifetime.
+    /// Create a client for the given server description and context. `options` and `callback` can be None (no handler).
+    /// The callback may be invoked on a different thread. It is released once the client is stopped or dropped.
     pub fn new(
         description: &ServerDescription,
         context: CGLContextObj,
-        _options: Option<&std::collections::HashMap<String, String>>,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+    ) -> Option<Self> {
+        Self::new_with_dispatch(description, context, options, callback, CallbackDispatch::Serialized)
+    }
+
+    /// Like `new`, choosing how `callback` is dispatched (see `CallbackDispatch::Direct` for its constraints).
+    pub fn new_with_dispatch(
+        description: &ServerDescription,
+        context: CGLContextObj,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+        dispatch: CallbackDispatch,
+    ) -> Option<Self> {
+        Self::try_new_with_dispatch(description, context, options, callback, dispatch).ok()
+    }
+
+    /// Like `new`, but new-frame notifications are queued on the returned channel instead of running a
+    /// closure on Syphon's thread, so a render thread can `recv` / `try_recv` them.
+    pub fn new_with_channel(
+        description: &ServerDescription,
+        context: CGLContextObj,
+        options: Option<&SyphonOptions>,
+    ) -> Option<(Self, std::sync::mpsc::Receiver<FrameInfo>)> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let client = Self::new(
+            description,
+            context,
+            options,
+            Some(Box::new(move |info| {
+                let _ = tx.send(info);
+            })),
+        )?;
+        Some((client, rx))
+    }
+
+    /// Like `new`, reporting why creation failed.
+    pub fn try_new(
+        description: &ServerDescription,
+        context: CGLContextObj,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+    ) -> Result<Self, SyphonError> {
+        Self::try_new_with_dispatch(description, context, options, callback, CallbackDispatch::Serialized)
+    }
+
+    /// Create a client on the CGL context of an `NSOpenGLContext*` (e.g. an `NSOpenGLView`'s
+    /// `openGLContext`), so frames can be drawn straight into that view. None if `ns_context` is null or
+    /// creation failed.
+    pub fn with_nsopengl_context(
+        description: &ServerDescription,
+        ns_context: *mut std::ffi::c_void,
+        options: Option<&SyphonOptions>,
         callback: Option<NewFrameCallback>,
     ) -> Option<Self> {
+        Self::try_with_nsopengl_context(description, ns_context, options, callback).ok()
+    }
+
+    /// Like `with_nsopengl_context`, reporting why creation failed.
+    pub fn try_with_nsopengl_context(
+        description: &ServerDescription,
+        ns_context: *mut std::ffi::c_void,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+    ) -> Result<Self, SyphonError> {
+        let context = cgl_context_from_nsopengl_context(ns_context).unwrap_or(std::ptr::null_mut());
+        Self::try_new(description, context, options, callback)
+    }
+
+    /// Like `new_with_dispatch`, reporting why creation failed.
+    pub fn try_new_with_dispatch(
+        description: &ServerDescription,
+        context: CGLContextObj,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+        dispatch: CallbackDispatch,
+    ) -> Result<Self, SyphonError> {
         #[cfg(target_os = "macos")]
         {
-            unsafe extern "C" fn raw_callback(userdata: *mut std::ffi::c_void) {
-                if userdata.is_null() {
-                    return;
-                }
-                let h = &*(userdata as *const CallbackHolder);
-                (h.0)();
+            if context.is_null() {
+                return Err(SyphonError::InvalidArgument("null CGL context"));
             }
-            let callback_storage: Option<Box<CallbackHolder>> =
-                callback.map(|c| Box::new(CallbackHolder(c)));
-            let userdata = callback_storage
-                .as_ref()
-                .map(|b| (&**b) as *const CallbackHolder as *mut std::ffi::c_void)
-                .unwrap_or(std::ptr::null_mut());
-            type Cb = unsafe extern "C" fn(*mut std::ffi::c_void);
-            let cb_opt: Option<Cb> = callback_storage.as_ref().map(|_| raw_callback as Cb);
+            let (cb, release, userdata) = CallbackHolder::into_raw(callback, dispatch);
             let ptr = unsafe {
                 ffi::syphon_opengl_client_create(
                     description.ptr.as_ptr(),
                     context,
-                    std::ptr::null_mut(),
-                    cb_opt,
+                    options.map(|o| o.as_ptr()).unwrap_or(std::ptr::null_mut()),
+                    cb,
+                    release,
                     userdata,
+                    dispatch == CallbackDispatch::Direct,
+                    dispatch == CallbackDispatch::SerializedWithSize,
                 )
             };
-            NonNull::new(ptr).map(|ptr| Self {
-                ptr,
-                _callback_storage: callback_storage,
-            })
+            NonNull::new(ptr).map(|ptr| Self { ptr }).ok_or_else(|| creation_failed("OpenGL client"))
         }
         #[cfg(not(target_os = "macos"))]
-        None
+        Err(SyphonError::Unsupported)
     }
 
     /// The CGL context associated with the client.
@@ -699,7 +1955,7 @@ impl OpenGLClient {
         #[cfg(target_os = "macos")]
         {
             let ptr = unsafe { ffi::syphon_opengl_client_server_description(self.ptr.as_ptr()) };
-            NonNull::new(ptr).map(|ptr| ServerDescription { ptr, owned: true })
+            NonNull::new(ptr).map(|ptr| ServerDescription { ptr })
         }
         #[cfg(not(target_os = "macos"))]
         None
@@ -719,6 +1975,17 @@ impl OpenGLClient {
         false
     }
 
+    /// Copy the current frame into your own texture `tex_id` (bound to `target`, e.g.
+    /// `GL_TEXTURE_RECTANGLE` or `GL_TEXTURE_2D`) at its origin. The client's context must be current.
+    /// Returns false if there is no frame, or the texture is smaller than the frame or not an RGBA
+    /// color format.
+    pub fn copy_frame_to(&self, tex_id: u32, target: u32) -> bool {
+        #[cfg(target_os = "macos")]
+        unsafe { ffi::syphon_opengl_client_copy_frame_to(self.ptr.as_ptr(), tex_id, target) }
+        #[cfg(not(target_os = "macos"))]
+        false
+    }
+
     /// Get the current frame image. Caller must drop the image when done drawing.
     pub fn new_frame_image(&self) -> Option<OpenGLImage> {
         #[cfg(target_os = "macos")]
@@ -730,9 +1997,36 @@ impl OpenGLClient {
         None
     }
 
+    /// Replace the new-frame callback (None detaches it) without recreating the client. Once this
+    /// returns the previous callback is not running and has been dropped. Uses `CallbackDispatch::Serialized`.
+    pub fn set_new_frame_handler(&self, callback: Option<NewFrameCallback>) {
+        self.set_new_frame_handler_with_dispatch(callback, CallbackDispatch::Serialized);
+    }
+
+    /// Like `set_new_frame_handler`, choosing how `callback` is dispatched.
+    pub fn set_new_frame_handler_with_dispatch(&self, callback: Option<NewFrameCallback>, dispatch: CallbackDispatch) {
+        #[cfg(target_os = "macos")]
+        {
+            let (cb, release, userdata) = CallbackHolder::into_raw(callback, dispatch);
+            unsafe {
+                ffi::syphon_opengl_client_set_new_frame_handler(
+                    self.ptr.as_ptr(),
+                    cb,
+                    release,
+                    userdata,
+                    dispatch == CallbackDispatch::Direct,
+                    dispatch == CallbackDispatch::SerializedWithSize,
+                );
+            }
+        }
+    }
+
+    /// Stop receiving frames (idempotent). The new-frame callback is unregistered first: once this
+    /// returns it is not running on another thread and will not be invoked again.
     pub fn stop(&self) {
         #[cfg(target_os = "macos")]
         unsafe {
+            ffi::syphon_opengl_client_unregister_new_frame_handler(self.ptr.as_ptr());
             ffi::syphon_opengl_client_stop(self.ptr.as_ptr());
         }
     }
@@ -750,7 +2044,9 @@ impl Drop for OpenGLClient {
     }
 }
 
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
 impl OpenGLImage {
+    /// The image's `GL_TEXTURE_RECTANGLE` texture, valid while the image is alive.
     pub fn texture_name(&self) -> u32 {
         #[cfg(target_os = "macos")]
         unsafe { ffi::syphon_opengl_image_texture_name(self.ptr.as_ptr()) }
@@ -758,6 +2054,7 @@ impl OpenGLImage {
         0
     }
 
+    /// Texture size in pixels.
     pub fn texture_size(&self) -> (f64, f64) {
         #[cfg(target_os = "macos")]
         {
@@ -771,6 +2068,53 @@ impl OpenGLImage {
         #[cfg(not(target_os = "macos"))]
         (0.0, 0.0)
     }
+
+    /// The frame's pixels as tightly packed RGBA8, top row first (`texture_size` gives the dimensions),
+    /// for apps that want bytes rather than a texture. The image's context must be current; empty on failure.
+    pub fn read_pixels(&self) -> Vec<u8> {
+        self.try_read_pixels().unwrap_or_default()
+    }
+
+    /// Like `read_pixels`, reporting a missing current context as an error.
+    pub fn try_read_pixels(&self) -> Result<Vec<u8>, SyphonError> {
+        let (w, h) = self.texture_size();
+        let (width, height) = (w as usize, h as usize);
+        let mut rgba = vec![0u8; width * height * 4];
+        try_gl_read_texture_rectangle_rgba8(self.texture_name(), width, height, &mut rgba)?;
+        // GL rows are bottom-up.
+        let row = width * 4;
+        for y in 0..height / 2 {
+            let (top, bottom) = rgba.split_at_mut((height - 1 - y) * row);
+            top[y * row..(y + 1) * row].swap_with_slice(&mut bottom[..row]);
+        }
+        Ok(rgba)
+    }
+
+    /// Copy the image into a `GL_TEXTURE_2D` you own, so the snapshot outlives this image without
+    /// holding the Syphon surface. With `dst` None a new RGBA8 texture of the image size is created
+    /// (delete it with `gl_delete_texture`); otherwise `dst` must be RGBA and at least that size.
+    /// The image's context must be current. Returns the texture name, or None on failure.
+    pub fn copy_to_owned_texture(&self, dst: Option<u32>) -> Option<u32> {
+        #[cfg(target_os = "macos")]
+        {
+            let tex = unsafe { ffi::syphon_opengl_image_copy_to_texture_2d(self.ptr.as_ptr(), dst.unwrap_or(0)) };
+            (tex != 0).then_some(tex)
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// The IOSurface backing the image (not retained; valid while the image is alive), or None if the
+    /// Syphon build doesn't expose it. `io_surface` (`io-surface` feature) returns it typed.
+    pub fn io_surface_ptr(&self) -> Option<IOSurfacePtr> {
+        #[cfg(target_os = "macos")]
+        {
+            let surface = unsafe { ffi::syphon_opengl_image_io_surface(self.ptr.as_ptr()) };
+            (!surface.is_null()).then_some(surface)
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
 }
 
 impl Drop for OpenGLImage {
@@ -795,24 +2139,95 @@ impl MetalServer {
         device: MTLDevicePtr,
         options: Option<&SyphonOptions>,
     ) -> Option<Self> {
+        Self::try_new(name, device, options).ok()
+    }
+
+    /// Like `new`, reporting why creation failed.
+    pub fn try_new(
+        name: Option<&str>,
+        device: MTLDevicePtr,
+        options: Option<&SyphonOptions>,
+    ) -> Result<Self, SyphonError> {
         #[cfg(target_os = "macos")]
         {
             if device.is_null() {
-                return None;
+                return Err(SyphonError::InvalidArgument("null MTLDevice"));
             }
-            let name_ptr = name
-                .map(|s| std::ffi::CString::new(s).ok())
-                .flatten()
-                .as_ref()
-                .map(|c| c.as_ptr())
-                .unwrap_or(std::ptr::null());
+            let name = optional_cstring(name)?;
+            let name_ptr = name.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
             let opts_ptr = options.map(|o| o.as_ptr()).unwrap_or(std::ptr::null_mut());
             let ptr =
                 unsafe { ffi::syphon_metal_server_create(name_ptr, device as *mut _, opts_ptr) };
-            NonNull::new(ptr).map(|ptr| Self { ptr })
+            NonNull::new(ptr).map(|ptr| Self { ptr }).ok_or_else(|| creation_failed("Metal server"))
         }
         #[cfg(not(target_os = "macos"))]
-        None
+        Err(SyphonError::Unsupported)
+    }
+
+    #[cfg(all(target_os = "macos", any(feature = "screen-capture", feature = "camera")))]
+    pub(crate) fn as_ptr(&self) -> *mut std::ffi::c_void {
+        self.ptr.as_ptr()
+    }
+
+    /// Create a Metal server on the system default device (`MTLCreateSystemDefaultDevice`), for tools that
+    /// don't otherwise use Metal (CPU publishers with `publish_pixels`, tests, command-line utilities).
+    pub fn new_with_default_device(name: Option<&str>, options: Option<&SyphonOptions>) -> Option<Self> {
+        Self::try_new_with_default_device(name, options).ok()
+    }
+
+    /// Like `new_with_default_device`, reporting why creation failed (e.g. no Metal device).
+    pub fn try_new_with_default_device(name: Option<&str>, options: Option<&SyphonOptions>) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            let name = optional_cstring(name)?;
+            let name_ptr = name.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
+            let opts_ptr = options.map(|o| o.as_ptr()).unwrap_or(std::ptr::null_mut());
+            let ptr = unsafe { ffi::syphon_metal_server_create_with_default_device(name_ptr, opts_ptr) };
+            NonNull::new(ptr).map(|ptr| Self { ptr }).ok_or_else(|| creation_failed("Metal server"))
+        }
+        #[cfg(not(target_os = "macos"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Create a Metal server that owns a command queue, for `publish_frame_simple` and `publish_pixels`.
+    /// Pass the queue you render on as `command_queue` so publishing is ordered after your rendering;
+    /// None creates a new queue on `device`.
+    pub fn new_with_command_queue(
+        name: Option<&str>,
+        device: MTLDevicePtr,
+        options: Option<&SyphonOptions>,
+        command_queue: Option<MTLCommandQueuePtr>,
+    ) -> Option<Self> {
+        Self::try_new_with_command_queue(name, device, options, command_queue).ok()
+    }
+
+    /// Like `new_with_command_queue`, reporting why creation failed (`InvalidArgument` if
+    /// `command_queue` belongs to another device).
+    pub fn try_new_with_command_queue(
+        name: Option<&str>,
+        device: MTLDevicePtr,
+        options: Option<&SyphonOptions>,
+        command_queue: Option<MTLCommandQueuePtr>,
+    ) -> Result<Self, SyphonError> {
+        let server = Self::try_new(name, device, options)?;
+        #[cfg(target_os = "macos")]
+        {
+            let queue = command_queue.unwrap_or(std::ptr::null_mut());
+            if !unsafe { ffi::syphon_metal_server_set_command_queue(server.ptr.as_ptr(), queue as *mut _) } {
+                return Err(match command_queue {
+                    Some(_) => SyphonError::InvalidArgument("command queue is not on the server's MTLDevice"),
+                    None => creation_failed("Metal command queue"),
+                });
+            }
+        }
+        Ok(server)
+    }
+
+    /// Create a private Metal server (see `OpenGLServer::new_private`).
+    pub fn new_private(name: Option<&str>, device: MTLDevicePtr) -> Option<Self> {
+        let options = SyphonOptions::new()?;
+        options.set_is_private(true);
+        Self::new(name, device, Some(&options))
     }
 
     /// The MTLDevice the server uses.
@@ -823,50 +2238,151 @@ impl MetalServer {
         std::ptr::null_mut()
     }
 
-    /// Human-readable server name, if set.
+    /// Raw `MTLPixelFormat` of the surface clients receive (`MTL_PIXEL_FORMAT_*`), negotiated from
+    /// `SyphonOptions::set_pixel_format` and what the framework supports. None before the first publish.
+    pub fn pixel_format(&self) -> Option<u64> {
+        #[cfg(target_os = "macos")]
+        {
+            let format = unsafe { ffi::syphon_metal_server_pixel_format(self.ptr.as_ptr()) } as u64;
+            (format != 0).then_some(format)
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Human-readable server name, if set (reflects `set_name`).
     pub fn name(&self) -> Option<String> {
         #[cfg(target_os = "macos")]
         {
-            let s = unsafe { ffi::syphon_metal_server_copy_name(self.ptr.as_ptr()) };
-            opt_cstr_to_string(s)
+            let s = unsafe { ffi::syphon_metal_server_copy_name(self.ptr.as_ptr()) };
+            opt_cstr_to_string(s)
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Rename the server while it runs (None clears the name); see `OpenGLServer::set_name`.
+    pub fn set_name(&self, name: Option<&str>) {
+        let _ = self.try_set_name(name);
+    }
+
+    /// Like `set_name`, reporting a NUL byte in `name` as `InvalidArgument`.
+    pub fn try_set_name(&self, name: Option<&str>) -> Result<(), SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            let name = optional_cstring(name)?;
+            let name_ptr = name.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
+            unsafe { ffi::syphon_metal_server_set_name(self.ptr.as_ptr(), name_ptr) };
+            Ok(())
+        }
+        #[cfg(not(target_os = "macos"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    pub fn has_clients(&self) -> bool {
+        #[cfg(target_os = "macos")]
+        unsafe { ffi::syphon_metal_server_has_clients(self.ptr.as_ptr()) }
+        #[cfg(not(target_os = "macos"))]
+        false
+    }
+
+    /// Server description (retained; caller owns).
+    pub fn server_description(&self) -> Option<ServerDescription> {
+        #[cfg(target_os = "macos")]
+        {
+            let ptr = unsafe { ffi::syphon_metal_server_server_description(self.ptr.as_ptr()) };
+            NonNull::new(ptr).map(|ptr| ServerDescription { ptr })
         }
         #[cfg(not(target_os = "macos"))]
         None
     }
 
-    /// Set the server's human-readable name.
-    pub fn set_name(&self, name: Option<&str>) {
+    /// Publish `region` of a Metal texture; `flipped` if the image is stored upside down. You must commit `command_buffer`.
+    pub fn publish(&self, texture: MTLTexturePtr, command_buffer: MTLCommandBufferPtr, region: Rect, flipped: bool) {
         #[cfg(target_os = "macos")]
-        {
-            let name_ptr = name
-                .map(|s| std::ffi::CString::new(s).ok())
-                .flatten()
-                .as_ref()
-                .map(|c| c.as_ptr())
-                .unwrap_or(std::ptr::null());
-            unsafe { ffi::syphon_metal_server_set_name(self.ptr.as_ptr(), name_ptr) };
+        if !texture.is_null() && !command_buffer.is_null() {
+            unsafe {
+                ffi::syphon_metal_server_publish_frame(
+                    self.ptr.as_ptr(),
+                    texture as *mut _,
+                    command_buffer as *mut _,
+                    region.x,
+                    region.y,
+                    region.w,
+                    region.h,
+                    flipped,
+                );
+            }
         }
     }
 
-    pub fn has_clients(&self) -> bool {
+    /// Publish `region` of a Metal texture on a command buffer from the server's queue (see
+    /// `new_with_command_queue`; created on first use otherwise), committed here. False if `texture`
+    /// is null or there is no queue.
+    pub fn publish_frame_simple(&self, texture: MTLTexturePtr, region: Rect, flipped: bool) -> bool {
         #[cfg(target_os = "macos")]
-        unsafe { ffi::syphon_metal_server_has_clients(self.ptr.as_ptr()) }
+        unsafe {
+            ffi::syphon_metal_server_publish_frame_simple(
+                self.ptr.as_ptr(),
+                texture as *mut _,
+                region.x,
+                region.y,
+                region.w,
+                region.h,
+                flipped,
+            )
+        }
         #[cfg(not(target_os = "macos"))]
         false
     }
 
-    /// Server description (retained; caller owns).
-    pub fn server_description(&self) -> Option<ServerDescription> {
+    /// Publish `region` of a texture rendered on another queue once `event` reaches `value` (signal it
+    /// from the producing command buffer): a wait for the event is encoded before Syphon's copy, so a
+    /// frame still being rendered is never published torn. With `command_buffer` None, publishes on the
+    /// server's queue and commits as `publish_frame_simple`; otherwise you must commit it.
+    /// False if a pointer is null or `event` is not an MTLSharedEvent.
+    pub fn publish_after_event(
+        &self,
+        texture: MTLTexturePtr,
+        command_buffer: Option<MTLCommandBufferPtr>,
+        region: Rect,
+        flipped: bool,
+        event: MTLSharedEventPtr,
+        value: u64,
+    ) -> bool {
         #[cfg(target_os = "macos")]
-        {
-            let ptr = unsafe { ffi::syphon_metal_server_server_description(self.ptr.as_ptr()) };
-            NonNull::new(ptr).map(|ptr| ServerDescription { ptr, owned: true })
+        unsafe {
+            ffi::syphon_metal_server_publish_frame_after_event(
+                self.ptr.as_ptr(),
+                texture as *mut _,
+                command_buffer.unwrap_or(std::ptr::null_mut()) as *mut _,
+                event as *mut _,
+                value,
+                region.x,
+                region.y,
+                region.w,
+                region.h,
+                flipped,
+            )
         }
         #[cfg(not(target_os = "macos"))]
-        None
+        false
+    }
+
+    /// The command queue the server publishes on (created on first use if the server was not made
+    /// with `new_with_command_queue`); null if it could not be created.
+    pub fn command_queue(&self) -> MTLCommandQueuePtr {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_metal_server_command_queue(self.ptr.as_ptr()) as MTLCommandQueuePtr
+        }
+        #[cfg(not(target_os = "macos"))]
+        std::ptr::null_mut()
     }
 
     /// Publish a frame from a Metal texture. Region (x, y, w, h). You must commit `command_buffer`.
+    #[deprecated(note = "use `publish` with a `Rect`")]
+    #[allow(clippy::too_many_arguments)]
     pub fn publish_frame(
         &self,
         texture: MTLTexturePtr,
@@ -877,21 +2393,107 @@ impl MetalServer {
         h: f64,
         flipped: bool,
     ) {
+        self.publish(texture, command_buffer, Rect::new(x, y, w, h), flipped);
+    }
+
+    /// Publish a frame of `size` of which only `region` (whole pixels, top-left origin) changed: only
+    /// `rgba` (the region's RGBA8 pixels, top row first) is uploaded, into a frame kept for this server,
+    /// which is then published. The first call, and the first after a size change, must cover the whole
+    /// frame. You must commit `command_buffer`.
+    pub fn publish_rgba_region(
+        &self,
+        command_buffer: MTLCommandBufferPtr,
+        region: Rect,
+        size: Size,
+        rgba: &[u8],
+    ) -> bool {
+        let (Some([x, y, w, h]), Some([frame_w, frame_h])) =
+            (whole_pixels([region.x, region.y, region.w, region.h]), whole_pixels([size.w, size.h]))
+        else {
+            return false;
+        };
+        if w.checked_mul(h).and_then(|n| n.checked_mul(4)).is_none_or(|n| rgba.len() < n) {
+            return false;
+        }
         #[cfg(target_os = "macos")]
-        if !texture.is_null() && !command_buffer.is_null() {
+        {
+            if command_buffer.is_null() {
+                return false;
+            }
             unsafe {
-                ffi::syphon_metal_server_publish_frame(
+                ffi::syphon_metal_server_publish_rgba8_region(
                     self.ptr.as_ptr(),
-                    texture as *mut _,
                     command_buffer as *mut _,
+                    frame_w,
+                    frame_h,
                     x,
                     y,
                     w,
                     h,
+                    rgba.as_ptr(),
+                )
+            }
+        }
+        #[cfg(not(target_os = "macos"))]
+        false
+    }
+
+    /// Publish a `width` x `height` frame of RGBA8 pixels from CPU memory (`flipped` if the rows are
+    /// bottom-up). The pixels are uploaded into a staging texture kept for this server and published on
+    /// a command buffer from the server's queue (`command_queue`), committed here. False if `pixels` is too short.
+    pub fn publish_pixels(&self, pixels: &[u8], width: usize, height: usize, flipped: bool) -> bool {
+        if width.checked_mul(height).and_then(|n| n.checked_mul(4)).is_none_or(|n| pixels.len() < n) {
+            return false;
+        }
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_metal_server_publish_pixels(self.ptr.as_ptr(), pixels.as_ptr(), width, height, flipped)
+        }
+        #[cfg(not(target_os = "macos"))]
+        false
+    }
+
+    /// Render a CIImage (raw `CIImage*`) through a CIContext the server keeps on its command queue and
+    /// publish it, committing the work (`publish_ci_image` takes a typed image). The frame is the image's
+    /// extent; false if the extent is infinite or empty or `image` is null.
+    #[cfg(feature = "core-image")]
+    pub fn publish_ci_image_ptr(&self, image: CIImagePtr) -> bool {
+        if image.is_null() {
+            return false;
+        }
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_metal_server_publish_ci_image(self.ptr.as_ptr(), image)
+        }
+        #[cfg(not(target_os = "macos"))]
+        false
+    }
+
+    /// Publish a `CAMetalDrawable`'s whole texture. Call after rendering into the drawable and
+    /// before `presentDrawable:`; you must commit `command_buffer`. Returns false if the drawable's
+    /// texture can't be read (the layer must have `framebufferOnly = false`) or a pointer is null.
+    pub fn publish_drawable(
+        &self,
+        drawable: CAMetalDrawablePtr,
+        command_buffer: MTLCommandBufferPtr,
+        flipped: bool,
+    ) -> bool {
+        #[cfg(target_os = "macos")]
+        {
+            if drawable.is_null() || command_buffer.is_null() {
+                return false;
+            }
+            unsafe {
+                ffi::syphon_metal_server_publish_drawable(
+                    self.ptr.as_ptr(),
+                    drawable as *mut _,
+                    command_buffer as *mut _,
                     flipped,
-                );
+                )
             }
         }
+        #[cfg(not(target_os = "macos"))]
+        false
     }
 
     /// Current frame as MTLTexture (caller must release via MetalTexture or syphon_metal_texture_release).
@@ -905,6 +2507,7 @@ impl MetalServer {
         None
     }
 
+    /// Stop the server. Idempotent: only the first call (explicit or from `Drop`) stops it.
     pub fn stop(&self) {
         #[cfg(target_os = "macos")]
         unsafe {
@@ -935,45 +2538,82 @@ impl MetalClient {
     pub fn new(
         description: &ServerDescription,
         device: MTLDevicePtr,
-        _options: Option<&std::collections::HashMap<String, String>>,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+    ) -> Option<Self> {
+        Self::new_with_dispatch(description, device, options, callback, CallbackDispatch::Serialized)
+    }
+
+    /// Like `new`, choosing how `callback` is dispatched (see `CallbackDispatch::Direct` for its constraints).
+    pub fn new_with_dispatch(
+        description: &ServerDescription,
+        device: MTLDevicePtr,
+        options: Option<&SyphonOptions>,
         callback: Option<NewFrameCallback>,
+        dispatch: CallbackDispatch,
     ) -> Option<Self> {
+        Self::try_new_with_dispatch(description, device, options, callback, dispatch).ok()
+    }
+
+    /// Like `new`, but new-frame notifications are queued on the returned channel instead of running a
+    /// closure on Syphon's thread, so a render thread can `recv` / `try_recv` them.
+    pub fn new_with_channel(
+        description: &ServerDescription,
+        device: MTLDevicePtr,
+        options: Option<&SyphonOptions>,
+    ) -> Option<(Self, std::sync::mpsc::Receiver<FrameInfo>)> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let client = Self::new(
+            description,
+            device,
+            options,
+            Some(Box::new(move |info| {
+                let _ = tx.send(info);
+            })),
+        )?;
+        Some((client, rx))
+    }
+
+    /// Like `new`, reporting why creation failed.
+    pub fn try_new(
+        description: &ServerDescription,
+        device: MTLDevicePtr,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+    ) -> Result<Self, SyphonError> {
+        Self::try_new_with_dispatch(description, device, options, callback, CallbackDispatch::Serialized)
+    }
+
+    /// Like `new_with_dispatch`, reporting why creation failed.
+    pub fn try_new_with_dispatch(
+        description: &ServerDescription,
+        device: MTLDevicePtr,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+        dispatch: CallbackDispatch,
+    ) -> Result<Self, SyphonError> {
         #[cfg(target_os = "macos")]
         {
             if device.is_null() {
-                return None;
+                return Err(SyphonError::InvalidArgument("null MTLDevice"));
             }
-            unsafe extern "C" fn raw_callback(userdata: *mut std::ffi::c_void) {
-                if userdata.is_null() {
-                    return;
-                }
-                let h = &*(userdata as *const CallbackHolder);
-                (h.0)();
-            }
-            let callback_storage: Option<Box<CallbackHolder>> =
-                callback.map(|c| Box::new(CallbackHolder(c)));
-            let userdata = callback_storage
-                .as_ref()
-                .map(|b| (&**b) as *const CallbackHolder as *mut std::ffi::c_void)
-                .unwrap_or(std::ptr::null_mut());
-            type Cb = unsafe extern "C" fn(*mut std::ffi::c_void);
-            let cb_opt: Option<Cb> = callback_storage.as_ref().map(|_| raw_callback as Cb);
+            let (cb, release, userdata) = CallbackHolder::into_raw(callback, dispatch);
             let ptr = unsafe {
                 ffi::syphon_metal_client_create(
                     description.ptr.as_ptr(),
                     device as *mut _,
-                    std::ptr::null_mut(),
-                    cb_opt,
+                    options.map(|o| o.as_ptr()).unwrap_or(std::ptr::null_mut()),
+                    cb,
+                    release,
                     userdata,
+                    dispatch == CallbackDispatch::Direct,
+                    dispatch == CallbackDispatch::SerializedWithSize,
                 )
             };
-            NonNull::new(ptr).map(|ptr| Self {
-                ptr,
-                _callback_storage: callback_storage,
-            })
+            NonNull::new(ptr).map(|ptr| Self { ptr }).ok_or_else(|| creation_failed("Metal client"))
         }
         #[cfg(not(target_os = "macos"))]
-        None
+        Err(SyphonError::Unsupported)
     }
 
     /// Server description for the server this client is attached to (retained; caller owns).
@@ -981,7 +2621,7 @@ impl MetalClient {
         #[cfg(target_os = "macos")]
         {
             let ptr = unsafe { ffi::syphon_metal_client_server_description(self.ptr.as_ptr()) };
-            NonNull::new(ptr).map(|ptr| ServerDescription { ptr, owned: true })
+            NonNull::new(ptr).map(|ptr| ServerDescription { ptr })
         }
         #[cfg(not(target_os = "macos"))]
         None
@@ -1001,6 +2641,28 @@ impl MetalClient {
         false
     }
 
+    /// Encode a copy of the current frame into your own `texture` (at its origin) on `command_buffer`;
+    /// you must commit it. The texture's storage mode and usage are up to you, but it must have the
+    /// frame's pixel format, one sample, not be framebuffer-only and be at least the frame size.
+    /// Returns false on mismatch, a null pointer or no frame.
+    pub fn copy_frame_to(&self, texture: MTLTexturePtr, command_buffer: MTLCommandBufferPtr) -> bool {
+        #[cfg(target_os = "macos")]
+        {
+            if texture.is_null() || command_buffer.is_null() {
+                return false;
+            }
+            unsafe {
+                ffi::syphon_metal_client_copy_frame_to(
+                    self.ptr.as_ptr(),
+                    texture as *mut _,
+                    command_buffer as *mut _,
+                )
+            }
+        }
+        #[cfg(not(target_os = "macos"))]
+        false
+    }
+
     /// Get the current frame as MTLTexture. Caller must drop the returned value when done.
     pub fn new_frame_image(&self) -> Option<MetalTexture> {
         #[cfg(target_os = "macos")]
@@ -1012,9 +2674,36 @@ impl MetalClient {
         None
     }
 
+    /// Replace the new-frame callback (None detaches it) without recreating the client. Once this
+    /// returns the previous callback is not running and has been dropped. Uses `CallbackDispatch::Serialized`.
+    pub fn set_new_frame_handler(&self, callback: Option<NewFrameCallback>) {
+        self.set_new_frame_handler_with_dispatch(callback, CallbackDispatch::Serialized);
+    }
+
+    /// Like `set_new_frame_handler`, choosing how `callback` is dispatched.
+    pub fn set_new_frame_handler_with_dispatch(&self, callback: Option<NewFrameCallback>, dispatch: CallbackDispatch) {
+        #[cfg(target_os = "macos")]
+        {
+            let (cb, release, userdata) = CallbackHolder::into_raw(callback, dispatch);
+            unsafe {
+                ffi::syphon_metal_client_set_new_frame_handler(
+                    self.ptr.as_ptr(),
+                    cb,
+                    release,
+                    userdata,
+                    dispatch == CallbackDispatch::Direct,
+                    dispatch == CallbackDispatch::SerializedWithSize,
+                );
+            }
+        }
+    }
+
+    /// Stop receiving frames (idempotent). The new-frame callback is unregistered first: once this
+    /// returns it is not running on another thread and will not be invoked again.
     pub fn stop(&self) {
         #[cfg(target_os = "macos")]
         unsafe {
+            ffi::syphon_metal_client_unregister_new_frame_handler(self.ptr.as_ptr());
             ffi::syphon_metal_client_stop(self.ptr.as_ptr());
         }
     }
@@ -1032,7 +2721,14 @@ impl Drop for MetalClient {
     }
 }
 
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
 impl MetalTexture {
+    /// Take ownership of a +1 retained MTLTexture from the glue.
+    #[cfg(target_os = "macos")]
+    pub(crate) unsafe fn from_retained(ptr: NonNull<std::ffi::c_void>) -> Self {
+        MetalTexture { ptr }
+    }
+
     /// Raw MTLTexture pointer for use with the `metal` crate or other Metal code.
     pub fn as_ptr(&self) -> MTLTexturePtr {
         #[cfg(target_os = "macos")]
@@ -1042,6 +2738,162 @@ impl MetalTexture {
         #[cfg(not(target_os = "macos"))]
         std::ptr::null_mut()
     }
+
+    /// Raw `MTLPixelFormat` (`MTL_PIXEL_FORMAT_*`); for received frames, the format the server negotiated.
+    pub fn pixel_format(&self) -> u64 {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_metal_texture_pixel_format(self.ptr.as_ptr()) as u64
+        }
+        #[cfg(not(target_os = "macos"))]
+        0
+    }
+
+    /// Width and height in pixels.
+    pub fn size(&self) -> (usize, usize) {
+        #[cfg(target_os = "macos")]
+        {
+            let (mut w, mut h) = (0, 0);
+            unsafe { ffi::syphon_metal_texture_size(self.ptr.as_ptr(), &mut w, &mut h) };
+            (w, h)
+        }
+        #[cfg(not(target_os = "macos"))]
+        (0, 0)
+    }
+
+    /// Width in pixels.
+    pub fn width(&self) -> usize {
+        self.size().0
+    }
+
+    /// Height in pixels.
+    pub fn height(&self) -> usize {
+        self.size().1
+    }
+
+    /// Raw `MTLTextureUsage` flags (`MTL_TEXTURE_USAGE_*`).
+    pub fn usage(&self) -> u64 {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_metal_texture_usage(self.ptr.as_ptr()) as u64
+        }
+        #[cfg(not(target_os = "macos"))]
+        0
+    }
+
+    /// Raw `MTLStorageMode` (`MTL_STORAGE_MODE_*`).
+    pub fn storage_mode(&self) -> u64 {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_metal_texture_storage_mode(self.ptr.as_ptr()) as u64
+        }
+        #[cfg(not(target_os = "macos"))]
+        0
+    }
+
+    /// The IOSurface backing the texture (not retained; valid while the texture is alive), or None if it
+    /// isn't IOSurface-backed (e.g. a `copy_to_owned` copy). `io_surface` (`io-surface` feature) returns it typed.
+    pub fn io_surface_ptr(&self) -> Option<IOSurfacePtr> {
+        #[cfg(target_os = "macos")]
+        {
+            let surface = unsafe { ffi::syphon_metal_texture_io_surface(self.ptr.as_ptr()) };
+            (!surface.is_null()).then_some(surface)
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// A shader-readable texture on `device` over `surface` (no copy), sized like the surface, with raw
+    /// `MTLPixelFormat` `pixel_format` (which must match the surface's layout). For importing a frame's
+    /// surface into another device or API, e.g. a Syphon surface passed across by `io_surface_ptr`.
+    pub fn from_io_surface(device: MTLDevicePtr, surface: IOSurfacePtr, pixel_format: u64) -> Option<MetalTexture> {
+        #[cfg(target_os = "macos")]
+        {
+            if device.is_null() || surface.is_null() {
+                return None;
+            }
+            let ptr = unsafe { ffi::syphon_metal_texture_create_with_io_surface(device, surface, pixel_format as _) };
+            NonNull::new(ptr).map(|ptr| MetalTexture { ptr })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Copy this frame into a new private-storage texture you can keep indefinitely (freeze frames,
+    /// thumbnails) without holding the Syphon surface. Uses `command_queue` if given, otherwise a
+    /// queue on the texture's device; blocks until the copy completes, so `self` can be dropped right away.
+    pub fn copy_to_owned(&self, command_queue: Option<MTLCommandQueuePtr>) -> Option<MetalTexture> {
+        self.copy_to_owned_with(command_queue, MetalTextureOptions::default())
+    }
+
+    /// Like `copy_to_owned`, creating the copy with `options` (e.g. shader-write usage for engines that
+    /// post-process in place, or managed storage for CPU readback). None if the device rejects them.
+    pub fn copy_to_owned_with(
+        &self,
+        command_queue: Option<MTLCommandQueuePtr>,
+        options: MetalTextureOptions,
+    ) -> Option<MetalTexture> {
+        #[cfg(target_os = "macos")]
+        {
+            let queue = command_queue.unwrap_or(std::ptr::null_mut());
+            let ptr = unsafe {
+                ffi::syphon_metal_texture_copy_to_owned(
+                    self.ptr.as_ptr(),
+                    queue as *mut _,
+                    options.storage_mode as _,
+                    options.usage as _,
+                )
+            };
+            NonNull::new(ptr).map(|ptr| MetalTexture { ptr })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Bytes per pixel of a `read_to_vec` readback (e.g. 4 for BGRA8, 8 for RGBA16Float); 0 if the
+    /// pixel format can't be read back.
+    pub fn bytes_per_pixel(&self) -> usize {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_metal_texture_bytes_per_pixel(self.ptr.as_ptr())
+        }
+        #[cfg(not(target_os = "macos"))]
+        0
+    }
+
+    /// Copy the pixels to the CPU: blits into a shared-storage buffer (queue as for `copy_to_owned`),
+    /// waits, and returns tightly packed rows (`width * bytes_per_pixel` bytes each, top row first) in the
+    /// texture's own pixel format. Empty if the format can't be read back or the blit failed.
+    pub fn read_to_vec(&self, command_queue: Option<MTLCommandQueuePtr>) -> Vec<u8> {
+        #[cfg(target_os = "macos")]
+        {
+            let (w, h) = self.size();
+            let mut bytes = vec![0u8; w * h * self.bytes_per_pixel()];
+            let queue = command_queue.unwrap_or(std::ptr::null_mut());
+            if !bytes.is_empty()
+                && unsafe {
+                    ffi::syphon_metal_texture_read_bytes(self.ptr.as_ptr(), queue as *mut _, bytes.as_mut_ptr(), bytes.len())
+                }
+            {
+                return bytes;
+            }
+        }
+        Vec::new()
+    }
+}
+
+impl Clone for MetalTexture {
+    /// Another reference to the same texture (an ObjC retain), not a copy of its pixels; see `copy_to_owned`.
+    fn clone(&self) -> Self {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_metal_texture_retain(self.ptr.as_ptr());
+        }
+        Self {
+            #[cfg(target_os = "macos")]
+            ptr: self.ptr,
+        }
+    }
 }
 
 impl Drop for MetalTexture {
@@ -1053,6 +2905,41 @@ impl Drop for MetalTexture {
     }
 }
 
+/// A `MetalTexture` that can be moved to another thread, e.g. from a client's new-frame callback to a
+/// render or encode thread (through a channel).
+///
+/// Retaining, releasing and reading an MTLTexture's properties are thread-safe, and GPU access goes
+/// through command buffers, so moving the reference is sound. The texture's contents are not
+/// synchronized by this: a received frame is only stable until the server publishes over it, so take a
+/// `copy_to_owned` first if the other thread uses it later than the next frame.
+pub struct SendableMetalTexture(MetalTexture);
+
+// See the type's documentation. Not Sync: one thread at a time holds the texture.
+unsafe impl Send for SendableMetalTexture {}
+
+impl SendableMetalTexture {
+    /// Wrap `texture` for sending.
+    pub fn new(texture: MetalTexture) -> Self {
+        Self(texture)
+    }
+
+    /// The texture, on the receiving thread.
+    pub fn into_inner(self) -> MetalTexture {
+        self.0
+    }
+
+    /// Borrow the texture.
+    pub fn get(&self) -> &MetalTexture {
+        &self.0
+    }
+}
+
+impl From<MetalTexture> for SendableMetalTexture {
+    fn from(texture: MetalTexture) -> Self {
+        Self::new(texture)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Spout (Windows)
 // ---------------------------------------------------------------------------
@@ -1073,13 +2960,12 @@ impl Spout {
     }
 
     /// ---- Sender ----
-    /// Set the sender name (publisher name).
+    /// Set the sender name (publisher name). A name containing a NUL byte is ignored.
     pub fn sender_set_name(&self, name: Option<&str>) {
-        let name_ptr = name
-            .and_then(|s| std::ffi::CString::new(s).ok())
-            .as_ref()
-            .map(|c| c.as_ptr())
-            .unwrap_or(std::ptr::null());
+        let Ok(name) = optional_cstring(name) else {
+            return;
+        };
+        let name_ptr = name.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
         unsafe { spout_ffi::spout_sender_set_name(self.handle.as_ptr(), name_ptr) };
     }
 
@@ -1189,13 +3075,13 @@ impl Spout {
     }
 
     /// ---- Receiver ----
-    /// Set the sender name to receive from (None = active sender).
+    /// Set the sender name to receive from (None = active sender). A name containing a NUL byte is
+    /// ignored.
     pub fn receiver_set_name(&self, sender_name: Option<&str>) {
-        let ptr = sender_name
-            .and_then(|s| std::ffi::CString::new(s).ok())
-            .as_ref()
-            .map(|c| c.as_ptr())
-            .unwrap_or(std::ptr::null());
+        let Ok(sender_name) = optional_cstring(sender_name) else {
+            return;
+        };
+        let ptr = sender_name.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
         unsafe { spout_ffi::spout_receiver_set_name(self.handle.as_ptr(), ptr) };
     }
 
@@ -1340,13 +3226,12 @@ impl Spout {
         std::str::from_utf8(&buf[..end]).ok().map(|s| s.to_string())
     }
 
-    /// Set the active sender by name.
+    /// Set the active sender by name. False for a name containing a NUL byte.
     pub fn set_active_sender(&self, sendername: Option<&str>) -> bool {
-        let ptr = sendername
-            .and_then(|s| std::ffi::CString::new(s).ok())
-            .as_ref()
-            .map(|c| c.as_ptr())
-            .unwrap_or(std::ptr::null());
+        let Ok(sendername) = optional_cstring(sendername) else {
+            return false;
+        };
+        let ptr = sendername.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
         unsafe { spout_ffi::spout_set_active_sender(self.handle.as_ptr(), ptr) }
     }
 
@@ -1378,23 +3263,21 @@ impl Spout {
         })
     }
 
-    /// Signal a frame-sync event for a sender.
+    /// Signal a frame-sync event for a sender. A name containing a NUL byte is ignored.
     pub fn set_frame_sync(&self, sendername: Option<&str>) {
-        let ptr = sendername
-            .and_then(|s| std::ffi::CString::new(s).ok())
-            .as_ref()
-            .map(|c| c.as_ptr())
-            .unwrap_or(std::ptr::null());
+        let Ok(sendername) = optional_cstring(sendername) else {
+            return;
+        };
+        let ptr = sendername.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
         unsafe { spout_ffi::spout_set_frame_sync(self.handle.as_ptr(), ptr) };
     }
 
-    /// Wait (or poll with timeout 0) for a frame-sync event.
+    /// Wait (or poll with timeout 0) for a frame-sync event. False for a name containing a NUL byte.
     pub fn wait_frame_sync(&self, sendername: Option<&str>, timeout_ms: u32) -> bool {
-        let ptr = sendername
-            .and_then(|s| std::ffi::CString::new(s).ok())
-            .as_ref()
-            .map(|c| c.as_ptr())
-            .unwrap_or(std::ptr::null());
+        let Ok(sendername) = optional_cstring(sendername) else {
+            return false;
+        };
+        let ptr = sendername.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
         unsafe { spout_ffi::spout_wait_frame_sync(self.handle.as_ptr(), ptr, timeout_ms) }
     }
 
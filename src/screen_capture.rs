@@ -0,0 +1,189 @@
+//! Screen to Syphon (`screen-capture` feature): `ScreenCapture` records a display or a single window through
+//! ScreenCaptureKit (macOS 12.3+) and republishes every new frame through its own `MetalServer`. The process
+//! needs screen-recording permission (System Settings > Privacy & Security); without it listing and capture
+//! fail.
+
+#[cfg(target_os = "macos")]
+use std::ptr::NonNull;
+
+#[cfg(target_os = "macos")]
+use crate::ffi;
+#[cfg(target_os = "macos")]
+use crate::safe::{creation_failed, opt_cstr_to_string};
+use crate::{MetalServer, SyphonError};
+
+/// A display ScreenCaptureKit can capture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CaptureDisplay {
+    /// `CGDirectDisplayID`.
+    pub id: u32,
+    /// Size in pixels.
+    pub width: usize,
+    pub height: usize,
+}
+
+/// An on-screen window ScreenCaptureKit can capture.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CaptureWindow {
+    /// `CGWindowID`.
+    pub id: u32,
+    pub title: Option<String>,
+    /// Name of the owning application.
+    pub app_name: Option<String>,
+    /// Size in points.
+    pub width: usize,
+    pub height: usize,
+}
+
+/// What a `ScreenCapture` records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CaptureSource {
+    /// A whole display, by `CGDirectDisplayID` (`CaptureDisplay::id`).
+    Display(u32),
+    /// One window wherever it is, even when covered, by `CGWindowID` (`CaptureWindow::id`).
+    Window(u32),
+}
+
+/// Capture settings; the default captures at the source's pixel size and ScreenCaptureKit's default rate
+/// (60 fps), with the cursor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenCaptureOptions {
+    /// Output size in pixels (the source is scaled to fit); None for the source's own size.
+    pub size: Option<(usize, usize)>,
+    /// Maximum frames per second; None for ScreenCaptureKit's default.
+    pub frame_rate: Option<f64>,
+    pub show_cursor: bool,
+}
+
+impl Default for ScreenCaptureOptions {
+    fn default() -> Self {
+        Self {
+            size: None,
+            frame_rate: None,
+            show_cursor: true,
+        }
+    }
+}
+
+/// Query ScreenCaptureKit's shareable content and read it with `f` (blocks until the query completes).
+#[cfg(target_os = "macos")]
+fn with_shareable_content<T>(f: impl FnOnce(*mut std::ffi::c_void) -> T) -> Result<T, SyphonError> {
+    let content = unsafe { ffi::syphon_sc_content_copy() };
+    if content.is_null() {
+        return Err(creation_failed("shareable content"));
+    }
+    let out = f(content);
+    unsafe { ffi::syphon_metal_object_release(content) };
+    Ok(out)
+}
+
+/// The displays that can be captured. `Unsupported` off macOS; `CreationFailed` before macOS 12.3 or
+/// without screen-recording permission.
+pub fn shareable_displays() -> Result<Vec<CaptureDisplay>, SyphonError> {
+    #[cfg(target_os = "macos")]
+    {
+        with_shareable_content(|content| {
+            let count = unsafe { ffi::syphon_sc_content_display_count(content) };
+            (0..count)
+                .filter_map(|i| {
+                    let (mut id, mut width, mut height) = (0, 0, 0);
+                    unsafe { ffi::syphon_sc_content_display_at(content, i, &mut id, &mut width, &mut height) }
+                        .then_some(CaptureDisplay { id, width, height })
+                })
+                .collect()
+        })
+    }
+    #[cfg(not(target_os = "macos"))]
+    Err(SyphonError::Unsupported)
+}
+
+/// The on-screen windows that can be captured (desktop elements excluded), with the same errors as
+/// `shareable_displays`.
+pub fn shareable_windows() -> Result<Vec<CaptureWindow>, SyphonError> {
+    #[cfg(target_os = "macos")]
+    {
+        with_shareable_content(|content| {
+            let count = unsafe { ffi::syphon_sc_content_window_count(content) };
+            (0..count)
+                .filter_map(|i| {
+                    let (mut id, mut width, mut height) = (0, 0, 0);
+                    if !unsafe { ffi::syphon_sc_content_window_at(content, i, &mut id, &mut width, &mut height) } {
+                        return None;
+                    }
+                    Some(CaptureWindow {
+                        id,
+                        title: opt_cstr_to_string(unsafe { ffi::syphon_sc_content_copy_window_title(content, i) }),
+                        app_name: opt_cstr_to_string(unsafe { ffi::syphon_sc_content_copy_window_app_name(content, i) }),
+                        width,
+                        height,
+                    })
+                })
+                .collect()
+        })
+    }
+    #[cfg(not(target_os = "macos"))]
+    Err(SyphonError::Unsupported)
+}
+
+/// A running capture publishing through its server. Frames are published from ScreenCaptureKit's queue as
+/// they arrive (only when the source changes); dropping the capture stops it, then the server.
+pub struct ScreenCapture {
+    #[cfg(target_os = "macos")]
+    ptr: NonNull<std::ffi::c_void>,
+    server: MetalServer,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl ScreenCapture {
+    /// Start capturing `source` into a new server named `name` on the system default Metal device.
+    pub fn new(name: Option<&str>, source: CaptureSource, options: &ScreenCaptureOptions) -> Option<Self> {
+        Self::try_new(name, source, options).ok()
+    }
+
+    /// Like `new`, reporting why capture could not start (no such display or window, no permission, ...).
+    pub fn try_new(
+        name: Option<&str>,
+        source: CaptureSource,
+        options: &ScreenCaptureOptions,
+    ) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            let server = MetalServer::try_new_with_default_device(name, None)?;
+            let (display, window) = match source {
+                CaptureSource::Display(id) => (id, 0),
+                CaptureSource::Window(id) => (0, id),
+            };
+            let (width, height) = options.size.unwrap_or((0, 0));
+            let ptr = unsafe {
+                ffi::syphon_screen_capture_create(
+                    server.as_ptr(),
+                    display,
+                    window,
+                    width,
+                    height,
+                    options.frame_rate.unwrap_or(0.0),
+                    options.show_cursor,
+                )
+            };
+            NonNull::new(ptr)
+                .map(|ptr| Self { ptr, server })
+                .ok_or_else(|| creation_failed("screen capture"))
+        }
+        #[cfg(not(target_os = "macos"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// The server the capture publishes through (for its description, client count, renaming, ...).
+    pub fn server(&self) -> &MetalServer {
+        &self.server
+    }
+}
+
+impl Drop for ScreenCapture {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_screen_capture_release(self.ptr.as_ptr());
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! SDL2 integration (`sdl2` feature): servers on an SDL GL window's context, and `Sdl2WindowPublisher`,
+//! which publishes what the window drew each frame, so an SDL app becomes a Syphon source with one call
+//! before `gl_swap_window`.
+
+use sdl2::video::{GLContext, Window};
+
+use crate::{cgl_current_context, CGLContextObj, MsaaResolver, OpenGLServer, SyphonError, SyphonOptions};
+
+/// Make `context` current on `window` and return its CGL context (unretained; valid while `context` lives).
+pub fn cgl_context_from_sdl2(window: &Window, context: &GLContext) -> Result<CGLContextObj, SyphonError> {
+    window.gl_make_current(context).map_err(|reason| SyphonError::CreationFailed {
+        object: "SDL GL context",
+        reason,
+    })?;
+    let ctx = cgl_current_context();
+    if ctx.is_null() {
+        return Err(SyphonError::InvalidArgument("SDL GL context has no CGL context"));
+    }
+    Ok(ctx)
+}
+
+impl OpenGLServer {
+    /// `new` on an SDL window's GL context (made current on `window`).
+    pub fn with_sdl2_window(
+        name: Option<&str>,
+        window: &Window,
+        context: &GLContext,
+        options: Option<&SyphonOptions>,
+    ) -> Option<Self> {
+        Self::try_with_sdl2_window(name, window, context, options).ok()
+    }
+
+    /// Like `with_sdl2_window`, reporting why creation failed.
+    pub fn try_with_sdl2_window(
+        name: Option<&str>,
+        window: &Window,
+        context: &GLContext,
+        options: Option<&SyphonOptions>,
+    ) -> Result<Self, SyphonError> {
+        Self::try_new(name, cgl_context_from_sdl2(window, context)?, options)
+    }
+}
+
+/// Publishes an SDL window's default framebuffer: `publish` copies the frame the app just drew (resolving
+/// multisampled windows) into a texture and publishes it. Use and drop with the window's context current.
+pub struct Sdl2WindowPublisher {
+    resolver: MsaaResolver,
+    server: OpenGLServer,
+}
+
+impl Sdl2WindowPublisher {
+    /// A server named `name` on `window`'s GL context.
+    pub fn new(
+        name: Option<&str>,
+        window: &Window,
+        context: &GLContext,
+        options: Option<&SyphonOptions>,
+    ) -> Result<Self, SyphonError> {
+        let server = OpenGLServer::try_with_sdl2_window(name, window, context, options)?;
+        let resolver = MsaaResolver::new().ok_or(SyphonError::Unsupported)?;
+        Ok(Self { resolver, server })
+    }
+
+    /// The underlying server (e.g. to check `has_clients` before rendering extra work).
+    pub fn server(&self) -> &OpenGLServer {
+        &self.server
+    }
+
+    /// Publish the window's back buffer at its drawable size (pixels, so Retina windows publish at full
+    /// resolution). Call after drawing and before `gl_swap_window`; false if the copy failed.
+    pub fn publish(&mut self, window: &Window) -> bool {
+        let (width, height) = window.drawable_size();
+        self.resolver.resolve_and_publish(&self.server, 0, width as usize, height as usize)
+    }
+}
@@ -0,0 +1,418 @@
+//! Shared-memory frame export: mirrors the latest frame into a named POSIX shared-memory region so
+//! processes without Syphon support (analysis scripts, other runtimes) can read frames without sockets.
+//!
+//! Region layout (little-endian, [`SHM_HEADER_SIZE`] byte header followed by pixel data):
+//!
+//! | offset | type     | field                                                        |
+//! |--------|----------|--------------------------------------------------------------|
+//! | 0      | [u8; 8]  | magic `b"SYPHSHM1"`                                          |
+//! | 8      | u32      | header size in bytes (64)                                    |
+//! | 12     | u32      | pixel format fourcc, `b"RGBA"` (8 bits per channel)          |
+//! | 16     | u32      | width                                                        |
+//! | 20     | u32      | height                                                       |
+//! | 24     | u32      | bytes per row                                                |
+//! | 28     | u32      | flags: bit 0 set when rows are stored bottom-up              |
+//! | 32     | u64      | sequence: odd while a frame is being written, even when done |
+//! | 40     | u64      | timestamp of the frame, nanoseconds since the Unix epoch     |
+//! | 48     | u64      | capacity of the pixel area in bytes                          |
+//!
+//! Readers copy the header and pixels, then re-read the sequence and retry if it changed or was odd.
+//! From Python: `multiprocessing.shared_memory.SharedMemory(name)` (without the leading `/`).
+
+#[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(target_os = "macos")]
+use crate::gl_read_texture_rectangle_rgba8;
+use crate::OpenGLImage;
+
+/// Size of the header at the start of an exported region.
+pub const SHM_HEADER_SIZE: usize = 64;
+/// Magic bytes at offset 0 of an exported region.
+pub const SHM_MAGIC: [u8; 8] = *b"SYPHSHM1";
+/// Header flag: pixel rows are stored bottom-up (OpenGL order).
+pub const SHM_FLAG_BOTTOM_UP: u32 = 1;
+
+/// Longest region name, including the leading `/` (macOS's `PSHMNAMLEN`, Linux's `NAME_MAX`).
+#[cfg(not(target_os = "linux"))]
+const MAX_NAME_LEN: usize = 31;
+#[cfg(target_os = "linux")]
+const MAX_NAME_LEN: usize = 255;
+
+#[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+const OFF_FORMAT: usize = 12;
+#[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+const OFF_WIDTH: usize = 16;
+#[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+const OFF_HEIGHT: usize = 20;
+#[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+const OFF_STRIDE: usize = 24;
+#[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+const OFF_FLAGS: usize = 28;
+#[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+const OFF_SEQUENCE: usize = 32;
+#[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+const OFF_TIMESTAMP: usize = 40;
+#[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+const OFF_CAPACITY: usize = 48;
+
+/// `name` with a leading `/`, or None if it is empty, too long, or contains another `/`.
+fn region_name(name: &str) -> Option<String> {
+    let name = if name.starts_with('/') { name.to_owned() } else { format!("/{name}") };
+    (name.len() >= 2 && name.len() <= MAX_NAME_LEN && !name[1..].contains('/') && !name.contains('\0')).then_some(name)
+}
+
+/// Writes frames into a named shared-memory region. The region is unlinked when the exporter is dropped.
+pub struct SharedFrameExporter {
+    name: String,
+    #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+    base: std::ptr::NonNull<u8>,
+    #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+    len: usize,
+    capacity: usize,
+    frames: u64,
+}
+
+#[cfg_attr(not(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm"))), allow(unused_variables))]
+impl SharedFrameExporter {
+    /// Create (or replace) the region `name` (e.g. `"/syphon-frames"`; at most 30 characters after the
+    /// `/` on macOS, 254 on Linux) sized for RGBA8 frames up to `max_width` x `max_height`. Returns None on
+    /// failure.
+    pub fn new(name: &str, max_width: usize, max_height: usize) -> Option<Self> {
+        let name = region_name(name)?;
+        let capacity = max_width.checked_mul(max_height)?.checked_mul(4)?;
+        #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+        {
+            let len = SHM_HEADER_SIZE.checked_add(capacity)?;
+            let cname = std::ffi::CString::new(name.as_str()).ok()?;
+            unsafe {
+                // A region that already exists cannot be resized on macOS; start from a fresh one.
+                libc::shm_unlink(cname.as_ptr());
+                let fd = libc::shm_open(cname.as_ptr(), libc::O_CREAT | libc::O_RDWR, 0o644 as libc::c_uint);
+                if fd < 0 {
+                    return None;
+                }
+                let base = if libc::ftruncate(fd, len as libc::off_t) == 0 {
+                    libc::mmap(
+                        std::ptr::null_mut(),
+                        len,
+                        libc::PROT_READ | libc::PROT_WRITE,
+                        libc::MAP_SHARED,
+                        fd,
+                        0,
+                    )
+                } else {
+                    libc::MAP_FAILED
+                };
+                libc::close(fd);
+                if base == libc::MAP_FAILED {
+                    libc::shm_unlink(cname.as_ptr());
+                    return None;
+                }
+                let exporter = Self {
+                    name,
+                    base: std::ptr::NonNull::new(base as *mut u8)?,
+                    len,
+                    capacity,
+                    frames: 0,
+                };
+                let header = std::slice::from_raw_parts_mut(exporter.base.as_ptr(), SHM_HEADER_SIZE);
+                header[..8].copy_from_slice(&SHM_MAGIC);
+                header[8..12].copy_from_slice(&(SHM_HEADER_SIZE as u32).to_le_bytes());
+                header[OFF_FORMAT..OFF_FORMAT + 4].copy_from_slice(b"RGBA");
+                header[OFF_CAPACITY..OFF_CAPACITY + 8].copy_from_slice(&(capacity as u64).to_le_bytes());
+                Some(exporter)
+            }
+        }
+        #[cfg(not(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm"))))]
+        None
+    }
+
+    /// Region name, including the leading `/`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Largest frame (in bytes) the region can hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of frames written so far.
+    pub fn frames_written(&self) -> u64 {
+        self.frames
+    }
+
+    /// Write a tightly packed RGBA8 frame (top row first). False if it is empty, does not fit or `rgba`
+    /// is short.
+    pub fn publish_rgba(&mut self, width: usize, height: usize, rgba: &[u8]) -> bool {
+        let Some(bytes) = width.checked_mul(height).and_then(|n| n.checked_mul(4)) else {
+            return false;
+        };
+        if bytes == 0 || bytes > self.capacity || rgba.len() < bytes {
+            return false;
+        }
+        #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+        {
+            self.write_frame(width, height, 0, |pixels| pixels.copy_from_slice(&rgba[..bytes]))
+        }
+        #[cfg(not(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm"))))]
+        false
+    }
+
+    /// Read back a received OpenGL frame straight into the region (rows bottom-up, flagged in the
+    /// header). The image's context must be current.
+    #[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+    pub fn publish_gl_image(&mut self, image: &OpenGLImage) -> bool {
+        #[cfg(target_os = "macos")]
+        {
+            let (w, h) = image.texture_size();
+            let (width, height) = (w as usize, h as usize);
+            let tex = image.texture_name();
+            self.write_frame(width, height, SHM_FLAG_BOTTOM_UP, |pixels| {
+                gl_read_texture_rectangle_rgba8(tex, width, height, pixels)
+            })
+        }
+        #[cfg(not(target_os = "macos"))]
+        false
+    }
+
+    /// Write a `width` x `height` RGBA8 frame with header `flags`, letting `fill` write the pixels in place.
+    /// False, writing nothing, if the frame is empty, larger than the region, or its size or row stride
+    /// doesn't fit the header's u32 fields.
+    #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+    pub(crate) fn write_frame(
+        &mut self,
+        width: usize,
+        height: usize,
+        flags: u32,
+        fill: impl FnOnce(&mut [u8]),
+    ) -> bool {
+        let header_fields = (u32::try_from(width), u32::try_from(height), width.checked_mul(4).map(u32::try_from));
+        let (Ok(width32), Ok(height32), Some(Ok(stride))) = header_fields else {
+            return false;
+        };
+        let bytes = match height.checked_mul(stride as usize) {
+            Some(bytes) if bytes > 0 && bytes <= self.capacity => bytes,
+            _ => return false,
+        };
+        // SAFETY: the mapping is `len` bytes, 8-byte aligned (page aligned), and owned by `self`.
+        let sequence = unsafe { &*(self.base.as_ptr().add(OFF_SEQUENCE) as *const AtomicU64) };
+        let seq = sequence.load(Ordering::Relaxed);
+        sequence.store(seq | 1, Ordering::Release);
+        std::sync::atomic::fence(Ordering::Release);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        unsafe {
+            let header = std::slice::from_raw_parts_mut(self.base.as_ptr(), OFF_SEQUENCE);
+            header[OFF_WIDTH..OFF_WIDTH + 4].copy_from_slice(&width32.to_le_bytes());
+            header[OFF_HEIGHT..OFF_HEIGHT + 4].copy_from_slice(&height32.to_le_bytes());
+            header[OFF_STRIDE..OFF_STRIDE + 4].copy_from_slice(&stride.to_le_bytes());
+            header[OFF_FLAGS..OFF_FLAGS + 4].copy_from_slice(&flags.to_le_bytes());
+            std::ptr::copy_nonoverlapping(
+                timestamp.to_le_bytes().as_ptr(),
+                self.base.as_ptr().add(OFF_TIMESTAMP),
+                8,
+            );
+            fill(std::slice::from_raw_parts_mut(self.base.as_ptr().add(SHM_HEADER_SIZE), bytes));
+        }
+        sequence.store((seq | 1) + 1, Ordering::Release);
+        self.frames += 1;
+        true
+    }
+}
+
+impl Drop for SharedFrameExporter {
+    fn drop(&mut self) {
+        #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+        unsafe {
+            libc::munmap(self.base.as_ptr() as *mut _, self.len);
+            if let Ok(cname) = std::ffi::CString::new(self.name.as_str()) {
+                libc::shm_unlink(cname.as_ptr());
+            }
+        }
+    }
+}
+
+/// Reads frames from a region written by a `SharedFrameExporter` in this or another process.
+pub struct SharedFrameReader {
+    name: String,
+    #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+    base: std::ptr::NonNull<u8>,
+    #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+    len: usize,
+    #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+    inode: u64,
+}
+
+// The mapping is read-only and reads go through the header's sequence.
+#[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+unsafe impl Send for SharedFrameReader {}
+
+#[cfg_attr(not(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm"))), allow(unused_variables))]
+impl SharedFrameReader {
+    /// Map the region `name` (with or without the leading `/`). None if it doesn't exist or isn't an
+    /// exported frame region.
+    pub fn open(name: &str) -> Option<Self> {
+        let name = region_name(name)?;
+        #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+        {
+            let cname = std::ffi::CString::new(name.as_str()).ok()?;
+            unsafe {
+                let fd = libc::shm_open(cname.as_ptr(), libc::O_RDONLY, 0);
+                if fd < 0 {
+                    return None;
+                }
+                let mut stat: libc::stat = std::mem::zeroed();
+                let len = if libc::fstat(fd, &mut stat) == 0 { stat.st_size as usize } else { 0 };
+                let base = if len >= SHM_HEADER_SIZE {
+                    libc::mmap(std::ptr::null_mut(), len, libc::PROT_READ, libc::MAP_SHARED, fd, 0)
+                } else {
+                    libc::MAP_FAILED
+                };
+                libc::close(fd);
+                if base == libc::MAP_FAILED {
+                    return None;
+                }
+                let reader = Self {
+                    name,
+                    base: std::ptr::NonNull::new(base as *mut u8)?,
+                    len,
+                    inode: stat.st_ino as u64,
+                };
+                let header = std::slice::from_raw_parts(reader.base.as_ptr(), SHM_HEADER_SIZE);
+                let header_size = u32::from_le_bytes(header[8..12].try_into().ok()?) as usize;
+                let capacity = u64::from_le_bytes(header[OFF_CAPACITY..OFF_CAPACITY + 8].try_into().ok()?);
+                let valid = header[..8] == SHM_MAGIC
+                    && header_size == SHM_HEADER_SIZE
+                    && header[OFF_FORMAT..OFF_FORMAT + 4] == *b"RGBA"
+                    && SHM_HEADER_SIZE as u64 + capacity <= len as u64;
+                valid.then_some(reader)
+            }
+        }
+        #[cfg(not(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm"))))]
+        None
+    }
+
+    /// Region name, including the leading `/`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the exporter still owns the region: false once it was dropped or replaced (e.g. by a new
+    /// exporter of the same name), after which `open` the name again to follow it.
+    pub fn is_live(&self) -> bool {
+        #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+        {
+            let Ok(cname) = std::ffi::CString::new(self.name.as_str()) else {
+                return false;
+            };
+            unsafe {
+                let fd = libc::shm_open(cname.as_ptr(), libc::O_RDONLY, 0);
+                if fd < 0 {
+                    return false;
+                }
+                let mut stat: libc::stat = std::mem::zeroed();
+                let same = libc::fstat(fd, &mut stat) == 0 && stat.st_ino as u64 == self.inode;
+                libc::close(fd);
+                same
+            }
+        }
+        #[cfg(not(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm"))))]
+        false
+    }
+
+    /// Number of frames written to the region so far.
+    pub fn frames_written(&self) -> u64 {
+        #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+        {
+            self.sequence().load(Ordering::Acquire) / 2
+        }
+        #[cfg(not(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm"))))]
+        0
+    }
+
+    /// Size of the latest frame; None before the first one.
+    pub fn frame_size(&self) -> Option<(usize, usize)> {
+        self.read(|_, _, _| ()).map(|(width, height, _)| (width, height))
+    }
+
+    /// Copy the latest frame out as RGBA8, top row first. None before the first frame.
+    pub fn read_frame(&self) -> Option<crate::CpuFrame> {
+        self.read_rows(false).map(|(width, height, rgba)| crate::CpuFrame {
+            width,
+            height,
+            rgba,
+            timestamp: std::time::Instant::now(),
+        })
+    }
+
+    /// The latest frame's size and tightly packed RGBA8 pixels, bottom row first if `bottom_up`.
+    pub(crate) fn read_rows(&self, bottom_up: bool) -> Option<(usize, usize, Vec<u8>)> {
+        let (width, height, (flags, mut rgba)) = self.read(|_, _, pixels| pixels.to_vec())?;
+        if (flags & SHM_FLAG_BOTTOM_UP != 0) != bottom_up {
+            let stride = width * 4;
+            let mut flipped = Vec::with_capacity(rgba.len());
+            for row in rgba.chunks_exact(stride).rev() {
+                flipped.extend_from_slice(row);
+            }
+            rgba = flipped;
+        }
+        Some((width, height, rgba))
+    }
+
+    /// Run `copy` on a consistent snapshot of the latest frame (retrying while the exporter writes),
+    /// returning the frame size, its header flags and what `copy` returned.
+    fn read<T>(&self, copy: impl Fn(usize, usize, &[u8]) -> T) -> Option<(usize, usize, (u32, T))> {
+        #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+        for _ in 0..100 {
+            let sequence = self.sequence();
+            let before = sequence.load(Ordering::Acquire);
+            if before == 0 {
+                return None;
+            }
+            if before & 1 == 1 {
+                std::thread::yield_now();
+                continue;
+            }
+            // SAFETY: the mapping is `len` bytes and outlives `self`; torn reads are discarded below.
+            let header = unsafe { std::slice::from_raw_parts(self.base.as_ptr(), SHM_HEADER_SIZE) };
+            let field = |offset: usize| u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap_or_default());
+            let (width, height, flags) = (field(OFF_WIDTH) as usize, field(OFF_HEIGHT) as usize, field(OFF_FLAGS));
+            // The header is written by another process: don't trust it to fit, or to be non-empty.
+            if width == 0 || height == 0 {
+                return None;
+            }
+            let bytes = width.checked_mul(height)?.checked_mul(4)?;
+            if SHM_HEADER_SIZE.checked_add(bytes)? > self.len {
+                return None;
+            }
+            let pixels = unsafe { std::slice::from_raw_parts(self.base.as_ptr().add(SHM_HEADER_SIZE), bytes) };
+            let value = copy(width, height, pixels);
+            std::sync::atomic::fence(Ordering::Acquire);
+            if sequence.load(Ordering::Relaxed) == before {
+                return Some((width, height, (flags, value)));
+            }
+        }
+        None
+    }
+
+    #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+    fn sequence(&self) -> &AtomicU64 {
+        // SAFETY: the header is page aligned, so the sequence field is 8-byte aligned.
+        unsafe { &*(self.base.as_ptr().add(OFF_SEQUENCE) as *const AtomicU64) }
+    }
+}
+
+impl Drop for SharedFrameReader {
+    fn drop(&mut self) {
+        #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+        unsafe {
+            libc::munmap(self.base.as_ptr() as *mut _, self.len);
+        }
+    }
+}
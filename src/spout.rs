@@ -0,0 +1,1246 @@
+//! Spout senders and receivers shaped like the Syphon types: `SpoutServer` / `SpoutClient` share OpenGL
+//! textures, `SpoutDxServer` / `SpoutDxClient` DirectX 11 textures and `SpoutDx12Server` / `SpoutDx12Client`
+//! DirectX 12 resources, with the constructors, naming,
+//! publishing and frame methods of `OpenGLServer` / `OpenGLClient` and `MetalServer` / `MetalClient`, so
+//! cross-platform apps can pick the type per platform and keep one code path. Off Windows the constructors
+//! report `Unsupported`.
+//!
+//! `SpoutGlDxServer` / `SpoutGlDxClient` are OpenGL senders and receivers that link a GL texture to a
+//! D3D11 texture themselves through `WGL_NV_DX_interop` (as SpoutGL does internally), so frames stay on the
+//! GPU; `SpoutServer` / `SpoutClient` leave that to SpoutLibrary, which may fall back to CPU copies.
+//!
+//! Spout has no client list or new-frame notification: servers can't tell whether anyone is receiving, and
+//! clients poll (`new_frame_image` / `new_frame_texture` each frame). A client follows its sender through
+//! restarts and size changes by name.
+
+#[cfg(target_os = "windows")]
+use std::cell::RefCell;
+#[cfg(target_os = "windows")]
+use std::ptr::NonNull;
+#[cfg(target_os = "windows")]
+use std::time::Instant;
+
+#[cfg(target_os = "windows")]
+use crate::ffi as spout_ffi;
+#[cfg(target_os = "windows")]
+use crate::safe::optional_cstring;
+#[cfg(target_os = "windows")]
+use crate::Spout;
+use crate::{CpuFrame, SyphonError};
+
+/// Opaque pointer to an `ID3D11Device` (e.g. from the `windows` crate's `ID3D11Device::as_raw`).
+pub type ID3D11DevicePtr = *mut std::ffi::c_void;
+
+/// Opaque pointer to an `ID3D11Texture2D`.
+pub type ID3D11Texture2DPtr = *mut std::ffi::c_void;
+
+/// Opaque pointer to an `ID3D12Device`.
+pub type ID3D12DevicePtr = *mut std::ffi::c_void;
+
+/// Opaque pointer to an `ID3D12CommandQueue`.
+pub type ID3D12CommandQueuePtr = *mut std::ffi::c_void;
+
+/// Opaque pointer to an `ID3D12Resource` (a 2D texture).
+pub type ID3D12ResourcePtr = *mut std::ffi::c_void;
+
+#[cfg(target_os = "windows")]
+const GL_RGBA: u32 = 0x1908;
+
+#[cfg(target_os = "windows")]
+fn send_failed() -> SyphonError {
+    SyphonError::CreationFailed {
+        object: "Spout frame",
+        reason: "Spout could not share the texture (no OpenGL context current, or no DirectX interop)".into(),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn buffer_to_string(buf: &[u8]) -> Option<String> {
+    let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    (end > 0).then(|| String::from_utf8_lossy(&buf[..end]).into_owned())
+}
+
+/// A Spout sender publishing OpenGL textures, the counterpart of `OpenGLServer`. Sharing starts with the
+/// first published frame; publish with the app's GL context current.
+pub struct SpoutServer {
+    #[cfg(target_os = "windows")]
+    spout: Spout,
+    #[cfg(target_os = "windows")]
+    name: RefCell<Option<String>>,
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
+impl SpoutServer {
+    /// Create a sender named `name` (None: the executable's name). None off Windows or if SpoutLibrary
+    /// can't be loaded.
+    pub fn new(name: Option<&str>) -> Option<Self> {
+        Self::try_new(name).ok()
+    }
+
+    /// Like `new`, reporting why the sender couldn't be created.
+    pub fn try_new(name: Option<&str>) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "windows")]
+        {
+            optional_cstring(name)?;
+            let spout = Spout::new().ok_or_else(|| SyphonError::CreationFailed {
+                object: "Spout sender",
+                reason: "SpoutLibrary is unavailable".into(),
+            })?;
+            spout.sender_set_name(name);
+            Ok(Self {
+                spout,
+                name: RefCell::new(name.map(str::to_owned)),
+            })
+        }
+        #[cfg(not(target_os = "windows"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// The sender name: as registered once sharing has started, else as requested.
+    pub fn name(&self) -> Option<String> {
+        #[cfg(target_os = "windows")]
+        {
+            if self.spout.sender_is_initialized() {
+                if let Some(name) = self.spout.sender_name().filter(|n| !n.is_empty()) {
+                    return Some(name);
+                }
+            }
+            self.name.borrow().clone()
+        }
+        #[cfg(not(target_os = "windows"))]
+        None
+    }
+
+    /// Rename the sender. Spout can't rename a live sender, so this releases it and the next frame
+    /// registers the new name (receivers following the old name lose it). Names with a NUL byte are ignored.
+    pub fn set_name(&self, name: Option<&str>) {
+        let _ = self.try_set_name(name);
+    }
+
+    /// Like `set_name`, reporting a NUL byte in `name` as `InvalidArgument`.
+    pub fn try_set_name(&self, name: Option<&str>) -> Result<(), SyphonError> {
+        #[cfg(target_os = "windows")]
+        {
+            optional_cstring(name)?;
+            self.spout.sender_release();
+            self.spout.sender_set_name(name);
+            *self.name.borrow_mut() = name.map(str::to_owned);
+            Ok(())
+        }
+        #[cfg(not(target_os = "windows"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Publish all of a `width` x `height` texture (bound to `target`, e.g. `GL_TEXTURE_2D`); `flipped` if
+    /// its rows are top-down, as for `OpenGLServer::publish_whole_texture`.
+    pub fn publish_whole_texture(&self, tex_id: u32, target: u32, width: f64, height: f64, flipped: bool) {
+        let _ = self.try_publish_whole_texture(tex_id, target, width, height, flipped);
+    }
+
+    /// Like `publish_whole_texture`, reporting a frame Spout couldn't share as `CreationFailed`.
+    pub fn try_publish_whole_texture(
+        &self,
+        tex_id: u32,
+        target: u32,
+        width: f64,
+        height: f64,
+        flipped: bool,
+    ) -> Result<(), SyphonError> {
+        #[cfg(target_os = "windows")]
+        {
+            if width < 1.0 || height < 1.0 {
+                return Err(SyphonError::InvalidArgument("texture size must not be zero"));
+            }
+            // Spout shares top-down (DirectX) textures; GL textures are bottom-up unless `flipped`.
+            match self.spout.sender_send_texture(tex_id, target, width as u32, height as u32, !flipped) {
+                true => Ok(()),
+                false => Err(send_failed()),
+            }
+        }
+        #[cfg(not(target_os = "windows"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Publish the `width` x `height` contents of framebuffer `fbo` (0 for the default framebuffer), which
+    /// must be bound for reading.
+    pub fn publish_fbo(&self, fbo: u32, width: u32, height: u32, flipped: bool) -> bool {
+        #[cfg(target_os = "windows")]
+        {
+            self.spout.sender_send_fbo(fbo, width, height, !flipped)
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// Publish a `width` x `height` frame of RGBA8 pixels from CPU memory (`flipped` if the rows are
+    /// bottom-up), as `MetalServer::publish_pixels`. False if `pixels` is too short.
+    pub fn publish_pixels(&self, pixels: &[u8], width: usize, height: usize, flipped: bool) -> bool {
+        if width.checked_mul(height).and_then(|n| n.checked_mul(4)).is_none_or(|n| pixels.len() < n) {
+            return false;
+        }
+        #[cfg(target_os = "windows")]
+        {
+            let (Ok(w), Ok(h)) = (u32::try_from(width), u32::try_from(height)) else {
+                return false;
+            };
+            self.spout.sender_send_image(pixels, w, h, GL_RGBA, flipped)
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// The underlying `Spout` instance, for settings without a counterpart here (frame sync, CPU mode,
+    /// buffering, sender format).
+    #[cfg(target_os = "windows")]
+    pub fn spout(&self) -> &Spout {
+        &self.spout
+    }
+
+    /// Stop sharing (idempotent; publishing again restarts it).
+    pub fn stop(&self) {
+        #[cfg(target_os = "windows")]
+        self.spout.sender_release();
+    }
+}
+
+impl Drop for SpoutServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// A Spout receiver of OpenGL textures, the counterpart of `OpenGLClient`. Receive with the app's GL
+/// context current.
+pub struct SpoutClient {
+    #[cfg(target_os = "windows")]
+    spout: Spout,
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
+impl SpoutClient {
+    /// Receive from the sender named `sender` (None: the active sender, as chosen in SpoutPanel). None off
+    /// Windows or if SpoutLibrary can't be loaded.
+    pub fn new(sender: Option<&str>) -> Option<Self> {
+        Self::try_new(sender).ok()
+    }
+
+    /// Like `new`, reporting why the receiver couldn't be created.
+    pub fn try_new(sender: Option<&str>) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "windows")]
+        {
+            optional_cstring(sender)?;
+            let spout = Spout::new().ok_or_else(|| SyphonError::CreationFailed {
+                object: "Spout receiver",
+                reason: "SpoutLibrary is unavailable".into(),
+            })?;
+            spout.receiver_set_name(sender);
+            Ok(Self { spout })
+        }
+        #[cfg(not(target_os = "windows"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Name of the sender being received, once connected.
+    pub fn server_name(&self) -> Option<String> {
+        #[cfg(target_os = "windows")]
+        {
+            self.spout.receiver_sender_name().filter(|n| !n.is_empty())
+        }
+        #[cfg(not(target_os = "windows"))]
+        None
+    }
+
+    /// Whether the last receive found the sender.
+    pub fn is_valid(&self) -> bool {
+        #[cfg(target_os = "windows")]
+        {
+            self.spout.receiver_is_connected()
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// Whether the last receive got a frame the sender hadn't delivered before.
+    pub fn has_new_frame(&self) -> bool {
+        #[cfg(target_os = "windows")]
+        {
+            self.spout.receiver_is_frame_new()
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// The sender's frame size in pixels, as of the last receive.
+    pub fn frame_size(&self) -> (usize, usize) {
+        #[cfg(target_os = "windows")]
+        {
+            (self.spout.receiver_sender_width() as usize, self.spout.receiver_sender_height() as usize)
+        }
+        #[cfg(not(target_os = "windows"))]
+        (0, 0)
+    }
+
+    /// Whether the last receive connected to a sender or saw its size change. No pixels were copied then:
+    /// reallocate textures at `frame_size` and receive again.
+    pub fn is_updated(&self) -> bool {
+        #[cfg(target_os = "windows")]
+        {
+            self.spout.receiver_is_updated()
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// Copy the current frame into your own texture `tex_id` (bound to `target`), bottom-up like
+    /// `OpenGLClient::copy_frame_to`. The texture must be `frame_size`; false if there is no sender, and
+    /// nothing is copied when `is_updated` reports a new size.
+    pub fn copy_frame_to(&self, tex_id: u32, target: u32) -> bool {
+        #[cfg(target_os = "windows")]
+        {
+            self.spout.receiver_receive_texture(tex_id, target, true) && !self.spout.receiver_is_updated()
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// Receive and bind the sender's shared texture. The image borrows the client; drop it when done
+    /// drawing to unbind the texture.
+    pub fn new_frame_image(&self) -> Option<SpoutImage<'_>> {
+        #[cfg(target_os = "windows")]
+        {
+            if !self.spout.receiver_receive_texture(0, 0, false) || !self.spout.bind_shared_texture() {
+                return None;
+            }
+            let texture_name = self.spout.shared_texture_id();
+            let (width, height) = self.frame_size();
+            Some(SpoutImage {
+                client: self,
+                texture_name,
+                width,
+                height,
+            })
+        }
+        #[cfg(not(target_os = "windows"))]
+        None
+    }
+
+    /// Read the current frame back as RGBA8, top row first, as `CpuClient::latest_frame`. None if there is
+    /// no sender.
+    pub fn latest_frame(&self) -> Option<CpuFrame> {
+        #[cfg(target_os = "windows")]
+        {
+            // Connecting, or a size change, is reported without copying: read again at the new size.
+            for _ in 0..2 {
+                let (width, height) = self.frame_size();
+                if width == 0 || height == 0 {
+                    if !self.spout.receiver_receive_texture(0, 0, false) {
+                        return None;
+                    }
+                    continue;
+                }
+                let mut rgba = vec![0; width * height * 4];
+                if !self.spout.receiver_receive_image(&mut rgba, GL_RGBA, false) {
+                    return None;
+                }
+                if !self.spout.receiver_is_updated() {
+                    return Some(CpuFrame {
+                        width,
+                        height,
+                        rgba,
+                        timestamp: Instant::now(),
+                    });
+                }
+            }
+            None
+        }
+        #[cfg(not(target_os = "windows"))]
+        None
+    }
+
+    /// The underlying `Spout` instance, for settings without a counterpart here.
+    #[cfg(target_os = "windows")]
+    pub fn spout(&self) -> &Spout {
+        &self.spout
+    }
+
+    /// Stop receiving (idempotent; receiving again reconnects).
+    pub fn stop(&self) {
+        #[cfg(target_os = "windows")]
+        self.spout.receiver_release();
+    }
+}
+
+impl Drop for SpoutClient {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// A received frame: the sender's shared texture, bound while the image lives. Like `OpenGLImage`, but
+/// the texture is a `GL_TEXTURE_2D` with top-down rows.
+pub struct SpoutImage<'a> {
+    #[cfg(target_os = "windows")]
+    client: &'a SpoutClient,
+    #[cfg(not(target_os = "windows"))]
+    client: std::marker::PhantomData<&'a SpoutClient>,
+    texture_name: u32,
+    width: usize,
+    height: usize,
+}
+
+impl SpoutImage<'_> {
+    /// GL texture name of the shared texture.
+    pub fn texture_name(&self) -> u32 {
+        self.texture_name
+    }
+
+    /// Texture target (`GL_TEXTURE_2D`).
+    pub fn target(&self) -> u32 {
+        crate::GL_TEXTURE_2D
+    }
+
+    /// Texture size in pixels, as `OpenGLImage::texture_size`.
+    pub fn texture_size(&self) -> (f64, f64) {
+        (self.width as f64, self.height as f64)
+    }
+
+    /// Always true: Spout textures are stored top-down.
+    pub fn is_flipped(&self) -> bool {
+        true
+    }
+}
+
+impl Drop for SpoutImage<'_> {
+    fn drop(&mut self) {
+        #[cfg(target_os = "windows")]
+        self.client.spout.unbind_shared_texture();
+    }
+}
+
+/// A SpoutDX instance on an app's D3D11 device, released on drop.
+#[cfg(target_os = "windows")]
+struct DxHandle(NonNull<std::ffi::c_void>);
+
+#[cfg(target_os = "windows")]
+impl DxHandle {
+    fn open(device: ID3D11DevicePtr, object: &'static str) -> Result<Self, SyphonError> {
+        if device.is_null() {
+            return Err(SyphonError::InvalidArgument("null D3D11 device"));
+        }
+        let ptr = unsafe { spout_ffi::spout_dx_create(device) };
+        NonNull::new(ptr).map(Self).ok_or_else(|| SyphonError::CreationFailed {
+            object,
+            reason: "SpoutDX could not open the D3D11 device".into(),
+        })
+    }
+
+    fn as_ptr(&self) -> *mut std::ffi::c_void {
+        self.0.as_ptr()
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for DxHandle {
+    fn drop(&mut self) {
+        unsafe { spout_ffi::spout_dx_destroy(self.0.as_ptr()) };
+    }
+}
+
+/// A Spout sender publishing DirectX 11 textures, the counterpart of `MetalServer`.
+pub struct SpoutDxServer {
+    #[cfg(target_os = "windows")]
+    handle: DxHandle,
+    #[cfg(target_os = "windows")]
+    name: RefCell<Option<String>>,
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
+impl SpoutDxServer {
+    /// Create a sender named `name` (None: the executable's name) on `device`. None off Windows, for a
+    /// null device, or if SpoutDX can't use it.
+    pub fn new(name: Option<&str>, device: ID3D11DevicePtr) -> Option<Self> {
+        Self::try_new(name, device).ok()
+    }
+
+    /// Like `new`, reporting why the sender couldn't be created.
+    pub fn try_new(name: Option<&str>, device: ID3D11DevicePtr) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "windows")]
+        {
+            let c_name = optional_cstring(name)?;
+            let handle = DxHandle::open(device, "SpoutDX sender")?;
+            if let Some(c_name) = &c_name {
+                unsafe { spout_ffi::spout_dx_sender_set_name(handle.as_ptr(), c_name.as_ptr()) };
+            }
+            Ok(Self {
+                handle,
+                name: RefCell::new(name.map(str::to_owned)),
+            })
+        }
+        #[cfg(not(target_os = "windows"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// The D3D11 device the sender shares through.
+    pub fn device(&self) -> ID3D11DevicePtr {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_dx_get_device(self.handle.as_ptr())
+        }
+        #[cfg(not(target_os = "windows"))]
+        std::ptr::null_mut()
+    }
+
+    /// The sender name: as registered once sharing has started, else as requested.
+    pub fn name(&self) -> Option<String> {
+        #[cfg(target_os = "windows")]
+        {
+            let s = unsafe { spout_ffi::spout_dx_sender_get_name(self.handle.as_ptr()) };
+            if !s.is_null() {
+                let name = unsafe { std::ffi::CStr::from_ptr(s) }.to_string_lossy().into_owned();
+                unsafe { spout_ffi::spout_string_free(s) };
+                return Some(name);
+            }
+            self.name.borrow().clone()
+        }
+        #[cfg(not(target_os = "windows"))]
+        None
+    }
+
+    /// Rename the sender; as with `SpoutServer::set_name`, the next frame registers the new name.
+    pub fn set_name(&self, name: Option<&str>) {
+        let _ = self.try_set_name(name);
+    }
+
+    /// Like `set_name`, reporting a NUL byte in `name` as `InvalidArgument`.
+    pub fn try_set_name(&self, name: Option<&str>) -> Result<(), SyphonError> {
+        #[cfg(target_os = "windows")]
+        {
+            let c_name = optional_cstring(name)?;
+            let ptr = c_name.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
+            unsafe {
+                spout_ffi::spout_dx_sender_release(self.handle.as_ptr());
+                spout_ffi::spout_dx_sender_set_name(self.handle.as_ptr(), ptr);
+            }
+            *self.name.borrow_mut() = name.map(str::to_owned);
+            Ok(())
+        }
+        #[cfg(not(target_os = "windows"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Publish `texture` (an `ID3D11Texture2D` on the sender's device), copied into the shared texture,
+    /// which follows its size and format. False if `texture` is null or couldn't be shared.
+    pub fn publish_texture(&self, texture: ID3D11Texture2DPtr) -> bool {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            !texture.is_null() && spout_ffi::spout_dx_sender_send_texture(self.handle.as_ptr(), texture)
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// Stop sharing (idempotent; publishing again restarts it).
+    pub fn stop(&self) {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_dx_sender_release(self.handle.as_ptr())
+        };
+    }
+}
+
+/// A Spout receiver of DirectX 11 textures, the counterpart of `MetalClient`.
+pub struct SpoutDxClient {
+    #[cfg(target_os = "windows")]
+    handle: DxHandle,
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
+impl SpoutDxClient {
+    /// Receive from the sender named `sender` (None: the active sender) on `device`. None off Windows, for
+    /// a null device, or if SpoutDX can't use it.
+    pub fn new(sender: Option<&str>, device: ID3D11DevicePtr) -> Option<Self> {
+        Self::try_new(sender, device).ok()
+    }
+
+    /// Like `new`, reporting why the receiver couldn't be created.
+    pub fn try_new(sender: Option<&str>, device: ID3D11DevicePtr) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "windows")]
+        {
+            let sender = optional_cstring(sender)?;
+            let handle = DxHandle::open(device, "SpoutDX receiver")?;
+            let ptr = sender.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
+            unsafe { spout_ffi::spout_dx_receiver_set_name(handle.as_ptr(), ptr) };
+            Ok(Self { handle })
+        }
+        #[cfg(not(target_os = "windows"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Name of the sender being received, once connected.
+    pub fn server_name(&self) -> Option<String> {
+        #[cfg(target_os = "windows")]
+        {
+            let mut buf = [0u8; 256];
+            let ok = unsafe {
+                spout_ffi::spout_dx_receiver_get_sender_name(
+                    self.handle.as_ptr(),
+                    buf.as_mut_ptr() as *mut std::os::raw::c_char,
+                    buf.len() as i32,
+                )
+            };
+            ok.then(|| buffer_to_string(&buf)).flatten()
+        }
+        #[cfg(not(target_os = "windows"))]
+        None
+    }
+
+    /// Whether the last receive found the sender.
+    pub fn is_valid(&self) -> bool {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_dx_receiver_is_connected(self.handle.as_ptr())
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// Whether the last receive got a frame the sender hadn't delivered before.
+    pub fn has_new_frame(&self) -> bool {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_dx_receiver_is_frame_new(self.handle.as_ptr())
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// Whether the last receive connected to a sender or saw its size or format change.
+    pub fn is_updated(&self) -> bool {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_dx_receiver_is_updated(self.handle.as_ptr())
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// The sender's frame size in pixels, as of the last receive.
+    pub fn frame_size(&self) -> (usize, usize) {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            (
+                spout_ffi::spout_dx_receiver_get_sender_width(self.handle.as_ptr()) as usize,
+                spout_ffi::spout_dx_receiver_get_sender_height(self.handle.as_ptr()) as usize,
+            )
+        }
+        #[cfg(not(target_os = "windows"))]
+        (0, 0)
+    }
+
+    /// The sender's `DXGI_FORMAT`, as of the last receive.
+    pub fn pixel_format(&self) -> u32 {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_dx_receiver_get_sender_format(self.handle.as_ptr())
+        }
+        #[cfg(not(target_os = "windows"))]
+        0
+    }
+
+    /// Receive the current frame into the client's own `ID3D11Texture2D` (on the client's device) and return
+    /// it. The texture is owned by the client (don't release it) and is replaced when the sender's size or
+    /// format changes (`is_updated`), so use it before the next call. None if there is no sender.
+    pub fn new_frame_texture(&self) -> Option<ID3D11Texture2DPtr> {
+        #[cfg(target_os = "windows")]
+        {
+            if !unsafe { spout_ffi::spout_dx_receiver_receive_texture(self.handle.as_ptr()) } {
+                return None;
+            }
+            let texture = unsafe { spout_ffi::spout_dx_receiver_get_sender_texture(self.handle.as_ptr()) };
+            (!texture.is_null()).then_some(texture)
+        }
+        #[cfg(not(target_os = "windows"))]
+        None
+    }
+
+    /// Receive the current frame into your own `ID3D11Texture2D` on the client's device, which must match
+    /// `frame_size` and `pixel_format` (check `is_updated` to resize it). False if there is no sender or
+    /// `texture` is null.
+    pub fn copy_frame_to(&self, texture: ID3D11Texture2DPtr) -> bool {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            !texture.is_null() && spout_ffi::spout_dx_receiver_copy_to_texture(self.handle.as_ptr(), texture)
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// Stop receiving (idempotent; receiving again reconnects).
+    pub fn stop(&self) {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_dx_receiver_release(self.handle.as_ptr())
+        };
+    }
+}
+
+/// A SpoutDX12 instance on an app's D3D12 device and command queue, released on drop.
+#[cfg(target_os = "windows")]
+struct Dx12Handle(NonNull<std::ffi::c_void>);
+
+#[cfg(target_os = "windows")]
+impl Dx12Handle {
+    fn open(device: ID3D12DevicePtr, queue: ID3D12CommandQueuePtr, object: &'static str) -> Result<Self, SyphonError> {
+        if device.is_null() {
+            return Err(SyphonError::InvalidArgument("null D3D12 device"));
+        }
+        if queue.is_null() {
+            return Err(SyphonError::InvalidArgument("null D3D12 command queue"));
+        }
+        let ptr = unsafe { spout_ffi::spout_dx12_create(device, queue) };
+        NonNull::new(ptr).map(Self).ok_or_else(|| SyphonError::CreationFailed {
+            object,
+            reason: "SpoutDX12 could not open the D3D12 device through D3D11On12".into(),
+        })
+    }
+
+    fn as_ptr(&self) -> *mut std::ffi::c_void {
+        self.0.as_ptr()
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for Dx12Handle {
+    fn drop(&mut self) {
+        unsafe { spout_ffi::spout_dx12_destroy(self.0.as_ptr()) };
+    }
+}
+
+/// A Spout sender publishing DirectX 12 resources. Spout shares D3D11 textures, so each resource is
+/// wrapped through D3D11On12 on `device` and `queue` (once per resource, kept until another is published).
+pub struct SpoutDx12Server {
+    #[cfg(target_os = "windows")]
+    handle: Dx12Handle,
+    #[cfg(target_os = "windows")]
+    name: RefCell<Option<String>>,
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
+impl SpoutDx12Server {
+    /// Create a sender named `name` (None: the executable's name) on `device`, submitting the copies to
+    /// `queue`. None off Windows, for a null device or queue, or if SpoutDX12 can't use them.
+    pub fn new(name: Option<&str>, device: ID3D12DevicePtr, queue: ID3D12CommandQueuePtr) -> Option<Self> {
+        Self::try_new(name, device, queue).ok()
+    }
+
+    /// Like `new`, reporting why the sender couldn't be created.
+    pub fn try_new(
+        name: Option<&str>,
+        device: ID3D12DevicePtr,
+        queue: ID3D12CommandQueuePtr,
+    ) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "windows")]
+        {
+            let c_name = optional_cstring(name)?;
+            let handle = Dx12Handle::open(device, queue, "SpoutDX12 sender")?;
+            if let Some(c_name) = &c_name {
+                unsafe { spout_ffi::spout_dx12_sender_set_name(handle.as_ptr(), c_name.as_ptr()) };
+            }
+            Ok(Self {
+                handle,
+                name: RefCell::new(name.map(str::to_owned)),
+            })
+        }
+        #[cfg(not(target_os = "windows"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// The D3D12 device the sender shares through.
+    pub fn device(&self) -> ID3D12DevicePtr {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_dx12_get_device(self.handle.as_ptr())
+        }
+        #[cfg(not(target_os = "windows"))]
+        std::ptr::null_mut()
+    }
+
+    /// The sender name: as registered once sharing has started, else as requested.
+    pub fn name(&self) -> Option<String> {
+        #[cfg(target_os = "windows")]
+        {
+            let s = unsafe { spout_ffi::spout_dx12_sender_get_name(self.handle.as_ptr()) };
+            if !s.is_null() {
+                let name = unsafe { std::ffi::CStr::from_ptr(s) }.to_string_lossy().into_owned();
+                unsafe { spout_ffi::spout_string_free(s) };
+                return Some(name);
+            }
+            self.name.borrow().clone()
+        }
+        #[cfg(not(target_os = "windows"))]
+        None
+    }
+
+    /// Rename the sender; as with `SpoutServer::set_name`, the next frame registers the new name.
+    pub fn set_name(&self, name: Option<&str>) {
+        let _ = self.try_set_name(name);
+    }
+
+    /// Like `set_name`, reporting a NUL byte in `name` as `InvalidArgument`.
+    pub fn try_set_name(&self, name: Option<&str>) -> Result<(), SyphonError> {
+        #[cfg(target_os = "windows")]
+        {
+            let c_name = optional_cstring(name)?;
+            let ptr = c_name.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
+            unsafe {
+                spout_ffi::spout_dx12_sender_release(self.handle.as_ptr());
+                spout_ffi::spout_dx12_sender_set_name(self.handle.as_ptr(), ptr);
+            }
+            *self.name.borrow_mut() = name.map(str::to_owned);
+            Ok(())
+        }
+        #[cfg(not(target_os = "windows"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Publish `resource` (a 2D texture on the sender's device), copied into the shared texture, which
+    /// follows its size and format. `state` is the `D3D12_RESOURCE_STATES` the resource is in when
+    /// published (e.g. `D3D12_RESOURCE_STATE_RENDER_TARGET` for a back buffer); it is back in that state
+    /// afterwards. False if `resource` is null or couldn't be shared.
+    pub fn publish_resource(&self, resource: ID3D12ResourcePtr, state: u32) -> bool {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            !resource.is_null() && spout_ffi::spout_dx12_sender_send_resource(self.handle.as_ptr(), resource, state)
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// Stop sharing (idempotent; publishing again restarts it).
+    pub fn stop(&self) {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_dx12_sender_release(self.handle.as_ptr())
+        };
+    }
+}
+
+/// A Spout receiver of DirectX 12 resources.
+pub struct SpoutDx12Client {
+    #[cfg(target_os = "windows")]
+    handle: Dx12Handle,
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
+impl SpoutDx12Client {
+    /// Receive from the sender named `sender` (None: the active sender) on `device` and `queue`. None off
+    /// Windows, for a null device or queue, or if SpoutDX12 can't use them.
+    pub fn new(sender: Option<&str>, device: ID3D12DevicePtr, queue: ID3D12CommandQueuePtr) -> Option<Self> {
+        Self::try_new(sender, device, queue).ok()
+    }
+
+    /// Like `new`, reporting why the receiver couldn't be created.
+    pub fn try_new(
+        sender: Option<&str>,
+        device: ID3D12DevicePtr,
+        queue: ID3D12CommandQueuePtr,
+    ) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "windows")]
+        {
+            let sender = optional_cstring(sender)?;
+            let handle = Dx12Handle::open(device, queue, "SpoutDX12 receiver")?;
+            let ptr = sender.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
+            unsafe { spout_ffi::spout_dx12_receiver_set_name(handle.as_ptr(), ptr) };
+            Ok(Self { handle })
+        }
+        #[cfg(not(target_os = "windows"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Name of the sender being received, once connected.
+    pub fn server_name(&self) -> Option<String> {
+        #[cfg(target_os = "windows")]
+        {
+            let mut buf = [0u8; 256];
+            let ok = unsafe {
+                spout_ffi::spout_dx12_receiver_get_sender_name(
+                    self.handle.as_ptr(),
+                    buf.as_mut_ptr() as *mut std::os::raw::c_char,
+                    buf.len() as i32,
+                )
+            };
+            ok.then(|| buffer_to_string(&buf)).flatten()
+        }
+        #[cfg(not(target_os = "windows"))]
+        None
+    }
+
+    /// Whether the last receive found the sender.
+    pub fn is_valid(&self) -> bool {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_dx12_receiver_is_connected(self.handle.as_ptr())
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// Whether the last receive got a frame the sender hadn't delivered before.
+    pub fn has_new_frame(&self) -> bool {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_dx12_receiver_is_frame_new(self.handle.as_ptr())
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// Whether the last receive connected to a sender or saw its size or format change.
+    pub fn is_updated(&self) -> bool {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_dx12_receiver_is_updated(self.handle.as_ptr())
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// The sender's frame size in pixels, as of the last receive.
+    pub fn frame_size(&self) -> (usize, usize) {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            (
+                spout_ffi::spout_dx12_receiver_get_sender_width(self.handle.as_ptr()) as usize,
+                spout_ffi::spout_dx12_receiver_get_sender_height(self.handle.as_ptr()) as usize,
+            )
+        }
+        #[cfg(not(target_os = "windows"))]
+        (0, 0)
+    }
+
+    /// The sender's `DXGI_FORMAT`, as of the last receive.
+    pub fn pixel_format(&self) -> u32 {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_dx12_receiver_get_sender_format(self.handle.as_ptr())
+        }
+        #[cfg(not(target_os = "windows"))]
+        0
+    }
+
+    /// Receive the current frame into the client's own `ID3D12Resource` (on the client's device) and return
+    /// it. As with `SpoutDxClient::new_frame_texture`, the resource is owned by the client and replaced when
+    /// the sender's size or format changes. None if there is no sender.
+    pub fn new_frame_resource(&self) -> Option<ID3D12ResourcePtr> {
+        #[cfg(target_os = "windows")]
+        {
+            if !unsafe { spout_ffi::spout_dx12_receiver_receive_resource(self.handle.as_ptr()) } {
+                return None;
+            }
+            let resource = unsafe { spout_ffi::spout_dx12_receiver_get_resource(self.handle.as_ptr()) };
+            (!resource.is_null()).then_some(resource)
+        }
+        #[cfg(not(target_os = "windows"))]
+        None
+    }
+
+    /// Stop receiving (idempotent; receiving again reconnects).
+    pub fn stop(&self) {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_dx12_receiver_release(self.handle.as_ptr())
+        };
+    }
+}
+
+/// Whether the OpenGL context current on this thread exposes `WGL_NV_DX_interop`, which
+/// `SpoutGlDxServer` / `SpoutGlDxClient` need. False off Windows.
+pub fn gl_dx_interop_available() -> bool {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        spout_ffi::spout_gldx_available()
+    }
+    #[cfg(not(target_os = "windows"))]
+    false
+}
+
+/// A glue GL/DX interop instance (SpoutDX on its own D3D11 device plus the linked texture), released on
+/// drop.
+#[cfg(target_os = "windows")]
+struct GlDxHandle(NonNull<std::ffi::c_void>);
+
+#[cfg(target_os = "windows")]
+impl GlDxHandle {
+    fn open(object: &'static str) -> Result<Self, SyphonError> {
+        if !gl_dx_interop_available() {
+            return Err(SyphonError::CreationFailed {
+                object,
+                reason: "the current OpenGL context has no WGL_NV_DX_interop".into(),
+            });
+        }
+        let ptr = unsafe { spout_ffi::spout_gldx_create() };
+        NonNull::new(ptr).map(Self).ok_or_else(|| SyphonError::CreationFailed {
+            object,
+            reason: "SpoutDX could not create a D3D11 device".into(),
+        })
+    }
+
+    fn as_ptr(&self) -> *mut std::ffi::c_void {
+        self.0.as_ptr()
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for GlDxHandle {
+    fn drop(&mut self) {
+        unsafe { spout_ffi::spout_gldx_destroy(self.0.as_ptr()) };
+    }
+}
+
+/// A Spout sender publishing OpenGL textures through `WGL_NV_DX_interop`: each frame is blitted on the GPU
+/// into a GL texture linked to the D3D11 texture Spout shares. Create, publish and drop with the same GL
+/// context current.
+pub struct SpoutGlDxServer {
+    #[cfg(target_os = "windows")]
+    handle: GlDxHandle,
+    #[cfg(target_os = "windows")]
+    name: RefCell<Option<String>>,
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
+impl SpoutGlDxServer {
+    /// Create a sender named `name` (None: the executable's name). None off Windows, if the current
+    /// context lacks `WGL_NV_DX_interop` (see `gl_dx_interop_available`), or if SpoutDX can't start.
+    pub fn new(name: Option<&str>) -> Option<Self> {
+        Self::try_new(name).ok()
+    }
+
+    /// Like `new`, reporting why the sender couldn't be created.
+    pub fn try_new(name: Option<&str>) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "windows")]
+        {
+            let c_name = optional_cstring(name)?;
+            let handle = GlDxHandle::open("Spout GL/DX sender")?;
+            if let Some(c_name) = &c_name {
+                unsafe { spout_ffi::spout_gldx_sender_set_name(handle.as_ptr(), c_name.as_ptr()) };
+            }
+            Ok(Self {
+                handle,
+                name: RefCell::new(name.map(str::to_owned)),
+            })
+        }
+        #[cfg(not(target_os = "windows"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// The sender name: as registered once sharing has started, else as requested.
+    pub fn name(&self) -> Option<String> {
+        #[cfg(target_os = "windows")]
+        {
+            let s = unsafe { spout_ffi::spout_gldx_sender_get_name(self.handle.as_ptr()) };
+            if !s.is_null() {
+                let name = unsafe { std::ffi::CStr::from_ptr(s) }.to_string_lossy().into_owned();
+                unsafe { spout_ffi::spout_string_free(s) };
+                return Some(name);
+            }
+            self.name.borrow().clone()
+        }
+        #[cfg(not(target_os = "windows"))]
+        None
+    }
+
+    /// Rename the sender; as with `SpoutServer::set_name`, the next frame registers the new name.
+    pub fn set_name(&self, name: Option<&str>) {
+        let _ = self.try_set_name(name);
+    }
+
+    /// Like `set_name`, reporting a NUL byte in `name` as `InvalidArgument`.
+    pub fn try_set_name(&self, name: Option<&str>) -> Result<(), SyphonError> {
+        #[cfg(target_os = "windows")]
+        {
+            let c_name = optional_cstring(name)?;
+            let ptr = c_name.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
+            unsafe {
+                spout_ffi::spout_gldx_sender_release(self.handle.as_ptr());
+                spout_ffi::spout_gldx_sender_set_name(self.handle.as_ptr(), ptr);
+            }
+            *self.name.borrow_mut() = name.map(str::to_owned);
+            Ok(())
+        }
+        #[cfg(not(target_os = "windows"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Publish all of a `width` x `height` texture bound to `target`; `flipped` if its rows are top-down,
+    /// as for `SpoutServer::publish_whole_texture`.
+    pub fn publish_whole_texture(&self, tex_id: u32, target: u32, width: f64, height: f64, flipped: bool) {
+        let _ = self.try_publish_whole_texture(tex_id, target, width, height, flipped);
+    }
+
+    /// Like `publish_whole_texture`, reporting a frame that couldn't be shared as `CreationFailed`.
+    pub fn try_publish_whole_texture(
+        &self,
+        tex_id: u32,
+        target: u32,
+        width: f64,
+        height: f64,
+        flipped: bool,
+    ) -> Result<(), SyphonError> {
+        #[cfg(target_os = "windows")]
+        {
+            if width < 1.0 || height < 1.0 {
+                return Err(SyphonError::InvalidArgument("texture size must not be zero"));
+            }
+            let sent = unsafe {
+                spout_ffi::spout_gldx_sender_send_texture(
+                    self.handle.as_ptr(),
+                    tex_id,
+                    target,
+                    width as u32,
+                    height as u32,
+                    !flipped,
+                )
+            };
+            match sent {
+                true => Ok(()),
+                false => Err(send_failed()),
+            }
+        }
+        #[cfg(not(target_os = "windows"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Stop sharing (idempotent; publishing again restarts it).
+    pub fn stop(&self) {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_gldx_sender_release(self.handle.as_ptr())
+        };
+    }
+}
+
+/// A Spout receiver of OpenGL textures through `WGL_NV_DX_interop`: each frame is copied on the GPU into a
+/// D3D11 texture linked to a GL texture. Create, receive and drop with the same GL context current.
+pub struct SpoutGlDxClient {
+    #[cfg(target_os = "windows")]
+    handle: GlDxHandle,
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
+impl SpoutGlDxClient {
+    /// Receive from the sender named `sender` (None: the active sender). None off Windows, if the current
+    /// context lacks `WGL_NV_DX_interop`, or if SpoutDX can't start.
+    pub fn new(sender: Option<&str>) -> Option<Self> {
+        Self::try_new(sender).ok()
+    }
+
+    /// Like `new`, reporting why the receiver couldn't be created.
+    pub fn try_new(sender: Option<&str>) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "windows")]
+        {
+            let sender = optional_cstring(sender)?;
+            let handle = GlDxHandle::open("Spout GL/DX receiver")?;
+            let ptr = sender.as_ref().map(|c| c.as_ptr()).unwrap_or(std::ptr::null());
+            unsafe { spout_ffi::spout_gldx_receiver_set_name(handle.as_ptr(), ptr) };
+            Ok(Self { handle })
+        }
+        #[cfg(not(target_os = "windows"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Name of the sender being received, once connected.
+    pub fn server_name(&self) -> Option<String> {
+        #[cfg(target_os = "windows")]
+        {
+            let mut buf = [0u8; 256];
+            let ok = unsafe {
+                spout_ffi::spout_gldx_receiver_get_sender_name(
+                    self.handle.as_ptr(),
+                    buf.as_mut_ptr() as *mut std::os::raw::c_char,
+                    buf.len() as i32,
+                )
+            };
+            ok.then(|| buffer_to_string(&buf)).flatten()
+        }
+        #[cfg(not(target_os = "windows"))]
+        None
+    }
+
+    /// Whether the last receive found the sender.
+    pub fn is_valid(&self) -> bool {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_gldx_receiver_is_connected(self.handle.as_ptr())
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// Whether the last receive got a frame the sender hadn't delivered before.
+    pub fn has_new_frame(&self) -> bool {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_gldx_receiver_is_frame_new(self.handle.as_ptr())
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// Whether the last receive connected to a sender or saw its size or format change.
+    pub fn is_updated(&self) -> bool {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_gldx_receiver_is_updated(self.handle.as_ptr())
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// The sender's frame size in pixels, as of the last receive.
+    pub fn frame_size(&self) -> (usize, usize) {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            (
+                spout_ffi::spout_gldx_receiver_get_sender_width(self.handle.as_ptr()) as usize,
+                spout_ffi::spout_gldx_receiver_get_sender_height(self.handle.as_ptr()) as usize,
+            )
+        }
+        #[cfg(not(target_os = "windows"))]
+        (0, 0)
+    }
+
+    /// Copy the current frame into your own texture `tex_id` (bound to `target`), bottom-up like
+    /// `SpoutClient::copy_frame_to`. The texture must be at least `frame_size`; false if there is no sender.
+    pub fn copy_frame_to(&self, tex_id: u32, target: u32) -> bool {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            tex_id != 0 && spout_ffi::spout_gldx_receiver_receive_texture(self.handle.as_ptr(), tex_id, target, true)
+        }
+        #[cfg(not(target_os = "windows"))]
+        false
+    }
+
+    /// Receive the current frame into the linked texture and return its GL name: a `GL_TEXTURE_2D` of
+    /// `frame_size` with top-down rows, owned by the client and usable until the next receive. None if
+    /// there is no sender.
+    pub fn new_frame_texture(&self) -> Option<u32> {
+        #[cfg(target_os = "windows")]
+        {
+            if !unsafe { spout_ffi::spout_gldx_receiver_receive_texture(self.handle.as_ptr(), 0, 0, false) } {
+                return None;
+            }
+            let texture = unsafe { spout_ffi::spout_gldx_receiver_get_texture(self.handle.as_ptr()) };
+            (texture != 0).then_some(texture)
+        }
+        #[cfg(not(target_os = "windows"))]
+        None
+    }
+
+    /// Stop receiving (idempotent; receiving again reconnects).
+    pub fn stop(&self) {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            spout_ffi::spout_gldx_receiver_release(self.handle.as_ptr())
+        };
+    }
+}
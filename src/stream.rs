@@ -0,0 +1,112 @@
+//! Async `Stream`s (`futures` feature): a callback-fed queue shared by `ServerDirectory::events` and
+//! the clients' `frames`.
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use futures_core::Stream;
+
+use crate::{FrameInfo, MetalClient, OpenGLClient};
+
+struct Queue<T> {
+    items: VecDeque<T>,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+/// Producer half, moved into a callback. Dropping it (with the callback) ends the stream.
+pub(crate) struct EventSink<T> {
+    queue: Arc<Mutex<Queue<T>>>,
+}
+
+/// Consumer half: yields queued items, then None once the sink is gone.
+pub(crate) struct EventStream<T> {
+    queue: Arc<Mutex<Queue<T>>>,
+}
+
+pub(crate) fn event_queue<T>() -> (EventSink<T>, EventStream<T>) {
+    let queue = Arc::new(Mutex::new(Queue {
+        items: VecDeque::new(),
+        waker: None,
+        closed: false,
+    }));
+    (EventSink { queue: queue.clone() }, EventStream { queue })
+}
+
+impl<T> EventSink<T> {
+    pub(crate) fn push(&self, item: T) {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        queue.items.push_back(item);
+        if let Some(waker) = queue.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Drop for EventSink<T> {
+    fn drop(&mut self) {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        queue.closed = true;
+        if let Some(waker) = queue.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Stream for EventStream<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        match queue.items.pop_front() {
+            Some(item) => Poll::Ready(Some(item)),
+            None if queue.closed => Poll::Ready(None),
+            None => {
+                queue.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Async stream of a client's new-frame notifications from `OpenGLClient::frames` /
+/// `MetalClient::frames`. Ends when the client is stopped or dropped, or its handler is replaced.
+/// Notifications queue up (unbounded) while the stream isn't polled.
+pub struct FrameStream {
+    events: EventStream<FrameInfo>,
+}
+
+impl Stream for FrameStream {
+    type Item = FrameInfo;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<FrameInfo>> {
+        Pin::new(&mut self.events).poll_next(cx)
+    }
+}
+
+fn frame_stream() -> (crate::NewFrameCallback, FrameStream) {
+    let (sink, events) = event_queue();
+    (Box::new(move |info| sink.push(info)), FrameStream { events })
+}
+
+impl OpenGLClient {
+    /// New-frame notifications as a `Stream`. Requires the `futures` feature; replaces the client's
+    /// new-frame callback (see `set_new_frame_handler`).
+    pub fn frames(&self) -> FrameStream {
+        let (callback, stream) = frame_stream();
+        self.set_new_frame_handler(Some(callback));
+        stream
+    }
+}
+
+impl MetalClient {
+    /// New-frame notifications as a `Stream`. Requires the `futures` feature; replaces the client's
+    /// new-frame callback (see `set_new_frame_handler`).
+    pub fn frames(&self) -> FrameStream {
+        let (callback, stream) = frame_stream();
+        self.set_new_frame_handler(Some(callback));
+        stream
+    }
+}
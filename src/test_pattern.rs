@@ -0,0 +1,278 @@
+//! Test-pattern source: `TestPatternServer` publishes SMPTE bars, gradients or a moving box (with an
+//! optional frame-counter overlay) at a chosen size and rate from its own thread, through a `CpuServer`
+//! (Metal), so downstream apps can be checked without a real content source. `render_test_pattern` draws
+//! the same frames into memory.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::worker::{frame_interval, Worker, POLL_INTERVAL};
+use crate::{render_overlay_text, CpuServer, ServerDescription, SyphonError};
+
+/// What a `TestPatternServer` draws.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TestPattern {
+    /// SMPTE ECR 1-1978 color bars: 75% bars, the reversed blue row, then -I, white, +Q and PLUGE.
+    #[default]
+    SmpteBars,
+    /// Left-to-right ramps from 0 to 255 in four bands: gray, red, green, blue (banding and channel order).
+    Gradient,
+    /// A white box bouncing over a grid (motion, tearing and dropped frames).
+    MovingBox,
+}
+
+impl TestPattern {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => TestPattern::Gradient,
+            2 => TestPattern::MovingBox,
+            _ => TestPattern::SmpteBars,
+        }
+    }
+}
+
+/// Size, rate and content of a `TestPatternServer`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestPatternOptions {
+    pub pattern: TestPattern,
+    pub width: usize,
+    pub height: usize,
+    /// Frames published per second: positive, finite, and large enough for the time between frames
+    /// to fit a `Duration`.
+    pub fps: f64,
+    /// Burn the frame number, size and rate into the top-left corner.
+    pub frame_counter: bool,
+}
+
+impl Default for TestPatternOptions {
+    fn default() -> Self {
+        Self {
+            pattern: TestPattern::SmpteBars,
+            width: 1920,
+            height: 1080,
+            fps: 60.0,
+            frame_counter: true,
+        }
+    }
+}
+
+/// Publishes a test pattern as a Syphon server until dropped.
+pub struct TestPatternServer {
+    worker: Worker,
+    description: Option<ServerDescription>,
+    pattern: Arc<AtomicU8>,
+    frames: Arc<AtomicU64>,
+}
+
+impl TestPatternServer {
+    /// Start publishing `options.pattern` as the server `name`.
+    pub fn new(name: Option<&str>, options: &TestPatternOptions) -> Option<Self> {
+        Self::try_new(name, options).ok()
+    }
+
+    /// Like `new`, reporting a zero size or non-positive rate as `InvalidArgument` and why the server
+    /// couldn't be created.
+    pub fn try_new(name: Option<&str>, options: &TestPatternOptions) -> Result<Self, SyphonError> {
+        if options.width == 0 || options.height == 0 {
+            return Err(SyphonError::InvalidArgument("test pattern size must not be zero"));
+        }
+        let Some(interval) = frame_interval(options.fps) else {
+            return Err(SyphonError::InvalidArgument("test pattern rate must be positive and finite"));
+        };
+        let options = *options;
+        let name = name.map(str::to_owned);
+        let pattern = Arc::new(AtomicU8::new(options.pattern as u8));
+        let frames = Arc::new(AtomicU64::new(0));
+        let (thread_pattern, thread_frames) = (pattern.clone(), frames.clone());
+        let (worker, description) = Worker::spawn("syphon-test-pattern", move |ready, stop| {
+            let server = match CpuServer::try_new(name.as_deref(), None) {
+                Ok(server) => server,
+                Err(e) => {
+                    let _ = ready.send(Err(e));
+                    return;
+                }
+            };
+            let _ = ready.send(Ok(server.server_description()));
+            run(&server, &options, interval, &thread_pattern, &thread_frames, stop);
+        })?;
+        Ok(Self {
+            worker,
+            description,
+            pattern,
+            frames,
+        })
+    }
+
+    /// The pattern being published.
+    pub fn pattern(&self) -> TestPattern {
+        TestPattern::from_u8(self.pattern.load(Ordering::Relaxed))
+    }
+
+    /// Switch pattern from the next frame on.
+    pub fn set_pattern(&self, pattern: TestPattern) {
+        self.pattern.store(pattern as u8, Ordering::Relaxed);
+    }
+
+    /// Frames published so far.
+    pub fn frames_published(&self) -> u64 {
+        self.frames.load(Ordering::Relaxed)
+    }
+
+    /// The description Syphon clients connect with.
+    pub fn server_description(&self) -> Option<ServerDescription> {
+        self.description.clone()
+    }
+
+    /// False if the publishing thread has stopped.
+    pub fn is_running(&self) -> bool {
+        self.worker.is_running()
+    }
+}
+
+/// Publish a frame every `interval` until `stop` is set. Late frames are not made up: after a stall
+/// the schedule restarts from now.
+fn run(
+    server: &CpuServer,
+    options: &TestPatternOptions,
+    interval: Duration,
+    pattern: &AtomicU8,
+    frames: &AtomicU64,
+    stop: &AtomicBool,
+) {
+    let mut next = Instant::now();
+    let mut index = 0u64;
+    while !stop.load(Ordering::Relaxed) {
+        let now = Instant::now();
+        if now < next {
+            std::thread::sleep((next - now).min(POLL_INTERVAL));
+            continue;
+        }
+        let pattern = TestPattern::from_u8(pattern.load(Ordering::Relaxed));
+        let mut rgba = render_test_pattern(pattern, options.width, options.height, index);
+        if options.frame_counter {
+            let lines = [
+                format!("#{index}"),
+                format!("{}x{} @ {:.2}", options.width, options.height, options.fps),
+            ];
+            let scale = (options.height / 270).max(1);
+            let text = render_overlay_text(&[&lines[0], &lines[1]], scale);
+            blit(&mut rgba, options.width, options.height, &text.rgba, text.width, text.height);
+        }
+        if server.publish(&rgba, options.width, options.height) {
+            frames.fetch_add(1, Ordering::Relaxed);
+        }
+        index += 1;
+        next += interval;
+        if next < Instant::now() {
+            next = Instant::now();
+        }
+    }
+}
+
+/// Copy a `src_width` x `src_height` RGBA8 image into the top-left corner of `dst`, clipped to it.
+fn blit(dst: &mut [u8], width: usize, height: usize, src: &[u8], src_width: usize, src_height: usize) {
+    let columns = src_width.min(width) * 4;
+    for row in 0..src_height.min(height) {
+        let from = row * src_width * 4;
+        let to = row * width * 4;
+        dst[to..to + columns].copy_from_slice(&src[from..from + columns]);
+    }
+}
+
+const BAR_GRAY: [u8; 3] = [192, 192, 192];
+const BAR_YELLOW: [u8; 3] = [192, 192, 0];
+const BAR_CYAN: [u8; 3] = [0, 192, 192];
+const BAR_GREEN: [u8; 3] = [0, 192, 0];
+const BAR_MAGENTA: [u8; 3] = [192, 0, 192];
+const BAR_RED: [u8; 3] = [192, 0, 0];
+const BAR_BLUE: [u8; 3] = [0, 0, 192];
+/// 7.5 IRE setup black, and the PLUGE steps 4 IRE either side of it.
+const BAR_BLACK: [u8; 3] = [19, 19, 19];
+const PLUGE_LOW: [u8; 3] = [9, 9, 9];
+const PLUGE_HIGH: [u8; 3] = [29, 29, 29];
+
+/// Draw frame `index` of `pattern` as `width` x `height` RGBA8, top row first, fully opaque. Only
+/// `MovingBox` depends on `index`.
+pub fn render_test_pattern(pattern: TestPattern, width: usize, height: usize, index: u64) -> Vec<u8> {
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+    let mut rgba = vec![255u8; width * height * 4];
+    for (y, row) in rgba.chunks_exact_mut(width * 4).enumerate() {
+        for (x, px) in row.chunks_exact_mut(4).enumerate() {
+            let color = match pattern {
+                TestPattern::SmpteBars => smpte_bars(x, y, width, height),
+                TestPattern::Gradient => gradient(x, y, width, height),
+                TestPattern::MovingBox => grid(x, y, width, height),
+            };
+            px[..3].copy_from_slice(&color);
+        }
+    }
+    if pattern == TestPattern::MovingBox {
+        draw_box(&mut rgba, width, height, index);
+    }
+    rgba
+}
+
+fn smpte_bars(x: usize, y: usize, width: usize, height: usize) -> [u8; 3] {
+    // Rows: bars to 2/3 of the height, the reversed row to 3/4, the -I / +Q / PLUGE row below.
+    if y * 3 < height * 2 {
+        const BARS: [[u8; 3]; 7] = [BAR_GRAY, BAR_YELLOW, BAR_CYAN, BAR_GREEN, BAR_MAGENTA, BAR_RED, BAR_BLUE];
+        BARS[x * 7 / width]
+    } else if y * 4 < height * 3 {
+        const REVERSED: [[u8; 3]; 7] = [BAR_BLUE, BAR_BLACK, BAR_MAGENTA, BAR_BLACK, BAR_CYAN, BAR_BLACK, BAR_GRAY];
+        REVERSED[x * 7 / width]
+    } else {
+        // Four blocks of 5/4 bar width, then the three PLUGE steps across one bar, then black.
+        match x * 28 / width {
+            0..=4 => [0, 33, 76],
+            5..=9 => [255, 255, 255],
+            10..=14 => [50, 0, 106],
+            15..=19 => BAR_BLACK,
+            20..=23 => [PLUGE_LOW, BAR_BLACK, PLUGE_HIGH][((x * 84 / width).saturating_sub(60) / 4).min(2)],
+            _ => BAR_BLACK,
+        }
+    }
+}
+
+fn gradient(x: usize, y: usize, width: usize, height: usize) -> [u8; 3] {
+    let level = if width > 1 { (x * 255 / (width - 1)) as u8 } else { 255 };
+    match y * 4 / height {
+        0 => [level, level, level],
+        1 => [level, 0, 0],
+        2 => [0, level, 0],
+        _ => [0, 0, level],
+    }
+}
+
+fn grid(x: usize, y: usize, width: usize, height: usize) -> [u8; 3] {
+    // Lines every 1/16 of the width, as square cells.
+    let cell = (width / 16).max(2);
+    let on_line = x.is_multiple_of(cell) || y.is_multiple_of(cell) || x + 1 == width || y + 1 == height;
+    if on_line {
+        [96, 96, 96]
+    } else {
+        [32, 32, 32]
+    }
+}
+
+/// A white square an eighth of the height tall, bouncing off the edges at about a tenth of the width
+/// per second at 60 fps.
+fn draw_box(rgba: &mut [u8], width: usize, height: usize, index: u64) {
+    let size = (height / 8).max(1).min(width);
+    let bounce = |travel: usize, speed: u64| -> usize {
+        if travel == 0 {
+            return 0;
+        }
+        let period = 2 * travel as u64;
+        let t = (index * speed) % period;
+        (if t < travel as u64 { t } else { period - t }) as usize
+    };
+    let speed = (width as u64 / 600).max(1);
+    let left = bounce(width - size, speed);
+    let top = bounce(height - size, speed);
+    for row in rgba.chunks_exact_mut(width * 4).skip(top).take(size) {
+        row[left * 4..(left + size) * 4].fill(255);
+    }
+}
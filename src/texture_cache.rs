@@ -0,0 +1,86 @@
+//! Zero-copy Metal textures through a `CVMetalTextureCache`: frames are rewrapped straight from the
+//! server's IOSurface, so latency-critical clients sample the shared memory directly and control when the
+//! cache lets go of old surfaces (`flush`).
+
+#[cfg(target_os = "macos")]
+use std::ptr::NonNull;
+
+#[cfg(target_os = "macos")]
+use crate::ffi;
+use crate::{IOSurfacePtr, MTLDevicePtr, MetalClient, MetalTexture, SyphonError};
+
+/// A `CVMetalTextureCache` on one device. Textures it creates keep their cache entry alive until dropped.
+pub struct MetalTextureCache {
+    #[cfg(target_os = "macos")]
+    ptr: NonNull<std::ffi::c_void>,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl MetalTextureCache {
+    /// Cache creating textures on `device` (use the client's device).
+    pub fn new(device: MTLDevicePtr) -> Option<Self> {
+        Self::try_new(device).ok()
+    }
+
+    /// Like `new`, reporting why creation failed.
+    pub fn try_new(device: MTLDevicePtr) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            if device.is_null() {
+                return Err(SyphonError::InvalidArgument("device is null"));
+            }
+            let ptr = unsafe { ffi::syphon_cv_metal_texture_cache_create(device) };
+            NonNull::new(ptr).map(|ptr| Self { ptr }).ok_or(SyphonError::CreationFailed {
+                object: "CVMetalTextureCache",
+                reason: "CVMetalTextureCacheCreate failed".into(),
+            })
+        }
+        #[cfg(not(target_os = "macos"))]
+        Err(SyphonError::Unsupported)
+    }
+
+    /// Wrap `surface` as a texture of raw `MTLPixelFormat` `pixel_format` (which must match the surface's
+    /// layout, e.g. BGRA8Unorm for `BGRA` surfaces). None if CoreVideo rejects the surface or format.
+    pub fn texture_from_io_surface(&self, surface: IOSurfacePtr, pixel_format: u64) -> Option<MetalTexture> {
+        #[cfg(target_os = "macos")]
+        {
+            let ptr = unsafe {
+                ffi::syphon_cv_metal_texture_cache_new_texture(self.ptr.as_ptr(), surface, pixel_format as _)
+            };
+            NonNull::new(ptr).map(|ptr| unsafe { MetalTexture::from_retained(ptr) })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Rewrap `frame`'s IOSurface through the cache, keeping its pixel format.
+    pub fn texture_for(&self, frame: &MetalTexture) -> Option<MetalTexture> {
+        self.texture_from_io_surface(frame.io_surface_ptr()?, frame.pixel_format())
+    }
+
+    /// Release cache entries whose textures have been dropped (call periodically, e.g. once per frame or
+    /// after a resolution change).
+    pub fn flush(&self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_cv_metal_texture_cache_flush(self.ptr.as_ptr());
+        }
+    }
+}
+
+impl Drop for MetalTextureCache {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_cv_metal_texture_cache_release(self.ptr.as_ptr());
+        }
+    }
+}
+
+impl MetalClient {
+    /// Like `new_frame_image`, but the texture is created through `cache` directly over the shared
+    /// IOSurface. Create the cache on the device this client was created with.
+    pub fn new_frame_image_cached(&self, cache: &MetalTextureCache) -> Option<MetalTexture> {
+        cache.texture_for(&self.new_frame_image()?)
+    }
+}
@@ -0,0 +1,107 @@
+//! Heap-backed Metal texture pools: working textures are sub-allocated from `MTLHeap`s instead of the
+//! device, cutting allocation overhead and fragmentation in long-running sessions with frequent
+//! resolution changes. Textures return their memory to the heap when dropped; emptied heaps are freed
+//! as the pool grows, so a size change does not keep the old resolution's memory around.
+
+#[cfg(target_os = "macos")]
+use std::ptr::NonNull;
+
+#[cfg(target_os = "macos")]
+use crate::ffi;
+use crate::{MTLCommandQueuePtr, MTLDevicePtr, MetalTexture, MTL_STORAGE_MODE_PRIVATE};
+
+/// Default size of each heap a pool allocates: room for two 4K BGRA8 frames.
+pub const DEFAULT_POOL_HEAP_SIZE: usize = 2 * 3840 * 2160 * 4;
+
+/// A pool of textures backed by one or more `MTLHeap`s on one device.
+pub struct MetalTexturePool {
+    #[cfg(target_os = "macos")]
+    ptr: NonNull<std::ffi::c_void>,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+impl MetalTexturePool {
+    /// Private-storage pool with `DEFAULT_POOL_HEAP_SIZE` heaps.
+    pub fn new(device: MTLDevicePtr) -> Option<Self> {
+        Self::with_heap_size(device, DEFAULT_POOL_HEAP_SIZE, MTL_STORAGE_MODE_PRIVATE)
+    }
+
+    /// Pool whose heaps are `heap_size` bytes (requests that don't fit get a heap of their own) with raw
+    /// `MTLStorageMode` `storage_mode` (private, or shared on Apple silicon; managed is rejected).
+    pub fn with_heap_size(device: MTLDevicePtr, heap_size: usize, storage_mode: u64) -> Option<Self> {
+        #[cfg(target_os = "macos")]
+        {
+            if device.is_null() {
+                return None;
+            }
+            let ptr = unsafe { ffi::syphon_metal_texture_pool_create(device as *mut _, heap_size, storage_mode as _) };
+            NonNull::new(ptr).map(|ptr| Self { ptr })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// A new `width` x `height` texture of raw `MTLPixelFormat` `pixel_format` with `MTL_TEXTURE_USAGE_*`
+    /// flags `usage`, allocated from the pool. Its memory goes back to the heap when dropped.
+    pub fn new_texture(&self, pixel_format: u64, width: usize, height: usize, usage: u64) -> Option<MetalTexture> {
+        #[cfg(target_os = "macos")]
+        {
+            let ptr = unsafe {
+                ffi::syphon_metal_texture_pool_new_texture(self.ptr.as_ptr(), pixel_format as _, width, height, usage as _)
+            };
+            NonNull::new(ptr).map(|ptr| unsafe { MetalTexture::from_retained(ptr) })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Copy `texture` into a pooled texture with the same size and format (see `MetalTexture::copy_to_owned`;
+    /// blocks until the copy completes).
+    pub fn copy(
+        &self,
+        texture: &MetalTexture,
+        command_queue: Option<MTLCommandQueuePtr>,
+        usage: u64,
+    ) -> Option<MetalTexture> {
+        #[cfg(target_os = "macos")]
+        {
+            let queue = command_queue.unwrap_or(std::ptr::null_mut());
+            let ptr = unsafe {
+                ffi::syphon_metal_texture_pool_copy(self.ptr.as_ptr(), texture.as_ptr(), queue as *mut _, usage as _)
+            };
+            NonNull::new(ptr).map(|ptr| unsafe { MetalTexture::from_retained(ptr) })
+        }
+        #[cfg(not(target_os = "macos"))]
+        None
+    }
+
+    /// Total bytes currently held in heaps (used or not).
+    pub fn allocated_size(&self) -> usize {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_metal_texture_pool_allocated_size(self.ptr.as_ptr())
+        }
+        #[cfg(not(target_os = "macos"))]
+        0
+    }
+
+    /// Let the heap alias `texture`'s memory for later allocations while the object stays alive
+    /// (no-op for textures not from a pool).
+    ///
+    /// # Safety
+    /// No submitted or future GPU work may read or write `texture` after this call; its contents are
+    /// undefined once another pooled texture is allocated over it.
+    pub unsafe fn make_aliasable(texture: &MetalTexture) {
+        #[cfg(target_os = "macos")]
+        ffi::syphon_metal_texture_make_aliasable(texture.as_ptr());
+    }
+}
+
+impl Drop for MetalTexturePool {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            ffi::syphon_metal_texture_pool_release(self.ptr.as_ptr());
+        }
+    }
+}
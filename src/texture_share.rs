@@ -0,0 +1,252 @@
+//! Cross-platform texture sharing: `TextureShareServer` / `TextureShareClient` publish and receive OpenGL
+//! textures through Syphon on macOS and Spout on Windows, and `texture_share_sources` lists what can be
+//! received on either, so plugins need no `#[cfg(target_os)]` of their own. On Linux the `linux-shm`
+//! feature shares frames through POSIX shared memory (read back and uploaded on the CPU, so slower, but
+//! any process can receive them); elsewhere the constructors report `Unsupported` and discovery finds
+//! nothing.
+//!
+//! All platforms share through the OpenGL context current on the calling thread: create, publish and
+//! receive with it current.
+
+use crate::{CpuFrame, ServerDescriptionInfo, SyphonError};
+#[cfg(not(all(target_os = "linux", feature = "linux-shm")))]
+use crate::ServerDirectory;
+#[cfg(target_os = "macos")]
+use crate::{check_current_context, cgl_current_context, OpenGLClient, OpenGLServer};
+#[cfg(all(target_os = "linux", feature = "linux-shm"))]
+use crate::linux_share::{self, ShmClient, ShmServer};
+#[cfg(not(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm"))))]
+use crate::{SpoutClient, SpoutServer};
+
+#[cfg(target_os = "macos")]
+type PlatformServer = OpenGLServer;
+#[cfg(all(target_os = "linux", feature = "linux-shm"))]
+type PlatformServer = ShmServer;
+#[cfg(not(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm"))))]
+type PlatformServer = SpoutServer;
+
+#[cfg(target_os = "macos")]
+type PlatformClient = OpenGLClient;
+#[cfg(all(target_os = "linux", feature = "linux-shm"))]
+type PlatformClient = ShmClient;
+#[cfg(not(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm"))))]
+type PlatformClient = SpoutClient;
+
+/// Sources that can be received, as listed by `ServerDirectory`: Syphon servers on macOS (all fields
+/// set), Spout senders on Windows (`uuid` and `name`, both the sender name). With `linux-shm`, the
+/// servers publishing in `/dev/shm` (`uuid` is the region, `name` the server name). Pass one to
+/// `TextureShareClient::new`, or persist it to find the source again later.
+pub fn texture_share_sources() -> Vec<ServerDescriptionInfo> {
+    #[cfg(all(target_os = "linux", feature = "linux-shm"))]
+    {
+        linux_share::sources()
+    }
+    #[cfg(not(all(target_os = "linux", feature = "linux-shm")))]
+    ServerDirectory::shared()
+        .map(|directory| directory.servers().iter().map(|d| d.info()).collect())
+        .unwrap_or_default()
+}
+
+/// Publishes OpenGL textures as a Syphon server (macOS), Spout sender (Windows) or shared-memory region
+/// (Linux with `linux-shm`).
+pub struct TextureShareServer {
+    inner: PlatformServer,
+}
+
+impl TextureShareServer {
+    /// Create a server named `name` on the current OpenGL context. None if no context is current (on
+    /// Linux, checked when publishing), on an unsupported platform, or if creation failed.
+    pub fn new(name: Option<&str>) -> Option<Self> {
+        Self::try_new(name).ok()
+    }
+
+    /// Like `new`, reporting why the server couldn't be created.
+    pub fn try_new(name: Option<&str>) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "macos")]
+        let inner = OpenGLServer::try_new_with_current_context(name, None)?;
+        #[cfg(all(target_os = "linux", feature = "linux-shm"))]
+        let inner = ShmServer::try_new(name)?;
+        #[cfg(not(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm"))))]
+        let inner = SpoutServer::try_new(name)?;
+        Ok(Self { inner })
+    }
+
+    /// The server name.
+    pub fn name(&self) -> Option<String> {
+        self.inner.name()
+    }
+
+    /// Rename the server; names containing a NUL byte are ignored.
+    pub fn set_name(&self, name: Option<&str>) {
+        self.inner.set_name(name)
+    }
+
+    /// Like `set_name`, reporting a NUL byte in `name` as `InvalidArgument`.
+    pub fn try_set_name(&self, name: Option<&str>) -> Result<(), SyphonError> {
+        self.inner.try_set_name(name)
+    }
+
+    /// Publish all of a `width` x `height` texture bound to `target` (`GL_TEXTURE_2D` or
+    /// `GL_TEXTURE_RECTANGLE`); `flipped` if its rows are top-down.
+    pub fn publish_whole_texture(&self, tex_id: u32, target: u32, width: f64, height: f64, flipped: bool) {
+        self.inner.publish_whole_texture(tex_id, target, width, height, flipped)
+    }
+
+    /// Like `publish_whole_texture`, reporting why a frame wasn't published.
+    pub fn try_publish_whole_texture(
+        &self,
+        tex_id: u32,
+        target: u32,
+        width: f64,
+        height: f64,
+        flipped: bool,
+    ) -> Result<(), SyphonError> {
+        self.inner.try_publish_whole_texture(tex_id, target, width, height, flipped)
+    }
+
+    /// The source clients (in this process or, on macOS, others) receive this server through.
+    pub fn source(&self) -> Option<ServerDescriptionInfo> {
+        #[cfg(target_os = "macos")]
+        {
+            self.inner.server_description().map(|d| d.info())
+        }
+        #[cfg(all(target_os = "linux", feature = "linux-shm"))]
+        {
+            self.inner.source()
+        }
+        #[cfg(not(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm"))))]
+        self.inner.name().map(|name| ServerDescriptionInfo {
+            uuid: Some(name.clone()),
+            name: Some(name),
+            ..Default::default()
+        })
+    }
+
+    /// The Syphon server underneath, for platform-specific calls.
+    #[cfg(target_os = "macos")]
+    pub fn opengl_server(&self) -> &OpenGLServer {
+        &self.inner
+    }
+
+    /// The Spout sender underneath, for platform-specific calls.
+    #[cfg(not(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm"))))]
+    pub fn spout_server(&self) -> &SpoutServer {
+        &self.inner
+    }
+
+    /// Stop publishing (idempotent).
+    pub fn stop(&self) {
+        self.inner.stop()
+    }
+}
+
+/// Receives a `texture_share_sources` source into OpenGL textures or CPU memory.
+pub struct TextureShareClient {
+    inner: PlatformClient,
+}
+
+impl TextureShareClient {
+    /// Receive from `source` on the current OpenGL context. On macOS the source must be running (matched
+    /// by UUID, then by name and app name, as `ServerDescriptionInfo::resolve`); a Spout client waits for
+    /// its sender by name (or follows the active sender if `source.name` is None). On Linux the source
+    /// must be running and is followed by region, then name, across server restarts.
+    pub fn new(source: &ServerDescriptionInfo) -> Option<Self> {
+        Self::try_new(source).ok()
+    }
+
+    /// Like `new`, reporting why the client couldn't be created (`NoCurrentContext` on macOS without a
+    /// current context, `CreationFailed` if the source isn't running).
+    pub fn try_new(source: &ServerDescriptionInfo) -> Result<Self, SyphonError> {
+        #[cfg(target_os = "macos")]
+        {
+            check_current_context(None)?;
+            let directory = ServerDirectory::try_shared()?;
+            let description = source.resolve(&directory).ok_or_else(|| SyphonError::CreationFailed {
+                object: "texture share client",
+                reason: "the source is not running".into(),
+            })?;
+            let inner = OpenGLClient::try_new(&description, cgl_current_context(), None, None)?;
+            Ok(Self { inner })
+        }
+        #[cfg(all(target_os = "linux", feature = "linux-shm"))]
+        {
+            Ok(Self {
+                inner: ShmClient::try_new(source)?,
+            })
+        }
+        #[cfg(not(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm"))))]
+        Ok(Self {
+            inner: SpoutClient::try_new(source.name.as_deref())?,
+        })
+    }
+
+    /// Name of the source being received.
+    pub fn server_name(&self) -> Option<String> {
+        #[cfg(target_os = "macos")]
+        {
+            self.inner.server_description().and_then(|d| d.name())
+        }
+        #[cfg(not(target_os = "macos"))]
+        self.inner.server_name()
+    }
+
+    /// False once the source has gone away (on Windows, as of the last receive).
+    pub fn is_valid(&self) -> bool {
+        self.inner.is_valid()
+    }
+
+    /// The current frame's size in pixels; None if there is no frame.
+    pub fn frame_size(&self) -> Option<(usize, usize)> {
+        #[cfg(all(target_os = "linux", feature = "linux-shm"))]
+        {
+            self.inner.frame_size()
+        }
+        #[cfg(not(all(target_os = "linux", feature = "linux-shm")))]
+        {
+            let image = self.inner.new_frame_image()?;
+            let (width, height) = image.texture_size();
+            Some((width as usize, height as usize))
+        }
+    }
+
+    /// Copy the current frame into your own RGBA texture `tex_id` (bound to `target`), bottom row first
+    /// as GL expects. The texture must be at least `frame_size`; false if there is no frame.
+    pub fn copy_frame_to(&self, tex_id: u32, target: u32) -> bool {
+        self.inner.copy_frame_to(tex_id, target)
+    }
+
+    /// Read the current frame back as RGBA8, top row first. None if there is no frame.
+    pub fn latest_frame(&self) -> Option<CpuFrame> {
+        #[cfg(target_os = "macos")]
+        {
+            let image = self.inner.new_frame_image()?;
+            let (width, height) = image.texture_size();
+            let rgba = image.try_read_pixels().ok().filter(|p| !p.is_empty())?;
+            Some(CpuFrame {
+                width: width as usize,
+                height: height as usize,
+                rgba,
+                timestamp: std::time::Instant::now(),
+            })
+        }
+        #[cfg(not(target_os = "macos"))]
+        self.inner.latest_frame()
+    }
+
+    /// The Syphon client underneath, for platform-specific calls.
+    #[cfg(target_os = "macos")]
+    pub fn opengl_client(&self) -> &OpenGLClient {
+        &self.inner
+    }
+
+    /// The Spout receiver underneath, for platform-specific calls.
+    #[cfg(not(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm"))))]
+    pub fn spout_client(&self) -> &SpoutClient {
+        &self.inner
+    }
+
+    /// Stop receiving (idempotent).
+    pub fn stop(&self) {
+        self.inner.stop()
+    }
+}
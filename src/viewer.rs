@@ -0,0 +1,154 @@
+//! Debug viewer (`viewer` feature): `Viewer::open` shows a server's frames in a plain window, read back
+//! through a `CpuClient` and drawn with softbuffer, to check a server is publishing without another app.
+
+use std::num::NonZeroU32;
+use std::rc::Rc;
+
+use winit::application::ApplicationHandler;
+use winit::dpi::LogicalSize;
+use winit::event::WindowEvent;
+use winit::event_loop::{ActiveEventLoop, EventLoop};
+use winit::window::{Window, WindowId};
+
+use crate::{CpuClient, CpuFrame, ServerDescription, SyphonError};
+
+fn failed(object: &'static str, reason: impl std::fmt::Display) -> SyphonError {
+    SyphonError::CreationFailed {
+        object,
+        reason: reason.to_string(),
+    }
+}
+
+/// Draw `frame` into a `width` x `height` 0RGB buffer, scaled to fit (nearest neighbour) and centered on black.
+fn draw_fit(frame: &CpuFrame, buffer: &mut [u32], width: usize, height: usize) {
+    buffer.fill(0);
+    if frame.width == 0 || frame.height == 0 {
+        return;
+    }
+    let scale = (width as f64 / frame.width as f64).min(height as f64 / frame.height as f64);
+    let (w, h) = ((frame.width as f64 * scale) as usize, (frame.height as f64 * scale) as usize);
+    if w == 0 || h == 0 {
+        return;
+    }
+    let (x0, y0) = ((width - w) / 2, (height - h) / 2);
+    for y in 0..h {
+        let src_row = y * frame.height / h * frame.width;
+        let row = &mut buffer[(y0 + y) * width + x0..][..w];
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let i = (src_row + x * frame.width / w) * 4;
+            let [r, g, b] = [frame.rgba[i], frame.rgba[i + 1], frame.rgba[i + 2]].map(u32::from);
+            *pixel = (r << 16) | (g << 8) | b;
+        }
+    }
+}
+
+struct ViewerWindow {
+    // The surface borrows the window's handles, so it is declared (and dropped) first.
+    surface: softbuffer::Surface<Rc<Window>, Rc<Window>>,
+    window: Rc<Window>,
+}
+
+/// A window showing the latest frame of one server; it redraws as frames arrive and keeps the last frame
+/// once the server goes away.
+pub struct Viewer {
+    client: CpuClient,
+    title: String,
+    window: Option<ViewerWindow>,
+    frame: Option<CpuFrame>,
+    error: Option<SyphonError>,
+}
+
+impl Viewer {
+    /// Open a viewer for the server in `description` and run it until the window is closed. Blocks, and
+    /// must be called on the main thread (AppKit runs the window's event loop there).
+    pub fn open(description: &ServerDescription) -> Result<(), SyphonError> {
+        let event_loop = EventLoop::new().map_err(|e| failed("event loop", e))?;
+        let proxy = event_loop.create_proxy();
+        let client = CpuClient::try_new(
+            description,
+            Some(Box::new(move |_| {
+                let _ = proxy.send_event(());
+            })),
+        )?;
+        let name = description.name().filter(|n| !n.is_empty());
+        let title = match (name, description.app_name()) {
+            (Some(name), Some(app)) => format!("{name} — {app}"),
+            (name, app) => name.or(app).unwrap_or_else(|| "Syphon server".into()),
+        };
+        let mut viewer = Self {
+            client,
+            title,
+            window: None,
+            frame: None,
+            error: None,
+        };
+        event_loop.run_app(&mut viewer).map_err(|e| failed("event loop", e))?;
+        viewer.error.map_or(Ok(()), Err)
+    }
+
+    fn create_window(&self, event_loop: &ActiveEventLoop) -> Result<ViewerWindow, SyphonError> {
+        let (width, height) = self.frame.as_ref().map_or((640, 360), |f| (f.width, f.height));
+        let attributes = Window::default_attributes()
+            .with_title(&self.title)
+            .with_inner_size(LogicalSize::new(width as f64, height as f64));
+        let window = Rc::new(event_loop.create_window(attributes).map_err(|e| failed("viewer window", e))?);
+        let context = softbuffer::Context::new(window.clone()).map_err(|e| failed("softbuffer context", e))?;
+        let surface = softbuffer::Surface::new(&context, window.clone()).map_err(|e| failed("softbuffer surface", e))?;
+        Ok(ViewerWindow { surface, window })
+    }
+
+    fn redraw(&mut self) -> Result<(), SyphonError> {
+        let Some(ViewerWindow { surface, window }) = &mut self.window else {
+            return Ok(());
+        };
+        let size = window.inner_size();
+        let (Some(width), Some(height)) = (NonZeroU32::new(size.width), NonZeroU32::new(size.height)) else {
+            return Ok(());
+        };
+        surface.resize(width, height).map_err(|e| failed("softbuffer surface", e))?;
+        let mut buffer = surface.buffer_mut().map_err(|e| failed("softbuffer buffer", e))?;
+        match &self.frame {
+            Some(frame) => draw_fit(frame, &mut buffer, size.width as usize, size.height as usize),
+            None => buffer.fill(0),
+        }
+        buffer.present().map_err(|e| failed("softbuffer buffer", e))
+    }
+
+    fn fail(&mut self, event_loop: &ActiveEventLoop, error: SyphonError) {
+        self.error = Some(error);
+        event_loop.exit();
+    }
+}
+
+impl ApplicationHandler for Viewer {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.window.is_some() {
+            return;
+        }
+        match self.create_window(event_loop) {
+            Ok(window) => self.window = Some(window),
+            Err(e) => self.fail(event_loop, e),
+        }
+    }
+
+    fn user_event(&mut self, _event_loop: &ActiveEventLoop, _event: ()) {
+        if let Some(frame) = self.client.latest_frame() {
+            self.frame = Some(frame);
+            if let Some(window) = &self.window {
+                window.window.request_redraw();
+            }
+        }
+    }
+
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, _window_id: WindowId, event: WindowEvent) {
+        match event {
+            WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::RedrawRequested => {
+                if let Err(e) = self.redraw() {
+                    self.fail(event_loop, e);
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Vulkan integration on MoltenVK (`vulkan` feature, `ash` types): frames' IOSurfaces are imported as
+//! `VkImage`s and Vulkan-rendered images are published through their MTLTextures, both via
+//! `VK_EXT_metal_objects`, which the `ash::Device` must have been created with.
+
+use ash::vk;
+
+use crate::{
+    Frame, IOSurfacePtr, MTLCommandQueuePtr, MTLDevicePtr, MTLTexturePtr, MetalServer, MetalTexture, Rect,
+    SyphonError, SyphonOptions, MTL_PIXEL_FORMAT_BGRA8_UNORM, MTL_PIXEL_FORMAT_BGRA8_UNORM_SRGB,
+    MTL_PIXEL_FORMAT_RGBA16_FLOAT, MTL_PIXEL_FORMAT_RGBA8_UNORM, MTL_PIXEL_FORMAT_RGBA8_UNORM_SRGB,
+};
+
+/// Vulkan format of a raw `MTLPixelFormat`, for the formats Syphon frames come in.
+fn vk_format(pixel_format: u64) -> Option<vk::Format> {
+    Some(match pixel_format {
+        MTL_PIXEL_FORMAT_BGRA8_UNORM => vk::Format::B8G8R8A8_UNORM,
+        MTL_PIXEL_FORMAT_BGRA8_UNORM_SRGB => vk::Format::B8G8R8A8_SRGB,
+        MTL_PIXEL_FORMAT_RGBA8_UNORM => vk::Format::R8G8B8A8_UNORM,
+        MTL_PIXEL_FORMAT_RGBA8_UNORM_SRGB => vk::Format::R8G8B8A8_SRGB,
+        MTL_PIXEL_FORMAT_RGBA16_FLOAT => vk::Format::R16G16B16A16_SFLOAT,
+        _ => return None,
+    })
+}
+
+fn vk_failed(object: &'static str, result: vk::Result) -> SyphonError {
+    SyphonError::CreationFailed {
+        object,
+        reason: result.to_string(),
+    }
+}
+
+impl MetalTexture {
+    /// The texture's format as a `vk::Format`; None for formats Vulkan import doesn't handle.
+    pub fn vk_format(&self) -> Option<vk::Format> {
+        vk_format(self.pixel_format())
+    }
+}
+
+/// A `VkImage` over a frame's IOSurface (no copy), with the memory bound to it. Sampled / transfer-source
+/// usage, `OPTIMAL` tiling, created in `UNDEFINED` layout. Destroyed on drop, so drop it before its device.
+pub struct VulkanImage {
+    device: ash::Device,
+    image: vk::Image,
+    memory: vk::DeviceMemory,
+    format: vk::Format,
+    extent: vk::Extent2D,
+}
+
+impl VulkanImage {
+    /// The image handle.
+    pub fn image(&self) -> vk::Image {
+        self.image
+    }
+
+    /// The format the surface was imported as.
+    pub fn format(&self) -> vk::Format {
+        self.format
+    }
+
+    /// Size in pixels.
+    pub fn extent(&self) -> vk::Extent2D {
+        self.extent
+    }
+}
+
+impl Drop for VulkanImage {
+    fn drop(&mut self) {
+        unsafe {
+            self.device.destroy_image(self.image, None);
+            self.device.free_memory(self.memory, None);
+        }
+    }
+}
+
+/// `VK_EXT_metal_objects` entry points and the device state imports need.
+pub struct VulkanInterop {
+    device: ash::Device,
+    metal_objects: ash::ext::metal_objects::Device,
+    memory_properties: vk::PhysicalDeviceMemoryProperties,
+}
+
+impl VulkanInterop {
+    /// Interop for `device` (created from `physical_device` with `VK_EXT_metal_objects` enabled).
+    pub fn new(instance: &ash::Instance, physical_device: vk::PhysicalDevice, device: &ash::Device) -> Self {
+        Self {
+            device: device.clone(),
+            metal_objects: ash::ext::metal_objects::Device::new(instance, device),
+            memory_properties: unsafe { instance.get_physical_device_memory_properties(physical_device) },
+        }
+    }
+
+    /// Import `surface` (`width` x `height`, laid out as `format`) as a `VkImage`.
+    pub fn import_io_surface(
+        &self,
+        surface: IOSurfacePtr,
+        width: u32,
+        height: u32,
+        format: vk::Format,
+    ) -> Result<VulkanImage, SyphonError> {
+        if surface.is_null() {
+            return Err(SyphonError::InvalidArgument("IOSurface is null"));
+        }
+        let mut import = vk::ImportMetalIOSurfaceInfoEXT::default().io_surface(surface as vk::IOSurfaceRef);
+        let info = vk::ImageCreateInfo::default()
+            .push_next(&mut import)
+            .image_type(vk::ImageType::TYPE_2D)
+            .format(format)
+            .extent(vk::Extent3D { width, height, depth: 1 })
+            .mip_levels(1)
+            .array_layers(1)
+            .samples(vk::SampleCountFlags::TYPE_1)
+            .tiling(vk::ImageTiling::OPTIMAL)
+            .usage(vk::ImageUsageFlags::SAMPLED | vk::ImageUsageFlags::TRANSFER_SRC)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(vk::ImageLayout::UNDEFINED);
+        let image = unsafe { self.device.create_image(&info, None) }.map_err(|e| vk_failed("VkImage", e))?;
+
+        // MoltenVK backs the image with the IOSurface; Vulkan still requires memory to be bound.
+        let requirements = unsafe { self.device.get_image_memory_requirements(image) };
+        let memory_types = &self.memory_properties.memory_types[..self.memory_properties.memory_type_count as usize];
+        let allowed = |i: usize| requirements.memory_type_bits & (1 << i) != 0;
+        let memory_type = (0..memory_types.len())
+            .find(|&i| allowed(i) && memory_types[i].property_flags.contains(vk::MemoryPropertyFlags::DEVICE_LOCAL))
+            .or_else(|| (0..memory_types.len()).find(|&i| allowed(i)));
+        let Some(memory_type) = memory_type else {
+            unsafe { self.device.destroy_image(image, None) };
+            return Err(SyphonError::CreationFailed {
+                object: "VkDeviceMemory",
+                reason: "no memory type fits the image".into(),
+            });
+        };
+        let allocate = vk::MemoryAllocateInfo::default()
+            .allocation_size(requirements.size)
+            .memory_type_index(memory_type as u32);
+        let bound = unsafe { self.device.allocate_memory(&allocate, None) }.and_then(|memory| {
+            match unsafe { self.device.bind_image_memory(image, memory, 0) } {
+                Ok(()) => Ok(memory),
+                Err(e) => {
+                    unsafe { self.device.free_memory(memory, None) };
+                    Err(e)
+                }
+            }
+        });
+        match bound {
+            Ok(memory) => Ok(VulkanImage {
+                device: self.device.clone(),
+                image,
+                memory,
+                format,
+                extent: vk::Extent2D { width, height },
+            }),
+            Err(e) => {
+                unsafe { self.device.destroy_image(image, None) };
+                Err(vk_failed("VkDeviceMemory", e))
+            }
+        }
+    }
+
+    /// Import a received frame's IOSurface. OpenGL frames are imported as `B8G8R8A8_UNORM` (Syphon's
+    /// surface format); Metal frames keep their texture's format.
+    pub fn import_frame(&self, frame: &Frame) -> Result<VulkanImage, SyphonError> {
+        let (surface, format) = match frame {
+            Frame::OpenGL(image) => (image.io_surface_ptr(), Some(vk::Format::B8G8R8A8_UNORM)),
+            Frame::Metal(texture) => (texture.io_surface_ptr(), texture.vk_format()),
+        };
+        let surface = surface.ok_or(SyphonError::InvalidArgument("frame has no IOSurface"))?;
+        let format = format.ok_or(SyphonError::InvalidArgument("frame pixel format has no Vulkan equivalent"))?;
+        let (width, height) = frame.size();
+        self.import_io_surface(surface, width as u32, height as u32, format)
+    }
+
+    /// The MTLDevice MoltenVK runs `device` on (unretained).
+    pub fn metal_device(&self) -> Option<MTLDevicePtr> {
+        let mut export = vk::ExportMetalDeviceInfoEXT::default();
+        self.export(&mut export);
+        (!export.mtl_device.is_null()).then_some(export.mtl_device as MTLDevicePtr)
+    }
+
+    /// The MTLCommandQueue behind `queue` (unretained).
+    pub fn metal_command_queue(&self, queue: vk::Queue) -> Option<MTLCommandQueuePtr> {
+        let mut export = vk::ExportMetalCommandQueueInfoEXT::default().queue(queue);
+        self.export(&mut export);
+        (!export.mtl_command_queue.is_null()).then_some(export.mtl_command_queue as MTLCommandQueuePtr)
+    }
+
+    /// The MTLTexture behind `image` (unretained; owned by MoltenVK). The image must have been created with
+    /// `VkExportMetalObjectCreateInfoEXT` requesting `METAL_TEXTURE` export.
+    pub fn metal_texture(&self, image: vk::Image) -> Option<MTLTexturePtr> {
+        let mut export = vk::ExportMetalTextureInfoEXT::default()
+            .image(image)
+            .plane(vk::ImageAspectFlags::PLANE_0);
+        self.export(&mut export);
+        (!export.mtl_texture.is_null()).then_some(export.mtl_texture as MTLTexturePtr)
+    }
+
+    fn export<T: vk::ExtendsExportMetalObjectsInfoEXT>(&self, object: &mut T) {
+        let mut info = vk::ExportMetalObjectsInfoEXT::default().push_next(object);
+        unsafe { (self.metal_objects.fp().export_metal_objects_ext)(self.device.handle(), &mut info) };
+    }
+
+    /// A server on MoltenVK's MTLDevice that publishes on `queue`'s MTLCommandQueue, so
+    /// `MetalServer::publish_vulkan_image` after `vkQueueSubmit` is ordered after the rendering it publishes.
+    pub fn new_server(
+        &self,
+        name: Option<&str>,
+        queue: vk::Queue,
+        options: Option<&SyphonOptions>,
+    ) -> Result<MetalServer, SyphonError> {
+        let device = self.metal_device().ok_or(SyphonError::CreationFailed {
+            object: "Metal server",
+            reason: "VK_EXT_metal_objects exported no MTLDevice".into(),
+        })?;
+        MetalServer::try_new_with_command_queue(name, device, options, self.metal_command_queue(queue))
+    }
+}
+
+impl MetalServer {
+    /// Publish `region` of a Vulkan image through its MTLTexture (see `VulkanInterop::metal_texture`), on the
+    /// server's command queue. Call after the `vkQueueSubmit` that renders it, with the image in a layout
+    /// readable by transfers or shaders. False if the image has no exportable MTLTexture.
+    pub fn publish_vulkan_image(&self, vulkan: &VulkanInterop, image: vk::Image, region: Rect, flipped: bool) -> bool {
+        match vulkan.metal_texture(image) {
+            Some(texture) => self.publish_frame_simple(texture, region, flipped),
+            None => false,
+        }
+    }
+}
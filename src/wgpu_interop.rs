@@ -0,0 +1,166 @@
+//! wgpu integration (`wgpu` feature): received frames become `wgpu::Texture`s through `wgpu-hal`'s Metal
+//! backend, and wgpu textures are published by unwrapping their MTLTextures, so wgpu apps can receive and send
+//! Syphon feeds without hal code of their own. The wgpu device must use the Metal backend; on any other backend
+//! the helpers return None or false.
+
+use wgpu::hal::api::Metal;
+use wgpu_metal::foreign_types::ForeignTypeRef;
+use wgpu_metal::{MTLTextureType, TextureRef};
+
+use crate::{
+    IOSurfacePtr, MTLCommandQueuePtr, MTLDevicePtr, MetalClient, MetalServer, MetalTexture, NewFrameCallback, Rect,
+    ServerDescription, SyphonError, SyphonOptions, MTL_PIXEL_FORMAT_BGRA8_UNORM, MTL_PIXEL_FORMAT_BGRA8_UNORM_SRGB, MTL_PIXEL_FORMAT_RGBA16_FLOAT,
+    MTL_PIXEL_FORMAT_RGBA8_UNORM, MTL_PIXEL_FORMAT_RGBA8_UNORM_SRGB,
+};
+
+/// The MTLDevice behind a Metal-backend wgpu device (unretained; valid while `device` lives).
+fn wgpu_mtl_device(device: &wgpu::Device) -> Option<MTLDevicePtr> {
+    let hal_device = unsafe { device.as_hal::<Metal>() }?;
+    let raw = hal_device.raw_device().lock();
+    Some(raw.as_ptr() as MTLDevicePtr)
+}
+
+/// The MTLCommandQueue behind a Metal-backend wgpu queue (unretained; valid while `queue` lives).
+fn wgpu_mtl_queue(queue: &wgpu::Queue) -> Option<MTLCommandQueuePtr> {
+    let hal_queue = unsafe { queue.as_hal::<Metal>() }?;
+    let raw = hal_queue.as_raw().lock();
+    Some(raw.as_ptr() as MTLCommandQueuePtr)
+}
+
+/// wgpu format of a raw `MTLPixelFormat`, for the formats Syphon frames come in.
+fn wgpu_format(pixel_format: u64) -> Option<wgpu::TextureFormat> {
+    Some(match pixel_format {
+        MTL_PIXEL_FORMAT_BGRA8_UNORM => wgpu::TextureFormat::Bgra8Unorm,
+        MTL_PIXEL_FORMAT_BGRA8_UNORM_SRGB => wgpu::TextureFormat::Bgra8UnormSrgb,
+        MTL_PIXEL_FORMAT_RGBA8_UNORM => wgpu::TextureFormat::Rgba8Unorm,
+        MTL_PIXEL_FORMAT_RGBA8_UNORM_SRGB => wgpu::TextureFormat::Rgba8UnormSrgb,
+        MTL_PIXEL_FORMAT_RGBA16_FLOAT => wgpu::TextureFormat::Rgba16Float,
+        _ => return None,
+    })
+}
+
+impl MetalTexture {
+    /// The texture's format as a `wgpu::TextureFormat`; None for formats wgpu import doesn't handle.
+    pub fn wgpu_format(&self) -> Option<wgpu::TextureFormat> {
+        wgpu_format(self.pixel_format())
+    }
+
+    /// Import the texture into `device` as a 2D `wgpu::Texture` usable as `TEXTURE_BINDING` and `COPY_SRC`
+    /// (no copy; the wgpu texture holds its own reference). `device` must be on the same MTLDevice the
+    /// texture was created on (see `MetalClient::with_wgpu_device`).
+    pub fn to_wgpu_texture(&self, device: &wgpu::Device) -> Option<wgpu::Texture> {
+        let format = self.wgpu_format()?;
+        let (width, height) = self.size();
+        let raw = unsafe { TextureRef::from_ptr(self.as_ptr() as *mut _) }.to_owned();
+        if wgpu_mtl_device(device)? != raw.device().as_ptr() as MTLDevicePtr {
+            return None;
+        }
+        let (width, height) = (width as u32, height as u32);
+        let hal_texture = unsafe {
+            wgpu::hal::metal::Device::texture_from_raw(
+                raw,
+                format,
+                MTLTextureType::D2,
+                1,
+                1,
+                wgpu::hal::CopyExtent { width, height, depth: 1 },
+            )
+        };
+        let desc = wgpu::TextureDescriptor {
+            label: Some("Syphon frame"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        };
+        Some(unsafe { device.create_texture_from_hal::<Metal>(hal_texture, &desc) })
+    }
+}
+
+impl MetalServer {
+    /// `new_with_command_queue` on a wgpu device and queue: the server publishes on wgpu's own MTLCommandQueue,
+    /// so `publish_wgpu_texture` after `queue.submit` is ordered after the rendering it publishes.
+    pub fn with_wgpu_device(
+        name: Option<&str>,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        options: Option<&SyphonOptions>,
+    ) -> Option<Self> {
+        Self::try_with_wgpu_device(name, device, queue, options).ok()
+    }
+
+    /// Like `with_wgpu_device`, reporting why creation failed.
+    pub fn try_with_wgpu_device(
+        name: Option<&str>,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        options: Option<&SyphonOptions>,
+    ) -> Result<Self, SyphonError> {
+        let not_metal = SyphonError::InvalidArgument("wgpu device is not on the Metal backend");
+        let raw_device = wgpu_mtl_device(device).ok_or(not_metal.clone())?;
+        let raw_queue = wgpu_mtl_queue(queue).ok_or(not_metal)?;
+        Self::try_new_with_command_queue(name, raw_device, options, Some(raw_queue))
+    }
+
+    /// Publish `region` of a wgpu texture through its underlying MTLTexture, on the server's command queue
+    /// (`publish_frame_simple`). The texture needs `TEXTURE_BINDING` or `COPY_SRC` usage; call after the
+    /// `queue.submit` that renders it. False if `device` isn't the server's device or isn't Metal-backed.
+    pub fn publish_wgpu_texture(
+        &self,
+        device: &wgpu::Device,
+        texture: &wgpu::Texture,
+        region: Rect,
+        flipped: bool,
+    ) -> bool {
+        if wgpu_mtl_device(device) != Some(self.device()) {
+            return false;
+        }
+        let Some(hal_texture) = (unsafe { texture.as_hal::<Metal>() }) else {
+            return false;
+        };
+        let raw = unsafe { hal_texture.raw_handle() };
+        self.publish_frame_simple(raw.as_ptr() as _, region, flipped)
+    }
+}
+
+impl MetalClient {
+    /// `new` on a wgpu device's MTLDevice, so frames can be imported with `to_wgpu_texture`. None (or
+    /// `InvalidArgument` from `try_with_wgpu_device`) if `device` isn't a Metal-backend device.
+    pub fn with_wgpu_device(
+        description: &ServerDescription,
+        device: &wgpu::Device,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+    ) -> Option<Self> {
+        Self::try_with_wgpu_device(description, device, options, callback).ok()
+    }
+
+    /// Like `with_wgpu_device`, reporting why creation failed.
+    pub fn try_with_wgpu_device(
+        description: &ServerDescription,
+        device: &wgpu::Device,
+        options: Option<&SyphonOptions>,
+        callback: Option<NewFrameCallback>,
+    ) -> Result<Self, SyphonError> {
+        let raw = wgpu_mtl_device(device).ok_or(SyphonError::InvalidArgument("wgpu device is not on the Metal backend"))?;
+        Self::try_new(description, raw, options, callback)
+    }
+
+    /// `new_frame_image` imported into `device` (see `MetalTexture::to_wgpu_texture`).
+    pub fn new_frame_wgpu_texture(&self, device: &wgpu::Device) -> Option<wgpu::Texture> {
+        self.new_frame_image()?.to_wgpu_texture(device)
+    }
+}
+
+/// Import an IOSurface (e.g. from `OpenGLImage::io_surface_ptr`) into `device` as a `wgpu::Texture`, with
+/// raw `MTLPixelFormat` `pixel_format` describing the surface's layout.
+pub fn wgpu_texture_from_io_surface(
+    device: &wgpu::Device,
+    surface: IOSurfacePtr,
+    pixel_format: u64,
+) -> Option<wgpu::Texture> {
+    MetalTexture::from_io_surface(wgpu_mtl_device(device)?, surface, pixel_format)?.to_wgpu_texture(device)
+}
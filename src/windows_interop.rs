@@ -0,0 +1,109 @@
+//! Typed `windows` crate integration (`windows` feature): `SpoutDxServer` / `SpoutDxClient` take
+//! `ID3D11Device` and `ID3D11Texture2D`, `SpoutDx12Server` / `SpoutDx12Client` take `ID3D12Device`,
+//! `ID3D12CommandQueue` and `ID3D12Resource`, and frames come back as owned (AddRef'd) interfaces, so no
+//! raw pointers or `Interface::from_raw` are needed.
+
+use windows::core::Interface;
+use windows::Win32::Graphics::Direct3D11::{ID3D11Device, ID3D11Texture2D};
+use windows::Win32::Graphics::Direct3D12::{ID3D12CommandQueue, ID3D12Device, ID3D12Resource, D3D12_RESOURCE_STATES};
+
+use crate::{SpoutDx12Client, SpoutDx12Server, SpoutDxClient, SpoutDxServer, SyphonError};
+
+/// An owned reference to the interface behind a borrowed pointer, or None if it is null.
+fn cloned_interface<T: Interface + Clone>(ptr: *mut std::ffi::c_void) -> Option<T> {
+    unsafe { T::from_raw_borrowed(&ptr) }.cloned()
+}
+
+impl SpoutDxServer {
+    /// `new` with a `windows` device.
+    pub fn with_d3d11_device(name: Option<&str>, device: &ID3D11Device) -> Option<Self> {
+        Self::new(name, device.as_raw())
+    }
+
+    /// Like `with_d3d11_device`, reporting why creation failed.
+    pub fn try_with_d3d11_device(name: Option<&str>, device: &ID3D11Device) -> Result<Self, SyphonError> {
+        Self::try_new(name, device.as_raw())
+    }
+
+    /// The sender's device as an `ID3D11Device`.
+    pub fn d3d11_device(&self) -> Option<ID3D11Device> {
+        cloned_interface(self.device())
+    }
+
+    /// `publish_texture` with a `windows` texture.
+    pub fn publish_d3d11_texture(&self, texture: &ID3D11Texture2D) -> bool {
+        self.publish_texture(texture.as_raw())
+    }
+}
+
+impl SpoutDxClient {
+    /// `new` with a `windows` device.
+    pub fn with_d3d11_device(sender: Option<&str>, device: &ID3D11Device) -> Option<Self> {
+        Self::new(sender, device.as_raw())
+    }
+
+    /// Like `with_d3d11_device`, reporting why creation failed.
+    pub fn try_with_d3d11_device(sender: Option<&str>, device: &ID3D11Device) -> Result<Self, SyphonError> {
+        Self::try_new(sender, device.as_raw())
+    }
+
+    /// `new_frame_texture` as an owned `ID3D11Texture2D`. It still follows the sender: after `is_updated`,
+    /// fetch the replacement rather than keep using this one.
+    pub fn new_frame_d3d11_texture(&self) -> Option<ID3D11Texture2D> {
+        self.new_frame_texture().and_then(cloned_interface)
+    }
+
+    /// `copy_frame_to` with a `windows` texture.
+    pub fn copy_frame_to_d3d11_texture(&self, texture: &ID3D11Texture2D) -> bool {
+        self.copy_frame_to(texture.as_raw())
+    }
+}
+
+impl SpoutDx12Server {
+    /// `new` with `windows` types; the device is `queue`'s.
+    pub fn with_d3d12_queue(name: Option<&str>, queue: &ID3D12CommandQueue) -> Option<Self> {
+        Self::try_with_d3d12_queue(name, queue).ok()
+    }
+
+    /// Like `with_d3d12_queue`, reporting why creation failed.
+    pub fn try_with_d3d12_queue(name: Option<&str>, queue: &ID3D12CommandQueue) -> Result<Self, SyphonError> {
+        let device = queue_device(queue)?;
+        Self::try_new(name, device.as_raw(), queue.as_raw())
+    }
+
+    /// The sender's device as an `ID3D12Device`.
+    pub fn d3d12_device(&self) -> Option<ID3D12Device> {
+        cloned_interface(self.device())
+    }
+
+    /// `publish_resource` with `windows` types.
+    pub fn publish_d3d12_resource(&self, resource: &ID3D12Resource, state: D3D12_RESOURCE_STATES) -> bool {
+        self.publish_resource(resource.as_raw(), state.0 as u32)
+    }
+}
+
+impl SpoutDx12Client {
+    /// `new` with `windows` types; the device is `queue`'s.
+    pub fn with_d3d12_queue(sender: Option<&str>, queue: &ID3D12CommandQueue) -> Option<Self> {
+        Self::try_with_d3d12_queue(sender, queue).ok()
+    }
+
+    /// Like `with_d3d12_queue`, reporting why creation failed.
+    pub fn try_with_d3d12_queue(sender: Option<&str>, queue: &ID3D12CommandQueue) -> Result<Self, SyphonError> {
+        let device = queue_device(queue)?;
+        Self::try_new(sender, device.as_raw(), queue.as_raw())
+    }
+
+    /// `new_frame_resource` as an owned `ID3D12Resource`, replaced like `new_frame_d3d11_texture`.
+    pub fn new_frame_d3d12_resource(&self) -> Option<ID3D12Resource> {
+        self.new_frame_resource().and_then(cloned_interface)
+    }
+}
+
+fn queue_device(queue: &ID3D12CommandQueue) -> Result<ID3D12Device, SyphonError> {
+    let mut device: Option<ID3D12Device> = None;
+    unsafe { queue.GetDevice(&mut device) }
+        .ok()
+        .and(device)
+        .ok_or(SyphonError::InvalidArgument("D3D12 command queue without a device"))
+}
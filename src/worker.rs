@@ -0,0 +1,72 @@
+//! Background threads for the bridges (NDI, network relay) and `TestPatternServer`: each owns its Syphon
+//! client or server, which must stay on the thread that created it, and reports whether it started before
+//! the constructor returns.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::SyphonError;
+
+/// How long worker threads block in one wait, which bounds how long stopping one takes.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The time between frames at `fps`; None unless `fps` is finite, positive and large enough for the
+/// interval to fit a `Duration`.
+pub(crate) fn frame_interval(fps: f64) -> Option<Duration> {
+    if !(fps.is_finite() && fps > 0.0) {
+        return None;
+    }
+    Duration::try_from_secs_f64(1.0 / fps).ok()
+}
+
+/// A worker thread: told to stop and joined on drop.
+pub(crate) struct Worker {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Worker {
+    /// Run `body` on a thread named `name`, returning once it reports through `ready` that it started (or
+    /// why it couldn't). `body` should return soon after the flag it is given is set.
+    pub(crate) fn spawn<T: Send + 'static>(
+        name: &str,
+        body: impl FnOnce(&mpsc::SyncSender<Result<T, SyphonError>>, &AtomicBool) + Send + 'static,
+    ) -> Result<(Self, T), SyphonError> {
+        let failed = |reason: String| SyphonError::CreationFailed {
+            object: "worker thread",
+            reason,
+        };
+        let stop = Arc::new(AtomicBool::new(false));
+        let (ready_tx, ready_rx) = mpsc::sync_channel(1);
+        let thread_stop = stop.clone();
+        let thread = std::thread::Builder::new()
+            .name(name.into())
+            .spawn(move || body(&ready_tx, &thread_stop))
+            .map_err(|e| failed(e.to_string()))?;
+        let worker = Self {
+            stop,
+            thread: Some(thread),
+        };
+        match ready_rx.recv() {
+            Ok(Ok(value)) => Ok((worker, value)),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(failed("exited before starting".into())),
+        }
+    }
+
+    /// False once the thread has returned.
+    pub(crate) fn is_running(&self) -> bool {
+        self.thread.as_ref().is_some_and(|t| !t.is_finished())
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
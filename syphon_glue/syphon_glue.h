@@ -7,6 +7,7 @@
 
 #include <stddef.h>
 #include <stdbool.h>
+#include <stdint.h>
 
 #ifdef __APPLE__
 #include <OpenGL/OpenGL.h>
@@ -16,9 +17,18 @@
 extern "C" {
 #endif
 
+/* Optional subsystems are only built when build.rs defines their SYPHON_GLUE_* macro, one per Cargo feature
+ * (core-image, screen-capture, camera, recorder, encoder, pasteboard, hotkey), so their frameworks are only
+ * linked when enabled. */
+
+/* Why the last create call (directory, servers, clients, headless context) on this thread returned NULL.
+ * Returns NULL if it succeeded; the caller frees the string. Reading clears it. */
+char *syphon_copy_last_error(void);
+
 /* Server directory */
 void *syphon_server_directory_shared(void);
 size_t syphon_server_directory_servers_count(void *dir);
+/* Retained; release with syphon_server_description_release. */
 void *syphon_server_directory_server_at_index(void *dir, size_t index);
 /* Query servers by name/app name; result must be released with syphon_server_directory_match_release. */
 void *syphon_server_directory_servers_matching(void *dir, const char *name, const char *app_name);
@@ -29,23 +39,55 @@ void syphon_server_directory_match_release(void *match_result);
 char *syphon_notification_name_server_announce(void);
 char *syphon_notification_name_server_update(void);
 char *syphon_notification_name_server_retire(void);
+/* Directory events: callback(userdata, event, description) for each announce (0), retire (1) and update (2)
+ * notification, on the thread that posts it (the main thread; its run loop must be running). description
+ * is borrowed for the call (retain to keep it). unsubscribe stops callbacks (waiting for a running one),
+ * calls release_userdata once and frees the handle. */
+void *syphon_directory_subscribe(void (*callback)(void *userdata, int event, void *description),
+    void (*release_userdata)(void *userdata), void *userdata);
+void syphon_directory_unsubscribe(void *subscription);
+/* Run the calling thread's run loop (default mode) for up to seconds; 0 handles what is pending and returns. */
+void syphon_run_loop_pump(double seconds);
+/* Wait up to seconds: on the main thread by running its run loop (so directory and other notifications
+ * are delivered), elsewhere by sleeping. */
+void syphon_run_loop_wait(double seconds);
+/* Start (once per process) a thread that creates the shared directory and runs its run loop, so directory
+ * updates and notifications arrive without a main run loop. true once it is running. */
+bool syphon_background_updates_start(void);
 
 /* Server description (NSDictionary*); do not release unless you retained */
 char *syphon_server_description_copy_uuid(void *desc);
 char *syphon_server_description_copy_name(void *desc);
 char *syphon_server_description_copy_app_name(void *desc);
+/* Hosting app resolved via NSRunningApplication (by app name; NULL if not running or ambiguous).
+ * Bundle identifier: caller frees. Icon: size*size premultiplied RGBA8, top row first; caller frees. */
+char *syphon_server_description_copy_bundle_identifier(void *desc);
+unsigned char *syphon_server_description_copy_app_icon_rgba8(void *desc, size_t size);
+/* Every entry as alternating NUL-terminated key and value strings (values stringified: numbers decimal,
+ * data base64, arrays/dictionaries JSON); *out_len is the total byte length. free() the result. */
+char *syphon_server_description_copy_entries(void *desc, size_t *out_len);
+/* Description dictionary version, or -1 if absent. */
+int64_t syphon_server_description_dictionary_version(void *desc);
+/* Surface types the server offers (e.g. SyphonSurfaceTypeIOSurface), newline-separated; NULL if absent. */
+char *syphon_server_description_copy_surface_types(void *desc);
 void syphon_server_description_retain(void *desc);
 void syphon_server_description_release(void *desc);
+/* Serialize a description as a binary plist (malloc'd, *out_len bytes; free with free()), and rebuild a
+ * retained description from one (NULL unless it is a dictionary with a server UUID). */
+unsigned char *syphon_server_description_copy_plist(void *desc, size_t *out_len);
+void *syphon_server_description_create_from_plist(const unsigned char *bytes, size_t len);
 
-/* Server options (for server create); keys below. Caller releases with syphon_options_release. */
+/* Options dictionary (for server and client create); server keys below. Caller releases with syphon_options_release. */
 void *syphon_options_create(void);
 void syphon_options_set_bool(void *opts, const char *key, bool value);
 void syphon_options_set_unsigned_long(void *opts, const char *key, unsigned long value);
+void syphon_options_set_string(void *opts, const char *key, const char *value);
 void syphon_options_release(void *opts);
 char *syphon_server_option_key_is_private(void);
 char *syphon_server_option_key_antialias_sample_count(void);
 char *syphon_server_option_key_depth_buffer_resolution(void);
 char *syphon_server_option_key_stencil_buffer_resolution(void);
+char *syphon_server_option_key_pixel_format(void);
 
 /* OpenGL server */
 void *syphon_opengl_server_create(const char *name, CGLContextObj context, void *options);
@@ -56,19 +98,43 @@ void syphon_opengl_server_publish_frame(void *server, GLuint tex_id, GLenum targ
     double x, double y, double w, double h, double tex_w, double tex_h, bool flipped);
 bool syphon_opengl_server_bind_to_draw_frame(void *server, double w, double h);
 void syphon_opengl_server_unbind_and_publish(void *server);
+/* Publish a tex_w x tex_h texture of which only (x, y, w, h) changed since the server's last frame through
+ * this function: if that frame had the same size only the region is drawn into the server's surface,
+ * otherwise the whole texture is published. Needs the server's context current. */
+bool syphon_opengl_server_publish_dirty_region(void *server, GLuint tex_id, GLenum target, double tex_w, double tex_h,
+    double x, double y, double w, double h, bool flipped);
 void syphon_opengl_server_stop(void *server);
 CGLContextObj syphon_opengl_server_context(void *server);
 char *syphon_opengl_server_copy_name(void *server);
 void syphon_opengl_server_set_name(void *server, const char *name);
 void *syphon_opengl_server_new_frame_image(void *server);
 
-/* OpenGL client. new_frame_callback may be NULL. */
+/* OpenGL client. new_frame_callback may be NULL. release_userdata (may be NULL) is called exactly once
+ * when the handler is unregistered or the client is freed, never while new_frame_callback is running.
+ * direct_dispatch invokes the callback on Syphon's delivery thread with a lighter non-recursive lock and
+ * no autorelease pool; if the callback unregisters, stops or releases its own client, release_userdata
+ * runs once it returns.
+ * The callback receives the frame's delivery number (from 1) and size. The size is 0 x 0 unless
+ * report_size is set (ignored with direct_dispatch): that fetches the new frame on the delivery thread
+ * before each call, using the client's context there and consuming has_new_frame. */
 void *syphon_opengl_client_create(void *server_description, CGLContextObj context,
-    void *options, void (*new_frame_callback)(void *userdata), void *userdata);
+    void *options, void (*new_frame_callback)(void *userdata, uint64_t frame_index, size_t width, size_t height),
+    void (*release_userdata)(void *userdata),
+    void *userdata, bool direct_dispatch, bool report_size);
+/* Replace the new-frame callback (NULL clears it), with the same release and dispatch rules as create.
+ * The previous callback is released, and not running, once this returns. */
+void syphon_opengl_client_set_new_frame_handler(void *client,
+    void (*new_frame_callback)(void *userdata, uint64_t frame_index, size_t width, size_t height),
+    void (*release_userdata)(void *userdata), void *userdata, bool direct_dispatch, bool report_size);
+/* Stop delivering new-frame callbacks; blocks until an in-flight callback on another thread returns. */
+void syphon_opengl_client_unregister_new_frame_handler(void *client);
 void syphon_opengl_client_release(void *client);
 bool syphon_opengl_client_is_valid(void *client);
 bool syphon_opengl_client_has_new_frame(void *client);
 void *syphon_opengl_client_new_frame_image(void *client);
+/* Copy the current frame into dst_tex (at origin) on the client's context, which must be current.
+ * dst must be at least the frame size with an RGBA color format; false on mismatch or no frame. */
+bool syphon_opengl_client_copy_frame_to(void *client, GLuint dst_tex, GLenum dst_target);
 void syphon_opengl_client_stop(void *client);
 CGLContextObj syphon_opengl_client_context(void *client);
 void *syphon_opengl_client_server_description(void *client);
@@ -77,43 +143,332 @@ void *syphon_opengl_client_server_description(void *client);
 void syphon_opengl_image_release(void *image);
 GLuint syphon_opengl_image_texture_name(void *image);
 void syphon_opengl_image_texture_size(void *image, double *out_w, double *out_h);
+/* Copy the image into a GL_TEXTURE_2D the caller owns; context must be current. dst_tex 0 creates a new
+ * RGBA8 texture of the image size, otherwise dst_tex must be RGBA and at least that size. Returns the
+ * texture name, or 0 on failure. */
+GLuint syphon_opengl_image_copy_to_texture_2d(void *image, GLuint dst_tex);
+/* Blit the whole frame into (0, 0, width, height) of dst_tex (rectangle or 2D, framebuffer-attachable),
+ * scaling with linear filtering if the sizes differ. Needs the image's context current. */
+bool syphon_opengl_image_blit_to_texture(void *image, GLuint dst_tex, GLenum dst_target, size_t width, size_t height);
+/* The IOSurfaceRef backing the image (not retained; valid while the image lives), or NULL if the image
+ * doesn't expose one. */
+void *syphon_opengl_image_io_surface(void *image);
 
 /* Metal server (device/texture/command_buffer are MTLDevice*, MTLTexture*, MTLCommandBuffer*) */
 void *syphon_metal_server_create(const char *name, void *device, void *options);
+/* create on MTLCreateSystemDefaultDevice() (NULL with an error if the system has no Metal device). */
+void *syphon_metal_server_create_with_default_device(const char *name, void *options);
 void syphon_metal_server_release(void *server);
 bool syphon_metal_server_has_clients(void *server);
 void *syphon_metal_server_server_description(void *server);
 void syphon_metal_server_publish_frame(void *server, void *texture, void *command_buffer,
     double x, double y, double w, double h, bool flipped);
+/* Publish a CAMetalDrawable's whole texture (call before presenting). False if the drawable is
+ * framebuffer-only (not blittable) or not a CAMetalDrawable. */
+bool syphon_metal_server_publish_drawable(void *server, void *drawable, void *command_buffer, bool flipped);
+/* Upload w x h RGBA8 pixels (top row first) into region (x, y) of a frame_w x frame_h frame the glue keeps
+ * for the server, then publish that frame on command_buffer. The first call, and the first after a size
+ * change, must cover the whole frame. */
+bool syphon_metal_server_publish_rgba8_region(void *server, void *command_buffer, size_t frame_w, size_t frame_h,
+    size_t x, size_t y, size_t w, size_t h, const unsigned char *rgba);
+/* Upload a whole width x height RGBA8 frame into a staging texture the glue keeps for the server and publish
+ * it on a command buffer from the server's own queue, which is committed here (no Metal objects needed from
+ * the caller). flipped as for publish_frame. */
+bool syphon_metal_server_publish_pixels(void *server, const unsigned char *rgba, size_t width, size_t height,
+    bool flipped);
+#if SYPHON_GLUE_CORE_IMAGE
+/* Render a CIImage (its whole extent, which must be finite) through a CIContext the glue keeps for the server
+ * into a BGRA8 staging texture and publish it on the server's queue, committed here. */
+bool syphon_metal_server_publish_ci_image(void *server, void *image);
+#endif
+#if SYPHON_GLUE_SCREEN_CAPTURE
+/* Capture a display (window_id 0) or a window (by CGWindowID) through ScreenCaptureKit (macOS 12.3+) and publish
+ * each new frame on the server's queue. width / height 0 use the source's pixel size; frame_rate <= 0 keeps
+ * ScreenCaptureKit's default (60). NULL with an error if the source is gone, screen-recording permission is
+ * missing or capture fails to start. release stops capturing (waiting for the stream to stop). */
+void *syphon_screen_capture_create(void *server, uint32_t display_id, uint32_t window_id, size_t width,
+    size_t height, double frame_rate, bool show_cursor);
+void syphon_screen_capture_release(void *capture);
+#endif
+#if SYPHON_GLUE_CAMERA
+/* Capture video from an AVCaptureDevice (unique_id; NULL for the default camera) and publish each frame on the
+ * server's queue, zero-copy from the capture buffers' IOSurfaces (copied through a staging texture for buffers
+ * without one). width x height picks the device format of that size (0 keeps the active format); frame_rate > 0
+ * sets the frame duration if the format supports it. Asks for camera access if undetermined (blocking). NULL
+ * with an error if the device is missing, access is denied or the session can't be configured. release stops
+ * the session. */
+void *syphon_camera_capture_create(void *server, const char *unique_id, size_t width, size_t height,
+    double frame_rate);
+void syphon_camera_capture_release(void *capture);
+#endif
+/* The command queue the glue keeps for the server (used by publish_pixels and publish_frame_simple). set_command_queue
+ * makes it command_queue (which must belong to the server's device; false otherwise), or a new queue on the server's
+ * device if NULL. command_queue returns the queue (unretained; created on first use), NULL if creation failed. */
+bool syphon_metal_server_set_command_queue(void *server, void *command_queue);
+void *syphon_metal_server_command_queue(void *server);
+/* publish_frame on a command buffer from the server's queue, committed here. False if there is no queue. */
+bool syphon_metal_server_publish_frame_simple(void *server, void *texture, double x, double y, double w, double h,
+    bool flipped);
+/* publish_frame after encoding a wait for shared_event (an MTLSharedEvent) to reach value, so a texture rendered on
+ * another queue is complete before Syphon copies it. command_buffer NULL publishes on the server's queue and commits
+ * (as publish_frame_simple); otherwise the caller commits. False if an object is NULL or there is no queue. */
+bool syphon_metal_server_publish_frame_after_event(void *server, void *texture, void *command_buffer,
+    void *shared_event, uint64_t value, double x, double y, double w, double h, bool flipped);
 void *syphon_metal_server_new_frame_image(void *server);
 void syphon_metal_server_stop(void *server);
 void *syphon_metal_server_device(void *server);
+/* Raw MTLPixelFormat of the server's shared surface (what clients receive); 0 before the first frame. */
+unsigned long syphon_metal_server_pixel_format(void *server);
 char *syphon_metal_server_copy_name(void *server);
 void syphon_metal_server_set_name(void *server, const char *name);
 
-/* Metal client. new_frame_callback may be NULL. */
+/* Metal client. new_frame_callback may be NULL; release_userdata, direct_dispatch and report_size as for the
+ * OpenGL client. */
 void *syphon_metal_client_create(void *server_description, void *device,
-    void *options, void (*new_frame_callback)(void *userdata), void *userdata);
+    void *options, void (*new_frame_callback)(void *userdata, uint64_t frame_index, size_t width, size_t height),
+    void (*release_userdata)(void *userdata),
+    void *userdata, bool direct_dispatch, bool report_size);
+void syphon_metal_client_set_new_frame_handler(void *client,
+    void (*new_frame_callback)(void *userdata, uint64_t frame_index, size_t width, size_t height),
+    void (*release_userdata)(void *userdata), void *userdata, bool direct_dispatch, bool report_size);
+void syphon_metal_client_unregister_new_frame_handler(void *client);
 void syphon_metal_client_release(void *client);
 bool syphon_metal_client_is_valid(void *client);
 bool syphon_metal_client_has_new_frame(void *client);
 void *syphon_metal_client_new_frame_image(void *client);
+/* Encode a blit of the current frame into dst_texture (at origin) on command_buffer. dst must share the
+ * frame's pixel format, be single-sampled, not framebuffer-only and at least the frame size. */
+bool syphon_metal_client_copy_frame_to(void *client, void *dst_texture, void *command_buffer);
 void syphon_metal_client_stop(void *client);
 void *syphon_metal_client_server_description(void *client);
 
 /* Metal texture (caller must release with syphon_metal_texture_release) */
 void syphon_metal_texture_release(void *texture);
+/* Take another reference (balance with syphon_metal_texture_release). */
+void syphon_metal_texture_retain(void *texture);
+/* MTLTexture properties: raw MTLPixelFormat, size in pixels, MTLTextureUsage flags, MTLStorageMode. */
+unsigned long syphon_metal_texture_pixel_format(void *texture);
+void syphon_metal_texture_size(void *texture, size_t *out_w, size_t *out_h);
+unsigned long syphon_metal_texture_usage(void *texture);
+unsigned long syphon_metal_texture_storage_mode(void *texture);
 
 /* CGL headless context for tests (caller must destroy with syphon_cgl_destroy_context) */
 CGLContextObj syphon_cgl_create_headless_context(void);
+/* Same with a chosen profile (3.2 core or legacy), 16-bit float color, and multisampling (0: none). */
+CGLContextObj syphon_cgl_create_headless_context_with(bool core_profile, bool color_float, uint32_t samples);
 void syphon_cgl_destroy_context(CGLContextObj ctx);
 void syphon_cgl_make_current(CGLContextObj ctx);
+CGLContextObj syphon_cgl_get_current_context(void);
+/* True if ctx was created with a 3.2+ core profile pixel format (false for legacy contexts or NULL). */
+bool syphon_cgl_context_is_core_profile(CGLContextObj ctx);
+/* The CGL context behind an NSOpenGLContext (unretained; NULL for NULL). */
+CGLContextObj syphon_cgl_context_from_nsopengl_context(void *context);
+
+/* Attach a temporary client to read the server's current frame size and MTLPixelFormat raw value,
+ * waiting up to timeout_seconds for a frame. False if no frame arrived in time. */
+bool syphon_probe_server_frame(void *server_description, double timeout_seconds,
+    size_t *out_width, size_t *out_height, unsigned long *out_pixel_format);
 
 /* OpenGL texture helpers; CGL context must be current. GL_TEXTURE_RECTANGLE, RGBA8. */
 GLuint syphon_gl_create_texture_rectangle_rgba8(size_t width, size_t height, const unsigned char *rgba);
 void syphon_gl_read_texture_rectangle_rgba8(GLuint tex_id, size_t width, size_t height, unsigned char *out_rgba);
+/* The same for GL_TEXTURE_2D (any size; linear filtering, no mipmaps, clamped to edge). */
+GLuint syphon_gl_create_texture_2d_rgba8(size_t width, size_t height, const unsigned char *rgba);
+void syphon_gl_read_texture_2d_rgba8(GLuint tex_id, size_t width, size_t height, unsigned char *out_rgba);
+/* Any target and format: 0 RGBA8, 1 BGRA8, 2 RGBA16F (half floats, 8 bytes per pixel). 0 / false for an
+ * unknown format. */
+GLuint syphon_gl_create_texture(GLenum target, uint32_t format, size_t width, size_t height, const void *pixels);
+bool syphon_gl_read_texture(GLenum target, GLuint tex_id, uint32_t format, size_t width, size_t height, void *out);
 void syphon_gl_delete_texture(GLuint tex_id);
 
+/* Asynchronous RGBA8 readback through pixel-pack buffers. A pool (no GL needed to create) recycles
+ * buffers; begin copies a texture into one and fences it; finish waits for the fence and copies the
+ * width*height*4 bytes (row y=0 first) out; release returns the buffer to the pool. All but pool
+ * create need the pool's GL context current; release readbacks before their pool. */
+void *syphon_gl_readback_pool_create(void);
+void syphon_gl_readback_pool_release(void *pool);
+void *syphon_gl_readback_begin(void *pool, GLuint tex_id, GLenum target, size_t width, size_t height);
+bool syphon_gl_readback_is_ready(void *readback);
+bool syphon_gl_readback_finish(void *readback, unsigned char *out, size_t len);
+void syphon_gl_readback_release(void *readback);
+/* Upload RGBA8 pixels into a sub-region of an existing texture (rectangle or 2D). */
+bool syphon_gl_write_texture_region_rgba8(GLuint tex_id, GLenum target, size_t x, size_t y,
+    size_t width, size_t height, const unsigned char *rgba);
+
+/* System default MTLDevice and a new command queue on a device, both +1 (NULL if unavailable); release either
+ * with syphon_metal_object_release. */
+void *syphon_metal_device_create_default(void);
+void *syphon_metal_device_new_command_queue(void *device);
+void syphon_metal_object_release(void *object);
+
+/* Metal texture helpers. Encodes a blit of RGBA8 pixels into a region of a BGRA8/RGBA8 texture on command_buffer. */
+bool syphon_metal_texture_write_region_rgba8(void *texture, void *command_buffer, size_t x, size_t y,
+    size_t width, size_t height, const unsigned char *rgba);
+/* Copy a texture into a new texture and wait for completion. command_queue may be NULL (a queue is created
+ * on the texture's device). storage_mode / usage are raw MTLStorageMode / MTLTextureUsage values.
+ * Release the result with syphon_metal_texture_release. */
+void *syphon_metal_texture_copy_to_owned(void *texture, void *command_queue, unsigned long storage_mode,
+    unsigned long usage);
+/* CPU readback. bytes_per_pixel is 0 for pixel formats readback doesn't support (compressed, depth, packed YUV).
+ * read_bytes blits the texture into a shared-storage buffer (rows padded to the blit alignment), waits, and
+ * copies tightly packed rows, top row first, into out; len must be at least width * height * bytes_per_pixel.
+ * command_queue may be NULL as for copy_to_owned. */
+size_t syphon_metal_texture_bytes_per_pixel(void *texture);
+bool syphon_metal_texture_read_bytes(void *texture, void *command_queue, unsigned char *out, size_t len);
+/* The texture's IOSurfaceRef (not retained), or NULL if it isn't IOSurface-backed. */
+void *syphon_metal_texture_io_surface(void *texture);
+/* A shader-readable 2D texture of the raw MTLPixelFormat pixel_format on device, backed by (and sized like)
+ * plane 0 of surface (+1, NULL on failure; release with syphon_metal_texture_release). */
+void *syphon_metal_texture_create_with_io_surface(void *device, void *surface, unsigned long pixel_format);
+
+/* CVPixelBuffers wrapping an IOSurface without copying (+1, NULL on failure; release with
+ * syphon_cv_pixel_buffer_release). pixel_format is the buffer's OSType FourCC. */
+void *syphon_cv_pixel_buffer_create_with_io_surface(void *surface);
+void syphon_cv_pixel_buffer_release(void *buffer);
+size_t syphon_cv_pixel_buffer_width(void *buffer);
+size_t syphon_cv_pixel_buffer_height(void *buffer);
+uint32_t syphon_cv_pixel_buffer_pixel_format(void *buffer);
+
+#if SYPHON_GLUE_RECORDER
+/* AVAssetWriter movie recorders. create opens a QuickTime movie at path (replacing any file there) with one video
+ * track of codec (0 H.264, 1 ProRes 422, 2 ProRes 4444) at width x height; bit_rate > 0 sets H.264's average bit
+ * rate (ProRes ignores it). NULL with an error if the writer can't be created. append renders pixel_buffer through
+ * Core Image into a buffer from the writer's pool (scaled to fit, flipped vertically if flip) and appends it at
+ * seconds since the first frame, which starts the session: 1 if appended, 0 if dropped (the writer isn't ready
+ * for more data, or seconds isn't after the previous frame's), -1 with an error if writing failed. finish marks the track finished and waits for the
+ * file to be written (false with an error on failure); release cancels an unfinished recorder. */
+void *syphon_recorder_create(const char *path, uint32_t codec, size_t width, size_t height, double bit_rate);
+int syphon_recorder_append(void *recorder, void *pixel_buffer, double seconds, bool flip);
+bool syphon_recorder_finish(void *recorder);
+void syphon_recorder_release(void *recorder);
+#endif
+
+#if SYPHON_GLUE_ENCODER
+/* VideoToolbox compression sessions (hardware-accelerated where available, no frame reordering). create opens
+ * a codec (0 H.264 High, 1 HEVC Main) session of width x height; bit_rate, frame_rate and keyframe_interval
+ * (frames) are hints applied when > 0. NULL with an error on failure (release_userdata is then called).
+ * encode submits pixel_buffer at seconds (rendered through Core Image, scaled to fit, if flip or if its size
+ * differs): 1 if submitted, 0 if dropped (seconds isn't after the previous frame's, or the encoder dropped it),
+ * -1 with an error. callback runs on VideoToolbox's thread for each packet: data is AVCC (four-byte lengths)
+ * or Annex-B (start codes, parameter sets repeated before keyframes) per annex_b; parameter_sets holds the
+ * keyframe's SPS / PPS (and VPS) NAL units with four-byte lengths, empty for other frames. flush waits for all
+ * submitted frames' packets; release flushes, stops the session and calls release_userdata once. */
+void *syphon_encoder_create(uint32_t codec, size_t width, size_t height, double bit_rate, double frame_rate,
+    uint32_t keyframe_interval, bool annex_b,
+    void (*callback)(void *userdata, const unsigned char *data, size_t len, const unsigned char *parameter_sets,
+        size_t parameter_sets_len, double pts, double dts, bool keyframe),
+    void (*release_userdata)(void *userdata), void *userdata);
+int syphon_encoder_encode(void *encoder, void *pixel_buffer, double seconds, bool flip, bool force_keyframe);
+bool syphon_encoder_flush(void *encoder);
+void syphon_encoder_release(void *encoder);
+#endif
+
+/* CVMetalTextureCaches on a device (NULL on failure). new_texture wraps an IOSurface in an MTLTexture of the raw
+ * MTLPixelFormat pixel_format through the cache (+1, release with syphon_metal_texture_release; the texture keeps
+ * its CVMetalTexture alive); flush frees cached textures no longer in use. */
+void *syphon_cv_metal_texture_cache_create(void *device);
+void *syphon_cv_metal_texture_cache_new_texture(void *cache, void *surface, unsigned long pixel_format);
+void syphon_cv_metal_texture_cache_flush(void *cache);
+void syphon_cv_metal_texture_cache_release(void *cache);
+
+#if SYPHON_GLUE_CORE_IMAGE
+/* A CIImage over an IOSurface (+1, NULL on failure; release with syphon_metal_object_release), oriented upright
+ * (Syphon surfaces store the bottom row first). */
+void *syphon_ci_image_create_with_io_surface(void *surface);
+#endif
+
+#if SYPHON_GLUE_SCREEN_CAPTURE
+/* ScreenCaptureKit shareable content (macOS 12.3+; NULL / 0 / false on older systems). content_copy waits for the
+ * displays and on-screen windows (+1, release with syphon_metal_object_release; NULL with an error without
+ * screen-recording permission). Display sizes are in pixels, window sizes in points; copy_* strings are
+ * malloc'd (NULL if unknown). */
+void *syphon_sc_content_copy(void);
+size_t syphon_sc_content_display_count(void *content);
+bool syphon_sc_content_display_at(void *content, size_t index, uint32_t *display_id, size_t *width, size_t *height);
+size_t syphon_sc_content_window_count(void *content);
+bool syphon_sc_content_window_at(void *content, size_t index, uint32_t *window_id, size_t *width, size_t *height);
+char *syphon_sc_content_copy_window_title(void *content, size_t index);
+char *syphon_sc_content_copy_window_app_name(void *content, size_t index);
+#endif
+
+#if SYPHON_GLUE_CAMERA
+/* AVFoundation video capture devices (built-in, external and Continuity cameras, capture cards). devices_copy
+ * snapshots them (+1, release with syphon_metal_object_release); copy_* strings are malloc'd. */
+void *syphon_av_video_devices_copy(void);
+size_t syphon_av_video_devices_count(void *devices);
+char *syphon_av_video_devices_copy_unique_id(void *devices, size_t index);
+char *syphon_av_video_devices_copy_name(void *devices, size_t index);
+char *syphon_av_video_devices_copy_model_id(void *devices, size_t index);
+#endif
+
+/* Heap-backed texture pools. storage_mode is a raw MTLStorageMode (private or shared; heaps can't be managed);
+ * heap_size is the size of each heap the pool allocates (larger requests get a heap of their own). new_texture
+ * and copy return retained textures (release with syphon_metal_texture_release); copy blits and waits like
+ * syphon_metal_texture_copy_to_owned. make_aliasable lets the heap reuse a pooled texture's memory while the
+ * object is still alive: only call it once no submitted or future GPU work reads or writes the texture. */
+void *syphon_metal_texture_pool_create(void *device, size_t heap_size, unsigned long storage_mode);
+void *syphon_metal_texture_pool_new_texture(void *pool, unsigned long pixel_format, size_t width, size_t height,
+    unsigned long usage);
+void *syphon_metal_texture_pool_copy(void *pool, void *texture, void *command_queue, unsigned long usage);
+size_t syphon_metal_texture_pool_allocated_size(void *pool);
+void syphon_metal_texture_pool_release(void *pool);
+void syphon_metal_texture_make_aliasable(void *texture);
+
+/* Frame blenders: output = mix(a, b, t). The blender owns its output texture (valid until the next blend
+ * or release). GL: create/blend/release with a current context; a and b are GL_TEXTURE_2D of the given
+ * size; returns the output texture name or 0. Metal: a and b must match in size and pixel format;
+ * encodes on command_buffer and returns the (unretained) output MTLTexture or NULL. */
+void *syphon_gl_blender_create(void);
+GLuint syphon_gl_blender_blend(void *blender, GLuint tex_a, GLuint tex_b, size_t width, size_t height, float t);
+void syphon_gl_blender_release(void *blender);
+
+/* sRGB transfer conversion; mode 0 copies, 1 encodes linear -> sRGB, 2 decodes sRGB -> linear (alpha kept).
+ * GL: create with a context current; convert renders src (rectangle or 2D, width x height) into an owned
+ * RGBA8 GL_TEXTURE_2D and returns it (0 on failure). Metal: convert encodes a compute pass on
+ * command_buffer into an owned private texture of the source's size and format (not retained; valid
+ * until the next convert). */
+void *syphon_gl_srgb_converter_create(void);
+GLuint syphon_gl_srgb_converter_convert(void *converter, GLuint src_tex, GLenum src_target, size_t width, size_t height,
+    int32_t mode);
+void syphon_gl_srgb_converter_release(void *converter);
+void *syphon_metal_srgb_converter_create(void *device);
+void *syphon_metal_srgb_converter_convert(void *converter, void *texture, void *command_buffer, int32_t mode);
+void syphon_metal_srgb_converter_release(void *converter);
+
+/* MSAA resolve into an owned single-sample RGBA8 GL_TEXTURE_2D, resized with the frame. create needs no GL;
+ * resolve blits (0, 0, width, height) of msaa_fbo's color buffer (0 is the default framebuffer) and returns
+ * the texture, or 0 on failure; release needs the context current. */
+void *syphon_gl_resolver_create(void);
+GLuint syphon_gl_resolver_resolve(void *resolver, GLuint msaa_fbo, size_t width, size_t height);
+void syphon_gl_resolver_release(void *resolver);
+void *syphon_metal_blender_create(void *device);
+void *syphon_metal_blender_blend(void *blender, void *texture_a, void *texture_b, void *command_buffer, float t);
+/* Storage mode and usage of the Metal blender's output (default private, shader-read; shader-write is
+ * always added). Takes effect on the next blend. */
+void syphon_metal_blender_set_output_options(void *blender, unsigned long storage_mode, unsigned long usage);
+void syphon_metal_blender_release(void *blender);
+
+#if SYPHON_GLUE_PASTEBOARD
+/* Pasteboard / drag: rgba is width*height*4 bytes, top row first, non-premultiplied. png/tiff pick the
+ * representations offered. write replaces the general pasteboard's contents. The dragging item (an
+ * NSDraggingItem*, for -[NSView beginDraggingSessionWithItems:event:source:]) has a preview frame of
+ * preview_w x preview_h at the origin (<= 0 uses the image size); release it after starting the session. */
+bool syphon_pasteboard_write_rgba8(const unsigned char *rgba, size_t width, size_t height, bool png, bool tiff);
+void *syphon_dragging_item_create_rgba8(const unsigned char *rgba, size_t width, size_t height, bool png, bool tiff,
+    double preview_w, double preview_h);
+void syphon_dragging_item_release(void *item);
+#endif
+
+#if SYPHON_GLUE_HOTKEY
+/* Global hotkey (Carbon). key_code is a virtual key code (kVK_*), modifiers Carbon flags (cmdKey 0x100,
+ * shiftKey 0x200, optionKey 0x800, controlKey 0x1000). callback runs on the main thread; the main run loop
+ * must be running. Returns NULL if the combination can't be registered (release_userdata is then called).
+ * unregister stops callbacks, calls release_userdata once and frees the handle. */
+void *syphon_hotkey_register(uint32_t key_code, uint32_t modifiers, void (*callback)(void *userdata),
+    void (*release_userdata)(void *userdata), void *userdata);
+void syphon_hotkey_unregister(void *hotkey);
+#endif
+
 #ifdef __cplusplus
 }
 #endif
@@ -10,11 +10,39 @@
 #endif
 
 #import <Foundation/Foundation.h>
+#import <AppKit/AppKit.h>
+#if SYPHON_GLUE_HOTKEY
+#import <Carbon/Carbon.h>
+#endif
 #import <CoreFoundation/CoreFoundation.h>
+#import <IOSurface/IOSurface.h>
+#import <CoreVideo/CoreVideo.h>
+#import <ImageIO/ImageIO.h>
+#if SYPHON_GLUE_CORE_IMAGE || SYPHON_GLUE_RECORDER || SYPHON_GLUE_ENCODER
+#import <CoreImage/CoreImage.h>
+#endif
+#if SYPHON_GLUE_SCREEN_CAPTURE || SYPHON_GLUE_CAMERA || SYPHON_GLUE_RECORDER || SYPHON_GLUE_ENCODER
+#import <CoreMedia/CoreMedia.h>
+#endif
+#if SYPHON_GLUE_CAMERA || SYPHON_GLUE_RECORDER
+#import <AVFoundation/AVFoundation.h>
+#endif
+#if SYPHON_GLUE_ENCODER
+#import <VideoToolbox/VideoToolbox.h>
+#endif
+#if SYPHON_GLUE_SCREEN_CAPTURE
+#import <ScreenCaptureKit/ScreenCaptureKit.h>
+#endif
 #import <OpenGL/OpenGL.h>
 #import <OpenGL/gl.h>
+#import <OpenGL/glext.h>
 #import <Metal/Metal.h>
+#import <QuartzCore/CAMetalLayer.h>
 #import <Syphon/Syphon.h>
+#import <objc/runtime.h>
+#include <os/lock.h>
+#include <pthread.h>
+#include <unistd.h>
 
 /* Support both newer framework (SyphonOpenGLServer etc.) and older (SyphonServer/SyphonClient/SyphonImage). */
 #if __has_include(<Syphon/SyphonOpenGLServer.h>)
@@ -28,6 +56,8 @@
 #define SYPHON_GL_SERVER SyphonServer
 #define SYPHON_GL_CLIENT SyphonClient
 #define SYPHON_GL_IMAGE SyphonImage
+/* The pre-5 classes draw with fixed-function GL, which core-profile contexts lack. */
+#define SYPHON_GL_LEGACY_ONLY 1
 #endif
 
 #if __has_include(<Syphon/SyphonMetalServer.h>)
@@ -43,9 +73,97 @@ static NSString *nullable_cstring_to_nsstring(const char *cstr) {
     return [NSString stringWithUTF8String:cstr];
 }
 
+/*
+ * Failure reasons for the create functions, per thread: each create clears it and records why it
+ * returned NULL; syphon_copy_last_error hands it to the caller.
+ */
+static __thread char *glue_last_error;
+
+static void glue_set_error(NSString *message) {
+    free(glue_last_error);
+    glue_last_error = message ? strdup(message.UTF8String ?: "") : NULL;
+}
+
+char *syphon_copy_last_error(void) {
+    char *error = glue_last_error;
+    glue_last_error = NULL;
+    return error;
+}
+
+bool syphon_cgl_context_is_core_profile(CGLContextObj ctx);
+
+/* Refuse contexts the framework's OpenGL classes cannot draw with, setting the glue error. */
+static bool glue_gl_context_supported(CGLContextObj context) {
+#if SYPHON_GL_LEGACY_ONLY
+    if (syphon_cgl_context_is_core_profile(context)) {
+        glue_set_error(@"this Syphon framework only supports legacy OpenGL contexts (core profile needs Syphon 5+)");
+        return false;
+    }
+#endif
+    (void)context;
+    return true;
+}
+
+/*
+ * Teardown helpers. Servers and clients are stopped exactly once (a second stop is a no-op) on the
+ * calling thread, so GL/Metal resources are torn down while the caller's context is still valid.
+ * The final release is handed to the main queue when called off the main thread while the main
+ * run loop is running, since Syphon's connection/notification machinery is bound to it; with no
+ * main run loop (command-line tools, test harnesses) it happens inline.
+ */
+static char kSyphonGlueStoppedKey;
+/* Size of the last frame an OpenGL server published through the dirty-region path; its surface keeps
+ * that frame's pixels, so later frames of the same size only need the changed region drawn. Other
+ * publishes may resize the surface, so they clear it. */
+static char kSyphonGlueDirtySizeKey;
+
+static bool glue_mark_stopped(id obj) {
+    if (!obj) return false;
+    @synchronized (obj) {
+        if (objc_getAssociatedObject(obj, &kSyphonGlueStoppedKey)) return false;
+        objc_setAssociatedObject(obj, &kSyphonGlueStoppedKey, @YES, OBJC_ASSOCIATION_RETAIN_NONATOMIC);
+        return true;
+    }
+}
+
+static bool glue_main_run_loop_is_running(void) {
+    CFStringRef mode = CFRunLoopCopyCurrentMode(CFRunLoopGetMain());
+    if (!mode) return false;
+    CFRelease(mode);
+    return true;
+}
+
+void syphon_opengl_server_stop(void *server);
+void syphon_opengl_client_stop(void *client);
+void syphon_metal_server_stop(void *server);
+void syphon_metal_client_stop(void *client);
+
+static void glue_release_object(void *ptr) {
+    if (!ptr) return;
+    id obj = (__bridge_transfer id)ptr;
+    if ([NSThread isMainThread] || !glue_main_run_loop_is_running()) {
+        obj = nil;
+        return;
+    }
+    dispatch_async(dispatch_get_main_queue(), ^{
+        (void)obj;
+    });
+}
+
+/*
+ * Per-frame entry points (publish, bind/unbind, new frame image, frame callbacks) wrap their Syphon
+ * call in an autorelease pool, draining what Syphon autoreleases each frame: Rust threads have no run
+ * loop to do it, so without the pool those objects accumulate until the thread exits. This bounds
+ * memory; it doesn't remove Syphon's own allocations.
+ * (Frame callbacks created with report_size fetch the new frame for its size on the delivery thread.)
+ */
+
 /* Server directory */
 void *syphon_server_directory_shared(void) {
-    return (__bridge void *)[SyphonServerDirectory sharedDirectory];
+    glue_set_error(nil);
+    SyphonServerDirectory *d = [SyphonServerDirectory sharedDirectory];
+    if (!d) glue_set_error(@"SyphonServerDirectory sharedDirectory returned nil");
+    return (__bridge void *)d;
 }
 
 size_t syphon_server_directory_servers_count(void *dir) {
@@ -57,7 +175,7 @@ void *syphon_server_directory_server_at_index(void *dir, size_t index) {
     SyphonServerDirectory *d = (__bridge SyphonServerDirectory *)dir;
     NSArray *servers = d.servers;
     if (index >= [servers count]) return NULL;
-    return (__bridge void *)[servers objectAtIndex:index];
+    return (__bridge_retained void *)[servers objectAtIndex:index];
 }
 
 void *syphon_server_directory_servers_matching(void *dir, const char *name, const char *app_name) {
@@ -109,6 +227,129 @@ char *syphon_server_description_copy_app_name(void *desc) {
     return copy_nsstring_to_cstring(v);
 }
 
+/* Keys Syphon puts in descriptions besides the public ones (SyphonPrivate.h). */
+static NSString *const kGlueDescriptionVersionKey = @"SyphonServerDescriptionDictionaryVersionKey";
+static NSString *const kGlueDescriptionSurfacesKey = @"SyphonServerDescriptionSurfacesKey";
+static NSString *const kGlueSurfaceTypeKey = @"SyphonSurfaceType";
+
+/* Display form of a description value: strings as-is, numbers as decimal, data as base64, arrays and
+ * dictionaries as JSON when representable. */
+static NSString *glue_description_value_string(id value) {
+    if ([value isKindOfClass:[NSString class]]) return value;
+    if ([value isKindOfClass:[NSNumber class]]) return [value stringValue];
+    if ([value isKindOfClass:[NSData class]]) return [value base64EncodedStringWithOptions:0];
+    if ([NSJSONSerialization isValidJSONObject:value]) {
+        NSData *json = [NSJSONSerialization dataWithJSONObject:value options:NSJSONWritingSortedKeys error:NULL];
+        if (json) return [[NSString alloc] initWithData:json encoding:NSUTF8StringEncoding];
+    }
+    return [value description];
+}
+
+char *syphon_server_description_copy_entries(void *desc, size_t *out_len) {
+    NSDictionary *d = (__bridge NSDictionary *)desc;
+    if (!d || !out_len) return NULL;
+    @autoreleasepool {
+        NSMutableData *buf = [NSMutableData data];
+        const char nul = 0;
+        for (id key in d) {
+            if (![key isKindOfClass:[NSString class]]) continue;
+            const char *k = [key UTF8String];
+            const char *v = [glue_description_value_string(d[key]) UTF8String];
+            if (!k || !v) continue;
+            [buf appendBytes:k length:strlen(k)];
+            [buf appendBytes:&nul length:1];
+            [buf appendBytes:v length:strlen(v)];
+            [buf appendBytes:&nul length:1];
+        }
+        char *out = malloc(buf.length ? buf.length : 1);
+        if (!out) return NULL;
+        memcpy(out, buf.bytes, buf.length);
+        *out_len = buf.length;
+        return out;
+    }
+}
+
+int64_t syphon_server_description_dictionary_version(void *desc) {
+    NSDictionary *d = (__bridge NSDictionary *)desc;
+    id v = d[kGlueDescriptionVersionKey];
+    return [v isKindOfClass:[NSNumber class]] ? [v longLongValue] : -1;
+}
+
+char *syphon_server_description_copy_surface_types(void *desc) {
+    NSDictionary *d = (__bridge NSDictionary *)desc;
+    id surfaces = d[kGlueDescriptionSurfacesKey];
+    if (![surfaces isKindOfClass:[NSArray class]]) return NULL;
+    NSMutableArray<NSString *> *types = [NSMutableArray array];
+    for (id surface in surfaces) {
+        if (![surface isKindOfClass:[NSDictionary class]]) continue;
+        id type = surface[kGlueSurfaceTypeKey];
+        if ([type isKindOfClass:[NSString class]]) [types addObject:type];
+    }
+    return copy_nsstring_to_cstring([types componentsJoinedByString:@"\n"]);
+}
+
+/* Running application hosting the server, matched by display name; nil if there is none or the name is
+ * ambiguous (several running apps with that name and different bundle identifiers). */
+static NSRunningApplication *glue_running_app_for_description(NSDictionary *d) {
+    NSString *appName = d[SyphonServerDescriptionAppNameKey];
+    if (!appName) return nil;
+    NSRunningApplication *found = nil;
+    for (NSRunningApplication *app in [[NSWorkspace sharedWorkspace] runningApplications]) {
+        if (![app.localizedName isEqualToString:appName]) continue;
+        if (!found) {
+            found = app;
+        } else if (!app.bundleIdentifier || ![found.bundleIdentifier isEqualToString:app.bundleIdentifier]) {
+            return nil;
+        }
+    }
+    return found;
+}
+
+char *syphon_server_description_copy_bundle_identifier(void *desc) {
+    NSDictionary *d = (__bridge NSDictionary *)desc;
+    return copy_nsstring_to_cstring(glue_running_app_for_description(d).bundleIdentifier);
+}
+
+unsigned char *syphon_server_description_copy_app_icon_rgba8(void *desc, size_t size) {
+    NSDictionary *d = (__bridge NSDictionary *)desc;
+    if (size == 0) return NULL;
+    @autoreleasepool {
+        /* Prefer an icon published in the description (image or image data), else the running app's. */
+        id published = d[@"SyphonServerDescriptionIconKey"];
+        NSImage *icon = nil;
+        if ([published isKindOfClass:[NSImage class]]) {
+            icon = published;
+        } else if ([published isKindOfClass:[NSData class]]) {
+            icon = [[NSImage alloc] initWithData:published];
+        }
+        if (!icon) icon = glue_running_app_for_description(d).icon;
+        if (!icon) return NULL;
+
+        NSBitmapImageRep *rep = [[NSBitmapImageRep alloc] initWithBitmapDataPlanes:NULL
+                                                                        pixelsWide:(NSInteger)size
+                                                                        pixelsHigh:(NSInteger)size
+                                                                     bitsPerSample:8
+                                                                   samplesPerPixel:4
+                                                                          hasAlpha:YES
+                                                                          isPlanar:NO
+                                                                    colorSpaceName:NSDeviceRGBColorSpace
+                                                                       bytesPerRow:(NSInteger)(size * 4)
+                                                                      bitsPerPixel:32];
+        if (!rep) return NULL;
+        [NSGraphicsContext saveGraphicsState];
+        NSGraphicsContext.currentContext = [NSGraphicsContext graphicsContextWithBitmapImageRep:rep];
+        [icon drawInRect:NSMakeRect(0, 0, (CGFloat)size, (CGFloat)size)
+                fromRect:NSZeroRect
+               operation:NSCompositingOperationCopy
+                fraction:1.0];
+        [NSGraphicsContext restoreGraphicsState];
+
+        unsigned char *out = malloc(size * size * 4);
+        if (out) memcpy(out, rep.bitmapData, size * size * 4);
+        return out;
+    }
+}
+
 char *syphon_notification_name_server_announce(void) {
     return copy_nsstring_to_cstring(SyphonServerAnnounceNotification);
 }
@@ -121,6 +362,102 @@ char *syphon_notification_name_server_retire(void) {
     return copy_nsstring_to_cstring(SyphonServerRetireNotification);
 }
 
+/*
+ * Directory subscriptions: observe announce/retire/update and forward each notification's server
+ * description to a C callback. Unsubscribing removes the observers and waits out a running callback
+ * (under the lock), so once it returns the callback is not running and will not run again. Unsubscribing
+ * from inside the callback is allowed: the userdata is then released when the callback returns.
+ */
+typedef void (*directory_event_callback_t)(void *userdata, int event, void *description);
+
+@interface SyphonGlueDirectorySubscription : NSObject {
+@public
+    os_unfair_lock _lock;
+    directory_event_callback_t _callback;
+    void (*_release)(void *);
+    void *_userdata;
+    pthread_t _delivering;
+    bool _releasePending;
+}
+@property (nonatomic, strong) NSArray *observers;
+@end
+
+@implementation SyphonGlueDirectorySubscription
+- (void)deliver:(int)event description:(id)description {
+    if (![description isKindOfClass:[NSDictionary class]]) return;
+    os_unfair_lock_lock(&_lock);
+    if (_callback) {
+        _delivering = pthread_self();
+        @autoreleasepool { _callback(_userdata, event, (__bridge void *)description); }
+        _delivering = NULL;
+    }
+    void (*release)(void *) = NULL;
+    void *userdata = NULL;
+    if (_releasePending) {
+        release = _release;
+        userdata = _userdata;
+        _release = NULL;
+        _userdata = NULL;
+        _releasePending = false;
+    }
+    os_unfair_lock_unlock(&_lock);
+    if (release) release(userdata);
+}
+
+- (void)invalidate {
+    for (id observer in self.observers) [[NSNotificationCenter defaultCenter] removeObserver:observer];
+    self.observers = nil;
+    if (_delivering && pthread_equal(_delivering, pthread_self())) {
+        /* Called from our own callback, which holds the lock: deliver finishes the release. */
+        _callback = NULL;
+        _releasePending = true;
+        return;
+    }
+    os_unfair_lock_lock(&_lock);
+    void (*release)(void *) = _release;
+    void *userdata = _userdata;
+    _callback = NULL;
+    _release = NULL;
+    _userdata = NULL;
+    os_unfair_lock_unlock(&_lock);
+    if (release) release(userdata);
+}
+
+- (void)dealloc {
+    [self invalidate];
+}
+@end
+
+void *syphon_directory_subscribe(directory_event_callback_t callback, void (*release_userdata)(void *), void *userdata) {
+    if (!callback) {
+        if (release_userdata) release_userdata(userdata);
+        return NULL;
+    }
+    SyphonGlueDirectorySubscription *sub = [[SyphonGlueDirectorySubscription alloc] init];
+    sub->_lock = OS_UNFAIR_LOCK_INIT;
+    sub->_callback = callback;
+    sub->_release = release_userdata;
+    sub->_userdata = userdata;
+    /* The directory only tracks servers once it exists. */
+    (void)[SyphonServerDirectory sharedDirectory];
+    NSNotificationCenter *center = [NSNotificationCenter defaultCenter];
+    __weak SyphonGlueDirectorySubscription *weakSub = sub;
+    NSArray<NSString *> *names = @[SyphonServerAnnounceNotification, SyphonServerRetireNotification, SyphonServerUpdateNotification];
+    NSMutableArray *observers = [NSMutableArray arrayWithCapacity:names.count];
+    for (int event = 0; event < (int)names.count; event++) {
+        [observers addObject:[center addObserverForName:names[event] object:nil queue:nil usingBlock:^(NSNotification *note) {
+            [weakSub deliver:event description:note.object];
+        }]];
+    }
+    sub.observers = observers;
+    return (__bridge_retained void *)sub;
+}
+
+void syphon_directory_unsubscribe(void *subscription) {
+    SyphonGlueDirectorySubscription *sub = (__bridge_transfer SyphonGlueDirectorySubscription *)subscription;
+    [sub invalidate];
+}
+
 void syphon_server_description_retain(void *desc) {
     (void)CFBridgingRetain((__bridge id)desc);
 }
@@ -129,6 +466,38 @@ void syphon_server_description_release(void *desc) {
     if (desc) CFRelease((CFTypeRef)desc);
 }
 
+/* Descriptions as binary property lists, to hand a (private) server's description to another process. */
+unsigned char *syphon_server_description_copy_plist(void *desc, size_t *out_len) {
+    NSDictionary *d = (__bridge NSDictionary *)desc;
+    if (!d || !out_len) return NULL;
+    @autoreleasepool {
+        NSData *data = [NSPropertyListSerialization dataWithPropertyList:d
+                                                                  format:NSPropertyListBinaryFormat_v1_0
+                                                                 options:0
+                                                                   error:NULL];
+        if (!data || data.length == 0) return NULL;
+        unsigned char *out = malloc(data.length);
+        if (!out) return NULL;
+        memcpy(out, data.bytes, data.length);
+        *out_len = data.length;
+        return out;
+    }
+}
+
+void *syphon_server_description_create_from_plist(const unsigned char *bytes, size_t len) {
+    if (!bytes || len == 0) return NULL;
+    @autoreleasepool {
+        NSData *data = [NSData dataWithBytes:bytes length:len];
+        id plist = [NSPropertyListSerialization propertyListWithData:data
+                                                             options:NSPropertyListImmutable
+                                                              format:NULL
+                                                               error:NULL];
+        if (![plist isKindOfClass:[NSDictionary class]]) return NULL;
+        if (![((NSDictionary *)plist)[SyphonServerDescriptionUUIDKey] isKindOfClass:[NSString class]]) return NULL;
+        return (__bridge_retained void *)plist;
+    }
+}
+
 /* Server options */
 void *syphon_options_create(void) {
     return (__bridge_retained void *)[NSMutableDictionary dictionary];
@@ -146,6 +515,13 @@ void syphon_options_set_unsigned_long(void *opts, const char *key, unsigned long
     if (k) d[k] = @(value);
 }
 
+void syphon_options_set_string(void *opts, const char *key, const char *value) {
+    NSMutableDictionary *d = (__bridge NSMutableDictionary *)opts;
+    NSString *k = nullable_cstring_to_nsstring(key);
+    NSString *v = nullable_cstring_to_nsstring(value);
+    if (k && v) d[k] = v;
+}
+
 void syphon_options_release(void *opts) {
     (void)(__bridge_transfer NSMutableDictionary *)opts;
 }
@@ -172,17 +548,34 @@ char *syphon_server_option_key_stencil_buffer_resolution(void) {
     return copy_key(SyphonServerOptionStencilBufferResolution);
 }
 
+/* Not declared by released frameworks (their Metal surfaces are always BGRA8); passed through for those
+ * that honor it. */
+char *syphon_server_option_key_pixel_format(void) {
+    return copy_key(@"SyphonServerOptionPixelFormat");
+}
+
 /* OpenGL server */
 void *syphon_opengl_server_create(const char *name, CGLContextObj context, void *options) {
+    glue_set_error(nil);
+    if (!context) {
+        glue_set_error(@"no CGL context");
+        return NULL;
+    }
+    if (!glue_gl_context_supported(context)) return NULL;
     NSString *nsName = nullable_cstring_to_nsstring(name);
-    SYPHON_GL_SERVER *server = [[SYPHON_GL_SERVER alloc] initWithName:nsName
-                                                                  context:context
-                                                                  options:(__bridge NSDictionary *)options];
+    SYPHON_GL_SERVER *server = nil;
+    @try {
+        server = [[SYPHON_GL_SERVER alloc] initWithName:nsName context:context options:(__bridge NSDictionary *)options];
+    } @catch (NSException *e) {
+        glue_set_error([NSString stringWithFormat:@"%@: %@", e.name, e.reason]);
+    }
+    if (!server && !glue_last_error) glue_set_error(@"Syphon OpenGL server init returned nil (invalid context or options?)");
     return (__bridge_retained void *)server;
 }
 
 void syphon_opengl_server_release(void *server) {
-    (void)(__bridge_transfer SYPHON_GL_SERVER *)server;
+    syphon_opengl_server_stop(server);
+    glue_release_object(server);
 }
 
 bool syphon_opengl_server_has_clients(void *server) {
@@ -199,26 +592,72 @@ void *syphon_opengl_server_server_description(void *server) {
 void syphon_opengl_server_publish_frame(void *server, GLuint tex_id, GLenum target,
     double x, double y, double w, double h, double tex_w, double tex_h, bool flipped) {
     SYPHON_GL_SERVER *s = (__bridge SYPHON_GL_SERVER *)server;
-    NSRect region = NSMakeRect(x, y, w, h);
-    NSSize size = NSMakeSize(tex_w, tex_h);
-    [s publishFrameTexture:tex_id textureTarget:target imageRegion:region
-        textureDimensions:size flipped:flipped ? YES : NO];
+    objc_setAssociatedObject(s, &kSyphonGlueDirtySizeKey, nil, OBJC_ASSOCIATION_RETAIN_NONATOMIC);
+    @autoreleasepool {
+        [s publishFrameTexture:tex_id textureTarget:target imageRegion:NSMakeRect(x, y, w, h)
+            textureDimensions:NSMakeSize(tex_w, tex_h) flipped:flipped];
+    }
 }
 
 bool syphon_opengl_server_bind_to_draw_frame(void *server, double w, double h) {
     SYPHON_GL_SERVER *s = (__bridge SYPHON_GL_SERVER *)server;
-    NSSize size = NSMakeSize(w, h);
-    return [s bindToDrawFrameOfSize:size] ? true : false;
+    BOOL ok = NO;
+    objc_setAssociatedObject(s, &kSyphonGlueDirtySizeKey, nil, OBJC_ASSOCIATION_RETAIN_NONATOMIC);
+    @autoreleasepool { ok = [s bindToDrawFrameOfSize:NSMakeSize(w, h)]; }
+    return ok ? true : false;
 }
 
 void syphon_opengl_server_unbind_and_publish(void *server) {
     SYPHON_GL_SERVER *s = (__bridge SYPHON_GL_SERVER *)server;
-    [s unbindAndPublish];
+    @autoreleasepool { [s unbindAndPublish]; }
+}
+
+bool syphon_opengl_server_publish_dirty_region(void *server, GLuint tex_id, GLenum target, double tex_w, double tex_h,
+    double x, double y, double w, double h, bool flipped) {
+    SYPHON_GL_SERVER *s = (__bridge SYPHON_GL_SERVER *)server;
+    if (!s || tex_w <= 0 || tex_h <= 0) return false;
+    NSSize size = NSMakeSize(tex_w, tex_h);
+    NSValue *last = objc_getAssociatedObject(s, &kSyphonGlueDirtySizeKey);
+    bool full = !last || !NSEqualSizes(last.sizeValue, size);
+    if (!full) {
+        /* Clip to the frame; an empty region republishes the unchanged surface. */
+        double x0 = MAX(0.0, x), y0 = MAX(0.0, y), x1 = MIN(tex_w, x + w), y1 = MIN(tex_h, y + h);
+        BOOL ok = NO;
+        @autoreleasepool { ok = [s bindToDrawFrameOfSize:size]; }
+        if (!ok) return false;
+        if (x1 > x0 && y1 > y0) {
+            GLint draw_fbo = 0;
+            glGetIntegerv(GL_DRAW_FRAMEBUFFER_BINDING, &draw_fbo);
+            GLuint read_fbo = 0;
+            glGenFramebuffers(1, &read_fbo);
+            glBindFramebuffer(GL_READ_FRAMEBUFFER, read_fbo);
+            glFramebufferTexture2D(GL_READ_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, target, tex_id, 0);
+            if (glCheckFramebufferStatus(GL_READ_FRAMEBUFFER) == GL_FRAMEBUFFER_COMPLETE) {
+                GLint sx0 = (GLint)x0, sy0 = (GLint)y0, sx1 = (GLint)x1, sy1 = (GLint)y1;
+                GLint dy0 = flipped ? (GLint)tex_h - sy0 : sy0;
+                GLint dy1 = flipped ? (GLint)tex_h - sy1 : sy1;
+                glBlitFramebuffer(sx0, sy0, sx1, sy1, sx0, dy0, sx1, dy1, GL_COLOR_BUFFER_BIT, GL_NEAREST);
+            } else {
+                full = true;
+            }
+            glFramebufferTexture2D(GL_READ_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, target, 0, 0);
+            glBindFramebuffer(GL_READ_FRAMEBUFFER, (GLuint)draw_fbo);
+            glDeleteFramebuffers(1, &read_fbo);
+        }
+        @autoreleasepool { [s unbindAndPublish]; }
+        if (!full) return true;
+    }
+    @autoreleasepool {
+        [s publishFrameTexture:tex_id textureTarget:target imageRegion:NSMakeRect(0, 0, tex_w, tex_h)
+            textureDimensions:size flipped:flipped];
+    }
+    objc_setAssociatedObject(s, &kSyphonGlueDirtySizeKey, [NSValue valueWithSize:size], OBJC_ASSOCIATION_RETAIN_NONATOMIC);
+    return true;
 }
 
 void syphon_opengl_server_stop(void *server) {
     SYPHON_GL_SERVER *s = (__bridge SYPHON_GL_SERVER *)server;
-    [s stop];
+    if (glue_mark_stopped(s)) [s stop];
 }
 
 CGLContextObj syphon_opengl_server_context(void *server) {
@@ -243,31 +682,266 @@ void *syphon_opengl_server_new_frame_image(void *server) {
 }
 
 /* OpenGL client */
-typedef void (*new_frame_callback_t)(void *userdata);
+typedef void (*new_frame_callback_t)(void *userdata, uint64_t frame_index, size_t width, size_t height);
+typedef void (*new_frame_release_t)(void *userdata);
+
+/*
+ * Owns a client's new-frame callback and userdata. Invocation and invalidation are serialized by a
+ * recursive lock, so once -invalidate returns no callback is running (on another thread) and none
+ * will start; userdata is released exactly once, on invalidation or dealloc.
+ *
+ * Direct handlers use an os_unfair_lock and no autorelease pool instead: cheaper per frame. The lock is
+ * not recursive, so invalidating from inside the callback (a callback stopping or releasing its own
+ * client) does not take it: like directory subscriptions, the userdata is then released when the
+ * callback returns.
+ */
+@interface SyphonGlueFrameHandler : NSObject
+- (instancetype)initWithCallback:(new_frame_callback_t)callback
+                         release:(new_frame_release_t)release
+                        userdata:(void *)userdata
+                          direct:(bool)direct
+                      reportSize:(bool)reportSize;
+@property (nonatomic, readonly) bool direct;
+@property (nonatomic, readonly) bool reportSize;
+- (void)invokeWithWidth:(size_t)width height:(size_t)height;
+- (void)invalidate;
+@end
+
+@implementation SyphonGlueFrameHandler {
+    NSRecursiveLock *_lock;
+    os_unfair_lock _directLock;
+    bool _direct;
+    bool _reportSize;
+    new_frame_callback_t _callback;
+    new_frame_release_t _release;
+    void *_userdata;
+    uint64_t _frameIndex;
+    pthread_t _delivering;
+    bool _releasePending;
+}
+
+- (instancetype)initWithCallback:(new_frame_callback_t)callback
+                         release:(new_frame_release_t)release
+                        userdata:(void *)userdata
+                          direct:(bool)direct
+                      reportSize:(bool)reportSize {
+    self = [super init];
+    if (self) {
+        _direct = direct;
+        _reportSize = reportSize;
+        if (direct) {
+            _directLock = OS_UNFAIR_LOCK_INIT;
+        } else {
+            _lock = [[NSRecursiveLock alloc] init];
+        }
+        _callback = callback;
+        _release = release;
+        _userdata = userdata;
+    }
+    return self;
+}
+
+- (bool)direct {
+    return _direct;
+}
+
+- (bool)reportSize {
+    return _reportSize;
+}
+
+/* Frames are numbered from 1 in delivery order, under the lock. */
+- (void)invokeWithWidth:(size_t)width height:(size_t)height {
+    if (_direct) {
+        os_unfair_lock_lock(&_directLock);
+        if (_callback) {
+            _delivering = pthread_self();
+            _callback(_userdata, ++_frameIndex, width, height);
+            _delivering = NULL;
+        }
+        new_frame_release_t release = NULL;
+        void *userdata = NULL;
+        if (_releasePending) {
+            release = _release;
+            userdata = _userdata;
+            _release = NULL;
+            _userdata = NULL;
+            _releasePending = false;
+        }
+        os_unfair_lock_unlock(&_directLock);
+        if (release) release(userdata);
+        return;
+    }
+    [_lock lock];
+    if (_callback) {
+        @autoreleasepool { _callback(_userdata, ++_frameIndex, width, height); }
+    }
+    [_lock unlock];
+}
+
+- (void)invalidate {
+    if (_direct) {
+        if (_delivering && pthread_equal(_delivering, pthread_self())) {
+            /* Called from our own callback, which holds the lock: invoke finishes the release. */
+            _callback = NULL;
+            _releasePending = true;
+            return;
+        }
+        os_unfair_lock_lock(&_directLock);
+    } else {
+        [_lock lock];
+    }
+    new_frame_release_t release = _release;
+    void *userdata = _userdata;
+    _callback = NULL;
+    _release = NULL;
+    _userdata = NULL;
+    if (_direct) {
+        os_unfair_lock_unlock(&_directLock);
+    } else {
+        [_lock unlock];
+    }
+    if (release) release(userdata);
+}
+
+- (void)dealloc {
+    [self invalidate];
+}
+@end
+
+/*
+ * Holds a client's current frame handler so it can be replaced after creation. Syphon's handler block
+ * (installed once, at init) captures the slot and looks the handler up per frame. Replacing or clearing
+ * the handler invalidates the previous one, so once that returns the old callback is not running.
+ */
+@interface SyphonGlueFrameHandlerSlot : NSObject
+- (SyphonGlueFrameHandler *)handler;
+- (void)setHandler:(SyphonGlueFrameHandler *)handler;
+@end
+
+@implementation SyphonGlueFrameHandlerSlot {
+    os_unfair_lock _lock;
+    SyphonGlueFrameHandler *_handler;
+}
+
+- (instancetype)init {
+    self = [super init];
+    if (self) _lock = OS_UNFAIR_LOCK_INIT;
+    return self;
+}
+
+- (SyphonGlueFrameHandler *)handler {
+    os_unfair_lock_lock(&_lock);
+    SyphonGlueFrameHandler *handler = _handler;
+    os_unfair_lock_unlock(&_lock);
+    return handler;
+}
+
+- (void)setHandler:(SyphonGlueFrameHandler *)handler {
+    os_unfair_lock_lock(&_lock);
+    SyphonGlueFrameHandler *previous = _handler;
+    _handler = handler;
+    os_unfair_lock_unlock(&_lock);
+    [previous invalidate];
+}
+@end
+
+static char kSyphonGlueFrameHandlerKey;
+
+static SyphonGlueFrameHandler *frame_handler_create(new_frame_callback_t callback,
+    new_frame_release_t release, void *userdata, bool direct, bool report_size) {
+    if (!callback) {
+        if (release) release(userdata);
+        return nil;
+    }
+    return [[SyphonGlueFrameHandler alloc] initWithCallback:callback release:release userdata:userdata
+                                                     direct:direct
+                                                 reportSize:report_size && !direct];
+}
+
+static void frame_handler_attach(id client, SyphonGlueFrameHandlerSlot *slot) {
+    if (client) {
+        objc_setAssociatedObject(client, &kSyphonGlueFrameHandlerKey, slot, OBJC_ASSOCIATION_RETAIN_NONATOMIC);
+    } else {
+        [slot setHandler:nil];
+    }
+}
+
+/* Replace (or with a NULL callback, clear) the handler of a client created by this glue. */
+static void frame_handler_set(id client, new_frame_callback_t callback, new_frame_release_t release,
+    void *userdata, bool direct, bool report_size) {
+    SyphonGlueFrameHandler *handler = frame_handler_create(callback, release, userdata, direct, report_size);
+    SyphonGlueFrameHandlerSlot *slot = client ? objc_getAssociatedObject(client, &kSyphonGlueFrameHandlerKey) : nil;
+    if (slot) {
+        [slot setHandler:handler];
+    } else {
+        [handler invalidate];
+    }
+}
+
+static void frame_handler_detach(id client) {
+    SyphonGlueFrameHandlerSlot *slot = objc_getAssociatedObject(client, &kSyphonGlueFrameHandlerKey);
+    [slot setHandler:nil];
+}
 
 void *syphon_opengl_client_create(void *server_description, CGLContextObj context,
-    void *options, new_frame_callback_t new_frame_callback, void *userdata) {
+    void *options, new_frame_callback_t new_frame_callback, new_frame_release_t release_userdata,
+    void *userdata, bool direct_dispatch, bool report_size) {
     NSDictionary *desc = (__bridge NSDictionary *)server_description;
-    void (^handler)(SYPHON_GL_CLIENT *);
-    if (new_frame_callback) {
-        new_frame_callback_t cb = new_frame_callback;
-        void *ud = userdata;
-        handler = ^(SYPHON_GL_CLIENT *client) {
-            (void)client;
-            cb(ud);
-        };
-    } else {
-        handler = nil;
+    glue_set_error(nil);
+    if (!desc || !context) {
+        glue_set_error(desc ? @"no CGL context" : @"no server description");
+        if (release_userdata) release_userdata(userdata);
+        return NULL;
+    }
+    if (!glue_gl_context_supported(context)) {
+        if (release_userdata) release_userdata(userdata);
+        return NULL;
+    }
+    SyphonGlueFrameHandlerSlot *slot = [[SyphonGlueFrameHandlerSlot alloc] init];
+    [slot setHandler:frame_handler_create(new_frame_callback, release_userdata, userdata, direct_dispatch,
+                                           report_size)];
+    void (^handler)(SYPHON_GL_CLIENT *) = ^(SYPHON_GL_CLIENT *client) {
+        SyphonGlueFrameHandler *frameHandler = [slot handler];
+        if (!frameHandler) return;
+        /* Only handlers that asked for the size fetch the frame: that uses the client's context on this
+         * thread and consumes hasNewFrame. */
+        size_t width = 0, height = 0;
+        if (frameHandler.reportSize) {
+            @autoreleasepool {
+                SYPHON_GL_IMAGE *image = [client newFrameImage];
+                width = (size_t)image.textureSize.width;
+                height = (size_t)image.textureSize.height;
+            }
+        }
+        [frameHandler invokeWithWidth:width height:height];
+    };
+    SYPHON_GL_CLIENT *client = nil;
+    @try {
+        client = [[SYPHON_GL_CLIENT alloc] initWithServerDescription:desc
+                                                             context:context
+                                                             options:(__bridge NSDictionary *)options
+                                                     newFrameHandler:handler];
+    } @catch (NSException *e) {
+        glue_set_error([NSString stringWithFormat:@"%@: %@", e.name, e.reason]);
     }
-    SYPHON_GL_CLIENT *client = [[SYPHON_GL_CLIENT alloc] initWithServerDescription:desc
-                                                                             context:context
-                                                                             options:(__bridge NSDictionary *)options
-                                                                       newFrameHandler:handler];
+    if (!client && !glue_last_error) glue_set_error(@"Syphon OpenGL client init returned nil");
+    frame_handler_attach(client, slot);
     return (__bridge_retained void *)client;
 }
 
+void syphon_opengl_client_set_new_frame_handler(void *client, new_frame_callback_t new_frame_callback,
+    new_frame_release_t release_userdata, void *userdata, bool direct_dispatch, bool report_size) {
+    frame_handler_set((__bridge SYPHON_GL_CLIENT *)client, new_frame_callback, release_userdata, userdata, direct_dispatch,
+        report_size);
+}
+
+void syphon_opengl_client_unregister_new_frame_handler(void *client) {
+    frame_handler_detach((__bridge SYPHON_GL_CLIENT *)client);
+}
+
 void syphon_opengl_client_release(void *client) {
-    (void)(__bridge_transfer SYPHON_GL_CLIENT *)client;
+    syphon_opengl_client_stop(client);
+    glue_release_object(client);
 }
 
 bool syphon_opengl_client_is_valid(void *client) {
@@ -280,15 +954,59 @@ bool syphon_opengl_client_has_new_frame(void *client) {
     return c.hasNewFrame ? true : false;
 }
 
-void *syphon_opengl_client_new_frame_image(void *client) {
+/* Copy a w x h region of rectangle texture src into dst (bound to dst_target) at the origin. */
+static bool glue_gl_copy_rectangle_to_texture(GLuint src, GLsizei w, GLsizei h, GLuint dst, GLenum dst_target) {
+    GLuint fbo = 0;
+    glGenFramebuffers(1, &fbo);
+    glBindFramebuffer(GL_FRAMEBUFFER, fbo);
+    glFramebufferTexture2D(GL_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, GL_TEXTURE_RECTANGLE, src, 0);
+    bool ok = glCheckFramebufferStatus(GL_FRAMEBUFFER) == GL_FRAMEBUFFER_COMPLETE;
+    if (ok) {
+        glBindTexture(dst_target, dst);
+        glCopyTexSubImage2D(dst_target, 0, 0, 0, 0, 0, w, h);
+        glBindTexture(dst_target, 0);
+        ok = glGetError() == GL_NO_ERROR;
+    }
+    glFramebufferTexture2D(GL_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, GL_TEXTURE_RECTANGLE, 0, 0);
+    glBindFramebuffer(GL_FRAMEBUFFER, 0);
+    glDeleteFramebuffers(1, &fbo);
+    return ok;
+}
+
+/* True if dst (bound to dst_target) is an RGBA color texture of at least w x h. */
+static bool glue_gl_texture_can_hold(GLuint dst, GLenum dst_target, GLsizei w, GLsizei h) {
+    GLint dst_w = 0, dst_h = 0, dst_format = 0;
+    glBindTexture(dst_target, dst);
+    glGetTexLevelParameteriv(dst_target, 0, GL_TEXTURE_WIDTH, &dst_w);
+    glGetTexLevelParameteriv(dst_target, 0, GL_TEXTURE_HEIGHT, &dst_h);
+    glGetTexLevelParameteriv(dst_target, 0, GL_TEXTURE_INTERNAL_FORMAT, &dst_format);
+    glBindTexture(dst_target, 0);
+    bool format_ok = dst_format == GL_RGBA8 || dst_format == GL_RGBA || dst_format == GL_SRGB8_ALPHA8
+        || dst_format == GL_RGBA16F_ARB || dst_format == GL_RGBA32F_ARB;
+    return format_ok && dst_w >= w && dst_h >= h;
+}
+
+bool syphon_opengl_client_copy_frame_to(void *client, GLuint dst_tex, GLenum dst_target) {
     SYPHON_GL_CLIENT *c = (__bridge SYPHON_GL_CLIENT *)client;
+    if (dst_tex == 0) return false;
     SYPHON_GL_IMAGE *img = [c newFrameImage];
+    if (!img) return false;
+    GLsizei w = (GLsizei)img.textureSize.width;
+    GLsizei h = (GLsizei)img.textureSize.height;
+    if (!glue_gl_texture_can_hold(dst_tex, dst_target, w, h)) return false;
+    return glue_gl_copy_rectangle_to_texture(img.textureName, w, h, dst_tex, dst_target);
+}
+
+void *syphon_opengl_client_new_frame_image(void *client) {
+    SYPHON_GL_CLIENT *c = (__bridge SYPHON_GL_CLIENT *)client;
+    SYPHON_GL_IMAGE *img = nil;
+    @autoreleasepool { img = [c newFrameImage]; }
     return (__bridge_retained void *)img;
 }
 
 void syphon_opengl_client_stop(void *client) {
     SYPHON_GL_CLIENT *c = (__bridge SYPHON_GL_CLIENT *)client;
-    [c stop];
+    if (glue_mark_stopped(c)) [c stop];
 }
 
 CGLContextObj syphon_opengl_client_context(void *client) {
@@ -319,19 +1037,100 @@ void syphon_opengl_image_texture_size(void *image, double *out_w, double *out_h)
     if (out_h) *out_h = size.height;
 }
 
+void *syphon_opengl_image_io_surface(void *image) {
+    SYPHON_GL_IMAGE *img = (__bridge SYPHON_GL_IMAGE *)image;
+    /* Syphon's concrete image classes keep their surface in a `surface` property that isn't in the public header. */
+    SEL sel = NSSelectorFromString(@"surface");
+    if (!img || ![img respondsToSelector:sel]) return NULL;
+    IOSurfaceRef (*get_surface)(id, SEL) = (IOSurfaceRef (*)(id, SEL))[img methodForSelector:sel];
+    return (void *)get_surface(img, sel);
+}
+
+bool syphon_opengl_image_blit_to_texture(void *image, GLuint dst_tex, GLenum dst_target, size_t width, size_t height) {
+    SYPHON_GL_IMAGE *img = (__bridge SYPHON_GL_IMAGE *)image;
+    GLint src_w = (GLint)img.textureSize.width;
+    GLint src_h = (GLint)img.textureSize.height;
+    if (!img || dst_tex == 0 || src_w <= 0 || src_h <= 0 || width == 0 || height == 0) return false;
+    GLint prev_read = 0, prev_draw = 0;
+    glGetIntegerv(GL_READ_FRAMEBUFFER_BINDING, &prev_read);
+    glGetIntegerv(GL_DRAW_FRAMEBUFFER_BINDING, &prev_draw);
+    GLuint fbos[2] = {0, 0};
+    glGenFramebuffers(2, fbos);
+    glBindFramebuffer(GL_READ_FRAMEBUFFER, fbos[0]);
+    glFramebufferTexture2D(GL_READ_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, GL_TEXTURE_RECTANGLE, img.textureName, 0);
+    glBindFramebuffer(GL_DRAW_FRAMEBUFFER, fbos[1]);
+    glFramebufferTexture2D(GL_DRAW_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, dst_target, dst_tex, 0);
+    bool ok = glCheckFramebufferStatus(GL_READ_FRAMEBUFFER) == GL_FRAMEBUFFER_COMPLETE
+        && glCheckFramebufferStatus(GL_DRAW_FRAMEBUFFER) == GL_FRAMEBUFFER_COMPLETE;
+    if (ok) {
+        bool scaled = (size_t)src_w != width || (size_t)src_h != height;
+        glBlitFramebuffer(0, 0, src_w, src_h, 0, 0, (GLint)width, (GLint)height, GL_COLOR_BUFFER_BIT,
+                          scaled ? GL_LINEAR : GL_NEAREST);
+        ok = glGetError() == GL_NO_ERROR;
+    }
+    glBindFramebuffer(GL_READ_FRAMEBUFFER, (GLuint)prev_read);
+    glBindFramebuffer(GL_DRAW_FRAMEBUFFER, (GLuint)prev_draw);
+    glDeleteFramebuffers(2, fbos);
+    return ok;
+}
+
+GLuint syphon_opengl_image_copy_to_texture_2d(void *image, GLuint dst_tex) {
+    SYPHON_GL_IMAGE *img = (__bridge SYPHON_GL_IMAGE *)image;
+    GLsizei w = (GLsizei)img.textureSize.width;
+    GLsizei h = (GLsizei)img.textureSize.height;
+    if (w <= 0 || h <= 0) return 0;
+    bool created = false;
+    if (dst_tex == 0) {
+        glGenTextures(1, &dst_tex);
+        if (dst_tex == 0) return 0;
+        glBindTexture(GL_TEXTURE_2D, dst_tex);
+        glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_MIN_FILTER, GL_LINEAR);
+        glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, GL_LINEAR);
+        glTexImage2D(GL_TEXTURE_2D, 0, GL_RGBA8, w, h, 0, GL_RGBA, GL_UNSIGNED_BYTE, NULL);
+        glBindTexture(GL_TEXTURE_2D, 0);
+        created = true;
+    } else if (!glue_gl_texture_can_hold(dst_tex, GL_TEXTURE_2D, w, h)) {
+        return 0;
+    }
+    if (!glue_gl_copy_rectangle_to_texture(img.textureName, w, h, dst_tex, GL_TEXTURE_2D)) {
+        if (created) glDeleteTextures(1, &dst_tex);
+        return 0;
+    }
+    return dst_tex;
+}
+
 #if SYPHON_HAS_METAL
 /* Metal server */
 void *syphon_metal_server_create(const char *name, void *device, void *options) {
+    glue_set_error(nil);
     NSString *nsName = nullable_cstring_to_nsstring(name);
     id<MTLDevice> mtlDevice = (__bridge id<MTLDevice>)device;
-    SyphonMetalServer *server = [[SyphonMetalServer alloc] initWithName:nsName
-                                                                  device:mtlDevice
-                                                                 options:(__bridge NSDictionary *)options];
+    if (!mtlDevice) {
+        glue_set_error(@"no MTLDevice");
+        return NULL;
+    }
+    SyphonMetalServer *server = nil;
+    @try {
+        server = [[SyphonMetalServer alloc] initWithName:nsName device:mtlDevice options:(__bridge NSDictionary *)options];
+    } @catch (NSException *e) {
+        glue_set_error([NSString stringWithFormat:@"%@: %@", e.name, e.reason]);
+    }
+    if (!server && !glue_last_error) glue_set_error(@"Syphon Metal server init returned nil");
     return (__bridge_retained void *)server;
 }
 
+void *syphon_metal_server_create_with_default_device(const char *name, void *options) {
+    id<MTLDevice> device = MTLCreateSystemDefaultDevice();
+    if (!device) {
+        glue_set_error(@"no system default MTLDevice");
+        return NULL;
+    }
+    return syphon_metal_server_create(name, (__bridge void *)device, options);
+}
+
 void syphon_metal_server_release(void *server) {
-    (void)(__bridge_transfer SyphonMetalServer *)server;
+    syphon_metal_server_stop(server);
+    glue_release_object(server);
 }
 
 bool syphon_metal_server_has_clients(void *server) {
@@ -350,8 +1149,196 @@ void syphon_metal_server_publish_frame(void *server, void *texture, void *comman
     SyphonMetalServer *s = (__bridge SyphonMetalServer *)server;
     id<MTLTexture> mtlTexture = (__bridge id<MTLTexture>)texture;
     id<MTLCommandBuffer> mtlCmdBuf = (__bridge id<MTLCommandBuffer>)command_buffer;
-    NSRect region = NSMakeRect(x, y, w, h);
-    [s publishFrameTexture:mtlTexture onCommandBuffer:mtlCmdBuf imageRegion:region flipped:flipped ? YES : NO];
+    @autoreleasepool {
+        [s publishFrameTexture:mtlTexture onCommandBuffer:mtlCmdBuf imageRegion:NSMakeRect(x, y, w, h)
+                       flipped:flipped];
+    }
+}
+
+bool syphon_metal_texture_write_region_rgba8(void *texture, void *command_buffer, size_t x, size_t y,
+    size_t width, size_t height, const unsigned char *rgba);
+
+/* Frame the dirty-region path keeps per Metal server; regions are uploaded into it and it is published whole. */
+static char kSyphonGlueBackingKey;
+
+bool syphon_metal_server_publish_rgba8_region(void *server, void *command_buffer, size_t frame_w, size_t frame_h,
+    size_t x, size_t y, size_t w, size_t h, const unsigned char *rgba) {
+    SyphonMetalServer *s = (__bridge SyphonMetalServer *)server;
+    id<MTLCommandBuffer> cb = (__bridge id<MTLCommandBuffer>)command_buffer;
+    if (!s || !cb || frame_w == 0 || frame_h == 0 || x + w > frame_w || y + h > frame_h) return false;
+    id<MTLTexture> backing = objc_getAssociatedObject(s, &kSyphonGlueBackingKey);
+    if (!backing || backing.width != frame_w || backing.height != frame_h) {
+        /* A new backing texture has undefined contents: the first region must cover the frame. */
+        if (x != 0 || y != 0 || w != frame_w || h != frame_h) return false;
+        MTLTextureDescriptor *desc = [MTLTextureDescriptor texture2DDescriptorWithPixelFormat:MTLPixelFormatRGBA8Unorm
+                                                                                        width:frame_w
+                                                                                       height:frame_h
+                                                                                    mipmapped:NO];
+        desc.storageMode = MTLStorageModePrivate;
+        desc.usage = MTLTextureUsageShaderRead;
+        backing = [s.device newTextureWithDescriptor:desc];
+        if (!backing) return false;
+        objc_setAssociatedObject(s, &kSyphonGlueBackingKey, backing, OBJC_ASSOCIATION_RETAIN_NONATOMIC);
+    }
+    if (w > 0 && h > 0 && !syphon_metal_texture_write_region_rgba8((__bridge void *)backing, command_buffer, x, y, w, h, rgba))
+        return false;
+    @autoreleasepool {
+        [s publishFrameTexture:backing onCommandBuffer:cb imageRegion:NSMakeRect(0, 0, frame_w, frame_h) flipped:NO];
+    }
+    return true;
+}
+
+/* Queue and staging frame the glue keeps per Metal server for publishing from CPU memory. */
+static char kSyphonGlueQueueKey;
+static char kSyphonGlueStagingKey;
+
+static id<MTLCommandQueue> glue_metal_server_queue(SyphonMetalServer *s) {
+    id<MTLCommandQueue> queue = objc_getAssociatedObject(s, &kSyphonGlueQueueKey);
+    if (!queue) {
+        queue = [s.device newCommandQueue];
+        if (queue) objc_setAssociatedObject(s, &kSyphonGlueQueueKey, queue, OBJC_ASSOCIATION_RETAIN_NONATOMIC);
+    }
+    return queue;
+}
+
+bool syphon_metal_server_set_command_queue(void *server, void *command_queue) {
+    SyphonMetalServer *s = (__bridge SyphonMetalServer *)server;
+    id<MTLCommandQueue> queue = (__bridge id<MTLCommandQueue>)command_queue;
+    if (!s) return false;
+    if (!queue) queue = [s.device newCommandQueue];
+    if (!queue || queue.device != s.device) return false;
+    objc_setAssociatedObject(s, &kSyphonGlueQueueKey, queue, OBJC_ASSOCIATION_RETAIN_NONATOMIC);
+    return true;
+}
+
+void *syphon_metal_server_command_queue(void *server) {
+    SyphonMetalServer *s = (__bridge SyphonMetalServer *)server;
+    return s ? (__bridge void *)glue_metal_server_queue(s) : NULL;
+}
+
+bool syphon_metal_server_publish_frame_simple(void *server, void *texture, double x, double y, double w, double h,
+    bool flipped) {
+    SyphonMetalServer *s = (__bridge SyphonMetalServer *)server;
+    id<MTLTexture> tex = (__bridge id<MTLTexture>)texture;
+    if (!s || !tex) return false;
+    id<MTLCommandQueue> queue = glue_metal_server_queue(s);
+    if (!queue) return false;
+    @autoreleasepool {
+        id<MTLCommandBuffer> cb = [queue commandBuffer];
+        [s publishFrameTexture:tex onCommandBuffer:cb imageRegion:NSMakeRect(x, y, w, h) flipped:flipped];
+        [cb commit];
+    }
+    return true;
+}
+
+bool syphon_metal_server_publish_frame_after_event(void *server, void *texture, void *command_buffer,
+    void *shared_event, uint64_t value, double x, double y, double w, double h, bool flipped) {
+    SyphonMetalServer *s = (__bridge SyphonMetalServer *)server;
+    id<MTLTexture> tex = (__bridge id<MTLTexture>)texture;
+    id<MTLCommandBuffer> cb = (__bridge id<MTLCommandBuffer>)command_buffer;
+    id<MTLSharedEvent> event = (__bridge id<MTLSharedEvent>)shared_event;
+    if (!s || !tex || ![(id)event conformsToProtocol:@protocol(MTLSharedEvent)]) return false;
+    @autoreleasepool {
+        bool commit = !cb;
+        if (commit) {
+            id<MTLCommandQueue> queue = glue_metal_server_queue(s);
+            if (!queue) return false;
+            cb = [queue commandBuffer];
+        }
+        [cb encodeWaitForEvent:event value:value];
+        [s publishFrameTexture:tex onCommandBuffer:cb imageRegion:NSMakeRect(x, y, w, h) flipped:flipped];
+        if (commit) [cb commit];
+    }
+    return true;
+}
+
+bool syphon_metal_server_publish_pixels(void *server, const unsigned char *rgba, size_t width, size_t height,
+    bool flipped) {
+    SyphonMetalServer *s = (__bridge SyphonMetalServer *)server;
+    if (!s || !rgba || width == 0 || height == 0) return false;
+    id<MTLCommandQueue> queue = glue_metal_server_queue(s);
+    if (!queue) return false;
+    id<MTLTexture> staging = objc_getAssociatedObject(s, &kSyphonGlueStagingKey);
+    if (!staging || staging.width != width || staging.height != height) {
+        MTLTextureDescriptor *desc = [MTLTextureDescriptor texture2DDescriptorWithPixelFormat:MTLPixelFormatRGBA8Unorm
+                                                                                        width:width
+                                                                                       height:height
+                                                                                    mipmapped:NO];
+        desc.storageMode = MTLStorageModePrivate;
+        desc.usage = MTLTextureUsageShaderRead;
+        staging = [s.device newTextureWithDescriptor:desc];
+        if (!staging) return false;
+        objc_setAssociatedObject(s, &kSyphonGlueStagingKey, staging, OBJC_ASSOCIATION_RETAIN_NONATOMIC);
+    }
+    @autoreleasepool {
+        /* The upload is a blit on the same queue, so it can't overwrite a frame Syphon is still copying. */
+        id<MTLCommandBuffer> cb = [queue commandBuffer];
+        if (!syphon_metal_texture_write_region_rgba8((__bridge void *)staging, (__bridge void *)cb, 0, 0, width, height,
+                rgba))
+            return false;
+        [s publishFrameTexture:staging onCommandBuffer:cb imageRegion:NSMakeRect(0, 0, width, height) flipped:flipped];
+        [cb commit];
+    }
+    return true;
+}
+
+#if SYPHON_GLUE_CORE_IMAGE
+static char kSyphonGlueCIContextKey;
+static char kSyphonGlueCIStagingKey;
+
+bool syphon_metal_server_publish_ci_image(void *server, void *image) {
+    SyphonMetalServer *s = (__bridge SyphonMetalServer *)server;
+    CIImage *ciImage = (__bridge CIImage *)image;
+    if (!s || !ciImage || CGRectIsInfinite(ciImage.extent) || CGRectIsEmpty(ciImage.extent)) return false;
+    id<MTLCommandQueue> queue = glue_metal_server_queue(s);
+    if (!queue) return false;
+    CIContext *context = objc_getAssociatedObject(s, &kSyphonGlueCIContextKey);
+    if (!context) {
+        context = [CIContext contextWithMTLCommandQueue:queue];
+        if (!context) return false;
+        objc_setAssociatedObject(s, &kSyphonGlueCIContextKey, context, OBJC_ASSOCIATION_RETAIN_NONATOMIC);
+    }
+    CGRect extent = CGRectIntegral(ciImage.extent);
+    size_t width = (size_t)extent.size.width, height = (size_t)extent.size.height;
+    id<MTLTexture> staging = objc_getAssociatedObject(s, &kSyphonGlueCIStagingKey);
+    if (!staging || staging.width != width || staging.height != height) {
+        MTLTextureDescriptor *desc = [MTLTextureDescriptor texture2DDescriptorWithPixelFormat:MTLPixelFormatBGRA8Unorm
+                                                                                        width:width
+                                                                                       height:height
+                                                                                    mipmapped:NO];
+        desc.storageMode = MTLStorageModePrivate;
+        desc.usage = MTLTextureUsageShaderRead | MTLTextureUsageShaderWrite | MTLTextureUsageRenderTarget;
+        staging = [s.device newTextureWithDescriptor:desc];
+        if (!staging) return false;
+        objc_setAssociatedObject(s, &kSyphonGlueCIStagingKey, staging, OBJC_ASSOCIATION_RETAIN_NONATOMIC);
+    }
+    @autoreleasepool {
+        CGColorSpaceRef colorSpace = CGColorSpaceCreateWithName(kCGColorSpaceSRGB);
+        id<MTLCommandBuffer> cb = [queue commandBuffer];
+        [context render:ciImage toMTLTexture:staging commandBuffer:cb bounds:extent colorSpace:colorSpace];
+        CGColorSpaceRelease(colorSpace);
+        /* Core Image renders with a bottom-left origin, so the texture's first row is the image's bottom row. */
+        [s publishFrameTexture:staging onCommandBuffer:cb imageRegion:NSMakeRect(0, 0, width, height) flipped:YES];
+        [cb commit];
+    }
+    return true;
+}
+#endif /* SYPHON_GLUE_CORE_IMAGE */
+
+bool syphon_metal_server_publish_drawable(void *server, void *drawable, void *command_buffer, bool flipped) {
+    SyphonMetalServer *s = (__bridge SyphonMetalServer *)server;
+    id<CAMetalDrawable> mtlDrawable = (__bridge id<CAMetalDrawable>)drawable;
+    id<MTLCommandBuffer> mtlCmdBuf = (__bridge id<MTLCommandBuffer>)command_buffer;
+    if (![mtlDrawable conformsToProtocol:@protocol(CAMetalDrawable)]) return false;
+    id<MTLTexture> tex = mtlDrawable.texture;
+    /* Syphon samples/blits from the texture; framebuffer-only drawables (CAMetalLayer.framebufferOnly = YES)
+     * are neither readable by shaders nor valid blit sources. */
+    if (!tex || tex.isFramebufferOnly || !(tex.usage & MTLTextureUsageShaderRead)) return false;
+    @autoreleasepool {
+        [s publishFrameTexture:tex onCommandBuffer:mtlCmdBuf
+                   imageRegion:NSMakeRect(0, 0, (CGFloat)tex.width, (CGFloat)tex.height) flipped:flipped];
+    }
+    return true;
 }
 
 void *syphon_metal_server_new_frame_image(void *server) {
@@ -362,7 +1349,7 @@ void *syphon_metal_server_new_frame_image(void *server) {
 
 void syphon_metal_server_stop(void *server) {
     SyphonMetalServer *s = (__bridge SyphonMetalServer *)server;
-    [s stop];
+    if (glue_mark_stopped(s)) [s stop];
 }
 
 void *syphon_metal_server_device(void *server) {
@@ -370,6 +1357,13 @@ void *syphon_metal_server_device(void *server) {
     return (__bridge void *)s.device;
 }
 
+unsigned long syphon_metal_server_pixel_format(void *server) {
+    SyphonMetalServer *s = (__bridge SyphonMetalServer *)server;
+    unsigned long format = 0;
+    @autoreleasepool { format = (unsigned long)[s newFrameImage].pixelFormat; }
+    return format;
+}
+
 char *syphon_metal_server_copy_name(void *server) {
     SyphonMetalServer *s = (__bridge SyphonMetalServer *)server;
     return copy_nsstring_to_cstring(s.name);
@@ -382,29 +1376,59 @@ void syphon_metal_server_set_name(void *server, const char *name) {
 
 /* Metal client */
 void *syphon_metal_client_create(void *server_description, void *device,
-    void *options, new_frame_callback_t new_frame_callback, void *userdata) {
+    void *options, new_frame_callback_t new_frame_callback, new_frame_release_t release_userdata,
+    void *userdata, bool direct_dispatch, bool report_size) {
     NSDictionary *desc = (__bridge NSDictionary *)server_description;
     id<MTLDevice> mtlDevice = (__bridge id<MTLDevice>)device;
-    void (^handler)(SyphonMetalClient *);
-    if (new_frame_callback) {
-        new_frame_callback_t cb = new_frame_callback;
-        void *ud = userdata;
-        handler = ^(SyphonMetalClient *client) {
-            (void)client;
-            cb(ud);
-        };
-    } else {
-        handler = nil;
+    glue_set_error(nil);
+    if (!desc || !mtlDevice) {
+        glue_set_error(desc ? @"no MTLDevice" : @"no server description");
+        if (release_userdata) release_userdata(userdata);
+        return NULL;
+    }
+    SyphonGlueFrameHandlerSlot *slot = [[SyphonGlueFrameHandlerSlot alloc] init];
+    [slot setHandler:frame_handler_create(new_frame_callback, release_userdata, userdata, direct_dispatch,
+                                           report_size)];
+    void (^handler)(SyphonMetalClient *) = ^(SyphonMetalClient *client) {
+        SyphonGlueFrameHandler *frameHandler = [slot handler];
+        if (!frameHandler) return;
+        size_t width = 0, height = 0;
+        if (frameHandler.reportSize) {
+            @autoreleasepool {
+                id<MTLTexture> texture = [client newFrameImage];
+                width = texture.width;
+                height = texture.height;
+            }
+        }
+        [frameHandler invokeWithWidth:width height:height];
+    };
+    SyphonMetalClient *client = nil;
+    @try {
+        client = [[SyphonMetalClient alloc] initWithServerDescription:desc
+                                                               device:mtlDevice
+                                                              options:(__bridge NSDictionary *)options
+                                                      newFrameHandler:handler];
+    } @catch (NSException *e) {
+        glue_set_error([NSString stringWithFormat:@"%@: %@", e.name, e.reason]);
     }
-    SyphonMetalClient *client = [[SyphonMetalClient alloc] initWithServerDescription:desc
-                                                                             device:mtlDevice
-                                                                            options:(__bridge NSDictionary *)options
-                                                                    newFrameHandler:handler];
+    if (!client && !glue_last_error) glue_set_error(@"Syphon Metal client init returned nil");
+    frame_handler_attach(client, slot);
     return (__bridge_retained void *)client;
 }
 
+void syphon_metal_client_set_new_frame_handler(void *client, new_frame_callback_t new_frame_callback,
+    new_frame_release_t release_userdata, void *userdata, bool direct_dispatch, bool report_size) {
+    frame_handler_set((__bridge SyphonMetalClient *)client, new_frame_callback, release_userdata, userdata, direct_dispatch,
+        report_size);
+}
+
+void syphon_metal_client_unregister_new_frame_handler(void *client) {
+    frame_handler_detach((__bridge SyphonMetalClient *)client);
+}
+
 void syphon_metal_client_release(void *client) {
-    (void)(__bridge_transfer SyphonMetalClient *)client;
+    syphon_metal_client_stop(client);
+    glue_release_object(client);
 }
 
 bool syphon_metal_client_is_valid(void *client) {
@@ -417,15 +1441,41 @@ bool syphon_metal_client_has_new_frame(void *client) {
     return c.hasNewFrame ? true : false;
 }
 
+bool syphon_metal_client_copy_frame_to(void *client, void *dst_texture, void *command_buffer) {
+    SyphonMetalClient *c = (__bridge SyphonMetalClient *)client;
+    id<MTLTexture> dst = (__bridge id<MTLTexture>)dst_texture;
+    id<MTLCommandBuffer> mtlCmdBuf = (__bridge id<MTLCommandBuffer>)command_buffer;
+    id<MTLTexture> src = [c newFrameImage];
+    if (!src || !dst || !mtlCmdBuf) return false;
+    if (dst.pixelFormat != src.pixelFormat || dst.sampleCount != 1 || dst.isFramebufferOnly
+        || dst.width < src.width || dst.height < src.height) {
+        return false;
+    }
+    id<MTLBlitCommandEncoder> blit = [mtlCmdBuf blitCommandEncoder];
+    if (!blit) return false;
+    [blit copyFromTexture:src
+              sourceSlice:0
+              sourceLevel:0
+             sourceOrigin:MTLOriginMake(0, 0, 0)
+               sourceSize:MTLSizeMake(src.width, src.height, 1)
+                toTexture:dst
+         destinationSlice:0
+         destinationLevel:0
+        destinationOrigin:MTLOriginMake(0, 0, 0)];
+    [blit endEncoding];
+    return true;
+}
+
 void *syphon_metal_client_new_frame_image(void *client) {
     SyphonMetalClient *c = (__bridge SyphonMetalClient *)client;
-    id<MTLTexture> tex = [c newFrameImage];
+    id<MTLTexture> tex = nil;
+    @autoreleasepool { tex = [c newFrameImage]; }
     return (__bridge_retained void *)tex;
 }
 
 void syphon_metal_client_stop(void *client) {
     SyphonMetalClient *c = (__bridge SyphonMetalClient *)client;
-    [c stop];
+    if (glue_mark_stopped(c)) [c stop];
 }
 
 void *syphon_metal_client_server_description(void *client) {
@@ -437,42 +1487,461 @@ void *syphon_metal_client_server_description(void *client) {
 void syphon_metal_texture_release(void *texture) {
     (void)(__bridge_transfer id)texture;
 }
-#else
-/* Stubs when framework has no Metal support (older Syphon) */
-void *syphon_metal_server_create(const char *name, void *device, void *options) { (void)name;(void)device;(void)options; return NULL; }
-void syphon_metal_server_release(void *server) { (void)server; }
-bool syphon_metal_server_has_clients(void *server) { (void)server; return false; }
-void *syphon_metal_server_server_description(void *server) { (void)server; return NULL; }
-void syphon_metal_server_publish_frame(void *server, void *texture, void *command_buffer, double x, double y, double w, double h, bool flipped) { (void)server;(void)texture;(void)command_buffer;(void)x;(void)y;(void)w;(void)h;(void)flipped; }
-void *syphon_metal_server_new_frame_image(void *server) { (void)server; return NULL; }
-void syphon_metal_server_stop(void *server) { (void)server; }
-void *syphon_metal_server_device(void *server) { (void)server; return NULL; }
-char *syphon_metal_server_copy_name(void *server) { (void)server; return NULL; }
-void syphon_metal_server_set_name(void *server, const char *name) { (void)server;(void)name; }
-void *syphon_metal_client_create(void *server_description, void *device, void *options, void (*new_frame_callback)(void *), void *userdata) { (void)server_description;(void)device;(void)options;(void)new_frame_callback;(void)userdata; return NULL; }
-void syphon_metal_client_release(void *client) { (void)client; }
-bool syphon_metal_client_is_valid(void *client) { (void)client; return false; }
-bool syphon_metal_client_has_new_frame(void *client) { (void)client; return false; }
-void *syphon_metal_client_new_frame_image(void *client) { (void)client; return NULL; }
-void syphon_metal_client_stop(void *client) { (void)client; }
-void *syphon_metal_client_server_description(void *client) { (void)client; return NULL; }
-void syphon_metal_texture_release(void *texture) { (void)texture; }
-#endif /* SYPHON_HAS_METAL */
 
-/* CGL headless context (for tests) */
-CGLContextObj syphon_cgl_create_headless_context(void) {
-    CGLPixelFormatAttribute attrs[] = {
-        kCGLPFAOpenGLProfile, (CGLPixelFormatAttribute)kCGLOGLPVersion_3_2_Core,
-        kCGLPFAAccelerated,
-        (CGLPixelFormatAttribute)0
+void syphon_metal_texture_retain(void *texture) {
+    (void)CFBridgingRetain((__bridge id)texture);
+}
+
+unsigned long syphon_metal_texture_pixel_format(void *texture) {
+    id<MTLTexture> tex = (__bridge id<MTLTexture>)texture;
+    return (unsigned long)tex.pixelFormat;
+}
+
+void syphon_metal_texture_size(void *texture, size_t *out_w, size_t *out_h) {
+    id<MTLTexture> tex = (__bridge id<MTLTexture>)texture;
+    if (out_w) *out_w = tex.width;
+    if (out_h) *out_h = tex.height;
+}
+
+unsigned long syphon_metal_texture_usage(void *texture) {
+    id<MTLTexture> tex = (__bridge id<MTLTexture>)texture;
+    return (unsigned long)tex.usage;
+}
+
+unsigned long syphon_metal_texture_storage_mode(void *texture) {
+    id<MTLTexture> tex = (__bridge id<MTLTexture>)texture;
+    return (unsigned long)tex.storageMode;
+}
+#if SYPHON_GLUE_SCREEN_CAPTURE
+/* ScreenCaptureKit capture into a Metal server */
+API_AVAILABLE(macos(12.3))
+@interface SyphonGlueScreenCapture : NSObject <SCStreamOutput>
+@property (nonatomic, strong) SCStream *stream;
+@property (nonatomic, strong) SyphonMetalServer *server;
+@property (nonatomic, strong) dispatch_queue_t queue;
+@end
+
+@implementation SyphonGlueScreenCapture
+- (void)stream:(SCStream *)stream didOutputSampleBuffer:(CMSampleBufferRef)sampleBuffer ofType:(SCStreamOutputType)type {
+    if (type != SCStreamOutputTypeScreen || !CMSampleBufferIsValid(sampleBuffer)) return;
+    /* Idle and blank frames carry no new image: only complete frames are published. */
+    CFArrayRef attachments = CMSampleBufferGetSampleAttachmentsArray(sampleBuffer, false);
+    if (!attachments || CFArrayGetCount(attachments) == 0) return;
+    NSDictionary *info = (__bridge NSDictionary *)CFArrayGetValueAtIndex(attachments, 0);
+    if ([info[SCStreamFrameInfoStatus] integerValue] != SCFrameStatusComplete) return;
+    CVPixelBufferRef pixelBuffer = CMSampleBufferGetImageBuffer(sampleBuffer);
+    IOSurfaceRef surface = pixelBuffer ? CVPixelBufferGetIOSurface(pixelBuffer) : NULL;
+    if (!surface) return;
+    SyphonMetalServer *s = self.server;
+    id<MTLCommandQueue> queue = glue_metal_server_queue(s);
+    if (!queue) return;
+    @autoreleasepool {
+        id<MTLTexture> tex = (__bridge_transfer id<MTLTexture>)syphon_metal_texture_create_with_io_surface(
+            (__bridge void *)s.device, surface, MTLPixelFormatBGRA8Unorm);
+        if (!tex) return;
+        id<MTLCommandBuffer> cb = [queue commandBuffer];
+        [s publishFrameTexture:tex onCommandBuffer:cb imageRegion:NSMakeRect(0, 0, tex.width, tex.height) flipped:NO];
+        /* ScreenCaptureKit reuses the surface once the sample buffer is released: hold it until the copy ran. */
+        CFRetain(sampleBuffer);
+        [cb addCompletedHandler:^(id<MTLCommandBuffer> buffer) {
+            (void)buffer;
+            CFRelease(sampleBuffer);
+        }];
+        [cb commit];
+    }
+}
+@end
+
+void *syphon_screen_capture_create(void *server, uint32_t display_id, uint32_t window_id, size_t width,
+    size_t height, double frame_rate, bool show_cursor) {
+    glue_set_error(nil);
+    if (@available(macOS 12.3, *)) {
+        SyphonMetalServer *s = (__bridge SyphonMetalServer *)server;
+        if (!s) {
+            glue_set_error(@"no Metal server");
+            return NULL;
+        }
+        SCShareableContent *content = (__bridge_transfer SCShareableContent *)syphon_sc_content_copy();
+        if (!content) return NULL;
+        SCContentFilter *filter = nil;
+        size_t nativeW = 0, nativeH = 0;
+        if (window_id) {
+            for (SCWindow *w in content.windows) {
+                if (w.windowID != window_id) continue;
+                filter = [[SCContentFilter alloc] initWithDesktopIndependentWindow:w];
+                CGFloat scale = NSScreen.mainScreen.backingScaleFactor ?: 1;
+                nativeW = (size_t)(w.frame.size.width * scale);
+                nativeH = (size_t)(w.frame.size.height * scale);
+            }
+        } else {
+            for (SCDisplay *d in content.displays) {
+                if (d.displayID != display_id) continue;
+                filter = [[SCContentFilter alloc] initWithDisplay:d excludingWindows:@[]];
+                CGDisplayModeRef mode = CGDisplayCopyDisplayMode(d.displayID);
+                nativeW = mode ? CGDisplayModeGetPixelWidth(mode) : (size_t)d.width;
+                nativeH = mode ? CGDisplayModeGetPixelHeight(mode) : (size_t)d.height;
+                CGDisplayModeRelease(mode);
+            }
+        }
+        if (!filter) {
+            glue_set_error(window_id ? @"no shareable window with that ID" : @"no shareable display with that ID");
+            return NULL;
+        }
+        SCStreamConfiguration *config = [[SCStreamConfiguration alloc] init];
+        config.width = width ?: nativeW;
+        config.height = height ?: nativeH;
+        config.pixelFormat = kCVPixelFormatType_32BGRA;
+        config.showsCursor = show_cursor;
+        if (frame_rate > 0) config.minimumFrameInterval = CMTimeMakeWithSeconds(1.0 / frame_rate, 600000);
+
+        SyphonGlueScreenCapture *capture = [[SyphonGlueScreenCapture alloc] init];
+        capture.server = s;
+        capture.queue = dispatch_queue_create("syphon.glue.screen-capture", DISPATCH_QUEUE_SERIAL);
+        capture.stream = [[SCStream alloc] initWithFilter:filter configuration:config delegate:nil];
+        NSError *error = nil;
+        if (![capture.stream addStreamOutput:capture type:SCStreamOutputTypeScreen
+                          sampleHandlerQueue:capture.queue error:&error]) {
+            glue_set_error(error.localizedDescription ?: @"addStreamOutput failed");
+            return NULL;
+        }
+        dispatch_semaphore_t started = dispatch_semaphore_create(0);
+        __block NSError *startError = nil;
+        [capture.stream startCaptureWithCompletionHandler:^(NSError *e) {
+            startError = e;
+            dispatch_semaphore_signal(started);
+        }];
+        dispatch_semaphore_wait(started, DISPATCH_TIME_FOREVER);
+        if (startError) {
+            [capture.stream removeStreamOutput:capture type:SCStreamOutputTypeScreen error:nil];
+            glue_set_error(startError.localizedDescription);
+            return NULL;
+        }
+        return (__bridge_retained void *)capture;
+    }
+    glue_set_error(@"ScreenCaptureKit needs macOS 12.3 or later");
+    return NULL;
+}
+
+void syphon_screen_capture_release(void *capture) {
+    if (!capture) return;
+    if (@available(macOS 12.3, *)) {
+        SyphonGlueScreenCapture *c = (__bridge_transfer SyphonGlueScreenCapture *)capture;
+        dispatch_semaphore_t stopped = dispatch_semaphore_create(0);
+        [c.stream stopCaptureWithCompletionHandler:^(NSError *e) {
+            (void)e;
+            dispatch_semaphore_signal(stopped);
+        }];
+        dispatch_semaphore_wait(stopped, DISPATCH_TIME_FOREVER);
+        /* The stream holds its output; removing it breaks the cycle. */
+        [c.stream removeStreamOutput:c type:SCStreamOutputTypeScreen error:nil];
+    }
+}
+
+#endif /* SYPHON_GLUE_SCREEN_CAPTURE */
+
+#if SYPHON_GLUE_CAMERA
+/* AVFoundation camera capture into a Metal server */
+static NSArray<AVCaptureDevice *> *glue_av_video_devices(void);
+
+@interface SyphonGlueCameraCapture : NSObject <AVCaptureVideoDataOutputSampleBufferDelegate>
+@property (nonatomic, strong) AVCaptureSession *session;
+@property (nonatomic, strong) SyphonMetalServer *server;
+@property (nonatomic, strong) dispatch_queue_t queue;
+@property (nonatomic, strong) id<MTLTexture> staging;
+@end
+
+@implementation SyphonGlueCameraCapture
+- (void)captureOutput:(AVCaptureOutput *)output
+    didOutputSampleBuffer:(CMSampleBufferRef)sampleBuffer
+           fromConnection:(AVCaptureConnection *)connection {
+    (void)output;
+    (void)connection;
+    CVPixelBufferRef pixelBuffer = CMSampleBufferGetImageBuffer(sampleBuffer);
+    if (!pixelBuffer) return;
+    SyphonMetalServer *s = self.server;
+    id<MTLCommandQueue> queue = glue_metal_server_queue(s);
+    if (!queue) return;
+    @autoreleasepool {
+        size_t w = CVPixelBufferGetWidth(pixelBuffer), h = CVPixelBufferGetHeight(pixelBuffer);
+        IOSurfaceRef surface = CVPixelBufferGetIOSurface(pixelBuffer);
+        id<MTLTexture> tex = nil;
+        if (surface) {
+            tex = (__bridge_transfer id<MTLTexture>)syphon_metal_texture_create_with_io_surface(
+                (__bridge void *)s.device, surface, MTLPixelFormatBGRA8Unorm);
+        } else {
+            if (!self.staging || self.staging.width != w || self.staging.height != h) {
+                MTLTextureDescriptor *desc =
+                    [MTLTextureDescriptor texture2DDescriptorWithPixelFormat:MTLPixelFormatBGRA8Unorm
+                                                                       width:w
+                                                                      height:h
+                                                                   mipmapped:NO];
+                desc.storageMode = MTLStorageModeManaged;
+                desc.usage = MTLTextureUsageShaderRead;
+                self.staging = [s.device newTextureWithDescriptor:desc];
+            }
+            tex = self.staging;
+            if (tex && CVPixelBufferLockBaseAddress(pixelBuffer, kCVPixelBufferLock_ReadOnly) == kCVReturnSuccess) {
+                [tex replaceRegion:MTLRegionMake2D(0, 0, w, h)
+                       mipmapLevel:0
+                         withBytes:CVPixelBufferGetBaseAddress(pixelBuffer)
+                       bytesPerRow:CVPixelBufferGetBytesPerRow(pixelBuffer)];
+                CVPixelBufferUnlockBaseAddress(pixelBuffer, kCVPixelBufferLock_ReadOnly);
+            } else {
+                tex = nil;
+            }
+        }
+        if (!tex) return;
+        id<MTLCommandBuffer> cb = [queue commandBuffer];
+        [s publishFrameTexture:tex onCommandBuffer:cb imageRegion:NSMakeRect(0, 0, w, h) flipped:NO];
+        /* The capture pool reuses the buffer once the sample buffer is released: hold it until the copy ran. */
+        CFRetain(sampleBuffer);
+        [cb addCompletedHandler:^(id<MTLCommandBuffer> buffer) {
+            (void)buffer;
+            CFRelease(sampleBuffer);
+        }];
+        [cb commit];
+        /* The staging texture is rewritten by the next frame from the CPU: let this copy finish first. */
+        if (!surface) [cb waitUntilCompleted];
+    }
+}
+@end
+
+static bool glue_av_request_camera_access(void) {
+    AVAuthorizationStatus status = [AVCaptureDevice authorizationStatusForMediaType:AVMediaTypeVideo];
+    if (status == AVAuthorizationStatusNotDetermined) {
+        dispatch_semaphore_t answered = dispatch_semaphore_create(0);
+        __block BOOL granted = NO;
+        [AVCaptureDevice requestAccessForMediaType:AVMediaTypeVideo completionHandler:^(BOOL ok) {
+            granted = ok;
+            dispatch_semaphore_signal(answered);
+        }];
+        dispatch_semaphore_wait(answered, DISPATCH_TIME_FOREVER);
+        return granted;
+    }
+    return status == AVAuthorizationStatusAuthorized;
+}
+
+void *syphon_camera_capture_create(void *server, const char *unique_id, size_t width, size_t height,
+    double frame_rate) {
+    glue_set_error(nil);
+    SyphonMetalServer *s = (__bridge SyphonMetalServer *)server;
+    if (!s) {
+        glue_set_error(@"no Metal server");
+        return NULL;
+    }
+    if (!glue_av_request_camera_access()) {
+        glue_set_error(@"camera access denied");
+        return NULL;
+    }
+    AVCaptureDevice *device = nil;
+    if (unique_id) {
+        NSString *wanted = nullable_cstring_to_nsstring(unique_id);
+        for (AVCaptureDevice *d in glue_av_video_devices()) {
+            if ([d.uniqueID isEqualToString:wanted]) device = d;
+        }
+    } else {
+        device = [AVCaptureDevice defaultDeviceWithMediaType:AVMediaTypeVideo];
+    }
+    if (!device) {
+        glue_set_error(unique_id ? @"no video capture device with that unique ID" : @"no video capture device");
+        return NULL;
+    }
+    NSError *error = nil;
+    AVCaptureDeviceInput *input = [AVCaptureDeviceInput deviceInputWithDevice:device error:&error];
+    if (!input) {
+        glue_set_error(error.localizedDescription ?: @"can't open the capture device");
+        return NULL;
+    }
+    SyphonGlueCameraCapture *capture = [[SyphonGlueCameraCapture alloc] init];
+    capture.server = s;
+    capture.queue = dispatch_queue_create("syphon.glue.camera-capture", DISPATCH_QUEUE_SERIAL);
+    capture.session = [[AVCaptureSession alloc] init];
+    AVCaptureVideoDataOutput *output = [[AVCaptureVideoDataOutput alloc] init];
+    output.videoSettings = @{
+        (NSString *)kCVPixelBufferPixelFormatTypeKey : @(kCVPixelFormatType_32BGRA),
+        (NSString *)kCVPixelBufferIOSurfacePropertiesKey : @{},
+        (NSString *)kCVPixelBufferMetalCompatibilityKey : @YES,
     };
+    output.alwaysDiscardsLateVideoFrames = YES;
+    [output setSampleBufferDelegate:capture queue:capture.queue];
+    [capture.session beginConfiguration];
+    if (![capture.session canAddInput:input] || ![capture.session canAddOutput:output]) {
+        [capture.session commitConfiguration];
+        glue_set_error(@"the capture session rejected the device");
+        return NULL;
+    }
+    [capture.session addInput:input];
+    [capture.session addOutput:output];
+    [capture.session commitConfiguration];
+
+    /* Format and rate are set after the session is configured, which would otherwise reset them to a preset. */
+    if ((width || frame_rate > 0) && [device lockForConfiguration:&error]) {
+        if (width && height) {
+            for (AVCaptureDeviceFormat *format in device.formats) {
+                CMVideoDimensions dims = CMVideoFormatDescriptionGetDimensions(format.formatDescription);
+                if ((size_t)dims.width == width && (size_t)dims.height == height) {
+                    device.activeFormat = format;
+                    break;
+                }
+            }
+        }
+        if (frame_rate > 0) {
+            for (AVFrameRateRange *range in device.activeFormat.videoSupportedFrameRateRanges) {
+                if (frame_rate >= range.minFrameRate && frame_rate <= range.maxFrameRate) {
+                    CMTime duration = CMTimeMakeWithSeconds(1.0 / frame_rate, 600000);
+                    device.activeVideoMinFrameDuration = duration;
+                    device.activeVideoMaxFrameDuration = duration;
+                    break;
+                }
+            }
+        }
+        [device unlockForConfiguration];
+    }
+    [capture.session startRunning];
+    if (!capture.session.isRunning) {
+        glue_set_error(@"the capture session failed to start");
+        return NULL;
+    }
+    return (__bridge_retained void *)capture;
+}
+
+void syphon_camera_capture_release(void *capture) {
+    if (!capture) return;
+    SyphonGlueCameraCapture *c = (__bridge_transfer SyphonGlueCameraCapture *)capture;
+    [c.session stopRunning];
+    /* Drain a frame still being published before the capture (and its server reference) goes. */
+    dispatch_sync(c.queue, ^{});
+}
+
+#endif /* SYPHON_GLUE_CAMERA */
+
+#else
+/* Stubs when framework has no Metal support (older Syphon) */
+void *syphon_metal_server_create(const char *name, void *device, void *options) { (void)name;(void)device;(void)options; glue_set_error(@"Syphon framework has no Metal support"); return NULL; }
+void *syphon_metal_server_create_with_default_device(const char *name, void *options) { (void)name;(void)options; glue_set_error(@"Syphon framework has no Metal support"); return NULL; }
+void syphon_metal_server_release(void *server) { (void)server; }
+bool syphon_metal_server_has_clients(void *server) { (void)server; return false; }
+void *syphon_metal_server_server_description(void *server) { (void)server; return NULL; }
+void syphon_metal_server_publish_frame(void *server, void *texture, void *command_buffer, double x, double y, double w, double h, bool flipped) { (void)server;(void)texture;(void)command_buffer;(void)x;(void)y;(void)w;(void)h;(void)flipped; }
+bool syphon_metal_server_publish_rgba8_region(void *server, void *command_buffer, size_t frame_w, size_t frame_h, size_t x, size_t y, size_t w, size_t h, const unsigned char *rgba) { (void)server;(void)command_buffer;(void)frame_w;(void)frame_h;(void)x;(void)y;(void)w;(void)h;(void)rgba; return false; }
+bool syphon_metal_server_set_command_queue(void *server, void *command_queue) { (void)server;(void)command_queue; return false; }
+void *syphon_metal_server_command_queue(void *server) { (void)server; return NULL; }
+bool syphon_metal_server_publish_frame_simple(void *server, void *texture, double x, double y, double w, double h, bool flipped) { (void)server;(void)texture;(void)x;(void)y;(void)w;(void)h;(void)flipped; return false; }
+bool syphon_metal_server_publish_frame_after_event(void *server, void *texture, void *command_buffer, void *shared_event, uint64_t value, double x, double y, double w, double h, bool flipped) { (void)server;(void)texture;(void)command_buffer;(void)shared_event;(void)value;(void)x;(void)y;(void)w;(void)h;(void)flipped; return false; }
+bool syphon_metal_server_publish_pixels(void *server, const unsigned char *rgba, size_t width, size_t height, bool flipped) { (void)server;(void)rgba;(void)width;(void)height;(void)flipped; return false; }
+#if SYPHON_GLUE_CORE_IMAGE
+bool syphon_metal_server_publish_ci_image(void *server, void *image) { (void)server;(void)image; return false; }
+#endif
+bool syphon_metal_server_publish_drawable(void *server, void *drawable, void *command_buffer, bool flipped) { (void)server;(void)drawable;(void)command_buffer;(void)flipped; return false; }
+void *syphon_metal_server_new_frame_image(void *server) { (void)server; return NULL; }
+void syphon_metal_server_stop(void *server) { (void)server; }
+void *syphon_metal_server_device(void *server) { (void)server; return NULL; }
+unsigned long syphon_metal_server_pixel_format(void *server) { (void)server; return 0; }
+char *syphon_metal_server_copy_name(void *server) { (void)server; return NULL; }
+void syphon_metal_server_set_name(void *server, const char *name) { (void)server;(void)name; }
+void *syphon_metal_client_create(void *server_description, void *device, void *options, new_frame_callback_t new_frame_callback, new_frame_release_t release_userdata, void *userdata, bool direct_dispatch, bool report_size) { (void)server_description;(void)device;(void)options;(void)new_frame_callback;(void)direct_dispatch;(void)report_size; glue_set_error(@"Syphon framework has no Metal support"); if (release_userdata) release_userdata(userdata); return NULL; }
+void syphon_metal_client_set_new_frame_handler(void *client, new_frame_callback_t new_frame_callback, new_frame_release_t release_userdata, void *userdata, bool direct_dispatch, bool report_size) { (void)client;(void)new_frame_callback;(void)direct_dispatch;(void)report_size; if (release_userdata) release_userdata(userdata); }
+void syphon_metal_client_unregister_new_frame_handler(void *client) { (void)client; }
+void syphon_metal_client_release(void *client) { (void)client; }
+bool syphon_metal_client_is_valid(void *client) { (void)client; return false; }
+bool syphon_metal_client_has_new_frame(void *client) { (void)client; return false; }
+bool syphon_metal_client_copy_frame_to(void *client, void *dst_texture, void *command_buffer) { (void)client;(void)dst_texture;(void)command_buffer; return false; }
+void *syphon_metal_client_new_frame_image(void *client) { (void)client; return NULL; }
+void syphon_metal_client_stop(void *client) { (void)client; }
+void *syphon_metal_client_server_description(void *client) { (void)client; return NULL; }
+void syphon_metal_texture_release(void *texture) { (void)texture; }
+void syphon_metal_texture_retain(void *texture) { (void)texture; }
+unsigned long syphon_metal_texture_pixel_format(void *texture) { (void)texture; return 0; }
+void syphon_metal_texture_size(void *texture, size_t *out_w, size_t *out_h) { (void)texture; if (out_w) *out_w = 0; if (out_h) *out_h = 0; }
+unsigned long syphon_metal_texture_usage(void *texture) { (void)texture; return 0; }
+unsigned long syphon_metal_texture_storage_mode(void *texture) { (void)texture; return 0; }
+#if SYPHON_GLUE_SCREEN_CAPTURE
+void *syphon_screen_capture_create(void *server, uint32_t display_id, uint32_t window_id, size_t width, size_t height, double frame_rate, bool show_cursor) { (void)server;(void)display_id;(void)window_id;(void)width;(void)height;(void)frame_rate;(void)show_cursor; glue_set_error(@"Syphon framework has no Metal support"); return NULL; }
+void syphon_screen_capture_release(void *capture) { (void)capture; }
+#endif
+#if SYPHON_GLUE_CAMERA
+void *syphon_camera_capture_create(void *server, const char *unique_id, size_t width, size_t height, double frame_rate) { (void)server;(void)unique_id;(void)width;(void)height;(void)frame_rate; glue_set_error(@"Syphon framework has no Metal support"); return NULL; }
+void syphon_camera_capture_release(void *capture) { (void)capture; }
+#endif
+#endif /* SYPHON_HAS_METAL */
+
+void syphon_run_loop_pump(double seconds) {
+    @autoreleasepool {
+        CFRunLoopRunInMode(kCFRunLoopDefaultMode, seconds > 0 ? seconds : 0, false);
+    }
+}
+
+void syphon_run_loop_wait(double seconds) {
+    if (seconds <= 0) return;
+    if ([NSThread isMainThread]) {
+        syphon_run_loop_pump(seconds);
+    } else {
+        [NSThread sleepForTimeInterval:seconds];
+    }
+}
+
+/* Background updates: a crate-owned thread that creates the shared directory (so Syphon registers its
+ * notification observers there) and runs its run loop for the life of the process. */
+static void glue_background_run_loop_main(dispatch_semaphore_t ready) {
+    @autoreleasepool {
+        [NSThread currentThread].name = @"rusty-syphon directory";
+        (void)[SyphonServerDirectory sharedDirectory];
+        /* A run loop without sources returns immediately; keep one timer scheduled. */
+        [NSTimer scheduledTimerWithTimeInterval:3600 repeats:YES block:^(NSTimer *timer) { (void)timer; }];
+    }
+    dispatch_semaphore_signal(ready);
+    for (;;) {
+        @autoreleasepool {
+            CFRunLoopRunInMode(kCFRunLoopDefaultMode, 1.0, false);
+        }
+    }
+}
+
+bool syphon_background_updates_start(void) {
+    static dispatch_once_t once;
+    static bool started = false;
+    dispatch_once(&once, ^{
+        dispatch_semaphore_t ready = dispatch_semaphore_create(0);
+        NSThread *thread = [[NSThread alloc] initWithBlock:^{ glue_background_run_loop_main(ready); }];
+        [thread start];
+        started = dispatch_semaphore_wait(ready, dispatch_time(DISPATCH_TIME_NOW, 5 * NSEC_PER_SEC)) == 0;
+    });
+    return started;
+}
+
+/* CGL headless context (for tests) */
+CGLContextObj syphon_cgl_create_headless_context_with(bool core_profile, bool color_float, uint32_t samples) {
+    CGLPixelFormatAttribute attrs[16];
+    int n = 0;
+    attrs[n++] = kCGLPFAOpenGLProfile;
+    attrs[n++] = (CGLPixelFormatAttribute)(core_profile ? kCGLOGLPVersion_3_2_Core : kCGLOGLPVersion_Legacy);
+    attrs[n++] = kCGLPFAAccelerated;
+    if (color_float) {
+        attrs[n++] = kCGLPFAColorFloat;
+        attrs[n++] = kCGLPFAColorSize;
+        attrs[n++] = (CGLPixelFormatAttribute)64;
+    }
+    if (samples > 0) {
+        attrs[n++] = kCGLPFAMultisample;
+        attrs[n++] = kCGLPFASampleBuffers;
+        attrs[n++] = (CGLPixelFormatAttribute)1;
+        attrs[n++] = kCGLPFASamples;
+        attrs[n++] = (CGLPixelFormatAttribute)samples;
+    }
+    attrs[n] = (CGLPixelFormatAttribute)0;
     CGLPixelFormatObj pix = NULL;
     GLint npix = 0;
-    if (CGLChoosePixelFormat(attrs, &pix, &npix) != kCGLNoError || !pix || npix == 0) {
+    glue_set_error(nil);
+    CGLError err = CGLChoosePixelFormat(attrs, &pix, &npix);
+    if (err != kCGLNoError || !pix || npix == 0) {
+        glue_set_error([NSString stringWithFormat:@"CGLChoosePixelFormat: %s",
+            err != kCGLNoError ? CGLErrorString(err) : "no matching pixel format"]);
         return NULL;
     }
     CGLContextObj ctx = NULL;
-    if (CGLCreateContext(pix, NULL, &ctx) != kCGLNoError) {
+    err = CGLCreateContext(pix, NULL, &ctx);
+    if (err != kCGLNoError) {
+        glue_set_error([NSString stringWithFormat:@"CGLCreateContext: %s", CGLErrorString(err)]);
         CGLDestroyPixelFormat(pix);
         return NULL;
     }
@@ -480,6 +1949,10 @@ CGLContextObj syphon_cgl_create_headless_context(void) {
     return ctx;
 }
 
+CGLContextObj syphon_cgl_create_headless_context(void) {
+    return syphon_cgl_create_headless_context_with(true, false, 0);
+}
+
 void syphon_cgl_destroy_context(CGLContextObj ctx) {
     if (ctx) {
         CGLSetCurrentContext(NULL);
@@ -487,43 +1960,1775 @@ void syphon_cgl_destroy_context(CGLContextObj ctx) {
     }
 }
 
+bool syphon_probe_server_frame(void *server_description, double timeout_seconds,
+    size_t *out_width, size_t *out_height, unsigned long *out_pixel_format) {
+    NSDictionary *desc = (__bridge NSDictionary *)server_description;
+    if (!desc) return false;
+    NSDate *deadline = [NSDate dateWithTimeIntervalSinceNow:timeout_seconds];
+#if SYPHON_HAS_METAL
+    id<MTLDevice> device = MTLCreateSystemDefaultDevice();
+    if (device) {
+        SyphonMetalClient *client = [[SyphonMetalClient alloc] initWithServerDescription:desc
+                                                                                 device:device
+                                                                                options:nil
+                                                                        newFrameHandler:nil];
+        id<MTLTexture> tex = nil;
+        while (client && !(tex = [client newFrameImage]) && [deadline timeIntervalSinceNow] > 0) {
+            usleep(2000);
+        }
+        [client stop];
+        if (!tex) return false;
+        if (out_width) *out_width = tex.width;
+        if (out_height) *out_height = tex.height;
+        if (out_pixel_format) *out_pixel_format = (unsigned long)tex.pixelFormat;
+        return true;
+    }
+#endif
+    /* No Metal: probe with an OpenGL client; Syphon surfaces are BGRA8. */
+    CGLContextObj ctx = syphon_cgl_create_headless_context();
+    if (!ctx) return false;
+    bool ok = false;
+    @autoreleasepool {
+        SYPHON_GL_CLIENT *client = [[SYPHON_GL_CLIENT alloc] initWithServerDescription:desc
+                                                                                context:ctx
+                                                                                options:nil
+                                                                        newFrameHandler:nil];
+        SYPHON_GL_IMAGE *img = nil;
+        while (client && !(img = [client newFrameImage]) && [deadline timeIntervalSinceNow] > 0) {
+            usleep(2000);
+        }
+        [client stop];
+        if (img) {
+            if (out_width) *out_width = (size_t)img.textureSize.width;
+            if (out_height) *out_height = (size_t)img.textureSize.height;
+            if (out_pixel_format) *out_pixel_format = 80; /* MTLPixelFormatBGRA8Unorm */
+            ok = true;
+        }
+    }
+    syphon_cgl_destroy_context(ctx);
+    return ok;
+}
+
 void syphon_cgl_make_current(CGLContextObj ctx) {
     CGLSetCurrentContext(ctx);
 }
 
+CGLContextObj syphon_cgl_get_current_context(void) {
+    return CGLGetCurrentContext();
+}
+
+CGLContextObj syphon_cgl_context_from_nsopengl_context(void *context) {
+    NSOpenGLContext *ctx = (__bridge NSOpenGLContext *)context;
+    return ctx ? ctx.CGLContextObj : NULL;
+}
+
 /* OpenGL texture helpers; CGL context must be current. GL_TEXTURE_RECTANGLE, RGBA8. */
-GLuint syphon_gl_create_texture_rectangle_rgba8(size_t width, size_t height, const unsigned char *rgba) {
+/* Create a texture on target (rectangle or 2D) with the given storage and optional initial pixels.
+ * 2D textures get linear filtering without mipmaps and edge clamping, so NPOT sizes are complete. */
+static GLuint glue_gl_create_texture(GLenum target, GLint internal_format, GLenum format, GLenum type,
+    size_t width, size_t height, const void *pixels) {
     GLuint tex = 0;
     glGenTextures(1, &tex);
     if (tex == 0) return 0;
-    glBindTexture(GL_TEXTURE_RECTANGLE, tex);
-    glTexImage2D(GL_TEXTURE_RECTANGLE, 0, GL_RGBA8, (GLsizei)width, (GLsizei)height, 0,
-                 GL_RGBA, GL_UNSIGNED_BYTE, rgba ? rgba : NULL);
-    glBindTexture(GL_TEXTURE_RECTANGLE, 0);
+    glBindTexture(target, tex);
+    glTexParameteri(target, GL_TEXTURE_MIN_FILTER, GL_LINEAR);
+    glTexParameteri(target, GL_TEXTURE_MAG_FILTER, GL_LINEAR);
+    glTexParameteri(target, GL_TEXTURE_WRAP_S, GL_CLAMP_TO_EDGE);
+    glTexParameteri(target, GL_TEXTURE_WRAP_T, GL_CLAMP_TO_EDGE);
+    glPixelStorei(GL_UNPACK_ALIGNMENT, 1);
+    glTexImage2D(target, 0, internal_format, (GLsizei)width, (GLsizei)height, 0, format, type, pixels);
+    glBindTexture(target, 0);
+    if (glGetError() != GL_NO_ERROR) {
+        glDeleteTextures(1, &tex);
+        return 0;
+    }
     return tex;
 }
 
-void syphon_gl_read_texture_rectangle_rgba8(GLuint tex_id, size_t width, size_t height, unsigned char *out_rgba) {
-    if (!out_rgba || tex_id == 0) return;
+/* Read a whole texture through a temporary framebuffer, row y=0 first (for Syphon frames, the top row).
+ * With a GL_PIXEL_PACK_BUFFER bound, out is an offset into it. */
+static void glue_gl_read_texture(GLenum target, GLuint tex_id, size_t width, size_t height,
+    GLenum format, GLenum type, void *out) {
+    if (tex_id == 0) return;
+    GLint prev_fbo = 0;
+    glGetIntegerv(GL_FRAMEBUFFER_BINDING, &prev_fbo);
     GLuint fbo = 0;
     glGenFramebuffers(1, &fbo);
     glBindFramebuffer(GL_FRAMEBUFFER, fbo);
-    glFramebufferTexture2D(GL_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, GL_TEXTURE_RECTANGLE, tex_id, 0);
-    /* Syphon client textures are top-down in GL (top = y=0); read rows in order so out_rgba is top row first. */
-    const size_t row_bytes = width * 4;
-    for (size_t row = 0; row < height; row++) {
-        glReadPixels(0, (GLint)row, (GLsizei)width, 1, GL_RGBA, GL_UNSIGNED_BYTE,
-                     out_rgba + row * row_bytes);
-    }
-    glFramebufferTexture2D(GL_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, GL_TEXTURE_RECTANGLE, 0, 0);
-    glBindFramebuffer(GL_FRAMEBUFFER, 0);
+    glFramebufferTexture2D(GL_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, target, tex_id, 0);
+    glPixelStorei(GL_PACK_ALIGNMENT, 1);
+    glReadPixels(0, 0, (GLsizei)width, (GLsizei)height, format, type, out);
+    glFramebufferTexture2D(GL_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, target, 0, 0);
+    glBindFramebuffer(GL_FRAMEBUFFER, (GLuint)prev_fbo);
     glDeleteFramebuffers(1, &fbo);
 }
 
+GLuint syphon_gl_create_texture_rectangle_rgba8(size_t width, size_t height, const unsigned char *rgba) {
+    return glue_gl_create_texture(GL_TEXTURE_RECTANGLE, GL_RGBA8, GL_RGBA, GL_UNSIGNED_BYTE, width, height, rgba);
+}
+
+void syphon_gl_read_texture_rectangle_rgba8(GLuint tex_id, size_t width, size_t height, unsigned char *out_rgba) {
+    if (!out_rgba) return;
+    glue_gl_read_texture(GL_TEXTURE_RECTANGLE, tex_id, width, height, GL_RGBA, GL_UNSIGNED_BYTE, out_rgba);
+}
+
+GLuint syphon_gl_create_texture_2d_rgba8(size_t width, size_t height, const unsigned char *rgba) {
+    return glue_gl_create_texture(GL_TEXTURE_2D, GL_RGBA8, GL_RGBA, GL_UNSIGNED_BYTE, width, height, rgba);
+}
+
+void syphon_gl_read_texture_2d_rgba8(GLuint tex_id, size_t width, size_t height, unsigned char *out_rgba) {
+    if (!out_rgba) return;
+    glue_gl_read_texture(GL_TEXTURE_2D, tex_id, width, height, GL_RGBA, GL_UNSIGNED_BYTE, out_rgba);
+}
+
+/* Upload/readback formats for syphon_gl_create_texture / syphon_gl_read_texture. */
+static bool glue_gl_pixel_format(uint32_t format, GLint *internal_format, GLenum *gl_format, GLenum *type) {
+    switch (format) {
+    case 0: /* RGBA8 */
+        *internal_format = GL_RGBA8; *gl_format = GL_RGBA; *type = GL_UNSIGNED_BYTE;
+        return true;
+    case 1: /* BGRA8 (the native order on macOS, no swizzle on upload) */
+        *internal_format = GL_RGBA8; *gl_format = GL_BGRA; *type = GL_UNSIGNED_INT_8_8_8_8_REV;
+        return true;
+    case 2: /* RGBA16F, half floats */
+        *internal_format = GL_RGBA16F; *gl_format = GL_RGBA; *type = GL_HALF_FLOAT;
+        return true;
+    default:
+        return false;
+    }
+}
+
+GLuint syphon_gl_create_texture(GLenum target, uint32_t format, size_t width, size_t height, const void *pixels) {
+    GLint internal_format;
+    GLenum gl_format, type;
+    if (!glue_gl_pixel_format(format, &internal_format, &gl_format, &type)) return 0;
+    return glue_gl_create_texture(target, internal_format, gl_format, type, width, height, pixels);
+}
+
+bool syphon_gl_read_texture(GLenum target, GLuint tex_id, uint32_t format, size_t width, size_t height, void *out) {
+    GLint internal_format;
+    GLenum gl_format, type;
+    if (!out || tex_id == 0 || !glue_gl_pixel_format(format, &internal_format, &gl_format, &type)) return false;
+    glue_gl_read_texture(target, tex_id, width, height, gl_format, type, out);
+    return true;
+}
+
+/* PBO readback: pools keep idle pixel-pack buffers for reuse; each readback holds one buffer and the
+ * fence that signals when its copy has landed. */
+typedef struct {
+    GLuint *idle;
+    size_t idle_count, idle_capacity;
+} glue_gl_readback_pool;
+
+typedef struct {
+    glue_gl_readback_pool *pool;
+    GLuint pbo;
+    GLsync fence;
+    size_t length;
+} glue_gl_readback;
+
+void *syphon_gl_readback_pool_create(void) {
+    return calloc(1, sizeof(glue_gl_readback_pool));
+}
+
+void syphon_gl_readback_pool_release(void *pool) {
+    glue_gl_readback_pool *p = (glue_gl_readback_pool *)pool;
+    if (!p) return;
+    if (p->idle_count) glDeleteBuffers((GLsizei)p->idle_count, p->idle);
+    free(p->idle);
+    free(p);
+}
+
+void *syphon_gl_readback_begin(void *pool, GLuint tex_id, GLenum target, size_t width, size_t height) {
+    glue_gl_readback_pool *p = (glue_gl_readback_pool *)pool;
+    if (!p || tex_id == 0 || width == 0 || height == 0) return NULL;
+    glue_gl_readback *rb = calloc(1, sizeof(glue_gl_readback));
+    if (!rb) return NULL;
+    rb->pool = p;
+    rb->length = width * height * 4;
+    if (p->idle_count) {
+        rb->pbo = p->idle[--p->idle_count];
+    } else {
+        glGenBuffers(1, &rb->pbo);
+    }
+    GLint prev_pack = 0;
+    glGetIntegerv(GL_PIXEL_PACK_BUFFER_BINDING, &prev_pack);
+    glBindBuffer(GL_PIXEL_PACK_BUFFER, rb->pbo);
+    glBufferData(GL_PIXEL_PACK_BUFFER, (GLsizeiptr)rb->length, NULL, GL_STREAM_READ);
+    glue_gl_read_texture(target, tex_id, width, height, GL_RGBA, GL_UNSIGNED_BYTE, NULL);
+    glBindBuffer(GL_PIXEL_PACK_BUFFER, (GLuint)prev_pack);
+    rb->fence = glFenceSync(GL_SYNC_GPU_COMMANDS_COMPLETE, 0);
+    glFlush();
+    return rb;
+}
+
+bool syphon_gl_readback_is_ready(void *readback) {
+    glue_gl_readback *rb = (glue_gl_readback *)readback;
+    if (!rb || !rb->fence) return true;
+    GLenum status = glClientWaitSync(rb->fence, 0, 0);
+    return status == GL_ALREADY_SIGNALED || status == GL_CONDITION_SATISFIED;
+}
+
+bool syphon_gl_readback_finish(void *readback, unsigned char *out, size_t len) {
+    glue_gl_readback *rb = (glue_gl_readback *)readback;
+    if (!rb || !out || len < rb->length) return false;
+    if (rb->fence) {
+        glClientWaitSync(rb->fence, GL_SYNC_FLUSH_COMMANDS_BIT, GL_TIMEOUT_IGNORED);
+        glDeleteSync(rb->fence);
+        rb->fence = NULL;
+    }
+    GLint prev_pack = 0;
+    glGetIntegerv(GL_PIXEL_PACK_BUFFER_BINDING, &prev_pack);
+    glBindBuffer(GL_PIXEL_PACK_BUFFER, rb->pbo);
+    const void *mapped = glMapBuffer(GL_PIXEL_PACK_BUFFER, GL_READ_ONLY);
+    if (mapped) {
+        memcpy(out, mapped, rb->length);
+        glUnmapBuffer(GL_PIXEL_PACK_BUFFER);
+    }
+    glBindBuffer(GL_PIXEL_PACK_BUFFER, (GLuint)prev_pack);
+    return mapped != NULL;
+}
+
+void syphon_gl_readback_release(void *readback) {
+    glue_gl_readback *rb = (glue_gl_readback *)readback;
+    if (!rb) return;
+    if (rb->fence) glDeleteSync(rb->fence);
+    glue_gl_readback_pool *p = rb->pool;
+    if (p->idle_count == p->idle_capacity) {
+        size_t capacity = p->idle_capacity ? p->idle_capacity * 2 : 2;
+        GLuint *idle = realloc(p->idle, capacity * sizeof(GLuint));
+        if (!idle) {
+            glDeleteBuffers(1, &rb->pbo);
+            free(rb);
+            return;
+        }
+        p->idle = idle;
+        p->idle_capacity = capacity;
+    }
+    p->idle[p->idle_count++] = rb->pbo;
+    free(rb);
+}
+
 void syphon_gl_delete_texture(GLuint tex_id) {
     if (tex_id != 0) {
         glDeleteTextures(1, &tex_id);
     }
 }
 
+bool syphon_gl_write_texture_region_rgba8(GLuint tex_id, GLenum target, size_t x, size_t y,
+    size_t width, size_t height, const unsigned char *rgba) {
+    if (tex_id == 0 || !rgba) return false;
+    glBindTexture(target, tex_id);
+    glPixelStorei(GL_UNPACK_ALIGNMENT, 1);
+    glTexSubImage2D(target, 0, (GLint)x, (GLint)y, (GLsizei)width, (GLsizei)height,
+                    GL_RGBA, GL_UNSIGNED_BYTE, rgba);
+    glBindTexture(target, 0);
+    return glGetError() == GL_NO_ERROR;
+}
+
+/* Metal devices and queues for callers that don't use Metal themselves */
+void *syphon_metal_device_create_default(void) {
+    return (__bridge_retained void *)MTLCreateSystemDefaultDevice();
+}
+
+void *syphon_metal_device_new_command_queue(void *device) {
+    id<MTLDevice> mtlDevice = (__bridge id<MTLDevice>)device;
+    return mtlDevice ? (__bridge_retained void *)[mtlDevice newCommandQueue] : NULL;
+}
+
+void syphon_metal_object_release(void *object) {
+    if (object) CFRelease((CFTypeRef)object);
+}
+
+/* Metal texture helpers */
+bool syphon_metal_texture_write_region_rgba8(void *texture, void *command_buffer, size_t x, size_t y,
+    size_t width, size_t height, const unsigned char *rgba) {
+    id<MTLTexture> dst = (__bridge id<MTLTexture>)texture;
+    id<MTLCommandBuffer> cb = (__bridge id<MTLCommandBuffer>)command_buffer;
+    if (!dst || !cb || !rgba || width == 0 || height == 0) return false;
+    MTLPixelFormat fmt = dst.pixelFormat;
+    bool bgra = (fmt == MTLPixelFormatBGRA8Unorm || fmt == MTLPixelFormatBGRA8Unorm_sRGB);
+    bool rgba8 = (fmt == MTLPixelFormatRGBA8Unorm || fmt == MTLPixelFormatRGBA8Unorm_sRGB);
+    if (!bgra && !rgba8) return false;
+    if (x + width > dst.width || y + height > dst.height) return false;
+
+    MTLTextureDescriptor *desc = [MTLTextureDescriptor texture2DDescriptorWithPixelFormat:fmt
+                                                                                    width:width
+                                                                                   height:height
+                                                                                mipmapped:NO];
+    desc.storageMode = MTLStorageModeManaged;
+    id<MTLTexture> staging = [dst.device newTextureWithDescriptor:desc];
+    if (!staging) return false;
+
+    const size_t row_bytes = width * 4;
+    if (bgra) {
+        unsigned char *swizzled = malloc(row_bytes * height);
+        if (!swizzled) return false;
+        for (size_t i = 0; i < row_bytes * height; i += 4) {
+            swizzled[i] = rgba[i + 2];
+            swizzled[i + 1] = rgba[i + 1];
+            swizzled[i + 2] = rgba[i];
+            swizzled[i + 3] = rgba[i + 3];
+        }
+        [staging replaceRegion:MTLRegionMake2D(0, 0, width, height) mipmapLevel:0 withBytes:swizzled bytesPerRow:row_bytes];
+        free(swizzled);
+    } else {
+        [staging replaceRegion:MTLRegionMake2D(0, 0, width, height) mipmapLevel:0 withBytes:rgba bytesPerRow:row_bytes];
+    }
+
+    id<MTLBlitCommandEncoder> blit = [cb blitCommandEncoder];
+    [blit copyFromTexture:staging sourceSlice:0 sourceLevel:0 sourceOrigin:MTLOriginMake(0, 0, 0)
+               sourceSize:MTLSizeMake(width, height, 1)
+                toTexture:dst destinationSlice:0 destinationLevel:0 destinationOrigin:MTLOriginMake(x, y, 0)];
+    [blit endEncoding];
+    return true;
+}
+
+/* Blit src into dst (same size and format) and wait, so the caller can drop the Syphon texture (and free
+ * the shared surface) immediately. */
+static bool glue_copy_texture_sync(id<MTLTexture> src, id<MTLTexture> dst, id<MTLCommandQueue> queue) {
+    if (!queue) queue = [src.device newCommandQueue];
+    if (!queue) return false;
+    id<MTLCommandBuffer> cb = [queue commandBuffer];
+    id<MTLBlitCommandEncoder> blit = [cb blitCommandEncoder];
+    [blit copyFromTexture:src sourceSlice:0 sourceLevel:0 sourceOrigin:MTLOriginMake(0, 0, 0)
+               sourceSize:MTLSizeMake(src.width, src.height, 1)
+                toTexture:dst destinationSlice:0 destinationLevel:0 destinationOrigin:MTLOriginMake(0, 0, 0)];
+    /* Managed textures need an explicit sync before the CPU copy reflects the GPU write. */
+    if (dst.storageMode == MTLStorageModeManaged) [blit synchronizeResource:dst];
+    [blit endEncoding];
+    [cb commit];
+    [cb waitUntilCompleted];
+    return cb.status == MTLCommandBufferStatusCompleted;
+}
+
+void *syphon_metal_texture_copy_to_owned(void *texture, void *command_queue, unsigned long storage_mode,
+    unsigned long usage) {
+    id<MTLTexture> src = (__bridge id<MTLTexture>)texture;
+    if (!src) return NULL;
+    MTLTextureDescriptor *desc = [MTLTextureDescriptor texture2DDescriptorWithPixelFormat:src.pixelFormat
+                                                                                    width:src.width
+                                                                                   height:src.height
+                                                                                mipmapped:NO];
+    desc.storageMode = (MTLStorageMode)storage_mode;
+    desc.usage = (MTLTextureUsage)usage;
+    id<MTLTexture> dst = [src.device newTextureWithDescriptor:desc];
+    if (!dst) return NULL;
+    if (!glue_copy_texture_sync(src, dst, (__bridge id<MTLCommandQueue>)command_queue)) return NULL;
+    return (__bridge_retained void *)dst;
+}
+
+void *syphon_metal_texture_io_surface(void *texture) {
+    id<MTLTexture> tex = (__bridge id<MTLTexture>)texture;
+    return tex ? (void *)tex.iosurface : NULL;
+}
+
+void *syphon_metal_texture_create_with_io_surface(void *device, void *surface, unsigned long pixel_format) {
+    id<MTLDevice> mtlDevice = (__bridge id<MTLDevice>)device;
+    IOSurfaceRef ioSurface = (IOSurfaceRef)surface;
+    if (!mtlDevice || !ioSurface) return NULL;
+    MTLTextureDescriptor *desc = [MTLTextureDescriptor texture2DDescriptorWithPixelFormat:(MTLPixelFormat)pixel_format
+                                                                                    width:IOSurfaceGetWidth(ioSurface)
+                                                                                   height:IOSurfaceGetHeight(ioSurface)
+                                                                                mipmapped:NO];
+    desc.usage = MTLTextureUsageShaderRead;
+    id<MTLTexture> tex = [mtlDevice newTextureWithDescriptor:desc iosurface:ioSurface plane:0];
+    return tex ? (__bridge_retained void *)tex : NULL;
+}
+
+/* CoreVideo pixel buffers over IOSurfaces */
+void *syphon_cv_pixel_buffer_create_with_io_surface(void *surface) {
+    if (!surface) return NULL;
+    CVPixelBufferRef buffer = NULL;
+    CVReturn err = CVPixelBufferCreateWithIOSurface(kCFAllocatorDefault, (IOSurfaceRef)surface, NULL, &buffer);
+    return err == kCVReturnSuccess ? (void *)buffer : NULL;
+}
+
+void syphon_cv_pixel_buffer_release(void *buffer) {
+    if (buffer) CVPixelBufferRelease((CVPixelBufferRef)buffer);
+}
+
+size_t syphon_cv_pixel_buffer_width(void *buffer) {
+    return buffer ? CVPixelBufferGetWidth((CVPixelBufferRef)buffer) : 0;
+}
+
+size_t syphon_cv_pixel_buffer_height(void *buffer) {
+    return buffer ? CVPixelBufferGetHeight((CVPixelBufferRef)buffer) : 0;
+}
+
+uint32_t syphon_cv_pixel_buffer_pixel_format(void *buffer) {
+    return buffer ? (uint32_t)CVPixelBufferGetPixelFormatType((CVPixelBufferRef)buffer) : 0;
+}
+
+#if SYPHON_GLUE_RECORDER || SYPHON_GLUE_ENCODER
+/* Render src into dst through Core Image, scaled to fit, centered on fill and flipped vertically if flip. */
+static void glue_render_pixel_buffer_fit(CIContext *context, CVPixelBufferRef src, CVPixelBufferRef dst, bool flip,
+    CIColor *fill) {
+    @autoreleasepool {
+        CIImage *image = [CIImage imageWithCVPixelBuffer:src];
+        CGSize size = image.extent.size;
+        CGFloat width = CVPixelBufferGetWidth(dst), height = CVPixelBufferGetHeight(dst);
+        CGFloat scale = MIN(width / size.width, height / size.height);
+        CGAffineTransform transform = CGAffineTransformIdentity;
+        if (flip) {
+            transform = CGAffineTransformMake(1, 0, 0, -1, 0, size.height);
+        }
+        transform = CGAffineTransformConcat(transform, CGAffineTransformMakeScale(scale, scale));
+        transform = CGAffineTransformConcat(transform,
+            CGAffineTransformMakeTranslation((width - size.width * scale) / 2, (height - size.height * scale) / 2));
+        CGRect bounds = CGRectMake(0, 0, width, height);
+        CIImage *background = [[CIImage imageWithColor:fill] imageByCroppingToRect:bounds];
+        image = [[image imageByApplyingTransform:transform] imageByCompositingOverImage:background];
+        CGColorSpaceRef colorSpace = CGColorSpaceCreateWithName(kCGColorSpaceSRGB);
+        [context render:image toCVPixelBuffer:dst bounds:bounds colorSpace:colorSpace];
+        CGColorSpaceRelease(colorSpace);
+    }
+}
+
+#endif
+
+#if SYPHON_GLUE_RECORDER
+/* AVAssetWriter recorders */
+@interface SyphonGlueRecorder : NSObject
+@property (nonatomic, strong) AVAssetWriter *writer;
+@property (nonatomic, strong) AVAssetWriterInput *input;
+@property (nonatomic, strong) AVAssetWriterInputPixelBufferAdaptor *adaptor;
+@property (nonatomic, strong) CIContext *context;
+@property (nonatomic, strong) CIColor *fill;
+@property (nonatomic) BOOL started;
+@property (nonatomic) CMTime lastTime;
+@end
+
+@implementation SyphonGlueRecorder
+@end
+
+static void glue_recorder_set_error(SyphonGlueRecorder *r, NSString *fallback) {
+    glue_set_error(r.writer.error.localizedDescription ?: fallback);
+}
+
+void *syphon_recorder_create(const char *path, uint32_t codec, size_t width, size_t height, double bit_rate) {
+    glue_set_error(nil);
+    NSString *nsPath = nullable_cstring_to_nsstring(path);
+    if (!nsPath || !width || !height) {
+        glue_set_error(@"a recorder needs a path and a size");
+        return NULL;
+    }
+    AVVideoCodecType codecType;
+    switch (codec) {
+        case 0: codecType = AVVideoCodecTypeH264; break;
+        case 1: codecType = AVVideoCodecTypeAppleProRes422; break;
+        case 2: codecType = AVVideoCodecTypeAppleProRes4444; break;
+        default:
+            glue_set_error(@"unknown codec");
+            return NULL;
+    }
+    NSURL *url = [NSURL fileURLWithPath:nsPath];
+    [[NSFileManager defaultManager] removeItemAtURL:url error:nil];
+    NSError *error = nil;
+    AVAssetWriter *writer = [AVAssetWriter assetWriterWithURL:url fileType:AVFileTypeQuickTimeMovie error:&error];
+    if (!writer) {
+        glue_set_error(error.localizedDescription ?: @"can't create the asset writer");
+        return NULL;
+    }
+    NSMutableDictionary<NSString *, id> *settings = [@{
+        AVVideoCodecKey : codecType,
+        AVVideoWidthKey : @(width),
+        AVVideoHeightKey : @(height),
+    } mutableCopy];
+    if (codec == 0 && bit_rate > 0) {
+        settings[AVVideoCompressionPropertiesKey] = @{AVVideoAverageBitRateKey : @(bit_rate)};
+    }
+    AVAssetWriterInput *input = [AVAssetWriterInput assetWriterInputWithMediaType:AVMediaTypeVideo
+                                                                   outputSettings:settings];
+    input.expectsMediaDataInRealTime = YES;
+    if (![writer canAddInput:input]) {
+        glue_set_error(@"the asset writer rejected the video settings");
+        return NULL;
+    }
+    [writer addInput:input];
+    NSDictionary<NSString *, id> *attributes = @{
+        (NSString *)kCVPixelBufferPixelFormatTypeKey : @(kCVPixelFormatType_32BGRA),
+        (NSString *)kCVPixelBufferWidthKey : @(width),
+        (NSString *)kCVPixelBufferHeightKey : @(height),
+        (NSString *)kCVPixelBufferIOSurfacePropertiesKey : @{},
+    };
+    SyphonGlueRecorder *recorder = [[SyphonGlueRecorder alloc] init];
+    recorder.writer = writer;
+    recorder.input = input;
+    recorder.adaptor = [AVAssetWriterInputPixelBufferAdaptor assetWriterInputPixelBufferAdaptorWithAssetWriterInput:input
+                                                                                         sourcePixelBufferAttributes:attributes];
+    recorder.context = [CIContext contextWithOptions:nil];
+    /* Letterboxing is transparent in ProRes 4444 (which keeps alpha) and black otherwise. */
+    recorder.fill = codec == 2 ? [CIColor clearColor] : [CIColor blackColor];
+    if (![writer startWriting]) {
+        glue_recorder_set_error(recorder, @"the asset writer failed to start");
+        return NULL;
+    }
+    return (__bridge_retained void *)recorder;
+}
+
+int syphon_recorder_append(void *recorder, void *pixel_buffer, double seconds, bool flip) {
+    glue_set_error(nil);
+    SyphonGlueRecorder *r = (__bridge SyphonGlueRecorder *)recorder;
+    CVPixelBufferRef src = (CVPixelBufferRef)pixel_buffer;
+    if (!r || !src) {
+        glue_set_error(@"no recorder or pixel buffer");
+        return -1;
+    }
+    if (r.writer.status != AVAssetWriterStatusWriting) {
+        glue_recorder_set_error(r, @"the recorder is not writing");
+        return -1;
+    }
+    CMTime time = CMTimeMakeWithSeconds(seconds, 60000);
+    if (!r.input.readyForMoreMediaData || (r.started && CMTimeCompare(time, r.lastTime) <= 0)) return 0;
+    CVPixelBufferPoolRef pool = r.adaptor.pixelBufferPool;
+    CVPixelBufferRef dst = NULL;
+    if (!pool || CVPixelBufferPoolCreatePixelBuffer(kCFAllocatorDefault, pool, &dst) != kCVReturnSuccess) {
+        glue_set_error(@"the writer's pixel buffer pool is unavailable");
+        return -1;
+    }
+    glue_render_pixel_buffer_fit(r.context, src, dst, flip, r.fill);
+    if (!r.started) {
+        [r.writer startSessionAtSourceTime:time];
+        r.started = YES;
+    }
+    BOOL ok = [r.adaptor appendPixelBuffer:dst withPresentationTime:time];
+    CVPixelBufferRelease(dst);
+    if (!ok) {
+        glue_recorder_set_error(r, @"the asset writer rejected the frame");
+        return -1;
+    }
+    r.lastTime = time;
+    return 1;
+}
+
+bool syphon_recorder_finish(void *recorder) {
+    glue_set_error(nil);
+    SyphonGlueRecorder *r = (__bridge SyphonGlueRecorder *)recorder;
+    if (!r) return false;
+    if (r.writer.status != AVAssetWriterStatusWriting) {
+        glue_recorder_set_error(r, @"the recorder is not writing");
+        return false;
+    }
+    if (!r.started) {
+        [r.writer cancelWriting];
+        glue_set_error(@"no frames were recorded");
+        return false;
+    }
+    [r.input markAsFinished];
+    dispatch_semaphore_t done = dispatch_semaphore_create(0);
+    [r.writer finishWritingWithCompletionHandler:^{
+        dispatch_semaphore_signal(done);
+    }];
+    dispatch_semaphore_wait(done, DISPATCH_TIME_FOREVER);
+    if (r.writer.status != AVAssetWriterStatusCompleted) {
+        glue_recorder_set_error(r, @"the asset writer failed to finish");
+        return false;
+    }
+    return true;
+}
+
+void syphon_recorder_release(void *recorder) {
+    if (!recorder) return;
+    SyphonGlueRecorder *r = (__bridge_transfer SyphonGlueRecorder *)recorder;
+    if (r.writer.status == AVAssetWriterStatusWriting) [r.writer cancelWriting];
+}
+
+#endif /* SYPHON_GLUE_RECORDER */
+
+#if SYPHON_GLUE_ENCODER
+/* VideoToolbox encoders */
+typedef OSStatus (*glue_parameter_set_getter_t)(CMFormatDescriptionRef, size_t, const uint8_t **, size_t *, size_t *,
+    int *);
+typedef void (*glue_packet_callback_t)(void *userdata, const unsigned char *data, size_t len,
+    const unsigned char *parameter_sets, size_t parameter_sets_len, double pts, double dts, bool keyframe);
+
+@interface SyphonGlueEncoder : NSObject
+@property (nonatomic) VTCompressionSessionRef session;
+@property (nonatomic) bool hevc;
+@property (nonatomic) bool annexB;
+@property (nonatomic) size_t width;
+@property (nonatomic) size_t height;
+@property (nonatomic, strong) CIContext *context;
+@property (nonatomic) BOOL started;
+@property (nonatomic) CMTime lastTime;
+@property (nonatomic) glue_packet_callback_t callback;
+@property (nonatomic) void (*releaseUserdata)(void *);
+@property (nonatomic) void *userdata;
+@end
+
+@implementation SyphonGlueEncoder
+@end
+
+/* Append each NAL unit of avcc (nal_length-byte big-endian lengths) to out with a four-byte start code. */
+static void glue_append_annex_b(NSMutableData *out, const uint8_t *avcc, size_t len, int nal_length) {
+    static const uint8_t start_code[4] = {0, 0, 0, 1};
+    size_t offset = 0;
+    while (offset + nal_length <= len) {
+        size_t nal = 0;
+        for (int i = 0; i < nal_length; i++) nal = (nal << 8) | avcc[offset + i];
+        offset += nal_length;
+        if (nal > len - offset) break;
+        [out appendBytes:start_code length:4];
+        [out appendBytes:avcc + offset length:nal];
+        offset += nal;
+    }
+}
+
+static void glue_encoder_output(void *refcon, void *frame_refcon, OSStatus status, VTEncodeInfoFlags flags,
+    CMSampleBufferRef sample) {
+    (void)frame_refcon;
+    SyphonGlueEncoder *e = (__bridge SyphonGlueEncoder *)refcon;
+    if (status != noErr || !sample || (flags & kVTEncodeInfo_FrameDropped) || !e.callback) return;
+    bool keyframe = true;
+    CFArrayRef attachments = CMSampleBufferGetSampleAttachmentsArray(sample, false);
+    if (attachments && CFArrayGetCount(attachments) > 0) {
+        CFDictionaryRef attachment = CFArrayGetValueAtIndex(attachments, 0);
+        CFBooleanRef notSync = CFDictionaryGetValue(attachment, kCMSampleAttachmentKey_NotSync);
+        keyframe = !notSync || !CFBooleanGetValue(notSync);
+    }
+    CMBlockBufferRef block = CMSampleBufferGetDataBuffer(sample);
+    size_t length = block ? CMBlockBufferGetDataLength(block) : 0;
+    NSMutableData *avcc = [NSMutableData dataWithLength:length];
+    if (!length || CMBlockBufferCopyDataBytes(block, 0, length, avcc.mutableBytes) != kCMBlockBufferNoErr) return;
+
+    /* Parameter sets (SPS / PPS, plus VPS for HEVC) go out with every keyframe, each with a four-byte length. */
+    NSMutableData *parameterSets = [NSMutableData data];
+    NSMutableData *annexB = e.annexB ? [NSMutableData dataWithCapacity:length + 64] : nil;
+    int nalLength = 4;
+    CMFormatDescriptionRef format = CMSampleBufferGetFormatDescription(sample);
+    glue_parameter_set_getter_t get = e.hevc ? CMVideoFormatDescriptionGetHEVCParameterSetAtIndex
+                                             : CMVideoFormatDescriptionGetH264ParameterSetAtIndex;
+    size_t count = 0;
+    if (format && get(format, 0, NULL, NULL, &count, &nalLength) == noErr && keyframe) {
+        for (size_t i = 0; i < count; i++) {
+            const uint8_t *set = NULL;
+            size_t size = 0;
+            if (get(format, i, &set, &size, NULL, NULL) != noErr) continue;
+            uint8_t prefix[4] = {(uint8_t)(size >> 24), (uint8_t)(size >> 16), (uint8_t)(size >> 8), (uint8_t)size};
+            [parameterSets appendBytes:prefix length:4];
+            [parameterSets appendBytes:set length:size];
+        }
+        if (annexB) glue_append_annex_b(annexB, parameterSets.bytes, parameterSets.length, 4);
+    }
+    NSData *data = avcc;
+    if (annexB) {
+        glue_append_annex_b(annexB, avcc.bytes, avcc.length, nalLength);
+        data = annexB;
+    }
+    CMTime pts = CMSampleBufferGetPresentationTimeStamp(sample);
+    CMTime dts = CMSampleBufferGetDecodeTimeStamp(sample);
+    if (!CMTIME_IS_VALID(dts)) dts = pts;
+    e.callback(e.userdata, data.bytes, data.length, parameterSets.bytes, parameterSets.length,
+        CMTimeGetSeconds(pts), CMTimeGetSeconds(dts), keyframe);
+}
+
+static void glue_encoder_set(VTCompressionSessionRef session, CFStringRef key, CFTypeRef value) {
+    VTSessionSetProperty(session, key, value);
+}
+
+void *syphon_encoder_create(uint32_t codec, size_t width, size_t height, double bit_rate, double frame_rate,
+    uint32_t keyframe_interval, bool annex_b, glue_packet_callback_t callback, void (*release_userdata)(void *userdata),
+    void *userdata) {
+    glue_set_error(nil);
+    if (!width || !height || codec > 1) {
+        glue_set_error(!width || !height ? @"an encoder needs a size" : @"unknown codec");
+        if (release_userdata) release_userdata(userdata);
+        return NULL;
+    }
+    SyphonGlueEncoder *encoder = [[SyphonGlueEncoder alloc] init];
+    encoder.hevc = codec == 1;
+    encoder.annexB = annex_b;
+    encoder.width = width;
+    encoder.height = height;
+    encoder.callback = callback;
+    encoder.releaseUserdata = release_userdata;
+    encoder.userdata = userdata;
+    NSDictionary *specification = @{
+        (NSString *)kVTVideoEncoderSpecification_EnableHardwareAcceleratedVideoEncoder : @YES,
+    };
+    NSDictionary *attributes = @{
+        (NSString *)kCVPixelBufferPixelFormatTypeKey : @(kCVPixelFormatType_32BGRA),
+        (NSString *)kCVPixelBufferWidthKey : @(width),
+        (NSString *)kCVPixelBufferHeightKey : @(height),
+        (NSString *)kCVPixelBufferIOSurfacePropertiesKey : @{},
+    };
+    VTCompressionSessionRef session = NULL;
+    OSStatus status = VTCompressionSessionCreate(kCFAllocatorDefault, (int32_t)width, (int32_t)height,
+        encoder.hevc ? kCMVideoCodecType_HEVC : kCMVideoCodecType_H264, (__bridge CFDictionaryRef)specification,
+        (__bridge CFDictionaryRef)attributes, NULL, glue_encoder_output, (__bridge void *)encoder, &session);
+    if (status != noErr || !session) {
+        glue_set_error([NSString stringWithFormat:@"VTCompressionSessionCreate failed (%d)", (int)status]);
+        if (release_userdata) release_userdata(userdata);
+        return NULL;
+    }
+    encoder.session = session;
+    glue_encoder_set(session, kVTCompressionPropertyKey_RealTime, kCFBooleanTrue);
+    /* Streaming stacks expect decode order to match presentation order. */
+    glue_encoder_set(session, kVTCompressionPropertyKey_AllowFrameReordering, kCFBooleanFalse);
+    glue_encoder_set(session, kVTCompressionPropertyKey_ProfileLevel,
+        encoder.hevc ? kVTProfileLevel_HEVC_Main_AutoLevel : kVTProfileLevel_H264_High_AutoLevel);
+    if (bit_rate > 0) {
+        glue_encoder_set(session, kVTCompressionPropertyKey_AverageBitRate, (__bridge CFTypeRef)@(bit_rate));
+    }
+    if (frame_rate > 0) {
+        glue_encoder_set(session, kVTCompressionPropertyKey_ExpectedFrameRate, (__bridge CFTypeRef)@(frame_rate));
+    }
+    if (keyframe_interval > 0) {
+        glue_encoder_set(session, kVTCompressionPropertyKey_MaxKeyFrameInterval,
+            (__bridge CFTypeRef)@(keyframe_interval));
+    }
+    status = VTCompressionSessionPrepareToEncodeFrames(session);
+    if (status != noErr) {
+        glue_set_error([NSString stringWithFormat:@"the encoder failed to start (%d)", (int)status]);
+        syphon_encoder_release((__bridge_retained void *)encoder);
+        return NULL;
+    }
+    encoder.context = [CIContext contextWithOptions:nil];
+    return (__bridge_retained void *)encoder;
+}
+
+int syphon_encoder_encode(void *encoder, void *pixel_buffer, double seconds, bool flip, bool force_keyframe) {
+    glue_set_error(nil);
+    SyphonGlueEncoder *e = (__bridge SyphonGlueEncoder *)encoder;
+    CVPixelBufferRef src = (CVPixelBufferRef)pixel_buffer;
+    if (!e || !src) {
+        glue_set_error(@"no encoder or pixel buffer");
+        return -1;
+    }
+    CMTime time = CMTimeMakeWithSeconds(seconds, 90000);
+    if (e.started && CMTimeCompare(time, e.lastTime) <= 0) return 0;
+    CVPixelBufferRef input = CVPixelBufferRetain(src);
+    if (flip || CVPixelBufferGetWidth(src) != e.width || CVPixelBufferGetHeight(src) != e.height) {
+        CVPixelBufferRelease(input);
+        input = NULL;
+        CVPixelBufferPoolRef pool = VTCompressionSessionGetPixelBufferPool(e.session);
+        if (!pool || CVPixelBufferPoolCreatePixelBuffer(kCFAllocatorDefault, pool, &input) != kCVReturnSuccess) {
+            glue_set_error(@"the encoder's pixel buffer pool is unavailable");
+            return -1;
+        }
+        glue_render_pixel_buffer_fit(e.context, src, input, flip, [CIColor blackColor]);
+    }
+    NSDictionary *options = force_keyframe ? @{(NSString *)kVTEncodeFrameOptionKey_ForceKeyFrame : @YES} : nil;
+    VTEncodeInfoFlags flags = 0;
+    OSStatus status = VTCompressionSessionEncodeFrame(e.session, input, time, kCMTimeInvalid,
+        (__bridge CFDictionaryRef)options, NULL, &flags);
+    CVPixelBufferRelease(input);
+    if (status != noErr) {
+        glue_set_error([NSString stringWithFormat:@"VTCompressionSessionEncodeFrame failed (%d)", (int)status]);
+        return -1;
+    }
+    e.started = YES;
+    e.lastTime = time;
+    return (flags & kVTEncodeInfo_FrameDropped) ? 0 : 1;
+}
+
+bool syphon_encoder_flush(void *encoder) {
+    glue_set_error(nil);
+    SyphonGlueEncoder *e = (__bridge SyphonGlueEncoder *)encoder;
+    if (!e) return false;
+    OSStatus status = VTCompressionSessionCompleteFrames(e.session, kCMTimeInvalid);
+    if (status != noErr) {
+        glue_set_error([NSString stringWithFormat:@"VTCompressionSessionCompleteFrames failed (%d)", (int)status]);
+        return false;
+    }
+    return true;
+}
+
+void syphon_encoder_release(void *encoder) {
+    if (!encoder) return;
+    SyphonGlueEncoder *e = (__bridge_transfer SyphonGlueEncoder *)encoder;
+    /* Deliver pending packets and stop the session before the callback's userdata goes. */
+    VTCompressionSessionCompleteFrames(e.session, kCMTimeInvalid);
+    VTCompressionSessionInvalidate(e.session);
+    CFRelease(e.session);
+    if (e.releaseUserdata) e.releaseUserdata(e.userdata);
+}
+
+#endif /* SYPHON_GLUE_ENCODER */
+
+/* CVMetalTextureCache over IOSurfaces */
+static char kSyphonGlueCVTextureKey;
+
+void *syphon_cv_metal_texture_cache_create(void *device) {
+    id<MTLDevice> mtlDevice = (__bridge id<MTLDevice>)device;
+    if (!mtlDevice) return NULL;
+    CVMetalTextureCacheRef cache = NULL;
+    CVReturn err = CVMetalTextureCacheCreate(kCFAllocatorDefault, NULL, mtlDevice, NULL, &cache);
+    return err == kCVReturnSuccess ? (void *)cache : NULL;
+}
+
+void *syphon_cv_metal_texture_cache_new_texture(void *cache, void *surface, unsigned long pixel_format) {
+    if (!cache || !surface) return NULL;
+    CVPixelBufferRef buffer = NULL;
+    if (CVPixelBufferCreateWithIOSurface(kCFAllocatorDefault, (IOSurfaceRef)surface, NULL, &buffer) != kCVReturnSuccess)
+        return NULL;
+    CVMetalTextureRef cvTexture = NULL;
+    CVReturn err = CVMetalTextureCacheCreateTextureFromImage(kCFAllocatorDefault, (CVMetalTextureCacheRef)cache, buffer,
+        NULL, (MTLPixelFormat)pixel_format, CVPixelBufferGetWidth(buffer), CVPixelBufferGetHeight(buffer), 0, &cvTexture);
+    CVPixelBufferRelease(buffer);
+    if (err != kCVReturnSuccess || !cvTexture) return NULL;
+    id<MTLTexture> texture = CVMetalTextureGetTexture(cvTexture);
+    if (texture) {
+        /* The MTLTexture is only valid while its CVMetalTexture lives; tie the two together. */
+        objc_setAssociatedObject(texture, &kSyphonGlueCVTextureKey, (__bridge id)cvTexture, OBJC_ASSOCIATION_RETAIN_NONATOMIC);
+    }
+    CFRelease(cvTexture);
+    return texture ? (__bridge_retained void *)texture : NULL;
+}
+
+void syphon_cv_metal_texture_cache_flush(void *cache) {
+    if (cache) CVMetalTextureCacheFlush((CVMetalTextureCacheRef)cache, 0);
+}
+
+void syphon_cv_metal_texture_cache_release(void *cache) {
+    if (cache) CFRelease((CFTypeRef)cache);
+}
+
+#if SYPHON_GLUE_CORE_IMAGE
+/* Core Image */
+void *syphon_ci_image_create_with_io_surface(void *surface) {
+    if (!surface) return NULL;
+    CIImage *image = [CIImage imageWithIOSurface:(IOSurfaceRef)surface];
+    image = [image imageByApplyingCGOrientation:kCGImagePropertyOrientationDownMirrored];
+    return image ? (__bridge_retained void *)image : NULL;
+}
+
+#endif /* SYPHON_GLUE_CORE_IMAGE */
+
+#if SYPHON_GLUE_SCREEN_CAPTURE
+/* ScreenCaptureKit shareable content */
+void *syphon_sc_content_copy(void) {
+    glue_set_error(nil);
+    if (@available(macOS 12.3, *)) {
+        dispatch_semaphore_t done = dispatch_semaphore_create(0);
+        __block SCShareableContent *result = nil;
+        __block NSError *failure = nil;
+        [SCShareableContent getShareableContentExcludingDesktopWindows:YES
+                                                   onScreenWindowsOnly:YES
+                                                     completionHandler:^(SCShareableContent *content, NSError *error) {
+            result = content;
+            failure = error;
+            dispatch_semaphore_signal(done);
+        }];
+        dispatch_semaphore_wait(done, DISPATCH_TIME_FOREVER);
+        if (!result) glue_set_error(failure.localizedDescription ?: @"no shareable content");
+        return (__bridge_retained void *)result;
+    }
+    glue_set_error(@"ScreenCaptureKit needs macOS 12.3 or later");
+    return NULL;
+}
+
+size_t syphon_sc_content_display_count(void *content) {
+    if (@available(macOS 12.3, *)) {
+        return content ? ((__bridge SCShareableContent *)content).displays.count : 0;
+    }
+    return 0;
+}
+
+bool syphon_sc_content_display_at(void *content, size_t index, uint32_t *display_id, size_t *width, size_t *height) {
+    if (@available(macOS 12.3, *)) {
+        NSArray<SCDisplay *> *displays = content ? ((__bridge SCShareableContent *)content).displays : nil;
+        if (index >= displays.count) return false;
+        SCDisplay *d = displays[index];
+        CGDisplayModeRef mode = CGDisplayCopyDisplayMode(d.displayID);
+        if (display_id) *display_id = d.displayID;
+        if (width) *width = mode ? CGDisplayModeGetPixelWidth(mode) : (size_t)d.width;
+        if (height) *height = mode ? CGDisplayModeGetPixelHeight(mode) : (size_t)d.height;
+        CGDisplayModeRelease(mode);
+        return true;
+    }
+    return false;
+}
+
+size_t syphon_sc_content_window_count(void *content) {
+    if (@available(macOS 12.3, *)) {
+        return content ? ((__bridge SCShareableContent *)content).windows.count : 0;
+    }
+    return 0;
+}
+
+API_AVAILABLE(macos(12.3))
+static SCWindow *glue_sc_window_at(void *content, size_t index) {
+    NSArray<SCWindow *> *windows = content ? ((__bridge SCShareableContent *)content).windows : nil;
+    return index < windows.count ? windows[index] : nil;
+}
+
+bool syphon_sc_content_window_at(void *content, size_t index, uint32_t *window_id, size_t *width, size_t *height) {
+    if (@available(macOS 12.3, *)) {
+        SCWindow *w = glue_sc_window_at(content, index);
+        if (!w) return false;
+        if (window_id) *window_id = w.windowID;
+        if (width) *width = (size_t)w.frame.size.width;
+        if (height) *height = (size_t)w.frame.size.height;
+        return true;
+    }
+    return false;
+}
+
+char *syphon_sc_content_copy_window_title(void *content, size_t index) {
+    if (@available(macOS 12.3, *)) {
+        return copy_nsstring_to_cstring(glue_sc_window_at(content, index).title);
+    }
+    return NULL;
+}
+
+char *syphon_sc_content_copy_window_app_name(void *content, size_t index) {
+    if (@available(macOS 12.3, *)) {
+        return copy_nsstring_to_cstring(glue_sc_window_at(content, index).owningApplication.applicationName);
+    }
+    return NULL;
+}
+
+#endif /* SYPHON_GLUE_SCREEN_CAPTURE */
+
+#if SYPHON_GLUE_CAMERA
+/* AVFoundation video capture devices */
+static NSArray<AVCaptureDevice *> *glue_av_video_devices(void) {
+    NSMutableArray<AVCaptureDeviceType> *types = [NSMutableArray arrayWithObject:AVCaptureDeviceTypeBuiltInWideAngleCamera];
+    if (@available(macOS 14.0, *)) {
+        [types addObjectsFromArray:@[AVCaptureDeviceTypeExternal, AVCaptureDeviceTypeContinuityCamera]];
+    } else {
+#pragma clang diagnostic push
+#pragma clang diagnostic ignored "-Wdeprecated-declarations"
+        [types addObject:AVCaptureDeviceTypeExternalUnknown];
+#pragma clang diagnostic pop
+    }
+    AVCaptureDeviceDiscoverySession *discovery =
+        [AVCaptureDeviceDiscoverySession discoverySessionWithDeviceTypes:types
+                                                                mediaType:AVMediaTypeVideo
+                                                                 position:AVCaptureDevicePositionUnspecified];
+    return discovery.devices;
+}
+
+void *syphon_av_video_devices_copy(void) {
+    return (__bridge_retained void *)glue_av_video_devices();
+}
+
+size_t syphon_av_video_devices_count(void *devices) {
+    return devices ? ((__bridge NSArray<AVCaptureDevice *> *)devices).count : 0;
+}
+
+static AVCaptureDevice *glue_av_device_at(void *devices, size_t index) {
+    NSArray<AVCaptureDevice *> *list = (__bridge NSArray<AVCaptureDevice *> *)devices;
+    return index < list.count ? list[index] : nil;
+}
+
+char *syphon_av_video_devices_copy_unique_id(void *devices, size_t index) {
+    return copy_nsstring_to_cstring(glue_av_device_at(devices, index).uniqueID);
+}
+
+char *syphon_av_video_devices_copy_name(void *devices, size_t index) {
+    return copy_nsstring_to_cstring(glue_av_device_at(devices, index).localizedName);
+}
+
+char *syphon_av_video_devices_copy_model_id(void *devices, size_t index) {
+    return copy_nsstring_to_cstring(glue_av_device_at(devices, index).modelID);
+}
+
+#endif /* SYPHON_GLUE_CAMERA */
+
+size_t syphon_metal_texture_bytes_per_pixel(void *texture) {
+    id<MTLTexture> tex = (__bridge id<MTLTexture>)texture;
+    if (!tex) return 0;
+    switch (tex.pixelFormat) {
+        case MTLPixelFormatR8Unorm:
+            return 1;
+        case MTLPixelFormatRG8Unorm:
+        case MTLPixelFormatR16Float:
+        case MTLPixelFormatR16Unorm:
+            return 2;
+        case MTLPixelFormatRGBA8Unorm:
+        case MTLPixelFormatRGBA8Unorm_sRGB:
+        case MTLPixelFormatBGRA8Unorm:
+        case MTLPixelFormatBGRA8Unorm_sRGB:
+        case MTLPixelFormatRGB10A2Unorm:
+        case MTLPixelFormatBGR10A2Unorm:
+        case MTLPixelFormatR32Float:
+            return 4;
+        case MTLPixelFormatRGBA16Float:
+        case MTLPixelFormatRGBA16Unorm:
+            return 8;
+        case MTLPixelFormatRGBA32Float:
+            return 16;
+        default:
+            return 0;
+    }
+}
+
+bool syphon_metal_texture_read_bytes(void *texture, void *command_queue, unsigned char *out, size_t len) {
+    id<MTLTexture> tex = (__bridge id<MTLTexture>)texture;
+    size_t bpp = syphon_metal_texture_bytes_per_pixel(texture);
+    if (!tex || !out || bpp == 0 || tex.framebufferOnly) return false;
+    size_t width = tex.width, height = tex.height;
+    size_t row = width * bpp;
+    if (len < row * height) return false;
+    id<MTLCommandQueue> queue = (__bridge id<MTLCommandQueue>)command_queue;
+    if (!queue) queue = [tex.device newCommandQueue];
+    if (!queue) return false;
+    /* Padded rows keep the blit on its fast path; the padding is dropped when copying out. */
+    size_t stride = (row + 255) & ~(size_t)255;
+    id<MTLBuffer> buffer = [tex.device newBufferWithLength:stride * height options:MTLResourceStorageModeShared];
+    if (!buffer) return false;
+    id<MTLCommandBuffer> cb = [queue commandBuffer];
+    id<MTLBlitCommandEncoder> blit = [cb blitCommandEncoder];
+    [blit copyFromTexture:tex sourceSlice:0 sourceLevel:0 sourceOrigin:MTLOriginMake(0, 0, 0)
+               sourceSize:MTLSizeMake(width, height, 1)
+                 toBuffer:buffer destinationOffset:0 destinationBytesPerRow:stride
+  destinationBytesPerImage:stride * height];
+    [blit endEncoding];
+    [cb commit];
+    [cb waitUntilCompleted];
+    if (cb.status != MTLCommandBufferStatusCompleted) return false;
+    const unsigned char *src = buffer.contents;
+    for (size_t y = 0; y < height; y++) memcpy(out + y * row, src + y * stride, row);
+    return true;
+}
+
+/*
+ * Heap-backed texture pools: textures are sub-allocated from MTLHeaps owned by the pool, so frequent
+ * (re)allocation does not go through the device allocator. Heaps are added when none has room for a
+ * request and dropped once empty, so a resolution change does not leave the old size's memory behind.
+ */
+
+@interface SyphonGlueTexturePool : NSObject
+@property (nonatomic, strong) id<MTLDevice> device;
+@property (nonatomic, strong) NSMutableArray<id<MTLHeap>> *heaps;
+@property (nonatomic) NSUInteger heapSize;
+@property (nonatomic) MTLStorageMode storageMode;
+@end
+
+@implementation SyphonGlueTexturePool
+@end
+
+void *syphon_metal_texture_pool_create(void *device, size_t heap_size, unsigned long storage_mode) {
+    id<MTLDevice> mtlDevice = (__bridge id<MTLDevice>)device;
+    MTLStorageMode mode = (MTLStorageMode)storage_mode;
+    /* Heaps can't be managed. */
+    if (!mtlDevice || mode == MTLStorageModeManaged) return NULL;
+    SyphonGlueTexturePool *pool = [[SyphonGlueTexturePool alloc] init];
+    pool.device = mtlDevice;
+    pool.heaps = [NSMutableArray array];
+    pool.heapSize = heap_size;
+    pool.storageMode = mode;
+    return (__bridge_retained void *)pool;
+}
+
+void *syphon_metal_texture_pool_new_texture(void *pool, unsigned long pixel_format, size_t width, size_t height,
+    unsigned long usage) {
+    SyphonGlueTexturePool *p = (__bridge SyphonGlueTexturePool *)pool;
+    if (!p || width == 0 || height == 0) return NULL;
+    MTLTextureDescriptor *desc = [MTLTextureDescriptor texture2DDescriptorWithPixelFormat:(MTLPixelFormat)pixel_format
+                                                                                    width:width
+                                                                                   height:height
+                                                                                mipmapped:NO];
+    desc.storageMode = p.storageMode;
+    desc.usage = (MTLTextureUsage)usage;
+    @autoreleasepool {
+        NSMutableArray<id<MTLHeap>> *empty = [NSMutableArray array];
+        for (id<MTLHeap> heap in p.heaps) {
+            id<MTLTexture> tex = [heap newTextureWithDescriptor:desc];
+            if (tex) {
+                [p.heaps removeObjectsInArray:empty];
+                return (__bridge_retained void *)tex;
+            }
+            if (heap.usedSize == 0) [empty addObject:heap];
+        }
+        [p.heaps removeObjectsInArray:empty];
+        MTLSizeAndAlign need = [p.device heapTextureSizeAndAlignWithDescriptor:desc];
+        MTLHeapDescriptor *heapDesc = [[MTLHeapDescriptor alloc] init];
+        heapDesc.storageMode = p.storageMode;
+        heapDesc.size = MAX(p.heapSize, need.size + need.align);
+        id<MTLHeap> heap = [p.device newHeapWithDescriptor:heapDesc];
+        if (!heap) return NULL;
+        [p.heaps addObject:heap];
+        id<MTLTexture> tex = [heap newTextureWithDescriptor:desc];
+        return tex ? (__bridge_retained void *)tex : NULL;
+    }
+}
+
+void *syphon_metal_texture_pool_copy(void *pool, void *texture, void *command_queue, unsigned long usage) {
+    id<MTLTexture> src = (__bridge id<MTLTexture>)texture;
+    if (!src) return NULL;
+    void *dst = syphon_metal_texture_pool_new_texture(pool, src.pixelFormat, src.width, src.height, usage);
+    if (!dst) return NULL;
+    if (!glue_copy_texture_sync(src, (__bridge id<MTLTexture>)dst, (__bridge id<MTLCommandQueue>)command_queue)) {
+        syphon_metal_texture_release(dst);
+        return NULL;
+    }
+    return dst;
+}
+
+size_t syphon_metal_texture_pool_allocated_size(void *pool) {
+    SyphonGlueTexturePool *p = (__bridge SyphonGlueTexturePool *)pool;
+    size_t total = 0;
+    for (id<MTLHeap> heap in p.heaps) total += heap.size;
+    return total;
+}
+
+void syphon_metal_texture_pool_release(void *pool) {
+    (void)(__bridge_transfer SyphonGlueTexturePool *)pool;
+}
+
+void syphon_metal_texture_make_aliasable(void *texture) {
+    id<MTLTexture> tex = (__bridge id<MTLTexture>)texture;
+    if (tex.heap) [tex makeAliasable];
+}
+
+/*
+ * Frame blenders: mix two frames (t = 0 -> a, t = 1 -> b) into an output texture the blender owns
+ * and reallocates when the size changes.
+ */
+
+/* Core-profile entry points; the legacy gl.h does not declare them and gl3.h can't be mixed with it. */
+extern void glGenVertexArrays(GLsizei n, GLuint *arrays);
+extern void glBindVertexArray(GLuint array);
+extern void glDeleteVertexArrays(GLsizei n, const GLuint *arrays);
+
+typedef struct {
+    GLuint program, vao, vbo, fbo, output;
+    GLint loc_a, loc_b, loc_t;
+    size_t width, height;
+    bool core;
+} glue_gl_blender;
+
+static GLuint glue_gl_compile(GLenum type, const char *src) {
+    GLuint shader = glCreateShader(type);
+    glShaderSource(shader, 1, &src, NULL);
+    glCompileShader(shader);
+    GLint ok = 0;
+    glGetShaderiv(shader, GL_COMPILE_STATUS, &ok);
+    if (!ok) {
+        glDeleteShader(shader);
+        return 0;
+    }
+    return shader;
+}
+
+void *syphon_gl_blender_create(void) {
+    static const char *vs_core = "#version 150\nin vec2 pos; out vec2 uv;\n"
+        "void main() { uv = pos * 0.5 + 0.5; gl_Position = vec4(pos, 0.0, 1.0); }\n";
+    static const char *fs_core = "#version 150\nuniform sampler2D a; uniform sampler2D b; uniform float t;\n"
+        "in vec2 uv; out vec4 color;\nvoid main() { color = mix(texture(a, uv), texture(b, uv), t); }\n";
+    static const char *vs_legacy = "#version 120\nattribute vec2 pos; varying vec2 uv;\n"
+        "void main() { uv = pos * 0.5 + 0.5; gl_Position = vec4(pos, 0.0, 1.0); }\n";
+    static const char *fs_legacy = "#version 120\nuniform sampler2D a; uniform sampler2D b; uniform float t;\n"
+        "varying vec2 uv;\nvoid main() { gl_FragColor = mix(texture2D(a, uv), texture2D(b, uv), t); }\n";
+    static const GLfloat quad[] = { -1.f, -1.f, 1.f, -1.f, -1.f, 1.f, 1.f, 1.f };
+
+    CGLContextObj ctx = CGLGetCurrentContext();
+    if (!ctx) return NULL;
+    bool core = syphon_cgl_context_is_core_profile(ctx);
+    GLuint vs = glue_gl_compile(GL_VERTEX_SHADER, core ? vs_core : vs_legacy);
+    GLuint fs = glue_gl_compile(GL_FRAGMENT_SHADER, core ? fs_core : fs_legacy);
+    if (!vs || !fs) {
+        if (vs) glDeleteShader(vs);
+        if (fs) glDeleteShader(fs);
+        return NULL;
+    }
+    GLuint program = glCreateProgram();
+    glAttachShader(program, vs);
+    glAttachShader(program, fs);
+    glBindAttribLocation(program, 0, "pos");
+    glLinkProgram(program);
+    glDeleteShader(vs);
+    glDeleteShader(fs);
+    GLint linked = 0;
+    glGetProgramiv(program, GL_LINK_STATUS, &linked);
+    if (!linked) {
+        glDeleteProgram(program);
+        return NULL;
+    }
+
+    glue_gl_blender *b = calloc(1, sizeof(glue_gl_blender));
+    if (!b) {
+        glDeleteProgram(program);
+        return NULL;
+    }
+    b->program = program;
+    b->core = core;
+    b->loc_a = glGetUniformLocation(program, "a");
+    b->loc_b = glGetUniformLocation(program, "b");
+    b->loc_t = glGetUniformLocation(program, "t");
+    if (core) {
+        glGenVertexArrays(1, &b->vao);
+        glBindVertexArray(b->vao);
+    }
+    glGenBuffers(1, &b->vbo);
+    glBindBuffer(GL_ARRAY_BUFFER, b->vbo);
+    glBufferData(GL_ARRAY_BUFFER, sizeof(quad), quad, GL_STATIC_DRAW);
+    glVertexAttribPointer(0, 2, GL_FLOAT, GL_FALSE, 0, NULL);
+    glEnableVertexAttribArray(0);
+    glBindBuffer(GL_ARRAY_BUFFER, 0);
+    if (core) glBindVertexArray(0);
+    glGenFramebuffers(1, &b->fbo);
+    return b;
+}
+
+GLuint syphon_gl_blender_blend(void *blender, GLuint tex_a, GLuint tex_b, size_t width, size_t height, float t) {
+    glue_gl_blender *b = (glue_gl_blender *)blender;
+    if (!b || !tex_a || !tex_b || width == 0 || height == 0) return 0;
+    if (!b->output || b->width != width || b->height != height) {
+        if (b->output) glDeleteTextures(1, &b->output);
+        glGenTextures(1, &b->output);
+        glBindTexture(GL_TEXTURE_2D, b->output);
+        glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_MIN_FILTER, GL_LINEAR);
+        glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, GL_LINEAR);
+        glTexImage2D(GL_TEXTURE_2D, 0, GL_RGBA8, (GLsizei)width, (GLsizei)height, 0, GL_RGBA, GL_UNSIGNED_BYTE, NULL);
+        glBindTexture(GL_TEXTURE_2D, 0);
+        b->width = width;
+        b->height = height;
+    }
+
+    GLint prev_fbo = 0, prev_program = 0, prev_viewport[4] = {0, 0, 0, 0};
+    glGetIntegerv(GL_FRAMEBUFFER_BINDING, &prev_fbo);
+    glGetIntegerv(GL_CURRENT_PROGRAM, &prev_program);
+    glGetIntegerv(GL_VIEWPORT, prev_viewport);
+
+    glBindFramebuffer(GL_FRAMEBUFFER, b->fbo);
+    glFramebufferTexture2D(GL_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, GL_TEXTURE_2D, b->output, 0);
+    bool ok = glCheckFramebufferStatus(GL_FRAMEBUFFER) == GL_FRAMEBUFFER_COMPLETE;
+    if (ok) {
+        glViewport(0, 0, (GLsizei)width, (GLsizei)height);
+        glUseProgram(b->program);
+        glActiveTexture(GL_TEXTURE0);
+        glBindTexture(GL_TEXTURE_2D, tex_a);
+        glActiveTexture(GL_TEXTURE1);
+        glBindTexture(GL_TEXTURE_2D, tex_b);
+        glUniform1i(b->loc_a, 0);
+        glUniform1i(b->loc_b, 1);
+        glUniform1f(b->loc_t, t);
+        if (b->core) {
+            glBindVertexArray(b->vao);
+        } else {
+            glBindBuffer(GL_ARRAY_BUFFER, b->vbo);
+            glVertexAttribPointer(0, 2, GL_FLOAT, GL_FALSE, 0, NULL);
+            glEnableVertexAttribArray(0);
+        }
+        glDrawArrays(GL_TRIANGLE_STRIP, 0, 4);
+        if (b->core) {
+            glBindVertexArray(0);
+        } else {
+            glDisableVertexAttribArray(0);
+            glBindBuffer(GL_ARRAY_BUFFER, 0);
+        }
+        glBindTexture(GL_TEXTURE_2D, 0);
+        glActiveTexture(GL_TEXTURE0);
+        glBindTexture(GL_TEXTURE_2D, 0);
+        ok = glGetError() == GL_NO_ERROR;
+    }
+    glFramebufferTexture2D(GL_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, GL_TEXTURE_2D, 0, 0);
+    glBindFramebuffer(GL_FRAMEBUFFER, (GLuint)prev_fbo);
+    glUseProgram((GLuint)prev_program);
+    glViewport(prev_viewport[0], prev_viewport[1], prev_viewport[2], prev_viewport[3]);
+    return ok ? b->output : 0;
+}
+
+void syphon_gl_blender_release(void *blender) {
+    glue_gl_blender *b = (glue_gl_blender *)blender;
+    if (!b) return;
+    if (b->output) glDeleteTextures(1, &b->output);
+    if (b->fbo) glDeleteFramebuffers(1, &b->fbo);
+    if (b->vbo) glDeleteBuffers(1, &b->vbo);
+    if (b->vao) glDeleteVertexArrays(1, &b->vao);
+    if (b->program) glDeleteProgram(b->program);
+    free(b);
+}
+
+/* sRGB transfer conversion (mode 1: linear -> sRGB encode, 2: sRGB -> linear decode) of a rectangle or 2D
+ * texture into an owned RGBA8 GL_TEXTURE_2D. One program per source target, compiled on first use. */
+typedef struct {
+    GLuint programs[2], vao, vbo, fbo, output;
+    GLint loc_src[2], loc_scale[2], loc_mode[2];
+    size_t width, height;
+    bool core;
+} glue_gl_srgb_converter;
+
+static GLuint glue_gl_srgb_program(bool core, bool rectangle) {
+    static const char *vs_core = "#version 150\nin vec2 pos; out vec2 uv; uniform vec2 scale;\n"
+        "void main() { uv = (pos * 0.5 + 0.5) * scale; gl_Position = vec4(pos, 0.0, 1.0); }\n";
+    static const char *vs_legacy = "#version 120\nattribute vec2 pos; varying vec2 uv; uniform vec2 scale;\n"
+        "void main() { uv = (pos * 0.5 + 0.5) * scale; gl_Position = vec4(pos, 0.0, 1.0); }\n";
+    static const char *transfer =
+        "vec3 encode(vec3 c) { return mix(c * 12.92, 1.055 * pow(c, vec3(1.0 / 2.4)) - 0.055, step(0.0031308, c)); }\n"
+        "vec3 decode(vec3 c) { return mix(c / 12.92, pow((c + 0.055) / 1.055, vec3(2.4)), step(0.04045, c)); }\n"
+        "vec4 convert(vec4 c) { c.rgb = clamp(c.rgb, 0.0, 1.0);\n"
+        "  return mode == 1 ? vec4(encode(c.rgb), c.a) : mode == 2 ? vec4(decode(c.rgb), c.a) : c; }\n";
+    char fs[1024];
+    if (core) {
+        snprintf(fs, sizeof(fs), "#version 150\nuniform %s src; uniform int mode; in vec2 uv; out vec4 color;\n%s"
+            "void main() { color = convert(texture(src, uv)); }\n", rectangle ? "sampler2DRect" : "sampler2D", transfer);
+    } else {
+        snprintf(fs, sizeof(fs), "#version 120\n%suniform %s src; uniform int mode; varying vec2 uv;\n%s"
+            "void main() { gl_FragColor = convert(%s(src, uv)); }\n",
+            rectangle ? "#extension GL_ARB_texture_rectangle : enable\n" : "", rectangle ? "sampler2DRect" : "sampler2D",
+            transfer, rectangle ? "texture2DRect" : "texture2D");
+    }
+    GLuint vs = glue_gl_compile(GL_VERTEX_SHADER, core ? vs_core : vs_legacy);
+    GLuint fsh = glue_gl_compile(GL_FRAGMENT_SHADER, fs);
+    GLuint program = 0;
+    if (vs && fsh) {
+        program = glCreateProgram();
+        glAttachShader(program, vs);
+        glAttachShader(program, fsh);
+        glBindAttribLocation(program, 0, "pos");
+        glLinkProgram(program);
+        GLint linked = 0;
+        glGetProgramiv(program, GL_LINK_STATUS, &linked);
+        if (!linked) {
+            glDeleteProgram(program);
+            program = 0;
+        }
+    }
+    if (vs) glDeleteShader(vs);
+    if (fsh) glDeleteShader(fsh);
+    return program;
+}
+
+void *syphon_gl_srgb_converter_create(void) {
+    static const GLfloat quad[] = { -1.f, -1.f, 1.f, -1.f, -1.f, 1.f, 1.f, 1.f };
+    CGLContextObj ctx = CGLGetCurrentContext();
+    if (!ctx) return NULL;
+    glue_gl_srgb_converter *c = calloc(1, sizeof(glue_gl_srgb_converter));
+    if (!c) return NULL;
+    c->core = syphon_cgl_context_is_core_profile(ctx);
+    if (c->core) {
+        glGenVertexArrays(1, &c->vao);
+        glBindVertexArray(c->vao);
+    }
+    glGenBuffers(1, &c->vbo);
+    glBindBuffer(GL_ARRAY_BUFFER, c->vbo);
+    glBufferData(GL_ARRAY_BUFFER, sizeof(quad), quad, GL_STATIC_DRAW);
+    glVertexAttribPointer(0, 2, GL_FLOAT, GL_FALSE, 0, NULL);
+    glEnableVertexAttribArray(0);
+    glBindBuffer(GL_ARRAY_BUFFER, 0);
+    if (c->core) glBindVertexArray(0);
+    glGenFramebuffers(1, &c->fbo);
+    return c;
+}
+
+GLuint syphon_gl_srgb_converter_convert(void *converter, GLuint src_tex, GLenum src_target, size_t width, size_t height,
+    int32_t mode) {
+    glue_gl_srgb_converter *c = (glue_gl_srgb_converter *)converter;
+    if (!c || !src_tex || width == 0 || height == 0) return 0;
+    int index = src_target == GL_TEXTURE_RECTANGLE ? 1 : 0;
+    if (!c->programs[index]) {
+        c->programs[index] = glue_gl_srgb_program(c->core, index == 1);
+        if (!c->programs[index]) return 0;
+        c->loc_src[index] = glGetUniformLocation(c->programs[index], "src");
+        c->loc_scale[index] = glGetUniformLocation(c->programs[index], "scale");
+        c->loc_mode[index] = glGetUniformLocation(c->programs[index], "mode");
+    }
+    if (!c->output || c->width != width || c->height != height) {
+        if (c->output) glDeleteTextures(1, &c->output);
+        c->output = glue_gl_create_texture(GL_TEXTURE_2D, GL_RGBA8, GL_RGBA, GL_UNSIGNED_BYTE, width, height, NULL);
+        if (!c->output) return 0;
+        c->width = width;
+        c->height = height;
+    }
+
+    GLint prev_fbo = 0, prev_program = 0, prev_viewport[4] = {0, 0, 0, 0};
+    glGetIntegerv(GL_FRAMEBUFFER_BINDING, &prev_fbo);
+    glGetIntegerv(GL_CURRENT_PROGRAM, &prev_program);
+    glGetIntegerv(GL_VIEWPORT, prev_viewport);
+
+    glBindFramebuffer(GL_FRAMEBUFFER, c->fbo);
+    glFramebufferTexture2D(GL_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, GL_TEXTURE_2D, c->output, 0);
+    bool ok = glCheckFramebufferStatus(GL_FRAMEBUFFER) == GL_FRAMEBUFFER_COMPLETE;
+    if (ok) {
+        glViewport(0, 0, (GLsizei)width, (GLsizei)height);
+        glUseProgram(c->programs[index]);
+        glActiveTexture(GL_TEXTURE0);
+        glBindTexture(src_target, src_tex);
+        glUniform1i(c->loc_src[index], 0);
+        glUniform1i(c->loc_mode[index], mode);
+        /* Rectangle textures are sampled in pixels. */
+        glUniform2f(c->loc_scale[index], index ? (GLfloat)width : 1.f, index ? (GLfloat)height : 1.f);
+        if (c->core) {
+            glBindVertexArray(c->vao);
+        } else {
+            glBindBuffer(GL_ARRAY_BUFFER, c->vbo);
+            glVertexAttribPointer(0, 2, GL_FLOAT, GL_FALSE, 0, NULL);
+            glEnableVertexAttribArray(0);
+        }
+        glDrawArrays(GL_TRIANGLE_STRIP, 0, 4);
+        if (c->core) {
+            glBindVertexArray(0);
+        } else {
+            glDisableVertexAttribArray(0);
+            glBindBuffer(GL_ARRAY_BUFFER, 0);
+        }
+        glBindTexture(src_target, 0);
+        ok = glGetError() == GL_NO_ERROR;
+    }
+    glFramebufferTexture2D(GL_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, GL_TEXTURE_2D, 0, 0);
+    glBindFramebuffer(GL_FRAMEBUFFER, (GLuint)prev_fbo);
+    glUseProgram((GLuint)prev_program);
+    glViewport(prev_viewport[0], prev_viewport[1], prev_viewport[2], prev_viewport[3]);
+    return ok ? c->output : 0;
+}
+
+void syphon_gl_srgb_converter_release(void *converter) {
+    glue_gl_srgb_converter *c = (glue_gl_srgb_converter *)converter;
+    if (!c) return;
+    if (c->output) glDeleteTextures(1, &c->output);
+    if (c->fbo) glDeleteFramebuffers(1, &c->fbo);
+    if (c->vbo) glDeleteBuffers(1, &c->vbo);
+    if (c->vao) glDeleteVertexArrays(1, &c->vao);
+    for (int i = 0; i < 2; i++) {
+        if (c->programs[i]) glDeleteProgram(c->programs[i]);
+    }
+    free(c);
+}
+
+/* MSAA resolve target: a single-sample GL_TEXTURE_2D (resized with the frame) behind its own FBO. */
+typedef struct {
+    GLuint fbo, texture;
+    size_t width, height;
+} glue_gl_resolver;
+
+void *syphon_gl_resolver_create(void) {
+    return calloc(1, sizeof(glue_gl_resolver));
+}
+
+GLuint syphon_gl_resolver_resolve(void *resolver, GLuint msaa_fbo, size_t width, size_t height) {
+    glue_gl_resolver *r = (glue_gl_resolver *)resolver;
+    if (!r || width == 0 || height == 0) return 0;
+    if (!r->texture || r->width != width || r->height != height) {
+        if (r->texture) glDeleteTextures(1, &r->texture);
+        r->texture = glue_gl_create_texture(GL_TEXTURE_2D, GL_RGBA8, GL_RGBA, GL_UNSIGNED_BYTE, width, height, NULL);
+        if (!r->texture) return 0;
+        r->width = width;
+        r->height = height;
+    }
+    if (!r->fbo) glGenFramebuffers(1, &r->fbo);
+
+    GLint prev_read = 0, prev_draw = 0;
+    glGetIntegerv(GL_READ_FRAMEBUFFER_BINDING, &prev_read);
+    glGetIntegerv(GL_DRAW_FRAMEBUFFER_BINDING, &prev_draw);
+    glBindFramebuffer(GL_DRAW_FRAMEBUFFER, r->fbo);
+    glFramebufferTexture2D(GL_DRAW_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, GL_TEXTURE_2D, r->texture, 0);
+    glBindFramebuffer(GL_READ_FRAMEBUFFER, msaa_fbo);
+    bool ok = glCheckFramebufferStatus(GL_READ_FRAMEBUFFER) == GL_FRAMEBUFFER_COMPLETE
+        && glCheckFramebufferStatus(GL_DRAW_FRAMEBUFFER) == GL_FRAMEBUFFER_COMPLETE;
+    if (ok) {
+        /* Multisample resolves must not scale, so source and destination rectangles match. */
+        glBlitFramebuffer(0, 0, (GLint)width, (GLint)height, 0, 0, (GLint)width, (GLint)height,
+                          GL_COLOR_BUFFER_BIT, GL_NEAREST);
+        ok = glGetError() == GL_NO_ERROR;
+    }
+    glBindFramebuffer(GL_READ_FRAMEBUFFER, (GLuint)prev_read);
+    glBindFramebuffer(GL_DRAW_FRAMEBUFFER, (GLuint)prev_draw);
+    return ok ? r->texture : 0;
+}
+
+void syphon_gl_resolver_release(void *resolver) {
+    glue_gl_resolver *r = (glue_gl_resolver *)resolver;
+    if (!r) return;
+    if (r->texture) glDeleteTextures(1, &r->texture);
+    if (r->fbo) glDeleteFramebuffers(1, &r->fbo);
+    free(r);
+}
+
+@interface SyphonGlueMetalBlender : NSObject
+@property (nonatomic, strong) id<MTLComputePipelineState> pipeline;
+@property (nonatomic, strong) id<MTLTexture> output;
+@property (nonatomic) MTLStorageMode outputStorageMode;
+@property (nonatomic) MTLTextureUsage outputUsage;
+@end
+
+@implementation SyphonGlueMetalBlender
+@end
+
+void *syphon_metal_blender_create(void *device) {
+    static NSString *source =
+        @"#include <metal_stdlib>\n"
+        @"using namespace metal;\n"
+        @"kernel void syphon_blend(texture2d<float, access::read> a [[texture(0)]],\n"
+        @"                         texture2d<float, access::read> b [[texture(1)]],\n"
+        @"                         texture2d<float, access::write> dst [[texture(2)]],\n"
+        @"                         constant float &t [[buffer(0)]],\n"
+        @"                         uint2 gid [[thread_position_in_grid]]) {\n"
+        @"    if (gid.x >= dst.get_width() || gid.y >= dst.get_height()) return;\n"
+        @"    dst.write(mix(a.read(gid), b.read(gid), t), gid);\n"
+        @"}\n";
+    id<MTLDevice> mtlDevice = (__bridge id<MTLDevice>)device;
+    if (!mtlDevice) return NULL;
+    NSError *error = nil;
+    id<MTLLibrary> library = [mtlDevice newLibraryWithSource:source options:nil error:&error];
+    id<MTLFunction> fn = [library newFunctionWithName:@"syphon_blend"];
+    if (!fn) return NULL;
+    id<MTLComputePipelineState> pipeline = [mtlDevice newComputePipelineStateWithFunction:fn error:&error];
+    if (!pipeline) return NULL;
+    SyphonGlueMetalBlender *b = [[SyphonGlueMetalBlender alloc] init];
+    b.pipeline = pipeline;
+    b.outputStorageMode = MTLStorageModePrivate;
+    b.outputUsage = MTLTextureUsageShaderRead;
+    return (__bridge_retained void *)b;
+}
+
+void *syphon_metal_blender_blend(void *blender, void *texture_a, void *texture_b, void *command_buffer, float t) {
+    SyphonGlueMetalBlender *b = (__bridge SyphonGlueMetalBlender *)blender;
+    id<MTLTexture> a = (__bridge id<MTLTexture>)texture_a;
+    id<MTLTexture> bt = (__bridge id<MTLTexture>)texture_b;
+    id<MTLCommandBuffer> cb = (__bridge id<MTLCommandBuffer>)command_buffer;
+    if (!b || !a || !bt || !cb) return NULL;
+    if (a.width != bt.width || a.height != bt.height || a.pixelFormat != bt.pixelFormat) return NULL;
+    if (!b.output || b.output.width != a.width || b.output.height != a.height || b.output.pixelFormat != a.pixelFormat) {
+        MTLTextureDescriptor *desc = [MTLTextureDescriptor texture2DDescriptorWithPixelFormat:a.pixelFormat
+                                                                                        width:a.width
+                                                                                       height:a.height
+                                                                                    mipmapped:NO];
+        desc.storageMode = b.outputStorageMode;
+        /* The blend kernel writes the output; callers can add usages on top. */
+        desc.usage = b.outputUsage | MTLTextureUsageShaderWrite;
+        b.output = [a.device newTextureWithDescriptor:desc];
+        if (!b.output) return NULL;
+    }
+    id<MTLComputeCommandEncoder> enc = [cb computeCommandEncoder];
+    if (!enc) return NULL;
+    [enc setComputePipelineState:b.pipeline];
+    [enc setTexture:a atIndex:0];
+    [enc setTexture:bt atIndex:1];
+    [enc setTexture:b.output atIndex:2];
+    [enc setBytes:&t length:sizeof(t) atIndex:0];
+    MTLSize group = MTLSizeMake(16, 16, 1);
+    MTLSize groups = MTLSizeMake((a.width + 15) / 16, (a.height + 15) / 16, 1);
+    [enc dispatchThreadgroups:groups threadsPerThreadgroup:group];
+    [enc endEncoding];
+    return (__bridge void *)b.output;
+}
+
+void syphon_metal_blender_set_output_options(void *blender, unsigned long storage_mode, unsigned long usage) {
+    SyphonGlueMetalBlender *b = (__bridge SyphonGlueMetalBlender *)blender;
+    if (!b) return;
+    if (b.outputStorageMode != (MTLStorageMode)storage_mode || b.outputUsage != (MTLTextureUsage)usage) b.output = nil;
+    b.outputStorageMode = (MTLStorageMode)storage_mode;
+    b.outputUsage = (MTLTextureUsage)usage;
+}
+
+void syphon_metal_blender_release(void *blender) {
+    (void)(__bridge_transfer SyphonGlueMetalBlender *)blender;
+}
+
+/* sRGB transfer conversion on Metal: a compute kernel into an owned texture of the source's format. */
+@interface SyphonGlueMetalSrgbConverter : NSObject
+@property (nonatomic, strong) id<MTLComputePipelineState> pipeline;
+@property (nonatomic, strong) id<MTLTexture> output;
+@end
+
+@implementation SyphonGlueMetalSrgbConverter
+@end
+
+void *syphon_metal_srgb_converter_create(void *device) {
+    static NSString *source =
+        @"#include <metal_stdlib>\n"
+        @"using namespace metal;\n"
+        @"kernel void syphon_srgb(texture2d<float, access::read> src [[texture(0)]],\n"
+        @"                        texture2d<float, access::write> dst [[texture(1)]],\n"
+        @"                        constant int &mode [[buffer(0)]],\n"
+        @"                        uint2 gid [[thread_position_in_grid]]) {\n"
+        @"    if (gid.x >= dst.get_width() || gid.y >= dst.get_height()) return;\n"
+        @"    float4 c = src.read(gid);\n"
+        @"    float3 rgb = saturate(c.rgb);\n"
+        @"    if (mode == 1) rgb = select(1.055 * pow(rgb, float3(1.0 / 2.4)) - 0.055, rgb * 12.92, rgb <= 0.0031308);\n"
+        @"    if (mode == 2) rgb = select(pow((rgb + 0.055) / 1.055, float3(2.4)), rgb / 12.92, rgb <= 0.04045);\n"
+        @"    dst.write(float4(rgb, c.a), gid);\n"
+        @"}\n";
+    id<MTLDevice> mtlDevice = (__bridge id<MTLDevice>)device;
+    if (!mtlDevice) return NULL;
+    NSError *error = nil;
+    id<MTLLibrary> library = [mtlDevice newLibraryWithSource:source options:nil error:&error];
+    id<MTLFunction> fn = [library newFunctionWithName:@"syphon_srgb"];
+    if (!fn) return NULL;
+    id<MTLComputePipelineState> pipeline = [mtlDevice newComputePipelineStateWithFunction:fn error:&error];
+    if (!pipeline) return NULL;
+    SyphonGlueMetalSrgbConverter *c = [[SyphonGlueMetalSrgbConverter alloc] init];
+    c.pipeline = pipeline;
+    return (__bridge_retained void *)c;
+}
+
+void *syphon_metal_srgb_converter_convert(void *converter, void *texture, void *command_buffer, int32_t mode) {
+    SyphonGlueMetalSrgbConverter *c = (__bridge SyphonGlueMetalSrgbConverter *)converter;
+    id<MTLTexture> src = (__bridge id<MTLTexture>)texture;
+    id<MTLCommandBuffer> cb = (__bridge id<MTLCommandBuffer>)command_buffer;
+    if (!c || !src || !cb) return NULL;
+    if (!c.output || c.output.width != src.width || c.output.height != src.height || c.output.pixelFormat != src.pixelFormat) {
+        MTLTextureDescriptor *desc = [MTLTextureDescriptor texture2DDescriptorWithPixelFormat:src.pixelFormat
+                                                                                        width:src.width
+                                                                                       height:src.height
+                                                                                    mipmapped:NO];
+        desc.storageMode = MTLStorageModePrivate;
+        desc.usage = MTLTextureUsageShaderRead | MTLTextureUsageShaderWrite;
+        c.output = [src.device newTextureWithDescriptor:desc];
+        if (!c.output) return NULL;
+    }
+    id<MTLComputeCommandEncoder> enc = [cb computeCommandEncoder];
+    if (!enc) return NULL;
+    [enc setComputePipelineState:c.pipeline];
+    [enc setTexture:src atIndex:0];
+    [enc setTexture:c.output atIndex:1];
+    [enc setBytes:&mode length:sizeof(mode) atIndex:0];
+    MTLSize group = MTLSizeMake(16, 16, 1);
+    MTLSize groups = MTLSizeMake((src.width + 15) / 16, (src.height + 15) / 16, 1);
+    [enc dispatchThreadgroups:groups threadsPerThreadgroup:group];
+    [enc endEncoding];
+    return (__bridge void *)c.output;
+}
+
+void syphon_metal_srgb_converter_release(void *converter) {
+    (void)(__bridge_transfer SyphonGlueMetalSrgbConverter *)converter;
+}
+
+#if SYPHON_GLUE_PASTEBOARD
+/* Pasteboard / drag helpers */
+static NSBitmapImageRep *glue_bitmap_rep_from_rgba8(const unsigned char *rgba, size_t width, size_t height) {
+    if (!rgba || width == 0 || height == 0) return nil;
+    NSBitmapImageRep *rep = [[NSBitmapImageRep alloc] initWithBitmapDataPlanes:NULL
+                                                                    pixelsWide:(NSInteger)width
+                                                                    pixelsHigh:(NSInteger)height
+                                                                 bitsPerSample:8
+                                                               samplesPerPixel:4
+                                                                      hasAlpha:YES
+                                                                      isPlanar:NO
+                                                                colorSpaceName:NSDeviceRGBColorSpace
+                                                                  bitmapFormat:NSBitmapFormatAlphaNonpremultiplied
+                                                                   bytesPerRow:(NSInteger)(width * 4)
+                                                                  bitsPerPixel:32];
+    if (!rep) return nil;
+    memcpy(rep.bitmapData, rgba, width * height * 4);
+    return rep;
+}
+
+static NSPasteboardItem *glue_pasteboard_item(NSBitmapImageRep *rep, bool png, bool tiff) {
+    NSPasteboardItem *item = [[NSPasteboardItem alloc] init];
+    bool any = false;
+    if (png) {
+        NSData *data = [rep representationUsingType:NSBitmapImageFileTypePNG properties:@{}];
+        if (data) any = [item setData:data forType:NSPasteboardTypePNG] || any;
+    }
+    if (tiff) {
+        NSData *data = rep.TIFFRepresentation;
+        if (data) any = [item setData:data forType:NSPasteboardTypeTIFF] || any;
+    }
+    return any ? item : nil;
+}
+
+bool syphon_pasteboard_write_rgba8(const unsigned char *rgba, size_t width, size_t height, bool png, bool tiff) {
+    @autoreleasepool {
+        NSBitmapImageRep *rep = glue_bitmap_rep_from_rgba8(rgba, width, height);
+        NSPasteboardItem *item = rep ? glue_pasteboard_item(rep, png, tiff) : nil;
+        if (!item) return false;
+        NSPasteboard *pb = [NSPasteboard generalPasteboard];
+        [pb clearContents];
+        return [pb writeObjects:@[ item ]] ? true : false;
+    }
+}
+
+void *syphon_dragging_item_create_rgba8(const unsigned char *rgba, size_t width, size_t height, bool png, bool tiff,
+    double preview_w, double preview_h) {
+    @autoreleasepool {
+        NSBitmapImageRep *rep = glue_bitmap_rep_from_rgba8(rgba, width, height);
+        NSPasteboardItem *item = rep ? glue_pasteboard_item(rep, png, tiff) : nil;
+        if (!item) return NULL;
+        NSImage *preview = [[NSImage alloc] initWithSize:NSMakeSize((CGFloat)width, (CGFloat)height)];
+        [preview addRepresentation:rep];
+        NSDraggingItem *drag = [[NSDraggingItem alloc] initWithPasteboardWriter:item];
+        CGFloat pw = preview_w > 0 ? preview_w : (CGFloat)width;
+        CGFloat ph = preview_h > 0 ? preview_h : (CGFloat)height;
+        [drag setDraggingFrame:NSMakeRect(0, 0, pw, ph) contents:preview];
+        return (__bridge_retained void *)drag;
+    }
+}
+
+void syphon_dragging_item_release(void *item) {
+    (void)(__bridge_transfer NSDraggingItem *)item;
+}
+#endif /* SYPHON_GLUE_PASTEBOARD */
+
+#if SYPHON_GLUE_HOTKEY
+/*
+ * Global hotkeys (Carbon RegisterEventHotKey). Handlers fire on the main thread's event loop, so the
+ * main run loop must be running (NSApplication or CFRunLoopRun). Registration and unregistration run on
+ * the main queue when called off-main while it is running, which also serializes them with callbacks.
+ */
+typedef void (*hotkey_callback_t)(void *userdata);
+
+@interface SyphonGlueHotKey : NSObject
+@property (nonatomic) EventHotKeyRef ref;
+@property (nonatomic) UInt32 identifier;
+@property (nonatomic) hotkey_callback_t callback;
+@property (nonatomic) hotkey_callback_t releaseUserdata;
+@property (nonatomic) void *userdata;
+@end
+
+@implementation SyphonGlueHotKey
+@end
+
+static NSMutableDictionary<NSNumber *, SyphonGlueHotKey *> *glue_hotkeys;
+static EventHandlerRef glue_hotkey_handler;
+static UInt32 glue_hotkey_next_id = 1;
+
+static OSStatus glue_hotkey_event(EventHandlerCallRef next, EventRef event, void *data) {
+    (void)next; (void)data;
+    EventHotKeyID hk;
+    if (GetEventParameter(event, kEventParamDirectObject, typeEventHotKeyID, NULL, sizeof(hk), NULL, &hk) != noErr) {
+        return eventNotHandledErr;
+    }
+    SyphonGlueHotKey *entry = glue_hotkeys[@(hk.id)];
+    if (!entry || !entry.callback) return eventNotHandledErr;
+    @autoreleasepool { entry.callback(entry.userdata); }
+    return noErr;
+}
+
+static void glue_on_main(dispatch_block_t block) {
+    if ([NSThread isMainThread] || !glue_main_run_loop_is_running()) {
+        block();
+    } else {
+        dispatch_sync(dispatch_get_main_queue(), block);
+    }
+}
+
+void *syphon_hotkey_register(uint32_t key_code, uint32_t modifiers, void (*callback)(void *userdata),
+    void (*release_userdata)(void *userdata), void *userdata) {
+    __block SyphonGlueHotKey *result = nil;
+    glue_on_main(^{
+        if (!glue_hotkeys) glue_hotkeys = [NSMutableDictionary dictionary];
+        if (!glue_hotkey_handler) {
+            EventTypeSpec spec = { kEventClassKeyboard, kEventHotKeyPressed };
+            if (InstallEventHandler(GetApplicationEventTarget(), glue_hotkey_event, 1, &spec, NULL,
+                    &glue_hotkey_handler) != noErr) {
+                return;
+            }
+        }
+        SyphonGlueHotKey *entry = [[SyphonGlueHotKey alloc] init];
+        entry.identifier = glue_hotkey_next_id++;
+        EventHotKeyID hk = { 'SyGl', entry.identifier };
+        EventHotKeyRef ref = NULL;
+        if (RegisterEventHotKey(key_code, modifiers, hk, GetApplicationEventTarget(), 0, &ref) != noErr) return;
+        entry.ref = ref;
+        entry.callback = callback;
+        entry.releaseUserdata = release_userdata;
+        entry.userdata = userdata;
+        glue_hotkeys[@(entry.identifier)] = entry;
+        result = entry;
+    });
+    if (!result) {
+        if (release_userdata) release_userdata(userdata);
+        return NULL;
+    }
+    return (__bridge void *)result;
+}
+
+void syphon_hotkey_unregister(void *hotkey) {
+    SyphonGlueHotKey *entry = (__bridge SyphonGlueHotKey *)hotkey;
+    if (!entry) return;
+    glue_on_main(^{
+        UnregisterEventHotKey(entry.ref);
+        [glue_hotkeys removeObjectForKey:@(entry.identifier)];
+    });
+    if (entry.releaseUserdata) entry.releaseUserdata(entry.userdata);
+}
+#endif /* SYPHON_GLUE_HOTKEY */
+
 #endif /* __APPLE__ */
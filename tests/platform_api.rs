@@ -1,3 +1,452 @@
+mod common {
+    use rusty_syphon_spout::*;
+
+    #[test]
+    fn overlay_text_bitmap_dimensions() {
+        let bitmap = render_overlay_text(&["FPS 60.0 #1", "12:00:00.000"], 2);
+        // 12 columns of 6px cells minus trailing spacing, 2 rows of 8px cells, 2px padding, scaled x2.
+        assert_eq!(bitmap.width, (2 * 2 + 12 * 6 - 1) * 2);
+        assert_eq!(bitmap.height, (2 * 2 + 2 * 8 - 1) * 2);
+        assert_eq!(bitmap.rgba.len(), bitmap.width * bitmap.height * 4);
+        assert!(bitmap.rgba.chunks_exact(4).any(|px| px == [255, 255, 255, 255]));
+        assert!(bitmap.rgba.chunks_exact(4).all(|px| px[3] == 255));
+    }
+
+    #[test]
+    fn receiver_set_tracks_nothing_without_servers() {
+        let mut set = ReceiverSet::new(std::ptr::null_mut());
+        set.set_auto_add(Some(Box::new(|_| false)));
+        set.sync();
+        assert!(set.is_empty());
+        assert!(!set.remove("missing"));
+        assert!(!set.is_connected("missing"));
+        assert_eq!(set.try_recv(), None);
+    }
+
+    #[test]
+    fn srgb_conversion_tables_roundtrip() {
+        let mut pixels = vec![0, 128, 255, 77, 255, 0, 0, 0];
+        SrgbConversion::Encode.apply_rgba8(&mut pixels);
+        // Linear 0.5 encodes to sRGB ~0.735; endpoints and alpha are unchanged.
+        assert_eq!(pixels, [0, 188, 255, 77, 255, 0, 0, 0]);
+        SrgbConversion::Decode.apply_rgba8(&mut pixels);
+        assert_eq!(pixels, [0, 128, 255, 77, 255, 0, 0, 0]);
+        SrgbConversion::None.apply_rgba8(&mut pixels);
+        assert_eq!(pixels[1], 128);
+    }
+
+    #[test]
+    fn blend_clock_ramps_over_source_interval() {
+        use std::time::{Duration, Instant};
+
+        let t0 = Instant::now();
+        let mut clock = BlendClock::new(BlendMode::Linear);
+        assert_eq!(clock.factor(t0), 1.0);
+        clock.push(t0);
+        assert_eq!(clock.factor(t0 + Duration::from_millis(10)), 1.0);
+        clock.push(t0 + Duration::from_millis(40));
+        assert_eq!(clock.source_interval(), Some(Duration::from_millis(40)));
+        assert_eq!(clock.factor(t0 + Duration::from_millis(40)), 0.0);
+        assert!((clock.factor(t0 + Duration::from_millis(50)) - 0.25).abs() < 1e-4);
+        assert_eq!(clock.factor(t0 + Duration::from_millis(200)), 1.0);
+        clock.set_mode(BlendMode::Off);
+        assert_eq!(clock.factor(t0 + Duration::from_millis(50)), 1.0);
+    }
+
+    #[test]
+    #[cfg(not(target_os = "macos"))]
+    fn gl_helpers_report_missing_context() {
+        let err = try_gl_create_texture_rectangle_rgba8(2, 2, &[0u8; 16]).unwrap_err();
+        assert_eq!(
+            err,
+            SyphonError::NoCurrentContext {
+                expected: None,
+                actual: None
+            }
+        );
+        assert!(err.to_string().contains("cgl_make_current"));
+        assert_eq!(gl_create_texture_rectangle_rgba8(2, 2, &[0u8; 16]), 0);
+        assert!(try_gl_read_texture_2d_rgba8(1, 3, 3, &mut [0u8; 36]).is_err());
+        assert!(try_gl_create_texture_rectangle_bgra8(1, 1, &[0u8; 4]).is_err());
+        assert_eq!(gl_create_texture_2d_rgba16f(1, 1, &[0u16; 4]), 0);
+        assert!(AsyncReadback::new().is_none());
+    }
+
+    #[test]
+    #[cfg(not(target_os = "macos"))]
+    fn constructors_report_unsupported_platform() {
+        let err = OpenGLServer::try_new(Some("x"), std::ptr::null_mut(), None).err();
+        assert_eq!(err, Some(SyphonError::Unsupported));
+        let err = OpenGLServer::try_new_with_current_context(Some("x"), None).err();
+        assert_eq!(err, Some(SyphonError::Unsupported));
+        let err = OpenGLServer::try_with_nsopengl_context(Some("x"), std::ptr::null_mut(), None).err();
+        assert_eq!(err, Some(SyphonError::Unsupported));
+        #[cfg(not(target_os = "windows"))]
+        assert_eq!(ServerDirectory::try_shared().err(), Some(SyphonError::Unsupported));
+        assert_eq!(try_cgl_create_headless_context().err(), Some(SyphonError::Unsupported));
+        let err = MetalServer::try_new_with_command_queue(Some("x"), std::ptr::null_mut(), None, None).err();
+        assert_eq!(err, Some(SyphonError::Unsupported));
+        assert_eq!(MetalServer::try_new_with_default_device(None, None).err(), Some(SyphonError::Unsupported));
+        assert_eq!(CpuServer::try_new(Some("x"), None).err(), Some(SyphonError::Unsupported));
+        assert_eq!(MetalTextureCache::try_new(std::ptr::null_mut()).err(), Some(SyphonError::Unsupported));
+        #[cfg(feature = "screen-capture")]
+        {
+            assert_eq!(shareable_displays().err(), Some(SyphonError::Unsupported));
+            let options = ScreenCaptureOptions::default();
+            let err = ScreenCapture::try_new(Some("x"), CaptureSource::Display(1), &options).err();
+            assert_eq!(err, Some(SyphonError::Unsupported));
+        }
+        #[cfg(feature = "camera")]
+        {
+            assert!(camera_devices().is_empty());
+            let err = CameraCapture::try_new(Some("x"), None, &CameraCaptureOptions::default()).err();
+            assert_eq!(err, Some(SyphonError::Unsupported));
+        }
+        #[cfg(feature = "recorder")]
+        {
+            let err = Recorder::try_new("x.mov", &RecorderOptions::default()).err();
+            assert_eq!(err, Some(SyphonError::Unsupported));
+        }
+        #[cfg(feature = "encoder")]
+        {
+            let err = Encoder::try_new(&EncoderOptions::default(), Box::new(|_| {})).err();
+            assert_eq!(err, Some(SyphonError::Unsupported));
+        }
+        let err = HeadlessContext::builder().core_profile(false).samples(4).try_build().err();
+        assert_eq!(err, Some(SyphonError::Unsupported));
+        assert!(SyphonError::Unsupported.to_string().contains("macOS"));
+        let err = SyphonError::CreationFailed {
+            object: "Metal server",
+            reason: "init returned nil".into(),
+        };
+        assert_eq!(err.to_string(), "failed to create Metal server: init returned nil");
+    }
+
+    #[test]
+    #[cfg(all(feature = "relay", not(target_os = "macos")))]
+    fn relay_reports_unsupported_platform() {
+        let options = RelayReceiverOptions::default();
+        let err = RelayReceiver::try_new("127.0.0.1:9", Some("x"), &options).err();
+        assert_eq!(err, Some(SyphonError::Unsupported));
+        for max_fps in [0.0, 1e-20, f64::NAN] {
+            let err = RelayReceiver::try_new(
+                "127.0.0.1:9",
+                None,
+                &RelayReceiverOptions {
+                    max_fps: Some(max_fps),
+                    ..options
+                },
+            )
+            .err();
+            assert!(matches!(err, Some(SyphonError::InvalidArgument(_))), "{max_fps}");
+        }
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn spout_types_report_unsupported_platform() {
+        assert_eq!(SpoutServer::try_new(Some("x")).err(), Some(SyphonError::Unsupported));
+        assert_eq!(SpoutClient::try_new(None).err(), Some(SyphonError::Unsupported));
+        let err = SpoutDxServer::try_new(Some("x"), std::ptr::null_mut()).err();
+        assert_eq!(err, Some(SyphonError::Unsupported));
+        let err = SpoutDxClient::try_new(None, std::ptr::null_mut()).err();
+        assert_eq!(err, Some(SyphonError::Unsupported));
+        let err = SpoutDx12Server::try_new(Some("x"), std::ptr::null_mut(), std::ptr::null_mut()).err();
+        assert_eq!(err, Some(SyphonError::Unsupported));
+        let err = SpoutDx12Client::try_new(None, std::ptr::null_mut(), std::ptr::null_mut()).err();
+        assert_eq!(err, Some(SyphonError::Unsupported));
+        assert!(!gl_dx_interop_available());
+        assert_eq!(SpoutGlDxServer::try_new(Some("x")).err(), Some(SyphonError::Unsupported));
+        assert_eq!(SpoutGlDxClient::try_new(None).err(), Some(SyphonError::Unsupported));
+    }
+
+    #[test]
+    fn texture_share_api_surface_compiles() {
+        let _: fn() -> Vec<ServerDescriptionInfo> = texture_share_sources;
+        let _: fn(Option<&str>) -> Result<TextureShareServer, SyphonError> = TextureShareServer::try_new;
+        let _: fn(&TextureShareServer, u32, u32, f64, f64, bool) = TextureShareServer::publish_whole_texture;
+        let _: fn(&TextureShareServer) -> Option<ServerDescriptionInfo> = TextureShareServer::source;
+        let _: fn(&ServerDescriptionInfo) -> Result<TextureShareClient, SyphonError> = TextureShareClient::try_new;
+        let _: fn(&TextureShareClient) -> Option<(usize, usize)> = TextureShareClient::frame_size;
+        let _: fn(&TextureShareClient, u32, u32) -> bool = TextureShareClient::copy_frame_to;
+        let _: fn(&TextureShareClient) -> Option<CpuFrame> = TextureShareClient::latest_frame;
+    }
+
+    #[test]
+    #[cfg(not(any(target_os = "macos", target_os = "windows", feature = "linux-shm")))]
+    fn texture_share_reports_unsupported_platform() {
+        assert!(texture_share_sources().is_empty());
+        assert_eq!(TextureShareServer::try_new(Some("x")).err(), Some(SyphonError::Unsupported));
+        let source = ServerDescriptionInfo {
+            name: Some("x".into()),
+            ..Default::default()
+        };
+        assert_eq!(TextureShareClient::try_new(&source).err(), Some(SyphonError::Unsupported));
+    }
+
+    #[test]
+    #[cfg(any(target_os = "macos", all(target_os = "linux", feature = "linux-shm")))]
+    fn shared_frame_reader_follows_exporter() {
+        let name = format!("/rss-reader-{}", std::process::id());
+        let Some(mut exporter) = SharedFrameExporter::new(&name, 2, 2) else {
+            return;
+        };
+        let reader = SharedFrameReader::open(&name).expect("exported region opens");
+        assert_eq!(reader.frame_size(), None);
+        let rgba: Vec<u8> = (0..16).collect();
+        assert!(exporter.publish_rgba(2, 2, &rgba));
+        assert_eq!(reader.frames_written(), 1);
+        assert_eq!(reader.frame_size(), Some((2, 2)));
+        assert_eq!(reader.read_frame().map(|f| f.rgba), Some(rgba));
+        assert!(!exporter.publish_rgba(0, 2, &[]));
+        assert!(reader.is_live());
+        drop(exporter);
+        assert!(!reader.is_live());
+        assert!(SharedFrameReader::open(&name).is_none());
+    }
+
+    /// Overwrite the width and flags of the frame header in the region `name` (Linux maps POSIX
+    /// shared memory under `/dev/shm`), as a misbehaving writer could.
+    #[cfg(all(target_os = "linux", feature = "linux-shm"))]
+    fn corrupt_shm_header(name: &str, width: u32, flags: u32) {
+        use std::io::{Seek, SeekFrom, Write};
+        let path = format!("/dev/shm/{}", name.trim_start_matches('/'));
+        let mut file = std::fs::OpenOptions::new().write(true).open(path).expect("region file");
+        file.seek(SeekFrom::Start(16)).expect("seek to width");
+        file.write_all(&width.to_le_bytes()).expect("write width");
+        file.seek(SeekFrom::Start(28)).expect("seek to flags");
+        file.write_all(&flags.to_le_bytes()).expect("write flags");
+    }
+
+    #[test]
+    #[cfg(all(target_os = "linux", feature = "linux-shm"))]
+    fn shared_frame_reader_rejects_empty_frames() {
+        let name = format!("/rss-empty-{}", std::process::id());
+        let mut exporter = SharedFrameExporter::new(&name, 2, 2).expect("region created");
+        assert!(exporter.publish_rgba(2, 2, &[0; 16]));
+        corrupt_shm_header(&name, 0, SHM_FLAG_BOTTOM_UP);
+        let reader = SharedFrameReader::open(&name).expect("exported region opens");
+        assert_eq!(reader.frame_size(), None);
+        assert!(reader.read_frame().is_none());
+    }
+
+    #[test]
+    #[cfg(all(target_os = "linux", feature = "linux-shm"))]
+    fn texture_share_over_shared_memory() {
+        let name = format!("rss-share-{}", std::process::id());
+        let server = TextureShareServer::try_new(Some(&name)).expect("region created");
+        let source = server.source().expect("server has a source");
+        assert_eq!(source.name.as_deref(), Some(name.as_str()));
+        assert!(texture_share_sources().contains(&source));
+        // No GL context in tests: publishing fails before touching the region.
+        assert!(server.try_publish_whole_texture(1, GL_TEXTURE_RECTANGLE, 4.0, 4.0, false).is_err());
+
+        let client = TextureShareClient::try_new(&source).expect("running source opens");
+        assert_eq!(client.server_name().as_deref(), Some(name.as_str()));
+        assert!(client.is_valid());
+        assert_eq!(client.frame_size(), None);
+        assert!(client.latest_frame().is_none());
+        assert!(!client.copy_frame_to(1, GL_TEXTURE_RECTANGLE));
+        server.stop();
+        assert!(!client.is_valid());
+        assert!(TextureShareClient::try_new(&source).is_err());
+        let invalid = ServerDescriptionInfo {
+            name: Some("a/b".into()),
+            ..Default::default()
+        };
+        assert!(matches!(TextureShareClient::try_new(&invalid), Err(SyphonError::InvalidArgument(_))));
+    }
+
+    #[test]
+    #[cfg(all(target_os = "linux", feature = "linux-shm"))]
+    fn texture_share_client_rejects_empty_frames() {
+        let name = format!("rss-share-empty-{}", std::process::id());
+        let region = format!("/texture-share.{name}");
+        let mut exporter = SharedFrameExporter::new(&region, 2, 2).expect("region created");
+        assert!(exporter.publish_rgba(2, 2, &[0; 16]));
+        corrupt_shm_header(&region, 0, SHM_FLAG_BOTTOM_UP);
+        let source = ServerDescriptionInfo {
+            name: Some(name),
+            ..Default::default()
+        };
+        let client = TextureShareClient::try_new(&source).expect("running source opens");
+        assert_eq!(client.frame_size(), None);
+        assert!(client.latest_frame().is_none());
+        assert!(!client.copy_frame_to(1, GL_TEXTURE_RECTANGLE));
+    }
+
+    #[test]
+    fn directory_snapshot_json_escapes_strings() {
+        let snapshot = DirectorySnapshot {
+            taken_at_ms: 1_700_000_000_000,
+            servers: vec![ServerSnapshot {
+                uuid: Some("u-1".into()),
+                name: Some("Main \"Out\"\n".into()),
+                app_name: Some("C:\\VJ\u{1}".into()),
+                backend: "syphon".into(),
+                surface_types: vec!["SyphonSurfaceTypeIOSurface".into()],
+                first_seen_ms: 1_699_999_999_000,
+                ..Default::default()
+            }],
+        };
+        assert_eq!(
+            snapshot.to_json(),
+            concat!(
+                r#"{"taken_at_ms":1700000000000,"servers":[{"uuid":"u-1","name":"Main \"Out\"\n","#,
+                r#""app_name":"C:\\VJ\u0001","bundle_identifier":null,"backend":"syphon","#,
+                r#""surface_types":["SyphonSurfaceTypeIOSurface"],"width":null,"height":null,"format":null,"#,
+                r#""first_seen_ms":1699999999000}]}"#
+            )
+        );
+        assert_eq!(DirectorySnapshot::default().to_json(), r#"{"taken_at_ms":0,"servers":[]}"#);
+    }
+
+    #[test]
+    fn test_patterns_render_expected_pixels() {
+        let px = |rgba: &[u8], width: usize, x: usize, y: usize| rgba[(y * width + x) * 4..][..4].to_vec();
+        let bars = render_test_pattern(TestPattern::SmpteBars, 280, 120, 0);
+        assert_eq!(bars.len(), 280 * 120 * 4);
+        assert_eq!(px(&bars, 280, 0, 0), [192, 192, 192, 255]);
+        assert_eq!(px(&bars, 280, 279, 0), [0, 0, 192, 255]);
+        assert_eq!(px(&bars, 280, 0, 85), [0, 0, 192, 255]);
+        assert_eq!(px(&bars, 280, 60, 119), [255, 255, 255, 255]);
+        assert_eq!(px(&bars, 280, 200, 119), [9, 9, 9, 255]);
+        assert_eq!(px(&bars, 280, 235, 119), [29, 29, 29, 255]);
+
+        let gradient = render_test_pattern(TestPattern::Gradient, 256, 4, 0);
+        assert_eq!(px(&gradient, 256, 0, 0), [0, 0, 0, 255]);
+        assert_eq!(px(&gradient, 256, 255, 0), [255, 255, 255, 255]);
+        assert_eq!(px(&gradient, 256, 255, 1), [255, 0, 0, 255]);
+        assert_eq!(px(&gradient, 256, 128, 3), [0, 0, 128, 255]);
+
+        // An 8px box bouncing across 56px comes back after 112 frames.
+        let moving = |index| render_test_pattern(TestPattern::MovingBox, 64, 64, index);
+        assert_eq!(moving(0), moving(112));
+        assert_ne!(moving(0), moving(1));
+        assert!(render_test_pattern(TestPattern::Gradient, 0, 4, 0).is_empty());
+    }
+
+    #[test]
+    fn test_pattern_server_rejects_bad_options() {
+        let zero = TestPatternOptions {
+            width: 0,
+            ..Default::default()
+        };
+        assert!(matches!(TestPatternServer::try_new(None, &zero), Err(SyphonError::InvalidArgument(_))));
+        for fps in [0.0, 1e-20, f64::INFINITY] {
+            let stopped = TestPatternOptions {
+                fps,
+                ..Default::default()
+            };
+            let err = TestPatternServer::try_new(None, &stopped).err();
+            assert!(matches!(err, Some(SyphonError::InvalidArgument(_))), "{fps}");
+        }
+        #[cfg(not(target_os = "macos"))]
+        assert_eq!(TestPatternServer::try_new(None, &Default::default()).err(), Some(SyphonError::Unsupported));
+    }
+
+    #[test]
+    fn image_sequence_orders_numbered_frames() {
+        let dir = std::env::temp_dir().join(format!("rss-sequence-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["shot_0010.png", "shot_0002.png", "shot_0009.png", "other_0001.png", "shot_0003.exr", "notes.txt"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+        let frames = image_sequence(&dir.join("shot_0009.png"));
+        assert_eq!(frames, ["shot_0002.png", "shot_0009.png", "shot_0010.png"].map(|n| dir.join(n)));
+        assert_eq!(image_sequence(&dir.join("notes.txt")), [dir.join("notes.txt")]);
+        let listed = image_sequence(&dir);
+        assert!(!listed.contains(&dir.join("notes.txt")));
+        assert_eq!(listed.len(), if cfg!(feature = "png") { 4 } else { 0 } + if cfg!(feature = "exr") { 1 } else { 0 });
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(load_image(&dir.join("notes.txt")), Err(SyphonError::InvalidArgument(_))));
+        let options = ImagePublisherOptions::default();
+        assert!(matches!(ImagePublisher::try_new(None, Vec::new(), &options), Err(SyphonError::InvalidArgument(_))));
+        for fps in [0.0, 1e-20, f64::NAN] {
+            let options = ImagePublisherOptions { fps, ..options };
+            let err = ImagePublisher::try_new(None, vec![dir.join("a.png")], &options).err();
+            assert!(matches!(err, Some(SyphonError::InvalidArgument(_))), "{fps}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn load_image_expands_png_to_rgba() {
+        let path = std::env::temp_dir().join(format!("rss-still-{}.png", std::process::id()));
+        let mut encoder = png::Encoder::new(std::fs::File::create(&path).unwrap(), 2, 1);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header().unwrap().write_image_data(&[255, 0, 0, 0, 0, 255]).unwrap();
+        let image = load_image(&path).unwrap();
+        assert_eq!((image.width, image.height), (2, 1));
+        assert_eq!(image.rgba, [255, 0, 0, 255, 0, 0, 255, 255]);
+        #[cfg(not(target_os = "macos"))]
+        assert_eq!(
+            ImagePublisher::try_new(None, vec![path.clone()], &Default::default()).err(),
+            Some(SyphonError::Unsupported)
+        );
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(load_image(&path), Err(SyphonError::CreationFailed { .. })));
+    }
+
+    #[test]
+    fn size_changes_are_reported_before_the_frame() {
+        use std::sync::{Arc, Mutex};
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let (frames, sizes) = (log.clone(), log.clone());
+        let callback = track_size_changes(
+            Some(Box::new(move |info: FrameInfo| frames.lock().unwrap().push(format!("frame {}", info.frame_index)))),
+            Box::new(move |c: SizeChange| {
+                sizes.lock().unwrap().push(format!("{}x{} -> {}x{}", c.old_width, c.old_height, c.width, c.height))
+            }),
+        );
+        let frame = |frame_index, size| FrameInfo {
+            frame_index,
+            timestamp: std::time::Instant::now(),
+            size,
+        };
+        callback(frame(1, Some((64, 32))));
+        callback(frame(2, Some((64, 32))));
+        callback(frame(3, None));
+        callback(frame(4, Some((128, 32))));
+        assert_eq!(
+            *log.lock().unwrap(),
+            ["0x0 -> 64x32", "frame 1", "frame 2", "frame 3", "64x32 -> 128x32", "frame 4"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "hotkey")]
+    fn hotkey_parse_combinations() {
+        assert_eq!(
+            HotKey::parse("cmd+shift+s"),
+            Some(HotKey::new(0x01, HOTKEY_CMD | HOTKEY_SHIFT))
+        );
+        assert_eq!(
+            HotKey::parse(" Ctrl + Option + F5 "),
+            Some(HotKey::new(0x60, HOTKEY_CONTROL | HOTKEY_OPTION))
+        );
+        assert_eq!(HotKey::parse("space"), Some(HotKey::new(0x31, 0)));
+        assert_eq!(HotKey::parse("cmd+shift"), None);
+        assert_eq!(HotKey::parse("cmd+a+b"), None);
+        assert_eq!(HotKey::parse("cmd+nope"), None);
+    }
+
+    #[test]
+    fn overlay_toggle_keeps_counting_frames() {
+        let overlay = DebugOverlay::new(Some("Deck A"));
+        assert!(overlay.is_enabled());
+        assert!(!overlay.toggle());
+        assert!(!overlay.stamp_gl(0, GL_TEXTURE_RECTANGLE, 640, 360, false));
+        assert!(!overlay.stamp_gl(0, GL_TEXTURE_RECTANGLE, 640, 360, false));
+        assert_eq!(overlay.sequence(), 2);
+        assert!(overlay.toggle());
+        assert!(overlay.is_enabled());
+    }
+}
+
 #[cfg(target_os = "macos")]
 mod macos {
     use rusty_syphon_spout::*;
@@ -10,19 +459,333 @@ mod macos {
         let _: fn(usize, usize, &[u8]) -> u32 = gl_create_texture_rectangle_rgba8;
         let _: fn(u32, usize, usize, &mut [u8]) = gl_read_texture_rectangle_rgba8;
         let _: fn(u32) = gl_delete_texture;
+        let _: fn() -> CGLContextObj = cgl_current_context;
+        let _: fn(*mut std::ffi::c_void) -> Option<CGLContextObj> = cgl_context_from_nsopengl_context;
+        let _: fn(Option<CGLContextObj>) -> Result<(), SyphonError> = check_current_context;
+        let _: fn(usize, usize, &[u8]) -> Result<u32, SyphonError> = try_gl_create_texture_rectangle_rgba8;
+        let _: fn(u32, usize, usize, &mut [u8]) -> Result<bool, SyphonError> = try_gl_read_texture_rectangle_rgba8;
+        let _: fn(usize, usize, &[u8]) -> u32 = gl_create_texture_2d_rgba8;
+        let _: fn(u32, usize, usize, &mut [u8]) = gl_read_texture_2d_rgba8;
+        let _: fn(usize, usize, &[u8]) -> u32 = gl_create_texture_rectangle_bgra8;
+        let _: fn(u32, usize, usize, &mut [u8]) = gl_read_texture_2d_bgra8;
+        let _: fn(usize, usize, &[u16]) -> u32 = gl_create_texture_2d_rgba16f;
+        let _: fn() -> Option<AsyncReadback> = AsyncReadback::new;
+        let _: fn(&OpenGLImage, u32, u32, usize, usize) -> bool = gl_copy_image_to_texture;
+        let _: fn(&mut MsaaResolver, &OpenGLServer, u32, usize, usize) -> bool = MsaaResolver::resolve_and_publish;
+        let _: fn(&mut GlSrgbConverter, &OpenGLServer, u32, u32, usize, usize, SrgbConversion) -> bool =
+            GlSrgbConverter::publish;
+        let _: fn(MTLDevicePtr) -> Option<MetalSrgbConverter> = MetalSrgbConverter::new;
+        let _: fn(&OpenGLImage, SrgbConversion) -> Vec<u8> = OpenGLImage::read_pixels_converted;
+        let _: fn(ReadbackFuture<'static>) -> Vec<u8> = ReadbackFuture::wait;
+        let _: fn(u32, usize, usize, &mut [u16]) -> Result<bool, SyphonError> = try_gl_read_texture_rectangle_rgba16f;
+        let _: fn(u32, u32, usize, usize, usize, usize, &[u8]) -> Result<bool, SyphonError> =
+            try_gl_write_texture_region_rgba8;
+        let _: fn(&OpenGLServer, f64, f64) -> Result<bool, SyphonError> = OpenGLServer::try_bind_to_draw_frame;
+        let _: fn(u32, u32, usize, usize, usize, usize, &[u8]) -> bool = gl_write_texture_region_rgba8;
+        let _: fn(&DebugOverlay, u32, u32, usize, usize, bool) -> bool = DebugOverlay::stamp_gl;
+        let _: fn(&DebugOverlay, MTLTexturePtr, MTLCommandBufferPtr, usize, usize, bool) -> bool =
+            DebugOverlay::stamp_metal;
         let _: fn() -> Option<String> = notification_name_server_announce;
         let _: fn() -> Option<String> = notification_name_server_update;
         let _: fn() -> Option<String> = notification_name_server_retire;
         let _: fn() -> Option<ServerDirectory> = ServerDirectory::shared;
         let _: fn() -> Option<SyphonOptions> = SyphonOptions::new;
+        let _: fn(&ServerDescription) -> Option<String> = ServerDescription::bundle_identifier;
+        let _: fn(&ServerDescription, usize) -> Option<AppIcon> = ServerDescription::app_icon;
+        let _: fn(&ServerDescription, std::time::Duration) -> Option<ServerFrameSize> = probe_server_size;
         let _: fn(Option<&str>, CGLContextObj, Option<&SyphonOptions>) -> Option<OpenGLServer> =
             OpenGLServer::new;
-        let _: fn(&ServerDescription, CGLContextObj, Option<&std::collections::HashMap<String, String>>, Option<NewFrameCallback>) -> Option<OpenGLClient> =
+        let _: fn(&ServerDescription, CGLContextObj, Option<&SyphonOptions>, Option<NewFrameCallback>) -> Option<OpenGLClient> =
             OpenGLClient::new;
+        let _: fn(&ServerDescription, CGLContextObj, Option<&SyphonOptions>, Option<NewFrameCallback>, CallbackDispatch) -> Option<OpenGLClient> =
+            OpenGLClient::new_with_dispatch;
+        let _: fn(&ServerDescription, MTLDevicePtr, Option<&SyphonOptions>, Option<NewFrameCallback>, CallbackDispatch) -> Option<MetalClient> =
+            MetalClient::new_with_dispatch;
+        let _: fn(&OpenGLClient, u32, u32) -> bool = OpenGLClient::copy_frame_to;
+        let _: fn(&OpenGLClient, usize, &std::path::Path, CaptureFormat) -> std::io::Result<Vec<std::path::PathBuf>> =
+            OpenGLClient::capture_sequence;
+        let _: fn(&str, usize, usize) -> Option<SharedFrameExporter> = SharedFrameExporter::new;
+        let _: fn(&mut SharedFrameExporter, usize, usize, &[u8]) -> bool = SharedFrameExporter::publish_rgba;
+        let _: fn(&mut SharedFrameExporter, &OpenGLImage) -> bool = SharedFrameExporter::publish_gl_image;
+        let _: fn(&str) -> Option<SharedFrameReader> = SharedFrameReader::open;
+        let _: fn(Option<&str>, &TestPatternOptions) -> Option<TestPatternServer> = TestPatternServer::new;
+        let _: fn(&TestPatternServer, TestPattern) = TestPatternServer::set_pattern;
+        let _: fn(&TestPatternServer) -> u64 = TestPatternServer::frames_published;
+        let _: fn(&std::path::Path) -> Result<StillImage, SyphonError> = load_image;
+        let _: fn(Option<&str>, Vec<std::path::PathBuf>, &ImagePublisherOptions) -> Option<ImagePublisher> =
+            ImagePublisher::new;
+        let _: fn(&ImagePublisher) -> Option<SyphonError> = ImagePublisher::last_error;
+        let _: fn(&SharedFrameReader) -> bool = SharedFrameReader::is_live;
+        let _: fn(&SharedFrameReader) -> Option<(usize, usize)> = SharedFrameReader::frame_size;
+        let _: fn(&SharedFrameReader) -> Option<CpuFrame> = SharedFrameReader::read_frame;
+        #[cfg(feature = "pasteboard")]
+        {
+            let _: fn(usize, usize, &[u8], PasteboardFormats) -> bool = copy_rgba_to_pasteboard;
+            let _: fn(&OpenGLImage, PasteboardFormats) -> bool = copy_gl_image_to_pasteboard;
+            let _: fn(usize, usize, &[u8], PasteboardFormats, Option<(f64, f64)>) -> Option<FrameDragItem> =
+                FrameDragItem::from_rgba;
+        }
+        #[cfg(feature = "hotkey")]
+        {
+            let _: fn(HotKey, Box<dyn Fn() + Send>) -> Option<GlobalHotKey> = GlobalHotKey::register;
+            let _: fn(SnapshotConfig) -> Option<SnapshotService> = SnapshotService::start;
+            let _: fn(&SnapshotService) -> Option<std::io::Result<std::path::PathBuf>> = SnapshotService::try_recv;
+        }
+        let _: fn(&OpenGLImage, Option<u32>) -> Option<u32> = OpenGLImage::copy_to_owned_texture;
+        let _: fn(&OpenGLImage) -> Option<IOSurfacePtr> = OpenGLImage::io_surface_ptr;
+        let _: fn(&OpenGLImage) -> Option<PixelBuffer> = OpenGLImage::to_pixel_buffer;
+        let _: fn(&OpenGLServer, u32, u32, f64, f64, bool) = OpenGLServer::publish_whole_texture;
+        let _: fn(&OpenGLServer, u32, u32, Rect, Size, bool) -> Result<(), SyphonError> = OpenGLServer::try_publish;
+        let _: fn(&MetalServer, MTLTexturePtr, MTLCommandBufferPtr, Rect, bool) = MetalServer::publish;
+        let _: fn(&OpenGLServer, f64, f64) -> Option<DrawFrameGuard<'_>> = OpenGLServer::draw_frame;
+        let _: fn(&OpenGLImage) -> Vec<u8> = OpenGLImage::read_pixels;
+        let _: fn(&OpenGLImage) -> Result<Vec<u8>, SyphonError> = OpenGLImage::try_read_pixels;
         let _: fn(Option<&str>, MTLDevicePtr, Option<&SyphonOptions>) -> Option<MetalServer> =
             MetalServer::new;
-        let _: fn(&ServerDescription, MTLDevicePtr, Option<&std::collections::HashMap<String, String>>, Option<NewFrameCallback>) -> Option<MetalClient> =
+        let _: fn(&MetalServer, CAMetalDrawablePtr, MTLCommandBufferPtr, bool) -> bool =
+            MetalServer::publish_drawable;
+        let _: fn(&ServerDescription, MTLDevicePtr, Option<&SyphonOptions>, Option<NewFrameCallback>) -> Option<MetalClient> =
             MetalClient::new;
+        let _: fn(&MetalClient, MTLTexturePtr, MTLCommandBufferPtr) -> bool = MetalClient::copy_frame_to;
+        let _: fn(&OpenGLServer, u32, u32, Rect, Size, bool) -> bool = OpenGLServer::publish_dirty_region;
+        let _: fn(&MetalServer, MTLCommandBufferPtr, Rect, Size, &[u8]) -> bool = MetalServer::publish_rgba_region;
+        let _: fn(&MetalServer, &[u8], usize, usize, bool) -> bool = MetalServer::publish_pixels;
+        let _: fn(Option<&str>, MTLDevicePtr, Option<&SyphonOptions>, Option<MTLCommandQueuePtr>) -> Option<MetalServer> =
+            MetalServer::new_with_command_queue;
+        let _: fn(Option<&str>, Option<&SyphonOptions>) -> Option<MetalServer> = MetalServer::new_with_default_device;
+        let _: fn(&ServerDescription, CGLContextObj, Option<&SyphonOptions>, Option<NewFrameCallback>, SizeChangeCallback) -> Option<OpenGLClient> =
+            OpenGLClient::new_with_size_changes;
+        let _: fn(&ServerDescription, MTLDevicePtr, Option<&SyphonOptions>, Option<NewFrameCallback>, SizeChangeCallback) -> Option<MetalClient> =
+            MetalClient::new_with_size_changes;
+        let _: fn(&MetalServer, MTLTexturePtr, Rect, bool) -> bool = MetalServer::publish_frame_simple;
+        let _: fn(&MetalServer) -> MTLCommandQueuePtr = MetalServer::command_queue;
+        let _: fn(&MetalServer, MTLTexturePtr, Option<MTLCommandBufferPtr>, Rect, bool, MTLSharedEventPtr, u64) -> bool =
+            MetalServer::publish_after_event;
+        let _: fn(&MetalTexture, Option<MTLCommandQueuePtr>) -> Option<MetalTexture> = MetalTexture::copy_to_owned;
+        let _: fn(&SyphonOptions, &str, bool) = SyphonOptions::set_bool;
+        let _: fn(Option<&str>, CGLContextObj) -> Option<OpenGLServer> = OpenGLServer::new_private;
+        let _: fn(Option<&str>, CGLContextObj, Option<&SyphonOptions>) -> Result<OpenGLServer, SyphonError> =
+            OpenGLServer::try_new;
+        let _: fn(Option<&str>, Option<&SyphonOptions>) -> Option<OpenGLServer> = OpenGLServer::new_with_current_context;
+        let _: fn(Option<&str>, *mut std::ffi::c_void, Option<&SyphonOptions>) -> Option<OpenGLServer> =
+            OpenGLServer::with_nsopengl_context;
+        let _: fn(
+            &ServerDescription,
+            *mut std::ffi::c_void,
+            Option<&SyphonOptions>,
+            Option<NewFrameCallback>,
+        ) -> Option<OpenGLClient> = OpenGLClient::with_nsopengl_context;
+        let _: fn(CGLContextObj) -> bool = cgl_context_is_core_profile;
+        let _: fn(&OpenGLServer, Option<&str>) -> Result<(), SyphonError> = OpenGLServer::try_set_name;
+        let _: fn(&OpenGLServer) -> Option<String> = OpenGLServer::name;
+        let _: fn(&MetalServer, Option<&str>) -> Result<(), SyphonError> = MetalServer::try_set_name;
+        let _: fn(&MetalServer) -> Option<String> = MetalServer::name;
+        let _: fn(&SyphonOptions, u64) = SyphonOptions::set_pixel_format;
+        let _: fn(&MetalServer) -> Option<u64> = MetalServer::pixel_format;
+        let _: fn(&MetalTexture) -> u64 = MetalTexture::pixel_format;
+        let _: fn(&MetalTexture) -> (usize, usize) = MetalTexture::size;
+        let _: fn(&MetalTexture) -> usize = MetalTexture::width;
+        let _: fn(&MetalTexture) -> u64 = MetalTexture::usage;
+        let _: fn(&MetalTexture) -> u64 = MetalTexture::storage_mode;
+        let _: fn(&MetalTexture) -> Option<IOSurfacePtr> = MetalTexture::io_surface_ptr;
+        let _: fn(&MetalTexture) -> Option<PixelBuffer> = MetalTexture::to_pixel_buffer;
+        let _: fn(&Frame) -> Option<PixelBuffer> = Frame::to_pixel_buffer;
+        let _: fn(IOSurfacePtr) -> Option<PixelBuffer> = PixelBuffer::from_io_surface;
+        let _: fn(MTLDevicePtr) -> Result<MetalTextureCache, SyphonError> = MetalTextureCache::try_new;
+        let _: fn(&MetalTextureCache, IOSurfacePtr, u64) -> Option<MetalTexture> = MetalTextureCache::texture_from_io_surface;
+        let _: fn(&MetalTextureCache, &MetalTexture) -> Option<MetalTexture> = MetalTextureCache::texture_for;
+        let _: fn(&MetalTextureCache) = MetalTextureCache::flush;
+        let _: fn(&MetalClient, &MetalTextureCache) -> Option<MetalTexture> = MetalClient::new_frame_image_cached;
+        let _: fn(MTLDevicePtr, IOSurfacePtr, u64) -> Option<MetalTexture> = MetalTexture::from_io_surface;
+        let _: fn(&PixelBuffer) -> (usize, usize) = PixelBuffer::size;
+        let _: fn(&PixelBuffer) -> u32 = PixelBuffer::pixel_format;
+        let _: fn(PixelBuffer) -> CVPixelBufferPtr = PixelBuffer::into_raw;
+        assert_send::<PixelBuffer>();
+        let _: fn(&MetalTexture) -> usize = MetalTexture::bytes_per_pixel;
+        let _: fn(&MetalTexture) -> MetalTexture = MetalTexture::clone;
+        let _: fn(Option<&str>, Option<&SyphonOptions>) -> Option<CpuServer> = CpuServer::new;
+        let _: fn(&CpuServer, &[u8], usize, usize) -> bool = CpuServer::publish;
+        let _: fn(&ServerDescription, Option<NewFrameCallback>) -> Result<CpuClient, SyphonError> = CpuClient::try_new;
+        let _: fn(&ServerDescription, Option<NewFrameCallback>, CallbackDispatch) -> Result<CpuClient, SyphonError> =
+            CpuClient::try_new_with_dispatch;
+        let _: fn(&CpuClient) -> Option<CpuFrame> = CpuClient::latest_frame;
+        fn describe<S: FrameServer>(server: &S) -> (Backend, Option<String>, bool) {
+            (server.backend(), server.name(), server.has_clients())
+        }
+        let _: fn(&OpenGLServer) -> (Backend, Option<String>, bool) = describe::<OpenGLServer>;
+        let _: fn(&MetalServer) -> (Backend, Option<String>, bool) = describe::<MetalServer>;
+        let _: fn(&OpenGLClient) -> Option<OpenGLImage> = <OpenGLClient as FrameClient>::new_frame_image;
+        let _: fn(&MetalClient) -> Option<MetalTexture> = <MetalClient as FrameClient>::new_frame_image;
+        let _: fn(&MetalClient) -> Backend = <MetalClient as FrameClient>::backend;
+        let _: fn(&OpenGLClient) -> Option<TimedFrame> = <OpenGLClient as FrameClient>::new_frame;
+        let _: fn(&MetalClient) -> Option<TimedFrame> = <MetalClient as FrameClient>::new_frame;
+        let _: fn(MetalTexture) -> Frame = Frame::from;
+        let _: fn(&Frame) -> (usize, usize) = Frame::size;
+        let _: fn(&Frame) -> bool = Frame::is_flipped;
+        let _: fn(&Frame) -> Option<&OpenGLImage> = Frame::as_opengl;
+        let _: fn(MetalTexture) -> SendableMetalTexture = SendableMetalTexture::new;
+        let _: fn(SendableMetalTexture) -> MetalTexture = SendableMetalTexture::into_inner;
+        assert_send::<SendableMetalTexture>();
+        let _: fn(&MetalTexture, Option<MTLCommandQueuePtr>) -> Vec<u8> = MetalTexture::read_to_vec;
+        let _: fn(HeadlessContextBuilder) -> Option<HeadlessContext> = HeadlessContextBuilder::build;
+        let _: fn(&HeadlessContext) -> CGLContextObj = HeadlessContext::context;
+        let _: fn(Option<&str>, MTLDevicePtr, Option<&SyphonOptions>) -> Result<MetalServer, SyphonError> =
+            MetalServer::try_new;
+        let _: fn(&ServerDescription, CGLContextObj, Option<&SyphonOptions>, Option<NewFrameCallback>) -> Result<OpenGLClient, SyphonError> =
+            OpenGLClient::try_new;
+        let _: fn(&ServerDescription, MTLDevicePtr, Option<&SyphonOptions>, Option<NewFrameCallback>, CallbackDispatch) -> Result<MetalClient, SyphonError> =
+            MetalClient::try_new_with_dispatch;
+        let _: fn() -> Result<ServerDirectory, SyphonError> = ServerDirectory::try_shared;
+        let _: fn(&ServerDirectory, DirectoryCallback) -> Option<DirectorySubscription> = ServerDirectory::subscribe;
+        let _: fn() -> bool = ServerDirectory::start_background_updates;
+        let _: fn(&OpenGLClient, InvalidationCallback) -> Option<DirectorySubscription> = OpenGLClient::on_invalidated;
+        let _: fn(&MetalClient, InvalidationCallback) -> Option<DirectorySubscription> = MetalClient::on_invalidated;
+        let _: fn(std::time::Duration) = pump_run_loop;
+        let _: fn(&ServerDirectory, Option<&str>, Option<&str>) -> Vec<ServerDescription> = ServerDirectory::servers_matching;
+        let _: fn(&ServerDirectory, Option<&str>, Option<&str>) -> Result<Vec<ServerDescription>, SyphonError> =
+            ServerDirectory::try_servers_matching;
+        let _: fn(&ServerDirectory) -> DirectorySnapshot = ServerDirectory::snapshot;
+        let _: fn(&ServerDirectory) -> String = ServerDirectory::snapshot_json;
+        let _: fn(&ServerDirectory, Option<&str>, Option<&str>, std::time::Duration) -> Option<ServerDescription> =
+            ServerDirectory::wait_for_server;
+        fn assert_send<T: Send>() {}
+        assert_send::<DirectoryEvent>();
+        fn assert_eq_hash<T: Eq + std::hash::Hash>() {}
+        assert_eq_hash::<ServerDescription>();
+        #[cfg(feature = "futures")]
+        {
+            fn assert_stream<S: futures_core::Stream<Item = DirectoryEvent> + Send + Unpin>() {}
+            assert_stream::<DirectoryEvents>();
+            let _: fn(&ServerDirectory) -> Option<DirectoryEvents> = ServerDirectory::events;
+            fn assert_frames<S: futures_core::Stream<Item = FrameInfo> + Send + Unpin>() {}
+            assert_frames::<FrameStream>();
+            let _: fn(&OpenGLClient) -> FrameStream = OpenGLClient::frames;
+            let _: fn(&MetalClient) -> FrameStream = MetalClient::frames;
+        }
+        let _: fn() -> Result<CGLContextObj, SyphonError> = try_cgl_create_headless_context;
+        let _: fn(Option<&str>, MTLDevicePtr) -> Option<MetalServer> = MetalServer::new_private;
+        let _: fn(&ServerDescription) -> Option<Vec<u8>> = ServerDescription::to_bytes;
+        let _: fn(&[u8]) -> Option<ServerDescription> = ServerDescription::from_bytes;
+        let _: fn(&ServerDescription) -> std::collections::HashMap<String, String> = ServerDescription::dictionary;
+        let _: fn(&ServerDescription) -> Option<u32> = ServerDescription::dictionary_version;
+        let _: fn(&ServerDescription) -> Vec<String> = ServerDescription::surface_types;
+        let _: fn(&ServerDescription) -> Option<(u32, u32)> = ServerDescription::size;
+        let _: fn(&ServerDescription) -> Option<*mut std::ffi::c_void> = ServerDescription::sender_handle;
+        let _: fn(&ServerDescription) -> Option<u32> = ServerDescription::sender_format;
+        let _: fn(&ServerDescription) -> ServerDescriptionInfo = ServerDescription::info;
+        let _: fn(&ServerDescriptionInfo, &ServerDirectory) -> Option<ServerDescription> = ServerDescriptionInfo::resolve;
+        let _: fn(&ServerDescription, CGLContextObj, Option<NewFrameCallback>) -> ReconnectingClient = ReconnectingClient::new;
+        let _: fn(&mut ReconnectingClient) -> bool = ReconnectingClient::sync;
+        let _: fn(&OpenGLClient, Option<NewFrameCallback>) = OpenGLClient::set_new_frame_handler;
+        let _: fn(&MetalClient, Option<NewFrameCallback>, CallbackDispatch) = MetalClient::set_new_frame_handler_with_dispatch;
+        let _: fn(&ServerDescription, CGLContextObj, Option<&SyphonOptions>) -> Option<(OpenGLClient, std::sync::mpsc::Receiver<FrameInfo>)> =
+            OpenGLClient::new_with_channel;
+        let _: fn(&ServerDescription, MTLDevicePtr, Option<&SyphonOptions>) -> Option<(MetalClient, std::sync::mpsc::Receiver<FrameInfo>)> =
+            MetalClient::new_with_channel;
+        let _: fn(FrameInfo) -> (u64, std::time::Instant, Option<(usize, usize)>) =
+            |info| (info.frame_index, info.timestamp, info.size);
+        let _: fn(&ServerDescription, MTLDevicePtr, Option<NewFrameCallback>) -> ReconnectingMetalClient =
+            ReconnectingMetalClient::new;
+        let _: fn(&mut ReconnectingMetalClient) -> Option<MetalTexture> = ReconnectingMetalClient::new_frame_image;
+        #[cfg(feature = "screen-capture")]
+        {
+            let _: fn() -> Result<Vec<CaptureDisplay>, SyphonError> = shareable_displays;
+            let _: fn() -> Result<Vec<CaptureWindow>, SyphonError> = shareable_windows;
+            let _: fn(Option<&str>, CaptureSource, &ScreenCaptureOptions) -> Result<ScreenCapture, SyphonError> =
+                ScreenCapture::try_new;
+            let _: fn(&ScreenCapture) -> &MetalServer = ScreenCapture::server;
+        }
+        #[cfg(feature = "camera")]
+        {
+            let _: fn() -> Vec<CameraDevice> = camera_devices;
+            let _: fn(Option<&str>, Option<&str>, &CameraCaptureOptions) -> Result<CameraCapture, SyphonError> =
+                CameraCapture::try_new;
+        }
+        #[cfg(feature = "recorder")]
+        {
+            let _: fn(std::path::PathBuf, &RecorderOptions) -> Result<Recorder, SyphonError> = Recorder::try_new;
+            let _: fn(&mut Recorder, &TimedFrame) -> Result<bool, SyphonError> = Recorder::record;
+            let _: fn(&mut Recorder, &MetalClient) -> Result<bool, SyphonError> =
+                Recorder::record_from::<MetalClient>;
+            let _: fn(Recorder) -> Result<(), SyphonError> = Recorder::finish;
+        }
+        #[cfg(feature = "encoder")]
+        {
+            let _: fn(&EncoderOptions, PacketCallback) -> Result<Encoder, SyphonError> = Encoder::try_new;
+            let _: fn(&EncoderOptions) -> Option<(Encoder, std::sync::mpsc::Receiver<EncodedPacket>)> =
+                Encoder::new_with_channel;
+            let _: fn(&mut Encoder, &TimedFrame) -> Result<bool, SyphonError> = Encoder::encode;
+            let _: fn(&mut Encoder, &OpenGLClient) -> Result<bool, SyphonError> =
+                Encoder::encode_from::<OpenGLClient>;
+            let _: fn(&mut Encoder) -> Result<(), SyphonError> = Encoder::flush;
+        }
+        #[cfg(feature = "egui")]
+        {
+            let _: fn(&egui::Context, &ServerDescription) -> Result<SyphonView, SyphonError> = SyphonView::try_new;
+            let _: for<'a> fn(&'a mut SyphonView, &egui::Context) -> Option<&'a egui::TextureHandle> =
+                SyphonView::texture;
+            fn assert_widget<W: egui::Widget>() {}
+            assert_widget::<&mut SyphonView>();
+        }
+        #[cfg(feature = "serde")]
+        {
+            fn assert_serde<T: serde::Serialize + for<'de> serde::Deserialize<'de>>() {}
+            assert_serde::<ServerDescriptionInfo>();
+            assert_serde::<DirectorySnapshot>();
+        }
+        let _: fn(&SyphonOptions, &str, u64) = SyphonOptions::set_unsigned;
+        let _: fn(&SyphonOptions, &str, &str) = SyphonOptions::set_string;
+        let _: fn(&MetalTexture, Option<MTLCommandQueuePtr>, MetalTextureOptions) -> Option<MetalTexture> =
+            MetalTexture::copy_to_owned_with;
+        let _: fn(&mut MetalFrameBlender, MetalTextureOptions) = MetalFrameBlender::set_texture_options;
+        let _: fn(&mut MetalFrameBlender, Option<MetalTexturePool>) = MetalFrameBlender::set_texture_pool;
+        let _: fn(MTLDevicePtr, usize, u64) -> Option<MetalTexturePool> = MetalTexturePool::with_heap_size;
+        let _: fn(&MetalTexturePool, u64, usize, usize, u64) -> Option<MetalTexture> = MetalTexturePool::new_texture;
+        let _: fn(&MetalTexturePool, &MetalTexture, Option<MTLCommandQueuePtr>, u64) -> Option<MetalTexture> =
+            MetalTexturePool::copy;
+        let _: fn(BlendMode) -> Option<GlFrameBlender> = GlFrameBlender::new;
+        let _: fn(&mut GlFrameBlender, &OpenGLImage) -> bool = GlFrameBlender::push_image;
+        let _: fn(&mut GlFrameBlender, std::time::Instant) -> Option<u32> = GlFrameBlender::render;
+        let _: fn(MTLDevicePtr, Option<MTLCommandQueuePtr>, BlendMode) -> Option<MetalFrameBlender> =
+            MetalFrameBlender::new;
+        let _: fn(&mut MetalFrameBlender, &MetalTexture) -> bool = MetalFrameBlender::push_texture;
+        let _: fn(&mut MetalFrameBlender, MTLCommandBufferPtr, std::time::Instant) -> MTLTexturePtr =
+            MetalFrameBlender::render;
+    }
+
+    #[test]
+    fn shared_frame_exporter_rejects_oversized_frames() {
+        let name = format!("/rss-test-{}", std::process::id());
+        if let Some(mut exporter) = SharedFrameExporter::new(&name, 4, 4) {
+            assert_eq!(exporter.capacity(), 64);
+            assert!(exporter.publish_rgba(4, 4, &[255u8; 64]));
+            assert!(!exporter.publish_rgba(8, 8, &[255u8; 256]));
+            assert_eq!(exporter.frames_written(), 1);
+        }
+        assert!(SharedFrameExporter::new("/a/b", 4, 4).is_none());
+    }
+
+    #[test]
+    fn context_check_reports_owning_thread() {
+        let Some(ctx) = cgl_create_headless_context() else {
+            return;
+        };
+        let addr = ctx as usize;
+        let owner = std::thread::spawn(move || {
+            cgl_make_current(addr as CGLContextObj);
+            assert_eq!(check_current_context(Some(addr as CGLContextObj)), Ok(()));
+            std::thread::current().id()
+        })
+        .join()
+        .unwrap();
+        match check_current_context(Some(ctx)) {
+            Err(SyphonError::WrongThread { expected, actual, .. }) => {
+                assert_eq!(expected, owner);
+                assert_eq!(actual, std::thread::current().id());
+            }
+            other => panic!("expected WrongThread, got {other:?}"),
+        }
+        cgl_destroy_context(ctx);
     }
 
     #[test]
@@ -39,6 +802,178 @@ mod macos {
         let _ = notification_name_server_update();
         let _ = notification_name_server_retire();
     }
+
+    #[test]
+    #[cfg(feature = "metal")]
+    fn metal_crate_api_surface_compiles() {
+        use metal::{CommandBufferRef, CommandQueueRef, DeviceRef, TextureRef};
+
+        let _: fn(Option<&str>, &DeviceRef, Option<&SyphonOptions>) -> Option<MetalServer> = MetalServer::with_device;
+        let _: fn(Option<&str>, &CommandQueueRef, Option<&SyphonOptions>) -> Option<MetalServer> =
+            MetalServer::with_command_queue;
+        let _: fn(&MetalServer) -> Option<metal::Device> = MetalServer::metal_device;
+        let _: fn(&MetalServer, &TextureRef, &CommandBufferRef, Rect, bool) = MetalServer::publish_texture;
+        let _: fn(&MetalServer, &TextureRef, Rect, bool) -> bool = MetalServer::publish_texture_simple;
+        let _: fn(&MetalServer) -> Option<metal::Texture> = MetalServer::new_frame_texture;
+        let _: fn(&ServerDescription, &DeviceRef, Option<&SyphonOptions>, Option<NewFrameCallback>) -> Option<MetalClient> =
+            MetalClient::with_device;
+        let _: fn(&MetalClient, &TextureRef, &CommandBufferRef) -> bool = MetalClient::copy_frame_to_texture;
+        let _: fn(&MetalClient) -> Option<metal::Texture> = MetalClient::new_frame_texture;
+        let _: fn(&MetalTexture) -> &TextureRef = MetalTexture::as_texture_ref;
+        let _: fn(MetalTexture) -> metal::Texture = metal::Texture::from;
+    }
+
+    #[test]
+    #[cfg(feature = "objc2-metal")]
+    fn objc2_metal_api_surface_compiles() {
+        use objc2::rc::Retained;
+        use objc2::runtime::ProtocolObject;
+        use objc2_metal::{MTLCommandBuffer, MTLCommandQueue, MTLDevice, MTLTexture};
+
+        type Device = ProtocolObject<dyn MTLDevice>;
+        type Texture = ProtocolObject<dyn MTLTexture>;
+        type CommandBuffer = ProtocolObject<dyn MTLCommandBuffer>;
+
+        let _: fn(Option<&str>, &Device, Option<&SyphonOptions>) -> Option<MetalServer> = MetalServer::with_objc2_device;
+        let _: fn(Option<&str>, &ProtocolObject<dyn MTLCommandQueue>, Option<&SyphonOptions>) -> Option<MetalServer> =
+            MetalServer::with_objc2_command_queue;
+        let _: fn(&MetalServer) -> Option<Retained<Device>> = MetalServer::objc2_device;
+        let _: fn(&MetalServer, &Texture, &CommandBuffer, Rect, bool) = MetalServer::publish_objc2;
+        let _: fn(&MetalServer, &Texture, Rect, bool) -> bool = MetalServer::publish_objc2_simple;
+        let _: fn(&MetalServer) -> Option<Retained<Texture>> = MetalServer::new_frame_objc2;
+        let _: fn(&ServerDescription, &Device, Option<&SyphonOptions>, Option<NewFrameCallback>) -> Option<MetalClient> =
+            MetalClient::with_objc2_device;
+        let _: fn(&MetalClient, &Texture, &CommandBuffer) -> bool = MetalClient::copy_frame_to_objc2;
+        let _: fn(&MetalClient) -> Option<Retained<Texture>> = MetalClient::new_frame_objc2;
+        let _: fn(&MetalTexture) -> &Texture = MetalTexture::as_protocol_object;
+        let _: fn(MetalTexture) -> Retained<Texture> = MetalTexture::into_retained;
+    }
+
+    #[test]
+    #[cfg(feature = "wgpu")]
+    fn wgpu_api_surface_compiles() {
+        let _: fn(&MetalTexture) -> Option<wgpu::TextureFormat> = MetalTexture::wgpu_format;
+        let _: fn(&MetalTexture, &wgpu::Device) -> Option<wgpu::Texture> = MetalTexture::to_wgpu_texture;
+        let _: fn(&ServerDescription, &wgpu::Device, Option<&SyphonOptions>, Option<NewFrameCallback>) -> Option<MetalClient> =
+            MetalClient::with_wgpu_device;
+        let _: fn(&MetalClient, &wgpu::Device) -> Option<wgpu::Texture> = MetalClient::new_frame_wgpu_texture;
+        let _: fn(&wgpu::Device, IOSurfacePtr, u64) -> Option<wgpu::Texture> = wgpu_texture_from_io_surface;
+        let _: fn(Option<&str>, &wgpu::Device, &wgpu::Queue, Option<&SyphonOptions>) -> Option<MetalServer> =
+            MetalServer::with_wgpu_device;
+        let _: fn(&MetalServer, &wgpu::Device, &wgpu::Texture, Rect, bool) -> bool = MetalServer::publish_wgpu_texture;
+    }
+
+    #[test]
+    #[cfg(feature = "glow")]
+    fn glow_api_surface_compiles() {
+        use glow::NativeTexture;
+
+        let _: fn(&OpenGLImage) -> Option<GlowTexture<'_>> = OpenGLImage::glow_texture;
+        let _: fn(&OpenGLServer, NativeTexture, u32, Rect, Size, bool) = OpenGLServer::publish_glow_texture;
+        let _: fn(&OpenGLServer, NativeTexture, u32, Rect, Size, bool) -> Result<(), SyphonError> =
+            OpenGLServer::try_publish_glow_texture;
+    }
+
+    #[test]
+    #[cfg(feature = "glutin")]
+    fn glutin_api_surface_compiles() {
+        use glutin::context::PossiblyCurrentContext;
+
+        let _: fn(&PossiblyCurrentContext) -> Option<CGLContextObj> = cgl_context_from_glutin;
+        let _: fn(Option<&str>, &PossiblyCurrentContext, Option<&SyphonOptions>) -> Option<OpenGLServer> =
+            OpenGLServer::with_glutin_context;
+        let _: fn(
+            &ServerDescription,
+            &PossiblyCurrentContext,
+            Option<&SyphonOptions>,
+            Option<NewFrameCallback>,
+        ) -> Result<OpenGLClient, SyphonError> = OpenGLClient::try_with_glutin_context;
+    }
+
+    #[test]
+    #[cfg(feature = "sdl2")]
+    fn sdl2_api_surface_compiles() {
+        use sdl2::video::{GLContext, Window};
+
+        let _: fn(&Window, &GLContext) -> Result<CGLContextObj, SyphonError> = cgl_context_from_sdl2;
+        let _: fn(Option<&str>, &Window, &GLContext, Option<&SyphonOptions>) -> Option<OpenGLServer> =
+            OpenGLServer::with_sdl2_window;
+        let _: fn(
+            Option<&str>,
+            &Window,
+            &GLContext,
+            Option<&SyphonOptions>,
+        ) -> Result<Sdl2WindowPublisher, SyphonError> = Sdl2WindowPublisher::new;
+        let _: fn(&mut Sdl2WindowPublisher, &Window) -> bool = Sdl2WindowPublisher::publish;
+    }
+
+    #[test]
+    #[cfg(feature = "viewer")]
+    fn viewer_api_surface_compiles() {
+        let _: fn(&ServerDescription) -> Result<(), SyphonError> = Viewer::open;
+    }
+
+    #[test]
+    #[cfg(feature = "ndi")]
+    fn ndi_api_surface_compiles() {
+        let _: fn(std::time::Duration) -> Result<Vec<NdiSource>, SyphonError> = ndi_sources;
+        let _: fn(&ServerDescription, &str) -> Result<SyphonToNdi, SyphonError> = SyphonToNdi::try_new;
+        let _: fn(&SyphonToNdi) -> bool = SyphonToNdi::is_running;
+        let _: fn(&NdiSource, Option<&str>) -> Result<NdiToSyphon, SyphonError> = NdiToSyphon::try_new;
+        let _: fn(&NdiToSyphon) -> Option<ServerDescription> = NdiToSyphon::server_description;
+    }
+
+    #[test]
+    #[cfg(feature = "relay")]
+    fn relay_api_surface_compiles() {
+        let _: fn(&ServerDescription, std::net::SocketAddr, RelayTransport) -> Result<RelaySender, SyphonError> = RelaySender::try_new;
+        let _: fn(&RelaySender) -> std::net::SocketAddr = RelaySender::local_addr;
+        let _: fn(&RelaySender) -> usize = RelaySender::receiver_count;
+        let _: fn(std::net::SocketAddr, Option<&str>, &RelayReceiverOptions) -> Result<RelayReceiver, SyphonError> =
+            RelayReceiver::try_new;
+        let _: fn(&RelayReceiver) -> Option<ServerDescription> = RelayReceiver::server_description;
+        let _: fn(&RelayReceiver) -> bool = RelayReceiver::is_connected;
+        assert!(RelayCompression::None.is_available());
+        assert_eq!(RelayCompression::Lz4.is_available(), cfg!(feature = "relay-lz4"));
+    }
+
+    #[test]
+    #[cfg(feature = "vulkan")]
+    fn vulkan_api_surface_compiles() {
+        use ash::vk;
+
+        let _: fn(&ash::Instance, vk::PhysicalDevice, &ash::Device) -> VulkanInterop = VulkanInterop::new;
+        let _: fn(&VulkanInterop, IOSurfacePtr, u32, u32, vk::Format) -> Result<VulkanImage, SyphonError> =
+            VulkanInterop::import_io_surface;
+        let _: fn(&VulkanInterop, &Frame) -> Result<VulkanImage, SyphonError> = VulkanInterop::import_frame;
+        let _: fn(&VulkanInterop, vk::Image) -> Option<MTLTexturePtr> = VulkanInterop::metal_texture;
+        let _: fn(&VulkanInterop, Option<&str>, vk::Queue, Option<&SyphonOptions>) -> Result<MetalServer, SyphonError> =
+            VulkanInterop::new_server;
+        let _: fn(&MetalServer, &VulkanInterop, vk::Image, Rect, bool) -> bool = MetalServer::publish_vulkan_image;
+        let _: fn(&VulkanImage) -> vk::Image = VulkanImage::image;
+        let _: fn(&MetalTexture) -> Option<vk::Format> = MetalTexture::vk_format;
+    }
+
+    #[test]
+    #[cfg(feature = "core-image")]
+    fn core_image_api_surface_compiles() {
+        use objc2::rc::Retained;
+        use objc2_core_image::CIImage;
+
+        let _: fn(&Frame) -> Option<Retained<CIImage>> = Frame::as_ci_image;
+        let _: fn(&MetalServer, &CIImage) -> bool = MetalServer::publish_ci_image;
+        let _: fn(&Frame) -> Option<CIImagePtr> = Frame::ci_image_ptr;
+        let _: fn(&MetalServer, CIImagePtr) -> bool = MetalServer::publish_ci_image_ptr;
+    }
+
+    #[test]
+    #[cfg(feature = "io-surface")]
+    fn io_surface_api_surface_compiles() {
+        use objc2_io_surface::IOSurfaceRef;
+
+        let _: fn(&OpenGLImage) -> Option<&IOSurfaceRef> = OpenGLImage::io_surface;
+        let _: fn(&MetalTexture) -> Option<&IOSurfaceRef> = MetalTexture::io_surface;
+    }
 }
 
 #[cfg(target_os = "windows")]
@@ -99,6 +1034,91 @@ mod windows {
         let _: fn(&Spout, bool) -> bool = Spout::set_cpu_mode;
     }
 
+    #[test]
+    fn spout_directory_api_surface_compiles() {
+        let _: fn() -> Result<ServerDirectory, SyphonError> = ServerDirectory::try_shared;
+        let _: fn(&ServerDirectory) -> usize = ServerDirectory::servers_count;
+        let _: fn(&ServerDirectory, usize) -> Option<ServerDescription> = ServerDirectory::server_at_index;
+        let _: fn(&ServerDirectory, Option<&str>, Option<&str>) -> Vec<ServerDescription> = ServerDirectory::servers_matching;
+        let _: fn(&ServerDirectory, Option<&str>, Option<&str>) -> Result<Vec<ServerDescription>, SyphonError> =
+            ServerDirectory::try_servers_matching;
+        let _: fn(&ServerDirectory) -> DirectorySnapshot = ServerDirectory::snapshot;
+        let _: fn(&ServerDirectory) -> String = ServerDirectory::snapshot_json;
+        let _: fn(&ServerDescription) -> Option<String> = ServerDescription::name;
+        let _: fn(&ServerDescription) -> Option<(u32, u32)> = ServerDescription::size;
+        let _: fn(&ServerDescription) -> Option<*mut std::ffi::c_void> = ServerDescription::sender_handle;
+        let _: fn(&ServerDescription) -> Option<u32> = ServerDescription::sender_format;
+        let _: fn(&ServerDescription) -> ServerDescriptionInfo = ServerDescription::info;
+    }
+
+    #[test]
+    fn spout_server_client_api_surface_compiles() {
+        let _: fn(Option<&str>) -> Result<SpoutServer, SyphonError> = SpoutServer::try_new;
+        let _: fn(&SpoutServer) -> Option<String> = SpoutServer::name;
+        let _: fn(&SpoutServer, Option<&str>) -> Result<(), SyphonError> = SpoutServer::try_set_name;
+        let _: fn(&SpoutServer, u32, u32, f64, f64, bool) = SpoutServer::publish_whole_texture;
+        let _: fn(&SpoutServer, u32, u32, u32, bool) -> bool = SpoutServer::publish_fbo;
+        let _: fn(&SpoutServer, &[u8], usize, usize, bool) -> bool = SpoutServer::publish_pixels;
+        let _: fn(&SpoutServer) -> &Spout = SpoutServer::spout;
+        let _: fn(Option<&str>) -> Result<SpoutClient, SyphonError> = SpoutClient::try_new;
+        let _: fn(&SpoutClient) -> Option<String> = SpoutClient::server_name;
+        let _: fn(&SpoutClient, u32, u32) -> bool = SpoutClient::copy_frame_to;
+        let _: for<'a> fn(&'a SpoutClient) -> Option<SpoutImage<'a>> = SpoutClient::new_frame_image;
+        let _: fn(&SpoutClient) -> Option<CpuFrame> = SpoutClient::latest_frame;
+        let _: fn(Option<&str>, ID3D11DevicePtr) -> Result<SpoutDxServer, SyphonError> = SpoutDxServer::try_new;
+        let _: fn(&SpoutDxServer, ID3D11Texture2DPtr) -> bool = SpoutDxServer::publish_texture;
+        let _: fn(Option<&str>, ID3D11DevicePtr) -> Result<SpoutDxClient, SyphonError> = SpoutDxClient::try_new;
+        let _: fn(&SpoutDxClient) -> Option<ID3D11Texture2DPtr> = SpoutDxClient::new_frame_texture;
+        let _: fn(&SpoutDxClient) -> u32 = SpoutDxClient::pixel_format;
+        let _: fn(&SpoutDxClient, ID3D11Texture2DPtr) -> bool = SpoutDxClient::copy_frame_to;
+        let _: fn(Option<&str>, ID3D12DevicePtr, ID3D12CommandQueuePtr) -> Result<SpoutDx12Server, SyphonError> =
+            SpoutDx12Server::try_new;
+        let _: fn(&SpoutDx12Server, ID3D12ResourcePtr, u32) -> bool = SpoutDx12Server::publish_resource;
+        let _: fn(Option<&str>, ID3D12DevicePtr, ID3D12CommandQueuePtr) -> Result<SpoutDx12Client, SyphonError> =
+            SpoutDx12Client::try_new;
+        let _: fn(&SpoutDx12Client) -> Option<ID3D12ResourcePtr> = SpoutDx12Client::new_frame_resource;
+        let _: fn(&SpoutDx12Client) -> (usize, usize) = SpoutDx12Client::frame_size;
+        let _: fn() -> bool = gl_dx_interop_available;
+        let _: fn(Option<&str>) -> Result<SpoutGlDxServer, SyphonError> = SpoutGlDxServer::try_new;
+        let _: fn(&SpoutGlDxServer, u32, u32, f64, f64, bool) = SpoutGlDxServer::publish_whole_texture;
+        let _: fn(Option<&str>) -> Result<SpoutGlDxClient, SyphonError> = SpoutGlDxClient::try_new;
+        let _: fn(&SpoutGlDxClient, u32, u32) -> bool = SpoutGlDxClient::copy_frame_to;
+        let _: fn(&SpoutGlDxClient) -> Option<u32> = SpoutGlDxClient::new_frame_texture;
+        #[cfg(feature = "windows")]
+        {
+            use ::windows::Win32::Graphics::Direct3D11::{ID3D11Device, ID3D11Texture2D};
+            use ::windows::Win32::Graphics::Direct3D12::{ID3D12CommandQueue, ID3D12Resource, D3D12_RESOURCE_STATES};
+            let _: fn(Option<&str>, &ID3D11Device) -> Option<SpoutDxServer> = SpoutDxServer::with_d3d11_device;
+            let _: fn(&SpoutDxServer, &ID3D11Texture2D) -> bool = SpoutDxServer::publish_d3d11_texture;
+            let _: fn(&SpoutDxClient) -> Option<ID3D11Texture2D> = SpoutDxClient::new_frame_d3d11_texture;
+            let _: fn(Option<&str>, &ID3D12CommandQueue) -> Option<SpoutDx12Server> = SpoutDx12Server::with_d3d12_queue;
+            let _: fn(&SpoutDx12Server, &ID3D12Resource, D3D12_RESOURCE_STATES) -> bool =
+                SpoutDx12Server::publish_d3d12_resource;
+            let _: fn(&SpoutDx12Client) -> Option<ID3D12Resource> = SpoutDx12Client::new_frame_d3d12_resource;
+        }
+    }
+
+    #[test]
+    fn spout_dx_rejects_null_device() {
+        let err = SpoutDxServer::try_new(Some("x"), std::ptr::null_mut()).err();
+        assert!(matches!(err, Some(SyphonError::InvalidArgument(_))));
+        let err = SpoutDxClient::try_new(None, std::ptr::null_mut()).err();
+        assert!(matches!(err, Some(SyphonError::InvalidArgument(_))));
+        let err = SpoutDx12Server::try_new(Some("x"), std::ptr::null_mut(), std::ptr::null_mut()).err();
+        assert!(matches!(err, Some(SyphonError::InvalidArgument(_))));
+        let err = SpoutDx12Client::try_new(None, std::ptr::null_mut(), std::ptr::null_mut()).err();
+        assert!(matches!(err, Some(SyphonError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn spout_gl_dx_requires_current_context() {
+        assert!(!gl_dx_interop_available());
+        let err = SpoutGlDxServer::try_new(Some("x")).err();
+        assert!(matches!(err, Some(SyphonError::CreationFailed { .. })));
+        let err = SpoutGlDxClient::try_new(None).err();
+        assert!(matches!(err, Some(SyphonError::CreationFailed { .. })));
+    }
+
     #[test]
     fn spout_sender_info_is_cloneable() {
         let info = SpoutSenderInfo {
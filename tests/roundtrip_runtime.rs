@@ -3,6 +3,8 @@
 //! These are intentionally `#[ignore]` because they require a working local
 //! graphics/runtime environment and are not stable on generic CI runners.
 
+#![cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+
 const W: usize = 64;
 const H: usize = 64;
 const BPP: usize = 4;
@@ -26,17 +28,24 @@ fn make_test_pattern() -> Vec<u8> {
 mod macos {
     use super::*;
     use rusty_syphon_spout::{
-        cgl_create_headless_context, cgl_destroy_context, cgl_make_current,
-        gl_create_texture_rectangle_rgba8, gl_delete_texture, gl_read_texture_rectangle_rgba8,
-        OpenGLClient, OpenGLServer, GL_TEXTURE_RECTANGLE,
+        cgl_context_is_core_profile, cgl_create_headless_context, cgl_destroy_context, cgl_make_current,
+        gl_copy_image_to_texture, gl_create_texture_2d_rgba16f, gl_create_texture_2d_rgba8,
+        gl_create_texture_rectangle_bgra8, gl_create_texture_rectangle_rgba8, gl_delete_texture,
+        gl_read_texture_2d_rgba16f, gl_read_texture_2d_rgba8, gl_read_texture_rectangle_bgra8,
+        gl_read_texture_rectangle_rgba8, gl_write_texture_region_rgba8, AsyncReadback, CallbackDispatch, GlSrgbConverter,
+        HeadlessContext, OpenGLClient, OpenGLServer, Rect, ServerDescription, ServerDirectory, Size, SrgbConversion,
+        GL_TEXTURE_2D, GL_TEXTURE_RECTANGLE,
     };
     use std::time::Duration;
 
     #[test]
     #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    #[allow(deprecated)]
     fn syphon_opengl_roundtrip_runtime() {
         let ctx = cgl_create_headless_context().expect("create headless CGL context");
         cgl_make_current(ctx);
+        // The headless context is 3.2 core: this roundtrip needs a framework that draws with core profiles.
+        assert!(cgl_context_is_core_profile(ctx));
 
         let pattern = make_test_pattern();
         let tex_id = gl_create_texture_rectangle_rgba8(W, H, &pattern);
@@ -78,6 +87,595 @@ mod macos {
 
         assert_eq!(pattern, readback, "Syphon OpenGL roundtrip mismatch");
     }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_opengl_publish_rect_and_size_roundtrip() {
+        let ctx = cgl_create_headless_context().expect("create headless CGL context");
+        cgl_make_current(ctx);
+
+        let pattern = make_test_pattern();
+        let tex_id = gl_create_texture_rectangle_rgba8(W, H, &pattern);
+        let server = OpenGLServer::new(Some("rusty-syphon-runtime-rect-size"), ctx, None)
+            .expect("failed to create OpenGL server");
+        let size = Size::new(W as f64, H as f64);
+        server.publish(tex_id, GL_TEXTURE_RECTANGLE, Rect::from_size(size), size, false);
+
+        let desc = server.server_description().expect("missing server description");
+        let client = OpenGLClient::new(&desc, ctx, None, None).expect("failed to create client");
+        for _ in 0..100 {
+            if client.has_new_frame() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let image = client.new_frame_image().expect("missing frame image");
+        let received_size = image.texture_size();
+        let mut readback = vec![0u8; BYTES];
+        gl_read_texture_rectangle_rgba8(image.texture_name(), W, H, &mut readback);
+        drop(image);
+
+        drop(client);
+        drop(server);
+        gl_delete_texture(tex_id);
+        cgl_destroy_context(ctx);
+        assert_eq!(received_size, (W as f64, H as f64));
+        assert_eq!(pattern, readback, "Rect/Size publish roundtrip mismatch");
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_headless_context_builder_profiles() {
+        let legacy = HeadlessContext::builder().core_profile(false).build().expect("legacy context");
+        assert!(!cgl_context_is_core_profile(legacy.context()));
+        let hdr = HeadlessContext::builder().color_float(true).samples(4).build().expect("float MSAA context");
+        assert!(cgl_context_is_core_profile(hdr.context()));
+
+        // Servers work from either profile (Syphon 5+).
+        for ctx in [&legacy, &hdr] {
+            ctx.make_current();
+            let server = OpenGLServer::new(Some("rusty-syphon-runtime-profiles"), ctx.context(), None);
+            assert!(server.is_some(), "server creation failed");
+        }
+        cgl_make_current(std::ptr::null_mut());
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_gl_texture_2d_npot_roundtrip() {
+        let ctx = HeadlessContext::builder().build().expect("create headless CGL context");
+        ctx.make_current();
+
+        let (w, h) = (13, 7);
+        let pattern: Vec<u8> = (0..w * h * BPP).map(|i| (i * 7 % 251) as u8).collect();
+        let tex_id = gl_create_texture_2d_rgba8(w, h, &pattern);
+        assert!(tex_id != 0, "failed to create NPOT 2D texture");
+        let mut readback = vec![0u8; pattern.len()];
+        gl_read_texture_2d_rgba8(tex_id, w, h, &mut readback);
+        assert_eq!(pattern, readback, "GL_TEXTURE_2D upload/readback mismatch");
+
+        // Syphon accepts GL_TEXTURE_2D sources too.
+        let server = OpenGLServer::new(Some("rusty-syphon-runtime-texture-2d"), ctx.context(), None)
+            .expect("failed to create OpenGL server");
+        server.publish_whole_texture(tex_id, GL_TEXTURE_2D, w as f64, h as f64, false);
+        let image = server.new_frame_image().expect("missing frame image");
+        assert_eq!(image.texture_size(), (w as f64, h as f64));
+        drop(image);
+        drop(server);
+        gl_delete_texture(tex_id);
+        cgl_make_current(std::ptr::null_mut());
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_gl_bgra8_and_rgba16f_roundtrip() {
+        let ctx = HeadlessContext::builder().build().expect("create headless CGL context");
+        ctx.make_current();
+
+        let bgra = make_test_pattern();
+        let tex_id = gl_create_texture_rectangle_bgra8(W, H, &bgra);
+        assert!(tex_id != 0, "failed to create BGRA8 texture");
+        let mut readback = vec![0u8; BYTES];
+        gl_read_texture_rectangle_bgra8(tex_id, W, H, &mut readback);
+        assert_eq!(bgra, readback, "BGRA8 upload/readback mismatch");
+        // Read as RGBA8, red and blue swap.
+        gl_read_texture_rectangle_rgba8(tex_id, W, H, &mut readback);
+        assert_eq!((readback[0], readback[2]), (bgra[2], bgra[0]));
+        gl_delete_texture(tex_id);
+
+        // Half floats 0.0, 0.5, 1.0 and 2.0 (HDR values above 1 survive).
+        let half: Vec<u16> = [0x0000, 0x3800, 0x3C00, 0x4000].repeat(5 * 3);
+        let tex_id = gl_create_texture_2d_rgba16f(5, 3, &half);
+        assert!(tex_id != 0, "failed to create RGBA16F texture");
+        let mut readback = vec![0u16; half.len()];
+        gl_read_texture_2d_rgba16f(tex_id, 5, 3, &mut readback);
+        assert_eq!(half, readback, "RGBA16F upload/readback mismatch");
+        gl_delete_texture(tex_id);
+        cgl_make_current(std::ptr::null_mut());
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_async_readback_matches_sync_readback() {
+        let ctx = HeadlessContext::builder().build().expect("create headless CGL context");
+        ctx.make_current();
+
+        let pattern = make_test_pattern();
+        let tex_id = gl_create_texture_rectangle_rgba8(W, H, &pattern);
+        let mut expected = vec![0u8; BYTES];
+        gl_read_texture_rectangle_rgba8(tex_id, W, H, &mut expected);
+
+        let readback = AsyncReadback::new().expect("readback pool");
+        // Two in flight, as in a begin-N / wait-N-1 pipeline.
+        let first = readback.begin(tex_id, GL_TEXTURE_RECTANGLE, W, H).expect("begin first");
+        let second = readback.begin(tex_id, GL_TEXTURE_RECTANGLE, W, H).expect("begin second");
+        assert_eq!(first.wait(), expected);
+        assert_eq!(second.wait(), expected);
+        // Buffers are recycled.
+        let third = readback.begin(tex_id, GL_TEXTURE_RECTANGLE, W, H).expect("begin third");
+        assert_eq!((third.width(), third.height()), (W, H));
+        assert_eq!(third.wait(), expected);
+
+        drop(readback);
+        gl_delete_texture(tex_id);
+        cgl_make_current(std::ptr::null_mut());
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_gl_copy_image_to_texture_owns_frame() {
+        let ctx = HeadlessContext::builder().build().expect("create headless CGL context");
+        ctx.make_current();
+
+        let pattern = make_test_pattern();
+        let tex_id = gl_create_texture_rectangle_rgba8(W, H, &pattern);
+        let server = OpenGLServer::new(Some("rusty-syphon-runtime-copy-image"), ctx.context(), None)
+            .expect("failed to create OpenGL server");
+        server.publish_whole_texture(tex_id, GL_TEXTURE_RECTANGLE, W as f64, H as f64, false);
+        let image = server.new_frame_image().expect("missing frame image");
+        let expected = image.read_pixels();
+
+        let dest = gl_create_texture_2d_rgba8(W, H, &vec![0u8; BYTES]);
+        assert!(gl_copy_image_to_texture(&image, dest, GL_TEXTURE_2D, W, H));
+        drop(image);
+        drop(server);
+        let mut copied = vec![0u8; BYTES];
+        gl_read_texture_2d_rgba8(dest, W, H, &mut copied);
+        assert_eq!(copied, expected, "copied frame differs from the image");
+
+        gl_delete_texture(dest);
+        gl_delete_texture(tex_id);
+        cgl_make_current(std::ptr::null_mut());
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_gl_srgb_converter_matches_cpu_tables() {
+        let ctx = HeadlessContext::builder().build().expect("create headless CGL context");
+        ctx.make_current();
+
+        let pattern = make_test_pattern();
+        let tex_id = gl_create_texture_rectangle_rgba8(W, H, &pattern);
+        let mut converter = GlSrgbConverter::new().expect("sRGB converter");
+        let converted = converter
+            .convert(tex_id, GL_TEXTURE_RECTANGLE, W, H, SrgbConversion::Encode)
+            .expect("GPU encode");
+        let mut gpu = vec![0u8; BYTES];
+        gl_read_texture_2d_rgba8(converted, W, H, &mut gpu);
+        let mut cpu = pattern.clone();
+        SrgbConversion::Encode.apply_rgba8(&mut cpu);
+        // Shader and table round differently by at most one step.
+        assert!(gpu.iter().zip(&cpu).all(|(g, c)| g.abs_diff(*c) <= 1), "GPU and CPU sRGB encode differ");
+
+        drop(converter);
+        gl_delete_texture(tex_id);
+        cgl_make_current(std::ptr::null_mut());
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_opengl_server_rename_at_runtime() {
+        let ctx = HeadlessContext::builder().build().expect("create headless CGL context");
+        ctx.make_current();
+
+        let server = OpenGLServer::new(Some("rusty-syphon-runtime-deck-a"), ctx.context(), None)
+            .expect("failed to create OpenGL server");
+        assert_eq!(server.name().as_deref(), Some("rusty-syphon-runtime-deck-a"));
+        server.set_name(Some("rusty-syphon-runtime-preview"));
+        assert_eq!(server.name().as_deref(), Some("rusty-syphon-runtime-preview"));
+        let description = server.server_description().expect("server description");
+        assert_eq!(description.name().as_deref(), Some("rusty-syphon-runtime-preview"));
+        assert!(server.try_set_name(Some("bad\0name")).is_err());
+        assert_eq!(server.name().as_deref(), Some("rusty-syphon-runtime-preview"));
+
+        drop(server);
+        cgl_make_current(std::ptr::null_mut());
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_opengl_image_read_pixels_is_top_down() {
+        let ctx = cgl_create_headless_context().expect("create headless CGL context");
+        cgl_make_current(ctx);
+
+        let pattern = make_test_pattern();
+        let tex_id = gl_create_texture_rectangle_rgba8(W, H, &pattern);
+        let server = OpenGLServer::new(Some("rusty-syphon-runtime-read-pixels"), ctx, None)
+            .expect("failed to create OpenGL server");
+        let (fw, fh) = (W as f64, H as f64);
+        server.publish_whole_texture(tex_id, GL_TEXTURE_RECTANGLE, fw, fh, false);
+        let pixels = server.new_frame_image().expect("missing frame image").read_pixels();
+
+        drop(server);
+        gl_delete_texture(tex_id);
+        cgl_destroy_context(ctx);
+        // The texture was uploaded bottom row first, so the first row read back is its last.
+        let row = W * BPP;
+        assert_eq!(pixels.len(), BYTES);
+        assert_eq!(&pixels[..row], &pattern[BYTES - row..]);
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_opengl_copy_frame_to_user_texture() {
+        let ctx = cgl_create_headless_context().expect("create headless CGL context");
+        cgl_make_current(ctx);
+
+        let pattern = make_test_pattern();
+        let tex_id = gl_create_texture_rectangle_rgba8(W, H, &pattern);
+        let server = OpenGLServer::new(Some("rusty-syphon-runtime-copy-frame"), ctx, None)
+            .expect("failed to create OpenGL server");
+        server.publish_whole_texture(tex_id, GL_TEXTURE_RECTANGLE, W as f64, H as f64, false);
+        let desc = server.server_description().expect("missing server description");
+        let client = OpenGLClient::new(&desc, ctx, None, None).expect("failed to create client");
+        for _ in 0..100 {
+            if client.has_new_frame() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let too_small = gl_create_texture_rectangle_rgba8(W / 2, H / 2, &vec![0u8; BYTES / 4]);
+        assert!(!client.copy_frame_to(too_small, GL_TEXTURE_RECTANGLE), "undersized target accepted");
+
+        let dst = gl_create_texture_rectangle_rgba8(W, H, &vec![0u8; BYTES]);
+        assert!(client.copy_frame_to(dst, GL_TEXTURE_RECTANGLE), "copy into user texture failed");
+        let mut readback = vec![0u8; BYTES];
+        gl_read_texture_rectangle_rgba8(dst, W, H, &mut readback);
+
+        drop(client);
+        drop(server);
+        for tex in [tex_id, too_small, dst] {
+            gl_delete_texture(tex);
+        }
+        cgl_destroy_context(ctx);
+
+        assert_eq!(pattern, readback, "copied frame mismatch");
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_client_drop_under_frame_traffic() {
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let ctx = cgl_create_headless_context().expect("create headless CGL context");
+        cgl_make_current(ctx);
+
+        let pattern = make_test_pattern();
+        let tex_id = gl_create_texture_rectangle_rgba8(W, H, &pattern);
+        assert!(tex_id != 0, "failed to create sender texture");
+        let server = OpenGLServer::new(Some("rusty-syphon-runtime-drop-stress"), ctx, None)
+            .expect("failed to create OpenGL server");
+        let publish = || {
+            server.publish_whole_texture(tex_id, GL_TEXTURE_RECTANGLE, W as f64, H as f64, false)
+        };
+        publish();
+        let desc = server.server_description().expect("missing server description");
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let late_calls = Arc::new(AtomicUsize::new(0));
+        for _ in 0..200 {
+            let alive = Arc::new(AtomicBool::new(true));
+            let client = {
+                let (alive, calls, late_calls) = (alive.clone(), calls.clone(), late_calls.clone());
+                OpenGLClient::new(
+                    &desc,
+                    ctx,
+                    None,
+                    Some(Box::new(move |_| {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        // Widen the window in which a drop can race an in-flight callback.
+                        std::thread::sleep(Duration::from_micros(200));
+                        if !alive.load(Ordering::SeqCst) {
+                            late_calls.fetch_add(1, Ordering::SeqCst);
+                        }
+                    })),
+                )
+                .expect("failed to create client")
+            };
+            for _ in 0..5 {
+                publish();
+            }
+            drop(client);
+            alive.store(false, Ordering::SeqCst);
+        }
+        // Give any straggling deliveries a chance to (incorrectly) arrive.
+        for _ in 0..20 {
+            publish();
+        }
+        std::thread::sleep(Duration::from_millis(100));
+
+        drop(server);
+        gl_delete_texture(tex_id);
+        cgl_destroy_context(ctx);
+
+        assert!(calls.load(Ordering::SeqCst) > 0, "no new-frame callbacks observed");
+        assert_eq!(late_calls.load(Ordering::SeqCst), 0, "callback ran after its client was dropped");
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_direct_callback_can_drop_its_own_client() {
+        use std::sync::{Arc, Mutex};
+
+        // The callback runs on Syphon's delivery thread; the client only moves there to be dropped.
+        struct SendClient(#[allow(dead_code)] OpenGLClient);
+        unsafe impl Send for SendClient {}
+
+        let ctx = cgl_create_headless_context().expect("create headless CGL context");
+        cgl_make_current(ctx);
+
+        let tex_id = gl_create_texture_rectangle_rgba8(W, H, &make_test_pattern());
+        let server = OpenGLServer::new(Some("rusty-syphon-runtime-direct-self-drop"), ctx, None)
+            .expect("failed to create OpenGL server");
+        let desc = server.server_description().expect("missing server description");
+
+        let slot: Arc<Mutex<Option<SendClient>>> = Arc::new(Mutex::new(None));
+        let (tx, rx) = std::sync::mpsc::channel();
+        let client = {
+            let slot = slot.clone();
+            OpenGLClient::new_with_dispatch(
+                &desc,
+                ctx,
+                None,
+                Some(Box::new(move |_| {
+                    let client = slot.lock().unwrap().take();
+                    drop(client);
+                    let _ = tx.send(());
+                })),
+                CallbackDispatch::Direct,
+            )
+            .expect("failed to create client")
+        };
+        *slot.lock().unwrap() = Some(SendClient(client));
+
+        server.publish_whole_texture(tex_id, GL_TEXTURE_RECTANGLE, W as f64, H as f64, false);
+        let called = rx.recv_timeout(Duration::from_secs(2)).is_ok();
+        // The closure (and its sender) is released once the callback that dropped the client returns.
+        let released = rx.recv_timeout(Duration::from_secs(2)) == Err(std::sync::mpsc::RecvTimeoutError::Disconnected);
+        let dropped = slot.lock().unwrap().is_none();
+
+        drop(server);
+        gl_delete_texture(tex_id);
+        cgl_destroy_context(ctx);
+        assert!(called, "no direct callback observed");
+        assert!(dropped, "client was not dropped by its callback");
+        assert!(released, "callback not released after its client was dropped");
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_teardown_on_worker_thread_is_idempotent() {
+        let workers: Vec<_> = (0..4)
+            .map(|i| {
+                std::thread::spawn(move || {
+                    let ctx = cgl_create_headless_context().expect("create headless CGL context");
+                    cgl_make_current(ctx);
+                    let server = OpenGLServer::new(Some(&format!("rusty-syphon-runtime-teardown-{i}")), ctx, None)
+                        .expect("failed to create OpenGL server");
+                    let desc = server.server_description().expect("missing server description");
+                    let client = OpenGLClient::new(&desc, ctx, None, Some(Box::new(|_| {})))
+                        .expect("failed to create client");
+                    client.stop();
+                    client.stop();
+                    drop(client);
+                    server.stop();
+                    server.stop();
+                    drop(server);
+                    cgl_destroy_context(ctx);
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().expect("teardown on worker thread panicked");
+        }
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_private_server_reachable_from_serialized_description() {
+        let ctx = cgl_create_headless_context().expect("create headless CGL context");
+        cgl_make_current(ctx);
+
+        let pattern = make_test_pattern();
+        let tex_id = gl_create_texture_rectangle_rgba8(W, H, &pattern);
+        let server = OpenGLServer::new_private(Some("rusty-syphon-runtime-private"), ctx)
+            .expect("failed to create private OpenGL server");
+        let uuid = server.server_description().and_then(|d| d.uuid()).expect("missing server uuid");
+        let bytes = server
+            .server_description()
+            .and_then(|d| d.to_bytes())
+            .expect("failed to serialize description");
+
+        std::thread::sleep(Duration::from_millis(200));
+        let directory = ServerDirectory::shared().expect("missing server directory");
+        assert!(
+            directory.servers().iter().all(|d| d.uuid().as_deref() != Some(uuid.as_str())),
+            "private server was listed in the directory"
+        );
+
+        let desc = ServerDescription::from_bytes(&bytes).expect("failed to rebuild description");
+        assert_eq!(desc.uuid().as_deref(), Some(uuid.as_str()));
+        let client = OpenGLClient::new(&desc, ctx, None, None).expect("failed to create client");
+        server.publish_whole_texture(tex_id, GL_TEXTURE_RECTANGLE, W as f64, H as f64, false);
+        for _ in 0..100 {
+            if client.has_new_frame() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let received = client.has_new_frame();
+
+        drop(client);
+        drop(server);
+        gl_delete_texture(tex_id);
+        cgl_destroy_context(ctx);
+        assert!(received, "no frame from private server");
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_opengl_dirty_region_keeps_unchanged_pixels() {
+        let ctx = cgl_create_headless_context().expect("create headless CGL context");
+        cgl_make_current(ctx);
+
+        let mut pattern = make_test_pattern();
+        let tex_id = gl_create_texture_rectangle_rgba8(W, H, &pattern);
+        let server = OpenGLServer::new(Some("rusty-syphon-runtime-dirty"), ctx, None)
+            .expect("failed to create OpenGL server");
+        let size = Size::new(W as f64, H as f64);
+        assert!(server.publish_dirty_region(tex_id, GL_TEXTURE_RECTANGLE, Rect::from_size(size), size, false));
+
+        // Change an 8x8 block and publish only that region.
+        let block = vec![255u8; 8 * 8 * BPP];
+        assert!(gl_write_texture_region_rgba8(tex_id, GL_TEXTURE_RECTANGLE, 16, 16, 8, 8, &block));
+        let region = Rect::new(16.0, 16.0, 8.0, 8.0);
+        assert!(server.publish_dirty_region(tex_id, GL_TEXTURE_RECTANGLE, region, size, false));
+        for y in 16..24 {
+            let row = (y * W + 16) * BPP;
+            pattern[row..row + 8 * BPP].fill(255);
+        }
+
+        let image = server.new_frame_image().expect("missing frame image");
+        let mut readback = vec![0u8; BYTES];
+        gl_read_texture_rectangle_rgba8(image.texture_name(), W, H, &mut readback);
+        drop(image);
+
+        drop(server);
+        gl_delete_texture(tex_id);
+        cgl_destroy_context(ctx);
+        assert_eq!(readback, pattern, "dirty-region publish did not match the full frame");
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_frame_callback_reports_frame_info() {
+        let ctx = cgl_create_headless_context().expect("create headless CGL context");
+        cgl_make_current(ctx);
+
+        let tex_id = gl_create_texture_rectangle_rgba8(W, H, &make_test_pattern());
+        let server = OpenGLServer::new(Some("rusty-syphon-runtime-frame-info"), ctx, None)
+            .expect("failed to create OpenGL server");
+        let desc = server.server_description().expect("missing server description");
+        let (tx, rx) = std::sync::mpsc::channel();
+        let client = OpenGLClient::new_with_dispatch(
+            &desc,
+            ctx,
+            None,
+            Some(Box::new(move |info| {
+                let _ = tx.send(info);
+            })),
+            CallbackDispatch::SerializedWithSize,
+        )
+        .expect("failed to create client");
+
+        let (fw, fh) = (W as f64, H as f64);
+        let mut infos = Vec::new();
+        for _ in 0..2 {
+            server.publish_whole_texture(tex_id, GL_TEXTURE_RECTANGLE, fw, fh, false);
+            infos.extend(rx.recv_timeout(Duration::from_secs(2)));
+        }
+
+        drop(client);
+        drop(server);
+        gl_delete_texture(tex_id);
+        cgl_destroy_context(ctx);
+        assert_eq!(infos.len(), 2, "missing frame callbacks");
+        assert_eq!(infos[0].size, Some((W, H)));
+        assert!(infos[1].frame_index > infos[0].frame_index);
+        assert!(infos[1].timestamp >= infos[0].timestamp);
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_frame_callback_leaves_new_frame_to_the_consumer() {
+        let ctx = cgl_create_headless_context().expect("create headless CGL context");
+        cgl_make_current(ctx);
+
+        let tex_id = gl_create_texture_rectangle_rgba8(W, H, &make_test_pattern());
+        let server = OpenGLServer::new(Some("rusty-syphon-runtime-callback-no-fetch"), ctx, None)
+            .expect("failed to create OpenGL server");
+        let desc = server.server_description().expect("missing server description");
+        let (tx, rx) = std::sync::mpsc::channel();
+        let client = OpenGLClient::new(
+            &desc,
+            ctx,
+            None,
+            Some(Box::new(move |info| {
+                let _ = tx.send(info);
+            })),
+        )
+        .expect("failed to create client");
+
+        server.publish_whole_texture(tex_id, GL_TEXTURE_RECTANGLE, W as f64, H as f64, false);
+        let info = rx.recv_timeout(Duration::from_secs(2)).ok();
+        // The default dispatch doesn't fetch the frame, so it is still new for the render thread.
+        let has_new_frame = client.has_new_frame();
+        let size = client.new_frame_image().map(|image| image.texture_size());
+
+        drop(client);
+        drop(server);
+        gl_delete_texture(tex_id);
+        cgl_destroy_context(ctx);
+        let info = info.expect("missing frame callback");
+        assert_eq!(info.size, None);
+        assert!(has_new_frame, "callback consumed the new frame");
+        assert_eq!(size, Some((W as f64, H as f64)));
+    }
+
+    #[test]
+    #[ignore = "requires local Syphon/OpenGL runtime (headless CGL + framework availability)"]
+    fn syphon_new_frame_handler_can_be_attached_and_detached() {
+        let ctx = cgl_create_headless_context().expect("create headless CGL context");
+        cgl_make_current(ctx);
+
+        let tex_id = gl_create_texture_rectangle_rgba8(W, H, &make_test_pattern());
+        let server = OpenGLServer::new(Some("rusty-syphon-runtime-set-handler"), ctx, None)
+            .expect("failed to create OpenGL server");
+        let desc = server.server_description().expect("missing server description");
+        let client = OpenGLClient::new(&desc, ctx, None, None).expect("failed to create client");
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        client.set_new_frame_handler(Some(Box::new(move |info| {
+            let _ = tx.send(info);
+        })));
+        let (fw, fh) = (W as f64, H as f64);
+        server.publish_whole_texture(tex_id, GL_TEXTURE_RECTANGLE, fw, fh, false);
+        let attached = rx.recv_timeout(Duration::from_secs(2)).is_ok();
+
+        // Detaching drops the callback and its sender.
+        client.set_new_frame_handler(None);
+        server.publish_whole_texture(tex_id, GL_TEXTURE_RECTANGLE, fw, fh, false);
+        let detached = rx.recv_timeout(Duration::from_millis(200)) == Err(std::sync::mpsc::RecvTimeoutError::Disconnected);
+
+        drop(client);
+        drop(server);
+        gl_delete_texture(tex_id);
+        cgl_destroy_context(ctx);
+        assert!(attached, "no callback after attaching a handler");
+        assert!(detached, "handler still registered after detaching");
+    }
 }
 
 #[cfg(target_os = "windows")]